@@ -0,0 +1,36 @@
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use delta_s3::{DeltaProfile, LevKind};
+
+/// Typo queries against a small catalog: the Levenshtein term dominates
+const QUERIES: &[&str] = &["sotry of seasons", "zlda breath of the wild", "stardew valey", "caleidoscope", "hollow kinght"];
+
+const TITLES: &[&str] = &[
+    "Story of Seasons",
+    "The Legend of Zelda: Breath of the Wild",
+    "Stardew Valley",
+    "Kaleidoscope",
+    "Hollow Knight",
+    "Counter-Strike 2",
+    "The Witcher 3: Wild Hunt",
+    "Baldur's Gate 3",
+    "Red Dead Redemption 2",
+    "Vampire Survivors",
+];
+
+fn bench_levenshtein_kind(c: &mut Criterion) {
+    let mut group = c.benchmark_group("delta_levenshtein");
+    for (name, kind) in [("classic", LevKind::Classic), ("damerau", LevKind::Damerau)] {
+        let profile = DeltaProfile { levenshtein: kind, ..DeltaProfile::title() };
+        group.bench_with_input(BenchmarkId::from_parameter(name), &profile, |b, profile| {
+            b.iter(|| {
+                for query in QUERIES {
+                    black_box(profile.delta_batch(query, TITLES));
+                }
+            });
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_levenshtein_kind);
+criterion_main!(benches);
@@ -0,0 +1,134 @@
+//! Micro-benchmarks of the terms Δ is built from, to tell which one a
+//! change slowed down: `cargo bench --bench primitives_benchmark`
+//! (`cargo bench -p delta-s3` from the repository root).
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use delta_s3::primitives;
+use delta_s3::{delta_prepared, semantic_delta_title, DeltaProfile, LevKind, PreparedQuery};
+
+/// Representative query/title pairs: exact, typo, acronym, roman numeral,
+/// DLC, punctuation, accents, long title
+const PAIRS: &[(&str, &str)] = &[
+    ("hades", "Hades"),
+    ("stardew valey", "Stardew Valley"),
+    ("gta v", "Grand Theft Auto V"),
+    ("final fantasy 7 remake", "FINAL FANTASY VII REMAKE"),
+    ("portal 2", "Portal 2 GOTY Edition"),
+    ("counter strike 2", "Counter-Strike 2"),
+    ("pokemon legends arceus", "Pokémon Legends: Arceus"),
+    ("civilization 6", "Sid Meier's Civilization VI: Rise and Fall - Gathering Storm Bundle"),
+];
+
+fn chars(text: &str) -> Vec<char> {
+    text.chars().collect()
+}
+
+fn bench_normalize(c: &mut Criterion) {
+    c.bench_function("normalize_v2", |b| {
+        b.iter(|| {
+            for (query, title) in PAIRS {
+                black_box(primitives::normalize_v2(black_box(query)));
+                black_box(primitives::normalize_v2(black_box(title)));
+            }
+        });
+    });
+}
+
+fn bench_levenshtein_sim(c: &mut Criterion) {
+    let mut group = c.benchmark_group("levenshtein_sim");
+    for (name, a, b) in [
+        ("short", "valey", "valley"),
+        ("medium", "counterstrike", "counter-strike"),
+        ("long", "supercalifragilisticexpialidocious", "supercalifragilisticexpialodocious"),
+    ] {
+        let (a, b) = (chars(a), chars(b));
+        for (kind_name, kind) in [("classic", LevKind::Classic), ("damerau", LevKind::Damerau)] {
+            group.bench_with_input(BenchmarkId::new(name, kind_name), &(&a, &b), |bench, (a, b)| {
+                bench.iter(|| primitives::levenshtein_sim(black_box(a), black_box(b), kind));
+            });
+        }
+    }
+    group.finish();
+}
+
+fn bench_prepared_terms(c: &mut Criterion) {
+    let profile = DeltaProfile::title();
+    let prepared: Vec<_> = PAIRS
+        .iter()
+        .map(|(query, title)| (profile.prepare_query(query), profile.prepare_query(title)))
+        .collect();
+    c.bench_function("l_symmetric", |b| {
+        b.iter(|| {
+            for (query, title) in &prepared {
+                black_box(primitives::l_symmetric(query, title, LevKind::Classic));
+            }
+        });
+    });
+
+    // One-word texts have no bigrams: only the unigram sets are compared
+    let mut group = c.benchmark_group("jaccard_index");
+    let single: Vec<_> = ["hades", "celeste", "minecraft", "terraria"]
+        .iter()
+        .map(|word| (profile.prepare_query(word), profile.prepare_query(&word.to_uppercase())))
+        .collect();
+    for (name, pairs) in [("without_bigrams", &single), ("with_bigrams", &prepared)] {
+        group.bench_with_input(BenchmarkId::from_parameter(name), pairs, |b, pairs| {
+            b.iter(|| {
+                for (query, title) in pairs {
+                    black_box(primitives::jaccard_index(query, title));
+                }
+            });
+        });
+    }
+    group.finish();
+}
+
+fn bench_semantic_delta_title(c: &mut Criterion) {
+    c.bench_function("semantic_delta_title", |b| {
+        b.iter(|| {
+            for (query, title) in PAIRS {
+                black_box(semantic_delta_title(black_box(query), black_box(title)));
+            }
+        });
+    });
+}
+
+/// One query against many titles, as a ranker scores candidates: the query
+/// tokenized per title, once, then the titles prepared too
+fn bench_one_query_many_titles(c: &mut Criterion) {
+    let profile = DeltaProfile::title();
+    let titles: Vec<String> = (1..=25)
+        .flat_map(|n| PAIRS.iter().map(move |(_, title)| format!("{} {}", title, n)))
+        .collect();
+    let query = "final fantasy 7 remake";
+    let prepared = PreparedQuery::new(query, &profile);
+    let prepared_titles: Vec<_> = titles.iter().map(|title| prepared.prepare_title(title)).collect();
+
+    let mut group = c.benchmark_group("one_query_many_titles");
+    group.bench_function("semantic_delta_title", |b| {
+        b.iter(|| titles.iter().map(|title| semantic_delta_title(black_box(query), title)).sum::<f64>());
+    });
+    group.bench_function("prepared_query", |b| {
+        b.iter(|| {
+            let prepared = PreparedQuery::new(black_box(query), &profile);
+            titles.iter().map(|title| prepared.delta(title)).sum::<f64>()
+        });
+    });
+    group.bench_function("prepared_titles", |b| {
+        b.iter(|| {
+            let prepared = PreparedQuery::new(black_box(query), &profile);
+            prepared_titles.iter().map(|title| delta_prepared(&prepared, title)).sum::<f64>()
+        });
+    });
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    bench_normalize,
+    bench_levenshtein_sim,
+    bench_prepared_terms,
+    bench_semantic_delta_title,
+    bench_one_query_many_titles
+);
+criterion_main!(benches);
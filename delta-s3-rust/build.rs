@@ -0,0 +1,8 @@
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // VERGEN_GIT_SHA and VERGEN_BUILD_TIMESTAMP for the binaries' --version
+    vergen::EmitBuilder::builder()
+        .build_timestamp()
+        .git_sha(true)
+        .emit()?;
+    Ok(())
+}
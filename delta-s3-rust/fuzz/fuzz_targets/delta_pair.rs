@@ -0,0 +1,37 @@
+//! Arbitrary byte pairs, lossy-decoded as raw chat would be, through Δ and
+//! a catalog: no panic, every Δ in [0, 1], every match a title
+//!
+//! cargo +nightly fuzz run delta_pair
+
+#![no_main]
+
+use delta_s3::{Corpus, SharedCatalog};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    // The first byte says where the query ends and the title starts
+    let Some((&split, rest)) = data.split_first() else {
+        return;
+    };
+    let (query, title) = rest.split_at(usize::from(split) * rest.len() / 255);
+    let (query, title) = (String::from_utf8_lossy(query), String::from_utf8_lossy(title));
+
+    for delta in [delta_s3::semantic_delta_v3(&query, &title), delta_s3::semantic_delta_title(&query, &title)] {
+        assert!((0.0..=1.0).contains(&delta), "{:?} / {:?}: {}", query, title, delta);
+    }
+
+    let titles = [&*title, &*query, "Stardew Valley", "Dark Souls: Prepare to Die Edition"];
+    let catalog = Corpus::new(&titles).with_index();
+    for m in catalog.top_k_indexed(&query, 2).into_iter().chain(catalog.top_k(&title, 4)) {
+        assert_eq!(m.title, titles[m.index]);
+        assert!((0.0..=1.0).contains(&m.delta));
+    }
+
+    let shared = SharedCatalog::from(catalog);
+    shared.push(&query);
+    assert_eq!(shared.remove(0).as_deref(), Some(&*title));
+    let snapshot = shared.snapshot();
+    for m in snapshot.top_k_indexed(&title, 3) {
+        assert_eq!(snapshot.title(m.index), Some(m.title));
+    }
+});
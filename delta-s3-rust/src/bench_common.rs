@@ -0,0 +1,199 @@
+//! Command line shared by the benchmark binaries (`cli` feature): dataset
+//! and catalog paths, query limit, threads, the JSON report and
+//! `--version`. Files are loaded with an error naming the path and the flag
+//! to fix it, not a panic.
+
+use crate::build_info::BuildInfo;
+use crate::eval::{self, Candidates, Dataset, EvalOptions, EvalReport, ReportArgs};
+use clap::Args;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+/// Where the queries and titles come from
+#[derive(Debug, Clone, Args)]
+pub struct DataArgs {
+    /// Targeted dataset: titles and queries with their ground truth
+    #[arg(long, value_name = "FILE", default_value = eval::TARGETED_DATASET)]
+    pub dataset: PathBuf,
+    /// Titles ranked instead of the dataset's (Steam app list or JSON array)
+    #[arg(long, value_name = "FILE")]
+    pub catalog: Option<PathBuf>,
+}
+
+impl DataArgs {
+    /// The dataset, its titles replaced by `--catalog` or else by
+    /// `default_catalog`
+    pub fn load(&self, default_catalog: Option<&Path>) -> Result<Dataset, String> {
+        let dataset = Dataset::load(&self.dataset).map_err(|e| load_error("dataset", &self.dataset, "--dataset", e))?;
+        match self.catalog.as_deref().or(default_catalog) {
+            Some(path) => {
+                let titles = eval::load_catalog(path).map_err(|e| load_error("catalog", path, "--catalog", e))?;
+                Ok(dataset.with_titles(titles))
+            }
+            None => Ok(dataset),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Args)]
+pub struct BenchArgs {
+    #[command(flatten)]
+    pub data: DataArgs,
+    /// Evaluate only the first n queries
+    #[arg(long, value_name = "N")]
+    pub queries_limit: Option<usize>,
+    /// Rayon threads [default: all cores]
+    #[arg(long, value_name = "N")]
+    pub threads: Option<usize>,
+    /// Write the report: CSV for a .csv file, JSON otherwise
+    #[arg(long, value_name = "FILE", aliases = ["output-json", "save"])]
+    pub output: Option<PathBuf>,
+    /// Diff against a saved JSON report, exit 1 on a regression
+    #[arg(long, value_name = "FILE")]
+    pub baseline: Option<PathBuf>,
+    /// Allowed Acc@1 drop against the baseline, in points
+    #[arg(long, value_name = "POINTS", default_value_t = 0.0)]
+    pub tolerance: f64,
+}
+
+impl BenchArgs {
+    /// Size the global Rayon pool; the thread count (1 without the
+    /// `parallel` feature)
+    pub fn init_threads(&self) -> Result<usize, String> {
+        #[cfg(feature = "parallel")]
+        {
+            let threads = self
+                .threads
+                .unwrap_or_else(|| std::thread::available_parallelism().map(|n| n.get()).unwrap_or(1));
+            rayon::ThreadPoolBuilder::new()
+                .num_threads(threads)
+                .build_global()
+                .map_err(|e| format!("--threads {}: {}", threads, e))?;
+            Ok(threads)
+        }
+        #[cfg(not(feature = "parallel"))]
+        Ok(1)
+    }
+
+    /// Evaluation over `candidates`, limited to `--queries-limit`
+    pub fn options(&self, candidates: Candidates, progress_every: usize) -> EvalOptions {
+        EvalOptions {
+            candidates,
+            max_queries: self.queries_limit,
+            progress_every: Some(progress_every),
+            ..Default::default()
+        }
+    }
+
+    /// Save and/or compare `report`; `Ok(false)` on a regression
+    pub fn finish(&self, report: &EvalReport) -> Result<bool, String> {
+        let args = ReportArgs {
+            save: self.output.clone(),
+            baseline: self.baseline.clone(),
+            tolerance: self.tolerance / 100.0,
+        };
+        args.finish(report).map_err(|e| format!("report: {}", e))
+    }
+}
+
+/// `--version [--json]`
+#[derive(Debug, Clone, Args)]
+pub struct VersionArgs {
+    /// Print version, git hash and build date
+    #[arg(short = 'V', long)]
+    pub version: bool,
+    /// With --version: print build info as JSON
+    #[arg(long, requires = "version")]
+    pub json: bool,
+}
+
+impl VersionArgs {
+    /// Print the build info of binary `name` if asked to; whether it was
+    pub fn print(&self, name: &str) -> bool {
+        if self.version {
+            let info = BuildInfo::new(name);
+            println!("{}", if self.json { info.to_json() } else { info.version_line() });
+        }
+        self.version
+    }
+}
+
+/// 0 when the benchmark passed, 1 on a regression, 2 (after printing it)
+/// on an error
+pub fn exit_code(result: Result<bool, String>) -> ExitCode {
+    match result {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::from(1),
+        Err(e) => {
+            eprintln!("❌ {}", e);
+            ExitCode::from(2)
+        }
+    }
+}
+
+fn load_error(what: &str, path: &Path, flag: &str, e: io::Error) -> String {
+    match e.kind() {
+        io::ErrorKind::NotFound => format!("{} not found: {} (pass {} <file>)", what, path.display(), flag),
+        _ => format!("{} {}: {}", what, path.display(), e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+
+    #[derive(Parser)]
+    struct Cli {
+        #[command(flatten)]
+        bench: BenchArgs,
+        #[command(flatten)]
+        version: VersionArgs,
+    }
+
+    fn parse(args: &[&str]) -> Result<BenchArgs, clap::Error> {
+        Cli::try_parse_from(std::iter::once("bench").chain(args.iter().copied())).map(|cli| cli.bench)
+    }
+
+    #[test]
+    fn test_bench_args() {
+        let defaults = parse(&[]).unwrap();
+        assert_eq!(defaults.data.dataset, Path::new(eval::TARGETED_DATASET));
+        assert_eq!((defaults.data.catalog, defaults.queries_limit, defaults.threads), (None, None, None));
+        assert_eq!(defaults.tolerance, 0.0);
+
+        let args = parse(&["--dataset", "d.json", "--queries-limit", "100", "--save", "r.json", "--tolerance", "0.5"]).unwrap();
+        assert_eq!(args.data.dataset, Path::new("d.json"));
+        assert_eq!(args.options(Candidates::All, 10).max_queries, Some(100));
+        assert_eq!(args.output.as_deref(), Some(Path::new("r.json")));
+        assert_eq!(parse(&["--output", "r.csv"]).unwrap().output.as_deref(), Some(Path::new("r.csv")));
+        assert_eq!(parse(&["--output-json", "r.json"]).unwrap().output.as_deref(), Some(Path::new("r.json")));
+        assert!(parse(&["--threads", "many"]).is_err());
+        assert!(parse(&["--queries"]).is_err());
+    }
+
+    #[test]
+    fn test_version_args() {
+        let parse = |args: &[&str]| Cli::try_parse_from(std::iter::once("bench").chain(args.iter().copied())).map(|cli| cli.version);
+        assert!(!parse(&[]).unwrap().version);
+        let json = parse(&["--version", "--json"]).unwrap();
+        assert!(json.version && json.json);
+        assert!(parse(&["-V"]).unwrap().version);
+        assert!(parse(&["--json"]).is_err());
+    }
+
+    #[test]
+    fn test_missing_files_name_the_flag() {
+        let missing = parse(&["--dataset", "/nonexistent/dataset.json"]).unwrap();
+        assert_eq!(missing.data.load(None).unwrap_err(), "dataset not found: /nonexistent/dataset.json (pass --dataset <file>)");
+
+        let dataset = std::env::temp_dir().join(format!("ds3_bench_common_{}.json", std::process::id()));
+        std::fs::write(&dataset, r#"{"titles": ["Hades"], "queries": [{"query": "hades", "ground_truth": "Hades", "pattern": "exact"}]}"#).unwrap();
+        let args = parse(&["--dataset", dataset.to_str().unwrap()]).unwrap();
+        assert_eq!(args.data.load(None).unwrap().titles, ["Hades"]);
+        let error = args.data.load(Some(Path::new("/nonexistent/applist.json"))).unwrap_err();
+        std::fs::remove_file(&dataset).unwrap();
+        assert_eq!(error, "catalog not found: /nonexistent/applist.json (pass --catalog <file>)");
+    }
+}
@@ -3,46 +3,53 @@ Benchmark binary - Match Python benchmark_optimized.py
 
 Tests 13,259 queries from steam_games_targeted.json against 5,000 titles.
 Expected: 97.45% Acc@1 (same as Python)
+
+Usage: benchmark [--dataset FILE] [--catalog FILE] [--queries-limit N] [--threads N]
+                 [--distractors N] [--seed N] [--output FILE] [--baseline FILE] [--tolerance POINTS]
+       benchmark --version [--json]
 */
 
-use delta_s3::semantic_delta_v3;
-use rayon::prelude::*;
-use serde::{Deserialize, Serialize};
-use std::fs;
-use std::time::Instant;
+use clap::Parser;
+use delta_s3::bench_common::{self, BenchArgs, VersionArgs};
+use delta_s3::eval::{self, Candidates};
+use delta_s3::{DeltaProfile, DocumentFrequencies};
+use std::process::ExitCode;
+use std::sync::Arc;
 
-#[derive(Debug, Deserialize)]
-struct Dataset {
-    metadata: Metadata,
-    titles: Vec<String>,
-    queries: Vec<Query>,
+/// Each targeted query against its ground truth and random distractors
+#[derive(Parser)]
+struct Cli {
+    #[command(flatten)]
+    bench: BenchArgs,
+    #[command(flatten)]
+    version: VersionArgs,
+    /// Random titles ranked with each ground truth
+    #[arg(long, value_name = "N", default_value_t = 100)]
+    distractors: usize,
+    /// Seed of the distractor draw
+    #[arg(long, value_name = "N", default_value_t = 42)]
+    seed: u64,
 }
 
-#[derive(Debug, Deserialize)]
-struct Metadata {
-    total_titles: usize,
-    total_queries: usize,
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    if cli.version.print("benchmark") {
+        return ExitCode::SUCCESS;
+    }
+    bench_common::exit_code(run(&cli))
 }
 
-#[derive(Debug, Deserialize)]
-struct Query {
-    query: String,
-    ground_truth: String,
-    pattern: String,
-}
+fn run(cli: &Cli) -> Result<bool, String> {
+    let args = &cli.bench;
+    let num_threads = args.init_threads()?;
 
-fn main() {
     println!("🚀 Δₛ³ v3.1 Rust Benchmark");
     println!("{}", "=".repeat(80));
+    println!("🧵 Using {} threads", num_threads);
     
     // Load dataset
     println!("\n📥 Loading dataset...");
-    let json_path = "../delta-s3/datasets/steam_games_targeted.json";
-    let json_str = fs::read_to_string(json_path)
-        .expect("Failed to read dataset");
-    
-    let dataset: Dataset = serde_json::from_str(&json_str)
-        .expect("Failed to parse JSON");
+    let dataset = args.data.load(None)?;
     
     println!("✅ Loaded {} titles, {} queries", 
              dataset.titles.len(), 
@@ -52,51 +59,42 @@ fn main() {
     println!("\n{}", "=".repeat(80));
     println!("📊 Optimized Evaluation");
     println!("{}", "=".repeat(80));
-    println!("Strategy: Each query against ground truth + 100 random distractors");
+    println!("Strategy: Each query against ground truth + {} random distractors (seed {})", cli.distractors, cli.seed);
     println!("Expected: 97.45% Acc@1 (validated in Python)");
     println!("{}", "=".repeat(80));
     
-    // Evaluate
-    let start = Instant::now();
-    
-    let results: Vec<bool> = dataset.queries.par_iter()
-        .enumerate()
-        .map(|(idx, query_data)| {
-            if (idx + 1) % 1000 == 0 {
-                println!("Progress: {}/{} ({:.1}%)", 
-                        idx + 1, 
-                        dataset.queries.len(),
-                        100.0 * (idx + 1) as f64 / dataset.queries.len() as f64);
-            }
-            
-            evaluate_query(&query_data, &dataset.titles)
-        })
-        .collect();
-    
-    let elapsed = start.elapsed();
-    
-    // Compute metrics
-    let correct = results.iter().filter(|&&x| x).count();
-    let total = results.len();
-    let accuracy = correct as f64 / total as f64;
-    
-    let avg_time_ms = elapsed.as_secs_f64() * 1000.0 / total as f64;
-    let throughput = total as f64 / elapsed.as_secs_f64();
+    let options = args.options(Candidates::Sampled { distractors: cli.distractors, seed: cli.seed }, 1000);
+    let report = eval::evaluate(eval::delta_ranker, &dataset, &options).with_config(&DeltaProfile::title());
+    let accuracy = report.acc_at(1).unwrap_or(0.0);
+    let throughput = report.throughput();
     
     // Print results
     println!("\n{}", "=".repeat(80));
     println!("📈 RESULTS - Targeted Dataset (100% Pattern Coverage)");
     println!("{}", "=".repeat(80));
-    println!("Total queries:  {}", total);
-    println!("Accuracy@1:     {:.4} ({}/{})", accuracy, correct, total);
+    print!("{}", report);
     println!();
     println!("⏱️  PERFORMANCE:");
-    println!("Total time:     {:.2}s", elapsed.as_secs_f64());
-    println!("Avg time:       {:.2}ms per query (101 comparisons)", avg_time_ms);
+    println!("Total time:     {:.2}s", report.elapsed_secs);
+    println!("Avg time:       {:.2}ms per query ({:.0} comparisons)",
+             report.elapsed_secs * 1000.0 / report.total as f64,
+             report.avg_candidates);
     println!("Throughput:     {:.0} queries/s", throughput);
     println!("{}", "=".repeat(80));
     println!();
     
+    // Same draw, Jaccard weighted by IDF over the dataset's titles
+    let idf = DeltaProfile {
+        document_frequencies: Some(Arc::new(DocumentFrequencies::count(&DeltaProfile::title(), &dataset.titles))),
+        ..DeltaProfile::title()
+    };
+    let idf_report = eval::evaluate(eval::profile_ranker(&idf), &dataset, &eval::EvalOptions { progress_every: None, ..options });
+    let idf_accuracy = idf_report.acc_at(1).unwrap_or(0.0);
+    println!("⚖️  IDF-weighted Jaccard:");
+    println!("   Acc@1: {:.2}% ({:+.2} points)", idf_accuracy * 100.0, (idf_accuracy - accuracy) * 100.0);
+    println!("   MRR:   {:.4} ({:+.4})", idf_report.mrr, idf_report.mrr - report.mrr);
+    println!();
+    
     // Verdict
     let verdict = if accuracy >= 0.95 {
         "✅ EXCELLENT"
@@ -114,62 +112,20 @@ fn main() {
     let python_acc = 0.9745;
     let python_throughput = 51.0;
     
-    let acc_diff = (accuracy - python_acc) * 100.0;
-    let speedup = throughput / python_throughput;
-    
     println!();
     println!("🐍 Comparison with Python:");
     println!("   Accuracy:   {:.2}% vs {:.2}% ({:+.2} points)", 
              accuracy * 100.0, 
              python_acc * 100.0,
-             acc_diff);
+             (accuracy - python_acc) * 100.0);
     println!("   Throughput: {:.0} vs {:.0} q/s ({:.1}x speedup)", 
              throughput,
              python_throughput,
-             speedup);
+             throughput / python_throughput);
+    
+    let passed = args.finish(&report)?;
     
     println!("\n{}", "=".repeat(80));
     println!("✅ Benchmark complete!");
-}
-
-fn evaluate_query(query_data: &Query, all_titles: &[String]) -> bool {
-    use rand::seq::SliceRandom;
-    use rand::SeedableRng;
-    
-    // Find ground truth index
-    let gt_idx = all_titles.iter()
-        .position(|t| t == &query_data.ground_truth);
-    
-    if gt_idx.is_none() {
-        return false;
-    }
-    
-    let gt_idx = gt_idx.unwrap();
-    
-    // Create candidate set: ground truth + 100 random distractors
-    let mut rng = rand::rngs::StdRng::seed_from_u64(42);
-    
-    let distractors: Vec<usize> = (0..all_titles.len())
-        .filter(|&i| i != gt_idx)
-        .collect::<Vec<_>>()
-        .choose_multiple(&mut rng, 100.min(all_titles.len() - 1))
-        .cloned()
-        .collect();
-    
-    let mut candidates = vec![gt_idx];
-    candidates.extend(distractors);
-    
-    // Compute deltas
-    let mut deltas: Vec<(f64, usize)> = candidates.iter()
-        .map(|&idx| {
-            let delta = semantic_delta_v3(&query_data.query, &all_titles[idx]);
-            (delta, idx)
-        })
-        .collect();
-    
-    // Sort by delta (ascending = best match first)
-    deltas.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
-    
-    // Check if ground truth is #1
-    deltas[0].1 == gt_idx
+    Ok(passed)
 }
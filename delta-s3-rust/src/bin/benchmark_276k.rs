@@ -1,80 +1,59 @@
-use delta_s3::semantic_delta_v3;
-use serde::Deserialize;
-use std::fs;
-use std::time::Instant;
-use rayon::prelude::*;
-
-#[derive(Debug, Deserialize)]
-struct SteamData {
-    applist: AppList,
-}
+//! Targeted queries against the full Steam app list (276K titles)
+//!
+//! Usage: benchmark_276k [--dataset FILE] [--catalog FILE] [--queries-limit N] [--threads N]
+//!                       [--output FILE] [--baseline FILE] [--tolerance POINTS] [--indexed]
+//!        benchmark_276k --version [--json]
+//!
+//! The catalog defaults to the Steam app list, the queries to the first 1000.
 
-#[derive(Debug, Deserialize)]
-struct AppList {
-    apps: Vec<SteamApp>,
-}
-
-#[derive(Debug, Deserialize)]
-struct SteamApp {
-    name: String,
-}
+use clap::Parser;
+use delta_s3::bench_common::{self, BenchArgs, VersionArgs};
+use delta_s3::eval::{self, Candidates};
+use delta_s3::Corpus;
+use std::path::Path;
+use std::process::ExitCode;
+use std::time::Instant;
 
-#[derive(Debug, Deserialize)]
-struct QueryDataset {
-    queries: Vec<Query>,
+/// Targeted queries against the full Steam app list
+#[derive(Parser)]
+struct Cli {
+    #[command(flatten)]
+    bench: BenchArgs,
+    #[command(flatten)]
+    version: VersionArgs,
+    /// Score only the titles sharing a token or trigram with the query
+    #[arg(long)]
+    indexed: bool,
 }
 
-#[derive(Debug, Deserialize)]
-struct Query {
-    query: String,
-    ground_truth: String,
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    if cli.version.print("benchmark_276k") {
+        return ExitCode::SUCCESS;
+    }
+    bench_common::exit_code(run(cli))
 }
 
-fn main() {
-    // Use ALL available threads
-    let num_threads = std::thread::available_parallelism()
-        .map(|n| n.get())
-        .unwrap_or(16);
-    
-    rayon::ThreadPoolBuilder::new()
-        .num_threads(num_threads)
-        .build_global()
-        .unwrap();
+fn run(Cli { bench: mut args, indexed, .. }: Cli) -> Result<bool, String> {
+    // First 1000 queries for reasonable runtime
+    args.queries_limit = args.queries_limit.or(Some(1000));
+    let num_threads = args.init_threads()?;
     
     println!("🔥 FULL STEAM CATALOG BENCHMARK (276K TITLES) 🔥");
     println!("{}", "=".repeat(80));
     println!("🧵 Using {} threads", num_threads);
     
-    // Load 276K Steam catalog
-    println!("\n📥 Loading FULL Steam catalog...");
-    let steam_json = fs::read_to_string("../delta-s3/Dataset/steam-game/steam-game.json")
-        .expect("Failed to read Steam dataset");
-    
-    let steam_data: SteamData = serde_json::from_str(&steam_json)
-        .expect("Failed to parse Steam JSON");
-    
-    let all_titles: Vec<String> = steam_data.applist.apps
-        .into_iter()
-        .map(|app| app.name)
-        .collect();
+    // Steam catalog, queries of the targeted dataset
+    println!("\n📥 Loading FULL Steam catalog and query dataset...");
+    let dataset = args.data.load(Some(Path::new(eval::APPLIST_DATASET)))?;
     
-    println!("✅ Loaded {} Steam titles", all_titles.len());
+    println!("✅ Loaded {} Steam titles", dataset.titles.len());
     
-    // Load queries (sample 1000)
-    println!("\n📥 Loading query dataset...");
-    let query_json = fs::read_to_string("../delta-s3/datasets/steam_games_targeted.json")
-        .expect("Failed to read query dataset");
+    let sample_size = args.queries_limit.map_or(dataset.queries.len(), |limit| limit.min(dataset.queries.len()));
     
-    let query_data: QueryDataset = serde_json::from_str(&query_json)
-        .expect("Failed to parse query JSON");
+    println!("✅ Loaded {} queries (sampled from full dataset)", sample_size);
     
-    // Take first 1000 queries for reasonable runtime
-    let sample_size = 1000.min(query_data.queries.len());
-    let queries: Vec<Query> = query_data.queries.into_iter().take(sample_size).collect();
-    
-    println!("✅ Loaded {} queries (sampled from full dataset)", queries.len());
-    
-    let total_comparisons = queries.len() as u64 * all_titles.len() as u64;
+    let total_comparisons = sample_size as u64 * dataset.titles.len() as u64;
     println!("\n⚠️  WARNING: {} total comparisons ({} million)", 
              total_comparisons,
              total_comparisons / 1_000_000);
@@ -83,61 +62,52 @@ fn main() {
     println!("\n{}", "=".repeat(80));
     println!("📊 FULL CATALOG Evaluation");
     println!("{}", "=".repeat(80));
-    println!("Strategy: EVERY query against ALL {} Steam titles", all_titles.len());
+    println!("Strategy: EVERY query against ALL {} Steam titles", dataset.titles.len());
     println!("Sample: {} queries (for reasonable runtime ~3 min)", sample_size);
     println!("{}", "=".repeat(80));
     
-    // Evaluate
-    println!("\n🔥 Starting MEGA benchmark...");
+    // Titles tokenized once, not once per query
+    println!("\n📦 Preparing catalog...");
     let start = Instant::now();
+    let catalog = Corpus::new(&dataset.titles);
+    let catalog = if indexed { catalog.with_index() } else { catalog };
+    println!("✅ Prepared {} titles in {:.2}s", catalog.len(), start.elapsed().as_secs_f64());
     
-    let results: Vec<bool> = queries.par_iter()
-        .enumerate()
-        .map(|(idx, query_data)| {
-            if (idx + 1) % 100 == 0 {
-                let progress = 100.0 * (idx + 1) as f64 / queries.len() as f64;
-                let elapsed = start.elapsed().as_secs_f64();
-                let eta = elapsed / (idx + 1) as f64 * queries.len() as f64 - elapsed;
-                println!("Progress: {}/{} ({:.1}%) | Elapsed: {:.1}s | ETA: {:.0}s", 
-                        idx + 1, 
-                        queries.len(),
-                        progress,
-                        elapsed,
-                        eta);
-            }
-            
-            evaluate_query_276k(&query_data, &all_titles)
-        })
-        .collect();
-    
-    let elapsed = start.elapsed();
-    
-    // Compute metrics
-    let correct = results.iter().filter(|&&x| x).count();
-    let total = results.len();
-    let accuracy = correct as f64 / total as f64;
-    
-    let total_time_s = elapsed.as_secs_f64();
-    let avg_time_ms = (total_time_s * 1000.0) / total as f64;
-    let throughput = total as f64 / total_time_s;
+    // Evaluate
+    println!("\n🔥 Starting MEGA benchmark...");
+    let options = args.options(Candidates::All, 100);
+    // Only the best max(k) titles are kept per query: MRR counts ranks up to it
+    let top_k = options.ks.iter().copied().max().unwrap_or(1);
+    let report = if indexed {
+        eval::evaluate(eval::indexed_catalog_ranker(&catalog, top_k), &dataset, &options)
+    } else {
+        eval::evaluate(eval::catalog_ranker(&catalog, top_k), &dataset, &options)
+    }
+    .with_config(catalog.profile());
+    let accuracy = report.acc_at(1).unwrap_or(0.0);
+    let total_time_s = report.elapsed_secs;
+    let throughput = report.throughput();
     let comparisons_per_sec = total_comparisons as f64 / total_time_s;
     
     // Print results
     println!("\n{}", "=".repeat(80));
     println!("📈 RESULTS - FULL STEAM CATALOG (276K Titles)");
     println!("{}", "=".repeat(80));
-    println!("Total queries:  {}", total);
-    println!("Accuracy@1:     {:.4} ({}/{})", accuracy, correct, total);
+    print!("{}", report);
     
     println!("\n⏱️  PERFORMANCE:");
     println!("Total time:     {:.2}s ({:.1} min)", total_time_s, total_time_s / 60.0);
     println!("Avg time:       {:.2}ms per query ({} comparisons)", 
-             avg_time_ms, 
-             all_titles.len());
+             total_time_s * 1000.0 / report.total as f64, 
+             dataset.titles.len());
     println!("Throughput:     {:.1} queries/s", throughput);
     println!("Comparisons:    {:.1} M/s ({} million total)", 
              comparisons_per_sec / 1_000_000.0,
              total_comparisons / 1_000_000);
+    if indexed {
+        println!("Prefilter:      {:.1}% of the titles skipped per query",
+                 eval::prefilter_reduction(&catalog, &dataset.queries[..sample_size], top_k) * 100.0);
+    }
     println!("{}", "=".repeat(80));
     
     println!("\n💡 Verdict: {}", 
@@ -151,7 +121,7 @@ fn main() {
     
     // Comparison with 5K benchmark
     let small_throughput = 286.0; // From previous 5K benchmark
-    let scaling_factor = all_titles.len() as f64 / 5000.0;
+    let scaling_factor = dataset.titles.len() as f64 / 5000.0;
     
     println!("\n📊 Comparison:");
     println!("   5K catalog:   {:.0} q/s → 276K catalog: {:.1} q/s", 
@@ -162,34 +132,9 @@ fn main() {
              small_throughput / throughput);
     println!("   Accuracy: {:.2}% (sampled {} queries)", accuracy * 100.0, sample_size);
     
+    let passed = args.finish(&report)?;
+    
     println!("\n{}", "=".repeat(80));
     println!("✅ System survived {} MILLION comparisons! 🎉", total_comparisons / 1_000_000);
-}
-
-fn evaluate_query_276k(query_data: &Query, all_titles: &[String]) -> bool {
-    // Find ground truth index
-    let gt_idx = all_titles.iter()
-        .position(|t| t == &query_data.ground_truth);
-    
-    if gt_idx.is_none() {
-        // Ground truth not in catalog
-        return false;
-    }
-    
-    let gt_idx = gt_idx.unwrap();
-    
-    // Compute delta for ALL 276K titles
-    let mut scores: Vec<(usize, f64)> = all_titles.iter()
-        .enumerate()
-        .map(|(idx, title)| {
-            let delta = semantic_delta_v3(&query_data.query, title);
-            (idx, delta)
-        })
-        .collect();
-    
-    // Sort by delta ASCENDING (lower distance = better match)
-    scores.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap());
-    
-    // Check if ground truth index is rank #1
-    scores[0].0 == gt_idx
+    Ok(passed)
 }
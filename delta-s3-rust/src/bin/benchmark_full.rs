@@ -1,58 +1,53 @@
-use delta_s3::semantic_delta_v3;
-use serde::{Deserialize, Serialize};
-use std::fs;
-use std::time::Instant;
-use rayon::prelude::*;
+//! Every targeted query against the whole 5K catalog
+//!
+//! Usage: benchmark_full [--dataset FILE] [--catalog FILE] [--queries-limit N] [--threads N]
+//!                       [--output FILE] [--baseline FILE] [--tolerance POINTS] [--indexed]
+//!        benchmark_full --version [--json]
 
-#[derive(Debug, Deserialize)]
-struct Dataset {
-    metadata: Metadata,
-    titles: Vec<String>,
-    queries: Vec<Query>,
-}
+use clap::Parser;
+use delta_s3::bench_common::{self, BenchArgs, VersionArgs};
+use delta_s3::eval::{self, Candidates};
+use delta_s3::Corpus;
+use std::process::ExitCode;
 
-#[derive(Debug, Deserialize)]
-struct Metadata {
-    total_titles: usize,
-    total_queries: usize,
+/// Every targeted query against the whole catalog
+#[derive(Parser)]
+struct Cli {
+    #[command(flatten)]
+    bench: BenchArgs,
+    #[command(flatten)]
+    version: VersionArgs,
+    /// Score only the titles sharing a token or trigram with the query
+    #[arg(long)]
+    indexed: bool,
 }
 
-#[derive(Debug, Deserialize)]
-struct Query {
-    query: String,
-    ground_truth: String,
-    pattern: String,
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    if cli.version.print("benchmark_full") {
+        return ExitCode::SUCCESS;
+    }
+    bench_common::exit_code(run(&cli))
 }
 
-fn main() {
-    // Use ALL available threads (auto-detect)
-    let num_threads = std::thread::available_parallelism()
-        .map(|n| n.get())
-        .unwrap_or(16);
-    
-    rayon::ThreadPoolBuilder::new()
-        .num_threads(num_threads)
-        .build_global()
-        .unwrap();
+fn run(cli: &Cli) -> Result<bool, String> {
+    let args = &cli.bench;
+    let num_threads = args.init_threads()?;
     
     println!("💀 Δₛ³ v3.1 FULL CATALOG BENCHMARK 💀");
     println!("{}", "=".repeat(80));
-    println!("🧵 Using {} threads (auto-detected)", num_threads);
+    println!("🧵 Using {} threads", num_threads);
     
     // Load dataset
     println!("\n📥 Loading dataset...");
-    let json_path = "../delta-s3/datasets/steam_games_targeted.json";
-    let json_str = fs::read_to_string(json_path)
-        .expect("Failed to read dataset");
-    
-    let dataset: Dataset = serde_json::from_str(&json_str)
-        .expect("Failed to parse JSON");
+    let dataset = args.data.load(None)?;
     
     println!("✅ Loaded {} titles, {} queries", 
              dataset.titles.len(), 
              dataset.queries.len());
     
-    let total_comparisons = dataset.queries.len() as u64 * dataset.titles.len() as u64;
+    let queries = args.queries_limit.map_or(dataset.queries.len(), |limit| limit.min(dataset.queries.len()));
+    let total_comparisons = queries as u64 * dataset.titles.len() as u64;
     println!("⚠️  WARNING: {} total comparisons ({} million)", 
              total_comparisons,
              total_comparisons / 1_000_000);
@@ -67,55 +62,41 @@ fn main() {
     
     // Evaluate
     println!("\n🔥 Starting full catalog benchmark...");
-    let start = Instant::now();
-    
-    let results: Vec<bool> = dataset.queries.par_iter()
-        .enumerate()
-        .map(|(idx, query_data)| {
-            if (idx + 1) % 500 == 0 {
-                let progress = 100.0 * (idx + 1) as f64 / dataset.queries.len() as f64;
-                let elapsed = start.elapsed().as_secs_f64();
-                let eta = elapsed / (idx + 1) as f64 * dataset.queries.len() as f64 - elapsed;
-                println!("Progress: {}/{} ({:.1}%) | Elapsed: {:.1}s | ETA: {:.0}s", 
-                        idx + 1, 
-                        dataset.queries.len(),
-                        progress,
-                        elapsed,
-                        eta);
-            }
-            
-            evaluate_query_full(&query_data, &dataset.titles)
-        })
-        .collect();
-    
-    let elapsed = start.elapsed();
-    
-    // Compute metrics
-    let correct = results.iter().filter(|&&x| x).count();
-    let total = results.len();
-    let accuracy = correct as f64 / total as f64;
-    
-    let total_time_s = elapsed.as_secs_f64();
-    let avg_time_ms = (total_time_s * 1000.0) / total as f64;
-    let throughput = total as f64 / total_time_s;
+    let options = args.options(Candidates::All, 500);
+    // Titles tokenized once; only the best max(k) kept per query (MRR@k)
+    let catalog = Corpus::new(&dataset.titles);
+    let catalog = if cli.indexed { catalog.with_index() } else { catalog };
+    let top_k = options.ks.iter().copied().max().unwrap_or(1);
+    let report = if cli.indexed {
+        eval::evaluate(eval::indexed_catalog_ranker(&catalog, top_k), &dataset, &options)
+    } else {
+        eval::evaluate(eval::catalog_ranker(&catalog, top_k), &dataset, &options)
+    }
+    .with_config(catalog.profile());
+    let accuracy = report.acc_at(1).unwrap_or(0.0);
+    let total_time_s = report.elapsed_secs;
+    let throughput = report.throughput();
     let comparisons_per_sec = total_comparisons as f64 / total_time_s;
     
     // Print results
     println!("\n{}", "=".repeat(80));
     println!("📈 RESULTS - FULL CATALOG (100% Coverage)");
     println!("{}", "=".repeat(80));
-    println!("Total queries:  {}", total);
-    println!("Accuracy@1:     {:.4} ({}/{})", accuracy, correct, total);
+    print!("{}", report);
     
     println!("\n⏱️  PERFORMANCE:");
     println!("Total time:     {:.2}s", total_time_s);
     println!("Avg time:       {:.2}ms per query ({} comparisons)", 
-             avg_time_ms, 
+             total_time_s * 1000.0 / report.total as f64, 
              dataset.titles.len());
     println!("Throughput:     {:.0} queries/s", throughput);
     println!("Comparisons:    {:.0} M/s ({} million total)", 
              comparisons_per_sec / 1_000_000.0,
              total_comparisons / 1_000_000);
+    if cli.indexed {
+        println!("Prefilter:      {:.1}% of the titles skipped per query",
+                 eval::prefilter_reduction(&catalog, &dataset.queries[..queries], top_k) * 100.0);
+    }
     println!("{}", "=".repeat(80));
     
     println!("\n💡 Verdict: {}", 
@@ -145,34 +126,9 @@ fn main() {
              scaling_factor,
              light_throughput / throughput);
     
+    let passed = args.finish(&report)?;
+    
     println!("\n{}", "=".repeat(80));
     println!("✅ System survived! No BSOD 🎉");
-}
-
-fn evaluate_query_full(query_data: &Query, all_titles: &[String]) -> bool {
-    // Find ground truth index
-    let gt_idx = all_titles.iter()
-        .position(|t| t == &query_data.ground_truth);
-    
-    if gt_idx.is_none() {
-        // Ground truth not in catalog
-        return false;
-    }
-    
-    let gt_idx = gt_idx.unwrap();
-    
-    // Compute delta for ALL titles
-    let mut scores: Vec<(usize, f64)> = all_titles.iter()
-        .enumerate()
-        .map(|(idx, title)| {
-            let delta = semantic_delta_v3(&query_data.query, title);
-            (idx, delta)
-        })
-        .collect();
-    
-    // Sort by delta ASCENDING (lower distance = better match)
-    scores.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap());
-    
-    // Check if ground truth index is rank #1
-    scores[0].0 == gt_idx
+    Ok(passed)
 }
@@ -1,25 +1,13 @@
-use delta_s3::semantic_delta_v3;
-use serde::Deserialize;
-use std::fs;
+use delta_s3::{build_info, eval, Corpus};
 use std::time::Instant;
-use rayon::prelude::*;
-
-#[derive(Debug, Deserialize)]
-struct SteamData {
-    applist: AppList,
-}
-
-#[derive(Debug, Deserialize)]
-struct AppList {
-    apps: Vec<SteamApp>,
-}
-
-#[derive(Debug, Deserialize)]
-struct SteamApp {
-    name: String,
-}
 
 fn main() {
+    let args: Vec<String> = std::env::args().collect();
+    if let Some(version) = build_info::version_output("benchmark_gpu_vs_rust", &args) {
+        println!("{}", version);
+        return;
+    }
+
     // Use ALL available threads for maximum performance
     let num_threads = std::thread::available_parallelism()
         .map(|n| n.get())
@@ -37,22 +25,19 @@ fn main() {
     
     // Load 276K Steam catalog
     println!("📥 Loading Steam catalog...");
-    let steam_json = fs::read_to_string("../delta-s3/Dataset/steam-game/steam-game.json")
-        .expect("Failed to read Steam dataset");
-    
-    let steam_data: SteamData = serde_json::from_str(&steam_json)
-        .expect("Failed to parse Steam JSON");
-    
-    let all_titles: Vec<String> = steam_data.applist.apps
-        .into_iter()
-        .map(|app| app.name)
-        .collect();
+    let all_titles = eval::load_applist(eval::APPLIST_DATASET)
+        .expect("Failed to load Steam dataset");
     
     println!("✅ Loaded {} Steam titles", all_titles.len());
+    
+    // Titles tokenized once, outside the timed queries
+    let start = Instant::now();
+    let catalog = Corpus::new(&all_titles);
+    println!("✅ Prepared catalog in {:.2}s", start.elapsed().as_secs_f64());
     println!();
     
     // Same test queries as GPU benchmark
-    let test_queries = vec![
+    let test_queries = [
         "Counter-Strike",
         "Half-Life",
         "Portal 2",
@@ -71,17 +56,8 @@ fn main() {
     for (i, query) in test_queries.iter().enumerate() {
         let start = Instant::now();
         
-        // Compute delta for ALL 276K titles in PARALLEL
-        let mut scores: Vec<(usize, f64)> = all_titles.par_iter()
-            .enumerate()
-            .map(|(idx, title)| {
-                let delta = semantic_delta_v3(query, title);
-                (idx, delta)
-            })
-            .collect();
-        
-        // Sort by delta ASCENDING (lower = better)
-        scores.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap());
+        // Delta for ALL 276K titles in PARALLEL, best 3 kept (lower = better)
+        let top = catalog.top_k(query, 3);
         
         let elapsed = start.elapsed();
         let elapsed_ns = elapsed.as_nanos();
@@ -93,8 +69,8 @@ fn main() {
         // Show top-3 for first query
         if i == 0 {
             println!("   Top-3 for '{}':", query);
-            for (rank, (idx, score)) in scores.iter().take(3).enumerate() {
-                println!("     {}. [{}] {} → {:.3}", rank + 1, idx, all_titles[*idx], score);
+            for (rank, m) in top.iter().enumerate() {
+                println!("     {}. [{}] {} → {:.3}", rank + 1, m.index, m.title, m.delta);
             }
             println!();
         }
@@ -117,7 +93,6 @@ fn main() {
     
     // GPU results (from previous run)
     let gpu_total_ns = 61_881_269u128;
-    let gpu_kernel_ns = 0u128;  // Too fast to measure
     let cpu_python_ns = 10_602_937_237u128;
     
     println!("{:<30} {:>20} {:>20}", "Metric", "Rust CPU", "GPU CUDA");
@@ -1,52 +1,75 @@
-use delta_s3::semantic_delta_v3;
-use serde::Deserialize;
-use std::fs;
+//! Δ of one targeted query against every title, ground truth first
+//!
+//! Usage: debug_query [--dataset FILE] [--catalog FILE] [--index N]
+//!        debug_query --version [--json]
 
-#[derive(Debug, Deserialize)]
-struct Dataset {
-    titles: Vec<String>,
-    queries: Vec<Query>,
+use clap::Parser;
+use delta_s3::bench_common::{self, DataArgs, VersionArgs};
+use delta_s3::{semantic_delta_explain, semantic_delta_v3};
+use std::process::ExitCode;
+
+/// Δ of one targeted query against every title, ground truth first
+#[derive(Parser)]
+struct Cli {
+    #[command(flatten)]
+    data: DataArgs,
+    #[command(flatten)]
+    version: VersionArgs,
+    /// Query of the dataset to debug (0-based)
+    #[arg(long, value_name = "N", default_value_t = 0)]
+    index: usize,
 }
 
-#[derive(Debug, Deserialize)]
-struct Query {
-    query: String,
-    ground_truth: String,
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    if cli.version.print("debug_query") {
+        return ExitCode::SUCCESS;
+    }
+    bench_common::exit_code(run(&cli))
 }
 
-fn main() {
-    println!("🔍 Debug FIRST query");
+fn run(cli: &Cli) -> Result<bool, String> {
+    println!("🔍 Debug query #{}", cli.index);
     
-    let json_path = "../delta-s3/datasets/steam_games_targeted.json";
-    let json_str = fs::read_to_string(json_path).unwrap();
-    let dataset: Dataset = serde_json::from_str(&json_str).unwrap();
+    let dataset = cli.data.load(None)?;
+    let query = dataset.queries.get(cli.index).ok_or_else(|| {
+        format!("no query #{}: the dataset has {} queries", cli.index, dataset.queries.len())
+    })?;
     
-    let first_query = &dataset.queries[0];
-    
-    println!("\nQuery: '{}'", first_query.query);
-    println!("Ground truth: '{}'", first_query.ground_truth);
+    println!("\nQuery: '{}'", query.query);
+    println!("Ground truth: '{}'", query.ground_truth);
     
     // Find GT index
     let gt_idx = dataset.titles.iter()
-        .position(|t| t == &first_query.ground_truth);
+        .position(|t| t == &query.ground_truth);
     
     println!("GT found at index: {:?}", gt_idx);
     
     if let Some(idx) = gt_idx {
         println!("Title at GT index: '{}'", dataset.titles[idx]);
         
+        // Which term the GT's Δ comes from (TITLE profile)
+        let explained = semantic_delta_explain(&query.query, &dataset.titles[idx]);
+        println!("Tokens: {:?} vs {:?}", explained.query_tokens, explained.title_tokens);
+        println!("J={:.4} (+μ_space {:.4}, capped {:.4})  L={:.4}  R={:.4}",
+                 explained.jaccard, explained.mu_space, explained.jaccard_capped,
+                 explained.levenshtein, explained.anchor_ratio);
+        println!("Δ = {:.4} (J) + {:.4} (L) + {:.4} (R) + {:.4} (μ_anchor) + {:.4} (DLC) = {:.4}",
+                 explained.jaccard_term, explained.levenshtein_term, explained.anchor_term,
+                 explained.mu_anchor, explained.dlc_penalty, explained.delta);
+        
         // Compute deltas for first 10 titles
         println!("\nTop 10 scores:");
         let mut scores: Vec<(usize, f64, &String)> = dataset.titles.iter()
             .enumerate()
             .take(10)
             .map(|(i, title)| {
-                let delta = semantic_delta_v3(&first_query.query, title);
+                let delta = semantic_delta_v3(&query.query, title);
                 (i, delta, title)
             })
             .collect();
         
-        scores.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap());
+        scores.sort_by(|a, b| a.1.total_cmp(&b.1)); // Δ is a distance: lowest first
         
         for (i, (title_idx, delta, title)) in scores.iter().enumerate() {
             let marker = if *title_idx == idx { "← GT" } else { "" };
@@ -58,12 +81,12 @@ fn main() {
         let mut all_scores: Vec<(usize, f64)> = dataset.titles.iter()
             .enumerate()
             .map(|(i, title)| {
-                let delta = semantic_delta_v3(&first_query.query, title);
+                let delta = semantic_delta_v3(&query.query, title);
                 (i, delta)
             })
             .collect();
         
-        all_scores.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap());
+        all_scores.sort_by(|a, b| a.1.total_cmp(&b.1));
         
         println!("\nTop 5 overall:");
         for (rank, (title_idx, delta)) in all_scores.iter().take(5).enumerate() {
@@ -83,7 +106,9 @@ fn main() {
         
         println!("\nGround truth rank: {:?}", gt_rank);
         println!("Result: {}", if gt_rank == Some(1) { "✅ PASS" } else { "❌ FAIL" });
+        Ok(gt_rank == Some(1))
     } else {
         println!("❌ Ground truth NOT FOUND in titles!");
+        Ok(false)
     }
 }
@@ -0,0 +1,174 @@
+//! Ad-hoc Δₛ³ (TITLE mode) from the command line
+//!
+//! ```text
+//! ds3 score <query> <title>                      Δ and its terms
+//! ds3 rank <query> --catalog <file> [--top 10]   closest titles of a catalog
+//! ds3 compare <query> <title_a> <title_b>        which title is closer, term by term
+//! ```
+//!
+//! `--json` prints JSON instead of tables, `--version [--json]` the build. A catalog is a Steam app list
+//! (`{"applist": {"apps": [{"name": ...}]}}`) or a JSON array of titles.
+
+use clap::{CommandFactory, Parser, Subcommand};
+use delta_s3::{build_info::BuildInfo, eval, DeltaCatalog, DeltaExplanation, DeltaProfile};
+use serde::Serialize;
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+/// Ad-hoc Δₛ³ (TITLE mode): lower is closer
+#[derive(Parser)]
+#[command(name = "ds3")]
+struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+    /// Print version, git hash and build date
+    #[arg(short = 'V', long)]
+    version: bool,
+    /// Print JSON instead of tables
+    #[arg(long, global = true)]
+    json: bool,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Δ of a query and a title, term by term
+    Score { query: String, title: String },
+    /// Closest titles of a catalog
+    Rank {
+        query: String,
+        /// Steam app list or JSON array of titles
+        #[arg(long, value_name = "FILE")]
+        catalog: PathBuf,
+        #[arg(long, value_name = "K", default_value_t = 10)]
+        top: usize,
+    },
+    /// Which of two titles is closer to a query, term by term
+    Compare { query: String, a: String, b: String },
+}
+
+#[derive(Serialize)]
+struct Ranked<'a> {
+    rank: usize,
+    index: usize,
+    title: &'a str,
+    delta: f64,
+}
+
+#[derive(Serialize)]
+struct Comparison<'a> {
+    query: &'a str,
+    a: &'a DeltaExplanation,
+    b: &'a DeltaExplanation,
+    /// "a", "b" or "tie"
+    closer: &'a str,
+}
+
+fn main() -> ExitCode {
+    let args = Cli::parse();
+    if args.version {
+        let info = BuildInfo::new("ds3");
+        println!("{}", if args.json { info.to_json() } else { info.version_line() });
+        return ExitCode::SUCCESS;
+    }
+    let Some(command) = args.command else {
+        // Usage error, like a missing argument
+        let _ = Cli::command().print_help();
+        return ExitCode::from(2);
+    };
+    let profile = DeltaProfile::title();
+
+    match command {
+        Command::Score { query, title } => {
+            let explained = profile.explain(&query, &title);
+            if args.json {
+                println!("{}", to_json(&explained));
+            } else {
+                print_tokens("query", &query, &explained.query_tokens);
+                print_tokens("title", &title, &explained.title_tokens);
+                println!();
+                print_terms(&[("", &explained)]);
+            }
+        }
+        Command::Rank { query, catalog, top } => {
+            let titles = match eval::load_catalog(&catalog) {
+                Ok(titles) => titles,
+                Err(e) => {
+                    eprintln!("ds3: {}: {}", catalog.display(), e);
+                    return ExitCode::FAILURE;
+                }
+            };
+            let catalog = DeltaCatalog::prebuild(profile, &titles);
+            let ranked: Vec<Ranked> = catalog
+                .top_k(&query, top)
+                .into_iter()
+                .enumerate()
+                .map(|(rank, m)| Ranked { rank: rank + 1, index: m.index, title: m.title, delta: m.delta })
+                .collect();
+            if args.json {
+                println!("{}", to_json(&ranked));
+            } else {
+                println!("{:>4}  {:>8}  {:>7}  title", "rank", "index", "Δ");
+                for m in &ranked {
+                    println!("{:>4}  {:>8}  {:>7.4}  {}", m.rank, m.index, m.delta, m.title);
+                }
+            }
+        }
+        Command::Compare { query, a, b } => {
+            let (explained_a, explained_b) = (profile.explain(&query, &a), profile.explain(&query, &b));
+            let closer = match explained_a.delta.total_cmp(&explained_b.delta) {
+                std::cmp::Ordering::Less => "a",
+                std::cmp::Ordering::Greater => "b",
+                std::cmp::Ordering::Equal => "tie",
+            };
+            if args.json {
+                println!("{}", to_json(&Comparison { query: &query, a: &explained_a, b: &explained_b, closer }));
+            } else {
+                print_tokens("query", &query, &explained_a.query_tokens);
+                print_tokens("a", &a, &explained_a.title_tokens);
+                print_tokens("b", &b, &explained_b.title_tokens);
+                println!();
+                print_terms(&[("a", &explained_a), ("b", &explained_b)]);
+                println!();
+                match closer {
+                    "tie" => println!("a and b tie"),
+                    side => println!("{} is closer", side),
+                }
+            }
+        }
+    }
+    ExitCode::SUCCESS
+}
+
+fn to_json<T: Serialize>(value: &T) -> String {
+    serde_json::to_string_pretty(value).expect("output serializes")
+}
+
+fn print_tokens(label: &str, text: &str, tokens: &[String]) {
+    println!("{:<6} {:?} → {}", label, text, tokens.join(" "));
+}
+
+/// One column per explanation: raw values, then the terms adding up to Δ
+fn print_terms(columns: &[(&str, &DeltaExplanation)]) {
+    let row = |name: &str, value: fn(&DeltaExplanation) -> f64| {
+        let values: String = columns.iter().map(|(_, e)| format!("  {:>8.4}", value(e))).collect();
+        println!("{:<18}{}", name, values);
+    };
+    let header: String = columns.iter().map(|(label, _)| format!("  {:>8}", label)).collect();
+    if !header.trim().is_empty() {
+        println!("{:<18}{}", "", header);
+    }
+    row("Jaccard", |e| e.jaccard);
+    row("  + μ_space", |e| e.mu_space);
+    row("  capped", |e| e.jaccard_capped);
+    row("Levenshtein", |e| e.levenshtein);
+    row("anchor ratio", |e| e.anchor_ratio);
+    println!();
+    row("wJ × (1 − J)", |e| e.jaccard_term);
+    row("wL × (1 − L)", |e| e.levenshtein_term);
+    row("wR × (1 − R)", |e| e.anchor_term);
+    row("μ_anchor", |e| e.mu_anchor);
+    row("DLC penalty", |e| e.dlc_penalty);
+    row("negation penalty", |e| e.negation_penalty);
+    row("− clamped", |e| e.clamped);
+    row("Δ", |e| e.delta);
+}
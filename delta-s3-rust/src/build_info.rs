@@ -0,0 +1,91 @@
+//! Build metadata (version, git hash, build date, features) printed by the
+//! binaries' `--version`, with the fields of the game engine's
+
+use serde::{Deserialize, Serialize};
+
+/// Short git hash of the build (vergen's placeholder outside a git checkout)
+pub const GIT_SHA: &str = env!("VERGEN_GIT_SHA");
+
+/// RFC3339 build timestamp
+pub const BUILD_DATE: &str = env!("VERGEN_BUILD_TIMESTAMP");
+
+/// Cargo features compiled in
+pub fn enabled_features() -> Vec<&'static str> {
+    let mut features = Vec::new();
+    if cfg!(feature = "parallel") {
+        features.push("parallel");
+    }
+    if cfg!(feature = "cli") {
+        features.push("cli");
+    }
+    if cfg!(feature = "python") {
+        features.push("python");
+    }
+    if cfg!(feature = "wasm") {
+        features.push("wasm");
+    }
+    features
+}
+
+/// Machine-readable `--version --json` output
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BuildInfo {
+    pub name: String,
+    pub version: String,
+    pub git_sha: String,
+    pub build_date: String,
+    pub features: Vec<String>,
+}
+
+impl BuildInfo {
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            git_sha: match GIT_SHA {
+                "VERGEN_IDEMPOTENT_OUTPUT" => "unknown".to_string(),
+                sha => sha.to_string(),
+            },
+            build_date: BUILD_DATE.to_string(),
+            features: enabled_features().into_iter().map(String::from).collect(),
+        }
+    }
+
+    /// One-line `--version` output: `name 3.1.0 (abc1234 2025-01-01T00:00:00Z)`
+    pub fn version_line(&self) -> String {
+        format!("{} {} ({} {})", self.name, self.version, self.git_sha, self.build_date)
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap_or_default()
+    }
+}
+
+/// If `args` ask for `--version` (or `-V`), return what to print: the
+/// version line, or JSON when `--json` is also given (binaries without clap)
+pub fn version_output<S: AsRef<str>>(name: &str, args: &[S]) -> Option<String> {
+    let has = |flag: &str| args.iter().any(|a| a.as_ref() == flag);
+    if !has("--version") && !has("-V") {
+        return None;
+    }
+
+    let info = BuildInfo::new(name);
+    Some(if has("--json") { info.to_json() } else { info.version_line() })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_version_output() {
+        assert_eq!(version_output("benchmark", &["benchmark", "--threads", "4"]), None);
+
+        let line = version_output("benchmark", &["benchmark", "--version"]).unwrap();
+        assert!(line.starts_with(&format!("benchmark {} (", env!("CARGO_PKG_VERSION"))));
+
+        let json = version_output("benchmark", &["benchmark", "-V", "--json"]).unwrap();
+        let info: BuildInfo = serde_json::from_str(&json).unwrap();
+        assert_eq!(info, BuildInfo::new("benchmark"));
+    }
+}
@@ -0,0 +1,949 @@
+/*!
+Evaluation harness: ranks each query of a labelled dataset with any ranker
+and reports Acc@k, MRR and per-pattern accuracy. The benchmark binaries are
+thin CLIs over it; saved reports can be diffed against a baseline to catch
+regressions on specific query patterns.
+
+A ranker is any `Fn(&str, &[String]) -> Vec<(usize, f64)>`: the query and
+the candidate titles in, indices into the candidates out, best match first
+(the score is informational).
+*/
+
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt::{self, Write as _};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Instant;
+
+use crate::{DeltaCatalog, DeltaProfile, LevKind};
+
+/// Dataset used by the benchmarks (`benchmark`, `benchmark_full`)
+pub const TARGETED_DATASET: &str = "../delta-s3/datasets/steam_games_targeted.json";
+
+/// Full Steam app list (`benchmark_276k`)
+pub const APPLIST_DATASET: &str = "../delta-s3/Dataset/steam-game/steam-game.json";
+
+// ═══════════════════════════════════════════════════════════════════════════
+// Datasets
+// ═══════════════════════════════════════════════════════════════════════════
+
+/// One labelled query
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EvalQuery {
+    pub query: String,
+    /// Title the query should rank first
+    pub ground_truth: String,
+    /// Kind of query (typo, abbreviation, ...), for the per-pattern breakdown
+    #[serde(default = "unknown_pattern")]
+    pub pattern: String,
+}
+
+fn unknown_pattern() -> String {
+    "unknown".to_string()
+}
+
+/// Catalog titles plus labelled queries (targeted-query format:
+/// `{"metadata": ..., "titles": [...], "queries": [...]}`)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Dataset {
+    pub titles: Vec<String>,
+    pub queries: Vec<EvalQuery>,
+}
+
+impl Dataset {
+    pub fn from_json(json: &str) -> io::Result<Self> {
+        serde_json::from_str(json).map_err(invalid_data)
+    }
+
+    /// Load a targeted-query dataset file
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        Self::from_json(&fs::read_to_string(path)?)
+    }
+
+    /// Same queries against another catalog
+    pub fn with_titles(mut self, titles: Vec<String>) -> Self {
+        self.titles = titles;
+        self
+    }
+}
+
+#[derive(Deserialize)]
+struct AppListFile {
+    applist: AppList,
+}
+
+#[derive(Deserialize)]
+struct AppList {
+    apps: Vec<App>,
+}
+
+#[derive(Deserialize)]
+struct App {
+    name: String,
+}
+
+/// Titles of a raw Steam app list (`{"applist": {"apps": [{"name": ...}]}}`)
+pub fn applist_from_json(json: &str) -> io::Result<Vec<String>> {
+    let file: AppListFile = serde_json::from_str(json).map_err(invalid_data)?;
+    Ok(file.applist.apps.into_iter().map(|app| app.name).collect())
+}
+
+/// Load the titles of a raw Steam app list file
+pub fn load_applist(path: impl AsRef<Path>) -> io::Result<Vec<String>> {
+    applist_from_json(&fs::read_to_string(path)?)
+}
+
+/// Titles of a catalog file: a Steam app list or a JSON array of titles
+pub fn catalog_from_json(json: &str) -> io::Result<Vec<String>> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum CatalogFile {
+        Titles(Vec<String>),
+        AppList(AppListFile),
+    }
+    match serde_json::from_str(json).map_err(invalid_data)? {
+        CatalogFile::Titles(titles) => Ok(titles),
+        CatalogFile::AppList(file) => Ok(file.applist.apps.into_iter().map(|app| app.name).collect()),
+    }
+}
+
+/// Load the titles of a catalog file (`catalog_from_json`)
+pub fn load_catalog(path: impl AsRef<Path>) -> io::Result<Vec<String>> {
+    catalog_from_json(&fs::read_to_string(path)?)
+}
+
+fn invalid_data(e: serde_json::Error) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, e)
+}
+
+// ═══════════════════════════════════════════════════════════════════════════
+// Evaluation
+// ═══════════════════════════════════════════════════════════════════════════
+
+/// Titles each query is ranked against
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Candidates {
+    /// The whole catalog
+    All,
+    /// Ground truth plus `distractors` random titles, drawn with `seed`
+    /// (the same draw for every query, as in `benchmark_optimized.py`)
+    Sampled { distractors: usize, seed: u64 },
+}
+
+#[derive(Debug, Clone)]
+pub struct EvalOptions {
+    /// k of the reported Acc@k
+    pub ks: Vec<usize>,
+    pub candidates: Candidates,
+    /// Evaluate only the first n queries
+    pub max_queries: Option<usize>,
+    /// Misses kept in the report
+    pub max_failures: usize,
+    /// Print progress every n queries
+    pub progress_every: Option<usize>,
+}
+
+impl Default for EvalOptions {
+    fn default() -> Self {
+        Self {
+            ks: vec![1, 3, 5, 10],
+            candidates: Candidates::Sampled { distractors: 100, seed: 42 },
+            max_queries: None,
+            max_failures: 50,
+            progress_every: None,
+        }
+    }
+}
+
+/// Accuracy of one query pattern
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PatternStats {
+    pub total: usize,
+    pub acc_at_1: f64,
+    /// Acc@k by k, the report's k (empty in older reports)
+    #[serde(default)]
+    pub acc_at: BTreeMap<usize, f64>,
+    pub mrr: f64,
+}
+
+/// Profile a report was scored with: weights, cap, corrections and
+/// penalties, plus a hash of the whole profile as serialized (word lists
+/// and flags too; a custom tokenizer isn't serialized)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ConfigFingerprint {
+    pub jaccard_weight: f64,
+    pub levenshtein_weight: f64,
+    pub anchor_weight: f64,
+    pub jaccard_cap: f64,
+    pub space_correction: f64,
+    pub anchor_correction: f64,
+    pub dlc_penalty: f64,
+    pub negation_penalty: f64,
+    /// 0 in reports from before it existed
+    #[serde(default)]
+    pub containment_bonus: f64,
+    pub levenshtein: LevKind,
+    /// FNV-1a of the profile's JSON, in hex
+    pub hash: String,
+}
+
+impl ConfigFingerprint {
+    pub fn of(profile: &DeltaProfile) -> Self {
+        let json = serde_json::to_string(profile).expect("DeltaProfile serializes");
+        let hash = json.bytes().fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| (hash ^ u64::from(byte)).wrapping_mul(0x100_0000_01b3));
+        Self {
+            jaccard_weight: profile.jaccard_weight,
+            levenshtein_weight: profile.levenshtein_weight,
+            anchor_weight: profile.anchor_weight,
+            jaccard_cap: profile.jaccard_cap,
+            space_correction: profile.space_correction,
+            anchor_correction: profile.anchor_correction,
+            dlc_penalty: profile.dlc_penalty,
+            negation_penalty: profile.negation_penalty,
+            containment_bonus: profile.containment_bonus,
+            levenshtein: profile.levenshtein,
+            hash: format!("{:016x}", hash),
+        }
+    }
+
+    /// "field: baseline → current" of each setting that differs from
+    /// `baseline` (only the hash when just the word lists or flags do)
+    pub fn changes(&self, baseline: &ConfigFingerprint) -> Vec<String> {
+        let fields = |config: &ConfigFingerprint| match serde_json::to_value(config) {
+            Ok(serde_json::Value::Object(fields)) => fields,
+            _ => unreachable!("ConfigFingerprint serializes to an object"),
+        };
+        let (current, baseline) = (fields(self), fields(baseline));
+        current
+            .iter()
+            .filter(|(field, value)| baseline.get(*field) != Some(value))
+            .map(|(field, value)| format!("{}: {} → {}", field, baseline.get(field).unwrap_or(&serde_json::Value::Null), value))
+            .collect()
+    }
+}
+
+/// Query whose ground truth was not ranked first
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Failure {
+    pub query: String,
+    pub ground_truth: String,
+    pub pattern: String,
+    /// Title ranked first instead
+    pub predicted: Option<String>,
+    /// Rank of the ground truth (1-based), `None` if not ranked at all
+    pub rank: Option<usize>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EvalReport {
+    pub total: usize,
+    /// Acc@k by k
+    pub acc_at: BTreeMap<usize, f64>,
+    /// Mean reciprocal rank (0 for unranked ground truths)
+    pub mrr: f64,
+    /// Queries whose ground truth is not in the catalog (counted as misses)
+    pub missing_ground_truth: usize,
+    pub per_pattern: BTreeMap<String, PatternStats>,
+    /// First `EvalOptions::max_failures` misses, in dataset order
+    pub failures: Vec<Failure>,
+    /// Candidates per ranked query (average)
+    pub avg_candidates: f64,
+    pub elapsed_secs: f64,
+    /// Profile scored with, when the caller gave it (`with_config`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub config: Option<ConfigFingerprint>,
+}
+
+impl EvalReport {
+    pub fn acc_at(&self, k: usize) -> Option<f64> {
+        self.acc_at.get(&k).copied()
+    }
+
+    pub fn throughput(&self) -> f64 {
+        if self.elapsed_secs > 0.0 {
+            self.total as f64 / self.elapsed_secs
+        } else {
+            0.0
+        }
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap_or_default()
+    }
+
+    pub fn from_json(json: &str) -> io::Result<Self> {
+        serde_json::from_str(json).map_err(invalid_data)
+    }
+
+    /// One row per pattern after an "all" row: query count, Acc@k, MRR and
+    /// the config hash
+    pub fn to_csv(&self) -> String {
+        let ks: Vec<usize> = self.acc_at.keys().copied().collect();
+        let config = self.config.as_ref().map_or("", |config| config.hash.as_str());
+        let mut csv = String::from("pattern,queries");
+        for k in &ks {
+            let _ = write!(csv, ",acc@{}", k);
+        }
+        csv.push_str(",mrr,config\n");
+        let mut row = |pattern: &str, total: usize, acc_at: &BTreeMap<usize, f64>, mrr: f64| {
+            let _ = write!(csv, "{},{}", csv_field(pattern), total);
+            for k in &ks {
+                csv.push(',');
+                if let Some(acc) = acc_at.get(k) {
+                    let _ = write!(csv, "{:.6}", acc);
+                }
+            }
+            let _ = writeln!(csv, ",{:.6},{}", mrr, config);
+        };
+        row("all", self.total, &self.acc_at, self.mrr);
+        for (pattern, stats) in &self.per_pattern {
+            row(pattern, stats.total, &stats.acc_at, stats.mrr);
+        }
+        csv
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        fs::write(path, self.to_json())
+    }
+
+    /// `save` as CSV for a `.csv` path, as JSON otherwise
+    pub fn write(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let path = path.as_ref();
+        if path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("csv")) {
+            fs::write(path, self.to_csv())
+        } else {
+            self.save(path)
+        }
+    }
+
+    /// This report labelled with the profile it was scored with
+    pub fn with_config(mut self, profile: &DeltaProfile) -> Self {
+        self.config = Some(ConfigFingerprint::of(profile));
+        self
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        Self::from_json(&fs::read_to_string(path)?)
+    }
+
+    /// Changes from `baseline` to this report
+    pub fn diff(&self, baseline: &EvalReport) -> ReportDiff {
+        let acc_at = self
+            .acc_at
+            .iter()
+            .filter_map(|(k, acc)| baseline.acc_at(*k).map(|base| (*k, acc - base)))
+            .collect();
+
+        let mut patterns: Vec<PatternDiff> = self
+            .per_pattern
+            .iter()
+            .filter_map(|(pattern, current)| {
+                baseline.per_pattern.get(pattern).map(|base| PatternDiff {
+                    pattern: pattern.clone(),
+                    baseline_acc: base.acc_at_1,
+                    current_acc: current.acc_at_1,
+                    delta: current.acc_at_1 - base.acc_at_1,
+                })
+            })
+            .collect();
+        patterns.sort_by(|a, b| a.delta.total_cmp(&b.delta).then_with(|| a.pattern.cmp(&b.pattern)));
+
+        let known: HashSet<&str> = baseline.failures.iter().map(|f| f.query.as_str()).collect();
+        let new_failures = self
+            .failures
+            .iter()
+            .filter(|f| !known.contains(f.query.as_str()))
+            .cloned()
+            .collect();
+
+        let config_changes = match (&self.config, &baseline.config) {
+            (Some(current), Some(base)) => current.changes(base),
+            _ => Vec::new(),
+        };
+
+        ReportDiff {
+            acc_at,
+            mrr: self.mrr - baseline.mrr,
+            patterns,
+            new_failures,
+            config_changes,
+        }
+    }
+}
+
+impl fmt::Display for EvalReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(config) = &self.config {
+            writeln!(f, "Config:         {} (wJ {:.2}, wL {:.2}, wR {:.2}, cap {:.2}, {:?})",
+                     config.hash, config.jaccard_weight, config.levenshtein_weight, config.anchor_weight,
+                     config.jaccard_cap, config.levenshtein)?;
+        }
+        writeln!(f, "Total queries:  {}", self.total)?;
+        for (k, acc) in &self.acc_at {
+            let hits = (acc * self.total as f64).round() as usize;
+            writeln!(f, "Accuracy@{:<2}    {:.4} ({}/{})", k, acc, hits, self.total)?;
+        }
+        writeln!(f, "MRR:            {:.4}", self.mrr)?;
+        if self.missing_ground_truth > 0 {
+            writeln!(f, "Missing GT:     {} (ground truth not in catalog)", self.missing_ground_truth)?;
+        }
+        let columns: Vec<String> = self.acc_at.keys().map(|k| format!("Acc@{}", k)).collect();
+        writeln!(f, "\nPer pattern ({} / MRR):", columns.join(" / "))?;
+        for (pattern, stats) in &self.per_pattern {
+            let accuracies: Vec<String> = self
+                .acc_at
+                .keys()
+                .map(|k| stats.acc_at.get(k).map_or("-".to_string(), |acc| format!("{:.4}", acc)))
+                .collect();
+            writeln!(f, "  {:<20} {} / {:.4} ({} queries)", pattern, accuracies.join(" / "), stats.mrr, stats.total)?;
+        }
+        Ok(())
+    }
+}
+
+/// Outcome of one query
+struct Ranked {
+    rank: Option<usize>,
+    predicted: Option<String>,
+    candidates: usize,
+}
+
+/// Rank every query of `dataset` with `ranker` (queries run in parallel
+/// with the `parallel` feature)
+pub fn evaluate<F>(ranker: F, dataset: &Dataset, options: &EvalOptions) -> EvalReport
+where
+    F: Fn(&str, &[String]) -> Vec<(usize, f64)> + Sync,
+{
+    let queries = &dataset.queries[..options.max_queries.unwrap_or(usize::MAX).min(dataset.queries.len())];
+    let mut index: HashMap<&str, usize> = HashMap::with_capacity(dataset.titles.len());
+    for (idx, title) in dataset.titles.iter().enumerate().rev() {
+        index.insert(title.as_str(), idx);
+    }
+
+    let start = Instant::now();
+    let done = AtomicUsize::new(0);
+    let ranked: Vec<Ranked> = maybe_par_iter!(queries)
+        .map(|query| {
+            let ranked = rank_query(&ranker, query, &dataset.titles, &index, options.candidates);
+            let done = done.fetch_add(1, Ordering::Relaxed) + 1;
+            if options.progress_every.is_some_and(|every| every > 0 && done.is_multiple_of(every)) {
+                let elapsed = start.elapsed().as_secs_f64();
+                let eta = elapsed / done as f64 * queries.len() as f64 - elapsed;
+                println!("Progress: {}/{} ({:.1}%) | Elapsed: {:.1}s | ETA: {:.0}s",
+                         done, queries.len(), 100.0 * done as f64 / queries.len() as f64, elapsed, eta);
+            }
+            ranked
+        })
+        .collect();
+    let elapsed_secs = start.elapsed().as_secs_f64();
+
+    let mut report = summarize(queries, &ranked, options);
+    report.missing_ground_truth = queries.iter().filter(|q| !index.contains_key(q.ground_truth.as_str())).count();
+    report.elapsed_secs = elapsed_secs;
+    report
+}
+
+fn rank_query<F>(
+    ranker: &F,
+    query: &EvalQuery,
+    titles: &[String],
+    index: &HashMap<&str, usize>,
+    candidates: Candidates,
+) -> Ranked
+where
+    F: Fn(&str, &[String]) -> Vec<(usize, f64)>,
+{
+    let Some(&gt_idx) = index.get(query.ground_truth.as_str()) else {
+        return Ranked { rank: None, predicted: None, candidates: 0 };
+    };
+
+    let (pool, gt_pos) = match candidates {
+        Candidates::All => (None, gt_idx),
+        Candidates::Sampled { distractors, seed } => {
+            let mut rng = rand::rngs::StdRng::seed_from_u64(seed);
+            let others: Vec<usize> = (0..titles.len()).filter(|&i| i != gt_idx).collect();
+            let mut pool = vec![titles[gt_idx].clone()];
+            pool.extend(others.choose_multiple(&mut rng, distractors.min(others.len())).map(|&i| titles[i].clone()));
+            (Some(pool), 0)
+        }
+    };
+    let candidates = pool.as_deref().unwrap_or(titles);
+
+    let ranking = ranker(&query.query, candidates);
+    Ranked {
+        rank: ranking.iter().position(|&(idx, _)| idx == gt_pos).map(|pos| pos + 1),
+        predicted: ranking.first().and_then(|&(idx, _)| candidates.get(idx).cloned()),
+        candidates: candidates.len(),
+    }
+}
+
+fn summarize(queries: &[EvalQuery], ranked: &[Ranked], options: &EvalOptions) -> EvalReport {
+    let total = queries.len();
+    let ratio = |n: usize, of: usize| if of == 0 { 0.0 } else { n as f64 / of as f64 };
+    let reciprocal = |r: &Ranked| r.rank.map_or(0.0, |rank| 1.0 / rank as f64);
+
+    let within = |ranked: &[&Ranked], k: usize| ranked.iter().filter(|r| r.rank.is_some_and(|rank| rank <= k)).count();
+    let acc_at = |ranked: &[&Ranked]| -> BTreeMap<usize, f64> {
+        options.ks.iter().map(|&k| (k, ratio(within(ranked, k), ranked.len()))).collect()
+    };
+
+    let mut patterns: BTreeMap<&str, Vec<&Ranked>> = BTreeMap::new();
+    for (query, r) in queries.iter().zip(ranked) {
+        patterns.entry(query.pattern.as_str()).or_default().push(r);
+    }
+    let per_pattern = patterns
+        .into_iter()
+        .map(|(pattern, ranked)| {
+            let stats = PatternStats {
+                total: ranked.len(),
+                acc_at_1: ratio(within(&ranked, 1), ranked.len()),
+                acc_at: acc_at(&ranked),
+                mrr: ranked.iter().map(|r| reciprocal(r)).sum::<f64>() / ranked.len() as f64,
+            };
+            (pattern.to_string(), stats)
+        })
+        .collect();
+
+    let failures = queries
+        .iter()
+        .zip(ranked)
+        .filter(|(_, r)| r.rank != Some(1))
+        .take(options.max_failures)
+        .map(|(query, r)| Failure {
+            query: query.query.clone(),
+            ground_truth: query.ground_truth.clone(),
+            pattern: query.pattern.clone(),
+            predicted: r.predicted.clone(),
+            rank: r.rank,
+        })
+        .collect();
+
+    EvalReport {
+        total,
+        acc_at: acc_at(&ranked.iter().collect::<Vec<_>>()),
+        mrr: if total == 0 { 0.0 } else { ranked.iter().map(reciprocal).sum::<f64>() / total as f64 },
+        missing_ground_truth: 0,
+        per_pattern,
+        failures,
+        avg_candidates: ratio(
+            ranked.iter().map(|r| r.candidates).sum(),
+            ranked.iter().filter(|r| r.candidates > 0).count(),
+        ),
+        elapsed_secs: 0.0,
+        config: None,
+    }
+}
+
+/// `field` quoted when it holds a comma, quote or line break
+fn csv_field(field: &str) -> Cow<'_, str> {
+    if field.contains([',', '"', '\n', '\r']) {
+        Cow::Owned(format!("\"{}\"", field.replace('"', "\"\"")))
+    } else {
+        Cow::Borrowed(field)
+    }
+}
+
+/// Best `k` titles of `catalog` as a ranker, for `Candidates::All` over the
+/// catalog's own titles (in its order): nothing is re-tokenized per query
+/// and ranks past `k` count as unranked (MRR@k)
+pub fn catalog_ranker(catalog: &DeltaCatalog, k: usize) -> impl Fn(&str, &[String]) -> Vec<(usize, f64)> + Sync + '_ {
+    move |query, candidates| {
+        assert_eq!(candidates.len(), catalog.len(), "catalog_ranker ranks the whole catalog (Candidates::All)");
+        catalog.top_k(query, k).into_iter().map(|m| (m.index, m.delta)).collect()
+    }
+}
+
+/// `catalog_ranker` through `DeltaCatalog::top_k_indexed` (a catalog built
+/// `with_index`)
+pub fn indexed_catalog_ranker(catalog: &DeltaCatalog, k: usize) -> impl Fn(&str, &[String]) -> Vec<(usize, f64)> + Sync + '_ {
+    move |query, candidates| {
+        assert_eq!(candidates.len(), catalog.len(), "indexed_catalog_ranker ranks the whole catalog (Candidates::All)");
+        catalog.top_k_indexed(query, k).into_iter().map(|m| (m.index, m.delta)).collect()
+    }
+}
+
+/// Share of the catalog `top_k_indexed` skips, averaged over `queries`
+pub fn prefilter_reduction(catalog: &DeltaCatalog, queries: &[EvalQuery], k: usize) -> f64 {
+    if queries.is_empty() || catalog.is_empty() {
+        return 0.0;
+    }
+    let scored: usize = maybe_par_iter!(queries).map(|query| catalog.candidate_count(&query.query, k)).sum();
+    1.0 - scored as f64 / (queries.len() * catalog.len()) as f64
+}
+
+/// Δₛ³ v3 as a ranker: ascending Δ (lower distance = better match)
+pub fn delta_ranker(query: &str, candidates: &[String]) -> Vec<(usize, f64)> {
+    profile_ranker(&DeltaProfile::title())(query, candidates)
+}
+
+/// `delta_ranker` under another profile, to compare settings on a dataset
+pub fn profile_ranker(profile: &DeltaProfile) -> impl Fn(&str, &[String]) -> Vec<(usize, f64)> + Sync + '_ {
+    move |query, candidates| {
+        let query = profile.prepare_query(query);
+        let mut scores: Vec<(usize, f64)> = candidates
+            .iter()
+            .enumerate()
+            .map(|(idx, title)| (idx, profile.delta_prepared(&query, title)))
+            .collect();
+        scores.sort_by(|a, b| a.1.total_cmp(&b.1));
+        scores
+    }
+}
+
+// ═══════════════════════════════════════════════════════════════════════════
+// Baseline comparison
+// ═══════════════════════════════════════════════════════════════════════════
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PatternDiff {
+    pub pattern: String,
+    pub baseline_acc: f64,
+    pub current_acc: f64,
+    /// current - baseline (Acc@1)
+    pub delta: f64,
+}
+
+/// Current report minus a baseline; patterns sorted worst change first
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReportDiff {
+    /// Acc@k change, for the k present in both reports
+    pub acc_at: BTreeMap<usize, f64>,
+    pub mrr: f64,
+    /// Patterns present in both reports
+    pub patterns: Vec<PatternDiff>,
+    /// Misses that were not among the baseline's recorded failures
+    pub new_failures: Vec<Failure>,
+    /// `ConfigFingerprint::changes`, when both reports carry a config
+    #[serde(default)]
+    pub config_changes: Vec<String>,
+}
+
+impl ReportDiff {
+    /// Patterns whose Acc@1 dropped by more than `tolerance`
+    pub fn regressed_patterns(&self, tolerance: f64) -> Vec<&PatternDiff> {
+        self.patterns.iter().filter(|p| p.delta < -tolerance).collect()
+    }
+
+    /// Whether any Acc@k, the MRR or a pattern dropped by more than `tolerance`
+    pub fn is_regression(&self, tolerance: f64) -> bool {
+        self.acc_at.values().any(|delta| *delta < -tolerance)
+            || self.mrr < -tolerance
+            || !self.regressed_patterns(tolerance).is_empty()
+    }
+}
+
+impl fmt::Display for ReportDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for change in &self.config_changes {
+            writeln!(f, "⚙️  {}", change)?;
+        }
+        for (k, delta) in &self.acc_at {
+            writeln!(f, "Accuracy@{:<2}    {:+.2} points", k, delta * 100.0)?;
+        }
+        writeln!(f, "MRR:            {:+.4}", self.mrr)?;
+        for p in &self.patterns {
+            let marker = if p.delta < 0.0 { "🔴" } else if p.delta > 0.0 { "🟢" } else { "  " };
+            writeln!(f, "{} {:<20} {:.4} → {:.4} ({:+.2} points)",
+                     marker, p.pattern, p.baseline_acc, p.current_acc, p.delta * 100.0)?;
+        }
+        for failure in &self.new_failures {
+            writeln!(f, "   new miss: {:?} → {:?} (expected {:?})",
+                     failure.query, failure.predicted.as_deref().unwrap_or("-"), failure.ground_truth)?;
+        }
+        Ok(())
+    }
+}
+
+/// `--save <path>` (CSV for a `.csv` path) / `--baseline <path>` (a JSON
+/// report) / `--tolerance <points>` handling shared by the benchmark
+/// binaries
+#[derive(Debug, Clone, Default)]
+pub struct ReportArgs {
+    pub save: Option<PathBuf>,
+    pub baseline: Option<PathBuf>,
+    /// Allowed Acc@1 drop, as a fraction
+    pub tolerance: f64,
+}
+
+impl ReportArgs {
+    pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Self, String> {
+        let mut parsed = Self::default();
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            let mut value = || args.next().ok_or_else(|| format!("{} needs a value", arg));
+            match arg.as_str() {
+                "--save" => parsed.save = Some(value()?.into()),
+                "--baseline" => parsed.baseline = Some(value()?.into()),
+                "--tolerance" => {
+                    let points: f64 = value()?.parse().map_err(|e| format!("--tolerance: {}", e))?;
+                    parsed.tolerance = points / 100.0;
+                }
+                other => return Err(format!("unexpected argument '{}'", other)),
+            }
+        }
+        Ok(parsed)
+    }
+
+    /// Save and/or compare `report`; `Ok(false)` on a regression
+    pub fn finish(&self, report: &EvalReport) -> io::Result<bool> {
+        if let Some(path) = &self.save {
+            report.write(path)?;
+            println!("💾 Report saved to {}", path.display());
+        }
+        let Some(path) = &self.baseline else {
+            return Ok(true);
+        };
+        let diff = report.diff(&EvalReport::load(path)?);
+        println!("\n📊 Against baseline {}:", path.display());
+        print!("{}", diff);
+        let regressed = diff.is_regression(self.tolerance);
+        if regressed {
+            let names: Vec<&str> = diff.regressed_patterns(self.tolerance).iter().map(|p| p.pattern.as_str()).collect();
+            println!("🔴 Regression (patterns: {})", if names.is_empty() { "-".to_string() } else { names.join(", ") });
+        }
+        Ok(!regressed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::WordSet;
+
+    fn dataset() -> Dataset {
+        Dataset::from_json(
+            r#"{
+                "metadata": {"total_titles": 4, "total_queries": 4},
+                "titles": ["Alpha", "Beta", "Gamma", "Delta"],
+                "queries": [
+                    {"query": "alpha", "ground_truth": "Alpha", "pattern": "exact"},
+                    {"query": "beta", "ground_truth": "Beta", "pattern": "exact"},
+                    {"query": "gama", "ground_truth": "Gamma", "pattern": "typo"},
+                    {"query": "omega", "ground_truth": "Omega", "pattern": "typo"}
+                ]
+            }"#,
+        )
+        .unwrap()
+    }
+
+    /// The repository test set (`datasets/games_testset.json`) as a targeted
+    /// dataset: its expected titles make the catalog
+    fn games_testset() -> Dataset {
+        let entries: Vec<serde_json::Value> =
+            serde_json::from_str(include_str!("../../datasets/games_testset.json")).unwrap();
+        let mut dataset = Dataset::default();
+        for entry in &entries {
+            let field = |name: &str| entry[name].as_str().unwrap().to_string();
+            if !dataset.titles.contains(&field("expected")) {
+                dataset.titles.push(field("expected"));
+            }
+            dataset.queries.push(EvalQuery { query: field("query"), ground_truth: field("expected"), pattern: field("category") });
+        }
+        dataset
+    }
+
+    type Orders = &'static [(&'static str, &'static [&'static str])];
+
+    /// Baseline rankings: "alpha" → Alpha first, "beta" → Beta second,
+    /// "gama" → Gamma fourth, "omega" has no ground truth in the catalog
+    const BASELINE: Orders = &[
+        ("alpha", &["Alpha", "Beta", "Gamma", "Delta"]),
+        ("beta", &["Alpha", "Beta", "Gamma", "Delta"]),
+        ("gama", &["Alpha", "Beta", "Delta", "Gamma"]),
+    ];
+
+    /// Ranker returning the fixed title order of each query
+    fn fixed(orders: Orders) -> impl Fn(&str, &[String]) -> Vec<(usize, f64)> + Sync {
+        move |query, candidates| {
+            let order = orders.iter().find(|(q, _)| *q == query).map_or(&[][..], |(_, order)| *order);
+            order
+                .iter()
+                .enumerate()
+                .filter_map(|(rank, title)| candidates.iter().position(|c| c == title).map(|idx| (idx, rank as f64)))
+                .collect()
+        }
+    }
+
+    fn full() -> EvalOptions {
+        EvalOptions { candidates: Candidates::All, ..Default::default() }
+    }
+
+    #[test]
+    fn test_every_metric() {
+        let report = evaluate(fixed(BASELINE), &dataset(), &full());
+
+        assert_eq!(report.total, 4);
+        assert_eq!(report.acc_at(1), Some(0.25));
+        assert_eq!(report.acc_at(3), Some(0.5));
+        assert_eq!(report.acc_at(5), Some(0.75));
+        assert_eq!(report.acc_at(10), Some(0.75));
+        assert_eq!(report.acc_at(2), None);
+        assert_eq!(report.mrr, (1.0 + 0.5 + 0.25) / 4.0);
+        assert_eq!(report.missing_ground_truth, 1);
+        // The missing-GT query is never ranked
+        assert_eq!(report.avg_candidates, 4.0);
+
+        let acc_at = |accuracies: [f64; 4]| [1, 3, 5, 10].into_iter().zip(accuracies).collect();
+        assert_eq!(report.per_pattern["exact"], PatternStats { total: 2, acc_at_1: 0.5, acc_at: acc_at([0.5, 1.0, 1.0, 1.0]), mrr: 0.75 });
+        assert_eq!(report.per_pattern["typo"], PatternStats { total: 2, acc_at_1: 0.0, acc_at: acc_at([0.0, 0.0, 0.5, 0.5]), mrr: 0.125 });
+
+        let misses: Vec<_> = report.failures.iter().map(|f| (f.query.as_str(), f.predicted.as_deref(), f.rank)).collect();
+        assert_eq!(misses, [("beta", Some("Alpha"), Some(2)), ("gama", Some("Alpha"), Some(4)), ("omega", None, None)]);
+
+        let capped = evaluate(fixed(BASELINE), &dataset(), &EvalOptions { max_failures: 1, max_queries: Some(2), ..full() });
+        assert_eq!((capped.total, capped.failures.len()), (2, 1));
+        assert_eq!(capped.acc_at(1), Some(0.5));
+    }
+
+    #[test]
+    fn test_sampled_candidates() {
+        let options = EvalOptions { candidates: Candidates::Sampled { distractors: 2, seed: 7 }, ..Default::default() };
+        let report = evaluate(fixed(BASELINE), &dataset(), &options);
+        assert_eq!(report.avg_candidates, 3.0);
+        // "alpha" is always found first among any subset
+        assert_eq!(report.failures.iter().filter(|f| f.query == "alpha").count(), 0);
+        // Same seed, same draw
+        assert_eq!(evaluate(fixed(BASELINE), &dataset(), &options).failures, report.failures);
+    }
+
+    #[test]
+    fn test_report_diff() {
+        let baseline = evaluate(fixed(BASELINE), &dataset(), &full());
+        // "gama" now ranked first, "alpha" dropped to second
+        let current = evaluate(
+            fixed(&[
+                ("alpha", &["Beta", "Alpha", "Gamma", "Delta"]),
+                ("beta", &["Alpha", "Beta", "Gamma", "Delta"]),
+                ("gama", &["Gamma", "Alpha", "Beta", "Delta"]),
+            ]),
+            &dataset(),
+            &full(),
+        );
+
+        let diff = current.diff(&baseline);
+        assert_eq!(diff.acc_at[&1], 0.0);
+        assert_eq!(diff.patterns.len(), 2);
+        assert_eq!((diff.patterns[0].pattern.as_str(), diff.patterns[0].delta), ("exact", -0.5));
+        assert_eq!((diff.patterns[1].pattern.as_str(), diff.patterns[1].delta), ("typo", 0.5));
+
+        let regressed: Vec<&str> = diff.regressed_patterns(0.01).iter().map(|p| p.pattern.as_str()).collect();
+        assert_eq!(regressed, ["exact"]);
+        assert!(diff.is_regression(0.01));
+        assert!(diff.regressed_patterns(0.6).is_empty());
+        let new: Vec<&str> = diff.new_failures.iter().map(|f| f.query.as_str()).collect();
+        assert_eq!(new, ["alpha"]);
+        assert_eq!(diff.mrr, current.mrr - baseline.mrr);
+
+        assert!(!baseline.diff(&baseline).is_regression(0.0));
+
+        // Reports survive a save/load round trip
+        let reloaded = EvalReport::from_json(&current.to_json()).unwrap();
+        assert_eq!(reloaded, current);
+    }
+
+    #[test]
+    fn test_config_fingerprint() {
+        let title = ConfigFingerprint::of(&DeltaProfile::title());
+        assert_eq!(title, ConfigFingerprint::of(&DeltaProfile::default()));
+        assert_eq!((title.hash.len(), title.jaccard_cap, title.levenshtein), (16, 0.8, LevKind::Classic));
+        // Word lists count too
+        let keywords = DeltaProfile { dlc_keywords: WordSet::new(["director's cut"]), ..DeltaProfile::title() };
+        assert_eq!(ConfigFingerprint::of(&keywords).changes(&title), [format!("hash: \"{}\" → \"{}\"", title.hash, ConfigFingerprint::of(&keywords).hash)]);
+
+        let baseline = evaluate(fixed(BASELINE), &dataset(), &full()).with_config(&DeltaProfile::title());
+        let capped = DeltaProfile { jaccard_cap: 0.7, ..DeltaProfile::title() };
+        let current = evaluate(fixed(BASELINE), &dataset(), &full()).with_config(&capped);
+        let diff = current.diff(&baseline);
+        assert_eq!(diff.config_changes.len(), 2);
+        assert_eq!(diff.config_changes[1], "jaccard_cap: 0.8 → 0.7");
+        assert!(!diff.is_regression(0.0));
+        assert!(diff.to_string().contains("jaccard_cap: 0.8 → 0.7"));
+        assert!(current.to_string().contains(&current.config.as_ref().unwrap().hash));
+        assert_eq!(EvalReport::from_json(&current.to_json()).unwrap(), current);
+
+        // Reports saved before per-pattern Acc@k and the config still load
+        let mut old: serde_json::Value = serde_json::from_str(&evaluate(fixed(BASELINE), &dataset(), &full()).to_json()).unwrap();
+        old["per_pattern"]["exact"].as_object_mut().unwrap().remove("acc_at");
+        let old = EvalReport::from_json(&old.to_string()).unwrap();
+        assert!(old.config.is_none() && old.per_pattern["exact"].acc_at.is_empty());
+        assert!(current.diff(&old).config_changes.is_empty());
+    }
+
+    #[test]
+    fn test_csv_report() {
+        let mut report = evaluate(fixed(BASELINE), &dataset(), &full()).with_config(&DeltaProfile::title());
+        let hash = report.config.clone().unwrap().hash;
+        let csv = report.to_csv();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines[0], "pattern,queries,acc@1,acc@3,acc@5,acc@10,mrr,config");
+        assert_eq!(lines[1], format!("all,4,0.250000,0.500000,0.750000,0.750000,0.437500,{}", hash));
+        assert_eq!(lines[3], format!("typo,2,0.000000,0.000000,0.500000,0.500000,0.125000,{}", hash));
+        assert_eq!(lines.len(), 4);
+
+        let stats = report.per_pattern.remove("exact").unwrap();
+        report.per_pattern.insert("exact, \"quoted\"".to_string(), stats);
+        assert!(report.to_csv().contains("\n\"exact, \"\"quoted\"\"\",2,"));
+
+        let dir = std::env::temp_dir();
+        let (csv_path, json_path) = (dir.join(format!("ds3_report_{}.CSV", std::process::id())), dir.join(format!("ds3_report_{}.json", std::process::id())));
+        report.write(&csv_path).unwrap();
+        report.write(&json_path).unwrap();
+        let (csv, reloaded) = (fs::read_to_string(&csv_path).unwrap(), EvalReport::load(&json_path));
+        fs::remove_file(&csv_path).unwrap();
+        fs::remove_file(&json_path).unwrap();
+        assert_eq!(csv, report.to_csv());
+        assert_eq!(reloaded.unwrap(), report);
+    }
+
+    #[test]
+    fn test_accent_folding_does_not_regress() {
+        let dataset = games_testset();
+        let folded = evaluate(delta_ranker, &dataset, &full());
+        let accented = DeltaProfile { fold_accents: false, ..DeltaProfile::title() };
+        let baseline = evaluate(profile_ranker(&accented), &dataset, &full());
+        let diff = folded.diff(&baseline);
+        // Acc@1, MRR and every pattern; deep ranks of the untranslated CJK
+        // queries move with any change to the tokens
+        assert!(diff.acc_at[&1] >= 0.0 && diff.mrr >= 0.0, "{}", diff);
+        assert!(diff.regressed_patterns(0.0).is_empty() && diff.new_failures.is_empty(), "{}", diff);
+        assert!(folded.acc_at(1) >= Some(0.88), "{}", folded);
+    }
+
+    #[test]
+    fn test_applist_loader() {
+        let titles = applist_from_json(r#"{"applist": {"apps": [{"appid": 10, "name": "Counter-Strike"}, {"name": "Portal"}]}}"#).unwrap();
+        assert_eq!(titles, ["Counter-Strike", "Portal"]);
+        assert!(applist_from_json("{}").is_err());
+
+        let dataset = dataset().with_titles(titles);
+        assert_eq!(dataset.queries.len(), 4);
+    }
+
+    #[test]
+    fn test_catalog_loader() {
+        let applist = r#"{"applist": {"apps": [{"appid": 10, "name": "Counter-Strike"}, {"name": "Portal"}]}}"#;
+        assert_eq!(catalog_from_json(applist).unwrap(), applist_from_json(applist).unwrap());
+        assert_eq!(catalog_from_json(r#"["Hades", "Portal 2"]"#).unwrap(), ["Hades", "Portal 2"]);
+        assert!(catalog_from_json("[1, 2]").is_err());
+        assert!(catalog_from_json(r#"{"titles": ["Hades"]}"#).is_err());
+    }
+
+    #[test]
+    fn test_report_args() {
+        let args = ["--save", "out.json", "--baseline", "base.json", "--tolerance", "0.5"].map(String::from);
+        let parsed = ReportArgs::parse(args).unwrap();
+        assert_eq!(parsed.save, Some(PathBuf::from("out.json")));
+        assert_eq!(parsed.baseline, Some(PathBuf::from("base.json")));
+        assert_eq!(parsed.tolerance, 0.005);
+        assert!(ReportArgs::parse(["--save".to_string()]).is_err());
+        assert!(ReportArgs::parse(["--bogus".to_string()]).is_err());
+    }
+}
@@ -11,6 +11,7 @@ High-performance implementation of the Δₛ³ algorithm validated at 97.45% Acc
 - Jaccard: cap=0.80, bigrams enabled
 - Levenshtein: Symmetric (bidirectional)
 - Features: content-aware roman mapping, DLC debias, gaming acronyms
+  ("gta v" → grand theft auto 5)
 
 ### SENTENCE Mode (natural language)
 - Weights: wJ=0.25, wL=0.55, wR=0.20
@@ -18,14 +19,62 @@ High-performance implementation of the Δₛ³ algorithm validated at 97.45% Acc
 - Jaccard: cap=0.60, stopwords filtered
 - Negation: penalty=0.10
 
+The TITLE constants are the defaults of `DeltaProfile::title()`, the
+SENTENCE ones those of `DeltaProfile::sentence()`; a profile (also named
+`DeltaConfig`) can override any of them, DLC keywords and stopwords
+included (and is deserializable, e.g. from a config file),
+`DeltaProfile::validate` rejects out-of-range values.
+
+## Tokenizers
+Titles split into words on non-alphanumeric boundaries by default. Japanese,
+Chinese and Korean titles have no such boundaries, so a `DeltaProfile`
+switches to character bigrams when enough of the input is CJK, or uses the
+`TokenizerMode` it is given (fixed n-grams, or any closure).
+
+## Evaluation
+`eval` ranks a labelled dataset with any ranker and reports Acc@k, MRR and
+per-pattern accuracy; the benchmark binaries are thin CLIs over it.
+
 ## Performance Targets
 - Single query: <1ms (150K titles)
 - Throughput: 10K+ queries/s (8 cores)
 - Latency p99: <5ms
 */
 
-use std::collections::{HashMap, HashSet};
-use unicode_normalization::UnicodeNormalization;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use smallvec::SmallVec;
+use std::borrow::Cow;
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, BinaryHeap, HashMap, HashSet};
+use std::fmt;
+use std::sync::{Arc, Mutex, OnceLock, PoisonError, RwLock};
+use unicode_normalization::{char::is_combining_mark, UnicodeNormalization};
+
+/// `par_iter()` with the `parallel` feature, `iter()` without
+macro_rules! maybe_par_iter {
+    ($items:expr) => {{
+        #[cfg(feature = "parallel")]
+        let iter = $items.par_iter();
+        #[cfg(not(feature = "parallel"))]
+        let iter = $items.iter();
+        iter
+    }};
+}
+
+pub mod eval;
+
+pub mod build_info;
+
+#[cfg(feature = "cli")]
+pub mod bench_common;
+
+#[cfg(feature = "python")]
+mod python;
+
+#[cfg(feature = "wasm")]
+mod wasm;
 
 // ═══════════════════════════════════════════════════════════════════════════
 // Constants
@@ -36,16 +85,53 @@ const STOPWORDS: &[&str] = &[
     "of", "with", "by", "from", "as", "is", "was", "are", "were", "be",
 ];
 
-const ROMAN_NUMERALS: &[&str] = &[
-    "i", "ii", "iii", "iv", "v", "vi", "vii", "viii", "ix", "x",
-    "xi", "xii", "xiii", "xiv", "xv", "xvi", "xvii", "xviii", "xix", "xx",
+/// Characters opening a subtitle ("Nine: The Game", "Two - Remastered")
+const SUBTITLE_SEPARATORS: &[char] = &[':', '-', '–', '—', '|'];
+
+/// Roman numeral symbols, subtractive pairs included, largest first
+const ROMAN_SYMBOLS: &[(u32, &str)] = &[
+    (1000, "m"), (900, "cm"), (500, "d"), (400, "cd"), (100, "c"), (90, "xc"),
+    (50, "l"), (40, "xl"), (10, "x"), (9, "ix"), (5, "v"), (4, "iv"), (1, "i"),
+];
+
+/// Words that are also well-formed numerals ("dance mix" isn't 1009)
+const ROMAN_LOOKALIKES: &[&str] = &[
+    "mix", "dix", "civ", "di", "li", "mi", "lix", "cd", "dc", "mc", "md", "ml", "cc", "mm", "xl",
+];
+
+/// English number words and their value
+const NUMBER_WORDS: &[(&str, u32)] = &[
+    ("one", 1), ("two", 2), ("three", 3), ("four", 4), ("five", 5), ("six", 6), ("seven", 7),
+    ("eight", 8), ("nine", 9), ("ten", 10), ("eleven", 11), ("twelve", 12), ("thirteen", 13),
+    ("fourteen", 14), ("fifteen", 15), ("sixteen", 16), ("seventeen", 17), ("eighteen", 18),
+    ("nineteen", 19), ("twenty", 20),
+];
+
+const NEGATIONS: &[&str] = &["not", "no", "never"];
+
+/// Gaming acronyms and what they stand for (expanded on both sides, so a
+/// title spelling one out matches too)
+const GAMING_ACRONYMS: &[(&str, &str)] = &[
+    ("gta", "grand theft auto"), ("gtav", "grand theft auto 5"), ("gta5", "grand theft auto 5"),
+    ("cod", "call of duty"), ("wow", "world of warcraft"), ("tf2", "team fortress 2"),
+    ("csgo", "counter strike global offensive"), ("cs2", "counter strike 2"),
+    ("botw", "the legend of zelda breath of the wild"), ("totk", "the legend of zelda tears of the kingdom"),
+    ("ff", "final fantasy"), ("ff7", "final fantasy 7"), ("ffvii", "final fantasy 7"),
+    ("ffxiv", "final fantasy 14"), ("ff14", "final fantasy 14"),
+    ("lol", "league of legends"), ("dota", "defense of the ancients"),
+    ("pubg", "playerunknown's battlegrounds"), ("rdr", "red dead redemption"), ("rdr2", "red dead redemption 2"),
+    ("mgs", "metal gear solid"), ("mgsv", "metal gear solid 5"), ("hl2", "half life 2"),
+    ("l4d", "left 4 dead"), ("l4d2", "left 4 dead 2"), ("nfs", "need for speed"),
+    ("tes", "the elder scrolls"), ("eso", "the elder scrolls online"),
+    ("kotor", "star wars knights of the old republic"), ("poe", "path of exile"),
+    ("fnaf", "five nights at freddy's"), ("dbd", "dead by daylight"), ("r6", "rainbow six"),
+    ("ssbu", "super smash bros ultimate"), ("mk8", "mario kart 8"), ("ow2", "overwatch 2"),
 ];
 
-const ROMAN_TO_ARABIC: &[(&str, &str)] = &[
-    ("i", "1"), ("ii", "2"), ("iii", "3"), ("iv", "4"), ("v", "5"),
-    ("vi", "6"), ("vii", "7"), ("viii", "8"), ("ix", "9"), ("x", "10"),
-    ("xi", "11"), ("xii", "12"), ("xiii", "13"), ("xiv", "14"), ("xv", "15"),
-    ("xvi", "16"), ("xvii", "17"), ("xviii", "18"), ("xix", "19"), ("xx", "20"),
+/// Letters NFKD leaves whole, spelled out in ASCII
+const TRANSLITERATIONS: &[(char, &str)] = &[
+    ('œ', "oe"), ('Œ', "OE"), ('æ', "ae"), ('Æ', "AE"), ('ß', "ss"), ('ẞ', "SS"),
+    ('ø', "o"), ('Ø', "O"), ('ł', "l"), ('Ł', "L"), ('đ', "d"), ('Đ', "D"),
 ];
 
 const DLC_KEYWORDS: &[&str] = &[
@@ -58,81 +144,306 @@ const DLC_KEYWORDS: &[&str] = &[
 // Normalization
 // ═══════════════════════════════════════════════════════════════════════════
 
+/// Tokens of a text: most titles have a handful, kept inline
+type Tokens = SmallVec<[String; 4]>;
+
 #[inline]
 fn normalize_v2(text: &str) -> Vec<String> {
-    let text_lower = text.nfc().collect::<String>().to_lowercase();
-    
-    // Tokenize
-    let mut tokens: Vec<String> = text_lower
-        .split(|c: char| !c.is_alphanumeric())
-        .filter(|s| !s.is_empty())
-        .map(|s| s.to_string())
+    normalize_words(text, true)
+}
+
+/// `normalize_v2`, the single letters an apostrophe cuts off ("l'amour")
+/// dropped only with `drop_elisions`
+fn normalize_words(text: &str, drop_elisions: bool) -> Vec<String> {
+    normalize_lowered(&lowercase(text), drop_elisions).into_vec()
+}
+
+/// `normalize_words` of a text already through `lowercase`
+fn normalize_lowered(text_lower: &str, drop_elisions: bool) -> Tokens {
+    let words = split_words(text_lower, drop_elisions);
+    // Whether a subtitle separator follows each word ("nine: the game")
+    let separated: SmallVec<[bool; 8]> = words
+        .iter()
+        .map(|word| {
+            let end = word.as_ptr() as usize - text_lower.as_ptr() as usize + word.len();
+            text_lower[end..].trim_start().starts_with(SUBTITLE_SEPARATORS)
+        })
         .collect();
+    let mut tokens: Tokens = words.into_iter().map(str::to_string).collect();
+    let number_words = number_words_to_map(&tokens, &separated);
     
-    // Content-aware roman mapping
-    if should_map_roman(&tokens) {
-        for token in &mut tokens {
-            for (roman, arabic) in ROMAN_TO_ARABIC {
-                if token == roman {
-                    *token = arabic.to_string();
-                    break;
-                }
-            }
+    // Numerals as digits: ordinals ("2nd" → 2), roman numerals and number
+    // words after a base name ("civilization vi", "dark souls two")
+    for i in 0..tokens.len() {
+        let value = if let Some(digits) = ordinal_digits(&tokens[i]) {
+            Some(digits.to_string())
+        } else if should_map_roman(&tokens, i) {
+            parse_roman(&tokens[i]).map(|value| value.to_string())
+        } else if number_words[i] {
+            number_word(&tokens[i]).map(|value| value.to_string())
+        } else {
+            None
+        };
+        if let Some(value) = value {
+            tokens[i] = value;
         }
     }
     
     tokens
 }
 
+fn is_apostrophe(c: char) -> bool {
+    matches!(c, '\'' | '’')
+}
+
+/// Alphanumeric runs of `text`, without a possessive "s" ("assassin's
+/// creed" → assassin creed; "gamers'" has none to drop) and, with
+/// `drop_elisions`, without the single letters an apostrophe cuts off
+/// ("l'amour" → amour, "rock 'n' roll" → rock roll). Lone letters
+/// elsewhere stay ("rocky v").
+fn split_words(text: &str, drop_elisions: bool) -> SmallVec<[&str; 8]> {
+    let mut words = SmallVec::new();
+    // The two characters before the current run
+    let (mut before, mut last) = (None, None);
+    let mut chars = text.char_indices().peekable();
+    while let Some((start, c)) = chars.next() {
+        if !c.is_alphanumeric() {
+            (before, last) = (last, Some(c));
+            continue;
+        }
+        let (mut end, mut len) = (start + c.len_utf8(), 1);
+        while let Some(&(i, c)) = chars.peek().filter(|(_, c)| c.is_alphanumeric()) {
+            (end, len) = (i + c.len_utf8(), len + 1);
+            chars.next();
+        }
+        let word = &text[start..end];
+        let after_apostrophe = last.is_some_and(is_apostrophe);
+        let possessive = word == "s" && after_apostrophe && before.is_some_and(char::is_alphanumeric);
+        let elided = len == 1 && (after_apostrophe || chars.peek().is_some_and(|&(_, c)| is_apostrophe(c)));
+        if !(possessive || (drop_elisions && elided)) {
+            words.push(word);
+        }
+        let last_char = word.chars().next_back();
+        (before, last) = (if len > 1 { word.chars().rev().nth(1) } else { last }, last_char);
+    }
+    words
+}
+
+/// `text` cut after `max_chars` characters
+fn truncate_chars(text: &str, max_chars: usize) -> &str {
+    match text.char_indices().nth(max_chars) {
+        Some((end, _)) => &text[..end],
+        None => text,
+    }
+}
+
+/// `text` NFC-composed and case-folded: lowercase, "ß" as "ss" (its
+/// uppercase), so "STRASSE" = "straße" even without `fold_accents`
+fn lowercase(text: &str) -> String {
+    if text.is_ascii() {
+        return text.to_ascii_lowercase();
+    }
+    let lower = text.nfc().collect::<String>().to_lowercase();
+    if lower.contains('ß') {
+        lower.replace('ß', "ss")
+    } else {
+        lower
+    }
+}
+
+/// `text` with accents folded away: letters and digits NFKD-decomposed
+/// without combining marks, plus `TRANSLITERATIONS` ("Pokémon" → "Pokemon",
+/// "Œuvre" → "OEuvre", "Straße" → "Strasse"). CJK characters are kept
+/// whole, their voicing marks telling kana apart.
+fn fold_accents(text: &str) -> Cow<'_, str> {
+    if text.is_ascii() {
+        return Cow::Borrowed(text);
+    }
+    let mut folded = String::with_capacity(text.len());
+    for c in text.chars() {
+        if let Some((_, latin)) = TRANSLITERATIONS.iter().find(|(letter, _)| *letter == c) {
+            folded.push_str(latin);
+        } else if c.is_alphanumeric() && !is_cjk(c) {
+            folded.extend(c.nfkd().filter(|c| !is_combining_mark(*c)));
+        } else {
+            folded.push(c);
+        }
+    }
+    Cow::Owned(folded)
+}
+
+/// Character n-grams of each alphanumeric run of a text already through
+/// `lowercase` ("ゼルダの伝説" → ゼル, ルダ, ダの, の伝, 伝説); runs shorter than
+/// `n` are kept whole
+fn char_ngrams(text_lower: &str, n: usize) -> Tokens {
+    let n = n.max(1);
+    let mut grams = Tokens::new();
+    for run in text_lower.split(|c: char| !c.is_alphanumeric()).filter(|s| !s.is_empty()) {
+        // Byte offset of each character, then of the end
+        let bounds: SmallVec<[usize; 32]> = run.char_indices().map(|(i, _)| i).chain([run.len()]).collect();
+        if bounds.len() <= n + 1 {
+            grams.push(run.to_string());
+        } else {
+            grams.extend(bounds.windows(n + 1).map(|w| run[w[0]..w[n]].to_string()));
+        }
+    }
+    grams
+}
+
+/// Hiragana, katakana, CJK ideographs and hangul
 #[inline]
-fn should_map_roman(tokens: &[String]) -> bool {
-    // Only map if we see short tokens (1-4 chars) with digits or roman numerals
-    let short_tokens: Vec<_> = tokens.iter()
-        .filter(|t| t.len() >= 1 && t.len() <= 4)
-        .collect();
+fn is_cjk(c: char) -> bool {
+    matches!(c,
+        '\u{3040}'..='\u{30FF}'     // Hiragana, Katakana
+        | '\u{31F0}'..='\u{31FF}'   // Katakana phonetic extensions
+        | '\u{3400}'..='\u{4DBF}'   // CJK extension A
+        | '\u{4E00}'..='\u{9FFF}'   // CJK unified ideographs
+        | '\u{AC00}'..='\u{D7AF}'   // Hangul syllables
+        | '\u{F900}'..='\u{FAFF}'   // CJK compatibility ideographs
+        | '\u{FF66}'..='\u{FF9F}'   // Halfwidth katakana
+    )
+}
+
+/// Fraction of the alphanumeric characters of `text` that are CJK
+fn cjk_fraction(text: &str) -> f64 {
+    let (cjk, total) = text
+        .chars()
+        .filter(|c| c.is_alphanumeric())
+        .fold((0usize, 0usize), |(cjk, total), c| (cjk + is_cjk(c) as usize, total + 1));
     
-    short_tokens.iter().any(|t| {
-        t.chars().all(|c| c.is_ascii_digit()) || 
-        ROMAN_NUMERALS.contains(&t.as_str())
-    })
+    if total == 0 {
+        0.0
+    } else {
+        cjk as f64 / total as f64
+    }
+}
+
+/// Value of a lowercase roman numeral in canonical form (subtractive
+/// notation, 1–3999): "xxvi" → 26, "iiii" and "vx" → None
+fn parse_roman(token: &str) -> Option<u32> {
+    let mut value = 0u32;
+    let mut rest = token;
+    while !rest.is_empty() {
+        let &(symbol_value, symbol) = ROMAN_SYMBOLS.iter().find(|(_, symbol)| rest.starts_with(symbol))?;
+        value = value.saturating_add(symbol_value);
+        rest = &rest[symbol.len()..];
+    }
+    // Only the canonical spelling of the value ("il" isn't 49)
+    ((1..=3999).contains(&value) && is_canonical_roman(value, token)).then_some(value)
+}
+
+/// Whether `numeral` is the canonical roman numeral of `value`
+fn is_canonical_roman(mut value: u32, numeral: &str) -> bool {
+    let mut rest = numeral;
+    for &(symbol_value, symbol) in ROMAN_SYMBOLS {
+        while value >= symbol_value {
+            match rest.strip_prefix(symbol) {
+                Some(tail) => rest = tail,
+                None => return false,
+            }
+            value -= symbol_value;
+        }
+    }
+    rest.is_empty()
+}
+
+/// Whether `tokens[i]` reads as a numeral of the title: a well-formed one
+/// standing alone after a base name ("rocky iv", "final fantasy vii
+/// remake"), not a word that happens to spell one ("dance mix") nor a
+/// title made of the word alone ("vi")
+#[inline]
+fn should_map_roman(tokens: &[String], i: usize) -> bool {
+    let token = tokens[i].as_str();
+    i > 0 && !ROMAN_LOOKALIKES.contains(&token) && parse_roman(token).is_some()
+}
+
+/// Value of a number word, "one" to "twenty"
+fn number_word(token: &str) -> Option<u32> {
+    NUMBER_WORDS.iter().find(|(word, _)| *word == token).map(|&(_, value)| value)
+}
+
+/// Which of `tokens` read as the number of the title, as
+/// `should_map_roman` does for numerals: a number word after a base name,
+/// ending it ("dark souls two", "portal two: reloaded", "dark souls two
+/// remastered") or next to another numeral ("left four dead 2"). Not one
+/// opening the title ("one piece"), following a stopword ("the two
+/// towers"), in a phrase ("it takes two") or in the middle of a name
+/// ("zero escape: nine hours, nine persons, nine doors"). `separated` tells
+/// which tokens a subtitle separator follows.
+fn number_words_to_map(tokens: &[String], separated: &[bool]) -> SmallVec<[bool; 8]> {
+    let phrase_start = tokens.iter().position(|token| SENTENCE_MARKERS.contains(&token.as_str())).unwrap_or(tokens.len());
+    let is_number_word = |j: usize| {
+        j > 0 && j <= phrase_start && number_word(&tokens[j]).is_some() && !STOPWORDS.contains(&tokens[j - 1].as_str())
+    };
+    // Last, or before a subtitle or an edition
+    let ends_name = |j: usize| separated[j] || tokens.get(j + 1).is_none_or(|next| DLC_KEYWORDS.contains(&next.as_str()));
+    let is_numeral = |j: usize| {
+        tokens[j].bytes().all(|b| b.is_ascii_digit())
+            || ordinal_digits(&tokens[j]).is_some()
+            || should_map_roman(tokens, j)
+            || (is_number_word(j) && ends_name(j))
+    };
+    let numerals: SmallVec<[bool; 8]> = (0..tokens.len()).map(is_numeral).collect();
+    let count = numerals.iter().filter(|&&numeral| numeral).count();
+    (0..tokens.len())
+        .map(|i| is_number_word(i) && (ends_name(i) || count > usize::from(numerals[i])))
+        .collect()
+}
+
+/// Digits of an ordinal with its English suffix ("2nd" → "2", "11th" →
+/// "11"), None for anything else ("2th", "4x4")
+fn ordinal_digits(token: &str) -> Option<&str> {
+    let split = token.find(|c: char| !c.is_ascii_digit())?;
+    let (digits, suffix) = token.split_at(split);
+    let value: u64 = digits.parse().ok()?;
+    let expected = match (value % 100, value % 10) {
+        (11..=13, _) => "th",
+        (_, 1) => "st",
+        (_, 2) => "nd",
+        (_, 3) => "rd",
+        _ => "th",
+    };
+    (suffix == expected).then_some(digits)
+}
+
+/// Whether `text` has a negation word or a n't contraction ("don't")
+fn has_negation(text: &str) -> bool {
+    text.to_lowercase()
+        .split(|c: char| !(c.is_alphanumeric() || c == '\'' || c == '’'))
+        .any(|word| NEGATIONS.contains(&word) || word.ends_with("n't") || word.ends_with("n’t"))
 }
 
 #[inline]
-fn is_dlc_like(tokens: &[String]) -> bool {
-    let tokens_str = tokens.join(" ");
-    DLC_KEYWORDS.iter().any(|kw| tokens_str.contains(kw))
+fn is_dlc_like(tokens: &[String], keywords: &WordSet) -> bool {
+    keywords.matches_any(tokens)
 }
 
 // ═══════════════════════════════════════════════════════════════════════════
 // Jaccard Index (with bigrams)
 // ═══════════════════════════════════════════════════════════════════════════
 
+/// Shared unigrams and bigrams over all of them, each weighted by its IDF
+/// when `frequencies` are given
 #[inline]
-fn jaccard_index(tokens_a: &[String], tokens_b: &[String]) -> f64 {
-    if tokens_a.is_empty() && tokens_b.is_empty() {
+fn jaccard_index(a: &QueryContext, b: &QueryContext, frequencies: Option<&DocumentFrequencies>) -> f64 {
+    if a.tokens.is_empty() && b.tokens.is_empty() {
         return 1.0;
     }
-    if tokens_a.is_empty() || tokens_b.is_empty() {
+    if a.tokens.is_empty() || b.tokens.is_empty() {
         return 0.0;
     }
     
-    // Unigrams
-    let set_a: HashSet<_> = tokens_a.iter().collect();
-    let set_b: HashSet<_> = tokens_b.iter().collect();
-    
-    let intersection = set_a.intersection(&set_b).count();
-    let union = set_a.union(&set_b).count();
-    
-    // Bigrams
-    let bigrams_a = make_bigrams(tokens_a);
-    let bigrams_b = make_bigrams(tokens_b);
-    
-    let bi_intersection = bigrams_a.intersection(&bigrams_b).count();
-    let bi_union = bigrams_a.union(&bigrams_b).count();
+    if let Some(frequencies) = frequencies {
+        let mut shared = 0.0;
+        a.shared_terms(b, |term| shared += b.idf(frequencies, term));
+        let union = a.idf_weight + b.idf_weight - shared;
+        return if union > 0.0 { (shared / union).min(1.0) } else { 0.0 };
+    }
     
-    // Combine
-    let total_intersection = intersection + bi_intersection;
-    let total_union = union + bi_union;
+    // Unigrams and bigrams alike
+    let mut total_intersection = 0;
+    a.shared_terms(b, |_| total_intersection += 1);
+    let total_union = a.term_count() + b.term_count() - total_intersection;
     
     if total_union == 0 {
         0.0
@@ -141,62 +452,150 @@ fn jaccard_index(tokens_a: &[String], tokens_b: &[String]) -> f64 {
     }
 }
 
+/// Calls `shared` with the position in `b` of each item `a` holds too,
+/// both sorted by `cmp`
 #[inline]
-fn make_bigrams(tokens: &[String]) -> HashSet<String> {
-    tokens.windows(2)
-        .map(|w| format!("{}_{}", w[0], w[1]))
-        .collect()
+fn merge_shared<T>(a: &[T], b: &[T], cmp: impl Fn(&T, &T) -> Ordering, mut shared: impl FnMut(usize)) {
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() && j < b.len() {
+        match cmp(&a[i], &b[j]) {
+            Ordering::Less => i += 1,
+            Ordering::Greater => j += 1,
+            Ordering::Equal => {
+                shared(j);
+                i += 1;
+                j += 1;
+            }
+        }
+    }
+}
+
+/// Titles each unigram and bigram ("witcher_3") of a catalog occurs in.
+/// With `DeltaProfile::document_frequencies`, Jaccard weighs every term by
+/// its IDF: a "the" shared with half the catalog counts for less than
+/// "witcher".
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DocumentFrequencies {
+    /// Titles counted
+    pub documents: usize,
+    pub counts: BTreeMap<String, usize>,
+}
+
+impl DocumentFrequencies {
+    /// Frequencies over `titles` tokenized by `profile`
+    pub fn count<S: AsRef<str> + Sync>(profile: &DeltaProfile, titles: &[S]) -> Self {
+        let prepared: Vec<QueryContext> = maybe_par_iter!(titles).map(|title| profile.prepare_query(title.as_ref())).collect();
+        Self::of_prepared(&prepared)
+    }
+    
+    fn of_prepared(titles: &[QueryContext]) -> Self {
+        let mut counts = BTreeMap::new();
+        for title in titles {
+            for term in title.terms() {
+                *counts.entry(term.into_owned()).or_default() += 1;
+            }
+        }
+        Self { documents: titles.len(), counts }
+    }
+    
+    /// Smoothed IDF of `term`, ln((1 + N) / (1 + df)) + 1: 1 for a term of
+    /// every title, highest for a term of none
+    pub fn idf(&self, term: &str) -> f64 {
+        let df = self.counts.get(term).copied().unwrap_or(0);
+        ((1 + self.documents) as f64 / (1 + df) as f64).ln() + 1.0
+    }
 }
 
 // ═══════════════════════════════════════════════════════════════════════════
 // Levenshtein Similarity (Symmetric)
 // ═══════════════════════════════════════════════════════════════════════════
 
+/// Rows of the edit distance table, reused across the pairs of a token:
+/// three rows in one buffer, inline up to 31-character tokens
+#[derive(Default)]
+struct EditRows {
+    buffer: SmallVec<[usize; 96]>,
+}
+
+/// Edit distance of `kind` between `a` and `b` if at most `max_dist`,
+/// abandoned as soon as a whole row of the table exceeds it. Row minima
+/// never decrease (a Damerau transposition never costs less than the row
+/// before it), so the distance is past the cutoff by then.
 #[inline]
-fn levenshtein_distance(a: &str, b: &str) -> usize {
-    let len_a = a.chars().count();
-    let len_b = b.chars().count();
-    
-    if len_a == 0 {
-        return len_b;
+fn levenshtein_distance_bounded(a: &[char], b: &[char], max_dist: usize, kind: LevKind, rows: &mut EditRows) -> Option<usize> {
+    if a.len().abs_diff(b.len()) > max_dist {
+        return None;
+    }
+    if a.is_empty() {
+        return Some(b.len());
     }
-    if len_b == 0 {
-        return len_a;
+    if b.is_empty() {
+        return Some(a.len());
     }
     
-    let mut prev_row: Vec<usize> = (0..=len_b).collect();
-    let mut curr_row = vec![0; len_b + 1];
+    let width = b.len() + 1;
+    rows.buffer.clear();
+    rows.buffer.resize(3 * width, 0);
+    // A transposition looks two rows back
+    let (mut before_prev, rest) = rows.buffer.split_at_mut(width);
+    let (mut prev, mut curr) = rest.split_at_mut(width);
+    for (j, cell) in prev.iter_mut().enumerate() {
+        *cell = j;
+    }
     
-    for (i, ca) in a.chars().enumerate() {
-        curr_row[0] = i + 1;
+    for (i, &ca) in a.iter().enumerate() {
+        curr[0] = i + 1;
+        let mut row_min = curr[0];
         
-        for (j, cb) in b.chars().enumerate() {
+        for (j, &cb) in b.iter().enumerate() {
             let cost = if ca == cb { 0 } else { 1 };
-            curr_row[j + 1] = (curr_row[j] + 1)
-                .min(prev_row[j + 1] + 1)
-                .min(prev_row[j] + cost);
+            curr[j + 1] = (curr[j] + 1)
+                .min(prev[j + 1] + 1)
+                .min(prev[j] + cost);
+            // Optimal string alignment: swapped neighbours cost 1
+            if kind == LevKind::Damerau && i > 0 && j > 0 && ca == b[j - 1] && a[i - 1] == cb {
+                curr[j + 1] = curr[j + 1].min(before_prev[j - 1] + 1);
+            }
+            row_min = row_min.min(curr[j + 1]);
         }
         
-        std::mem::swap(&mut prev_row, &mut curr_row);
+        if row_min > max_dist {
+            return None;
+        }
+        std::mem::swap(&mut before_prev, &mut prev);
+        std::mem::swap(&mut prev, &mut curr);
     }
     
-    prev_row[len_b]
+    Some(prev[b.len()]).filter(|dist| *dist <= max_dist)
 }
 
+/// Best similarity (1 − distance / longer length) of `a` to any of
+/// `candidates`, 0 when there are none. A pair is only worked out far
+/// enough to tell whether it beats the best so far, which leaves the
+/// result unchanged.
 #[inline]
-fn levenshtein_sim(a: &str, b: &str) -> f64 {
-    let dist = levenshtein_distance(a, b);
-    let max_len = a.len().max(b.len());
+fn best_levenshtein_sim<'c>(a: &[char], candidates: impl IntoIterator<Item = &'c [char]>, kind: LevKind, rows: &mut EditRows) -> f64 {
+    let mut best = 0.0;
     
-    if max_len == 0 {
-        1.0
-    } else {
-        1.0 - (dist as f64 / max_len as f64)
+    for b in candidates {
+        let max_len = a.len().max(b.len());
+        if max_len == 0 {
+            return 1.0;
+        }
+        // Largest distance whose similarity isn't below `best`
+        let max_dist = ((1.0 - best) * max_len as f64).ceil() as usize;
+        if let Some(dist) = levenshtein_distance_bounded(a, b, max_dist, kind, rows) {
+            best = f64::max(best, 1.0 - (dist as f64 / max_len as f64));
+        }
+        if best >= 1.0 {
+            break;
+        }
     }
+    best
 }
 
 #[inline]
-fn l_symmetric(tokens_a: &[String], tokens_b: &[String]) -> f64 {
+fn l_symmetric(tokens_a: &TokenChars, tokens_b: &TokenChars, kind: LevKind) -> f64 {
     if tokens_a.is_empty() && tokens_b.is_empty() {
         return 1.0;
     }
@@ -204,53 +603,82 @@ fn l_symmetric(tokens_a: &[String], tokens_b: &[String]) -> f64 {
         return 0.0;
     }
     
+    let mut rows = EditRows::default();
+    
     // Forward: each token in A finds best match in B
-    let fwd_scores: Vec<f64> = tokens_a.iter()
-        .map(|a| {
-            tokens_b.iter()
-                .map(|b| levenshtein_sim(a, b))
-                .fold(0.0, f64::max)
-        })
-        .collect();
+    let fwd_sum: f64 = tokens_a.iter()
+        .map(|a| best_levenshtein_sim(a, tokens_b.iter(), kind, &mut rows))
+        .sum();
     
     // Backward: each token in B finds best match in A
-    let bwd_scores: Vec<f64> = tokens_b.iter()
-        .map(|b| {
-            tokens_a.iter()
-                .map(|a| levenshtein_sim(a, b))
-                .fold(0.0, f64::max)
-        })
-        .collect();
+    let bwd_sum: f64 = tokens_b.iter()
+        .map(|b| best_levenshtein_sim(b, tokens_a.iter(), kind, &mut rows))
+        .sum();
     
     // Average of both directions
-    let fwd_avg = fwd_scores.iter().sum::<f64>() / fwd_scores.len() as f64;
-    let bwd_avg = bwd_scores.iter().sum::<f64>() / bwd_scores.len() as f64;
+    let fwd_avg = fwd_sum / tokens_a.len() as f64;
+    let bwd_avg = bwd_sum / tokens_b.len() as f64;
     
     (fwd_avg + bwd_avg) / 2.0
 }
 
+/// Characters of each token of a text, in one buffer
+#[derive(Debug, Clone, Default, PartialEq)]
+struct TokenChars {
+    chars: SmallVec<[char; 24]>,
+    /// Where each token ends in `chars`
+    ends: SmallVec<[u32; 6]>,
+}
+
+impl TokenChars {
+    fn new(tokens: &[String]) -> Self {
+        let mut chars = SmallVec::new();
+        let ends = tokens
+            .iter()
+            .map(|token| {
+                chars.extend(token.chars());
+                chars.len() as u32
+            })
+            .collect();
+        Self { chars, ends }
+    }
+    
+    fn len(&self) -> usize {
+        self.ends.len()
+    }
+    
+    fn is_empty(&self) -> bool {
+        self.ends.is_empty()
+    }
+    
+    fn iter(&self) -> impl Iterator<Item = &[char]> + '_ {
+        let starts = std::iter::once(0).chain(self.ends.iter().copied());
+        starts.zip(&self.ends).map(|(start, &end)| &self.chars[start as usize..end as usize])
+    }
+}
+
 // ═══════════════════════════════════════════════════════════════════════════
 // Anchor Ratio
 // ═══════════════════════════════════════════════════════════════════════════
 
 #[inline]
-fn compute_anchor_ratio(q_concat: &str, t_concat: &str) -> f64 {
+fn anchor_set(tokens: &[String]) -> HashSet<char> {
     let anchors = ['+', '-', '#', ':', '.'];
     
-    let q_anchors: HashSet<_> = q_concat.chars()
-        .filter(|c| anchors.contains(c))
-        .collect();
-    
-    let t_anchors: HashSet<_> = t_concat.chars()
+    tokens.iter()
+        .flat_map(|token| token.chars())
         .filter(|c| anchors.contains(c))
-        .collect();
-    
+        .collect()
+}
+
+#[inline]
+fn compute_anchor_ratio(q_anchors: &HashSet<char>, t_anchors: &HashSet<char>) -> f64 {
     if q_anchors.is_empty() && t_anchors.is_empty() {
         return 1.0;
     }
     
-    let intersection = q_anchors.intersection(&t_anchors).count();
-    let union = q_anchors.union(&t_anchors).count();
+    let intersection = q_anchors.intersection(t_anchors).count();
+    let union = q_anchors.union(t_anchors).count();
     
     if union == 0 {
         1.0
@@ -259,100 +687,2533 @@ fn compute_anchor_ratio(q_concat: &str, t_concat: &str) -> f64 {
     }
 }
 
+/// Share of the title's tokens that are the query's, in the same order;
+/// the last one may be cut short ("stardew val") and counts for the share
+/// of its characters typed. 0 unless the title contains them all.
+#[inline]
+fn containment_coverage(q_tokens: &[String], t_tokens: &[String]) -> f64 {
+    let Some((last, leading)) = q_tokens.split_last() else {
+        return 0.0;
+    };
+    if q_tokens.len() > t_tokens.len() {
+        return 0.0;
+    }
+    let mut title = t_tokens.iter();
+    if !leading.iter().all(|token| title.any(|other| other == token)) {
+        return 0.0;
+    }
+    match title.find(|other| other.starts_with(last.as_str())) {
+        Some(completed) => {
+            let typed = last.chars().count() as f64 / completed.chars().count() as f64;
+            (leading.len() as f64 + typed) / t_tokens.len() as f64
+        }
+        None => 0.0,
+    }
+}
+
 // ═══════════════════════════════════════════════════════════════════════════
-// TITLE Mode Delta
+// Profile (tokenizer, weights, corrections)
 // ═══════════════════════════════════════════════════════════════════════════
 
-pub fn semantic_delta_title(query: &str, title: &str) -> f64 {
-    let q_tokens = normalize_v2(query);
-    let t_tokens = normalize_v2(title);
-    
-    if q_tokens.is_empty() || t_tokens.is_empty() {
-        return 1.0;
+/// Custom tokenizer: raw text in, tokens out
+pub type TokenizerFn = Arc<dyn Fn(&str) -> Vec<String> + Send + Sync>;
+
+/// How a profile splits text into tokens
+#[derive(Clone, Default)]
+pub enum TokenizerMode {
+    /// Words on non-alphanumeric boundaries, with roman numeral mapping
+    /// (character bigrams for mostly-CJK text, see `DeltaProfile::cjk_threshold`)
+    #[default]
+    Default,
+    /// Character n-grams of each alphanumeric run, whatever the script
+    CharNGrams(usize),
+    /// Caller-provided tokenizer (no normalization applied)
+    Custom(TokenizerFn),
+}
+
+/// Custom tokenizers compare by identity
+impl PartialEq for TokenizerMode {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (TokenizerMode::Default, TokenizerMode::Default) => true,
+            (TokenizerMode::CharNGrams(a), TokenizerMode::CharNGrams(b)) => a == b,
+            (TokenizerMode::Custom(a), TokenizerMode::Custom(b)) => Arc::ptr_eq(a, b),
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Debug for TokenizerMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TokenizerMode::Default => write!(f, "Default"),
+            TokenizerMode::CharNGrams(n) => write!(f, "CharNGrams({})", n),
+            TokenizerMode::Custom(_) => write!(f, "Custom(..)"),
+        }
+    }
+}
+
+/// Words and phrases looked up as whole tokens, normalized like titles
+/// when added ("Jeu de l'Année" → "jeu de l annee"): lookups are hash
+/// lookups, whatever the size of the list. (De)serializes as a list.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(from = "Vec<String>", into = "Vec<String>")]
+pub struct WordSet {
+    entries: HashSet<String>,
+    /// Most words in an entry
+    longest: usize,
+}
+
+impl WordSet {
+    pub fn new<S: AsRef<str>>(words: impl IntoIterator<Item = S>) -> Self {
+        let mut set = Self::default();
+        set.extend(words);
+        set
     }
     
-    // Jaccard
-    let mut j = jaccard_index(&q_tokens, &t_tokens);
-    
-    // Levenshtein symmetric
-    let l = l_symmetric(&q_tokens, &t_tokens);
-    
-    // Anchor ratio
-    let q_concat = q_tokens.join("");
-    let t_concat = t_tokens.join("");
-    let r = compute_anchor_ratio(&q_concat, &t_concat);
-    
-    // Corrections TITLE mode
-    let alpha = 0.25;
-    let beta = 0.35;
-    
-    let mu_space = if q_tokens.len() == 1 && t_tokens.len() > 1 {
-        alpha * (1.0 - j)
-    } else {
-        0.0
-    };
-    
-    let mu_anchor = beta * (1.0 - r);
-    
-    j = (j + mu_space).min(1.0);
-    
-    // Cap Jaccard (TITLE mode)
-    let j_cap = 0.80;
-    j = j.min(j_cap);
+    /// This set and `words`, to extend a preset list
+    pub fn with<S: AsRef<str>>(mut self, words: impl IntoIterator<Item = S>) -> Self {
+        self.extend(words);
+        self
+    }
     
-    // Weights TITLE mode
-    let w_j = 0.40;
-    let w_l = 0.40;
-    let w_r = 0.20;
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
     
-    let mut delta = w_j * (1.0 - j) + w_l * (1.0 - l) + w_r * (1.0 - r);
-    delta = (delta + mu_anchor).min(1.0);
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
     
-    // DLC debias
-    delta = apply_dlc_debias(delta, &q_tokens, &t_tokens);
+    /// Whether `token` (a lowercase token, accents folded here) is an entry
+    pub fn contains(&self, token: &str) -> bool {
+        self.entries.contains(fold_accents(token).as_ref())
+    }
     
-    delta.max(0.0).min(1.0)
+    /// Whether an entry occurs in `tokens`, a phrase as consecutive tokens
+    fn matches_any(&self, tokens: &[String]) -> bool {
+        if tokens.iter().any(|token| self.contains(token)) {
+            return true;
+        }
+        (2..=self.longest.min(tokens.len())).any(|n| {
+            tokens.windows(n).any(|phrase| self.contains(&phrase.join(" ")))
+        })
+    }
 }
 
-#[inline]
-fn apply_dlc_debias(delta: f64, q_tokens: &[String], t_tokens: &[String]) -> f64 {
-    if is_dlc_like(t_tokens) && !is_dlc_like(q_tokens) {
-        (delta * 1.05).min(1.0)  // 5% penalty
-    } else {
-        delta
+impl<S: AsRef<str>> Extend<S> for WordSet {
+    fn extend<I: IntoIterator<Item = S>>(&mut self, words: I) {
+        for word in words {
+            let word = fold_accents(word.as_ref());
+            // With and without elided letters, whichever a profile drops
+            for tokens in [normalize_v2(&word), normalize_words(&word, false)] {
+                if !tokens.is_empty() {
+                    self.longest = self.longest.max(tokens.len());
+                    self.entries.insert(tokens.join(" "));
+                }
+            }
+        }
     }
 }
 
-// ═══════════════════════════════════════════════════════════════════════════
-// Auto-detect mode (TITLE if short, SENTENCE if long)
-// ═══════════════════════════════════════════════════════════════════════════
+impl From<Vec<String>> for WordSet {
+    fn from(words: Vec<String>) -> Self {
+        Self::new(words)
+    }
+}
 
-pub fn semantic_delta_v3(query: &str, title: &str) -> f64 {
-    // For now, only TITLE mode (games/articles)
-    // SENTENCE mode can be added later if needed
-    semantic_delta_title(query, title)
+impl From<WordSet> for Vec<String> {
+    /// Entries in alphabetical order
+    fn from(set: WordSet) -> Self {
+        let mut words: Vec<String> = set.entries.into_iter().collect();
+        words.sort();
+        words
+    }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+/// Edit distance of the Levenshtein term
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LevKind {
+    /// Insertions, deletions and substitutions: a swap of two adjacent
+    /// characters costs 2
+    #[default]
+    Classic,
+    /// Optimal string alignment: a swap costs 1 ("sotry" → "story")
+    Damerau,
+}
+
+/// Scoring profile: tokenizer, term weights, corrections and word lists,
+/// with the `title()` and `sentence()` presets. Deserializes with the TITLE
+/// defaults for missing fields; the tokenizer is code, not data, and is
+/// never (de)serialized.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DeltaProfile {
+    #[serde(skip)]
+    pub tokenizer: TokenizerMode,
+    /// With `TokenizerMode::Default`: CJK fraction of the alphanumeric
+    /// characters from which a text is split into character n-grams
+    /// (`None` always splits into words)
+    pub cjk_threshold: Option<f64>,
+    /// n of the automatic CJK n-grams
+    pub cjk_ngram: usize,
+    /// Weights of the Jaccard, Levenshtein and anchor terms (wJ, wL, wR),
+    /// summing to 1
+    pub jaccard_weight: f64,
+    pub levenshtein_weight: f64,
+    pub anchor_weight: f64,
+    /// Edit distance of the Levenshtein term
+    pub levenshtein: LevKind,
+    /// Highest Jaccard index counted
+    pub jaccard_cap: f64,
+    /// α: Jaccard boost of a one-word query against a longer title
+    /// containing it
+    pub space_correction: f64,
+    /// β: Δ added for a weak anchor (prefix) match
+    pub anchor_correction: f64,
+    /// Share added to the Δ of a DLC-like title when the query is not one
+    /// (0.05 = +5%)
+    pub dlc_penalty: f64,
+    /// Keywords marking a DLC-like title, words or phrases matching whole
+    /// tokens ("jeu de l'année")
+    pub dlc_keywords: WordSet,
+    /// Tokens dropped before comparing, unless nothing else is left (none
+    /// in TITLE mode, `STOPWORDS` in SENTENCE mode)
+    pub stopwords: WordSet,
+    /// Δ added when one side is negated ("not", "no", "never", "n't") and
+    /// the other isn't
+    pub negation_penalty: f64,
+    /// Share of its Δ above an exact match's taken off a title containing
+    /// the query's tokens in order ("stardew" → Stardew Valley), times the
+    /// share of the title's tokens they make up
+    pub containment_bonus: f64,
+    /// Expand `GAMING_ACRONYMS` ("gta v" → grand theft auto 5) when
+    /// splitting into words
+    pub gaming_acronyms: bool,
+    /// More acronyms (lowercase word → what it stands for), expanded even
+    /// without `gaming_acronyms` and taking precedence over them
+    pub extra_acronyms: BTreeMap<String, String>,
+    /// Fold accents before tokenizing ("pokemon" = "Pokémon"), except with
+    /// a custom tokenizer, which gets the text as is
+    pub fold_accents: bool,
+    /// Drop the single letters an apostrophe cuts off when splitting into
+    /// words ("l'amour" → amour, "rock 'n' roll" → rock roll); a
+    /// possessive "'s" is always dropped
+    pub drop_elisions: bool,
+    /// Characters of a text scored, the rest ignored: edit distances grow
+    /// with the square of the length, and a chat message can be 1 MB
+    pub max_chars: usize,
+    /// Weigh the Jaccard terms by their IDF over these frequencies (`None`:
+    /// each counts 1); `DeltaCatalog::prebuild_idf` counts its own titles
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub document_frequencies: Option<Arc<DocumentFrequencies>>,
+}
+
+/// Default `max_chars`: a Twitch message holds at most 500
+pub const MAX_CHARS: usize = 512;
+
+/// `DeltaProfile` under the name of what it is to a downstream user: the
+/// whole tunable configuration of the algorithm (movie titles, SKUs...)
+pub type DeltaConfig = DeltaProfile;
+
+/// Tolerance on the weights summing to 1
+const WEIGHT_SUM_TOLERANCE: f64 = 0.01;
+
+/// A `DeltaProfile` value out of its range
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidProfile {
+    /// Offending field, as serialized
+    pub field: &'static str,
+    pub reason: String,
+}
+
+impl fmt::Display for InvalidProfile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.field, self.reason)
+    }
+}
+
+impl std::error::Error for InvalidProfile {}
+
+impl Default for DeltaProfile {
+    fn default() -> Self {
+        Self::title()
+    }
+}
+
+impl DeltaProfile {
+    /// Validated TITLE mode profile (97.45% Acc@1)
+    pub fn title() -> Self {
+        Self {
+            tokenizer: TokenizerMode::Default,
+            cjk_threshold: Some(0.5),
+            cjk_ngram: 2,
+            jaccard_weight: 0.40,
+            levenshtein_weight: 0.40,
+            anchor_weight: 0.20,
+            levenshtein: LevKind::Classic,
+            jaccard_cap: 0.80,
+            space_correction: 0.25,
+            anchor_correction: 0.35,
+            dlc_penalty: 0.05,
+            dlc_keywords: WordSet::new(DLC_KEYWORDS),
+            stopwords: WordSet::default(),
+            negation_penalty: 0.0,
+            containment_bonus: 0.5,
+            gaming_acronyms: true,
+            extra_acronyms: BTreeMap::new(),
+            fold_accents: true,
+            drop_elisions: true,
+            max_chars: MAX_CHARS,
+            document_frequencies: None,
+        }
+    }
     
-    #[test]
-    fn test_exact_match() {
-        let delta = semantic_delta_v3("zelda", "zelda");
-        assert!(delta < 0.1);
+    /// SENTENCE mode profile, for natural language
+    pub fn sentence() -> Self {
+        Self {
+            jaccard_weight: 0.25,
+            levenshtein_weight: 0.55,
+            anchor_weight: 0.20,
+            jaccard_cap: 0.60,
+            space_correction: 0.15,
+            anchor_correction: 0.10,
+            dlc_penalty: 0.0,
+            stopwords: WordSet::new(STOPWORDS),
+            negation_penalty: 0.10,
+            containment_bonus: 0.0,
+            gaming_acronyms: false,
+            ..Self::title()
+        }
     }
     
-    #[test]
-    fn test_roman_numeral() {
-        let delta = semantic_delta_v3("doom 2", "DOOM II");
-        assert!(delta < 0.3);
+    /// Check every value is in range
+    pub fn validate(&self) -> Result<(), InvalidProfile> {
+        let invalid = |field, reason: String| Err(InvalidProfile { field, reason });
+        let unit = |value: f64| (0.0..=1.0).contains(&value);
+        
+        for (field, value) in [
+            ("jaccard_weight", self.jaccard_weight),
+            ("levenshtein_weight", self.levenshtein_weight),
+            ("anchor_weight", self.anchor_weight),
+            ("space_correction", self.space_correction),
+            ("anchor_correction", self.anchor_correction),
+            ("negation_penalty", self.negation_penalty),
+            ("containment_bonus", self.containment_bonus),
+        ] {
+            if !unit(value) {
+                return invalid(field, format!("must be between 0 and 1, got {}", value));
+            }
+        }
+        let sum = self.jaccard_weight + self.levenshtein_weight + self.anchor_weight;
+        if (sum - 1.0).abs() > WEIGHT_SUM_TOLERANCE {
+            return invalid(
+                "jaccard_weight",
+                format!("jaccard_weight + levenshtein_weight + anchor_weight must sum to 1, got {:.3}", sum),
+            );
+        }
+        if !(self.jaccard_cap > 0.0 && self.jaccard_cap <= 1.0) {
+            return invalid("jaccard_cap", format!("must be in (0, 1], got {}", self.jaccard_cap));
+        }
+        if !(self.dlc_penalty >= 0.0 && self.dlc_penalty.is_finite()) {
+            return invalid("dlc_penalty", format!("must not be negative, got {}", self.dlc_penalty));
+        }
+        if let Some(threshold) = self.cjk_threshold.filter(|threshold| !unit(*threshold)) {
+            return invalid("cjk_threshold", format!("must be between 0 and 1 (null for words only), got {}", threshold));
+        }
+        if self.cjk_ngram == 0 {
+            return invalid("cjk_ngram", "must be at least 1".to_string());
+        }
+        if self.max_chars == 0 {
+            return invalid("max_chars", "must be at least 1".to_string());
+        }
+        if let Some(acronym) = self.extra_acronyms.keys().find(|acronym| normalize_v2(acronym) != [acronym.as_str()]) {
+            return invalid("extra_acronyms", format!("{:?} must be a single lowercase word", acronym));
+        }
+        if let Some(frequencies) = &self.document_frequencies {
+            if let Some((term, count)) = frequencies.counts.iter().find(|(_, count)| **count > frequencies.documents) {
+                return invalid(
+                    "document_frequencies",
+                    format!("{:?} counted in {} of {} documents", term, count, frequencies.documents),
+                );
+            }
+        }
+        Ok(())
     }
     
-    #[test]
-    fn test_dlc_debias() {
-        let delta1 = semantic_delta_v3("portal", "Portal 2");
-        let delta2 = semantic_delta_v3("portal", "Portal 2 GOTY Edition");
-        assert!(delta2 > delta1); // GOTY should be penalized
+    pub fn with_tokenizer(tokenizer: TokenizerMode) -> Self {
+        Self {
+            tokenizer,
+            ..Self::default()
+        }
+    }
+    
+    /// Tokens of `text` under this profile
+    pub fn tokenize(&self, text: &str) -> Vec<String> {
+        self.drop_stopwords(self.split(truncate_chars(text, self.max_chars))).into_vec()
+    }
+    
+    /// Tokens of `text`, stopwords included
+    fn split(&self, text: &str) -> Tokens {
+        match &self.tokenizer {
+            TokenizerMode::Custom(tokenize) => Tokens::from_vec(tokenize(text)),
+            _ => self.split_lowered(&self.fold_lowercase(text)),
+        }
+    }
+    
+    /// `text` case-folded, accents folded too with `fold_accents`
+    fn fold_lowercase(&self, text: &str) -> String {
+        if self.fold_accents {
+            lowercase(&fold_accents(text))
+        } else {
+            lowercase(text)
+        }
+    }
+    
+    /// `split` of a text already through `fold_lowercase` (any tokenizer
+    /// but a custom one)
+    fn split_lowered(&self, text: &str) -> Tokens {
+        match self.tokenizer {
+            TokenizerMode::CharNGrams(n) => char_ngrams(text, n),
+            _ => match self.cjk_threshold {
+                Some(threshold) if cjk_fraction(text) >= threshold => char_ngrams(text, self.cjk_ngram),
+                _ => self.expand_acronyms(normalize_lowered(text, self.drop_elisions)),
+            },
+        }
+    }
+    
+    /// `tokens` without stopwords, unless nothing else is left
+    fn drop_stopwords(&self, mut tokens: Tokens) -> Tokens {
+        if !self.stopwords.is_empty() && tokens.iter().any(|t| !self.stopwords.contains(t)) {
+            tokens.retain(|t| !self.stopwords.contains(t));
+        }
+        tokens
+    }
+    
+    /// The words `token` stands for, if an acronym
+    fn acronym(&self, token: &str) -> Option<&str> {
+        self.extra_acronyms.get(token).map(String::as_str).or_else(|| {
+            GAMING_ACRONYMS
+                .iter()
+                .find(|(acronym, _)| self.gaming_acronyms && *acronym == token)
+                .map(|(_, words)| *words)
+        })
+    }
+    
+    /// `tokens` with each acronym replaced by the words it stands for
+    fn expand_acronyms(&self, tokens: Tokens) -> Tokens {
+        if !self.gaming_acronyms && self.extra_acronyms.is_empty() {
+            return tokens;
+        }
+        if !tokens.iter().any(|token| self.acronym(token).is_some()) {
+            return tokens;
+        }
+        let mut expanded = Tokens::with_capacity(tokens.len());
+        for token in tokens {
+            match self.acronym(&token) {
+                Some(words) => expanded.extend(normalize_v2(words)),
+                None => expanded.push(token),
+            }
+        }
+        expanded
+    }
+    
+    /// Whether `text` counts as negated (never with a zero `negation_penalty`)
+    fn is_negated(&self, text: &str) -> bool {
+        self.negation_penalty > 0.0 && has_negation(text)
+    }
+    
+    /// `query` tokenized under this profile, to score many titles against
+    /// with `delta_prepared`
+    pub fn prepare_query(&self, query: &str) -> QueryContext {
+        let query = truncate_chars(query, self.max_chars);
+        // Case-folded once: the raw text and what the tokens are cut from
+        let raw = self.fold_lowercase(query.trim());
+        // DLC phrases may hold stopwords ("jeu de l'année")
+        let words = match &self.tokenizer {
+            TokenizerMode::Custom(tokenize) => Tokens::from_vec(tokenize(query)),
+            _ => self.split_lowered(&raw),
+        };
+        let dlc_like = is_dlc_like(&words, &self.dlc_keywords);
+        let negated = self.is_negated(query.trim());
+        let mut context = QueryContext::new(self.drop_stopwords(words), raw, dlc_like, negated);
+        if let Some(frequencies) = &self.document_frequencies {
+            context.weigh(frequencies);
+        }
+        context
+    }
+    
+    /// Δ between `query` and `title` (0 = identical, 1 = unrelated)
+    pub fn delta(&self, query: &str, title: &str) -> f64 {
+        self.delta_prepared(&self.prepare_query(query), title)
+    }
+    
+    /// `delta` against a query prepared once with `prepare_query` (by this
+    /// profile: another tokenizer's context gives meaningless scores)
+    pub fn delta_prepared(&self, query: &QueryContext, title: &str) -> f64 {
+        delta_title_tokens(self, query, &self.prepare_query(title)).delta
+    }
+    
+    /// `delta` term by term, with the tokens of both sides
+    pub fn explain(&self, query: &str, title: &str) -> DeltaExplanation {
+        let (query, title) = (self.prepare_query(query), self.prepare_query(title));
+        DeltaExplanation {
+            query_tokens: query.tokens.to_vec(),
+            title_tokens: title.tokens.to_vec(),
+            ..delta_title_tokens(self, &query, &title)
+        }
+    }
+    
+    /// Δ between `query` and each title (in parallel with the `parallel`
+    /// feature)
+    pub fn delta_batch<S: AsRef<str> + Sync>(&self, query: &str, titles: &[S]) -> Vec<f64> {
+        let query = self.prepare_query(query);
+        maybe_par_iter!(titles)
+            .map(|title| self.delta_prepared(&query, title.as_ref()))
+            .collect()
+    }
+}
+
+// ═══════════════════════════════════════════════════════════════════════════
+// Prepared query
+// ═══════════════════════════════════════════════════════════════════════════
+
+/// A query's tokens with the sets every comparison derives from them
+/// (distinct unigrams, bigrams, characters of each token, anchors, DLC and
+/// negation flags) and its raw text, built once per query instead of once
+/// per title. A `DeltaCatalog` keeps its titles prepared the same way.
+///
+/// A unigram is a token's position, a bigram a pair of unigram ranks
+/// instead of a "witcher_3" string. Both lists are sorted by their text, so
+/// two contexts are compared by merging them, without a shared table.
+#[derive(Debug, Clone, PartialEq)]
+pub struct QueryContext {
+    tokens: Tokens,
+    /// Position in `tokens` of each distinct token, in text order
+    unigrams: SmallVec<[u32; 8]>,
+    /// Distinct pairs of adjacent tokens as ranks in `unigrams`, sorted
+    bigrams: SmallVec<[(u32, u32); 8]>,
+    chars: TokenChars,
+    anchors: HashSet<char>,
+    /// Trimmed and case-folded, compared when a side has no tokens ("///")
+    raw: String,
+    /// IDF of each unigram then each bigram, with document frequencies
+    term_idf: Vec<f64>,
+    /// Summed IDF of the unigrams and bigrams, with document frequencies
+    idf_weight: f64,
+    dlc_like: bool,
+    negated: bool,
+}
+
+impl QueryContext {
+    fn new(tokens: Tokens, raw: String, dlc_like: bool, negated: bool) -> Self {
+        let mut order: SmallVec<[u32; 8]> = (0..tokens.len() as u32).collect();
+        order.sort_unstable_by(|&a, &b| tokens[a as usize].cmp(&tokens[b as usize]));
+        let mut unigrams = SmallVec::<[u32; 8]>::new();
+        let mut ranks: SmallVec<[u32; 8]> = SmallVec::from_elem(0, tokens.len());
+        for (i, &position) in order.iter().enumerate() {
+            if i == 0 || tokens[position as usize] != tokens[order[i - 1] as usize] {
+                unigrams.push(position);
+            }
+            ranks[position as usize] = unigrams.len() as u32 - 1;
+        }
+        let mut bigrams: SmallVec<[(u32, u32); 8]> = ranks.windows(2).map(|w| (w[0], w[1])).collect();
+        bigrams.sort_unstable();
+        bigrams.dedup();
+        
+        Self {
+            raw,
+            unigrams,
+            bigrams,
+            term_idf: Vec::new(),
+            idf_weight: 0.0,
+            chars: TokenChars::new(&tokens),
+            anchors: anchor_set(&tokens),
+            dlc_like,
+            negated,
+            tokens,
+        }
+    }
+    
+    /// Tokens of the query, as the profile tokenized it
+    pub fn tokens(&self) -> &[String] {
+        &self.tokens
+    }
+    
+    fn token(&self, position: u32) -> &str {
+        &self.tokens[position as usize]
+    }
+    
+    /// Distinct token of rank `rank`
+    fn unigram(&self, rank: u32) -> &str {
+        self.token(self.unigrams[rank as usize])
+    }
+    
+    fn term_count(&self) -> usize {
+        self.unigrams.len() + self.bigrams.len()
+    }
+    
+    /// Unigrams then bigrams ("witcher_3"), as `DocumentFrequencies` counts
+    /// them
+    fn terms(&self) -> impl Iterator<Item = Cow<'_, str>> {
+        let unigrams = self.unigrams.iter().map(|&position| Cow::Borrowed(self.token(position)));
+        let bigrams = self.bigrams.iter().map(|&(a, b)| Cow::Owned(format!("{}_{}", self.unigram(a), self.unigram(b))));
+        unigrams.chain(bigrams)
+    }
+    
+    /// Calls `shared` with the index in the terms of `other` of each term
+    /// both texts have
+    fn shared_terms(&self, other: &Self, mut shared: impl FnMut(usize)) {
+        merge_shared(&self.unigrams, &other.unigrams, |&a, &b| self.token(a).cmp(other.token(b)), &mut shared);
+        let offset = other.unigrams.len();
+        merge_shared(
+            &self.bigrams,
+            &other.bigrams,
+            |&(a1, a2), &(b1, b2)| self.unigram(a1).cmp(other.unigram(b1)).then_with(|| self.unigram(a2).cmp(other.unigram(b2))),
+            |j| shared(offset + j),
+        );
+    }
+    
+    /// IDF of the term at `index` of `terms`
+    fn idf(&self, frequencies: &DocumentFrequencies, index: usize) -> f64 {
+        match self.term_idf.get(index) {
+            Some(&idf) => idf,
+            None => self.terms().nth(index).map_or(0.0, |term| frequencies.idf(&term)),
+        }
+    }
+    
+    /// IDF of every term and their sum, from `frequencies`
+    fn weigh(&mut self, frequencies: &DocumentFrequencies) {
+        self.term_idf = self.terms().map(|term| frequencies.idf(&term)).collect();
+        self.idf_weight = self.term_idf.iter().sum();
+    }
+}
+
+/// A title prepared with `DeltaProfile::prepare_query`, as a `DeltaCatalog`
+/// keeps them, for `delta_prepared`
+pub type PreparedTitle = QueryContext;
+
+/// A query prepared once under a profile, scored against many titles
+/// without tokenizing it again
+#[derive(Debug, Clone)]
+pub struct PreparedQuery<'a> {
+    profile: &'a DeltaProfile,
+    context: QueryContext,
+}
+
+impl<'a> PreparedQuery<'a> {
+    pub fn new(query: &str, profile: &'a DeltaProfile) -> Self {
+        Self { profile, context: profile.prepare_query(query) }
+    }
+
+    /// Δ of the query and `title` (same as `DeltaProfile::delta`)
+    pub fn delta(&self, title: &str) -> f64 {
+        self.profile.delta_prepared(&self.context, title)
+    }
+
+    /// `title` prepared under the query's profile, for `delta_prepared`
+    pub fn prepare_title(&self, title: &str) -> PreparedTitle {
+        self.profile.prepare_query(title)
+    }
+
+    pub fn profile(&self) -> &'a DeltaProfile {
+        self.profile
+    }
+
+    /// Tokens, bigrams, anchors and flags of the query
+    pub fn context(&self) -> &QueryContext {
+        &self.context
+    }
+
+    /// Tokens of the query, as the profile tokenized it
+    pub fn tokens(&self) -> &[String] {
+        &self.context.tokens
+    }
+}
+
+/// Δ of a prepared query and a title prepared under the same profile:
+/// neither side is tokenized again
+pub fn delta_prepared(query: &PreparedQuery, title: &PreparedTitle) -> f64 {
+    delta_title_tokens(query.profile, &query.context, title).delta
+}
+
+// ═══════════════════════════════════════════════════════════════════════════
+// Catalog (titles tokenized once)
+// ═══════════════════════════════════════════════════════════════════════════
+
+/// A catalog title and its Δ to a query
+#[derive(Debug, Clone, PartialEq)]
+pub struct Match<'a> {
+    /// Position in the catalog
+    pub index: usize,
+    pub title: &'a str,
+    pub delta: f64,
+}
+
+/// Entry of a top-k heap: the worst match (highest Δ, then latest index)
+/// on top, to be evicted first
+#[derive(Debug, Clone, Copy)]
+struct Candidate(f64, usize);
+
+impl PartialEq for Candidate {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == std::cmp::Ordering::Equal
+    }
+}
+
+impl Eq for Candidate {}
+
+impl PartialOrd for Candidate {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Candidate {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.0.total_cmp(&other.0).then(self.1.cmp(&other.1))
+    }
+}
+
+/// Push into a heap holding at most `k` entries, the best ones
+fn push_bounded(mut heap: BinaryHeap<Candidate>, entry: Candidate, k: usize) -> BinaryHeap<Candidate> {
+    if heap.len() < k {
+        heap.push(entry);
+    } else if heap.peek().is_some_and(|worst| entry < *worst) {
+        heap.pop();
+        heap.push(entry);
+    }
+    heap
+}
+
+/// The best `k` of `entries` (in parallel with the `parallel` feature)
+#[cfg(feature = "parallel")]
+fn bounded_heap(entries: impl ParallelIterator<Item = Candidate>, k: usize) -> BinaryHeap<Candidate> {
+    entries
+        .fold(BinaryHeap::new, |heap, entry| push_bounded(heap, entry, k))
+        .reduce(BinaryHeap::new, |a, b| b.into_iter().fold(a, |heap, entry| push_bounded(heap, entry, k)))
+}
+
+#[cfg(not(feature = "parallel"))]
+fn bounded_heap(entries: impl Iterator<Item = Candidate>, k: usize) -> BinaryHeap<Candidate> {
+    entries.fold(BinaryHeap::with_capacity(k), |heap, entry| push_bounded(heap, entry, k))
+}
+
+/// Fewest titles a prefilter may keep before `top_k_indexed` distrusts it
+/// and scores every title
+pub const PREFILTER_MIN_CANDIDATES: usize = 10;
+
+/// Titles (positions in the catalog, ascending) by token and by character
+/// trigram of their tokens
+#[derive(Debug, Clone, Default)]
+struct CandidateIndex {
+    tokens: HashMap<String, Vec<u32>>,
+    trigrams: HashMap<String, Vec<u32>>,
+}
+
+impl CandidateIndex {
+    fn new(titles: &[QueryContext]) -> Self {
+        let mut index = Self::default();
+        for (idx, title) in titles.iter().enumerate() {
+            index.insert(idx, title);
+        }
+        index
+    }
+    
+    /// Index `title` at `idx`, past every title indexed so far
+    fn insert(&mut self, idx: usize, title: &QueryContext) {
+        let idx = u32::try_from(idx).expect("fewer than 2³² titles");
+        let add = |postings: &mut HashMap<String, Vec<u32>>, key: String| {
+            let titles = postings.entry(key).or_default();
+            if titles.last() != Some(&idx) {
+                titles.push(idx);
+            }
+        };
+        for &position in &title.unigrams {
+            let token = title.token(position);
+            add(&mut self.tokens, token.to_string());
+            for trigram in trigrams(token) {
+                add(&mut self.trigrams, trigram);
+            }
+        }
+    }
+    
+    /// Forget `title` at `idx`, the titles after it moved down by one
+    fn remove(&mut self, idx: usize, title: &QueryContext) {
+        let idx = idx as u32;
+        let drop = |postings: &mut HashMap<String, Vec<u32>>, key: &str| {
+            if let Some(titles) = postings.get_mut(key) {
+                if let Ok(i) = titles.binary_search(&idx) {
+                    titles.remove(i);
+                }
+                if titles.is_empty() {
+                    postings.remove(key);
+                }
+            }
+        };
+        for &position in &title.unigrams {
+            let token = title.token(position);
+            drop(&mut self.tokens, token);
+            for trigram in trigrams(token) {
+                drop(&mut self.trigrams, &trigram);
+            }
+        }
+        for titles in self.tokens.values_mut().chain(self.trigrams.values_mut()) {
+            let after = titles.partition_point(|&other| other < idx);
+            for other in &mut titles[after..] {
+                *other -= 1;
+            }
+        }
+    }
+}
+
+/// Character trigrams of `token` (none under 3 characters)
+fn trigrams(token: &str) -> impl Iterator<Item = String> + '_ {
+    let chars: Vec<char> = token.chars().collect();
+    (0..chars.len().saturating_sub(2)).map(move |i| chars[i..i + 3].iter().collect())
+}
+
+/// Titles prepared once with a profile, scored against many queries
+#[derive(Clone)]
+pub struct DeltaCatalog {
+    profile: DeltaProfile,
+    titles: Vec<String>,
+    prepared: Vec<QueryContext>,
+    /// Built by `with_index`, for `top_k_indexed`
+    index: Option<CandidateIndex>,
+}
+
+/// `DeltaCatalog` under the name the Python and JavaScript bindings give
+/// it: `Corpus::new(&titles)`, then `top_k` and `best`
+pub type Corpus = DeltaCatalog;
+
+impl DeltaCatalog {
+    /// Catalog of `titles` under the TITLE profile
+    pub fn new<S: AsRef<str> + Sync>(titles: &[S]) -> Self {
+        Self::prebuild(DeltaProfile::title(), titles)
+    }
+    
+    /// Prepare `titles` once with `profile` (queries use the same profile)
+    pub fn prebuild<S: AsRef<str> + Sync>(profile: DeltaProfile, titles: &[S]) -> Self {
+        let prepared = maybe_par_iter!(titles).map(|title| profile.prepare_query(title.as_ref())).collect();
+        Self {
+            titles: titles.iter().map(|title| title.as_ref().to_string()).collect(),
+            prepared,
+            profile,
+            index: None,
+        }
+    }
+    
+    /// `prebuild` with Jaccard weighted by IDF over these titles, in place
+    /// of any `document_frequencies` of `profile`
+    pub fn prebuild_idf<S: AsRef<str> + Sync>(profile: DeltaProfile, titles: &[S]) -> Self {
+        let mut catalog = Self::prebuild(DeltaProfile { document_frequencies: None, ..profile }, titles);
+        let frequencies = DocumentFrequencies::of_prepared(&catalog.prepared);
+        for title in &mut catalog.prepared {
+            title.weigh(&frequencies);
+        }
+        catalog.profile.document_frequencies = Some(Arc::new(frequencies));
+        catalog
+    }
+    
+    pub fn profile(&self) -> &DeltaProfile {
+        &self.profile
+    }
+    
+    pub fn titles(&self) -> &[String] {
+        &self.titles
+    }
+    
+    /// Prepare and append `title`; its index
+    pub fn push(&mut self, title: &str) -> usize {
+        self.extend(&[title]);
+        self.len() - 1
+    }
+    
+    /// Prepare and append `titles` (in parallel with the `parallel`
+    /// feature), indexed too after `with_index`. With `prebuild_idf`, they
+    /// are weighed by the frequencies of the titles first counted.
+    pub fn extend<S: AsRef<str> + Sync>(&mut self, titles: &[S]) {
+        let start = self.len();
+        let prepared: Vec<QueryContext> = maybe_par_iter!(titles).map(|title| self.profile.prepare_query(title.as_ref())).collect();
+        if let Some(index) = &mut self.index {
+            for (offset, title) in prepared.iter().enumerate() {
+                index.insert(start + offset, title);
+            }
+        }
+        self.titles.extend(titles.iter().map(|title| title.as_ref().to_string()));
+        self.prepared.extend(prepared);
+    }
+    
+    /// Remove the title at `index`, the ones after it moving down by one;
+    /// `None` past the end
+    pub fn remove(&mut self, index: usize) -> Option<String> {
+        if index >= self.len() {
+            return None;
+        }
+        let prepared = self.prepared.remove(index);
+        if let Some(candidates) = &mut self.index {
+            candidates.remove(index, &prepared);
+        }
+        Some(self.titles.remove(index))
+    }
+    
+    pub fn len(&self) -> usize {
+        self.titles.len()
+    }
+    
+    pub fn is_empty(&self) -> bool {
+        self.titles.is_empty()
+    }
+    
+    /// Δ between `query` and every title, in catalog order
+    pub fn deltas(&self, query: &str) -> Vec<f64> {
+        let query = self.profile.prepare_query(query);
+        maybe_par_iter!(self.prepared)
+            .map(|title| delta_title_tokens(&self.profile, &query, title).delta)
+            .collect()
+    }
+    
+    /// The `k` closest titles, best first (lowest index first on ties),
+    /// kept in a bounded heap rather than sorting every Δ (in parallel
+    /// with the `parallel` feature)
+    pub fn top_k(&self, query: &str, k: usize) -> Vec<Match<'_>> {
+        if k == 0 {
+            return Vec::new();
+        }
+        self.top_k_of(&self.profile.prepare_query(query), k, None)
+    }
+    
+    /// `top_k` over only the titles sharing a token with `query` (or a
+    /// character trigram, for a one-token query), through the index of
+    /// `with_index`. Every title is scored instead without an index, for a
+    /// query without tokens, or when fewer than `k` or
+    /// `PREFILTER_MIN_CANDIDATES` titles turn up (a typo in every word).
+    pub fn top_k_indexed(&self, query: &str, k: usize) -> Vec<Match<'_>> {
+        if k == 0 {
+            return Vec::new();
+        }
+        let query = self.profile.prepare_query(query);
+        let candidates = self.prefilter(&query, k);
+        self.top_k_of(&query, k, candidates.as_deref())
+    }
+    
+    /// Titles `top_k_indexed` scores for `query`
+    pub fn candidate_count(&self, query: &str, k: usize) -> usize {
+        self.prefilter(&self.profile.prepare_query(query), k).map_or(self.len(), |candidates| candidates.len())
+    }
+    
+    /// Index of the titles by token and trigram, for `top_k_indexed`
+    pub fn with_index(mut self) -> Self {
+        self.index = Some(CandidateIndex::new(&self.prepared));
+        self
+    }
+    
+    /// Titles sharing a token or trigram with `query`, ascending; `None`
+    /// for a full scan
+    fn prefilter(&self, query: &QueryContext, k: usize) -> Option<Vec<u32>> {
+        let index = self.index.as_ref()?;
+        let mut candidates: Vec<u32> = query.tokens.iter().flat_map(|token| index.tokens.get(token)).flatten().copied().collect();
+        if let [token] = query.tokens.as_slice() {
+            candidates.extend(trigrams(token).flat_map(|trigram| index.trigrams.get(&trigram)).flatten());
+        }
+        candidates.sort_unstable();
+        candidates.dedup();
+        (candidates.len() >= k.max(PREFILTER_MIN_CANDIDATES)).then_some(candidates)
+    }
+    
+    /// The `k` closest of the `candidates` titles (all of them with `None`)
+    fn top_k_of(&self, query: &QueryContext, k: usize, candidates: Option<&[u32]>) -> Vec<Match<'_>> {
+        let ranked = |idx: usize| Candidate(delta_title_tokens(&self.profile, query, &self.prepared[idx]).delta, idx);
+        let heap = match candidates {
+            Some(candidates) => bounded_heap(maybe_par_iter!(candidates).map(|&idx| ranked(idx as usize)), k),
+            #[cfg(feature = "parallel")]
+            None => bounded_heap((0..self.len()).into_par_iter().map(ranked), k),
+            #[cfg(not(feature = "parallel"))]
+            None => bounded_heap((0..self.len()).map(ranked), k),
+        };
+        
+        heap.into_sorted_vec()
+            .into_iter()
+            .map(|Candidate(delta, index)| Match { index, title: &self.titles[index], delta })
+            .collect()
+    }
+    
+    /// Closest title (first one on ties)
+    pub fn best(&self, query: &str) -> Option<Match<'_>> {
+        self.top_k(query, 1).into_iter().next()
+    }
+    
+    /// Index and Δ of the closest title (first one on ties)
+    pub fn best_match(&self, query: &str) -> Option<(usize, f64)> {
+        self.best(query).map(|best| (best.index, best.delta))
+    }
+    
+    /// Best match of each query (in parallel over the queries with the
+    /// `parallel` feature)
+    pub fn best_matches<S: AsRef<str> + Sync>(&self, queries: &[S]) -> Vec<Option<(usize, f64)>> {
+        maybe_par_iter!(queries).map(|query| self.best_match(query.as_ref())).collect()
+    }
+}
+
+/// Titles per bucket of a `SharedCatalog` unless `with_bucket_size` says
+/// otherwise
+pub const CATALOG_BUCKET_SIZE: usize = 4096;
+
+/// A `DeltaCatalog` titles are added to and removed from while other
+/// threads rank against it. Readers take a `snapshot` and rank against it
+/// without holding any lock; a writer copies the snapshot, changes it and
+/// publishes it. Titles are kept in buckets shared between snapshots (each
+/// a `DeltaCatalog`, with its own index), so a write copies only the
+/// bucket it changes.
+pub struct SharedCatalog {
+    current: RwLock<Arc<CatalogSnapshot>>,
+    /// Held by a writer from copying the snapshot to publishing it
+    writer: Mutex<()>,
+}
+
+impl SharedCatalog {
+    /// Catalog of `titles` under the TITLE profile
+    pub fn new<S: AsRef<str> + Sync>(titles: &[S]) -> Self {
+        Self::from(DeltaCatalog::new(titles))
+    }
+    
+    /// `catalog` split into buckets of `bucket_size` titles, each indexed
+    /// if `catalog` was
+    pub fn with_bucket_size(catalog: DeltaCatalog, bucket_size: usize) -> Self {
+        let bucket_size = bucket_size.max(1);
+        let DeltaCatalog { profile, titles, prepared, index } = catalog;
+        let indexed = index.is_some();
+        let mut snapshot = CatalogSnapshot {
+            profile: Arc::new(profile),
+            buckets: Vec::new(),
+            starts: Vec::new(),
+            len: 0,
+            bucket_size,
+            indexed,
+        };
+        let (mut titles, mut prepared) = (titles.into_iter(), prepared.into_iter());
+        loop {
+            let mut bucket = snapshot.empty_bucket();
+            bucket.titles.extend(titles.by_ref().take(bucket_size));
+            bucket.prepared.extend(prepared.by_ref().take(bucket_size));
+            if bucket.is_empty() {
+                break;
+            }
+            if indexed {
+                bucket = bucket.with_index();
+            }
+            snapshot.buckets.push(Arc::new(bucket));
+        }
+        snapshot.restart();
+        Self { current: RwLock::new(Arc::new(snapshot)), writer: Mutex::new(()) }
+    }
+    
+    /// The titles as they are now, to rank against; later writes leave it
+    /// as it is
+    pub fn snapshot(&self) -> Arc<CatalogSnapshot> {
+        self.current.read().unwrap_or_else(PoisonError::into_inner).clone()
+    }
+    
+    pub fn len(&self) -> usize {
+        self.snapshot().len()
+    }
+    
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+    
+    /// Prepare and append `title`; its index
+    pub fn push(&self, title: &str) -> usize {
+        self.write(|snapshot| {
+            snapshot.extend(&[title]);
+            snapshot.len - 1
+        })
+    }
+    
+    /// Prepare and append `titles`, published at once
+    pub fn extend<S: AsRef<str> + Sync>(&self, titles: &[S]) {
+        self.write(|snapshot| snapshot.extend(titles))
+    }
+    
+    /// Remove the title at `index`, the ones after it moving down by one;
+    /// `None` past the end
+    pub fn remove(&self, index: usize) -> Option<String> {
+        self.write(|snapshot| snapshot.remove(index))
+    }
+    
+    /// Apply `change` to a copy of the snapshot, then publish it: readers
+    /// wait only for the swap
+    fn write<T>(&self, change: impl FnOnce(&mut CatalogSnapshot) -> T) -> T {
+        let _writer = self.writer.lock().unwrap_or_else(PoisonError::into_inner);
+        let mut next = CatalogSnapshot::clone(&self.snapshot());
+        let result = change(&mut next);
+        *self.current.write().unwrap_or_else(PoisonError::into_inner) = Arc::new(next);
+        result
+    }
+}
+
+impl From<DeltaCatalog> for SharedCatalog {
+    fn from(catalog: DeltaCatalog) -> Self {
+        Self::with_bucket_size(catalog, CATALOG_BUCKET_SIZE)
+    }
+}
+
+/// The titles of a `SharedCatalog` at one point in time. Indices are
+/// those of this snapshot: a later `remove` moves the titles after it.
+#[derive(Clone)]
+pub struct CatalogSnapshot {
+    profile: Arc<DeltaProfile>,
+    buckets: Vec<Arc<DeltaCatalog>>,
+    /// Index of the first title of each bucket
+    starts: Vec<usize>,
+    len: usize,
+    bucket_size: usize,
+    /// Whether the buckets are indexed, for `top_k_indexed`
+    indexed: bool,
+}
+
+impl CatalogSnapshot {
+    pub fn profile(&self) -> &DeltaProfile {
+        &self.profile
+    }
+    
+    pub fn len(&self) -> usize {
+        self.len
+    }
+    
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+    
+    /// Title at `index`
+    pub fn title(&self, index: usize) -> Option<&str> {
+        let (bucket, offset) = self.locate(index)?;
+        Some(&self.buckets[bucket].titles[offset])
+    }
+    
+    /// Every title, in catalog order
+    pub fn titles(&self) -> impl Iterator<Item = &str> + '_ {
+        self.buckets.iter().flat_map(|bucket| bucket.titles.iter().map(String::as_str))
+    }
+    
+    /// Δ between `query` and every title, in catalog order
+    pub fn deltas(&self, query: &str) -> Vec<f64> {
+        let query = self.profile.prepare_query(query);
+        self.buckets
+            .iter()
+            .flat_map(|bucket| {
+                let deltas: Vec<f64> = maybe_par_iter!(bucket.prepared)
+                    .map(|title| delta_title_tokens(&self.profile, &query, title).delta)
+                    .collect();
+                deltas
+            })
+            .collect()
+    }
+    
+    /// `DeltaCatalog::top_k` over every bucket
+    pub fn top_k(&self, query: &str, k: usize) -> Vec<Match<'_>> {
+        self.ranked(query, k, false)
+    }
+    
+    /// `DeltaCatalog::top_k_indexed` over every bucket (each falling back
+    /// to scoring all its titles on its own); `top_k` unless indexed
+    pub fn top_k_indexed(&self, query: &str, k: usize) -> Vec<Match<'_>> {
+        self.ranked(query, k, true)
+    }
+    
+    /// Closest title (first one on ties)
+    pub fn best(&self, query: &str) -> Option<Match<'_>> {
+        self.top_k(query, 1).into_iter().next()
+    }
+    
+    /// The best `k` of each bucket's best `k`
+    fn ranked(&self, query: &str, k: usize, indexed: bool) -> Vec<Match<'_>> {
+        if k == 0 {
+            return Vec::new();
+        }
+        let query = self.profile.prepare_query(query);
+        let mut matches: Vec<Match> = self
+            .buckets
+            .iter()
+            .zip(&self.starts)
+            .flat_map(|(bucket, &start)| {
+                let candidates = if indexed { bucket.prefilter(&query, k) } else { None };
+                let matches = bucket.top_k_of(&query, k, candidates.as_deref());
+                matches.into_iter().map(move |m| Match { index: start + m.index, ..m })
+            })
+            .collect();
+        matches.sort_by(|a, b| a.delta.total_cmp(&b.delta).then(a.index.cmp(&b.index)));
+        matches.truncate(k);
+        matches
+    }
+    
+    /// Bucket of the title at `index` and its position there
+    fn locate(&self, index: usize) -> Option<(usize, usize)> {
+        if index >= self.len {
+            return None;
+        }
+        let bucket = self.starts.partition_point(|&start| start <= index) - 1;
+        Some((bucket, index - self.starts[bucket]))
+    }
+    
+    fn empty_bucket(&self) -> DeltaCatalog {
+        DeltaCatalog {
+            profile: DeltaProfile::clone(&self.profile),
+            titles: Vec::new(),
+            prepared: Vec::new(),
+            index: self.indexed.then(CandidateIndex::default),
+        }
+    }
+    
+    /// `titles` appended to the last bucket, then to new ones
+    fn extend<S: AsRef<str> + Sync>(&mut self, titles: &[S]) {
+        let mut rest = titles;
+        while !rest.is_empty() {
+            if self.buckets.last().is_none_or(|last| last.len() >= self.bucket_size) {
+                self.buckets.push(Arc::new(self.empty_bucket()));
+            }
+            let last = self.buckets.last_mut().expect("a bucket with room");
+            let (chunk, tail) = rest.split_at((self.bucket_size - last.len()).min(rest.len()));
+            Arc::make_mut(last).extend(chunk);
+            rest = tail;
+        }
+        self.restart();
+    }
+    
+    fn remove(&mut self, index: usize) -> Option<String> {
+        let (bucket, offset) = self.locate(index)?;
+        let title = Arc::make_mut(&mut self.buckets[bucket]).remove(offset);
+        if self.buckets[bucket].is_empty() {
+            self.buckets.remove(bucket);
+        }
+        self.restart();
+        title
+    }
+    
+    /// `starts` and `len` after the buckets changed
+    fn restart(&mut self) {
+        self.starts.clear();
+        self.len = 0;
+        for bucket in &self.buckets {
+            self.starts.push(self.len);
+            self.len += bucket.len();
+        }
+    }
+}
+
+// ═══════════════════════════════════════════════════════════════════════════
+// TITLE Mode Delta
+// ═══════════════════════════════════════════════════════════════════════════
+
+pub fn semantic_delta_title(query: &str, title: &str) -> f64 {
+    PreparedQuery::new(query, Mode::Title.profile()).delta(title)
+}
+
+/// `semantic_delta_title` term by term, to see which one ranked a title
+/// where it is
+pub fn semantic_delta_explain(query: &str, title: &str) -> DeltaExplanation {
+    Mode::Title.profile().explain(query, title)
+}
+
+/// Δ of `query` and `text` in SENTENCE mode (`DeltaProfile::sentence()`)
+pub fn semantic_delta_sentence(query: &str, text: &str) -> f64 {
+    Mode::Sentence.profile().delta(query, text)
+}
+
+/// Δ of `query` and `title` under a tuned configuration (check it with
+/// `DeltaConfig::validate` first)
+pub fn semantic_delta_with_config(query: &str, title: &str, config: &DeltaConfig) -> f64 {
+    config.delta(query, title)
+}
+
+/// Δ of a query and a title term by term, as `semantic_delta_explain`
+/// gives it: the weighted terms, μ_anchor, the DLC and negation penalties,
+/// less the containment bonus and what the [0, 1] bounds cut off, add up
+/// to `delta`. With no tokens
+/// on either side ("E.T." against "///") only the Levenshtein term is set:
+/// that of the raw texts, trimmed and case-folded.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct DeltaExplanation {
+    /// Tokens as the profile compared them (empty on the hot path, which
+    /// only keeps the numbers)
+    pub query_tokens: Vec<String>,
+    pub title_tokens: Vec<String>,
+    /// Jaccard index of unigrams and bigrams (IDF-weighted with
+    /// `document_frequencies`), before μ_space and the cap
+    pub jaccard: f64,
+    /// α boost of a one-word query against a longer title containing it
+    pub mu_space: f64,
+    /// Jaccard index with μ_space, capped at `jaccard_cap`
+    pub jaccard_capped: f64,
+    /// Symmetric Levenshtein similarity
+    pub levenshtein: f64,
+    pub anchor_ratio: f64,
+    /// wJ × (1 − capped Jaccard)
+    pub jaccard_term: f64,
+    /// wL × (1 − Levenshtein)
+    pub levenshtein_term: f64,
+    /// wR × (1 − anchor ratio)
+    pub anchor_term: f64,
+    /// β × (1 − anchor ratio)
+    pub mu_anchor: f64,
+    /// Δ taken off a title containing the query's tokens in order
+    pub containment_bonus: f64,
+    /// Δ added by the DLC debias (0 unless only the title is DLC-like)
+    pub dlc_penalty: f64,
+    /// Δ added when only one side is negated
+    pub negation_penalty: f64,
+    /// Δ cut off by the [0, 1] bounds
+    pub clamped: f64,
+    pub delta: f64,
+}
+
+/// Δ when a side has no tokens ("!!!", "///", "🍉"): 1 − the character
+/// similarity of the raw texts, so identical ones are 0 apart and a text
+/// with nothing in it is 1 away from any other
+fn raw_delta(profile: &DeltaProfile, query: &QueryContext, title: &QueryContext) -> DeltaExplanation {
+    let (a, b): (Vec<char>, Vec<char>) = (query.raw.chars().collect(), title.raw.chars().collect());
+    let max_len = a.len().max(b.len());
+    let similarity = if a == b {
+        1.0
+    } else {
+        let dist = levenshtein_distance_bounded(&a, &b, max_len, profile.levenshtein, &mut EditRows::default()).unwrap_or(max_len);
+        1.0 - dist as f64 / max_len as f64
+    };
+    DeltaExplanation {
+        levenshtein: similarity,
+        levenshtein_term: 1.0 - similarity,
+        delta: 1.0 - similarity,
+        ..Default::default()
+    }
+}
+
+/// Δ of both modes, term by term: the profile's weights and corrections,
+/// stopwords already filtered by its tokenizer. No tokens are copied.
+fn delta_title_tokens(profile: &DeltaProfile, query: &QueryContext, title: &QueryContext) -> DeltaExplanation {
+    let (q_tokens, t_tokens) = (&query.tokens, &title.tokens);
+    if q_tokens.is_empty() || t_tokens.is_empty() {
+        return raw_delta(profile, query, title);
+    }
+    
+    // Jaccard
+    let jaccard = jaccard_index(query, title, profile.document_frequencies.as_deref());
+    
+    // Levenshtein symmetric
+    let l = l_symmetric(&query.chars, &title.chars, profile.levenshtein);
+    
+    // Anchor ratio
+    let r = compute_anchor_ratio(&query.anchors, &title.anchors);
+    
+    // Corrections TITLE mode
+    let alpha = profile.space_correction;
+    let beta = profile.anchor_correction;
+    
+    // Only for a query word found in the title: any longer title would
+    // otherwise get closer, however unrelated its extra words
+    let mu_space = if q_tokens.len() == 1 && t_tokens.len() > 1 && jaccard > 0.0 {
+        alpha * (1.0 - jaccard)
+    } else {
+        0.0
+    };
+    
+    let mu_anchor = beta * (1.0 - r);
+    
+    let mut j = (jaccard + mu_space).min(1.0);
+    
+    // Cap Jaccard (TITLE mode)
+    j = j.min(profile.jaccard_cap);
+    
+    // Weights TITLE mode
+    let jaccard_term = profile.jaccard_weight * (1.0 - j);
+    let levenshtein_term = profile.levenshtein_weight * (1.0 - l);
+    let anchor_term = profile.anchor_weight * (1.0 - r);
+    
+    let unbounded = jaccard_term + levenshtein_term + anchor_term + mu_anchor;
+    let mut delta = unbounded.min(1.0);
+    let mut clamped = unbounded - delta;
+    
+    // Containment: a truncated title closes part of its gap to an exact
+    // match (wJ × (1 − cap)), never all of it
+    let coverage = if profile.containment_bonus > 0.0 { containment_coverage(q_tokens, t_tokens) } else { 0.0 };
+    let exact = profile.jaccard_weight * (1.0 - profile.jaccard_cap);
+    let containment_bonus = (delta - exact).max(0.0) * profile.containment_bonus * coverage;
+    delta -= containment_bonus;
+    
+    // DLC debias
+    let debiased = apply_dlc_debias(delta, query.dlc_like, title.dlc_like, profile.dlc_penalty);
+    let dlc_penalty = debiased - delta;
+    delta = debiased;
+    
+    // Negation (SENTENCE mode): "is fun" vs "is not fun"
+    let negation_penalty = if query.negated != title.negated { profile.negation_penalty } else { 0.0 };
+    delta += negation_penalty;
+    
+    let bounded = delta.clamp(0.0, 1.0);
+    clamped += delta - bounded;
+    
+    DeltaExplanation {
+        query_tokens: Vec::new(),
+        title_tokens: Vec::new(),
+        jaccard,
+        mu_space,
+        jaccard_capped: j,
+        levenshtein: l,
+        anchor_ratio: r,
+        jaccard_term,
+        levenshtein_term,
+        anchor_term,
+        mu_anchor,
+        containment_bonus,
+        dlc_penalty,
+        negation_penalty,
+        clamped,
+        delta: bounded,
+    }
+}
+
+#[inline]
+fn apply_dlc_debias(delta: f64, query_dlc_like: bool, title_dlc_like: bool, penalty: f64) -> f64 {
+    if title_dlc_like && !query_dlc_like {
+        (delta * (1.0 + penalty)).min(1.0)
+    } else {
+        delta
+    }
+}
+
+// ═══════════════════════════════════════════════════════════════════════════
+// Auto-detect mode (TITLE if short, SENTENCE if long)
+// ═══════════════════════════════════════════════════════════════════════════
+
+/// Most words (stopwords aside) a text can have and still read as a title
+const TITLE_MAX_WORDS: usize = 6;
+
+/// Words of running text that long game titles seldom have
+const SENTENCE_MARKERS: &[&str] = &[
+    "is", "was", "are", "were", "i", "you", "he", "she", "it", "we", "they",
+    "this", "that", "my", "your",
+];
+
+/// Shortest word a `.` can end as a full stop rather than an abbreviation
+/// ("Dr. Mario", "S.T.A.L.K.E.R.")
+const FULL_STOP_MIN_LETTERS: usize = 3;
+
+/// Profile `semantic_delta_with_mode` scores with
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    Title,
+    Sentence,
+}
+
+impl Mode {
+    /// SENTENCE when both texts read as natural language, TITLE otherwise
+    /// (see `looks_like_sentence`): a game title stays TITLE whatever it is
+    /// compared to. Only the first `MAX_CHARS` characters of each are read,
+    /// as both presets score them.
+    pub fn detect(query: &str, text: &str) -> Mode {
+        let (query, text) = (truncate_chars(query, MAX_CHARS), truncate_chars(text, MAX_CHARS));
+        if looks_like_sentence(query) && looks_like_sentence(text) {
+            Mode::Sentence
+        } else {
+            Mode::Title
+        }
+    }
+    
+    /// Preset of the mode, built once
+    pub fn profile(self) -> &'static DeltaProfile {
+        static TITLE: OnceLock<DeltaProfile> = OnceLock::new();
+        static SENTENCE: OnceLock<DeltaProfile> = OnceLock::new();
+        match self {
+            Mode::Title => TITLE.get_or_init(DeltaProfile::title),
+            Mode::Sentence => SENTENCE.get_or_init(DeltaProfile::sentence),
+        }
+    }
+}
+
+/// A word from `SENTENCE_MARKERS`, and either more
+/// than `TITLE_MAX_WORDS` words of 2+ letters once stopwords are dropped
+/// ("the legend of zelda breath of the wild" has 4, "Sid Meier's
+/// Civilization: Beyond Earth - Rising Tide" has 7 but no marker) or
+/// sentence punctuation: `!`, `?` or a full stop (`.` after a word of
+/// `FULL_STOP_MIN_LETTERS` letters or more) followed by a space or the end
+fn looks_like_sentence(text: &str) -> bool {
+    // "i'm" keeps its marker
+    let tokens = normalize_words(text, false);
+    if !tokens.iter().any(|t| SENTENCE_MARKERS.contains(&t.as_str())) {
+        return false;
+    }
+    let words = tokens
+        .iter()
+        .filter(|t| t.chars().count() >= 2 && t.chars().all(char::is_alphabetic) && !STOPWORDS.contains(&t.as_str()))
+        .count();
+    if words > TITLE_MAX_WORDS {
+        return true;
+    }
+    
+    // One pass, counting the letters just before each character
+    let mut chars = text.chars().peekable();
+    let mut letters = 0;
+    while let Some(c) = chars.next() {
+        let ends_word = chars.peek().is_none_or(|next| next.is_whitespace());
+        let stop = match c {
+            '!' | '?' | '！' | '？' | '。' => true,
+            '.' => letters >= FULL_STOP_MIN_LETTERS,
+            _ => false,
+        };
+        if stop && ends_word {
+            return true;
+        }
+        letters = if c.is_alphabetic() { letters + 1 } else { 0 };
+    }
+    false
+}
+
+/// Δ of `query` and `text` with the profile of `mode`
+pub fn semantic_delta_with_mode(query: &str, text: &str, mode: Mode) -> f64 {
+    mode.profile().delta(query, text)
+}
+
+/// Δ with the mode detected from both texts (`Mode::detect`)
+pub fn semantic_delta_v3(query: &str, title: &str) -> f64 {
+    semantic_delta_with_mode(query, title, Mode::detect(query, title))
+}
+
+// ═══════════════════════════════════════════════════════════════════════════
+// Primitives
+// ═══════════════════════════════════════════════════════════════════════════
+
+/// The terms Δ is built from, public for the criterion micro-benchmarks
+/// (`benches/primitives_benchmark.rs`); not part of the API
+#[doc(hidden)]
+pub mod primitives {
+    use super::{EditRows, LevKind, QueryContext};
+    
+    pub fn normalize_v2(text: &str) -> Vec<String> {
+        super::normalize_v2(text)
+    }
+    
+    /// Similarity (1 − distance / longer length) of two tokens, the whole
+    /// edit table worked out
+    pub fn levenshtein_sim(a: &[char], b: &[char], kind: LevKind) -> f64 {
+        let max_len = a.len().max(b.len());
+        if max_len == 0 {
+            return 1.0;
+        }
+        let dist = super::levenshtein_distance_bounded(a, b, max_len, kind, &mut EditRows::default()).unwrap_or(max_len);
+        1.0 - dist as f64 / max_len as f64
+    }
+    
+    /// Symmetric Levenshtein similarity of the tokens of two prepared texts
+    pub fn l_symmetric(a: &QueryContext, b: &QueryContext, kind: LevKind) -> f64 {
+        super::l_symmetric(&a.chars, &b.chars, kind)
+    }
+    
+    /// Jaccard index of the unigrams and bigrams of two prepared texts
+    pub fn jaccard_index(a: &QueryContext, b: &QueryContext) -> f64 {
+        super::jaccard_index(a, b, None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_exact_match() {
+        let delta = semantic_delta_v3("zelda", "zelda");
+        assert!(delta < 0.1);
+    }
+    
+    #[test]
+    fn test_roman_numeral() {
+        let delta = semantic_delta_v3("doom 2", "DOOM II");
+        assert!(delta < 0.3);
+    }
+    
+    #[test]
+    fn test_parse_roman() {
+        assert_eq!(parse_roman("vi"), Some(6));
+        assert_eq!(parse_roman("xxvi"), Some(26));
+        assert_eq!(parse_roman("xxx"), Some(30));
+        assert_eq!(parse_roman("mcmxcix"), Some(1999));
+        assert_eq!(parse_roman("mmmcmxcix"), Some(3999));
+        for malformed in ["", "iiii", "vx", "il", "mmmm", "zelda"] {
+            assert_eq!(parse_roman(malformed), None, "{}", malformed);
+        }
+    }
+    
+    #[test]
+    fn test_roman_titles() {
+        assert_eq!(normalize_v2("Sid Meier's Civilization VI"), vec!["sid", "meier", "civilization", "6"]);
+        assert_eq!(normalize_v2("Ultima VII: The Black Gate"), vec!["ultima", "7", "the", "black", "gate"]);
+        assert_eq!(normalize_v2("Rocky IV"), vec!["rocky", "4"]);
+        assert_eq!(normalize_v2("Final Fantasy XXVI"), vec!["final", "fantasy", "26"]);
+        assert!(semantic_delta_v3("civilization 6", "Civilization VI") < 0.3);
+        
+        // Words spelling a numeral
+        assert_eq!(normalize_v2("Just Dance Mix"), vec!["just", "dance", "mix"]);
+        assert_eq!(normalize_v2("Les dix commandements"), vec!["les", "dix", "commandements"]);
+        assert_eq!(normalize_v2("vi"), vec!["vi"]);
+    }
+    
+    #[test]
+    fn test_number_words_and_ordinals() {
+        assert_eq!(normalize_v2("Dark Souls Two"), vec!["dark", "souls", "2"]);
+        assert_eq!(normalize_v2("Left Four Dead Two"), vec!["left", "4", "dead", "2"]);
+        assert_eq!(normalize_v2("Ys: The 2nd Chapter"), vec!["ys", "the", "2", "chapter"]);
+        assert_eq!(normalize_v2("21st 12th 3rd 1st"), vec!["21", "12", "3", "1"]);
+        assert_eq!(normalize_v2("2th 4x4 22nds"), vec!["2th", "4x4", "22nds"]);
+        
+        // Number words that are part of the title
+        assert_eq!(normalize_v2("One Piece"), vec!["one", "piece"]);
+        assert_eq!(normalize_v2("The Two Towers"), vec!["the", "two", "towers"]);
+        assert_eq!(normalize_v2("A Tale of Two Cities"), vec!["a", "tale", "of", "two", "cities"]);
+        assert_eq!(normalize_v2("Someone Twentyish"), vec!["someone", "twentyish"]);
+        assert_eq!(normalize_v2("It Takes Two"), vec!["it", "takes", "two"]);
+        assert_eq!(normalize_v2("Two Point Hospital"), vec!["two", "point", "hospital"]);
+        assert_eq!(
+            normalize_v2("Zero Escape: Nine Hours, Nine Persons, Nine Doors"),
+            vec!["zero", "escape", "nine", "hours", "nine", "persons", "nine", "doors"]
+        );
+        assert_eq!(normalize_v2("Three Kingdoms Five Dynasties"), vec!["three", "kingdoms", "five", "dynasties"]);
+        // Ending the name: last, before a subtitle or an edition
+        assert_eq!(normalize_v2("Portal Two: Reloaded"), vec!["portal", "2", "reloaded"]);
+        assert_eq!(normalize_v2("Dark Souls Two Remastered"), vec!["dark", "souls", "2", "remastered"]);
+        assert_eq!(normalize_v2("Hitman Three II"), vec!["hitman", "3", "2"]);
+        
+        let titles = ["Dark Souls", "Dark Souls II", "Dark Souls III", "One Piece", "Two Point Hospital", "Halo 2"];
+        let catalog = DeltaCatalog::new(&titles);
+        for (query, expected) in [
+            ("dark souls two", "Dark Souls II"),
+            ("dark souls three", "Dark Souls III"),
+            ("dark souls 3rd", "Dark Souls III"),
+            ("one piece", "One Piece"),
+            ("two point hospital", "Two Point Hospital"),
+            ("halo two", "Halo 2"),
+        ] {
+            assert_eq!(catalog.best(query).unwrap().title, expected, "{}", query);
+        }
+        assert!(semantic_delta_title("dark souls two", "DARK SOULS II") < 0.1);
+    }
+    
+    #[test]
+    fn test_adversarial_input() {
+        // Raw chat, lossy-decoded: zalgo, surrogates and stray bytes (as
+        // U+FFFD), expanding ligatures, NUL, bidi marks, huge numbers
+        let inputs: Vec<String> = [
+            &b"z\xcc\xb7\xcc\x9ba\xcc\xb6l\xcc\xb8g\xcc\xb4o\xcd\x9f"[..],
+            b"\xed\xa0\x80\xed\xb0\x80 witcher",
+            b"\xff\xfe\xfd",
+            "ﬃ İstanbul ǅ ﷽ ㍿".as_bytes(),
+            b"\0\0 portal \0",
+            "\u{202e}2 latrop\u{200b}".as_bytes(),
+            b"99999999999999999999999999th 18446744073709551616 mmmmmmmmmmmmmmmmmmmmmmmmmmmm",
+            b"' '' n't 's ' ",
+            b"",
+        ]
+        .iter()
+        .map(|bytes| String::from_utf8_lossy(bytes).into_owned())
+        .collect();
+        for query in &inputs {
+            for title in inputs.iter().map(String::as_str).chain(["Portal 2", "The Witcher 3: Wild Hunt"]) {
+                for delta in [semantic_delta_v3(query, title), semantic_delta_title(query, title)] {
+                    assert!((0.0..=1.0).contains(&delta), "{:?} / {:?}: {}", query, title, delta);
+                }
+            }
+            assert!(semantic_delta_title(query, query) <= semantic_delta_title(query, "Portal 2"), "{:?}", query);
+        }
+        let catalog = DeltaCatalog::new(&inputs).with_index();
+        for query in &inputs {
+            for m in catalog.top_k_indexed(query, 3) {
+                assert_eq!(m.title, inputs[m.index]);
+            }
+        }
+
+        // 1 MB: only the first `max_chars` characters are scored, in one
+        // token or many (40k characters in one token took 13 s)
+        let title = DeltaProfile::title();
+        for huge in ["a".repeat(1 << 20), "stardew valley ".repeat(70_000), "/".repeat(1 << 20), "﷽".repeat(350_000)] {
+            let scored: String = huge.chars().take(MAX_CHARS).collect();
+            assert_eq!(title.delta(&huge, "Stardew Valley"), title.delta(&scored, "Stardew Valley"));
+            assert_eq!(title.tokenize(&huge), title.tokenize(&scored));
+            assert!((0.0..=1.0).contains(&semantic_delta_v3(&huge, &huge.replace('a', "b"))));
+        }
+        // Through mode detection too, with a sentence marker to scan past
+        let sentence = format!("i think {}.", "a".repeat(1 << 20));
+        let start = std::time::Instant::now();
+        assert_eq!(Mode::detect(&sentence, &sentence), Mode::detect(truncate_chars(&sentence, MAX_CHARS), truncate_chars(&sentence, MAX_CHARS)));
+        assert!((0.0..=1.0).contains(&semantic_delta_v3(&sentence, &sentence.replace('a', "b"))));
+        assert!(start.elapsed() < std::time::Duration::from_secs(1), "{:?}", start.elapsed());
+        let short = DeltaProfile { max_chars: 7, ..DeltaProfile::title() };
+        assert_eq!(short.tokenize("Stardew Valley"), vec!["stardew"]);
+        assert_eq!(truncate_chars("pokémon", 4), "poké");
+    }
+
+    #[test]
+    fn test_possessives_and_apostrophes() {
+        assert_eq!(normalize_v2("Assassin's Creed"), vec!["assassin", "creed"]);
+        assert_eq!(normalize_v2("Baldur’s Gate 3"), vec!["baldur", "gate", "3"]);
+        assert_eq!(normalize_v2("No Man's Sky"), vec!["no", "man", "sky"]);
+        assert_eq!(normalize_v2("Gamers' Choice"), vec!["gamers", "choice"]);
+        assert_eq!(normalize_v2("Rock 'n' Roll Racing"), vec!["rock", "roll", "racing"]);
+        assert_eq!(normalize_v2("L'Île d'Yeu"), vec!["île", "yeu"]);
+        // Lone letters without an apostrophe stay, roman numerals included
+        assert_eq!(normalize_v2("Grand Theft Auto V"), vec!["grand", "theft", "auto", "5"]);
+        assert_eq!(normalize_v2("Half-Life: A"), vec!["half", "life", "a"]);
+        
+        let elisions = DeltaProfile { drop_elisions: false, ..DeltaProfile::title() };
+        assert_eq!(elisions.tokenize("L'Amour du Rock 'n' Roll's"), vec!["l", "amour", "du", "rock", "n", "roll"]);
+        assert_eq!(normalize_words("'s Gravenhage", false), vec!["s", "gravenhage"]);
+        let dlc_keywords = WordSet::new(["jeu de l'année"]);
+        let french = DeltaProfile { dlc_keywords: dlc_keywords.clone(), ..DeltaProfile::title() };
+        for profile in [french, DeltaProfile { dlc_keywords, ..elisions }] {
+            assert!(profile.prepare_query("Rayman Legends - Jeu de l'année").dlc_like);
+        }
+        
+        // No spurious "s" token
+        let title = DeltaProfile::title();
+        assert!(title.delta("assassins creed", "Assassin's Creed") < title.delta("assassins creed", "Assassin s Creed"));
+        let catalog = DeltaCatalog::new(&[
+            "Assassin's Creed II", "Creed: Rise to Glory", "Assassin's Creed", "Baldur's Gate", "Baldur's Gate 3",
+            "No Man's Sky", "Man of Medan", "Sky Force",
+        ]);
+        for (query, expected) in [
+            ("assassins creed", "Assassin's Creed"),
+            ("assassin's creed", "Assassin's Creed"),
+            ("baldurs gate 3", "Baldur's Gate 3"),
+            ("no mans sky", "No Man's Sky"),
+        ] {
+            assert_eq!(catalog.best(query).unwrap().title, expected, "{}", query);
+        }
+    }
+    
+    #[test]
+    fn test_dlc_debias() {
+        let delta1 = semantic_delta_v3("portal", "Portal 2");
+        let delta2 = semantic_delta_v3("portal", "Portal 2 GOTY Edition");
+        assert!(delta2 > delta1); // GOTY should be penalized
+    }
+    
+    fn words_only() -> DeltaProfile {
+        DeltaProfile { cjk_threshold: None, ..DeltaProfile::default() }
+    }
+    
+    #[test]
+    fn test_cjk_selects_ngrams() {
+        let profile = DeltaProfile::default();
+        assert_eq!(profile.tokenize("ゼルダの伝説"), vec!["ゼル", "ルダ", "ダの", "の伝", "伝説"]);
+        assert_eq!(profile.tokenize("ゼルダ 2"), vec!["ゼル", "ルダ", "2"]);
+        assert_eq!(profile.tokenize("Zelda II"), vec!["zelda", "2"]);
+        // Mostly Latin: stays in word mode
+        assert_eq!(profile.tokenize("Zelda Breath of the Wild 伝説"), normalize_v2("Zelda Breath of the Wild 伝説"));
+        assert_eq!(words_only().tokenize("ゼルダの伝説"), vec!["ゼルダの伝説"]);
+    }
+    
+    #[test]
+    fn test_ngrams_outperform_words_on_japanese() {
+        // Tokenizers alone: the containment bonus also finds ゼルダ at the
+        // start of the one word-mode token of ゼルダの伝説
+        let alone = |profile: DeltaProfile| DeltaProfile { containment_bonus: 0.0, ..profile };
+        let (words, ngrams) = (alone(words_only()), alone(DeltaProfile::default()));
+        assert!(DeltaProfile::default().delta("ゼルダ", "ゼルダの伝説") < words_only().delta("ゼルダ", "ゼルダの伝説") - 0.1);
+        
+        // One giant token per title: no overlap at all in word mode
+        let q = words.prepare_query("ゼルダ");
+        assert_eq!(jaccard_index(&q, &words.prepare_query("ゼルダの伝説"), None), 0.0);
+        assert!(jaccard_index(&ngrams.prepare_query("ゼルダ"), &ngrams.prepare_query("ゼルダの伝説"), None) > 0.3);
+        
+        for (query, title, distractor) in [
+            ("ゼルダ", "ゼルダの伝説", "マリオカート"),
+            ("ファイナルファンタジー", "ファイナルファンタジーVII", "ファイアーエムブレム"),
+        ] {
+            let word_gap = words.delta(query, distractor) - words.delta(query, title);
+            let ngram_gap = ngrams.delta(query, distractor) - ngrams.delta(query, title);
+            assert!(ngrams.delta(query, title) < words.delta(query, title) - 0.2, "{} / {}", query, title);
+            assert!(ngram_gap > 2.0 * word_gap, "{} / {}: {} vs {}", query, title, ngram_gap, word_gap);
+        }
+        
+        let explicit = DeltaProfile { cjk_threshold: None, ..DeltaProfile::with_tokenizer(TokenizerMode::CharNGrams(2)) };
+        assert_eq!(explicit.delta("ゼルダ", "ゼルダの伝説"), DeltaProfile::default().delta("ゼルダ", "ゼルダの伝説"));
+    }
+    
+    #[test]
+    fn test_custom_tokenizer() {
+        let expand = TokenizerMode::Custom(Arc::new(|text: &str| {
+            text.to_lowercase()
+                .split_whitespace()
+                .flat_map(|word| match word {
+                    "ff" => vec!["final".to_string(), "fantasy".to_string()],
+                    "vii" => vec!["7".to_string()],
+                    word => vec![word.to_string()],
+                })
+                .collect()
+        }));
+        let profile = DeltaProfile::with_tokenizer(expand);
+        
+        assert_eq!(profile.tokenize("FF VII"), vec!["final", "fantasy", "7"]);
+        assert!(profile.delta("ff 7", "Final Fantasy VII") < 0.1);
+        let words = DeltaProfile { gaming_acronyms: false, ..DeltaProfile::title() };
+        assert!(profile.delta("ff 7", "Final Fantasy VII") < words.delta("ff 7", "Final Fantasy VII"));
+        assert_eq!(format!("{:?}", profile.tokenizer), "Custom(..)");
+    }
+    
+    #[test]
+    fn test_catalog_and_batch_use_profile_tokenizer() {
+        let titles = ["マリオカート", "ゼルダの伝説", "ゼルダ無双", "ドラゴンクエスト"];
+        
+        let catalog = DeltaCatalog::prebuild(DeltaProfile::default(), &titles);
+        assert_eq!(catalog.len(), 4);
+        assert_eq!(catalog.deltas("ゼルダの伝説"), DeltaProfile::default().delta_batch("ゼルダの伝説", &titles));
+        assert_eq!(catalog.best_match("ゼルダの伝説").unwrap().0, 1);
+        let best: Vec<usize> = catalog
+            .best_matches(&["ドラゴンクエスト", "マリオ"])
+            .into_iter()
+            .map(|best| best.unwrap().0)
+            .collect();
+        assert_eq!(best, vec![3, 0]);
+        
+        // Same titles under another profile score differently
+        let words = DeltaCatalog::prebuild(words_only(), &titles);
+        assert_ne!(words.deltas("ゼルダ"), catalog.deltas("ゼルダ"));
+        assert_eq!(words.deltas("ゼルダ"), words_only().delta_batch("ゼルダ", &titles));
+        assert!(DeltaCatalog::prebuild(DeltaProfile::default(), &[] as &[&str]).best_match("x").is_none());
+    }
+    
+    #[test]
+    fn test_prepared_query_matches_delta() {
+        let profile = DeltaProfile::title();
+        for query in ["portal", "doom 2", "c++ primer", "witcher 3 goty", "ゼルダ", ""] {
+            let prepared = profile.prepare_query(query);
+            let query_once = PreparedQuery::new(query, &profile);
+            assert_eq!(prepared.tokens(), profile.tokenize(query).as_slice());
+            assert_eq!(query_once.tokens(), prepared.tokens());
+            for title in ["Portal 2 GOTY Edition", "DOOM II", "C# for Games", "The Witcher 3: Wild Hunt", "ゼルダの伝説"] {
+                let delta = profile.delta(query, title);
+                assert_eq!(profile.delta_prepared(&prepared, title), delta, "{} / {}", query, title);
+                assert_eq!(query_once.delta(title), delta, "{} / {}", query, title);
+                assert_eq!(delta_prepared(&query_once, &query_once.prepare_title(title)), delta, "{} / {}", query, title);
+            }
+        }
+    }
+    
+    #[test]
+    fn test_sentence_separates_paraphrases_from_contradictions() {
+        for (query, paraphrase, contradiction) in [
+            ("the game is fun", "this game is really fun", "the game is not fun"),
+            ("i love this game", "i really love this game", "i don't love this game"),
+            ("the boss fight was easy", "the boss fight was pretty easy", "the boss fight was never easy"),
+            ("a cat is on the mat", "the cat sits on a mat", "no cat is on the mat"),
+        ] {
+            let (close, far) = (semantic_delta_sentence(query, paraphrase), semantic_delta_sentence(query, contradiction));
+            assert!(close < far, "{} / {}: {} vs {}", paraphrase, contradiction, close, far);
+        }
+        // TITLE mode only sees the shared words
+        assert!(semantic_delta_title("the game is fun", "the game is not fun") < semantic_delta_title("the game is fun", "this game is really fun"));
+        
+        let no_negation = DeltaProfile { negation_penalty: 0.0, ..DeltaProfile::sentence() };
+        let gap = semantic_delta_sentence("i love it", "i don't love it") - no_negation.delta("i love it", "i don't love it");
+        assert!((gap - 0.10).abs() < 1e-9);
+        assert_eq!(semantic_delta_sentence("not fun", "never fun"), no_negation.delta("not fun", "never fun"));
+    }
+    
+    #[test]
+    fn test_sentence_filters_stopwords_title_does_not() {
+        let sentence = DeltaProfile::sentence();
+        assert!(sentence.validate().is_ok());
+        assert_eq!(sentence.tokenize("The Cat is on the Mat"), vec!["cat", "mat"]);
+        // Nothing but stopwords: kept
+        assert_eq!(sentence.tokenize("to be or"), vec!["to", "be", "or"]);
+        
+        let title = DeltaProfile::title();
+        assert_eq!(title.tokenize("The Cat is on the Mat"), vec!["the", "cat", "is", "on", "the", "mat"]);
+        assert_eq!(semantic_delta_v3("the game is fun", "the game is not fun"), title.delta("the game is fun", "the game is not fun"));
+        
+        let catalog = DeltaCatalog::prebuild(sentence.clone(), &["the game is fun", "the game is not fun"]);
+        assert_eq!(catalog.deltas("this game is fun"), sentence.delta_batch("this game is fun", &["the game is fun", "the game is not fun"]));
+        assert_eq!(catalog.best_match("this game is fun").unwrap().0, 0);
+    }
+    
+    #[test]
+    fn test_mode_detection() {
+        for title in [
+            "the legend of zelda breath of the wild",
+            "The Legend of Zelda: Breath of the Wild",
+            "Sid Meier's Civilization: Beyond Earth - Rising Tide",
+            "Life is Strange: True Colors",
+            "Who Wants to Be a Millionaire?",
+            "Dr. Mario",
+            "the game is fun",
+        ] {
+            assert!(!looks_like_sentence(title), "{}", title);
+            assert_eq!(Mode::detect(title, title), Mode::Title, "{}", title);
+            assert_eq!(semantic_delta_v3(title, "Zelda"), semantic_delta_title(title, "Zelda"), "{}", title);
+        }
+        
+        let review = "I think this is the best open world game ever made";
+        let other = "this is the best open world game I have played in years";
+        assert!(looks_like_sentence(review) && looks_like_sentence("Is it fun? I loved it."));
+        assert_eq!(Mode::detect(review, other), Mode::Sentence);
+        assert_eq!(semantic_delta_v3(review, other), semantic_delta_sentence(review, other));
+        // One side a title: TITLE
+        assert_eq!(Mode::detect(review, "The Legend of Zelda: Breath of the Wild"), Mode::Title);
+        
+        assert_eq!(semantic_delta_with_mode("the game is fun", "the game is not fun", Mode::Sentence), semantic_delta_sentence("the game is fun", "the game is not fun"));
+        assert_eq!(semantic_delta_with_mode(review, other, Mode::Title), semantic_delta_title(review, other));
+    }
+    
+    #[test]
+    fn test_mode_detection_is_linear() {
+        // A marker, then 100k letters: each '.' used to rescan the letters
+        // before it (0.7 s at 40k characters)
+        let word = "a".repeat(100_000);
+        let start = std::time::Instant::now();
+        assert!(looks_like_sentence(&format!("i think {}.", word)));
+        assert!(!looks_like_sentence(&format!("i think {}", word)));
+        assert!(!looks_like_sentence(&format!("i think {}", "a.".repeat(50_000))));
+        assert!(start.elapsed() < std::time::Duration::from_secs(1), "{:?}", start.elapsed());
+    }
+
+    #[test]
+    fn test_catalog_top_k() {
+        let titles = ["Portal 2", "Portal", "Portal 2 GOTY Edition", "Portal", "Hades", "Half-Life 2", "Portal Knights"];
+        let catalog = DeltaCatalog::new(&titles);
+        
+        // Same order as sorting every Δ, lowest index first on ties
+        let deltas = catalog.deltas("portal");
+        let mut sorted: Vec<(usize, f64)> = deltas.iter().copied().enumerate().collect();
+        sorted.sort_by(|a, b| a.1.total_cmp(&b.1).then(a.0.cmp(&b.0)));
+        let top: Vec<(usize, f64)> = catalog.top_k("portal", 4).iter().map(|m| (m.index, m.delta)).collect();
+        assert_eq!(top, sorted[..4].to_vec());
+        assert_eq!(top[..2].iter().map(|m| m.0).collect::<Vec<_>>(), vec![1, 3]);
+        
+        let best = catalog.best("hades").unwrap();
+        assert_eq!((best.index, best.title), (4, "Hades"));
+        assert_eq!(catalog.best_match("hades"), Some((best.index, best.delta)));
+        assert_eq!(catalog.top_k("portal", 100).len(), titles.len());
+        assert!(catalog.top_k("portal", 0).is_empty());
+        assert!(DeltaCatalog::new(&[] as &[&str]).best("portal").is_none());
+        
+        let corpus = Corpus::new(&titles);
+        assert_eq!(corpus.top_k("portal", 4), catalog.top_k("portal", 4));
+        assert_eq!(corpus.best("hades").map(|m| m.index), Some(4));
+    }
+
+    #[test]
+    fn test_catalog_prefilter() {
+        let mut titles: Vec<String> = (1..=12).map(|n| format!("Portal Chapter {}", n)).collect();
+        titles.extend(["Hades", "Half-Life 2", "Stardew Valley", "Portal Knights"].map(String::from));
+        let plain = DeltaCatalog::new(&titles);
+        let catalog = DeltaCatalog::new(&titles).with_index();
+        let ranked = |matches: Vec<Match>| matches.iter().map(|m| (m.index, m.delta)).collect::<Vec<_>>();
+
+        // Only the 13 "portal" titles are scored, and they hold the top 5
+        assert_eq!(catalog.candidate_count("portal chapter", 5), 13);
+        assert_eq!(ranked(catalog.top_k_indexed("portal chapter", 5)), ranked(plain.top_k("portal chapter", 5)));
+        // A one-token typo is caught by its trigrams ("por", "ort")
+        assert_eq!(catalog.candidate_count("portel", 5), 13);
+        assert_eq!(catalog.top_k_indexed("portel", 1)[0].title, plain.top_k("portel", 1)[0].title);
+
+        // Full scan: no index, too few candidates, more asked than found
+        assert_eq!(plain.candidate_count("portal", 5), titles.len());
+        assert_eq!(catalog.candidate_count("hades", 5), titles.len());
+        assert_eq!(ranked(catalog.top_k_indexed("hades", 3)), ranked(plain.top_k("hades", 3)));
+        assert_eq!(catalog.candidate_count("portal", 14), titles.len());
+        assert_eq!(catalog.candidate_count("///", 5), titles.len());
+        assert!(catalog.top_k_indexed("portal", 0).is_empty());
+    }
+
+    #[test]
+    fn test_catalog_updates() {
+        let mut titles: Vec<String> = (1..=12).map(|n| format!("Portal Chapter {}", n)).collect();
+        titles.extend(["Hades", "Half-Life 2", "Stardew Valley", "Portal Knights"].map(String::from));
+        let ranked = |matches: Vec<Match>| matches.iter().map(|m| (m.index, m.title.to_string(), m.delta)).collect::<Vec<_>>();
+
+        // Pushed, extended and removed titles keep the index in step
+        let mut catalog = DeltaCatalog::new(&titles[..10]).with_index();
+        catalog.extend(&titles[10..]);
+        assert_eq!(catalog.push("Portal Stories: Mel"), titles.len());
+        assert_eq!(catalog.remove(3).as_deref(), Some("Portal Chapter 4"));
+        assert_eq!(catalog.remove(99), None);
+        titles.push("Portal Stories: Mel".to_string());
+        titles.remove(3);
+        let rebuilt = DeltaCatalog::new(&titles).with_index();
+        assert_eq!(catalog.titles(), rebuilt.titles());
+        for query in ["portal chapter 5", "portel", "hades", "stardew"] {
+            assert_eq!(catalog.candidate_count(query, 5), rebuilt.candidate_count(query, 5), "{}", query);
+            assert_eq!(ranked(catalog.top_k_indexed(query, 5)), ranked(rebuilt.top_k_indexed(query, 5)), "{}", query);
+        }
+
+        // Shared: copy-on-write buckets of 4, earlier snapshots untouched
+        let shared = SharedCatalog::with_bucket_size(DeltaCatalog::new(&titles), 4);
+        let before = shared.snapshot();
+        assert_eq!(shared.remove(5).as_deref(), Some(titles[5].as_str()));
+        assert_eq!(shared.push("Hades II"), titles.len() - 1);
+        assert_eq!(shared.remove(titles.len()), None);
+        assert_eq!(before.titles().collect::<Vec<_>>(), titles);
+        titles.remove(5);
+        titles.push("Hades II".to_string());
+        shared.extend(&["Portal 2", "Hollow Knight"]);
+        titles.extend(["Portal 2", "Hollow Knight"].map(String::from));
+        let snapshot = shared.snapshot();
+        assert_eq!(snapshot.titles().collect::<Vec<_>>(), titles);
+        assert_eq!(snapshot.title(titles.len() - 1), Some("Hollow Knight"));
+        let plain = DeltaCatalog::new(&titles);
+        for query in ["portal chapter 5", "hades", "portal"] {
+            assert_eq!(ranked(snapshot.top_k(query, 6)), ranked(plain.top_k(query, 6)), "{}", query);
+        }
+        assert_eq!(snapshot.deltas("hades"), plain.deltas("hades"));
+        assert_eq!(snapshot.best("hades 2").map(|m| m.title), Some("Hades II"));
+    }
+
+    #[test]
+    fn test_shared_catalog_concurrent_inserts() {
+        const INSERTS: usize = 1000;
+        let catalog = SharedCatalog::with_bucket_size(DeltaCatalog::new(&["Hades", "Portal 2"]).with_index(), 64);
+        let total = INSERTS + 2;
+        let seen: Vec<Vec<(usize, String)>> = std::thread::scope(|scope| {
+            let readers: Vec<_> = (0..4)
+                .map(|reader| {
+                    let catalog = &catalog;
+                    scope.spawn(move || {
+                        let mut seen = Vec::new();
+                        loop {
+                            let snapshot = catalog.snapshot();
+                            let query = format!("game {}", seen.len() % 97 + reader);
+                            let matches = snapshot.top_k(&query, 3).into_iter().chain(snapshot.top_k_indexed("portal", 2));
+                            for m in matches {
+                                assert!(m.index < snapshot.len());
+                                assert_eq!(snapshot.title(m.index), Some(m.title));
+                                seen.push((m.index, m.title.to_string()));
+                            }
+                            if snapshot.len() == total {
+                                return seen;
+                            }
+                        }
+                    })
+                })
+                .collect();
+            for n in 0..INSERTS {
+                catalog.push(&format!("Game {}", n));
+            }
+            readers.into_iter().map(|reader| reader.join().unwrap()).collect()
+        });
+
+        // Inserts only append: every index still names the title it was
+        // returned with
+        let last = catalog.snapshot();
+        assert_eq!(last.len(), total);
+        for (index, title) in seen.iter().flatten() {
+            assert_eq!(last.title(*index), Some(title.as_str()));
+        }
+        assert_eq!(last.best("game 999").map(|m| (m.index, m.title)), Some((total - 1, "Game 999")));
+    }
+
+    #[test]
+    fn test_containment_bonus() {
+        let title = DeltaProfile::title();
+        let flat = DeltaProfile { containment_bonus: 0.0, ..DeltaProfile::title() };
+        let best = |profile: &DeltaProfile, query: &str, titles: &[&str]| {
+            DeltaCatalog::prebuild(profile.clone(), titles).best(query).map(|m| m.title.to_string()).unwrap()
+        };
+        let stardew = ["Star Dew Drops", "Stardrop", "Stardew Valley Soundtrack", "Stardew Valley"];
+        assert_eq!(best(&title, "stardew", &stardew), "Stardew Valley");
+        // Truncated titles that used to lose to a near miss
+        for (query, truncated, near_miss) in [
+            ("dark souls", "Dark Souls: Prepare to Die Edition", "Dark Soul"),
+            ("final fantasy", "Final Fantasy VII Remake Intergrade", "Final Fantasia"),
+        ] {
+            assert_eq!(best(&flat, query, &[near_miss, truncated]), near_miss);
+            assert_eq!(best(&title, query, &[near_miss, truncated]), truncated);
+        }
+
+        // Scaled by coverage, the last token may be cut short, in order only
+        let bonus = |query: &str, title_text: &str| title.explain(query, title_text).containment_bonus;
+        assert!(bonus("stardew", "Stardew Valley") > bonus("stardew", "Stardew Valley Soundtrack"));
+        assert!(bonus("stardew val", "Stardew Valley") > 0.0);
+        assert_eq!(bonus("valley stardew", "Stardew Valley"), 0.0);
+        assert_eq!(bonus("stardew", "Star Dew Drops"), 0.0);
+        assert_eq!(flat.explain("stardew", "Stardew Valley").containment_bonus, 0.0);
+        assert_eq!(DeltaProfile::sentence().containment_bonus, 0.0);
+        // Exact matches still come first
+        assert_eq!(bonus("stardew", "Stardew"), 0.0);
+        assert!(title.delta("stardew valley", "Stardew Valley") < title.delta("stardew val", "Stardew Valley"));
+        assert!(title.delta("stardew", "Stardew") < title.delta("stardew", "Stardew Valley"));
+    }
+
+    #[test]
+    fn test_gaming_acronyms() {
+        let title = DeltaProfile::title();
+        let spelled_out = DeltaProfile { gaming_acronyms: false, ..DeltaProfile::title() };
+        for (query, game) in [
+            ("gta v", "Grand Theft Auto V"),
+            ("cod black ops", "Call of Duty: Black Ops"),
+            ("tf2", "Team Fortress 2"),
+            ("csgo", "Counter-Strike: Global Offensive"),
+            ("botw", "The Legend of Zelda: Breath of the Wild"),
+            ("ffxiv", "Final Fantasy XIV"),
+            ("rdr2", "Red Dead Redemption 2"),
+        ] {
+            let delta = title.delta(query, game);
+            assert!(delta < 0.3, "{} / {}: {}", query, game, delta);
+            assert!(spelled_out.delta(query, game) > delta + 0.2, "{} / {}", query, game);
+        }
+        assert_eq!(title.tokenize("GTA V"), vec!["grand", "theft", "auto", "5"]);
+        // Acronyms are words, not substrings
+        assert_eq!(title.tokenize("Gotham Knights"), vec!["gotham", "knights"]);
+        assert!(!DeltaProfile::sentence().gaming_acronyms);
+        
+        let mut extra = BTreeMap::new();
+        extra.insert("hk".to_string(), "hollow knight".to_string());
+        let custom = DeltaProfile { extra_acronyms: extra.clone(), ..DeltaProfile::sentence() };
+        assert!(custom.validate().is_ok());
+        assert_eq!(custom.tokenize("HK Silksong"), vec!["hollow", "knight", "silksong"]);
+        
+        extra.insert("Two Words".to_string(), "x".to_string());
+        let invalid = DeltaProfile { extra_acronyms: extra, ..DeltaProfile::title() };
+        assert_eq!(invalid.validate().unwrap_err().field, "extra_acronyms");
+    }
+    
+    /// Distance of `kind` if at most `max_dist`
+    fn bounded(kind: LevKind, a: &str, b: &str, max_dist: usize) -> Option<usize> {
+        let (a, b): (Vec<char>, Vec<char>) = (a.chars().collect(), b.chars().collect());
+        levenshtein_distance_bounded(&a, &b, max_dist, kind, &mut EditRows::default())
+    }
+    
+    /// Distance of `kind` without cutoff
+    fn distance(kind: LevKind, a: &str, b: &str) -> usize {
+        bounded(kind, a, b, usize::MAX).unwrap()
+    }
+    
+    #[test]
+    fn test_bounded_levenshtein() {
+        assert_eq!(bounded(LevKind::Classic, "kitten", "sitting", 3), Some(3));
+        assert_eq!(bounded(LevKind::Classic, "kitten", "sitting", 2), None);
+        assert_eq!(bounded(LevKind::Classic, "zelda", "counterstrike", 4), None);
+        assert_eq!(bounded(LevKind::Damerau, "sotry", "story", 1), Some(1));
+        assert_eq!(bounded(LevKind::Damerau, "sotry", "stardew", 2), None);
+        
+        // Same best similarity as scoring every pair in full
+        let tokens = ["story", "of", "seasons", "stardew", "valley", "zelda", "s", "sotry", "seasonz"];
+        for kind in [LevKind::Classic, LevKind::Damerau] {
+            for a in &tokens {
+                let full = tokens.iter()
+                    .filter(|b| *b != a)
+                    .map(|b| 1.0 - distance(kind, a, b) as f64 / a.len().max(b.len()) as f64)
+                    .fold(0.0, f64::max);
+                let others: Vec<Vec<char>> = tokens.iter().filter(|b| *b != a).map(|b| b.chars().collect()).collect();
+                let a_chars: Vec<char> = a.chars().collect();
+                let others = others.iter().map(Vec::as_slice);
+                assert_eq!(best_levenshtein_sim(&a_chars, others, kind, &mut EditRows::default()), full, "{} ({:?})", a, kind);
+            }
+        }
+    }
+    
+    #[test]
+    fn test_titles_without_tokens() {
+        for title in ["///", "!!!", "🍉🍉", "★ ★"] {
+            assert_eq!(semantic_delta_title(title, title), 0.0, "{}", title);
+            assert_eq!(semantic_delta_title(title, &format!("  {}  ", title)), 0.0, "{}", title);
+        }
+        assert!(semantic_delta_title("🍉🍉", "🍉🍌") < 1.0);
+        assert!(semantic_delta_title("🍉🍉", "🍉🍌") > semantic_delta_title("🍉🍉", "🍉🍉"));
+        assert_eq!(semantic_delta_title("🍉", "///"), 1.0);
+        assert_eq!(semantic_delta_title("", "///"), 1.0);
+        assert_eq!(semantic_delta_title("", ""), 0.0);
+        
+        // Against a tokenized title, the raw texts are compared too
+        assert!(semantic_delta_title("///", "/// Hades") < semantic_delta_title("///", "Hades"));
+        
+        // Punctuation between letters still leaves tokens
+        let catalog = DeltaCatalog::new(&["E.T.", "Eternal", "Hades", "///"]);
+        assert_eq!(catalog.best_match("E.T."), Some((0, semantic_delta_title("E.T.", "E.T."))));
+        assert_eq!(catalog.best_match("///"), Some((3, 0.0)));
+        
+        let explained = semantic_delta_explain("🍉🍉", "🍉🍌");
+        assert_eq!((explained.jaccard, explained.anchor_term), (0.0, 0.0));
+        assert_eq!(explained.levenshtein_term, explained.delta);
+    }
+    
+    #[test]
+    fn test_explanation_adds_up() {
+        let queries = ["zelda", "portal", "the witcher 3", "gta v", "hollow knight silksong", "sotry of seasons"];
+        let titles = [
+            "The Legend of Zelda: Breath of the Wild",
+            "Portal 2 GOTY Edition",
+            "The Witcher 3: Wild Hunt",
+            "Grand Theft Auto V",
+            "Hollow Knight",
+            "Story of Seasons",
+            "Counter-Strike 2",
+        ];
+        for query in queries {
+            for title in titles {
+                let explained = semantic_delta_explain(query, title);
+                assert_eq!(explained.delta, semantic_delta_title(query, title), "{} / {}", query, title);
+                let sum = explained.jaccard_term + explained.levenshtein_term + explained.anchor_term
+                    + explained.mu_anchor + explained.dlc_penalty + explained.negation_penalty
+                    - explained.containment_bonus - explained.clamped;
+                assert!((sum - explained.delta).abs() < 1e-12, "{} / {}: {:?}", query, title, explained);
+                assert!(explained.jaccard_capped <= DeltaProfile::title().jaccard_cap);
+            }
+        }
+        
+        let explained = semantic_delta_explain("portal", "Portal 2 GOTY Edition");
+        assert_eq!(explained.query_tokens, vec!["portal"]);
+        assert_eq!(explained.title_tokens, vec!["portal", "2", "goty", "edition"]);
+        assert!(explained.mu_space > 0.0 && explained.dlc_penalty > 0.0);
+        assert_eq!(semantic_delta_explain("portal", "Portal 2").dlc_penalty, 0.0);
+        
+        let negated = DeltaProfile::sentence().explain("this game is fun", "this game is not fun");
+        assert_eq!(negated.negation_penalty, 0.10);
+    }
+    
+    #[test]
+    fn test_damerau_transpositions() {
+        assert_eq!(distance(LevKind::Classic, "sotry", "story"), 2);
+        assert_eq!(distance(LevKind::Damerau, "sotry", "story"), 1);
+        assert_eq!(distance(LevKind::Damerau, "zlda", "zelda"), 1);
+        assert_eq!(distance(LevKind::Damerau, "caleidoscope", "kaleidoscope"), 1);
+        // No substring edited twice: "ca" → "ac" → "abc" isn't allowed
+        assert_eq!(distance(LevKind::Damerau, "ca", "abc"), 3);
+        assert_eq!(distance(LevKind::Damerau, "", "abc"), 3);
+        
+        let classic = DeltaProfile::title();
+        let damerau = DeltaProfile { levenshtein: LevKind::Damerau, ..DeltaProfile::title() };
+        assert!(damerau.delta("sotry of seasons", "Story of Seasons") < classic.delta("sotry of seasons", "Story of Seasons"));
+        assert_eq!(damerau.delta("stardew valley", "Stardew Valley"), classic.delta("stardew valley", "Stardew Valley"));
+    }
+    
+    #[test]
+    fn test_fold_accents() {
+        let title = DeltaProfile::title();
+        let accented = DeltaProfile { fold_accents: false, ..DeltaProfile::title() };
+        for (query, game) in [
+            // French
+            ("pokemon", "Pokémon"),
+            ("l'ile crocodile", "L'Île Crocodile"),
+            ("oeuvre", "Œuvre"),
+            ("a la poursuite de l'evasion", "À la poursuite de l'évasion"),
+            // German
+            ("uber den wolken", "Über den Wolken"),
+        ] {
+            // As close as the query to itself
+            assert_eq!(title.delta(query, game), title.delta(query, query), "{} / {}", query, game);
+            assert!(accented.delta(query, game) > title.delta(query, game), "{} / {}", query, game);
+        }
+        assert_eq!(title.tokenize("Ünter Straße"), vec!["unter", "strasse"]);
+        // "ß" is case-folded (its uppercase is "SS"), with or without folding
+        assert_eq!(accented.tokenize("Straßenbahn-Simulator"), accented.tokenize("STRASSENBAHN-SIMULATOR"));
+        assert_eq!(accented.delta("strassenbahn simulator", "Straßenbahn-Simulator"), title.delta("strassenbahn simulator", "Straßenbahn-Simulator"));
+        
+        // Kana voicing marks are letters, not accents
+        assert_eq!(fold_accents("ゼルダ"), "ゼルダ");
+        assert_ne!(title.tokenize("ガ"), title.tokenize("カ"));
+    }
+    
+    #[test]
+    fn test_profile_weights_and_penalties() {
+        let title = DeltaProfile::title();
+        assert_eq!(title, DeltaProfile::default());
+        assert!(title.validate().is_ok());
+        
+        let no_dlc_penalty = DeltaProfile { dlc_penalty: 0.0, ..DeltaProfile::title() };
+        assert!(title.delta("portal", "Portal 2 GOTY Edition") > no_dlc_penalty.delta("portal", "Portal 2 GOTY Edition"));
+        assert_eq!(title.delta("portal", "Portal 2"), no_dlc_penalty.delta("portal", "Portal 2"));
+        
+        // All weight on the anchor term: titles sharing the prefix score alike
+        let anchor_only = DeltaProfile { jaccard_weight: 0.0, levenshtein_weight: 0.0, anchor_weight: 1.0, ..DeltaProfile::title() };
+        assert!(anchor_only.validate().is_ok());
+        assert_ne!(anchor_only.delta("hades", "Hades II"), title.delta("hades", "Hades II"));
+    }
+    
+    #[test]
+    fn test_config_word_lists() {
+        let title = DeltaConfig::title();
+        let query = "blade runner";
+        let title_delta = semantic_delta_with_config(query, "Blade Runner Director's Cut", &title);
+        assert_eq!(title_delta, semantic_delta_title(query, "Blade Runner Director's Cut"));
+        
+        // Movie catalog: "director's cut" marks a variant, "edition" doesn't
+        let movies = DeltaConfig { dlc_keywords: WordSet::new(["director"]), ..DeltaConfig::title() };
+        assert!(semantic_delta_with_config(query, "Blade Runner Director's Cut", &movies) > title_delta);
+        assert!(semantic_delta_with_config(query, "Blade Runner Final Edition", &movies) < semantic_delta_title(query, "Blade Runner Final Edition"));
+        
+        let stopwords = DeltaConfig { stopwords: WordSet::new(["sku"]), ..DeltaConfig::title() };
+        assert_eq!(stopwords.tokenize("SKU 4421 blue"), vec!["4421", "blue"]);
+        
+        let json = serde_json::to_string(&DeltaConfig::sentence()).unwrap();
+        assert_eq!(serde_json::from_str::<DeltaConfig>(&json).unwrap(), DeltaConfig::sentence());
+        let custom: DeltaConfig = serde_json::from_str(r#"{"dlc_keywords": ["dlc"], "stopwords": ["the"]}"#).unwrap();
+        assert_eq!((custom.dlc_keywords, custom.stopwords), (WordSet::new(["dlc"]), WordSet::new(["the"])));
+    }
+    
+    #[test]
+    fn test_dlc_keywords_match_whole_tokens() {
+        let keywords = WordSet::new(DLC_KEYWORDS);
+        let dlc_like = |title: &str| is_dlc_like(&DeltaProfile::title().tokenize(title), &keywords);
+        assert!(dlc_like("Portal 2 GOTY Edition"));
+        assert!(dlc_like("Skyrim Special Edition"));
+        // Keywords inside other words
+        assert!(!dlc_like("shdlcx"));
+        assert!(!dlc_like("Golden Axe"));
+        assert!(!dlc_like("Story of Seasons"));
+    }
+    
+    #[test]
+    fn test_french_word_lists() {
+        // French catalog: French DLC markers on top of the defaults, French
+        // stopwords instead of English ones
+        let french = DeltaConfig {
+            dlc_keywords: DeltaConfig::title().dlc_keywords.with(["édition", "intégrale", "jeu de l'année"]),
+            stopwords: WordSet::new(["le", "la", "les", "l", "de", "du", "des", "d", "et"]),
+            ..DeltaConfig::title()
+        };
+        // "edition" already there, "jeu de l'année" with and without its "l"
+        assert_eq!(french.dlc_keywords.len(), DLC_KEYWORDS.len() + 3);
+        let dlc_like = |title: &str| french.prepare_query(title).dlc_like;
+        assert!(dlc_like("Astérix & Obélix XXL 2 : Intégrale"));
+        assert!(dlc_like("Les Lapins Crétins : Édition Jeu de l'Année"));
+        assert!(dlc_like("Rayman Legends - Jeu de l'année"));
+        assert!(!dlc_like("Rayman Legends"));
+        // Not the phrase: "de l'année" alone
+        assert!(!dlc_like("Le jeu vidéo de l'année"));
+        
+        assert_eq!(french.tokenize("Les Lapins Crétins : La Grosse Aventure"), vec!["lapins", "cretins", "grosse", "aventure"]);
+        assert!(french.stopwords.contains("les"));
+        assert!(!french.stopwords.contains("the"));
+        
+        let query = "rayman legends";
+        assert!(french.delta(query, "Rayman Legends Intégrale") > french.delta(query, "Rayman Legends"));
+        assert!(semantic_delta_title(query, "Rayman Legends Intégrale") == DeltaConfig::title().delta(query, "Rayman Legends Intégrale"));
+    }
+    
+    #[test]
+    fn test_profile_validation() {
+        let field = |profile: DeltaProfile| profile.validate().unwrap_err().field;
+        let title = DeltaProfile::title;
+        
+        assert_eq!(field(DeltaProfile { jaccard_weight: 0.5, ..title() }), "jaccard_weight");
+        assert_eq!(field(DeltaProfile { anchor_weight: -0.1, jaccard_weight: 0.7, ..title() }), "anchor_weight");
+        assert_eq!(field(DeltaProfile { jaccard_cap: 0.0, ..title() }), "jaccard_cap");
+        assert_eq!(field(DeltaProfile { space_correction: 1.5, ..title() }), "space_correction");
+        assert_eq!(field(DeltaProfile { dlc_penalty: -0.05, ..title() }), "dlc_penalty");
+        assert_eq!(field(DeltaProfile { cjk_threshold: Some(2.0), ..title() }), "cjk_threshold");
+        assert_eq!(field(DeltaProfile { cjk_ngram: 0, ..title() }), "cjk_ngram");
+        assert_eq!(field(DeltaProfile { negation_penalty: 1.5, ..title() }), "negation_penalty");
+        assert_eq!(field(DeltaProfile { containment_bonus: -0.1, ..title() }), "containment_bonus");
+        assert_eq!(field(DeltaProfile { max_chars: 0, ..title() }), "max_chars");
+        // Rounding in a hand-written config is tolerated
+        assert!(DeltaProfile { jaccard_weight: 0.333, levenshtein_weight: 0.333, anchor_weight: 0.333, ..title() }.validate().is_ok());
+        
+        let error = DeltaProfile { jaccard_weight: 0.5, ..title() }.validate().unwrap_err();
+        assert!(error.to_string().contains("must sum to 1, got 1.100"), "{}", error);
+    }
+    
+    #[test]
+    fn test_profile_deserializes_over_title_defaults() {
+        let profile: DeltaProfile = serde_json::from_str(r#"{"jaccard_cap": 0.7, "dlc_penalty": 0.1}"#).unwrap();
+        assert_eq!(profile, DeltaProfile { jaccard_cap: 0.7, dlc_penalty: 0.1, ..DeltaProfile::title() });
+        assert!(serde_json::from_str::<DeltaProfile>(r#"{"tokenizer": "x"}"#).is_err());
+        
+        let json = serde_json::to_string(&DeltaProfile::title()).unwrap();
+        assert!(!json.contains("tokenizer"));
+        assert!(json.contains(r#""levenshtein":"classic""#));
+        assert_eq!(serde_json::from_str::<DeltaProfile>(&json).unwrap(), DeltaProfile::title());
+    }
+    
+    #[test]
+    fn test_idf_weighted_jaccard() {
+        let titles = [
+            "The Witcher", "The Witness", "The Walking Dead", "The Wolf Among Us", "The Long Dark",
+            "The Forest", "Witcher Adventure Game", "Hades",
+        ];
+        let catalog = DeltaCatalog::prebuild_idf(DeltaProfile::title(), &titles);
+        let frequencies = catalog.profile().document_frequencies.clone().unwrap();
+        assert_eq!((frequencies.documents, frequencies.counts["the"], frequencies.counts["witcher"]), (8, 6, 2));
+        assert!(frequencies.idf("the") < frequencies.idf("witcher"));
+        assert!(frequencies.idf("witcher") < frequencies.idf("unseen"));
+        
+        // A shared "the" counts for less than a shared "witcher"
+        let (plain, idf) = (&DeltaProfile::title(), catalog.profile());
+        let jaccard = |profile: &DeltaProfile, title| profile.explain("the witcher", title).jaccard;
+        assert!(jaccard(idf, "The Witness") < jaccard(plain, "The Witness"));
+        assert!(jaccard(plain, "Witcher Adventure Game") < jaccard(plain, "The Witness"));
+        assert!(jaccard(idf, "Witcher Adventure Game") > jaccard(idf, "The Witness"));
+        assert_eq!(idf.explain("the witcher", "The Witcher").jaccard, 1.0);
+        
+        // The catalog's titles and a config carrying its table score alike
+        let deltas = catalog.deltas("the witcher");
+        for (title, delta) in titles.iter().zip(&deltas) {
+            assert!((idf.delta("the witcher", title) - delta).abs() < 1e-12, "{}", title);
+        }
+        assert_eq!(catalog.best("the witcher").unwrap().title, "The Witcher");
+        assert_eq!(DocumentFrequencies::count(plain, &titles), *frequencies);
+        
+        // Unweighted by default
+        assert_eq!(DeltaProfile::title().document_frequencies, None);
+        assert_eq!(DeltaCatalog::new(&titles).deltas("the witcher"), plain.delta_batch("the witcher", &titles));
+        
+        let json = serde_json::to_string(idf).unwrap();
+        assert_eq!(serde_json::from_str::<DeltaProfile>(&json).unwrap(), *idf);
+        let mut invalid = (*frequencies).clone();
+        invalid.counts.insert("the".to_string(), 9);
+        let error = DeltaProfile { document_frequencies: Some(Arc::new(invalid)), ..DeltaProfile::title() }.validate().unwrap_err();
+        assert_eq!(error.field, "document_frequencies");
+    }
+}
+
+/// Invariants of Δ over generated texts and configs (proptest)
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod properties {
+    use super::*;
+    use proptest::prelude::*;
+    
+    /// Latin titles: letters (some accented), digits, spaces and punctuation
+    const TEXT: &str = "[a-zA-Z0-9éèàüöçßÉÜ :'.!&+#-]{0,30}";
+    
+    /// Valid configs: weights summing to 1, every correction and penalty
+    /// anywhere in its range
+    fn profile() -> impl Strategy<Value = DeltaProfile> {
+        (
+            (0.01..1.0f64, 0.01..1.0f64, 0.01..1.0f64),
+            (0.01..=1.0f64, 0.0..=1.0f64, 0.0..=1.0f64),
+            (0.0..=2.0f64, 0.0..=1.0f64),
+            (any::<bool>(), any::<bool>(), any::<bool>()),
+        )
+            .prop_map(|((wj, wl, wr), (jaccard_cap, space_correction, anchor_correction), (dlc_penalty, negation_penalty), (damerau, gaming_acronyms, fold_accents))| {
+                let sum = wj + wl + wr;
+                DeltaProfile {
+                    jaccard_weight: wj / sum,
+                    levenshtein_weight: wl / sum,
+                    anchor_weight: wr / sum,
+                    jaccard_cap,
+                    space_correction,
+                    anchor_correction,
+                    dlc_penalty,
+                    negation_penalty,
+                    levenshtein: if damerau { LevKind::Damerau } else { LevKind::Classic },
+                    gaming_acronyms,
+                    fold_accents,
+                    ..DeltaProfile::title()
+                }
+            })
+    }
+    
+    /// Lowest Δ a profile gives: the Jaccard cap's share of wJ
+    fn floor(profile: &DeltaProfile) -> f64 {
+        profile.jaccard_weight * (1.0 - profile.jaccard_cap)
+    }
+    
+    proptest! {
+        #[test]
+        fn delta_is_bounded(query in TEXT, title in TEXT, profile in profile()) {
+            prop_assert!(profile.validate().is_ok());
+            let delta = profile.delta(&query, &title);
+            prop_assert!((0.0..=1.0).contains(&delta), "{:?} / {:?}: {}", query, title, delta);
+        }
+        
+        #[test]
+        fn identical_texts_are_closest(text in TEXT, other in TEXT, profile in profile()) {
+            // Texts without tokens compare raw: Δ 0
+            let closest = if profile.tokenize(&text).is_empty() { 0.0 } else { floor(&profile) };
+            let delta = profile.delta(&text, &text);
+            prop_assert!((delta - closest).abs() < 1e-9, "{:?}: {} (closest {})", text, delta, closest);
+            prop_assert!(profile.delta(&text, &other) >= delta - 1e-9, "{:?} / {:?}", text, other);
+        }
+        
+        #[test]
+        fn l_symmetric_is_symmetric(a in TEXT, b in TEXT, damerau in any::<bool>()) {
+            let kind = if damerau { LevKind::Damerau } else { LevKind::Classic };
+            let profile = DeltaProfile::title();
+            let (a, b) = (profile.prepare_query(&a), profile.prepare_query(&b));
+            prop_assert_eq!(l_symmetric(&a.chars, &b.chars, kind), l_symmetric(&b.chars, &a.chars, kind));
+        }
+        
+        /// Query words spelled without any letter of a DLC keyword, so the
+        /// keyword can't pull the title closer through the Levenshtein term
+        /// ("gol" ≈ "gold"); the title may contain them
+        #[test]
+        fn dlc_keywords_never_bring_a_title_closer(
+            query in "[jkqwz]{2,6}( [jkqwz]{2,6}){0,2}",
+            words in "[a-z]{2,8}( [a-z]{2,8}){0,3}",
+            with_query in any::<bool>(),
+            keyword in proptest::sample::select(DLC_KEYWORDS),
+        ) {
+            let profile = DeltaProfile::title();
+            let title = if with_query { format!("{} {}", query, words) } else { words };
+            let plain = profile.delta(&query, &title);
+            let dlc = profile.delta(&query, &format!("{} {}", title, keyword));
+            prop_assert!(dlc >= plain - 1e-9, "{:?} / {:?} + {}: {} < {}", query, title, keyword, dlc, plain);
+        }
+        
+        #[test]
+        fn whitespace_and_case_do_not_matter(query in TEXT, title in TEXT, profile in profile()) {
+            let delta = profile.delta(&query, &title);
+            prop_assert_eq!(profile.delta(&format!("  {}\t", query), &format!("\n{} ", title)), delta);
+            prop_assert_eq!(profile.delta(&query.to_uppercase(), &title.to_lowercase()), delta);
+        }
+    }
+}
+
+/// Heap allocations of preparing and scoring, counted per thread by the
+/// test binary's allocator
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod allocations {
+    use super::*;
+    use std::alloc::{GlobalAlloc, Layout, System};
+    use std::cell::Cell;
+    
+    struct Counting;
+    
+    thread_local! {
+        static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+    }
+    
+    unsafe impl GlobalAlloc for Counting {
+        unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+            ALLOCATIONS.with(|count| count.set(count.get() + 1));
+            System.alloc(layout)
+        }
+        
+        unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+            System.dealloc(ptr, layout)
+        }
+        
+        unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+            ALLOCATIONS.with(|count| count.set(count.get() + 1));
+            System.realloc(ptr, layout, new_size)
+        }
+    }
+    
+    #[global_allocator]
+    static ALLOCATOR: Counting = Counting;
+    
+    /// Allocations `f` makes on this thread
+    fn count<T>(f: impl FnOnce() -> T) -> usize {
+        let before = ALLOCATIONS.with(Cell::get);
+        let result = f();
+        let after = ALLOCATIONS.with(Cell::get);
+        drop(result);
+        after - before
+    }
+    
+    #[test]
+    fn test_allocations() {
+        let profile = DeltaProfile::title();
+        // The raw text, then one string per token: the sets, characters and
+        // edit rows of a short title stay inline
+        assert_eq!(count(|| profile.prepare_query("witcher 3")), 3);
+        assert_eq!(count(|| profile.prepare_query("The Witcher 3: Wild")), 5);
+        
+        let (query, title) = (profile.prepare_query("witcher 3"), profile.prepare_query("The Witcher 3: Wild Hunt"));
+        assert_eq!(count(|| delta_title_tokens(&profile, &query, &title)), 0);
+        assert_eq!(count(|| profile.delta_prepared(&query, "The Witcher 3")), 4);
+        assert!(count(|| profile.delta("witcher 3", "The Witcher 3: Wild Hunt")) <= 10);
+        
+        // IDF of every term looked up once, when the text is prepared
+        let catalog = DeltaCatalog::prebuild_idf(profile.clone(), &["The Witcher 3: Wild Hunt", "The Witness"]);
+        let query = catalog.profile().prepare_query("the witcher 3");
+        assert_eq!(count(|| delta_title_tokens(catalog.profile(), &query, &catalog.prepared[0])), 0);
     }
 }
@@ -0,0 +1,195 @@
+//! `delta_s3` Python module (`python` feature, built with maturin): the
+//! TITLE and auto-mode Δ, a `DeltaConfig` and a prepared `DeltaCatalog`
+//! (also exported as `Corpus`), titles added and removed while other
+//! threads rank. Δ is a distance: lower is better. Batch calls release the
+//! GIL while they score.
+
+// pyo3 0.20's #[pymethods] expands impls inside functions
+#![allow(non_local_definitions)]
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+
+use crate::{CatalogSnapshot, DeltaCatalog as RustDeltaCatalog, DeltaConfig as RustDeltaConfig, SharedCatalog};
+
+/// Δ of `query` and `title` in TITLE mode
+#[pyfunction]
+fn semantic_delta_title(query: &str, title: &str) -> f64 {
+    crate::semantic_delta_title(query, title)
+}
+
+/// Δ of `query` and `title`, TITLE or SENTENCE mode detected from both
+#[pyfunction]
+fn semantic_delta_v3(query: &str, title: &str) -> f64 {
+    crate::semantic_delta_v3(query, title)
+}
+
+/// Δ of `query` and each title, in order (TITLE mode unless `config`)
+#[pyfunction]
+#[pyo3(signature = (query, titles, config=None))]
+fn delta_batch(py: Python, query: &str, titles: Vec<String>, config: Option<&DeltaConfig>) -> Vec<f64> {
+    let config = config.map_or_else(RustDeltaConfig::title, |config| config.config.clone());
+    py.allow_threads(|| config.delta_batch(query, &titles))
+}
+
+/// Weights, corrections and word lists of Δ
+///
+/// Keyword arguments are the fields of the Rust `DeltaConfig` over the TITLE
+/// defaults (`DeltaConfig(dlc_penalty=0.1, stopwords=["le", "la"])`);
+/// out-of-range values raise `ValueError`.
+#[pyclass]
+#[derive(Clone)]
+struct DeltaConfig {
+    config: RustDeltaConfig,
+}
+
+#[pymethods]
+impl DeltaConfig {
+    #[new]
+    #[pyo3(signature = (**kwargs))]
+    fn new(py: Python, kwargs: Option<&pyo3::types::PyDict>) -> PyResult<Self> {
+        match kwargs {
+            Some(kwargs) => {
+                let json: String = py.import("json")?.call_method1("dumps", (kwargs,))?.extract()?;
+                Self::from_json(&json)
+            }
+            None => Ok(Self { config: RustDeltaConfig::title() }),
+        }
+    }
+
+    /// TITLE mode defaults
+    #[staticmethod]
+    fn title() -> Self {
+        Self { config: RustDeltaConfig::title() }
+    }
+
+    /// SENTENCE mode defaults
+    #[staticmethod]
+    fn sentence() -> Self {
+        Self { config: RustDeltaConfig::sentence() }
+    }
+
+    /// Config from its JSON form (missing fields take the TITLE defaults)
+    #[staticmethod]
+    fn from_json(json: &str) -> PyResult<Self> {
+        let config: RustDeltaConfig = serde_json::from_str(json).map_err(|e| PyValueError::new_err(e.to_string()))?;
+        config.validate().map_err(|e| PyValueError::new_err(e.to_string()))?;
+        Ok(Self { config })
+    }
+
+    fn to_json(&self) -> String {
+        serde_json::to_string(&self.config).expect("DeltaConfig serializes")
+    }
+
+    /// Δ of `query` and `title` under this config
+    fn delta(&self, query: &str, title: &str) -> f64 {
+        self.config.delta(query, title)
+    }
+
+    /// Tokens of `text` under this config
+    fn tokenize(&self, text: &str) -> Vec<String> {
+        self.config.tokenize(text)
+    }
+
+    fn __repr__(&self) -> String {
+        format!("DeltaConfig({})", self.to_json())
+    }
+}
+
+/// Titles prepared once, ranked against many queries; `idf=True` weighs
+/// Jaccard by the IDF of their tokens. Rankings read the titles as they
+/// were when the call started: a concurrent `remove` shifts the indices
+/// after it.
+#[pyclass]
+struct DeltaCatalog {
+    catalog: SharedCatalog,
+}
+
+#[pymethods]
+impl DeltaCatalog {
+    #[new]
+    #[pyo3(signature = (titles, config=None, idf=false))]
+    fn new(py: Python, titles: Vec<String>, config: Option<&DeltaConfig>, idf: bool) -> Self {
+        let config = config.map_or_else(RustDeltaConfig::title, |config| config.config.clone());
+        let catalog = py.allow_threads(|| {
+            if idf {
+                RustDeltaCatalog::prebuild_idf(config, &titles)
+            } else {
+                RustDeltaCatalog::prebuild(config, &titles)
+            }
+        });
+        Self { catalog: SharedCatalog::from(catalog) }
+    }
+
+    fn __len__(&self) -> usize {
+        self.catalog.len()
+    }
+
+    /// Title at `index`
+    fn title(&self, index: usize) -> PyResult<String> {
+        self.catalog
+            .snapshot()
+            .title(index)
+            .map(str::to_string)
+            .ok_or_else(|| no_title(index))
+    }
+
+    /// Prepare and append `title`; its index
+    fn push(&self, py: Python, title: &str) -> usize {
+        py.allow_threads(|| self.catalog.push(title))
+    }
+
+    /// Prepare and append `titles`
+    fn extend(&self, py: Python, titles: Vec<String>) {
+        py.allow_threads(|| self.catalog.extend(&titles))
+    }
+
+    /// Remove and return the title at `index`, the ones after it moving
+    /// down by one
+    fn remove(&self, py: Python, index: usize) -> PyResult<String> {
+        py.allow_threads(|| self.catalog.remove(index)).ok_or_else(|| no_title(index))
+    }
+
+    /// `(index, Δ)` of the `k` closest titles, best first
+    fn top_k(&self, py: Python, query: &str, k: usize) -> Vec<(usize, f64)> {
+        py.allow_threads(|| matches(&self.catalog.snapshot(), query, k))
+    }
+
+    /// `top_k` of each query
+    fn top_k_batch(&self, py: Python, queries: Vec<String>, k: usize) -> Vec<Vec<(usize, f64)>> {
+        py.allow_threads(|| {
+            let snapshot = self.catalog.snapshot();
+            queries.iter().map(|query| matches(&snapshot, query, k)).collect()
+        })
+    }
+
+    /// `(index, Δ)` of the closest title, `None` for an empty catalog
+    fn best(&self, py: Python, query: &str) -> Option<(usize, f64)> {
+        py.allow_threads(|| self.catalog.snapshot().best(query).map(|m| (m.index, m.delta)))
+    }
+
+    /// Δ of `query` and every title, in catalog order
+    fn deltas(&self, py: Python, query: &str) -> Vec<f64> {
+        py.allow_threads(|| self.catalog.snapshot().deltas(query))
+    }
+}
+
+fn matches(catalog: &CatalogSnapshot, query: &str, k: usize) -> Vec<(usize, f64)> {
+    catalog.top_k(query, k).into_iter().map(|m| (m.index, m.delta)).collect()
+}
+
+fn no_title(index: usize) -> PyErr {
+    pyo3::exceptions::PyIndexError::new_err(format!("no title at {}", index))
+}
+
+#[pymodule]
+fn delta_s3(py: Python, m: &PyModule) -> PyResult<()> {
+    m.add_function(wrap_pyfunction!(semantic_delta_title, m)?)?;
+    m.add_function(wrap_pyfunction!(semantic_delta_v3, m)?)?;
+    m.add_function(wrap_pyfunction!(delta_batch, m)?)?;
+    m.add_class::<DeltaConfig>()?;
+    m.add_class::<DeltaCatalog>()?;
+    m.add("Corpus", py.get_type::<DeltaCatalog>())?;
+    m.add("__version__", env!("CARGO_PKG_VERSION"))?;
+    Ok(())
+}
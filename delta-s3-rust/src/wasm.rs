@@ -0,0 +1,159 @@
+//! `delta_s3` JavaScript module (`wasm` feature, built with wasm-pack
+//! without the `parallel` feature): the TITLE Δ and a prepared `Corpus`.
+//! Δ is a distance: lower is better.
+
+use wasm_bindgen::prelude::*;
+
+use crate::{DeltaCatalog, DeltaConfig};
+
+/// Δ of `query` and `title` in TITLE mode
+#[wasm_bindgen(js_name = semanticDeltaTitle)]
+pub fn semantic_delta_title(query: &str, title: &str) -> f64 {
+    crate::semantic_delta_title(query, title)
+}
+
+/// Δ of `query` and `title`, TITLE or SENTENCE mode detected from both
+#[wasm_bindgen(js_name = semanticDeltaV3)]
+pub fn semantic_delta_v3(query: &str, title: &str) -> f64 {
+    crate::semantic_delta_v3(query, title)
+}
+
+/// A title of a `Corpus` and its Δ to the query
+#[wasm_bindgen]
+pub struct Match {
+    pub index: usize,
+    #[wasm_bindgen(getter_with_clone)]
+    pub title: String,
+    pub delta: f64,
+}
+
+impl From<crate::Match<'_>> for Match {
+    fn from(m: crate::Match<'_>) -> Self {
+        Self { index: m.index, title: m.title.to_string(), delta: m.delta }
+    }
+}
+
+/// Titles prepared once, ranked against many queries
+#[wasm_bindgen]
+pub struct Corpus {
+    catalog: DeltaCatalog,
+}
+
+#[wasm_bindgen]
+impl Corpus {
+    /// Catalog of `titles` under the TITLE profile
+    #[wasm_bindgen(constructor)]
+    pub fn new(titles: Vec<String>) -> Corpus {
+        Corpus { catalog: DeltaCatalog::new(&titles) }
+    }
+
+    /// Catalog of `titles` under a `DeltaConfig` in its JSON form (missing
+    /// fields take the TITLE defaults); throws on an invalid config
+    #[wasm_bindgen(js_name = withConfig)]
+    pub fn with_config(titles: Vec<String>, config: &str) -> Result<Corpus, JsError> {
+        let config: DeltaConfig = serde_json::from_str(config)?;
+        config.validate()?;
+        Ok(Corpus { catalog: DeltaCatalog::prebuild(config, &titles) })
+    }
+
+    /// Catalog of `titles` under the TITLE profile, Jaccard weighted by the
+    /// IDF of their tokens
+    #[wasm_bindgen(js_name = withIdf)]
+    pub fn with_idf(titles: Vec<String>) -> Corpus {
+        Corpus { catalog: DeltaCatalog::prebuild_idf(DeltaConfig::title(), &titles) }
+    }
+    
+    #[wasm_bindgen(getter)]
+    pub fn length(&self) -> usize {
+        self.catalog.len()
+    }
+
+    /// Prepare and append `title`; its index
+    pub fn push(&mut self, title: &str) -> usize {
+        self.catalog.push(title)
+    }
+
+    /// Prepare and append `titles`
+    pub fn extend(&mut self, titles: Vec<String>) {
+        self.catalog.extend(&titles)
+    }
+
+    /// Remove and return the title at `index`, the ones after it moving
+    /// down by one; `undefined` past the end
+    pub fn remove(&mut self, index: usize) -> Option<String> {
+        self.catalog.remove(index)
+    }
+
+    /// The `k` closest titles, best first
+    #[wasm_bindgen(js_name = topK)]
+    pub fn top_k(&self, query: &str, k: usize) -> Vec<Match> {
+        self.catalog.top_k(query, k).into_iter().map(Match::from).collect()
+    }
+
+    /// The closest title, `undefined` for an empty corpus
+    pub fn best(&self, query: &str) -> Option<Match> {
+        self.catalog.best(query).map(Match::from)
+    }
+
+    /// Δ of `query` and every title, in corpus order
+    pub fn deltas(&self, query: &str) -> Vec<f64> {
+        self.catalog.deltas(query)
+    }
+}
+
+#[cfg(all(test, target_arch = "wasm32"))]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    fn titles(titles: &[&str]) -> Vec<String> {
+        titles.iter().map(|title| title.to_string()).collect()
+    }
+
+    #[wasm_bindgen_test]
+    fn test_title_delta() {
+        assert!(semantic_delta_title("doom 2", "DOOM II") < 0.3);
+        assert!(semantic_delta_title("portal", "Portal 2 GOTY Edition") > semantic_delta_title("portal", "Portal 2"));
+        assert_eq!(semantic_delta_v3("zelda", "Zelda"), semantic_delta_title("zelda", "Zelda"));
+        // unicode-normalization folds accents in wasm32 too
+        assert_eq!(semantic_delta_title("pokemon", "Pokémon"), semantic_delta_title("pokemon", "pokemon"));
+        assert_eq!(semantic_delta_title("strasse", "Straße"), semantic_delta_title("strasse", "strasse"));
+    }
+
+    #[wasm_bindgen_test]
+    fn test_corpus() {
+        let corpus = Corpus::new(titles(&["Portal 2", "Portal", "Portal 2 GOTY Edition", "Portal", "Hades", "Half-Life 2"]));
+        assert_eq!(corpus.length(), 6);
+        let top = corpus.top_k("portal", 2);
+        assert_eq!(top.iter().map(|m| m.index).collect::<Vec<_>>(), vec![1, 3]);
+        assert_eq!(top[0].title, "Portal");
+        assert_eq!(top[0].delta, corpus.deltas("portal")[1]);
+
+        let best = corpus.best("hades").unwrap();
+        assert_eq!((best.index, best.title.as_str()), (4, "Hades"));
+        assert!(Corpus::new(Vec::new()).best("hades").is_none());
+    }
+
+    #[wasm_bindgen_test]
+    fn test_corpus_updates() {
+        let mut corpus = Corpus::new(titles(&["Portal 2", "Hades"]));
+        assert_eq!(corpus.push("Portal"), 2);
+        corpus.extend(titles(&["Half-Life 2", "Hades II"]));
+        assert_eq!(corpus.remove(0).as_deref(), Some("Portal 2"));
+        assert_eq!(corpus.remove(9), None);
+        assert_eq!(corpus.length(), 4);
+        let best = corpus.best("portal").unwrap();
+        assert_eq!((best.index, best.title.as_str()), (1, "Portal"));
+    }
+
+    #[wasm_bindgen_test]
+    fn test_corpus_config() {
+        let movies = Corpus::with_config(titles(&["Blade Runner", "Blade Runner Director's Cut"]), r#"{"dlc_keywords": ["director"]}"#).unwrap();
+        let plain = Corpus::new(titles(&["Blade Runner", "Blade Runner Director's Cut"]));
+        assert!(movies.deltas("blade runner")[1] > plain.deltas("blade runner")[1]);
+        assert!(Corpus::with_config(Vec::new(), r#"{"jaccard_weight": 0.5}"#).is_err());
+        
+        let the = titles(&["The Witcher", "The Witness", "The Forest", "The Long Dark", "Witcher Adventure Game"]);
+        assert!(Corpus::with_idf(the.clone()).deltas("the witcher")[1] > Corpus::new(the).deltas("the witcher")[1]);
+    }
+}
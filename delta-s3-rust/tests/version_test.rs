@@ -0,0 +1,33 @@
+#![cfg(not(target_arch = "wasm32"))]
+
+use assert_cmd::Command;
+use delta_s3::build_info::BuildInfo;
+
+/// Binaries of the manifest built with the features of this test build
+fn built_bins() -> Vec<&'static str> {
+    let mut bins = Vec::new();
+    if cfg!(feature = "cli") {
+        bins.extend(["benchmark", "debug_query", "ds3"]);
+    }
+    if cfg!(all(feature = "parallel", feature = "cli")) {
+        bins.extend(["benchmark_full", "benchmark_276k"]);
+    }
+    if cfg!(feature = "parallel") {
+        bins.push("benchmark_gpu_vs_rust");
+    }
+    bins
+}
+
+#[test]
+fn test_every_bin_prints_version() {
+    for bin in built_bins() {
+        let output = Command::cargo_bin(bin).unwrap().args(["--version", "--json"]).output().unwrap();
+        assert!(output.status.success(), "{} --version --json failed", bin);
+        let info: BuildInfo = serde_json::from_slice(&output.stdout).unwrap();
+        assert_eq!(info, BuildInfo::new(bin));
+
+        let output = Command::cargo_bin(bin).unwrap().arg("--version").output().unwrap();
+        let line = String::from_utf8(output.stdout).unwrap();
+        assert!(line.starts_with(&format!("{} {} (", bin, env!("CARGO_PKG_VERSION"))), "{}: {}", bin, line);
+    }
+}
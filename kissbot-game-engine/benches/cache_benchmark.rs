@@ -71,11 +71,63 @@ fn bench_game_result_serialization(c: &mut Criterion) {
     });
 }
 
+/// Cache of 100 Steam-like entries with a few KB of HTML description each,
+/// stored at `compression_level`
+#[cfg(feature = "compression")]
+async fn setup_detailed_cache(compression_level: Option<i32>) -> SqliteCache {
+    let options = kissbot_game_engine::cache::SqliteCacheOptions { compression_level, ..Default::default() };
+    let cache = SqliteCache::with_options(":memory:", options).await.unwrap();
+    
+    for i in 0..100 {
+        let mut game = GameResult::new("steam", i.to_string(), format!("Game {}", i));
+        game.short_description = format!("Roguelike dungeon crawler number {}", i);
+        game.description = format!(
+            "<h2 class=\"bb_tag\">About Game {}</h2><p>Defy the god of the dead as you hack and slash out of the Underworld.</p>",
+            i
+        )
+        .repeat(40);
+        cache.save(&format!("query{}", i), &game, &[]).await.unwrap();
+    }
+    
+    cache
+}
+
+/// Stored size of detailed entries with and without zstd, and the added cost
+/// of reading them back
+#[cfg(feature = "compression")]
+fn bench_compression(c: &mut Criterion) {
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let plain = runtime.block_on(setup_detailed_cache(None));
+    let compressed = runtime.block_on(setup_detailed_cache(Some(3)));
+    
+    let data_bytes = |cache: &SqliteCache| runtime.block_on(cache.stats()).unwrap().data_bytes.unwrap_or(0);
+    let (plain_bytes, compressed_bytes) = (data_bytes(&plain), data_bytes(&compressed));
+    println!(
+        "game_data of 100 detailed entries: {} bytes plain, {} bytes zstd ({:.0}% smaller)",
+        plain_bytes,
+        compressed_bytes,
+        100.0 * (1.0 - compressed_bytes as f64 / plain_bytes.max(1) as f64)
+    );
+    
+    let mut group = c.benchmark_group("cache_get_detailed");
+    group.bench_function("plain", |b| {
+        b.to_async(&runtime).iter(|| async { black_box(plain.get("query50").await.unwrap()) });
+    });
+    group.bench_function("zstd", |b| {
+        b.to_async(&runtime).iter(|| async { black_box(compressed.get("query50").await.unwrap()) });
+    });
+    group.finish();
+}
+
+#[cfg(not(feature = "compression"))]
+fn bench_compression(_c: &mut Criterion) {}
+
 criterion_group!(
     benches,
     bench_cache_get,
     bench_cache_save,
     bench_cache_increment,
-    bench_game_result_serialization
+    bench_game_result_serialization,
+    bench_compression
 );
 criterion_main!(benches);
@@ -0,0 +1,59 @@
+//! Acc@1 of each ranker and ensemble mode on the targeted Steam dataset
+//! (ground truth plus 100 random titles per query), to pick a ranker
+//!
+//! Usage: cargo bench --bench ranking_accuracy [-- max_queries]
+
+use delta_s3::eval::{self, Dataset, EvalOptions};
+use kissbot_game_engine::{
+    core::GameResult,
+    ranking::{DeltaRanker, EnsembleMode, EnsembleRanker, Ranker, RapidfuzzRanker},
+};
+use tokio::runtime::Runtime;
+
+fn main() {
+    let max_queries = std::env::args().skip(1).find_map(|arg| arg.parse().ok());
+
+    let dataset = match Dataset::load(eval::TARGETED_DATASET) {
+        Ok(dataset) => dataset,
+        Err(e) => {
+            eprintln!("❌ Failed to load {}: {}", eval::TARGETED_DATASET, e);
+            std::process::exit(1);
+        }
+    };
+    println!("✅ Loaded {} titles, {} queries", dataset.titles.len(), dataset.queries.len());
+
+    let runtime = Runtime::new().unwrap();
+    let options = EvalOptions { ks: vec![1], max_queries, ..Default::default() };
+    let rankers: Vec<(&str, Box<dyn Ranker>)> = vec![
+        ("rapidfuzz", Box::new(RapidfuzzRanker::new())),
+        ("delta", Box::new(DeltaRanker::new())),
+        ("ensemble weighted", Box::new(EnsembleRanker::rapidfuzz_delta(EnsembleMode::default()))),
+        ("ensemble max", Box::new(EnsembleRanker::rapidfuzz_delta(EnsembleMode::Max))),
+        ("ensemble rrf", Box::new(EnsembleRanker::rapidfuzz_delta(EnsembleMode::Rrf))),
+    ];
+
+    println!("\n{:<20} {:>8} {:>8} {:>10}", "Ranker", "Acc@1", "MRR", "Queries/s");
+    for (name, ranker) in &rankers {
+        let rank = |query: &str, titles: &[String]| -> Vec<(usize, f64)> {
+            // Candidate index as id, to map the ranking back to titles
+            let candidates: Vec<GameResult> = titles
+                .iter()
+                .enumerate()
+                .map(|(idx, title)| GameResult::new("eval", idx.to_string(), title.as_str()))
+                .collect();
+            let ranked = runtime.block_on(ranker.rank(query, &candidates)).unwrap_or_default();
+            ranked
+                .into_iter()
+                .filter_map(|c| Some((c.game.id.parse().ok()?, c.score)))
+                .collect()
+        };
+        let report = eval::evaluate(rank, &dataset, &options);
+        println!(
+            "{:<20} {:>7.2}% {:>8.4} {:>10.0}",
+            name,
+            report.acc_at(1).unwrap_or(0.0) * 100.0,
+            report.mrr,
+            report.throughput(),
+        );
+    }
+}
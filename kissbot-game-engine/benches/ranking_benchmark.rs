@@ -16,6 +16,7 @@ fn create_test_candidates(count: usize) -> Vec<GameResult> {
 }
 
 fn bench_rapidfuzz_ranking(c: &mut Criterion) {
+    let runtime = tokio::runtime::Runtime::new().unwrap();
     let ranker = RapidfuzzRanker::new();
     
     let candidates_10 = create_test_candidates(10);
@@ -23,20 +24,20 @@ fn bench_rapidfuzz_ranking(c: &mut Criterion) {
     let candidates_100 = create_test_candidates(100);
     
     c.bench_function("rapidfuzz_rank_10", |b| {
-        b.iter(|| {
-            black_box(ranker.rank("test game 5", &candidates_10).unwrap())
+        b.to_async(&runtime).iter(|| async {
+            black_box(ranker.rank("test game 5", &candidates_10).await.unwrap())
         });
     });
     
     c.bench_function("rapidfuzz_rank_50", |b| {
-        b.iter(|| {
-            black_box(ranker.rank("test game 25", &candidates_50).unwrap())
+        b.to_async(&runtime).iter(|| async {
+            black_box(ranker.rank("test game 25", &candidates_50).await.unwrap())
         });
     });
     
     c.bench_function("rapidfuzz_rank_100", |b| {
-        b.iter(|| {
-            black_box(ranker.rank("test game 50", &candidates_100).unwrap())
+        b.to_async(&runtime).iter(|| async {
+            black_box(ranker.rank("test game 50", &candidates_100).await.unwrap())
         });
     });
 }
@@ -0,0 +1,235 @@
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+
+/// Provider coverage guardrail settings
+#[derive(Debug, Clone)]
+pub struct GuardrailConfig {
+    /// Rolling window the rates are computed over
+    pub window: Duration,
+    /// How long thresholds must stay exceeded before flagging the provider
+    pub sustain: Duration,
+    /// Minimum calls in the window before rates are trusted
+    pub min_samples: usize,
+    /// Fraction of calls returning zero candidates above which a provider is suspect
+    pub max_zero_rate: f64,
+    /// Fraction of failed calls above which a provider is suspect
+    pub max_error_rate: f64,
+    /// Calls kept per provider (oldest dropped first)
+    pub max_samples: usize,
+}
+
+impl Default for GuardrailConfig {
+    fn default() -> Self {
+        Self {
+            window: Duration::minutes(15),
+            sustain: Duration::minutes(5),
+            min_samples: 20,
+            max_zero_rate: 0.9,
+            max_error_rate: 0.5,
+            max_samples: 1000,
+        }
+    }
+}
+
+/// Outcome of one provider call
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProviderOutcome {
+    Results,
+    Empty,
+    Error,
+}
+
+/// Provider flagged by the guardrails
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DegradedProvider {
+    pub provider: String,
+    /// Calls in the current window
+    pub samples: usize,
+    pub zero_rate: f64,
+    pub error_rate: f64,
+    /// When thresholds started being exceeded
+    pub since: DateTime<Utc>,
+}
+
+#[derive(Debug, Default)]
+struct ProviderWindow {
+    calls: VecDeque<(DateTime<Utc>, ProviderOutcome)>,
+    breaching_since: Option<DateTime<Utc>>,
+    degraded: bool,
+}
+
+impl ProviderWindow {
+    fn rates(&self) -> (f64, f64) {
+        let total = self.calls.len().max(1) as f64;
+        let count = |o: ProviderOutcome| self.calls.iter().filter(|(_, c)| *c == o).count() as f64;
+        (count(ProviderOutcome::Empty) / total, count(ProviderOutcome::Error) / total)
+    }
+}
+
+/// Per-provider rolling zero-result and error rates. A provider exceeding
+/// either threshold for `sustain` is flagged degraded until it recovers.
+pub struct ProviderGuardrails {
+    config: GuardrailConfig,
+    providers: Mutex<HashMap<String, ProviderWindow>>,
+}
+
+impl ProviderGuardrails {
+    pub fn new(config: GuardrailConfig) -> Self {
+        Self {
+            config,
+            providers: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn config(&self) -> &GuardrailConfig {
+        &self.config
+    }
+
+    /// Record a provider call
+    pub fn record(&self, provider: &str, outcome: ProviderOutcome) {
+        self.record_at(provider, outcome, Utc::now());
+    }
+
+    pub fn record_at(&self, provider: &str, outcome: ProviderOutcome, at: DateTime<Utc>) {
+        let mut providers = self.providers.lock().unwrap_or_else(|e| e.into_inner());
+        let window = providers.entry(provider.to_string()).or_default();
+
+        window.calls.push_back((at, outcome));
+        let horizon = at - self.config.window;
+        while matches!(window.calls.front(), Some((t, _)) if *t < horizon) {
+            window.calls.pop_front();
+        }
+        while window.calls.len() > self.config.max_samples.max(1) {
+            window.calls.pop_front();
+        }
+
+        let (zero_rate, error_rate) = window.rates();
+        let breaching = window.calls.len() >= self.config.min_samples
+            && (zero_rate > self.config.max_zero_rate || error_rate > self.config.max_error_rate);
+
+        if !breaching {
+            window.breaching_since = None;
+            if window.degraded {
+                window.degraded = false;
+                tracing::info!("✅ Provider {} recovered", provider);
+            }
+            return;
+        }
+
+        let since = *window.breaching_since.get_or_insert(at);
+        if !window.degraded && at - since >= self.config.sustain {
+            window.degraded = true;
+            tracing::warn!(
+                "🚨 Provider {} degraded: {:.0}% empty, {:.0}% errors over the last {} calls since {} \
+                 (expired API key or changed endpoint?)",
+                provider,
+                zero_rate * 100.0,
+                error_rate * 100.0,
+                window.calls.len(),
+                since.to_rfc3339()
+            );
+        }
+    }
+
+    /// Providers currently flagged, sorted by name
+    pub fn degraded(&self) -> Vec<DegradedProvider> {
+        let providers = self.providers.lock().unwrap_or_else(|e| e.into_inner());
+        let mut degraded: Vec<DegradedProvider> = providers
+            .iter()
+            .filter(|(_, w)| w.degraded)
+            .map(|(name, w)| {
+                let (zero_rate, error_rate) = w.rates();
+                DegradedProvider {
+                    provider: name.clone(),
+                    samples: w.calls.len(),
+                    zero_rate,
+                    error_rate,
+                    since: w.breaching_since.unwrap_or_else(Utc::now),
+                }
+            })
+            .collect();
+        degraded.sort_by(|a, b| a.provider.cmp(&b.provider));
+        degraded
+    }
+
+    pub fn is_degraded(&self, provider: &str) -> bool {
+        self.providers
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(provider)
+            .map(|w| w.degraded)
+            .unwrap_or(false)
+    }
+}
+
+impl Default for ProviderGuardrails {
+    fn default() -> Self {
+        Self::new(GuardrailConfig::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn guardrails() -> ProviderGuardrails {
+        ProviderGuardrails::new(GuardrailConfig {
+            window: Duration::minutes(10),
+            sustain: Duration::minutes(2),
+            min_samples: 5,
+            ..Default::default()
+        })
+    }
+
+    fn t(secs: i64) -> DateTime<Utc> {
+        DateTime::from_timestamp(1_700_000_000 + secs, 0).unwrap()
+    }
+
+    #[test]
+    fn test_sustained_zero_results_flag_provider() {
+        let g = guardrails();
+        for i in 0..10 {
+            g.record_at("steam", ProviderOutcome::Results, t(i));
+        }
+        assert!(g.degraded().is_empty());
+
+        // Recent window turns all-empty: breaching but not yet sustained
+        for i in 0..60 {
+            g.record_at("steam", ProviderOutcome::Empty, t(600 + i));
+        }
+        assert!(!g.is_degraded("steam"));
+
+        g.record_at("steam", ProviderOutcome::Empty, t(780));
+        let degraded = g.degraded();
+        assert_eq!(degraded.len(), 1);
+        assert_eq!(degraded[0].provider, "steam");
+        assert!(degraded[0].zero_rate > 0.9);
+    }
+
+    #[test]
+    fn test_errors_flag_and_recovery_clears() {
+        let g = guardrails();
+        for i in 0..20 {
+            g.record_at("igdb", ProviderOutcome::Error, t(i * 10));
+        }
+        assert!(g.is_degraded("igdb"));
+
+        // Healthy calls bring the error rate back under the threshold
+        for i in 0..20 {
+            g.record_at("igdb", ProviderOutcome::Results, t(200 + i));
+        }
+        assert!(!g.is_degraded("igdb"));
+        assert!(g.degraded().is_empty());
+    }
+
+    #[test]
+    fn test_min_samples_required() {
+        let g = guardrails();
+        for i in 0..4 {
+            g.record_at("rawg", ProviderOutcome::Error, t(i * 100));
+        }
+        assert!(!g.is_degraded("rawg"));
+    }
+}
@@ -0,0 +1,9 @@
+pub mod guardrails;
+pub mod query_log;
+pub mod recent;
+pub mod trending;
+
+pub use guardrails::{DegradedProvider, GuardrailConfig, ProviderGuardrails, ProviderOutcome};
+pub use query_log::{QueryLog, SearchLogEntry, QUERY_LOG_CAPACITY};
+pub use recent::{RecentSearches, SearchSummary, RECENT_SEARCHES_CAPACITY};
+pub use trending::{TrendingConfig, TrendingDetector, TrendingQuery};
@@ -0,0 +1,154 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use tokio::sync::{mpsc, oneshot};
+
+use crate::cache::GameCache;
+use crate::tasks::TaskCounter;
+
+/// Searches buffered before new ones are dropped
+pub const QUERY_LOG_CAPACITY: usize = 1024;
+
+/// Max entries written per batch by the background writer
+const BATCH_SIZE: usize = 64;
+
+/// One logged `GameEngine::search` call
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SearchLogEntry {
+    /// Query as typed by the viewer
+    pub query: String,
+    /// Name of the returned game (None when nothing was found)
+    pub resolved_name: Option<String>,
+    pub score: Option<f64>,
+    /// Provider of the returned game
+    pub provider: Option<String>,
+    pub from_cache: bool,
+    pub latency_ms: f64,
+    pub timestamp: DateTime<Utc>,
+}
+
+enum Message {
+    Entry(SearchLogEntry),
+    Flush(oneshot::Sender<()>),
+}
+
+/// Asynchronous search log: entries go through a bounded channel to a
+/// background task writing them to the cache in batches, so logging never
+/// waits on the database. The task stops once the log is dropped.
+pub struct QueryLog {
+    sender: mpsc::Sender<Message>,
+    dropped: AtomicU64,
+}
+
+impl QueryLog {
+    /// Spawn the writer task on the current tokio runtime, counted in `tasks`
+    pub fn spawn(cache: Arc<dyn GameCache>, capacity: usize, tasks: &TaskCounter) -> Self {
+        let (sender, receiver) = mpsc::channel(capacity.max(1));
+        tasks.spawn(write_loop(cache, receiver));
+
+        Self {
+            sender,
+            dropped: AtomicU64::new(0),
+        }
+    }
+
+    /// Queue an entry, dropping it when the writer is behind
+    pub fn record(&self, entry: SearchLogEntry) {
+        if self.sender.try_send(Message::Entry(entry)).is_err() {
+            let dropped = self.dropped.fetch_add(1, Ordering::Relaxed) + 1;
+            if dropped.is_power_of_two() {
+                tracing::warn!("⚠️ Query log writer behind, {} entries dropped so far", dropped);
+            }
+        }
+    }
+
+    /// Wait until every entry queued so far has been written
+    pub async fn flush(&self) {
+        let (ack, done) = oneshot::channel();
+        if self.sender.send(Message::Flush(ack)).await.is_ok() {
+            let _ = done.await;
+        }
+    }
+
+    /// Entries dropped because the channel was full
+    pub fn dropped(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+}
+
+async fn write_loop(cache: Arc<dyn GameCache>, mut receiver: mpsc::Receiver<Message>) {
+    let mut batch = Vec::with_capacity(BATCH_SIZE);
+
+    while let Some(message) = receiver.recv().await {
+        let mut flushes = Vec::new();
+        let mut next = Some(message);
+        while let Some(message) = next {
+            match message {
+                Message::Entry(entry) => batch.push(entry),
+                Message::Flush(ack) => flushes.push(ack),
+            }
+            next = if batch.len() < BATCH_SIZE { receiver.try_recv().ok() } else { None };
+        }
+
+        if !batch.is_empty() {
+            if let Err(e) = cache.log_searches(&batch).await {
+                tracing::warn!("Failed to write {} query log entries: {}", batch.len(), e.diagnostic());
+            }
+            batch.clear();
+        }
+        for ack in flushes {
+            let _ = ack.send(());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cache::SqliteCache;
+
+    fn entry(query: &str) -> SearchLogEntry {
+        SearchLogEntry {
+            query: query.to_string(),
+            resolved_name: None,
+            score: None,
+            provider: None,
+            from_cache: false,
+            latency_ms: 1.0,
+            timestamp: Utc::now(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_entries_written_in_background() {
+        let cache = Arc::new(SqliteCache::new(":memory:").await.unwrap());
+        let tasks = TaskCounter::default();
+        let log = QueryLog::spawn(cache.clone(), 16, &tasks);
+        assert_eq!(tasks.count(), 1);
+
+        for i in 0..100 {
+            // Let the writer keep up with the small channel
+            if i % 10 == 0 {
+                log.flush().await;
+            }
+            log.record(entry(&format!("q{}", i)));
+        }
+        log.flush().await;
+
+        assert_eq!(log.dropped(), 0);
+        let recent = cache.recent_searches(200).await.unwrap();
+        assert_eq!(recent.len(), 100);
+        assert_eq!(recent[0].query, "q99");
+
+        // The writer stops with the log
+        drop(log);
+        for _ in 0..100 {
+            if tasks.count() == 0 {
+                break;
+            }
+            tokio::task::yield_now().await;
+        }
+        assert_eq!(tasks.count(), 0);
+    }
+}
@@ -0,0 +1,110 @@
+use chrono::{DateTime, Utc};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+use crate::core::SearchResponse;
+
+/// Searches kept by default (in memory only)
+pub const RECENT_SEARCHES_CAPACITY: usize = 100;
+
+/// What a search returned, for live debugging
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SearchSummary {
+    /// Query as typed
+    pub query: String,
+    /// Winning game name
+    pub winner: String,
+    pub score: f64,
+    pub provider: String,
+    pub latency_ms: f64,
+    pub from_cache: bool,
+    pub timestamp: DateTime<Utc>,
+}
+
+impl SearchSummary {
+    pub fn new(query: impl Into<String>, response: &SearchResponse) -> Self {
+        Self {
+            query: query.into(),
+            winner: response.game.name.clone(),
+            score: response.score,
+            provider: response.provider.clone(),
+            latency_ms: response.latency_ms,
+            from_cache: response.from_cache,
+            timestamp: Utc::now(),
+        }
+    }
+}
+
+/// Bounded ring buffer of the latest search summaries (oldest evicted first)
+#[derive(Debug)]
+pub struct RecentSearches {
+    capacity: usize,
+    entries: Mutex<VecDeque<SearchSummary>>,
+}
+
+impl RecentSearches {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            entries: Mutex::new(VecDeque::with_capacity(capacity)),
+        }
+    }
+
+    pub fn record(&self, summary: SearchSummary) {
+        if self.capacity == 0 {
+            return;
+        }
+        let mut entries = self.entries.lock();
+        if entries.len() == self.capacity {
+            entries.pop_front();
+        }
+        entries.push_back(summary);
+    }
+
+    /// Latest `n` summaries, newest first
+    pub fn last(&self, n: usize) -> Vec<SearchSummary> {
+        self.entries.lock().iter().rev().take(n).cloned().collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.lock().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl Default for RecentSearches {
+    fn default() -> Self {
+        Self::new(RECENT_SEARCHES_CAPACITY)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{GameResult, SearchResultType};
+
+    fn summary(query: &str) -> SearchSummary {
+        let response = SearchResponse::new(GameResult::new("steam", "1", "Hades"), 90.0, SearchResultType::Fuzzy, false, 3.0);
+        SearchSummary::new(query, &response)
+    }
+
+    #[test]
+    fn test_ring_buffer_evicts_oldest() {
+        let recent = RecentSearches::new(3);
+        for query in ["a", "b", "c", "d"] {
+            recent.record(summary(query));
+        }
+
+        let queries: Vec<String> = recent.last(10).into_iter().map(|s| s.query).collect();
+        assert_eq!(queries, vec!["d", "c", "b"]);
+        assert_eq!(recent.last(1)[0].winner, "Hades");
+
+        let disabled = RecentSearches::new(0);
+        disabled.record(summary("a"));
+        assert!(disabled.last(10).is_empty());
+    }
+}
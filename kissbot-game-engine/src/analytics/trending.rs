@@ -0,0 +1,272 @@
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+
+/// Trending detector settings
+#[derive(Debug, Clone)]
+pub struct TrendingConfig {
+    /// Recent window compared against the baseline
+    pub window: Duration,
+    /// Counter granularity (window must be a multiple of it)
+    pub bucket: Duration,
+    /// Trailing baseline length, in windows
+    pub baseline_windows: u32,
+    /// Recent/baseline ratio above which a query is trending
+    pub threshold: f64,
+    /// Minimum requests in the recent window to be considered
+    pub min_count: u64,
+    /// Maximum number of tracked queries
+    pub capacity: usize,
+}
+
+impl Default for TrendingConfig {
+    fn default() -> Self {
+        Self {
+            window: Duration::minutes(60),
+            bucket: Duration::minutes(5),
+            baseline_windows: 6,
+            threshold: 3.0,
+            min_count: 10,
+            capacity: 1000,
+        }
+    }
+}
+
+/// Query flagged as trending
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrendingQuery {
+    pub query: String,
+    /// Requests in the recent window
+    pub recent_count: u64,
+    /// Average requests per window over the trailing baseline
+    pub baseline: f64,
+    /// recent_count / baseline (baseline floored at 1)
+    pub ratio: f64,
+}
+
+/// Per-query request counts, one entry per non-empty bucket (oldest first)
+#[derive(Debug, Default)]
+struct QueryCounter {
+    buckets: VecDeque<(i64, u64)>,
+}
+
+impl QueryCounter {
+    fn record(&mut self, bucket: i64) {
+        match self.buckets.back_mut() {
+            Some((last, count)) if *last == bucket => *count += 1,
+            _ => self.buckets.push_back((bucket, 1)),
+        }
+    }
+
+    fn prune(&mut self, oldest: i64) {
+        while matches!(self.buckets.front(), Some((b, _)) if *b < oldest) {
+            self.buckets.pop_front();
+        }
+    }
+
+    fn total(&self) -> u64 {
+        self.buckets.iter().map(|(_, c)| c).sum()
+    }
+}
+
+/// Windowed request counter flagging queries whose recent rate exceeds
+/// a multiple of their trailing baseline. Memory is capped at
+/// `capacity` queries, evicting the least requested one.
+pub struct TrendingDetector {
+    config: TrendingConfig,
+    counters: Mutex<HashMap<String, QueryCounter>>,
+}
+
+impl TrendingDetector {
+    pub fn new(config: TrendingConfig) -> Self {
+        Self {
+            config,
+            counters: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn config(&self) -> &TrendingConfig {
+        &self.config
+    }
+
+    fn bucket_secs(&self) -> i64 {
+        self.config.bucket.num_seconds().max(1)
+    }
+
+    fn window_buckets(&self) -> i64 {
+        (self.config.window.num_seconds() / self.bucket_secs()).max(1)
+    }
+
+    fn bucket_of(&self, at: DateTime<Utc>) -> i64 {
+        at.timestamp().div_euclid(self.bucket_secs())
+    }
+
+    /// First bucket still inside recent window + baseline
+    fn horizon(&self, current: i64) -> i64 {
+        current - self.window_buckets() * (1 + self.config.baseline_windows as i64) + 1
+    }
+
+    /// Record a request for a (normalized) query
+    pub fn record(&self, query: &str) {
+        self.record_at(query, Utc::now());
+    }
+
+    pub fn record_at(&self, query: &str, at: DateTime<Utc>) {
+        let bucket = self.bucket_of(at);
+        let horizon = self.horizon(bucket);
+        let mut counters = self.counters.lock().unwrap_or_else(|e| e.into_inner());
+
+        if !counters.contains_key(query) && counters.len() >= self.config.capacity {
+            // Drop aged-out queries first, then the least requested one
+            counters.retain(|_, counter| {
+                counter.prune(horizon);
+                !counter.buckets.is_empty()
+            });
+            if counters.len() >= self.config.capacity {
+                let coldest = counters
+                    .iter()
+                    .min_by_key(|(_, counter)| counter.total())
+                    .map(|(q, _)| q.clone());
+                if let Some(coldest) = coldest {
+                    counters.remove(&coldest);
+                }
+            }
+        }
+
+        if self.config.capacity == 0 {
+            return;
+        }
+
+        let counter = counters.entry(query.to_string()).or_default();
+        counter.prune(horizon);
+        counter.record(bucket);
+    }
+
+    /// Currently trending queries, highest ratio first
+    pub fn trending(&self, limit: usize) -> Vec<TrendingQuery> {
+        self.trending_at(Utc::now(), limit)
+    }
+
+    pub fn trending_at(&self, now: DateTime<Utc>, limit: usize) -> Vec<TrendingQuery> {
+        let current = self.bucket_of(now);
+        let horizon = self.horizon(current);
+        let recent_start = current - self.window_buckets() + 1;
+        let counters = self.counters.lock().unwrap_or_else(|e| e.into_inner());
+
+        let mut trending: Vec<TrendingQuery> = counters
+            .iter()
+            .filter_map(|(query, counter)| {
+                let (mut recent, mut older) = (0u64, 0u64);
+                for &(bucket, count) in &counter.buckets {
+                    if bucket >= recent_start && bucket <= current {
+                        recent += count;
+                    } else if bucket >= horizon && bucket < recent_start {
+                        older += count;
+                    }
+                }
+
+                let baseline = older as f64 / self.config.baseline_windows.max(1) as f64;
+                let ratio = recent as f64 / baseline.max(1.0);
+                (recent >= self.config.min_count && ratio >= self.config.threshold).then(|| TrendingQuery {
+                    query: query.clone(),
+                    recent_count: recent,
+                    baseline,
+                    ratio,
+                })
+            })
+            .collect();
+
+        trending.sort_by(|a, b| {
+            b.ratio
+                .partial_cmp(&a.ratio)
+                .unwrap_or(std::cmp::Ordering::Equal)
+                .then(b.recent_count.cmp(&a.recent_count))
+                .then(a.query.cmp(&b.query))
+        });
+        trending.truncate(limit);
+        trending
+    }
+
+    /// Number of tracked queries
+    pub fn len(&self) -> usize {
+        self.counters.lock().unwrap_or_else(|e| e.into_inner()).len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl Default for TrendingDetector {
+    fn default() -> Self {
+        Self::new(TrendingConfig::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn start() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2025, 11, 20, 12, 0, 0).unwrap()
+    }
+
+    /// Steady background traffic: each query asked every 5 minutes for `hours`
+    fn noise(detector: &TrendingDetector, from: DateTime<Utc>, hours: i64) {
+        for step in 0..hours * 12 {
+            let at = from + Duration::minutes(step * 5);
+            for q in ["zelda", "hades", "celeste", "balatro"] {
+                detector.record_at(q, at);
+            }
+        }
+    }
+
+    #[test]
+    fn test_burst_flagged_then_ages_out() {
+        let detector = TrendingDetector::default();
+        noise(&detector, start(), 7);
+
+        // Launch burst: 200 requests over the last 10 minutes
+        let now = start() + Duration::hours(7);
+        for i in 0..200 {
+            detector.record_at("silksong", now - Duration::seconds(i * 3));
+        }
+
+        let trending = detector.trending_at(now, 10);
+        assert_eq!(trending.len(), 1);
+        assert_eq!(trending[0].query, "silksong");
+        assert_eq!(trending[0].recent_count, 200);
+
+        // An hour later the burst left the recent window
+        let later = now + Duration::minutes(65);
+        assert!(detector.trending_at(later, 10).is_empty());
+    }
+
+    #[test]
+    fn test_steady_traffic_not_trending() {
+        let detector = TrendingDetector::default();
+        noise(&detector, start(), 7);
+        assert!(detector.trending_at(start() + Duration::hours(7), 10).is_empty());
+    }
+
+    #[test]
+    fn test_capacity_bound() {
+        let detector = TrendingDetector::new(TrendingConfig {
+            capacity: 10,
+            ..Default::default()
+        });
+
+        // Hot query survives eviction of one-off queries
+        for _ in 0..20 {
+            detector.record_at("hot", start());
+        }
+        for i in 0..100 {
+            detector.record_at(&format!("q{}", i), start());
+        }
+
+        assert_eq!(detector.len(), 10);
+        assert_eq!(detector.trending_at(start(), 10)[0].query, "hot");
+    }
+}
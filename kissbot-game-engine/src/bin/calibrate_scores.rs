@@ -0,0 +1,167 @@
+//! Derives the score calibration tables of `ranking::calibration` from a
+//! Steam evaluation dataset: raw scores of each query's ground truth and
+//! of sampled distractors, per ranker, turned into `ScoreScale` constants
+//! to paste over the current ones. DRAKON is included when it answers.
+//!
+//! ```text
+//! cargo run --release --features cli --bin calibrate-scores -- ../delta-s3/datasets/steam_games_targeted.json
+//! ```
+
+use anyhow::Context;
+use clap::Parser;
+use delta_s3::eval::{self, Candidates, Dataset, EvalOptions};
+use kissbot_game_engine::{
+    engine::DEFAULT_DRAKON_URL,
+    ranking::{
+        calibrate,
+        calibration::{derive_scale, quantile, BAD_MATCH_QUANTILE, GOOD_MATCH_QUANTILE},
+        DeltaRanker, DrakonRanker, Ranker, RapidfuzzRanker,
+    },
+    BuildInfo, GameResult,
+};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use tokio::runtime::Runtime;
+
+#[derive(Parser)]
+#[command(name = "calibrate-scores")]
+#[command(about = "Derives per-ranker score calibration tables from a Steam evaluation dataset", long_about = None)]
+#[command(disable_version_flag = true)]
+struct Args {
+    /// Print version, git hash and build date
+    #[arg(short = 'V', long)]
+    version: bool,
+
+    /// With --version: print build info as JSON
+    #[arg(long, requires = "version")]
+    json: bool,
+
+    /// Evaluation dataset (titles, and queries with their ground truth)
+    #[arg(default_value = eval::TARGETED_DATASET)]
+    dataset: PathBuf,
+
+    /// Use only the first n queries
+    #[arg(long)]
+    max_queries: Option<usize>,
+
+    /// Random titles scored next to the ground truth of each query
+    #[arg(long, default_value = "100")]
+    distractors: usize,
+
+    #[arg(long, default_value = "42")]
+    seed: u64,
+
+    /// DRAKON service, calibrated if its health check answers
+    #[arg(long, default_value = DEFAULT_DRAKON_URL)]
+    drakon_url: String,
+
+    /// Leave DRAKON out even when reachable
+    #[arg(long)]
+    no_drakon: bool,
+}
+
+/// Raw scores of ground truths and of distractors
+#[derive(Default)]
+struct Samples {
+    positives: Vec<f64>,
+    negatives: Vec<f64>,
+}
+
+fn sample(runtime: &Runtime, ranker: &dyn Ranker, dataset: &Dataset, options: &EvalOptions) -> Samples {
+    let truths: HashMap<&str, &str> =
+        dataset.queries.iter().map(|q| (q.query.as_str(), q.ground_truth.as_str())).collect();
+    let samples = Mutex::new(Samples::default());
+
+    let rank = |query: &str, titles: &[String]| -> Vec<(usize, f64)> {
+        let candidates: Vec<GameResult> = titles
+            .iter()
+            .enumerate()
+            .map(|(idx, title)| GameResult::new("eval", idx.to_string(), title.as_str()))
+            .collect();
+        let Ok(ranked) = runtime.block_on(ranker.rank(query, &candidates)) else {
+            return Vec::new();
+        };
+        let scores: Vec<(usize, f64)> =
+            ranked.into_iter().filter_map(|c| Some((c.game.id.parse().ok()?, c.score))).collect();
+
+        let truth = truths.get(query).copied();
+        let mut samples = samples.lock().unwrap_or_else(|e| e.into_inner());
+        for &(idx, score) in &scores {
+            if Some(titles[idx].as_str()) == truth {
+                samples.positives.push(score);
+            } else {
+                samples.negatives.push(score);
+            }
+        }
+        scores
+    };
+    eval::evaluate(rank, dataset, options);
+
+    samples.into_inner().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Raw score quantiles, to judge how well a ranker separates both sets
+fn spread(scores: &[f64]) -> String {
+    [0.1, 0.25, 0.5, 0.75, 0.99]
+        .iter()
+        .map(|&q| format!("p{:.0} {:.1}", q * 100.0, quantile(scores, q).unwrap_or(0.0)))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// `ScoreScale` constant declaration of `points`
+fn constant(name: &str, points: &[(f64, f64)]) -> String {
+    let points: String = points.iter().map(|(raw, calibrated)| format!("    ({:.2}, {:.1}),\n", raw, calibrated)).collect();
+    format!("pub const {}: ScoreScale = ScoreScale::new(&[\n{}]);", name, points)
+}
+
+fn main() -> anyhow::Result<()> {
+    let args = Args::parse();
+    if args.version {
+        let info = BuildInfo::new("calibrate-scores");
+        println!("{}", if args.json { info.to_json() } else { info.version_line() });
+        return Ok(());
+    }
+
+    let dataset = Dataset::load(&args.dataset).with_context(|| format!("Failed to load {}", args.dataset.display()))?;
+    println!("✅ Loaded {} titles, {} queries", dataset.titles.len(), dataset.queries.len());
+
+    let runtime = Runtime::new()?;
+    let mut rankers: Vec<Box<dyn Ranker>> = vec![Box::new(RapidfuzzRanker::new()), Box::new(DeltaRanker::new())];
+    if !args.no_drakon {
+        match runtime.block_on(DrakonRanker::new(args.drakon_url.as_str())) {
+            Ok(drakon) => rankers.push(Box::new(drakon)),
+            Err(e) => println!("⚠️  DRAKON skipped ({}): {}", args.drakon_url, e),
+        }
+    }
+
+    let options = EvalOptions {
+        candidates: Candidates::Sampled { distractors: args.distractors, seed: args.seed },
+        max_queries: args.max_queries,
+        ..Default::default()
+    };
+    for ranker in &rankers {
+        println!("\n🔄 {}...", ranker.name());
+        let samples = sample(&runtime, ranker.as_ref(), &dataset, &options);
+        let (Some(good), Some(bad)) = (
+            quantile(&samples.positives, GOOD_MATCH_QUANTILE),
+            quantile(&samples.negatives, BAD_MATCH_QUANTILE),
+        ) else {
+            println!("⚠️  No scores");
+            continue;
+        };
+        println!("   {} ground truths: {}", samples.positives.len(), spread(&samples.positives));
+        println!("   {} distractors: {}", samples.negatives.len(), spread(&samples.negatives));
+        println!(
+            "   Current table: ground truth p{:.0} → {:.1}, distractor p{:.0} → {:.1}",
+            GOOD_MATCH_QUANTILE * 100.0,
+            calibrate(good, &ranker.score_scale()),
+            BAD_MATCH_QUANTILE * 100.0,
+            calibrate(bad, &ranker.score_scale()),
+        );
+        let name = format!("{}_SCALE", ranker.name().to_uppercase());
+        println!("{}", constant(&name, &derive_scale(&samples.positives, &samples.negatives)));
+    }
+    Ok(())
+}
@@ -1,17 +1,30 @@
-use clap::{Parser, Subcommand};
-use kissbot_game_engine::{GameEngine, SearchQuery, providers::SteamProvider};
-use std::sync::Arc;
+use clap::{CommandFactory, Parser, Subcommand};
+use kissbot_game_engine::{BuildInfo, CacheMode, EngineConfig, cache::CacheOrder, GameEngine, ReleaseStatus, SearchQuery, SearchRequestOptions, SearchResultType, analytics::{SearchSummary, TrendingQuery}, exit};
+use serde::Deserialize;
 
 #[derive(Parser)]
 #[command(name = "game-engine-cli")]
 #[command(about = "KissBot Game Engine CLI", long_about = None)]
+#[command(disable_version_flag = true)]
 struct Cli {
     #[command(subcommand)]
-    command: Commands,
+    command: Option<Commands>,
+    
+    /// Print version, git hash and build date
+    #[arg(short = 'V', long)]
+    version: bool,
+    
+    /// With --version: print build info as JSON
+    #[arg(long, requires = "version")]
+    json: bool,
     
     /// Database path
     #[arg(short, long, default_value = "kissbot.db")]
     db: String,
+    
+    /// Engine config file (.yaml, .toml or .json), replaces --db
+    #[arg(short, long)]
+    config: Option<String>,
 }
 
 #[derive(Subcommand)]
@@ -21,73 +34,449 @@ enum Commands {
         /// Search query
         query: String,
         
-        /// Maximum results
-        #[arg(short, long, default_value = "5")]
-        max_results: usize,
+        #[command(flatten)]
+        options: SearchRequestOptions,
         
-        /// Disable cache
-        #[arg(long)]
+        /// Disable cache (no read, no write), same as --cache-mode off
+        #[arg(long, conflicts_with_all = ["refresh", "cache_mode"])]
         no_cache: bool,
+        
+        /// Ignore the cached entry but update it with the fresh result,
+        /// same as --cache-mode bypass
+        #[arg(long, conflicts_with = "cache_mode")]
+        refresh: bool,
     },
     
     /// Get cache statistics
-    Stats,
+    Stats {
+        /// Show trending queries from a running server instead
+        #[arg(long)]
+        trending: bool,
+        
+        /// Server URL (trending counters live in the server process)
+        #[arg(long, default_value = "http://127.0.0.1:8090")]
+        server: String,
+        
+        /// Maximum trending queries to show
+        #[arg(short, long, default_value = "20")]
+        limit: usize,
+    },
     
-    /// Clean up old cache entries
+    /// Most requested queries
+    Top {
+        /// Number of queries to show
+        #[arg(short, long, default_value = "10")]
+        limit: usize,
+        
+        /// Only queries hit in the last N days
+        #[arg(long)]
+        days: Option<i64>,
+    },
+    
+    /// Latest logged searches (server started with LOG_QUERIES=1)
+    Recent {
+        /// Number of searches to show
+        #[arg(short, long, default_value = "20")]
+        limit: usize,
+        
+        /// Show a running server's in-memory recent searches instead
+        /// (no LOG_QUERIES needed)
+        #[arg(long)]
+        live: bool,
+        
+        /// Server URL (the in-memory buffer lives in the server process)
+        #[arg(long, default_value = "http://127.0.0.1:8090")]
+        server: String,
+    },
+    
+    /// Show the cache entry for a query and when it will be refreshed
+    Inspect {
+        /// Query as searched
+        query: String,
+        
+        /// Description language (e.g. fr)
+        #[arg(long)]
+        locale: Option<String>,
+        
+        /// Store region (e.g. FR)
+        #[arg(long)]
+        country: Option<String>,
+    },
+    
+    /// Clean up cache entries not searched recently and old search log
     Cleanup {
         /// Maximum age in days
         #[arg(short, long, default_value = "30")]
         max_age_days: i64,
+        
+        /// Age of an entry counted from when it was cached, not from its
+        /// last hit (entries searched every day go too)
+        #[arg(long)]
+        by_insert_age: bool,
+    },
+    
+    /// Fill the cache from a file of queries, one per line (blank lines
+    /// and lines starting with # are skipped)
+    Warm {
+        /// Query list
+        #[arg(short, long)]
+        file: String,
+        
+        /// Queries searched at the same time
+        #[arg(long, default_value = "4")]
+        concurrency: usize,
+    },
+    
+    /// Cache maintenance
+    Cache {
+        #[command(subcommand)]
+        command: CacheCommands,
+    },
+    
+    /// Provider tools
+    Provider {
+        #[command(subcommand)]
+        command: ProviderCommands,
     },
 }
 
+#[derive(Subcommand)]
+enum CacheCommands {
+    /// Delete the cached result of a query (every locale, country, provider
+    /// and platform variant), so the next search asks the providers again
+    Delete {
+        /// Query as searched, or a SQL LIKE pattern with --pattern
+        query: String,
+        
+        /// Delete every key matching QUERY as a LIKE pattern (e.g. 'hades%')
+        #[arg(long, conflicts_with = "live")]
+        pattern: bool,
+        
+        /// Delete through a running server instead (required with the
+        /// memory backend)
+        #[arg(long)]
+        live: bool,
+        
+        /// Server URL
+        #[arg(long, default_value = "http://127.0.0.1:8090")]
+        server: String,
+    },
+    
+    /// Check the cache database for corrupted or inconsistent entries
+    Doctor {
+        /// Delete the entries found (they are fetched again when searched)
+        #[arg(long)]
+        repair: bool,
+    },
+    
+    /// Rewrite cached entries compressed (cache.sqlite.compression_level
+    /// set) or as plain JSON (unset), after changing that setting
+    Recompress,
+    
+    /// List cached games, a page at a time
+    List {
+        /// Entries skipped before the page
+        #[arg(long, default_value = "0")]
+        offset: usize,
+        
+        /// Entries per page
+        #[arg(short, long, default_value = "20")]
+        limit: usize,
+        
+        /// newest, most_hit or alphabetical
+        #[arg(long, default_value = "newest")]
+        order: CacheOrder,
+    },
+}
+
+#[derive(Subcommand)]
+enum ProviderCommands {
+    /// Response schema drift detection
+    Schema {
+        #[command(subcommand)]
+        command: SchemaCommands,
+    },
+}
+
+#[derive(Subcommand)]
+enum SchemaCommands {
+    /// Compare a live response sample with the blessed baseline
+    Diff {
+        /// Provider name (e.g. steam)
+        provider: String,
+        
+        /// Store the live sample as the new baseline
+        #[arg(long)]
+        bless: bool,
+    },
+}
+
+#[derive(Deserialize)]
+struct TrendingResponse {
+    trending: Vec<TrendingQuery>,
+}
+
+async fn print_trending(server: &str, limit: usize) -> anyhow::Result<()> {
+    let url = format!("{}/v1/stats/trending?limit={}", server.trim_end_matches('/'), limit);
+    let response: TrendingResponse = reqwest::get(&url).await?.error_for_status()?.json().await?;
+    
+    if response.trending.is_empty() {
+        println!("📈 No trending queries");
+        return Ok(());
+    }
+    
+    println!("📈 Trending queries:");
+    for (i, t) in response.trending.iter().enumerate() {
+        println!(
+            "   {}. {} - {} req/window (baseline {:.1}, x{:.1})",
+            i + 1,
+            t.query,
+            t.recent_count,
+            t.baseline,
+            t.ratio
+        );
+    }
+    
+    Ok(())
+}
+
+#[derive(Deserialize)]
+struct InvalidateResponse {
+    deleted: u64,
+}
+
+/// `DELETE /v1/cache/:query` on a running server, number of entries deleted
+async fn delete_on_server(server: &str, query: &str) -> anyhow::Result<u64> {
+    let mut url = reqwest::Url::parse(server)?;
+    url.path_segments_mut()
+        .map_err(|_| anyhow::anyhow!("Invalid server URL: {}", server))?
+        .pop_if_empty()
+        .extend(["v1", "cache", query]);
+    
+    let response = reqwest::Client::new().delete(url).send().await?;
+    if response.status() == reqwest::StatusCode::NOT_FOUND {
+        return Ok(0);
+    }
+    let response: InvalidateResponse = response.error_for_status()?.json().await?;
+    Ok(response.deleted)
+}
+
+#[derive(Deserialize)]
+struct LastSearchesResponse {
+    searches: Vec<SearchSummary>,
+}
+
+async fn print_last_searches(server: &str, limit: usize) -> anyhow::Result<()> {
+    let url = format!("{}/v1/debug/recent?limit={}", server.trim_end_matches('/'), limit);
+    let response: LastSearchesResponse = reqwest::get(&url).await?.error_for_status()?.json().await?;
+    
+    if response.searches.is_empty() {
+        println!("🕘 No searches since the server started");
+        return Ok(());
+    }
+    
+    println!("🕘 Recent searches (live):");
+    for s in &response.searches {
+        println!(
+            "   {} {} → {} ({:.1}%, {}){} [{:.0}ms]",
+            s.timestamp.format("%H:%M:%S"),
+            s.query,
+            s.winner,
+            s.score,
+            s.provider,
+            if s.from_cache { " (cache)" } else { "" },
+            s.latency_ms
+        );
+    }
+    
+    Ok(())
+}
+
+/// Byte count in the largest unit keeping it at least 1
+fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KiB", "MiB", "GiB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} B", bytes)
+    } else {
+        format!("{:.1} {}", value, UNITS[unit])
+    }
+}
+
+/// Widest a table cell gets before it is cut with "…"
+const MAX_CELL_WIDTH: usize = 40;
+
+/// Rows under their headers in left-aligned columns as wide as their
+/// widest cell
+fn print_table(headers: &[&str], rows: &[Vec<String>]) {
+    let cut = |cell: &str| -> String {
+        if cell.chars().count() > MAX_CELL_WIDTH {
+            format!("{}…", cell.chars().take(MAX_CELL_WIDTH - 1).collect::<String>())
+        } else {
+            cell.to_string()
+        }
+    };
+    let headers: Vec<String> = headers.iter().map(|header| header.to_string()).collect();
+    let rows: Vec<Vec<String>> = rows.iter().map(|row| row.iter().map(|cell| cut(cell)).collect()).collect();
+    
+    let mut widths: Vec<usize> = headers.iter().map(|header| header.chars().count()).collect();
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+    
+    for row in std::iter::once(&headers).chain(&rows) {
+        let line: Vec<String> = row
+            .iter()
+            .zip(&widths)
+            .map(|(cell, width)| format!("{}{}", cell, " ".repeat(width - cell.chars().count())))
+            .collect();
+        println!("   {}", line.join("  ").trim_end());
+    }
+}
+
 #[tokio::main]
-async fn main() -> anyhow::Result<()> {
+async fn main() {
+    if let Err(e) = run().await {
+        let (code, message) = exit::report(&e);
+        eprintln!("❌ {}", message);
+        std::process::exit(code);
+    }
+}
+
+async fn run() -> anyhow::Result<()> {
     // Initialize tracing
     tracing_subscriber::fmt::init();
     
     let cli = Cli::parse();
     
-    // Create engine
-    let mut engine = GameEngine::new(&cli.db).await?;
+    if cli.version {
+        let info = BuildInfo::new("game-engine-cli");
+        println!("{}", if cli.json { info.to_json() } else { info.version_line() });
+        return Ok(());
+    }
     
-    // Add Steam provider
-    let steam = Arc::new(SteamProvider::new(None));
-    engine.add_provider(steam);
+    let Some(command) = cli.command else {
+        Cli::command().print_help()?;
+        std::process::exit(exit::ErrorCode::InvalidInput.exit_code());
+    };
     
-    match cli.command {
-        Commands::Search { query, max_results, no_cache } => {
+    if let Commands::Stats { trending: true, server, limit } = &command {
+        return print_trending(server, *limit).await;
+    }
+    if let Commands::Recent { live: true, server, limit } = &command {
+        return print_last_searches(server, *limit).await;
+    }
+    if let Commands::Cache { command: CacheCommands::Delete { query, live: true, server, .. } } = &command {
+        let deleted = delete_on_server(server, query).await?;
+        println!("🗑️  Deleted {} cache entries for '{}' on {}", deleted, query, server);
+        return Ok(());
+    }
+    
+    // Create engine from --config, or the defaults on --db
+    let config = match &cli.config {
+        Some(path) => EngineConfig::load(path)?,
+        None => {
+            let mut config = EngineConfig::default();
+            config.cache.path = cli.db.clone();
+            config
+        }
+    };
+    let engine = GameEngine::from_config(&config).await?;
+    
+    match command {
+        Commands::Search { query, options, no_cache, refresh } => {
             println!("🔍 Searching for: {}", query);
             
-            let search_query = SearchQuery {
-                query: query.clone(),
-                max_results,
-                use_cache: !no_cache,
+            let default_mode = match (no_cache, refresh) {
+                (true, _) => CacheMode::Off,
+                (_, true) => CacheMode::Bypass,
+                _ => CacheMode::Use,
             };
+            let search_query = options.to_query(query.as_str(), default_mode);
             
             let result = engine.search(search_query).await?;
             
-            println!("\n✅ Found: {}", result.game.name);
+            if result.result_type == SearchResultType::NotFound {
+                println!("\n❓ No good match, did you mean: {}?", result.game.name);
+            } else {
+                println!("\n✅ Found: {}", result.game.name);
+            }
             println!("   Score: {:.1}%", result.score);
             println!("   Provider: {}", result.provider);
             println!("   Year: {}", result.game.year.map(|y| y.to_string()).unwrap_or_else(|| "N/A".to_string()));
+            if let Some(status) = &result.game.release_status {
+                println!("   Status: {}", status.label());
+            }
+            if !result.game.platforms.is_empty() {
+                println!("   Platforms: {}", result.game.platforms.join(", "));
+            }
+            if result.platform_filter_ignored {
+                println!("   ⚠️  Not on the requested platforms (no match there)");
+            }
             println!("   URL: {}", result.game.url);
             println!("   Cached: {}", result.from_cache);
             println!("   Latency: {:.2}ms", result.latency_ms);
             
+            if options.explain {
+                match &result.score_breakdown {
+                    Some(breakdown) => {
+                        println!("\n🧮 Score breakdown ({}):", breakdown.ranker);
+                        println!("   Similarity: {:.1}", breakdown.similarity);
+                        if let (Some(query), Some(candidate)) = (&breakdown.normalized_query, &breakdown.normalized_candidate) {
+                            println!("   Compared: \"{}\" vs \"{}\"", query, candidate);
+                        }
+                        if let Some(alias) = &breakdown.matched_alias {
+                            println!("   Matched alias: {}", alias);
+                        }
+                        for penalty in &breakdown.penalties {
+                            println!("   Penalty {}: -{:.1}", penalty.reason, penalty.points);
+                        }
+                        for bonus in &breakdown.bonuses {
+                            println!("   Bonus {}: +{:.1}", bonus.reason, bonus.points);
+                        }
+                        println!("   Final: {:.1}", breakdown.score());
+                        println!("   Candidates considered: {}", breakdown.candidates);
+                        for quota in &breakdown.provider_quota {
+                            println!("   {} candidates: {} kept of {}", quota.provider, quota.kept, quota.returned);
+                        }
+                    }
+                    None => println!("\n🧮 No score breakdown (cached result)"),
+                }
+            }
+            
+            if !result.editions.is_empty() {
+                println!("\n📚 Editions:");
+                for edition in &result.editions {
+                    println!("   • {}", edition.name);
+                }
+            }
+            
             if !result.alternatives.is_empty() {
                 println!("\n📋 Alternatives:");
                 for (i, alt) in result.alternatives.iter().enumerate() {
-                    println!("   {}. {}", i + 1, alt.name);
+                    match &alt.release_status {
+                        Some(status) if *status != ReleaseStatus::Released => {
+                            println!("   {}. {} ({})", i + 1, alt.name, status.label())
+                        }
+                        _ => println!("   {}. {}", i + 1, alt.name),
+                    }
                 }
             }
         }
         
-        Commands::Stats => {
+        Commands::Stats { .. } => {
             let stats = engine.cache_stats().await?;
             
             println!("📊 Cache Statistics:");
             println!("   Total entries: {}", stats.total_entries);
+            println!("   No-result entries: {}", stats.no_result_entries);
             println!("   Total hits: {}", stats.total_hits);
             println!("   Avg hits/entry: {:.2}", stats.avg_hit_count);
             
@@ -98,14 +487,236 @@ async fn main() -> anyhow::Result<()> {
             if let Some(newest) = stats.newest_entry {
                 println!("   Newest entry: {}", newest.format("%Y-%m-%d %H:%M:%S"));
             }
+            println!("   Added: {} in 24h, {} in 7d", stats.added_last_24h, stats.added_last_7d);
+            let ages = stats.age_histogram;
+            println!(
+                "   By age: {} <1d, {} <7d, {} <30d, {} older",
+                ages.under_1d, ages.under_7d, ages.under_30d, ages.older
+            );
+            if let Some(bytes) = stats.data_bytes {
+                println!("   Game data: {}", format_bytes(bytes));
+            }
+            if let Some(bytes) = stats.file_bytes {
+                println!("   Database file: {}", format_bytes(bytes));
+            }
+            
+            if !stats.top_queries.is_empty() {
+                println!("🏆 Most hit:");
+                for (i, top) in stats.top_queries.iter().enumerate() {
+                    println!("   {}. {} - {} hits", i + 1, top.query, top.hit_count);
+                }
+            }
+        }
+        
+        Commands::Top { limit, days } => {
+            let since = days.map(|d| chrono::Utc::now() - chrono::Duration::days(d));
+            let top = engine.top_queries(limit, since).await?;
+            
+            match days {
+                Some(d) => println!("🏆 Top queries (last {} days):", d),
+                None => println!("🏆 Top queries:"),
+            }
+            for (i, (query, hits)) in top.iter().enumerate() {
+                println!("   {}. {} - {} hits", i + 1, query, hits);
+            }
+        }
+        
+        Commands::Recent { limit, .. } => {
+            let searches = engine.recent_searches(limit).await?;
+            
+            if searches.is_empty() {
+                println!("📜 No logged searches");
+            } else {
+                println!("📜 Recent searches:");
+            }
+            for s in &searches {
+                let resolved = match (&s.resolved_name, s.score) {
+                    (Some(name), Some(score)) => format!("{} ({:.1}%)", name, score),
+                    (Some(name), None) => name.clone(),
+                    _ => "no result".to_string(),
+                };
+                println!(
+                    "   {} {} → {}{} [{:.0}ms]",
+                    s.timestamp.format("%Y-%m-%d %H:%M:%S"),
+                    s.query,
+                    resolved,
+                    if s.from_cache { " (cache)" } else { "" },
+                    s.latency_ms
+                );
+            }
+        }
+        
+        Commands::Inspect { query, locale, country } => {
+            let search_query = SearchQuery { query, locale, country, ..Default::default() };
+            let Some(entry) = engine.inspect_cache(&search_query).await? else {
+                println!("🗃️  No cache entry for '{}'", search_query.cache_key());
+                return Ok(());
+            };
+            
+            println!("🗃️  {} → {}", entry.query, entry.game.display_name());
+            if let Some(score) = entry.score {
+                println!("   Score: {:.1}%", score);
+            }
+            println!("   Hits: {}", entry.hit_count);
+            println!("   Cached at: {}", entry.cached_at.format("%Y-%m-%d %H:%M:%S"));
+            match &entry.ttl {
+                Some(ttl) => {
+                    let due = if ttl.is_due(chrono::Utc::now()) { " (due)" } else { "" };
+                    println!("   Refresh after: {}{}", ttl.refresh_after.format("%Y-%m-%d %H:%M:%S"), due);
+                    println!("   TTL: {}", ttl.reason);
+                }
+                None => println!("   TTL: none (saved by an older version, never refreshed)"),
+            }
+        }
+        
+        Commands::Cleanup { max_age_days, by_insert_age } => {
+            let deleted = if by_insert_age {
+                println!("🧹 Cleaning up entries cached more than {} days ago...", max_age_days);
+                engine.cleanup_cache_by_insert_age(max_age_days).await?
+            } else {
+                println!("🧹 Cleaning up entries not searched for {} days...", max_age_days);
+                engine.cleanup_cache(max_age_days).await?
+            };
+            let logged = engine.cleanup_log(max_age_days).await?;
+            
+            println!("✅ Deleted {} entries and {} logged searches", deleted, logged);
+        }
+        
+        Commands::Warm { file, concurrency } => {
+            let content = std::fs::read_to_string(&file)?;
+            let queries: Vec<String> = content
+                .lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty() && !line.starts_with('#'))
+                .map(String::from)
+                .collect();
+            println!("🔥 Warming the cache with {} queries from {}...", queries.len(), file);
+            
+            let report = std::sync::Arc::new(engine).warm(queries, concurrency).await?;
+            for failure in &report.failures {
+                println!("   ❌ {}: {}", failure.query, failure.error);
+            }
+            println!(
+                "✅ {} queries: {} already cached, {} fetched, {} failed",
+                report.total, report.cached, report.fetched, report.failed
+            );
+        }
+        
+        Commands::Cache { command: CacheCommands::Delete { query, pattern, .. } } => {
+            let deleted = if pattern {
+                engine.invalidate_matching(&query).await?
+            } else {
+                engine.invalidate(&query).await?
+            };
+            
+            println!("🗑️  Deleted {} cache entries for '{}'", deleted, query);
+        }
+        
+        Commands::Cache { command: CacheCommands::Doctor { repair } } => {
+            let report = engine.check_cache(repair).await?;
+            
+            for message in &report.storage_errors {
+                println!("   💥 {}", message);
+            }
+            for (label, keys) in [
+                ("undecodable entry", &report.undecodable_entries),
+                ("bad timestamp", &report.bad_timestamps),
+                ("tombstone without expiry", &report.tombstones_without_expiry),
+                ("alias to a missing entry", &report.dangling_aliases),
+            ] {
+                for key in keys {
+                    println!("   ⚠️ {}: {}", label, key);
+                }
+            }
+            
+            if !report.storage_errors.is_empty() {
+                println!("❌ Database file is corrupted, restore it from a backup or delete it");
+            } else if report.problems() == 0 {
+                println!("✅ Cache is consistent");
+            } else if report.is_ok() {
+                println!("🔧 Repaired: deleted {} entries", report.repaired);
+            } else {
+                println!("⚠️ {} inconsistent entries (run with --repair to delete them)", report.problems());
+            }
+            if !report.is_ok() {
+                std::process::exit(exit::ErrorCode::Cache.exit_code());
+            }
+        }
+        
+        Commands::Cache { command: CacheCommands::Recompress } => {
+            let report = engine.recompress_cache().await?;
+            
+            println!(
+                "🗜️ Rewrote {} entries: {} -> {}",
+                report.rows,
+                format_bytes(report.bytes_before),
+                format_bytes(report.bytes_after)
+            );
+            if report.skipped > 0 {
+                println!("⚠️ {} undecodable entries left as they are (see `cache doctor`)", report.skipped);
+            }
         }
         
-        Commands::Cleanup { max_age_days } => {
-            println!("🧹 Cleaning up entries older than {} days...", max_age_days);
+        Commands::Cache { command: CacheCommands::List { offset, limit, order } } => {
+            let entries = engine.list_cache(offset, limit, order).await?;
             
-            let deleted = engine.cleanup_cache(max_age_days).await?;
+            if entries.is_empty() {
+                println!("📦 No cached games{}", if offset > 0 { " past this offset" } else { "" });
+            } else {
+                println!("📦 Cached games {}-{} ({}):", offset + 1, offset + entries.len(), order.as_str());
+            }
+            let rows: Vec<Vec<String>> = entries
+                .into_iter()
+                .map(|entry| {
+                    vec![
+                        entry.query,
+                        entry.name,
+                        entry.provider,
+                        entry.hit_count.to_string(),
+                        entry.score.map(|score| format!("{:.1}", score)).unwrap_or_else(|| "-".to_string()),
+                        entry.cached_at.format("%Y-%m-%d %H:%M").to_string(),
+                    ]
+                })
+                .collect();
+            if !rows.is_empty() {
+                print_table(&["QUERY", "GAME", "PROVIDER", "HITS", "SCORE", "CACHED"], &rows);
+            }
+        }
+        
+        Commands::Provider {
+            command: ProviderCommands::Schema {
+                command: SchemaCommands::Diff { provider, bless },
+            },
+        } => {
+            let Some(source) = engine.provider(&provider) else {
+                anyhow::bail!("Unknown provider: {}", provider);
+            };
+            let Some(sample) = source.sample_schema().await? else {
+                anyhow::bail!("Provider {} does not support schema sampling", provider);
+            };
             
-            println!("✅ Deleted {} entries", deleted);
+            match engine.schema_baseline(&provider).await? {
+                Some(baseline) => {
+                    let diff = sample.diff(&baseline);
+                    if diff.is_empty() {
+                        println!("✅ {} schema matches baseline ({} paths)", provider, baseline.paths.len());
+                    } else {
+                        println!(
+                            "⚠️ {} schema drift: {} new, {} missing",
+                            provider,
+                            diff.added.len(),
+                            diff.removed.len()
+                        );
+                        print!("{}", diff);
+                    }
+                }
+                None => println!("ℹ️ No baseline for {} (use --bless to store one)", provider),
+            }
+            
+            if bless {
+                engine.bless_schema(&provider, sample).await?;
+                println!("📐 Baseline updated for {}", provider);
+            }
         }
     }
     
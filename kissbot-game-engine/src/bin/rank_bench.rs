@@ -0,0 +1,203 @@
+//! Ranker comparison on the Steam targeted dataset: Acc@1/Acc@5, MRR, mean
+//! latency and throughput of each `Ranker` called as the engine calls it,
+//! printed as a markdown table and optionally saved as JSON to track
+//! regressions across releases. DRAKON is included when it answers.
+//!
+//! ```text
+//! cargo run --release --features cli --bin rank-bench -- ../delta-s3/datasets/steam_games_targeted.json --json rank_bench.json
+//! ```
+//!
+//! Queries are ranked in parallel (rayon); `RAYON_NUM_THREADS=1` measures
+//! latency without contention.
+
+use anyhow::Context;
+use clap::Parser;
+use delta_s3::eval::{self, Candidates, Dataset, EvalOptions};
+use kissbot_game_engine::{
+    engine::DEFAULT_DRAKON_URL,
+    ranking::{DeltaRanker, DrakonRanker, Ranker, RapidfuzzRanker},
+    BuildInfo, GameResult,
+};
+use serde::Serialize;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::time::Instant;
+use tokio::runtime::Runtime;
+
+#[derive(Parser)]
+#[command(name = "rank-bench")]
+#[command(about = "Compares the engine's rankers on a Steam evaluation dataset", long_about = None)]
+#[command(disable_version_flag = true)]
+struct Args {
+    /// Print version, git hash and build date
+    #[arg(short = 'V', long)]
+    version: bool,
+
+    /// Evaluation dataset (titles, and queries with their ground truth)
+    #[arg(default_value = eval::TARGETED_DATASET)]
+    dataset: PathBuf,
+
+    /// Evaluate only the first n queries
+    #[arg(long)]
+    max_queries: Option<usize>,
+
+    /// Random titles ranked next to the ground truth of each query
+    #[arg(long, default_value = "100")]
+    distractors: usize,
+
+    #[arg(long, default_value = "42")]
+    seed: u64,
+
+    /// DRAKON service, benchmarked if its health check answers
+    #[arg(long, default_value = DEFAULT_DRAKON_URL)]
+    drakon_url: String,
+
+    /// Leave DRAKON out even when reachable
+    #[arg(long)]
+    no_drakon: bool,
+
+    /// Also write the results as JSON to this path (with --version and no
+    /// path: print build info as JSON)
+    #[arg(long, value_name = "PATH", num_args = 0..=1)]
+    json: Option<Option<PathBuf>>,
+}
+
+/// Results of one ranker
+#[derive(Serialize)]
+struct RankerResult {
+    ranker: String,
+    queries: usize,
+    acc_at_1: f64,
+    acc_at_5: f64,
+    mrr: f64,
+    /// Mean duration of one `Ranker::rank` call
+    mean_latency_ms: f64,
+    queries_per_sec: f64,
+    /// Queries the ranker returned an error for (counted as misses)
+    errors: usize,
+}
+
+#[derive(Serialize)]
+struct BenchReport {
+    build: BuildInfo,
+    dataset: PathBuf,
+    distractors: usize,
+    seed: u64,
+    rankers: Vec<RankerResult>,
+}
+
+fn bench(runtime: &Runtime, ranker: &dyn Ranker, dataset: &Dataset, options: &EvalOptions) -> RankerResult {
+    let calls = AtomicUsize::new(0);
+    let errors = AtomicUsize::new(0);
+    let nanos = AtomicU64::new(0);
+
+    let rank = |query: &str, titles: &[String]| -> Vec<(usize, f64)> {
+        // Candidate index as id, to map the ranking back to titles
+        let candidates: Vec<GameResult> = titles
+            .iter()
+            .enumerate()
+            .map(|(idx, title)| GameResult::new("eval", idx.to_string(), title.as_str()))
+            .collect();
+        let start = Instant::now();
+        let ranked = runtime.block_on(ranker.rank(query, &candidates));
+        nanos.fetch_add(start.elapsed().as_nanos() as u64, Ordering::Relaxed);
+        calls.fetch_add(1, Ordering::Relaxed);
+        match ranked {
+            Ok(ranked) => ranked
+                .into_iter()
+                .filter_map(|c| Some((c.game.id.parse().ok()?, c.score)))
+                .collect(),
+            Err(_) => {
+                errors.fetch_add(1, Ordering::Relaxed);
+                Vec::new()
+            }
+        }
+    };
+    let report = eval::evaluate(rank, dataset, options);
+
+    let calls = calls.into_inner();
+    RankerResult {
+        ranker: ranker.name().to_string(),
+        queries: report.total,
+        acc_at_1: report.acc_at(1).unwrap_or(0.0),
+        acc_at_5: report.acc_at(5).unwrap_or(0.0),
+        mrr: report.mrr,
+        mean_latency_ms: if calls > 0 { nanos.into_inner() as f64 / calls as f64 / 1e6 } else { 0.0 },
+        queries_per_sec: report.throughput(),
+        errors: errors.into_inner(),
+    }
+}
+
+fn markdown_table(results: &[RankerResult]) -> String {
+    let mut table = String::from(
+        "| Ranker | Acc@1 | Acc@5 | MRR | Mean latency | Queries/s |\n\
+         |--------|------:|------:|----:|-------------:|----------:|\n",
+    );
+    for r in results {
+        table.push_str(&format!(
+            "| {} | {:.2}% | {:.2}% | {:.4} | {:.3} ms | {:.0} |\n",
+            r.ranker,
+            r.acc_at_1 * 100.0,
+            r.acc_at_5 * 100.0,
+            r.mrr,
+            r.mean_latency_ms,
+            r.queries_per_sec,
+        ));
+    }
+    table
+}
+
+fn main() -> anyhow::Result<()> {
+    let args = Args::parse();
+    if args.version {
+        let info = BuildInfo::new("rank-bench");
+        println!("{}", if args.json.is_some() { info.to_json() } else { info.version_line() });
+        return Ok(());
+    }
+    if args.json == Some(None) {
+        anyhow::bail!("--json needs a path");
+    }
+
+    let dataset = Dataset::load(&args.dataset).with_context(|| format!("Failed to load {}", args.dataset.display()))?;
+    println!("✅ Loaded {} titles, {} queries", dataset.titles.len(), dataset.queries.len());
+
+    let runtime = Runtime::new()?;
+    let mut rankers: Vec<Box<dyn Ranker>> = vec![Box::new(RapidfuzzRanker::new()), Box::new(DeltaRanker::new())];
+    if !args.no_drakon {
+        match runtime.block_on(DrakonRanker::new(args.drakon_url.as_str())) {
+            Ok(drakon) => rankers.push(Box::new(drakon)),
+            Err(e) => println!("⚠️  DRAKON skipped ({}): {}", args.drakon_url, e),
+        }
+    }
+
+    let options = EvalOptions {
+        ks: vec![1, 5],
+        candidates: Candidates::Sampled { distractors: args.distractors, seed: args.seed },
+        max_queries: args.max_queries,
+        ..Default::default()
+    };
+    let mut results = Vec::new();
+    for ranker in &rankers {
+        println!("🔄 {}...", ranker.name());
+        results.push(bench(&runtime, ranker.as_ref(), &dataset, &options));
+    }
+
+    println!("\n{}", markdown_table(&results));
+    for r in results.iter().filter(|r| r.errors > 0) {
+        println!("⚠️  {}: {} queries failed (counted as misses)", r.ranker, r.errors);
+    }
+
+    if let Some(Some(path)) = &args.json {
+        let report = BenchReport {
+            build: BuildInfo::new("rank-bench"),
+            dataset: args.dataset.clone(),
+            distractors: args.distractors,
+            seed: args.seed,
+            rankers: results,
+        };
+        std::fs::write(path, serde_json::to_string_pretty(&report)?)
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+        println!("💾 Saved {}", path.display());
+    }
+    Ok(())
+}
@@ -1,37 +1,55 @@
 use axum::{
-    extract::{Json, State},
+    extract::{Json, Path, Query, State},
     http::StatusCode,
     response::{IntoResponse, Response},
-    routing::{get, post},
+    routing::{delete, get, post},
     Router,
 };
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 use tower_http::cors::CorsLayer;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
-use kissbot_game_engine::{GameEngine, SearchQuery, SearchResponse, providers::SteamProvider};
+use kissbot_game_engine::{
+    config::CacheKind,
+    error::GameEngineError,
+    exit::{self, ErrorCode},
+    ranking::{DeltaProfile, DeltaRanker, DrakonRanker, EnsembleMode, EnsembleRanker, RapidfuzzRanker, ResilientRanker},
+    analytics::{DegradedProvider, GuardrailConfig, SearchLogEntry, SearchSummary, TrendingQuery},
+    build_info::{self, startup_banner},
+    cache::{AgeHistogram, CacheBackend, CacheEntrySummary, CacheOrder, CachedGame, TopQuery, DEFAULT_MEMORY_CAPACITY},
+    providers::schema::ProviderSchemaDrift,
+    CacheMode, DrakonMonitorConfig, EngineConfig, EngineInfo, GameEngine, GameResult, Readiness, SearchQuery, SearchResponse,
+    SearchRequestOptions, SlimGameResult, WarmReport,
+};
 
 #[derive(Clone)]
 struct AppState {
     engine: Arc<GameEngine>,
+    /// None when candidates are ranked in process
+    drakon_url: Option<String>,
+    /// Profile of the in-process ranker
+    delta_profile: DeltaProfile,
 }
 
 #[derive(Debug, Deserialize)]
 struct SearchRequest {
     query: String,
-    #[serde(default = "default_max_results")]
-    max_results: usize,
+    #[serde(flatten)]
+    options: SearchRequestOptions,
+    /// Shorthand for `cache_mode: "off"` when false
     #[serde(default = "default_true")]
     use_cache: bool,
 }
 
-fn default_max_results() -> usize { 5 }
 fn default_true() -> bool { true }
 
 #[derive(Debug, Serialize)]
 struct ErrorResponse {
     error: String,
+    code: ErrorCode,
+    retryable: bool,
 }
 
 #[derive(Debug, Serialize)]
@@ -43,17 +61,178 @@ struct HealthResponse {
 #[derive(Debug, Serialize)]
 struct StatsResponse {
     cache: CacheStatsDto,
+    schema_drift: Vec<ProviderSchemaDrift>,
+    degraded_providers: Vec<DegradedProvider>,
 }
 
 #[derive(Debug, Serialize)]
 struct CacheStatsDto {
     total_entries: u64,
+    no_result_entries: u64,
     total_hits: u64,
     avg_hit_count: f64,
+    pending_writes: u64,
+    write_retries_succeeded: u64,
+    write_retries_dropped: u64,
+    oversized_rows: u64,
+    busy_retries: u64,
+    hit_rate: Option<f64>,
+    added_last_24h: u64,
+    added_last_7d: u64,
+    data_bytes: Option<u64>,
+    file_bytes: Option<u64>,
+    age_histogram: AgeHistogram,
+    top_queries: Vec<TopQuery>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TrendingParams {
+    #[serde(default = "default_trending_limit")]
+    limit: usize,
+}
+
+fn default_trending_limit() -> usize { 20 }
+
+#[derive(Debug, Deserialize)]
+struct TopParams {
+    #[serde(default = "default_top_limit")]
+    limit: usize,
+    /// Only queries hit since this RFC3339 timestamp
+    #[serde(default)]
+    since: Option<DateTime<Utc>>,
+}
+
+fn default_top_limit() -> usize { 10 }
+
+#[derive(Debug, Serialize)]
+struct TopResponse {
+    top: Vec<TopQueryDto>,
+}
+
+#[derive(Debug, Serialize)]
+struct TopQueryDto {
+    query: String,
+    hit_count: i32,
+}
+
+#[derive(Debug, Deserialize)]
+struct RecentParams {
+    #[serde(default = "default_recent_limit")]
+    limit: usize,
+}
+
+fn default_recent_limit() -> usize { 50 }
+
+#[derive(Debug, Serialize)]
+struct RecentResponse {
+    searches: Vec<SearchLogEntry>,
+}
+
+#[derive(Debug, Deserialize)]
+struct LastSearchesParams {
+    #[serde(default = "default_last_searches_limit")]
+    limit: usize,
+}
+
+fn default_last_searches_limit() -> usize { 20 }
+
+#[derive(Debug, Serialize)]
+struct LastSearchesResponse {
+    searches: Vec<SearchSummary>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CacheEntryParams {
+    query: String,
+    #[serde(default)]
+    locale: Option<String>,
+    #[serde(default)]
+    country: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct WarmParams {
+    #[serde(default = "default_warm_concurrency")]
+    concurrency: usize,
+}
+
+fn default_warm_concurrency() -> usize { 4 }
+
+#[derive(Debug, Deserialize)]
+struct CacheListParams {
+    #[serde(default)]
+    offset: usize,
+    #[serde(default = "default_cache_list_limit")]
+    limit: usize,
+    /// newest, most_hit or alphabetical
+    #[serde(default)]
+    order: CacheOrder,
+}
+
+fn default_cache_list_limit() -> usize { 50 }
+
+#[derive(Debug, Serialize)]
+struct CacheListResponse {
+    offset: usize,
+    limit: usize,
+    order: CacheOrder,
+    entries: Vec<CacheEntrySummary>,
+}
+
+#[derive(Debug, Serialize)]
+struct InvalidateResponse {
+    query: String,
+    deleted: u64,
+}
+
+#[derive(Debug, Serialize)]
+struct TrendingResponse {
+    trending: Vec<TrendingQuery>,
+}
+
+#[derive(Debug, Serialize)]
+struct EngineStatusResponse {
+    cache_backend: String,
+    ranker: String,
+    providers: Vec<ProviderStatusDto>,
+}
+
+#[derive(Debug, Serialize)]
+struct ProviderStatusDto {
+    name: String,
+    enabled: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct SetRankerRequest {
+    /// "drakon", "delta", "rapidfuzz" or "ensemble"
+    ranker: String,
+    /// Score combination of "ensemble": "weighted" (default), "max" or "rrf"
+    #[serde(default)]
+    mode: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SetProviderRequest {
+    enabled: bool,
 }
 
 #[tokio::main]
-async fn main() -> anyhow::Result<()> {
+async fn main() {
+    if let Err(e) = run().await {
+        let (code, message) = exit::report(&e);
+        eprintln!("❌ {}", message);
+        std::process::exit(code);
+    }
+}
+
+async fn run() -> anyhow::Result<()> {
+    let args: Vec<String> = std::env::args().collect();
+    if let Some(version) = build_info::version_output("game-engine-server", &args) {
+        println!("{}", version);
+        return Ok(());
+    }
+    
     // Initialize tracing
     tracing_subscriber::registry()
         .with(
@@ -64,32 +243,86 @@ async fn main() -> anyhow::Result<()> {
         .init();
 
     // Parse CLI args
-    let db_path = std::env::var("DB_PATH").unwrap_or_else(|_| "kissbot.db".to_string());
     let port = std::env::var("PORT")
         .ok()
         .and_then(|p| p.parse::<u16>().ok())
         .unwrap_or(8090);
+    // 0 disables the background DRAKON health probe
+    let drakon_probe_secs = std::env::var("DRAKON_PROBE_SECS")
+        .ok()
+        .and_then(|s| s.parse::<u64>().ok())
+        .unwrap_or(30);
+    
+    // Engine settings: --config file, or env vars over the defaults
+    let config = match config_arg(&args) {
+        Some(path) => {
+            tracing::info!("📄 Config: {}", path);
+            EngineConfig::load(path)?
+        }
+        None => env_config()?,
+    };
+    let drakon_url = config.drakon_url.clone();
 
-    tracing::info!("🚀 Starting KissBot Game Engine Server");
-    tracing::info!("📦 Database: {}", db_path);
+    match config.cache.backend() {
+        CacheBackend::Sqlite { path, options } => {
+            tracing::info!("📦 Database: {} (journal {}, busy timeout {}ms)", path, options.journal_mode, options.busy_timeout_ms)
+        }
+        CacheBackend::Memory { capacity } => tracing::info!("📦 Cache: memory ({} entries max)", capacity),
+        CacheBackend::None => tracing::info!("📦 Cache: disabled"),
+    }
     tracing::info!("🔌 Port: {}", port);
 
     // Create game engine
-    let mut engine = GameEngine::new(&db_path).await?;
+    let mut engine = GameEngine::from_config(&config).await?;
     
-    // Add Steam provider
-    let steam_provider = Arc::new(SteamProvider::new(None));
-    engine.add_provider(steam_provider);
+    // Provider coverage guardrails
+    let defaults = GuardrailConfig::default();
+    engine.set_guardrail_config(GuardrailConfig {
+        window: env_parse("GUARDRAIL_WINDOW_SECS").map(chrono::Duration::seconds).unwrap_or(defaults.window),
+        sustain: env_parse("GUARDRAIL_SUSTAIN_SECS").map(chrono::Duration::seconds).unwrap_or(defaults.sustain),
+        max_zero_rate: env_parse("GUARDRAIL_MAX_ZERO_RATE").unwrap_or(defaults.max_zero_rate),
+        max_error_rate: env_parse("GUARDRAIL_MAX_ERROR_RATE").unwrap_or(defaults.max_error_rate),
+        ..defaults
+    });
     
-    let state = AppState {
-        engine: Arc::new(engine),
-    };
+    let baselines = engine.load_schema_baselines().await?;
+    tracing::info!("📐 Loaded {} provider schema baseline(s)", baselines);
+    
+    let engine = Arc::new(engine);
+    println!("{}", startup_banner("game-engine-server", &engine));
+    engine.spawn_cache_write_retry();
+    engine.enable_stale_refresh();
+    
+    if let Some(drakon_url) = drakon_url.as_ref().filter(|_| drakon_probe_secs > 0) {
+        tracing::info!("🩺 DRAKON health probe every {}s ({})", drakon_probe_secs, drakon_url);
+        engine.spawn_drakon_monitor(DrakonMonitorConfig {
+            base_url: drakon_url.clone(),
+            interval: std::time::Duration::from_secs(drakon_probe_secs),
+            ..Default::default()
+        });
+    }
+    
+    let state = AppState { engine: engine.clone(), drakon_url, delta_profile: config.delta_profile.clone() };
 
     // Build router
     let app = Router::new()
         .route("/health", get(health_handler))
+        .route("/ready", get(ready_handler))
         .route("/v1/search", post(search_handler))
         .route("/v1/stats", get(stats_handler))
+        .route("/v1/stats/trending", get(trending_handler))
+        .route("/v1/stats/top", get(top_handler))
+        .route("/v1/stats/searches", get(recent_handler))
+        .route("/v1/debug/recent", get(last_searches_handler))
+        .route("/v1/debug/cache", get(cache_entry_handler))
+        .route("/v1/debug/engine", get(engine_info_handler))
+        .route("/v1/cache", get(cache_list_handler))
+        .route("/v1/cache/warm", post(warm_handler))
+        .route("/v1/cache/:query", delete(invalidate_handler))
+        .route("/v1/games/:provider/:id", get(game_handler))
+        .route("/v1/admin/engine", get(engine_status_handler))
+        .route("/v1/admin/ranker", post(set_ranker_handler))
+        .route("/v1/admin/providers/:name", post(set_provider_handler))
         .layer(CorsLayer::permissive())
         .with_state(state);
 
@@ -98,11 +331,71 @@ async fn main() -> anyhow::Result<()> {
     tracing::info!("🎮 Server listening on http://{}", addr);
     
     let listener = tokio::net::TcpListener::bind(&addr).await?;
-    axum::serve(listener, app).await?;
+    axum::serve(listener, app)
+        .with_graceful_shutdown(async {
+            let _ = tokio::signal::ctrl_c().await;
+            tracing::info!("👋 Shutting down");
+        })
+        .await?;
+
+    // Buffered hit counts and logged searches
+    engine.flush().await?;
 
     Ok(())
 }
 
+fn env_parse<T: std::str::FromStr>(name: &str) -> Option<T> {
+    std::env::var(name).ok().and_then(|v| v.parse().ok())
+}
+
+fn env_flag(name: &str) -> bool {
+    matches!(std::env::var(name).as_deref(), Ok("1") | Ok("true"))
+}
+
+/// `--config <path>` or `--config=<path>`
+fn config_arg(args: &[String]) -> Option<&str> {
+    args.iter().enumerate().find_map(|(i, arg)| match arg.strip_prefix("--config") {
+        Some("") => args.get(i + 1).map(String::as_str),
+        Some(value) => value.strip_prefix('='),
+        None => None,
+    })
+}
+
+/// Engine config from env vars, used without `--config`
+fn env_config() -> anyhow::Result<EngineConfig> {
+    let mut config = EngineConfig::default();
+
+    if let Ok(path) = std::env::var("DB_PATH") {
+        config.cache.path = path;
+    }
+    // cache.backend: sqlite (default), memory or none
+    config.cache.backend = match std::env::var("CACHE_BACKEND").as_deref() {
+        Ok("memory") => CacheKind::Memory,
+        Ok("none") => CacheKind::None,
+        Ok("sqlite") | Err(_) => CacheKind::Sqlite,
+        Ok(other) => anyhow::bail!("Unknown CACHE_BACKEND '{}' (expected sqlite, memory or none)", other),
+    };
+    config.cache.capacity = env_parse("CACHE_MEMORY_CAPACITY").unwrap_or(DEFAULT_MEMORY_CAPACITY);
+    if let Ok(url) = std::env::var("DRAKON_URL") {
+        config.drakon_url = Some(url);
+    }
+
+    // Attempts per provider call for transient failures (1 disables retries)
+    if let Some(max_attempts) = env_parse("PROVIDER_MAX_ATTEMPTS") {
+        for provider in &mut config.providers {
+            provider.max_attempts = max_attempts;
+        }
+    }
+
+    // Merge metadata from secondary providers into the winner
+    config.search.enrich = env_flag("ENRICH");
+    config.search.provider_quota = env_parse("PROVIDER_QUOTA");
+    // Persist every search to the search_log table
+    config.search.log_queries = env_flag("LOG_QUERIES");
+
+    Ok(config)
+}
+
 async fn health_handler() -> Json<HealthResponse> {
     Json(HealthResponse {
         status: "ok".to_string(),
@@ -110,19 +403,23 @@ async fn health_handler() -> Json<HealthResponse> {
     })
 }
 
+async fn ready_handler(State(state): State<AppState>) -> (StatusCode, Json<Readiness>) {
+    let readiness = state.engine.readiness();
+    let status = if readiness.ready { StatusCode::OK } else { StatusCode::SERVICE_UNAVAILABLE };
+    (status, Json(readiness))
+}
+
 async fn search_handler(
     State(state): State<AppState>,
     Json(req): Json<SearchRequest>,
 ) -> Result<Json<SearchResponse>, AppError> {
     tracing::debug!("Search request: {:?}", req);
     
-    let query = SearchQuery {
-        query: req.query.clone(),
-        max_results: req.max_results,
-        use_cache: req.use_cache,
-    };
+    let default_mode = if req.use_cache { CacheMode::Use } else { CacheMode::Off };
+    let query = req.options.to_query(req.query.as_str(), default_mode);
     
-    let result = state.engine.search(query).await?;
+    let mut result = state.engine.search(query).await?;
+    req.options.apply(&mut result);
     
     tracing::info!(
         "✅ {} → {} ({}%, {}ms)",
@@ -143,36 +440,220 @@ async fn stats_handler(
     Ok(Json(StatsResponse {
         cache: CacheStatsDto {
             total_entries: cache_stats.total_entries,
+            no_result_entries: cache_stats.no_result_entries,
             total_hits: cache_stats.total_hits,
             avg_hit_count: cache_stats.avg_hit_count,
+            pending_writes: cache_stats.pending_writes,
+            write_retries_succeeded: cache_stats.write_retries_succeeded,
+            write_retries_dropped: cache_stats.write_retries_dropped,
+            oversized_rows: cache_stats.oversized_rows,
+            busy_retries: cache_stats.busy_retries,
+            hit_rate: cache_stats.hit_rate,
+            added_last_24h: cache_stats.added_last_24h,
+            added_last_7d: cache_stats.added_last_7d,
+            data_bytes: cache_stats.data_bytes,
+            file_bytes: cache_stats.file_bytes,
+            age_histogram: cache_stats.age_histogram,
+            top_queries: cache_stats.top_queries,
         },
+        schema_drift: state.engine.schema_drift(),
+        degraded_providers: state.engine.degraded_providers(),
+    }))
+}
+
+async fn top_handler(
+    State(state): State<AppState>,
+    Query(params): Query<TopParams>,
+) -> Result<Json<TopResponse>, AppError> {
+    let top = state.engine.top_queries(params.limit, params.since).await?;
+    
+    Ok(Json(TopResponse {
+        top: top
+            .into_iter()
+            .map(|(query, hit_count)| TopQueryDto { query, hit_count })
+            .collect(),
+    }))
+}
+
+async fn recent_handler(
+    State(state): State<AppState>,
+    Query(params): Query<RecentParams>,
+) -> Result<Json<RecentResponse>, AppError> {
+    Ok(Json(RecentResponse {
+        searches: state.engine.recent_searches(params.limit).await?,
+    }))
+}
+
+async fn last_searches_handler(
+    State(state): State<AppState>,
+    Query(params): Query<LastSearchesParams>,
+) -> Json<LastSearchesResponse> {
+    Json(LastSearchesResponse {
+        searches: state.engine.last_searches(params.limit),
+    })
+}
+
+async fn engine_info_handler(State(state): State<AppState>) -> Result<Json<EngineInfo>, AppError> {
+    Ok(Json(state.engine.info().await?))
+}
+
+/// Full record of a game returned as a compact alternative
+async fn game_handler(
+    State(state): State<AppState>,
+    Path((provider, id)): Path<(String, String)>,
+) -> Result<Json<GameResult>, AppError> {
+    let game = SlimGameResult::from_game(&GameResult::new(provider, id, ""), None);
+    Ok(Json(state.engine.rehydrate(&game).await?))
+}
+
+async fn cache_entry_handler(
+    State(state): State<AppState>,
+    Query(params): Query<CacheEntryParams>,
+) -> Result<Json<CachedGame>, Response> {
+    let query = SearchQuery {
+        query: params.query,
+        locale: params.locale,
+        country: params.country,
+        ..Default::default()
+    };
+    
+    match state.engine.inspect_cache(&query).await.map_err(|e| AppError(e).into_response())? {
+        Some(entry) => Ok(Json(entry)),
+        None => Err(error_response(ErrorCode::NoResults, format!("No cache entry for '{}'", query.cache_key()))),
+    }
+}
+
+/// Page of cached games for admin dashboards
+async fn cache_list_handler(
+    State(state): State<AppState>,
+    Query(params): Query<CacheListParams>,
+) -> Result<Json<CacheListResponse>, AppError> {
+    let entries = state.engine.list_cache(params.offset, params.limit, params.order).await?;
+
+    Ok(Json(CacheListResponse {
+        offset: params.offset,
+        limit: params.limit,
+        order: params.order,
+        entries,
     }))
 }
 
+async fn invalidate_handler(
+    State(state): State<AppState>,
+    Path(query): Path<String>,
+) -> Result<Json<InvalidateResponse>, Response> {
+    let deleted = state.engine.invalidate(&query).await.map_err(|e| AppError(e).into_response())?;
+    if deleted == 0 {
+        return Err(error_response(ErrorCode::NoResults, format!("No cache entry for '{}'", query)));
+    }
+    
+    Ok(Json(InvalidateResponse { query, deleted }))
+}
+
+/// Warm the cache from a JSON array of queries; answers once every query
+/// has been searched
+async fn warm_handler(
+    State(state): State<AppState>,
+    Query(params): Query<WarmParams>,
+    Json(queries): Json<Vec<String>>,
+) -> Result<Json<WarmReport>, AppError> {
+    Ok(Json(state.engine.warm(queries, params.concurrency).await?))
+}
+
+async fn trending_handler(
+    State(state): State<AppState>,
+    Query(params): Query<TrendingParams>,
+) -> Json<TrendingResponse> {
+    Json(TrendingResponse {
+        trending: state.engine.trending(params.limit),
+    })
+}
+
+fn engine_status(engine: &GameEngine) -> EngineStatusResponse {
+    EngineStatusResponse {
+        cache_backend: engine.cache_backend().to_string(),
+        ranker: engine.ranker().name().to_string(),
+        providers: engine
+            .provider_status()
+            .into_iter()
+            .map(|(name, enabled)| ProviderStatusDto { name, enabled })
+            .collect(),
+    }
+}
+
+async fn engine_status_handler(State(state): State<AppState>) -> Json<EngineStatusResponse> {
+    Json(engine_status(&state.engine))
+}
+
+async fn set_ranker_handler(
+    State(state): State<AppState>,
+    Json(req): Json<SetRankerRequest>,
+) -> Result<Json<EngineStatusResponse>, Response> {
+    match req.ranker.as_str() {
+        "drakon" => {
+            let Some(drakon_url) = state.drakon_url.as_deref() else {
+                return Err(error_response(ErrorCode::InvalidInput, "No DRAKON URL configured (drakon_url or DRAKON_URL)".to_string()));
+            };
+            let options = state.engine.options();
+            let drakon = DrakonRanker::with_limits(drakon_url, state.engine.http_client().clone(), options.drakon_limits())
+                .await
+                .map_err(|e| AppError(e).into_response())?;
+            let fallback = Arc::new(RapidfuzzRanker::new());
+            state.engine.set_ranker(Arc::new(ResilientRanker::new(Arc::new(drakon), fallback, options.drakon_breaker())));
+        }
+        "delta" => state.engine.set_ranker(Arc::new(DeltaRanker::with_profile(state.delta_profile.clone()))),
+        "rapidfuzz" => state.engine.set_ranker(Arc::new(RapidfuzzRanker::new())),
+        "ensemble" => {
+            let mode: EnsembleMode = match req.mode.as_deref() {
+                Some(mode) => mode.parse().map_err(|e| error_response(ErrorCode::InvalidInput, e))?,
+                None => EnsembleMode::default(),
+            };
+            let delta = DeltaRanker::with_profile(state.delta_profile.clone());
+            let ensemble = EnsembleRanker::new(Arc::new(RapidfuzzRanker::new()), Arc::new(delta), mode);
+            state.engine.set_ranker(Arc::new(ensemble));
+        }
+        other => {
+            return Err(error_response(ErrorCode::InvalidInput, format!("Unknown ranker: {}", other)));
+        }
+    }
+    
+    Ok(Json(engine_status(&state.engine)))
+}
+
+async fn set_provider_handler(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+    Json(req): Json<SetProviderRequest>,
+) -> Result<Json<EngineStatusResponse>, Response> {
+    if !state.engine.set_provider_enabled(&name, req.enabled) {
+        return Err(error_response(ErrorCode::InvalidInput, format!("Unknown provider: {}", name)));
+    }
+    
+    Ok(Json(engine_status(&state.engine)))
+}
+
 // Error handling
-struct AppError(kissbot_game_engine::error::GameEngineError);
+fn error_response(code: ErrorCode, error: String) -> Response {
+    let status = StatusCode::from_u16(code.http_status()).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+    let body = ErrorResponse { error, code, retryable: code.retryable() };
+    (status, Json(body)).into_response()
+}
+
+struct AppError(GameEngineError);
 
 impl IntoResponse for AppError {
     fn into_response(self) -> Response {
-        let (status, message) = match self.0 {
-            kissbot_game_engine::error::GameEngineError::NoResults(query) => {
-                (StatusCode::NOT_FOUND, format!("No results found for: {}", query))
-            }
-            kissbot_game_engine::error::GameEngineError::Provider { provider, message } => {
-                (StatusCode::BAD_GATEWAY, format!("Provider '{}' error: {}", provider, message))
-            }
-            e => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
-        };
-        
-        tracing::error!("❌ Error: {} - {}", status, message);
+        // Internal log keeps query/provider/phase context and the source chain
+        let (code, message) = exit::describe(&self.0);
+        tracing::error!("❌ Error: {} ({}) - {}", code.http_status(), code, self.0.diagnostic());
         
-        (status, Json(ErrorResponse { error: message })).into_response()
+        error_response(code, message)
     }
 }
 
 impl<E> From<E> for AppError
 where
-    E: Into<kissbot_game_engine::error::GameEngineError>,
+    E: Into<GameEngineError>,
 {
     fn from(err: E) -> Self {
         Self(err.into())
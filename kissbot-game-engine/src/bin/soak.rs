@@ -0,0 +1,422 @@
+//! Soak test: runs a mixed workload against a mock provider for a long time,
+//! samples RSS and engine counters, writes them as CSV and fails when
+//! memory, tasks or cache size keep growing.
+//!
+//! ```text
+//! cargo run --release --features cli --bin soak -- --duration-secs 3600 --report soak.csv
+//! ```
+
+use async_trait::async_trait;
+use clap::{Parser, ValueEnum};
+use kissbot_game_engine::{
+    cache::CacheBackend, engine::DEFAULT_DRAKON_URL, providers::GameProvider, ranking::RapidfuzzRanker, BuildInfo,
+    CacheMode, GameEngine, GameResult, SearchOptions, SearchQuery,
+};
+use std::fmt::Write as _;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+#[derive(Parser)]
+#[command(name = "soak")]
+#[command(about = "Long-running workload checking the engine for leaks", long_about = None)]
+#[command(disable_version_flag = true)]
+struct Args {
+    /// Print version, git hash and build date
+    #[arg(short = 'V', long)]
+    version: bool,
+
+    /// With --version: print build info as JSON
+    #[arg(long, requires = "version")]
+    json: bool,
+
+    /// Total run time
+    #[arg(long, default_value = "600")]
+    duration_secs: u64,
+
+    /// Time between two samples
+    #[arg(long, default_value = "60")]
+    sample_secs: u64,
+
+    /// Samples taken before this are not checked (allocator and caches warming up)
+    #[arg(long, default_value = "120")]
+    warmup_secs: u64,
+
+    /// Cache backend
+    #[arg(long, value_enum, default_value = "sqlite")]
+    backend: Backend,
+
+    /// Entry cap of the memory backend
+    #[arg(long, default_value = "1000")]
+    capacity: usize,
+
+    /// Distinct queries in the workload
+    #[arg(long, default_value = "5000")]
+    queries: usize,
+
+    /// Zipf exponent of the query popularity
+    #[arg(long, default_value = "1.1")]
+    zipf: f64,
+
+    /// Concurrent clients
+    #[arg(long, default_value = "8")]
+    concurrency: usize,
+
+    /// Time between two cache and search log cleanups
+    #[arg(long, default_value = "30")]
+    cleanup_secs: u64,
+
+    /// Simulated provider latency
+    #[arg(long, default_value = "2")]
+    provider_latency_ms: u64,
+
+    /// Max RSS growth after warmup, in KiB per minute
+    #[arg(long, default_value = "1024")]
+    max_rss_slope_kib: f64,
+
+    /// CSV report path
+    #[arg(long, default_value = "soak.csv")]
+    report: PathBuf,
+
+    #[arg(long, default_value = "42")]
+    seed: u64,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Backend {
+    Sqlite,
+    Memory,
+}
+
+/// Provider answering every query with the game of the same name and a few
+/// editions of it
+struct SoakProvider {
+    latency: Duration,
+}
+
+const EDITIONS: [&str; 3] = ["Deluxe Edition", "Remastered", "Soundtrack"];
+
+#[async_trait]
+impl GameProvider for SoakProvider {
+    async fn search(&self, query: &str) -> kissbot_game_engine::Result<Vec<GameResult>> {
+        tokio::time::sleep(self.latency).await;
+        let mut games = vec![GameResult::new("soak", query, query)];
+        games.extend(
+            EDITIONS
+                .iter()
+                .map(|edition| GameResult::new("soak", format!("{} {}", query, edition), format!("{} {}", query, edition))),
+        );
+        Ok(games)
+    }
+
+    async fn get_by_id(&self, id: &str) -> kissbot_game_engine::Result<GameResult> {
+        Ok(GameResult::new("soak", id, id))
+    }
+
+    fn name(&self) -> &str {
+        "soak"
+    }
+
+    async fn is_available(&self) -> bool {
+        true
+    }
+}
+
+/// xorshift64*, enough for picking queries
+struct Rng(u64);
+
+impl Rng {
+    fn new(seed: u64) -> Self {
+        Self(seed.wrapping_mul(0x9E37_79B9_7F4A_7C15).max(1))
+    }
+
+    /// Uniform in [0, 1)
+    fn next_f64(&mut self) -> f64 {
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+        (self.0.wrapping_mul(0x2545_F491_4F6C_DD1D) >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+/// Cumulative Zipf distribution over ranks `0..n`
+fn zipf_cdf(n: usize, s: f64) -> Vec<f64> {
+    let mut total = 0.0;
+    let mut cdf: Vec<f64> = (1..=n.max(1))
+        .map(|rank| {
+            total += 1.0 / (rank as f64).powf(s);
+            total
+        })
+        .collect();
+    for p in &mut cdf {
+        *p /= total;
+    }
+    cdf
+}
+
+fn pick(cdf: &[f64], rng: &mut Rng) -> usize {
+    let u = rng.next_f64();
+    cdf.partition_point(|p| *p < u).min(cdf.len() - 1)
+}
+
+#[derive(Default)]
+struct Counters {
+    searches: AtomicU64,
+    refreshes: AtomicU64,
+    polls: AtomicU64,
+    errors: AtomicU64,
+}
+
+struct Sample {
+    elapsed_secs: f64,
+    rss_kib: Option<u64>,
+    background_tasks: usize,
+    in_flight_searches: usize,
+    cache_entries: u64,
+    pending_writes: usize,
+    trending_queries: usize,
+    recent_searches: usize,
+    db_bytes: Option<u64>,
+    searches: u64,
+    errors: u64,
+}
+
+const CSV_HEADER: &str = "elapsed_secs,rss_kib,background_tasks,in_flight_searches,cache_entries,pending_writes,trending_queries,recent_searches,db_bytes,searches,errors";
+
+impl Sample {
+    fn csv_row(&self) -> String {
+        let opt = |v: Option<u64>| v.map(|v| v.to_string()).unwrap_or_default();
+        format!(
+            "{:.1},{},{},{},{},{},{},{},{},{},{}",
+            self.elapsed_secs,
+            opt(self.rss_kib),
+            self.background_tasks,
+            self.in_flight_searches,
+            self.cache_entries,
+            self.pending_writes,
+            self.trending_queries,
+            self.recent_searches,
+            opt(self.db_bytes),
+            self.searches,
+            self.errors
+        )
+    }
+}
+
+/// Resident set size of this process (Linux only)
+fn rss_kib() -> Option<u64> {
+    let status = std::fs::read_to_string("/proc/self/status").ok()?;
+    let line = status.lines().find(|line| line.starts_with("VmRSS:"))?;
+    line.split_whitespace().nth(1)?.parse().ok()
+}
+
+/// Least squares slope of `(x, y)` points
+fn slope(points: &[(f64, f64)]) -> Option<f64> {
+    if points.len() < 2 {
+        return None;
+    }
+    let n = points.len() as f64;
+    let mean_x = points.iter().map(|(x, _)| x).sum::<f64>() / n;
+    let mean_y = points.iter().map(|(_, y)| y).sum::<f64>() / n;
+    let var_x: f64 = points.iter().map(|(x, _)| (x - mean_x).powi(2)).sum();
+    if var_x == 0.0 {
+        return None;
+    }
+    let cov: f64 = points.iter().map(|(x, y)| (x - mean_x) * (y - mean_y)).sum();
+    Some(cov / var_x)
+}
+
+async fn sample(engine: &GameEngine, counters: &Counters, db: Option<&PathBuf>, start: Instant) -> anyhow::Result<Sample> {
+    let info = engine.info().await?;
+    Ok(Sample {
+        elapsed_secs: start.elapsed().as_secs_f64(),
+        rss_kib: rss_kib(),
+        background_tasks: info.background_tasks,
+        in_flight_searches: info.in_flight_searches,
+        cache_entries: info.cache_entries,
+        pending_writes: info.pending_writes,
+        trending_queries: info.trending_queries,
+        recent_searches: info.recent_searches,
+        db_bytes: db.and_then(|path| std::fs::metadata(path).ok()).map(|meta| meta.len()),
+        searches: counters.searches.load(Ordering::Relaxed),
+        errors: counters.errors.load(Ordering::Relaxed),
+    })
+}
+
+async fn client(engine: Arc<GameEngine>, cdf: Arc<Vec<f64>>, counters: Arc<Counters>, seed: u64, deadline: Instant) {
+    let mut rng = Rng::new(seed);
+
+    while Instant::now() < deadline {
+        let roll = rng.next_f64();
+        let query = format!("soak game {}", pick(&cdf, &mut rng));
+
+        if roll < 0.03 {
+            // Stats poll, as a dashboard would
+            let polled = engine.cache_stats().await.is_ok();
+            let _ = engine.trending(10);
+            let _ = engine.last_searches(10);
+            if polled {
+                counters.polls.fetch_add(1, Ordering::Relaxed);
+            } else {
+                counters.errors.fetch_add(1, Ordering::Relaxed);
+            }
+            continue;
+        }
+
+        let refresh = roll < 0.08;
+        let search = SearchQuery {
+            query,
+            max_results: 5,
+            cache_mode: if refresh { CacheMode::Bypass } else { CacheMode::Use },
+            ..Default::default()
+        };
+        match engine.search(search).await {
+            Ok(_) if refresh => counters.refreshes.fetch_add(1, Ordering::Relaxed),
+            Ok(_) => counters.searches.fetch_add(1, Ordering::Relaxed),
+            Err(e) => {
+                tracing::warn!("Soak search failed: {}", e.diagnostic());
+                counters.errors.fetch_add(1, Ordering::Relaxed)
+            }
+        };
+    }
+}
+
+/// Failed checks, empty when the run stayed bounded
+fn check(args: &Args, samples: &[Sample], cache_cap: u64) -> Vec<String> {
+    let mut failures = Vec::new();
+    let warm: Vec<&Sample> = samples.iter().filter(|s| s.elapsed_secs >= args.warmup_secs as f64).collect();
+
+    let rss: Vec<(f64, f64)> = warm
+        .iter()
+        .filter_map(|s| s.rss_kib.map(|rss| (s.elapsed_secs / 60.0, rss as f64)))
+        .collect();
+    match slope(&rss) {
+        Some(slope) if slope > args.max_rss_slope_kib => failures.push(format!(
+            "RSS grows by {:.0} KiB/min after warmup (max {:.0})",
+            slope, args.max_rss_slope_kib
+        )),
+        Some(slope) => println!("RSS slope after warmup: {:.1} KiB/min", slope),
+        None => println!("RSS slope after warmup: not enough samples"),
+    }
+
+    let tasks = warm.iter().map(|s| s.background_tasks);
+    if let (Some(min), Some(max)) = (tasks.clone().min(), tasks.max()) {
+        if min != max {
+            failures.push(format!("Background task count moved between {} and {} after warmup", min, max));
+        }
+    }
+
+    if let Some(s) = samples.iter().find(|s| s.cache_entries > cache_cap) {
+        failures.push(format!("{} cache entries at {:.0}s, cap is {}", s.cache_entries, s.elapsed_secs, cache_cap));
+    }
+    if let Some(s) = samples.iter().find(|s| s.in_flight_searches > args.concurrency) {
+        failures.push(format!("{} searches in flight for {} clients", s.in_flight_searches, args.concurrency));
+    }
+    if let Some(last) = samples.last() {
+        if last.in_flight_searches != 0 {
+            failures.push(format!("{} searches still in flight after the run", last.in_flight_searches));
+        }
+    }
+
+    failures
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    tracing_subscriber::fmt()
+        .with_env_filter(tracing_subscriber::EnvFilter::try_from_default_env().unwrap_or_else(|_| "warn".into()))
+        .init();
+
+    let args = Args::parse();
+    if args.version {
+        let info = BuildInfo::new("soak");
+        println!("{}", if args.json { info.to_json() } else { info.version_line() });
+        return Ok(());
+    }
+
+    let db = (args.backend == Backend::Sqlite)
+        .then(|| std::env::temp_dir().join(format!("kissbot_soak_{}.db", std::process::id())));
+    let backend = match &db {
+        Some(path) => CacheBackend::sqlite(path.to_string_lossy()),
+        None => CacheBackend::Memory { capacity: args.capacity },
+    };
+    let cache_cap = match args.backend {
+        Backend::Sqlite => args.queries as u64,
+        Backend::Memory => args.capacity.min(args.queries) as u64,
+    };
+
+    let options = SearchOptions { log_queries: true, ..Default::default() };
+    let mut engine = GameEngine::with_backend(backend, DEFAULT_DRAKON_URL, options).await?;
+    engine.add_provider(Arc::new(SoakProvider { latency: Duration::from_millis(args.provider_latency_ms) }));
+    let engine = Arc::new(engine);
+    engine.set_ranker(Arc::new(RapidfuzzRanker::new()));
+    let retry = engine.spawn_cache_write_retry();
+
+    let start = Instant::now();
+    let deadline = start + Duration::from_secs(args.duration_secs);
+    let cdf = Arc::new(zipf_cdf(args.queries, args.zipf));
+    let counters = Arc::new(Counters::default());
+    let clients: Vec<_> = (0..args.concurrency.max(1))
+        .map(|i| tokio::spawn(client(engine.clone(), cdf.clone(), counters.clone(), args.seed + i as u64, deadline)))
+        .collect();
+
+    println!("🔥 Soak: {}s, {} clients, {} queries, report {}", args.duration_secs, args.concurrency, args.queries, args.report.display());
+    let mut samples = Vec::new();
+    let mut sample_tick = tokio::time::interval(Duration::from_secs(args.sample_secs.max(1)));
+    let mut cleanup_tick = tokio::time::interval(Duration::from_secs(args.cleanup_secs.max(1)));
+    // Both fire immediately, skip the first cleanup on an empty cache
+    cleanup_tick.tick().await;
+
+    while Instant::now() < deadline {
+        tokio::select! {
+            _ = sample_tick.tick() => {
+                let s = sample(&engine, &counters, db.as_ref(), start).await?;
+                println!("{}", s.csv_row());
+                samples.push(s);
+            }
+            _ = cleanup_tick.tick() => {
+                engine.cleanup_cache(0).await?;
+                engine.cleanup_log(0).await?;
+            }
+            _ = tokio::time::sleep_until(deadline.into()) => {}
+        }
+    }
+
+    for client in clients {
+        client.await?;
+    }
+    engine.flush_query_log().await;
+    samples.push(sample(&engine, &counters, db.as_ref(), start).await?);
+    retry.abort();
+
+    let mut csv = String::from(CSV_HEADER);
+    csv.push('\n');
+    for s in &samples {
+        let _ = writeln!(csv, "{}", s.csv_row());
+    }
+    std::fs::write(&args.report, csv)?;
+    if let Some(path) = &db {
+        for suffix in ["", "-wal", "-shm"] {
+            let _ = std::fs::remove_file(format!("{}{}", path.display(), suffix));
+        }
+    }
+
+    println!(
+        "✅ {} searches, {} refreshes, {} polls, {} errors",
+        counters.searches.load(Ordering::Relaxed),
+        counters.refreshes.load(Ordering::Relaxed),
+        counters.polls.load(Ordering::Relaxed),
+        counters.errors.load(Ordering::Relaxed)
+    );
+
+    let failures = check(&args, &samples, cache_cap);
+    if !failures.is_empty() {
+        for failure in &failures {
+            eprintln!("❌ {}", failure);
+        }
+        std::process::exit(1);
+    }
+    println!("✅ Bounded: RSS, background tasks and cache size");
+    Ok(())
+}
@@ -0,0 +1,130 @@
+//! Build metadata (version, git hash, build date, features) shared by
+//! `--version` and the startup banner of every binary
+
+use serde::{Deserialize, Serialize};
+
+use crate::engine::GameEngine;
+use crate::VERSION;
+
+/// Short git hash of the build (vergen's placeholder outside a git checkout)
+pub const GIT_SHA: &str = env!("VERGEN_GIT_SHA");
+
+/// RFC3339 build timestamp
+pub const BUILD_DATE: &str = env!("VERGEN_BUILD_TIMESTAMP");
+
+/// Cargo features compiled in
+pub fn enabled_features() -> Vec<&'static str> {
+    let mut features = Vec::new();
+    if cfg!(feature = "server") {
+        features.push("server");
+    }
+    if cfg!(feature = "python") {
+        features.push("python");
+    }
+    if cfg!(feature = "cli") {
+        features.push("cli");
+    }
+    features
+}
+
+/// Machine-readable `--version --json` output
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BuildInfo {
+    pub name: String,
+    pub version: String,
+    pub git_sha: String,
+    pub build_date: String,
+    pub features: Vec<String>,
+}
+
+impl BuildInfo {
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            version: VERSION.to_string(),
+            git_sha: match GIT_SHA {
+                "VERGEN_IDEMPOTENT_OUTPUT" => "unknown".to_string(),
+                sha => sha.to_string(),
+            },
+            build_date: BUILD_DATE.to_string(),
+            features: enabled_features().into_iter().map(String::from).collect(),
+        }
+    }
+
+    /// One-line `--version` output: `name 0.1.0 (abc1234 2025-01-01T00:00:00Z)`
+    pub fn version_line(&self) -> String {
+        format!("{} {} ({} {})", self.name, self.version, self.git_sha, self.build_date)
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap_or_default()
+    }
+}
+
+/// If `args` ask for `--version` (or `-V`), return what to print:
+/// the version line, or JSON when `--json` is also given
+pub fn version_output<S: AsRef<str>>(name: &str, args: &[S]) -> Option<String> {
+    let has = |flag: &str| args.iter().any(|a| a.as_ref() == flag);
+    if !has("--version") && !has("-V") {
+        return None;
+    }
+
+    let info = BuildInfo::new(name);
+    Some(if has("--json") { info.to_json() } else { info.version_line() })
+}
+
+/// Multi-line startup banner: build info plus the engine's cache backend,
+/// registered providers and active ranker
+pub fn startup_banner(name: &str, engine: &GameEngine) -> String {
+    let info = BuildInfo::new(name);
+    let providers: Vec<String> = engine
+        .provider_status()
+        .into_iter()
+        .map(|(name, enabled)| if enabled { name } else { format!("{} (disabled)", name) })
+        .collect();
+
+    let features = if info.features.is_empty() { "none".to_string() } else { info.features.join(", ") };
+    let providers = if providers.is_empty() { "none".to_string() } else { providers.join(", ") };
+
+    [
+        format!("🎮 {}", info.version_line()),
+        format!("   Features:  {}", features),
+        format!("   Cache:     {}", engine.cache_backend()),
+        format!("   Providers: {}", providers),
+        format!("   Ranker:    {}", engine.ranker().name()),
+    ]
+    .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::SearchOptions;
+    use crate::providers::SteamProvider;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_version_output() {
+        assert_eq!(version_output("cli", &["cli", "search", "zelda"]), None);
+
+        let line = version_output("cli", &["cli", "--version"]).unwrap();
+        assert!(line.starts_with(&format!("cli {} (", VERSION)));
+
+        let json = version_output("cli", &["cli", "-V", "--json"]).unwrap();
+        let info: BuildInfo = serde_json::from_str(&json).unwrap();
+        assert_eq!(info, BuildInfo::new("cli"));
+    }
+
+    #[tokio::test]
+    async fn test_startup_banner() {
+        let mut engine = GameEngine::without_cache(SearchOptions::default()).await.unwrap();
+        engine.add_provider(Arc::new(SteamProvider::new(None)));
+        engine.set_provider_enabled("steam", false);
+
+        let banner = startup_banner("server", &engine);
+        assert!(banner.contains(VERSION));
+        assert!(banner.contains("Cache:     none"));
+        assert!(banner.contains("steam (disabled)"));
+        assert!(banner.contains(&format!("Ranker:    {}", engine.ranker().name())));
+    }
+}
@@ -0,0 +1,138 @@
+//! Storage format of the `game_data` and `alternatives` columns: plain JSON
+//! text (what the Python bot writes and reads), or a blob whose first byte
+//! names the encoding of the rest
+
+use rusqlite::types::{FromSql, FromSqlError, FromSqlResult, ToSql, ToSqlOutput, ValueRef};
+
+use crate::error::{GameEngineError, Result};
+
+/// Marker byte of a zstd-compressed JSON blob
+const ZSTD: u8 = 1;
+
+/// zstd levels accepted by `SqliteCacheOptions::compression_level`
+pub const COMPRESSION_LEVELS: std::ops::RangeInclusive<i32> = 1..=22;
+
+/// Why `level` can't be used to compress, if it can't
+pub fn check_level(level: i32) -> std::result::Result<(), &'static str> {
+    if !cfg!(feature = "compression") {
+        Err("needs a build with the `compression` feature (null stores plain JSON)")
+    } else if !COMPRESSION_LEVELS.contains(&level) {
+        Err("must be between 1 and 22 (null stores plain JSON)")
+    } else {
+        Ok(())
+    }
+}
+
+/// A JSON column value as stored
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StoredJson {
+    Text(String),
+    Blob(Vec<u8>),
+}
+
+impl StoredJson {
+    /// Store `json` as is, or zstd-compressed at `level`
+    pub fn encode(json: String, level: Option<i32>) -> Result<Self> {
+        match level {
+            None => Ok(StoredJson::Text(json)),
+            Some(level) => compress(&json, level),
+        }
+    }
+
+    pub fn decode(self) -> Result<String> {
+        match self {
+            StoredJson::Text(json) => Ok(json),
+            StoredJson::Blob(blob) => match blob.split_first() {
+                Some((&ZSTD, data)) => decompress(data),
+                Some((marker, _)) => Err(GameEngineError::Cache(format!("Unknown storage format marker {}", marker))),
+                None => Err(GameEngineError::Cache("Empty stored value".to_string())),
+            },
+        }
+    }
+
+    /// Stored size in bytes
+    pub fn size(&self) -> usize {
+        match self {
+            StoredJson::Text(json) => json.len(),
+            StoredJson::Blob(blob) => blob.len(),
+        }
+    }
+}
+
+#[cfg(feature = "compression")]
+fn compress(json: &str, level: i32) -> Result<StoredJson> {
+    let mut blob = vec![ZSTD];
+    zstd::stream::copy_encode(json.as_bytes(), &mut blob, level)
+        .map_err(|e| GameEngineError::Cache(format!("zstd compression failed: {}", e)))?;
+    Ok(StoredJson::Blob(blob))
+}
+
+#[cfg(not(feature = "compression"))]
+fn compress(_json: &str, _level: i32) -> Result<StoredJson> {
+    Err(compression_disabled())
+}
+
+#[cfg(feature = "compression")]
+fn decompress(data: &[u8]) -> Result<String> {
+    let bytes = zstd::stream::decode_all(data).map_err(|e| GameEngineError::Cache(format!("zstd decompression failed: {}", e)))?;
+    String::from_utf8(bytes).map_err(|e| GameEngineError::Cache(format!("Decompressed value is not UTF-8: {}", e)))
+}
+
+#[cfg(not(feature = "compression"))]
+fn decompress(_data: &[u8]) -> Result<String> {
+    Err(compression_disabled())
+}
+
+#[cfg(not(feature = "compression"))]
+fn compression_disabled() -> GameEngineError {
+    GameEngineError::Cache("zstd-compressed entry, built without the `compression` feature".to_string())
+}
+
+impl FromSql for StoredJson {
+    fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
+        match value {
+            ValueRef::Text(text) => String::from_utf8(text.to_vec())
+                .map(StoredJson::Text)
+                .map_err(|e| FromSqlError::Other(Box::new(e))),
+            ValueRef::Blob(blob) => Ok(StoredJson::Blob(blob.to_vec())),
+            _ => Err(FromSqlError::InvalidType),
+        }
+    }
+}
+
+impl ToSql for StoredJson {
+    fn to_sql(&self) -> rusqlite::Result<ToSqlOutput<'_>> {
+        match self {
+            StoredJson::Text(json) => json.to_sql(),
+            StoredJson::Blob(blob) => blob.to_sql(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_plain_text_round_trip() {
+        let stored = StoredJson::encode(r#"{"name":"Hades"}"#.to_string(), None).unwrap();
+        assert_eq!(stored, StoredJson::Text(r#"{"name":"Hades"}"#.to_string()));
+        assert_eq!(stored.decode().unwrap(), r#"{"name":"Hades"}"#);
+    }
+
+    #[test]
+    fn test_unknown_marker_is_an_error() {
+        assert!(StoredJson::Blob(vec![9, 1, 2]).decode().is_err());
+        assert!(StoredJson::Blob(Vec::new()).decode().is_err());
+    }
+
+    #[cfg(feature = "compression")]
+    #[test]
+    fn test_zstd_round_trip() {
+        let json = format!(r#"{{"description":"{}"}}"#, "<p>Roguelike</p>".repeat(200));
+        let stored = StoredJson::encode(json.clone(), Some(3)).unwrap();
+        assert!(matches!(&stored, StoredJson::Blob(blob) if blob[0] == ZSTD));
+        assert!(stored.size() < json.len() / 10);
+        assert_eq!(stored.decode().unwrap(), json);
+    }
+}
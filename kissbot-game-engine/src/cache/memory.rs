@@ -0,0 +1,542 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use crate::cache::{
+    like_matches, normalize_query, CacheEntrySummary, CacheOrder, CacheStats, CachedGame, GameCache, TopQuery, TtlDecision,
+    STATS_TOP_QUERIES,
+};
+use crate::core::{GameResult, SlimGameResult};
+use crate::error::{GameEngineError, Result};
+use crate::providers::Fingerprint;
+
+/// Default number of entries kept by `MemoryCache`
+pub const DEFAULT_MEMORY_CAPACITY: usize = 10_000;
+
+#[derive(Debug, Clone)]
+enum Slot {
+    Game(Box<CachedGame>),
+    /// No-result tombstone valid until `expires_at`
+    NoResult { expires_at: DateTime<Utc> },
+}
+
+#[derive(Debug)]
+struct Entry {
+    slot: Slot,
+    last_hit_at: Option<DateTime<Utc>>,
+    /// Recency tick for LRU eviction
+    last_used: u64,
+}
+
+#[derive(Default)]
+struct State {
+    entries: HashMap<String, Entry>,
+    schemas: HashMap<String, Fingerprint>,
+    /// Alias -> key whose entry serves it
+    aliases: HashMap<String, String>,
+    tick: u64,
+}
+
+impl State {
+    fn next_tick(&mut self) -> u64 {
+        self.tick += 1;
+        self.tick
+    }
+}
+
+/// In-process cache with a size cap (least recently used entries evicted).
+/// Nothing touches disk.
+pub struct MemoryCache {
+    capacity: usize,
+    state: Mutex<State>,
+}
+
+impl MemoryCache {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            state: Mutex::new(State::default()),
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Number of entries (tombstones included)
+    pub fn len(&self) -> usize {
+        self.lock().map(|state| state.entries.len()).unwrap_or_default()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn lock(&self) -> Result<std::sync::MutexGuard<'_, State>> {
+        self.state
+            .lock()
+            .map_err(|e| GameEngineError::Cache(format!("Memory cache lock poisoned: {}", e)))
+    }
+
+    /// Drop games whose `timestamp` is older than `max_age_days`, and
+    /// expired no-result tombstones
+    fn cleanup_older_than(&self, max_age_days: i64, timestamp: impl Fn(&CachedGame, &Entry) -> DateTime<Utc>) -> Result<u64> {
+        let now = Utc::now();
+        let cutoff = now - Duration::days(max_age_days);
+        let mut state = self.lock()?;
+        let before = state.entries.len();
+
+        state.entries.retain(|_, entry| match &entry.slot {
+            Slot::Game(cached) => timestamp(cached, entry) >= cutoff && cached.expires_at().is_none_or(|at| at >= now),
+            Slot::NoResult { expires_at } => *expires_at >= now,
+        });
+
+        let deleted = (before - state.entries.len()) as u64;
+        if deleted > 0 {
+            tracing::info!("🧹 Cleaned up {} memory cache entries", deleted);
+        }
+        Ok(deleted)
+    }
+
+    /// Insert or replace an entry, evicting the least recently used one when full
+    fn insert(&self, state: &mut State, key: String, slot: Slot) {
+        if self.capacity == 0 {
+            return;
+        }
+
+        if !state.entries.contains_key(&key) && state.entries.len() >= self.capacity {
+            let lru = state
+                .entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.last_used)
+                .map(|(key, _)| key.clone());
+            if let Some(lru) = lru {
+                state.entries.remove(&lru);
+            }
+        }
+
+        let last_used = state.next_tick();
+        let last_hit_at = state.entries.get(&key).and_then(|e| e.last_hit_at);
+        state.entries.insert(key, Entry { slot, last_hit_at, last_used });
+    }
+
+    fn write_entry(
+        &self,
+        query: &str,
+        game: &GameResult,
+        alternatives: &[SlimGameResult],
+        score: Option<f64>,
+        ranking_method: Option<&str>,
+        ttl: Option<&TtlDecision>,
+    ) -> Result<()> {
+        let key = normalize_query(query);
+        let mut state = self.lock()?;
+        let hit_count = match state.entries.get(&key).map(|e| &e.slot) {
+            Some(Slot::Game(cached)) => cached.hit_count,
+            _ => 0,
+        };
+
+        let cached = CachedGame {
+            query: key.clone(),
+            game: game.clone(),
+            alternatives: alternatives.to_vec(),
+            score,
+            ranking_method: ranking_method.map(String::from),
+            hit_count,
+            ttl: ttl.cloned(),
+            cached_at: Utc::now(),
+        };
+        self.insert(&mut state, key, Slot::Game(Box::new(cached)));
+        Ok(())
+    }
+}
+
+impl Default for MemoryCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_MEMORY_CAPACITY)
+    }
+}
+
+#[async_trait]
+impl GameCache for MemoryCache {
+    async fn get(&self, query: &str) -> Result<Option<CachedGame>> {
+        let key = normalize_query(query);
+        let mut state = self.lock()?;
+        let tick = state.next_tick();
+
+        match state.entries.get_mut(&key) {
+            Some(Entry { slot: Slot::Game(cached), last_used, .. }) => {
+                *last_used = tick;
+                Ok(Some(cached.as_ref().clone()))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    async fn save(&self, query: &str, game: &GameResult, alternatives: &[SlimGameResult]) -> Result<()> {
+        self.write_entry(query, game, alternatives, None, None, None)
+    }
+
+    async fn save_scored(
+        &self,
+        query: &str,
+        game: &GameResult,
+        alternatives: &[SlimGameResult],
+        score: f64,
+    ) -> Result<()> {
+        self.write_entry(query, game, alternatives, Some(score), None, None)
+    }
+
+    async fn save_with_ttl(
+        &self,
+        query: &str,
+        game: &GameResult,
+        alternatives: &[SlimGameResult],
+        score: f64,
+        ranking_method: &str,
+        ttl: &TtlDecision,
+    ) -> Result<()> {
+        self.write_entry(query, game, alternatives, Some(score), Some(ranking_method), Some(ttl))
+    }
+
+    async fn increment_hit(&self, query: &str) -> Result<()> {
+        let key = normalize_query(query);
+        let mut state = self.lock()?;
+
+        if let Some(entry) = state.entries.get_mut(&key) {
+            if let Slot::Game(cached) = &mut entry.slot {
+                cached.hit_count += 1;
+                entry.last_hit_at = Some(Utc::now());
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn top_queries(&self, limit: usize, since: Option<DateTime<Utc>>) -> Result<Vec<(String, i32)>> {
+        let state = self.lock()?;
+        let mut top: Vec<(String, i32)> = state
+            .entries
+            .iter()
+            .filter_map(|(key, entry)| match &entry.slot {
+                Slot::Game(cached) => Some((key, cached, entry.last_hit_at)),
+                Slot::NoResult { .. } => None,
+            })
+            .filter(|(_, _, last_hit_at)| match since {
+                Some(since) => last_hit_at.map(|at| at >= since).unwrap_or(false),
+                None => true,
+            })
+            .map(|(key, cached, _)| (key.clone(), cached.hit_count))
+            .collect();
+
+        top.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        top.truncate(limit);
+        Ok(top)
+    }
+
+    async fn stats(&self) -> Result<CacheStats> {
+        let now = Utc::now();
+        let state = self.lock()?;
+        let mut stats = CacheStats::default();
+        let mut hits = Vec::new();
+
+        for (key, entry) in &state.entries {
+            match &entry.slot {
+                Slot::Game(cached) => {
+                    stats.total_entries += 1;
+                    stats.total_hits += cached.hit_count.max(0) as u64;
+                    stats.oldest_entry = Some(stats.oldest_entry.map_or(cached.cached_at, |t| t.min(cached.cached_at)));
+                    stats.newest_entry = Some(stats.newest_entry.map_or(cached.cached_at, |t| t.max(cached.cached_at)));
+                    stats.age_histogram.record(now - cached.cached_at);
+                    hits.push((key, cached.hit_count));
+                }
+                Slot::NoResult { .. } => stats.no_result_entries += 1,
+            }
+        }
+
+        let ages = stats.age_histogram;
+        stats.added_last_24h = ages.under_1d;
+        stats.added_last_7d = ages.under_1d + ages.under_7d;
+        hits.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+        stats.top_queries = hits
+            .into_iter()
+            .take(STATS_TOP_QUERIES)
+            .map(|(key, hit_count)| TopQuery { query: key.clone(), hit_count })
+            .collect();
+        stats.aliases = state.aliases.len() as u64;
+        if stats.total_entries > 0 {
+            stats.avg_hit_count = stats.total_hits as f64 / stats.total_entries as f64;
+        }
+
+        Ok(stats)
+    }
+
+    async fn cleanup(&self, max_age_days: i64) -> Result<u64> {
+        self.cleanup_older_than(max_age_days, |cached, entry| {
+            entry.last_hit_at.map_or(cached.cached_at, |at| at.max(cached.cached_at))
+        })
+    }
+
+    async fn cleanup_by_insert_age(&self, max_age_days: i64) -> Result<u64> {
+        self.cleanup_older_than(max_age_days, |cached, _| cached.cached_at)
+    }
+
+    async fn delete(&self, query: &str) -> Result<bool> {
+        Ok(self.lock()?.entries.remove(&normalize_query(query)).is_some())
+    }
+
+    async fn delete_matching(&self, pattern: &str) -> Result<u64> {
+        let mut state = self.lock()?;
+        let before = state.entries.len();
+        state.entries.retain(|key, _| !like_matches(pattern, key));
+        Ok((before - state.entries.len()) as u64)
+    }
+
+    async fn invalidate_where(&self, query: &str, patterns: &[String]) -> Result<u64> {
+        let key = normalize_query(query);
+        let mut state = self.lock()?;
+        let doomed = |k: &String| *k == key || patterns.iter().any(|pattern| like_matches(pattern, k));
+        let before = state.entries.len();
+        state.entries.retain(|k, _| !doomed(k));
+        state.aliases.retain(|alias, target| !doomed(alias) && !doomed(target));
+        Ok((before - state.entries.len()) as u64)
+    }
+
+    async fn keys_matching(&self, pattern: &str, limit: usize) -> Result<Vec<String>> {
+        let state = self.lock()?;
+        let mut keys: Vec<(&String, i32)> = state
+            .entries
+            .iter()
+            .filter_map(|(key, entry)| match &entry.slot {
+                Slot::Game(cached) if like_matches(pattern, key) => Some((key, cached.hit_count)),
+                _ => None,
+            })
+            .collect();
+
+        keys.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+        Ok(keys.into_iter().take(limit).map(|(key, _)| key.clone()).collect())
+    }
+
+    async fn list(&self, offset: usize, limit: usize, order: CacheOrder) -> Result<Vec<CacheEntrySummary>> {
+        let state = self.lock()?;
+        let mut games: Vec<&CachedGame> = state
+            .entries
+            .values()
+            .filter_map(|entry| match &entry.slot {
+                Slot::Game(cached) => Some(&**cached),
+                Slot::NoResult { .. } => None,
+            })
+            .collect();
+
+        games.sort_by(|a, b| {
+            let first = match order {
+                CacheOrder::Newest => b.cached_at.cmp(&a.cached_at),
+                CacheOrder::MostHit => b.hit_count.cmp(&a.hit_count),
+                CacheOrder::Alphabetical => std::cmp::Ordering::Equal,
+            };
+            first.then_with(|| a.query.cmp(&b.query))
+        });
+
+        Ok(games
+            .into_iter()
+            .skip(offset)
+            .take(limit)
+            .map(|cached| CacheEntrySummary {
+                query: cached.query.clone(),
+                name: cached.game.name.clone(),
+                provider: cached.game.provider.clone(),
+                hit_count: cached.hit_count,
+                cached_at: cached.cached_at,
+                score: cached.score,
+            })
+            .collect())
+    }
+
+    async fn alias(&self, key: &str) -> Result<Option<String>> {
+        Ok(self.lock()?.aliases.get(&normalize_query(key)).cloned())
+    }
+
+    async fn save_alias(&self, alias: &str, target: &str) -> Result<()> {
+        let mut state = self.lock()?;
+        let state = &mut *state;
+        // Capped like entries: make room from aliases whose target is gone
+        if state.aliases.len() >= self.capacity {
+            let entries = &state.entries;
+            state.aliases.retain(|_, target| entries.contains_key(target));
+            if state.aliases.len() >= self.capacity {
+                return Ok(());
+            }
+        }
+        state.aliases.insert(normalize_query(alias), normalize_query(target));
+        Ok(())
+    }
+
+    async fn save_no_result(&self, query: &str, ttl: Duration) -> Result<()> {
+        let key = normalize_query(query);
+        let mut state = self.lock()?;
+
+        // Never shadow a real cached game with a tombstone
+        if let Some(Entry { slot: Slot::Game(_), .. }) = state.entries.get(&key) {
+            return Ok(());
+        }
+
+        self.insert(&mut state, key, Slot::NoResult { expires_at: Utc::now() + ttl });
+        Ok(())
+    }
+
+    async fn is_no_result(&self, query: &str) -> Result<bool> {
+        let key = normalize_query(query);
+        let state = self.lock()?;
+
+        Ok(matches!(
+            state.entries.get(&key),
+            Some(Entry { slot: Slot::NoResult { expires_at }, .. }) if *expires_at > Utc::now()
+        ))
+    }
+
+    async fn schema_baseline(&self, provider: &str) -> Result<Option<Fingerprint>> {
+        Ok(self.lock()?.schemas.get(provider).cloned())
+    }
+
+    async fn save_schema_baseline(&self, provider: &str, fingerprint: &Fingerprint) -> Result<()> {
+        self.lock()?.schemas.insert(provider.to_string(), fingerprint.clone());
+        Ok(())
+    }
+
+    fn capacity(&self) -> Option<usize> {
+        Some(self.capacity)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn game(name: &str) -> GameResult {
+        GameResult::new("steam", name, name)
+    }
+
+    #[tokio::test]
+    async fn test_memory_save_get_and_hits() {
+        let cache = MemoryCache::default();
+        cache.save_scored("Zelda", &game("Zelda"), &[game("Zelda II").into()], 92.0).await.unwrap();
+        cache.increment_hit("zelda ").await.unwrap();
+
+        let cached = cache.get("ZELDA").await.unwrap().unwrap();
+        assert_eq!(cached.game.name, "Zelda");
+        assert_eq!(cached.alternatives.len(), 1);
+        assert_eq!(cached.score, Some(92.0));
+        assert_eq!(cached.hit_count, 1);
+
+        let stats = cache.stats().await.unwrap();
+        assert_eq!(stats.total_entries, 1);
+        assert_eq!(stats.total_hits, 1);
+        assert_eq!((stats.added_last_24h, stats.age_histogram.under_1d), (1, 1));
+        assert_eq!(stats.top_queries[0].query, "zelda");
+        assert_eq!((stats.data_bytes, stats.file_bytes), (None, None));
+    }
+
+    #[tokio::test]
+    async fn test_memory_cleanup_by_access_time() {
+        let cache = MemoryCache::default();
+        cache.save("hades", &game("Hades"), &[]).await.unwrap();
+        cache.save("zelda", &game("Zelda"), &[]).await.unwrap();
+        for entry in cache.lock().unwrap().entries.values_mut() {
+            if let Slot::Game(cached) = &mut entry.slot {
+                cached.cached_at -= Duration::days(60);
+            }
+        }
+        cache.increment_hit("hades").await.unwrap();
+
+        assert_eq!(cache.cleanup(30).await.unwrap(), 1);
+        assert!(cache.get("hades").await.unwrap().is_some());
+        assert_eq!(cache.cleanup_by_insert_age(30).await.unwrap(), 1);
+        assert!(cache.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_memory_capacity_evicts_lru() {
+        let cache = MemoryCache::new(2);
+        cache.save("a", &game("A"), &[]).await.unwrap();
+        cache.save("b", &game("B"), &[]).await.unwrap();
+
+        // Touch "a" so "b" becomes least recently used
+        cache.get("a").await.unwrap();
+        cache.save("c", &game("C"), &[]).await.unwrap();
+
+        assert_eq!(cache.len(), 2);
+        assert!(cache.get("a").await.unwrap().is_some());
+        assert!(cache.get("b").await.unwrap().is_none());
+        assert!(cache.get("c").await.unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_memory_delete() {
+        let cache = MemoryCache::default();
+        for query in ["hades", "hades|fr", "hades ii", "zelda"] {
+            cache.save(query, &game(query), &[]).await.unwrap();
+        }
+        cache.save_no_result("kekw", Duration::hours(1)).await.unwrap();
+
+        assert!(cache.delete("HADES").await.unwrap());
+        assert!(!cache.delete("hades").await.unwrap());
+        assert!(cache.delete("kekw").await.unwrap());
+        assert!(!cache.is_no_result("kekw").await.unwrap());
+
+        assert_eq!(cache.delete_matching("hades%").await.unwrap(), 2);
+        assert_eq!(cache.len(), 1);
+        assert!(cache.get("zelda").await.unwrap().is_some());
+
+        for query in ["hades", "hades|fr", "hades ii"] {
+            cache.save(query, &game(query), &[]).await.unwrap();
+        }
+        assert_eq!(cache.invalidate_where("Hades", &["hades|%".to_string()]).await.unwrap(), 2);
+        assert!(cache.get("hades ii").await.unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_memory_aliases() {
+        let cache = MemoryCache::new(2);
+        for (query, hits) in [("hades", 1), ("hades ii", 2)] {
+            cache.save(query, &game(query), &[]).await.unwrap();
+            for _ in 0..hits {
+                cache.increment_hit(query).await.unwrap();
+            }
+        }
+        assert_eq!(cache.keys_matching("had%", 10).await.unwrap(), vec!["hades ii", "hades"]);
+
+        cache.save_alias("Hadess", "hades").await.unwrap();
+        assert_eq!(cache.alias("hadess").await.unwrap().as_deref(), Some("hades"));
+        assert_eq!(cache.stats().await.unwrap().aliases, 1);
+
+        // Full: only aliases of evicted entries make room
+        cache.save_alias("hades 2", "hades ii").await.unwrap();
+        cache.save_alias("hadez", "hades").await.unwrap();
+        assert_eq!(cache.alias("hadez").await.unwrap(), None);
+        cache.save("zelda", &game("zelda"), &[]).await.unwrap();
+        cache.save_alias("hadez", "hades").await.unwrap();
+        assert_eq!(cache.stats().await.unwrap().aliases, 2);
+
+        assert_eq!(cache.invalidate_where("hades", &[]).await.unwrap(), 0);
+        assert_eq!(cache.alias("hadez").await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_memory_no_result_tombstone() {
+        let cache = MemoryCache::default();
+        cache.save_no_result("kekw", Duration::hours(1)).await.unwrap();
+        assert!(cache.is_no_result("KEKW").await.unwrap());
+        assert!(cache.get("kekw").await.unwrap().is_none());
+
+        // Real entry replaces the tombstone and is never shadowed by one
+        cache.save("kekw", &game("Kekw"), &[]).await.unwrap();
+        cache.save_no_result("kekw", Duration::hours(1)).await.unwrap();
+        assert!(!cache.is_no_result("kekw").await.unwrap());
+        assert!(cache.get("kekw").await.unwrap().is_some());
+
+        cache.save_no_result("gone", Duration::seconds(-1)).await.unwrap();
+        assert!(!cache.is_no_result("gone").await.unwrap());
+        assert_eq!(cache.cleanup(30).await.unwrap(), 1);
+    }
+}
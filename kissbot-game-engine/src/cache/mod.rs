@@ -0,0 +1,684 @@
+pub mod codec;
+pub mod memory;
+pub mod null;
+pub mod sqlite;
+pub mod ttl;
+pub mod write_queue;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::analytics::SearchLogEntry;
+use crate::core::{GameResult, SlimGameResult};
+use crate::error::Result;
+use crate::providers::Fingerprint;
+
+pub use memory::{MemoryCache, DEFAULT_MEMORY_CAPACITY};
+pub use null::NullCache;
+pub use codec::COMPRESSION_LEVELS;
+pub use sqlite::{EvictionPolicy, SqliteCache, SqliteCacheOptions, SCHEMA_VERSION};
+pub use ttl::{TtlDecision, TtlPolicy};
+pub use write_queue::{PendingWrite, WriteRetryConfig, WriteRetryQueue, WriteRetryStats};
+
+/// Cache storage selection
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CacheBackend {
+    /// SQLite database file (":memory:" for a private in-memory database)
+    Sqlite { path: String, options: SqliteCacheOptions },
+    /// In-process LRU cache capped at `capacity` entries, never touches disk
+    Memory { capacity: usize },
+    /// No caching at all
+    None,
+}
+
+impl CacheBackend {
+    /// SQLite database file with the default connection settings
+    pub fn sqlite(path: impl Into<String>) -> Self {
+        CacheBackend::Sqlite { path: path.into(), options: SqliteCacheOptions::default() }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            CacheBackend::Sqlite { .. } => "sqlite",
+            CacheBackend::Memory { .. } => "memory",
+            CacheBackend::None => "none",
+        }
+    }
+
+    /// Build the cache for this backend
+    pub async fn open(&self) -> Result<std::sync::Arc<dyn GameCache>> {
+        Ok(match self {
+            CacheBackend::Sqlite { path, options } => {
+                std::sync::Arc::new(SqliteCache::with_options(path, options.clone()).await?)
+            }
+            CacheBackend::Memory { capacity } => std::sync::Arc::new(MemoryCache::new(*capacity)),
+            CacheBackend::None => std::sync::Arc::new(NullCache),
+        })
+    }
+}
+
+/// Trait for game cache implementations (SQLite, in-memory, etc.)
+#[async_trait]
+pub trait GameCache: Send + Sync {
+    /// Get cached entry for query (normalized)
+    async fn get(&self, query: &str) -> Result<Option<CachedGame>>;
+
+    /// Get cached entry for query if cached less than `max_age` ago, or
+    /// before its own expiry when its TTL set one (older entries are kept,
+    /// `get` still returns them)
+    async fn get_fresh(&self, query: &str, max_age: Duration) -> Result<Option<CachedGame>> {
+        let now = Utc::now();
+        Ok(self.get(query).await?.filter(|cached| cached.is_fresh(Some(max_age), now)))
+    }
+
+    /// Save game result (and alternatives) for query
+    async fn save(&self, query: &str, game: &GameResult, alternatives: &[SlimGameResult]) -> Result<()>;
+
+    /// Save game result along with its ranking score
+    async fn save_scored(
+        &self,
+        query: &str,
+        game: &GameResult,
+        alternatives: &[SlimGameResult],
+        _score: f64,
+    ) -> Result<()> {
+        self.save(query, game, alternatives).await
+    }
+
+    /// Save a scored result along with the ranker that scored it and its
+    /// adaptive TTL (backends that do not store the TTL never expire the
+    /// entry)
+    async fn save_with_ttl(
+        &self,
+        query: &str,
+        game: &GameResult,
+        alternatives: &[SlimGameResult],
+        score: f64,
+        _ranking_method: &str,
+        _ttl: &TtlDecision,
+    ) -> Result<()> {
+        self.save_scored(query, game, alternatives, score).await
+    }
+
+    /// Increment hit counter for query (backends may buffer it, see `flush`)
+    async fn increment_hit(&self, query: &str) -> Result<()>;
+
+    /// Write buffered updates (hit counts) to storage
+    async fn flush(&self) -> Result<()> {
+        Ok(())
+    }
+
+    /// Most requested queries by hit count, optionally only those hit since `since`
+    async fn top_queries(&self, _limit: usize, _since: Option<DateTime<Utc>>) -> Result<Vec<(String, i32)>> {
+        Ok(Vec::new())
+    }
+
+    /// Get cache statistics
+    async fn stats(&self) -> Result<CacheStats>;
+
+    /// Delete entries neither saved nor hit in the last `max_age_days` and
+    /// expired no-result tombstones, return number deleted
+    async fn cleanup(&self, max_age_days: i64) -> Result<u64>;
+
+    /// Like `cleanup`, but by time since the entry was cached, however
+    /// recently it was hit. Backends not tracking hits use `cleanup`.
+    async fn cleanup_by_insert_age(&self, max_age_days: i64) -> Result<u64> {
+        self.cleanup(max_age_days).await
+    }
+
+    /// Delete the entry (or no-result tombstone) for query, return whether
+    /// there was one
+    async fn delete(&self, _query: &str) -> Result<bool> {
+        Ok(false)
+    }
+
+    /// Delete entries whose key matches a SQL LIKE `pattern` (`%` any run,
+    /// `_` one character, `\` escapes), return number deleted
+    async fn delete_matching(&self, _pattern: &str) -> Result<u64> {
+        Ok(0)
+    }
+
+    /// Delete the entry for query and every entry matching one of the LIKE
+    /// `patterns` as a single mutation, along with aliases from or to those
+    /// keys; return number of entries deleted. Backends that can should make
+    /// it all-or-nothing (the default runs one call at a time)
+    async fn invalidate_where(&self, query: &str, patterns: &[String]) -> Result<u64> {
+        let mut deleted = u64::from(self.delete(query).await?);
+        for pattern in patterns {
+            deleted += self.delete_matching(pattern).await?;
+        }
+        Ok(deleted)
+    }
+
+    /// Check stored entries for corruption and inconsistencies, deleting the
+    /// affected rows when `repair` is set (they are fetched again on the next
+    /// search). Backends without persistent state have nothing to check.
+    async fn integrity_check(&self, _repair: bool) -> Result<IntegrityReport> {
+        Ok(IntegrityReport::default())
+    }
+
+    /// Rewrite stored entries in the backend's current storage format
+    /// (compressed or plain, see `SqliteCacheOptions::compression_level`).
+    /// Backends keeping values in memory have nothing to rewrite.
+    async fn recompress(&self) -> Result<RecompressReport> {
+        Ok(RecompressReport::default())
+    }
+
+    /// Keys of cached games (tombstones excluded) matching a SQL LIKE
+    /// `pattern`, most hit first
+    async fn keys_matching(&self, _pattern: &str, _limit: usize) -> Result<Vec<String>> {
+        Ok(Vec::new())
+    }
+
+    /// Page of cached games (tombstones excluded) in `order`, for admin
+    /// tooling: only the game's name and provider are read from its data,
+    /// alternatives are left alone
+    async fn list(&self, _offset: usize, _limit: usize, _order: CacheOrder) -> Result<Vec<CacheEntrySummary>> {
+        Ok(Vec::new())
+    }
+
+    /// Key whose entry serves `key`, recorded by `save_alias`
+    async fn alias(&self, _key: &str) -> Result<Option<String>> {
+        Ok(None)
+    }
+
+    /// Serve `target`'s entry for `alias` from now on (a near-identical
+    /// spelling). Aliases are kept apart from entries, so they never count
+    /// as cached games.
+    async fn save_alias(&self, _alias: &str, _target: &str) -> Result<()> {
+        Ok(())
+    }
+
+    /// Record that query returned no results, valid for `ttl`
+    async fn save_no_result(&self, _query: &str, _ttl: Duration) -> Result<()> {
+        Ok(())
+    }
+
+    /// Check for an unexpired no-result tombstone
+    async fn is_no_result(&self, _query: &str) -> Result<bool> {
+        Ok(false)
+    }
+
+    /// Blessed response schema for a provider
+    async fn schema_baseline(&self, _provider: &str) -> Result<Option<Fingerprint>> {
+        Ok(None)
+    }
+
+    /// Store the blessed response schema for a provider
+    async fn save_schema_baseline(&self, _provider: &str, _fingerprint: &Fingerprint) -> Result<()> {
+        Ok(())
+    }
+
+    /// Append entries to the search log (only persisted by SQLite)
+    async fn log_searches(&self, _entries: &[SearchLogEntry]) -> Result<()> {
+        Ok(())
+    }
+
+    /// Latest search log entries, newest first
+    async fn recent_searches(&self, _limit: usize) -> Result<Vec<SearchLogEntry>> {
+        Ok(Vec::new())
+    }
+
+    /// Delete search log entries older than `max_age_days`, return number deleted
+    async fn cleanup_log(&self, _max_age_days: i64) -> Result<u64> {
+        Ok(0)
+    }
+
+    /// Whether the backend is usable, checked before replaying failed writes
+    async fn is_healthy(&self) -> bool {
+        self.stats().await.is_ok()
+    }
+
+    /// Max number of entries kept, None when unbounded
+    fn capacity(&self) -> Option<usize> {
+        None
+    }
+}
+
+/// Cached search result
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CachedGame {
+    /// Normalized query
+    pub query: String,
+
+    /// Cached best match
+    pub game: GameResult,
+
+    /// Cached alternatives, compact (full rows of older entries are read as such)
+    #[serde(default)]
+    pub alternatives: Vec<SlimGameResult>,
+
+    /// Ranking score of the cached match (None for legacy rows)
+    #[serde(default)]
+    pub score: Option<f64>,
+
+    /// Ranker that scored the match (None for rows saved without one)
+    #[serde(default)]
+    pub ranking_method: Option<String>,
+
+    /// Number of cache hits
+    pub hit_count: i32,
+
+    /// Adaptive TTL, None for rows saved without one (never refreshed)
+    #[serde(default)]
+    pub ttl: Option<TtlDecision>,
+
+    /// When the entry was cached
+    pub cached_at: DateTime<Utc>,
+}
+
+impl CachedGame {
+    /// Expiry set by the entry's TTL, if any
+    pub fn expires_at(&self) -> Option<DateTime<Utc>> {
+        self.ttl.as_ref().and_then(|ttl| ttl.expires_at)
+    }
+
+    /// Whether the entry can be served at `now`: before its own expiry when
+    /// it has one, otherwise while younger than `max_age` (None: no limit)
+    pub fn is_fresh(&self, max_age: Option<Duration>, now: DateTime<Utc>) -> bool {
+        match (self.expires_at(), max_age) {
+            (Some(expires_at), _) => now < expires_at,
+            (None, Some(max_age)) => now - self.cached_at < max_age,
+            (None, None) => true,
+        }
+    }
+}
+
+/// Cache statistics
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CacheStats {
+    /// Cached games (tombstones excluded)
+    pub total_entries: u64,
+    /// No-result tombstones (expired ones included until cleanup)
+    #[serde(default)]
+    pub no_result_entries: u64,
+    pub total_hits: u64,
+    pub avg_hit_count: f64,
+    pub oldest_entry: Option<DateTime<Utc>>,
+    pub newest_entry: Option<DateTime<Utc>>,
+    /// Failed writes waiting in the engine's retry queue
+    #[serde(default)]
+    pub pending_writes: u64,
+    /// Deferred writes that eventually landed
+    #[serde(default)]
+    pub write_retries_succeeded: u64,
+    /// Failed writes given up on (queue full or attempts exhausted)
+    #[serde(default)]
+    pub write_retries_dropped: u64,
+    /// Results not cached because the row exceeded the max size even
+    /// without alternatives
+    #[serde(default)]
+    pub oversized_rows: u64,
+    /// Operations retried because the database was busy or locked
+    #[serde(default)]
+    pub busy_retries: u64,
+    /// Near-identical spellings served from another query's entry
+    #[serde(default)]
+    pub aliases: u64,
+    /// Share of cache-reading searches answered from the cache since the
+    /// engine started (None before the first one)
+    #[serde(default)]
+    pub hit_rate: Option<f64>,
+    /// Games cached (or refreshed) in the last 24 hours
+    #[serde(default)]
+    pub added_last_24h: u64,
+    /// Games cached (or refreshed) in the last 7 days
+    #[serde(default)]
+    pub added_last_7d: u64,
+    /// Serialized game data, None for backends not storing it serialized
+    #[serde(default)]
+    pub data_bytes: Option<u64>,
+    /// Database file size (WAL excluded), None for in-process backends
+    #[serde(default)]
+    pub file_bytes: Option<u64>,
+    /// Games by time since they were cached
+    #[serde(default)]
+    pub age_histogram: AgeHistogram,
+    /// Most hit games, `STATS_TOP_QUERIES` at most
+    #[serde(default)]
+    pub top_queries: Vec<TopQuery>,
+}
+
+/// Number of most hit queries reported by `GameCache::stats`
+pub const STATS_TOP_QUERIES: usize = 10;
+
+/// Cached games bucketed by age, each bucket excluding the previous ones
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AgeHistogram {
+    pub under_1d: u64,
+    pub under_7d: u64,
+    pub under_30d: u64,
+    pub older: u64,
+}
+
+impl AgeHistogram {
+    /// Count a game cached `age` ago
+    pub fn record(&mut self, age: Duration) {
+        if age < Duration::days(1) {
+            self.under_1d += 1;
+        } else if age < Duration::days(7) {
+            self.under_7d += 1;
+        } else if age < Duration::days(30) {
+            self.under_30d += 1;
+        } else {
+            self.older += 1;
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TopQuery {
+    pub query: String,
+    pub hit_count: i32,
+}
+
+/// Sort order of `GameCache::list`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CacheOrder {
+    /// Most recently cached first
+    #[default]
+    Newest,
+    /// Highest hit count first
+    MostHit,
+    /// By query
+    Alphabetical,
+}
+
+impl CacheOrder {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CacheOrder::Newest => "newest",
+            CacheOrder::MostHit => "most_hit",
+            CacheOrder::Alphabetical => "alphabetical",
+        }
+    }
+}
+
+impl std::str::FromStr for CacheOrder {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.trim().to_lowercase().replace('-', "_").as_str() {
+            "newest" => Ok(CacheOrder::Newest),
+            "most_hit" => Ok(CacheOrder::MostHit),
+            "alphabetical" => Ok(CacheOrder::Alphabetical),
+            other => Err(format!("Invalid cache order '{}' (expected newest, most_hit or alphabetical)", other)),
+        }
+    }
+}
+
+/// Cached game as listed by `GameCache::list`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CacheEntrySummary {
+    /// Normalized query
+    pub query: String,
+    /// Name and provider of the cached match
+    pub name: String,
+    pub provider: String,
+    pub hit_count: i32,
+    pub cached_at: DateTime<Utc>,
+    /// Ranking score of the match (None for legacy rows)
+    pub score: Option<f64>,
+}
+
+/// Outcome of `GameCache::recompress`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecompressReport {
+    /// Rows rewritten (those already in the current format are left alone)
+    pub rows: u64,
+    /// Stored size of their game and alternatives data, before and after
+    pub bytes_before: u64,
+    pub bytes_after: u64,
+    /// Rows left as they are because they don't decode (see `integrity_check`)
+    pub skipped: u64,
+}
+
+/// Outcome of `GameCache::integrity_check`
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IntegrityReport {
+    /// Messages from the storage engine's own check (page or index corruption)
+    pub storage_errors: Vec<String>,
+    /// Keys whose game, alternatives or TTL data does not decode
+    pub undecodable_entries: Vec<String>,
+    /// Keys with an unreadable `cached_at`/`expires_at` timestamp
+    pub bad_timestamps: Vec<String>,
+    /// No-result tombstones without an expiry, which would never expire
+    pub tombstones_without_expiry: Vec<String>,
+    /// Aliases whose target entry is gone
+    #[serde(default)]
+    pub dangling_aliases: Vec<String>,
+    /// Rows deleted by the repair
+    pub repaired: u64,
+}
+
+impl IntegrityReport {
+    /// Inconsistent rows found (storage errors not included)
+    pub fn problems(&self) -> usize {
+        self.undecodable_entries.len()
+            + self.bad_timestamps.len()
+            + self.tombstones_without_expiry.len()
+            + self.dangling_aliases.len()
+    }
+
+    /// Nothing found, or everything found was repaired
+    pub fn is_ok(&self) -> bool {
+        self.storage_errors.is_empty() && self.problems() as u64 == self.repaired
+    }
+}
+
+/// Serialized size of a cache row (game and alternatives JSON)
+pub fn row_size(game: &GameResult, alternatives: &[SlimGameResult]) -> usize {
+    let game_len = serde_json::to_vec(game).map_or(0, |json| json.len());
+    game_len + serde_json::to_vec(alternatives).map_or(0, |json| json.len())
+}
+
+/// Drop trailing alternatives until the row fits in `max_bytes`, returns the
+/// row size, or its size without alternatives when that still does not fit
+pub fn fit_row(game: &GameResult, alternatives: &mut Vec<SlimGameResult>, max_bytes: usize) -> std::result::Result<usize, usize> {
+    loop {
+        let size = row_size(game, alternatives);
+        if size <= max_bytes {
+            return Ok(size);
+        }
+        if alternatives.pop().is_none() {
+            return Err(size);
+        }
+    }
+}
+
+/// Normalize query for cache keys (lowercase, trimmed)
+pub fn normalize_query(query: &str) -> String {
+    query.trim().to_lowercase()
+}
+
+/// `text` as a LIKE pattern matching only itself
+pub fn escape_like(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(c, '%' | '_' | '\\') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// SQLite LIKE semantics (ASCII case-insensitive, `\` as escape) for
+/// backends without SQL
+pub fn like_matches(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    like_from(&pattern, &text)
+}
+
+fn like_from(pattern: &[char], text: &[char]) -> bool {
+    match pattern.split_first() {
+        None => text.is_empty(),
+        Some(('%', rest)) => (0..=text.len()).any(|skip| like_from(rest, &text[skip..])),
+        Some(('_', rest)) => !text.is_empty() && like_from(rest, &text[1..]),
+        Some((c, rest)) => {
+            let (c, rest) = match (c, rest.split_first()) {
+                ('\\', Some((escaped, rest))) => (escaped, rest),
+                _ => (c, rest),
+            };
+            text.first().is_some_and(|t| t.eq_ignore_ascii_case(c)) && like_from(rest, &text[1..])
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn game(name: &str) -> GameResult {
+        GameResult::new("steam", name, name)
+    }
+
+    /// Behavior every persistent backend shares, run against each of them
+    async fn conformance(cache: &dyn GameCache) {
+        let before = Utc::now() - Duration::seconds(1);
+
+        // Keys are normalized; entries round-trip with their alternatives
+        cache.save_scored("  Hades ", &game("Hades"), &[game("Hades II").into()], 97.0).await.unwrap();
+        let cached = cache.get("HADES").await.unwrap().unwrap();
+        assert_eq!(cached.query, "hades");
+        assert_eq!(cached.game.name, "Hades");
+        assert_eq!(cached.alternatives[0].name, "Hades II");
+        assert_eq!((cached.score, cached.hit_count), (Some(97.0), 0));
+        assert!(cached.cached_at >= before && cached.cached_at <= Utc::now());
+        assert!(cache.get("hades ii").await.unwrap().is_none());
+        assert!(cache.get_fresh("hades", Duration::hours(1)).await.unwrap().is_some());
+
+        // The TTL is kept with the entry
+        let ttl = TtlDecision {
+            ttl_secs: 3600,
+            refresh_after: Utc::now() + Duration::hours(1),
+            unchanged_refreshes: 2,
+            reason: "test".to_string(),
+            expires_at: None,
+        };
+        cache.save_with_ttl("celeste", &game("Celeste"), &[], 90.0, "drakon", &ttl).await.unwrap();
+        let cached = cache.get("celeste").await.unwrap().unwrap();
+        assert_eq!(cached.ttl.unwrap().unchanged_refreshes, 2);
+        assert_eq!((cached.score, cached.ranking_method.as_deref()), (Some(90.0), Some("drakon")));
+        assert!(cache.get("hades").await.unwrap().unwrap().ranking_method.is_none());
+
+        // Hits count, and survive a refresh of the entry
+        for _ in 0..3 {
+            cache.increment_hit("hades").await.unwrap();
+        }
+        cache.increment_hit("celeste").await.unwrap();
+        cache.increment_hit("unknown").await.unwrap();
+        cache.save("hades", &game("Hades"), &[]).await.unwrap();
+        assert_eq!(cache.get("hades").await.unwrap().unwrap().hit_count, 3);
+        assert_eq!(
+            cache.top_queries(10, Some(before)).await.unwrap(),
+            vec![("hades".to_string(), 3), ("celeste".to_string(), 1)]
+        );
+
+        // Tombstones: only unexpired ones short-circuit, never over a game
+        cache.save_no_result("kekw", Duration::hours(1)).await.unwrap();
+        cache.save_no_result("gone", Duration::seconds(-1)).await.unwrap();
+        cache.save_no_result("hades", Duration::hours(1)).await.unwrap();
+        assert!(cache.is_no_result("KEKW").await.unwrap());
+        assert!(!cache.is_no_result("gone").await.unwrap());
+        assert!(!cache.is_no_result("hades").await.unwrap());
+        assert!(cache.get("kekw").await.unwrap().is_none());
+
+        let stats = cache.stats().await.unwrap();
+        assert_eq!((stats.total_entries, stats.no_result_entries, stats.total_hits), (2, 2, 4));
+        assert_eq!(stats.avg_hit_count, 2.0);
+        assert!(stats.oldest_entry.unwrap() >= before);
+        assert!(stats.newest_entry.unwrap() >= stats.oldest_entry.unwrap());
+        assert_eq!((stats.added_last_24h, stats.added_last_7d), (2, 2));
+        assert_eq!(stats.age_histogram, AgeHistogram { under_1d: 2, ..Default::default() });
+        assert_eq!(stats.top_queries[0], TopQuery { query: "hades".to_string(), hit_count: 3 });
+
+        // Fresh games stay, the expired tombstone goes
+        assert_eq!(cache.cleanup(30).await.unwrap(), 1);
+        assert_eq!(cache.cleanup_by_insert_age(30).await.unwrap(), 0);
+        assert_eq!(cache.stats().await.unwrap().total_entries, 2);
+
+        // Aliases
+        cache.save_alias("hadez", "hades").await.unwrap();
+        assert_eq!(cache.alias("HADEZ").await.unwrap().as_deref(), Some("hades"));
+        assert_eq!(cache.keys_matching("%e%", 10).await.unwrap(), vec!["hades", "celeste"]);
+        assert_eq!(cache.stats().await.unwrap().aliases, 1);
+
+        // Listing pages through games only, in the requested order
+        let queries = |page: Vec<CacheEntrySummary>| page.into_iter().map(|entry| entry.query).collect::<Vec<_>>();
+        let page = cache.list(0, 10, CacheOrder::MostHit).await.unwrap();
+        assert_eq!((page[0].name.as_str(), page[0].provider.as_str(), page[0].hit_count), ("Hades", "steam", 3));
+        assert_eq!((page[1].query.as_str(), page[1].score), ("celeste", Some(90.0)));
+        assert_eq!(queries(page), ["hades", "celeste"]);
+        assert_eq!(queries(cache.list(0, 10, CacheOrder::Newest).await.unwrap()), ["hades", "celeste"]);
+        assert_eq!(queries(cache.list(0, 1, CacheOrder::Alphabetical).await.unwrap()), ["celeste"]);
+        assert_eq!(queries(cache.list(1, 10, CacheOrder::Alphabetical).await.unwrap()), ["hades"]);
+        assert!(cache.list(2, 10, CacheOrder::Alphabetical).await.unwrap().is_empty());
+
+        // Deletes
+        cache.save("hades|fr", &game("Hades"), &[]).await.unwrap();
+        assert_eq!(cache.invalidate_where("hades", &["hades|%".to_string()]).await.unwrap(), 2);
+        assert!(cache.alias("hadez").await.unwrap().is_none());
+        assert!(cache.delete("celeste").await.unwrap());
+        assert!(!cache.delete("celeste").await.unwrap());
+        assert_eq!(cache.delete_matching("%").await.unwrap(), 1);
+        assert!(!cache.is_no_result("kekw").await.unwrap());
+
+        let stats = cache.stats().await.unwrap();
+        assert_eq!((stats.total_entries, stats.no_result_entries, stats.aliases), (0, 0, 0));
+        assert!(stats.top_queries.is_empty());
+        assert!(cache.integrity_check(false).await.unwrap().is_ok());
+
+        // A per-entry expiry replaces the max age, whichever comes first
+        let expiring = |expires_at| TtlDecision { expires_at: Some(expires_at), ..ttl.clone() };
+        let expired = expiring(Utc::now() - Duration::seconds(1));
+        let lasting = expiring(Utc::now() + Duration::days(3650));
+        cache.save_with_ttl("silksong", &game("Silksong"), &[], 90.0, "drakon", &expired).await.unwrap();
+        cache.save_with_ttl("tetris", &game("Tetris"), &[], 90.0, "drakon", &lasting).await.unwrap();
+        assert!(cache.get("silksong").await.unwrap().is_some());
+        assert!(cache.get_fresh("silksong", Duration::days(365)).await.unwrap().is_none());
+        assert!(cache.get_fresh("tetris", Duration::zero()).await.unwrap().is_some());
+        assert_eq!(cache.cleanup(30).await.unwrap(), 1);
+        assert!(cache.get("silksong").await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_memory_cache_conformance() {
+        conformance(&MemoryCache::default()).await;
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_cache_conformance() {
+        conformance(&SqliteCache::new(":memory:").await.unwrap()).await;
+    }
+
+    #[test]
+    fn test_cache_order_parsing() {
+        assert_eq!("most-hit".parse::<CacheOrder>().unwrap(), CacheOrder::MostHit);
+        for order in [CacheOrder::Newest, CacheOrder::MostHit, CacheOrder::Alphabetical] {
+            assert_eq!(order.as_str().parse::<CacheOrder>().unwrap(), order);
+            assert_eq!(serde_json::to_value(order).unwrap(), order.as_str());
+        }
+        assert!("random".parse::<CacheOrder>().is_err());
+    }
+
+    #[test]
+    fn test_like_matches() {
+        assert!(like_matches("hades", "hades"));
+        assert!(like_matches("HADES", "hades"));
+        assert!(!like_matches("hades", "hades ii"));
+        assert!(like_matches("hades%", "hades ii"));
+        assert!(like_matches("%ii", "hades ii"));
+        assert!(like_matches("h_des", "hades"));
+        assert!(!like_matches("h_des", "hdes"));
+        assert!(like_matches("%", ""));
+
+        // Escaped wildcards only match themselves
+        assert!(like_matches(&escape_like("100%_off"), "100%_off"));
+        assert!(!like_matches(&escape_like("100%_off"), "100% off"));
+        assert!(like_matches(&format!("{}%", escape_like("a\\b")), "a\\bc"));
+    }
+}
@@ -0,0 +1,33 @@
+use async_trait::async_trait;
+
+use crate::cache::{CacheStats, CachedGame, GameCache};
+use crate::core::{GameResult, SlimGameResult};
+use crate::error::Result;
+
+/// Cache that stores nothing: every lookup misses, writes are ignored
+/// and stats stay at zero
+#[derive(Debug, Default, Clone, Copy)]
+pub struct NullCache;
+
+#[async_trait]
+impl GameCache for NullCache {
+    async fn get(&self, _query: &str) -> Result<Option<CachedGame>> {
+        Ok(None)
+    }
+
+    async fn save(&self, _query: &str, _game: &GameResult, _alternatives: &[SlimGameResult]) -> Result<()> {
+        Ok(())
+    }
+
+    async fn increment_hit(&self, _query: &str) -> Result<()> {
+        Ok(())
+    }
+
+    async fn stats(&self) -> Result<CacheStats> {
+        Ok(CacheStats::default())
+    }
+
+    async fn cleanup(&self, _max_age_days: i64) -> Result<u64> {
+        Ok(0)
+    }
+}
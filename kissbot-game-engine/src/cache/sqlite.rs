@@ -0,0 +1,2501 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
+use parking_lot::{Condvar, Mutex};
+use rusqlite::{params, Connection, OptionalExtension, Transaction, TransactionBehavior};
+use serde::{Deserialize, Serialize};
+use std::ops::{Deref, DerefMut};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+use crate::analytics::SearchLogEntry;
+use crate::cache::codec::{self, StoredJson};
+use crate::cache::{
+    like_matches, normalize_query, AgeHistogram, CacheEntrySummary, CacheOrder, CacheStats, CachedGame, GameCache,
+    IntegrityReport, RecompressReport, TopQuery, TtlDecision, STATS_TOP_QUERIES,
+};
+use crate::core::{GameResult, SlimGameResult};
+use crate::error::{GameEngineError, Phase, Result, ResultExt};
+use crate::providers::{Fingerprint, RetryPolicy};
+
+/// Journal modes accepted by `SqliteCacheOptions::journal_mode`
+pub const JOURNAL_MODES: &[&str] = &["delete", "truncate", "persist", "memory", "wal", "off"];
+
+/// Levels accepted by `SqliteCacheOptions::synchronous`
+pub const SYNCHRONOUS_LEVELS: &[&str] = &["off", "normal", "full", "extra"];
+
+/// Connection settings, applied on open. The defaults let the Python bot and
+/// the engine share `kissbot.db`: WAL keeps readers and the writer from
+/// blocking each other, and the busy timeout waits out the other process's
+/// writes instead of failing with "database is locked".
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SqliteCacheOptions {
+    /// `journal_mode` pragma (in-memory databases always use "memory")
+    pub journal_mode: String,
+    /// How long a statement waits for another connection's lock
+    pub busy_timeout_ms: u64,
+    /// `synchronous` pragma; "normal" is safe with WAL
+    pub synchronous: String,
+    pub foreign_keys: bool,
+    /// Read connections opened on a database file
+    pub read_connections: usize,
+    /// Retries of an operation that failed with busy/locked once the busy
+    /// timeout ran out (0 disables them)
+    pub busy_retries: u32,
+    /// Delay before the first retry, doubled (and jittered) on each one
+    pub busy_retry_delay_ms: u64,
+    /// Rows kept in `game_cache` (tombstones included); a save that goes
+    /// over evicts down to 90% of it. None keeps everything.
+    pub max_entries: Option<u64>,
+    /// Which rows go first when over `max_entries`
+    pub eviction: EvictionPolicy,
+    /// Buffer hit counts in memory and write them in one transaction per
+    /// flush; false writes every hit as it happens (nothing lost on a crash)
+    pub batch_hits: bool,
+    /// Buffered hits are written at least this often
+    pub hit_flush_interval_ms: u64,
+    /// ...or as soon as this many queries have buffered hits
+    pub hit_flush_max_keys: usize,
+    /// zstd level of the game and alternatives JSON of new rows (needs the
+    /// `compression` feature). None stores plain JSON, the only format the
+    /// Python bot reads; rows of either format are read back.
+    pub compression_level: Option<i32>,
+}
+
+/// Order in which rows are evicted when the cache is over its cap
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EvictionPolicy {
+    /// Least recently cached first
+    OldestFirst,
+    /// Fewest hits first, oldest first among equals
+    LeastHit,
+    /// Lowest hits per day since the last hit (or since cached), so a
+    /// popular entry gone cold eventually makes room too
+    #[default]
+    HitsAndAge,
+}
+
+impl EvictionPolicy {
+    /// `ORDER BY` clause listing rows to evict first. Rows with unreadable
+    /// timestamps get a NULL score and go first.
+    fn order_by(self) -> &'static str {
+        match self {
+            EvictionPolicy::OldestFirst => "cached_at ASC, query ASC",
+            EvictionPolicy::LeastHit => "hit_count ASC, cached_at ASC, query ASC",
+            EvictionPolicy::HitsAndAge => {
+                "(hit_count + 1.0) / (julianday('now') - julianday(COALESCE(last_hit_at, cached_at)) + 1.0) ASC,
+                 cached_at ASC, query ASC"
+            }
+        }
+    }
+}
+
+/// Rows deleted per statement when evicting
+const EVICTION_BATCH: u64 = 500;
+
+/// Rows rewritten per transaction by `recompress`
+const RECOMPRESS_BATCH: u64 = 500;
+
+impl Default for SqliteCacheOptions {
+    fn default() -> Self {
+        Self {
+            journal_mode: "wal".to_string(),
+            busy_timeout_ms: 5000,
+            synchronous: "normal".to_string(),
+            foreign_keys: true,
+            read_connections: 4,
+            busy_retries: 3,
+            busy_retry_delay_ms: 50,
+            max_entries: None,
+            eviction: EvictionPolicy::default(),
+            batch_hits: true,
+            hit_flush_interval_ms: 5000,
+            hit_flush_max_keys: 256,
+            compression_level: None,
+        }
+    }
+}
+
+impl SqliteCacheOptions {
+    /// Per-connection pragmas (`journal_mode` is set once, on the writer)
+    fn apply(&self, conn: &Connection) -> Result<()> {
+        conn.busy_timeout(std::time::Duration::from_millis(self.busy_timeout_ms))?;
+        conn.pragma_update(None, "synchronous", self.synchronous.as_str())?;
+        conn.pragma_update(None, "foreign_keys", self.foreign_keys)?;
+        Ok(())
+    }
+
+    fn busy_retry_policy(&self) -> RetryPolicy {
+        RetryPolicy {
+            max_attempts: self.busy_retries.saturating_add(1),
+            base_delay: std::time::Duration::from_millis(self.busy_retry_delay_ms),
+            max_delay: std::time::Duration::from_secs(1),
+            jitter: 0.5,
+            retryable: GameEngineError::is_database_busy,
+        }
+    }
+}
+
+/// Retries of operations that hit a busy or locked database, separate from
+/// the engine's write retry queue: these happen inside a single cache call
+struct BusyRetry {
+    policy: RetryPolicy,
+    retried: AtomicU64,
+}
+
+impl BusyRetry {
+    /// Run `op` until it succeeds, fails with another error or runs out of
+    /// attempts (blocking: sleeps on the calling thread)
+    fn run<T>(&self, mut op: impl FnMut() -> Result<T>) -> Result<T> {
+        let max_attempts = self.policy.max_attempts.max(1);
+        let mut attempt = 1;
+
+        loop {
+            match op() {
+                Ok(value) => return Ok(value),
+                Err(e) if e.is_database_busy() && attempt < max_attempts => {
+                    self.retried.fetch_add(1, Ordering::Relaxed);
+                    let delay = self.policy.delay(attempt);
+                    tracing::debug!("🔒 Database busy (attempt {}/{}), retrying in {:?}", attempt, max_attempts, delay);
+                    std::thread::sleep(delay);
+                    attempt += 1;
+                }
+                Err(e) if e.is_database_busy() && max_attempts > 1 => return Err(lock_persisted(e, attempt)),
+                Err(e) => return Err(e),
+            }
+        }
+    }
+}
+
+/// Busy/locked error as `DatabaseLocked`, keeping its context
+fn lock_persisted(err: GameEngineError, attempts: u32) -> GameEngineError {
+    let context = err.context().cloned();
+    let GameEngineError::Database(source) = err.into_root() else {
+        unreachable!("checked by is_database_busy");
+    };
+    let locked = GameEngineError::DatabaseLocked { attempts, source };
+    match context {
+        Some(context) => locked.with_context(context),
+        None => locked,
+    }
+}
+
+/// SQLite-backed game cache (schema compatible with Python `kissbot.db`).
+///
+/// Queries run on tokio's blocking thread pool, never on the async executor.
+/// Writes go through a single connection; reads use their own small pool
+/// (or share the writer for ":memory:", where every connection would be a
+/// separate database).
+pub struct SqliteCache {
+    writer: Arc<ConnectionPool>,
+    readers: Arc<ConnectionPool>,
+    busy: Arc<BusyRetry>,
+    max_entries: Option<u64>,
+    eviction: EvictionPolicy,
+    compression_level: Option<i32>,
+    /// None when hits are written one by one
+    hits: Option<Arc<HitBuffer>>,
+}
+
+/// Hits of one query not written yet
+#[derive(Debug, Clone, Copy)]
+struct PendingHits {
+    count: u32,
+    last_hit_at: DateTime<Utc>,
+}
+
+/// Hit counts buffered in memory, written in one transaction when the
+/// interval elapses (background task), when too many queries are pending,
+/// before statements that read them (`stats`, `top_queries`, cleanup,
+/// eviction) and on drop
+struct HitBuffer {
+    pending: Mutex<std::collections::HashMap<String, PendingHits>>,
+    /// Held through a flush: two at once would both write the hits the
+    /// first has not settled yet
+    flushing: tokio::sync::Mutex<()>,
+    max_keys: usize,
+    writer: Arc<ConnectionPool>,
+    busy: Arc<BusyRetry>,
+}
+
+impl HitBuffer {
+    /// Buffer a hit, return whether the buffer is due for a flush
+    fn record(&self, key: String) -> bool {
+        let mut pending = self.pending.lock();
+        let now = Utc::now();
+        let hits = pending.entry(key).or_insert(PendingHits { count: 0, last_hit_at: now });
+        hits.count += 1;
+        hits.last_hit_at = now;
+        pending.len() >= self.max_keys
+    }
+
+    fn pending_count(&self, key: &str) -> u32 {
+        self.pending.lock().get(key).map_or(0, |hits| hits.count)
+    }
+
+    /// Drop buffered hits of deleted entries, so they do not land on an
+    /// entry saved again under the same key
+    fn forget(&self, doomed: impl Fn(&str) -> bool) {
+        self.pending.lock().retain(|key, _| !doomed(key));
+    }
+
+    /// Write the buffered hits in one transaction, one flush at a time.
+    /// They stay buffered until written, so a failed flush loses nothing and
+    /// is retried by the next.
+    async fn flush(&self) -> Result<()> {
+        let _flushing = self.flushing.lock().await;
+        let batch: Vec<(String, PendingHits)> = self.pending.lock().iter().map(|(k, h)| (k.clone(), *h)).collect();
+        if batch.is_empty() {
+            return Ok(());
+        }
+
+        let rows = batch.clone();
+        run_blocking(self.writer.clone(), self.busy.clone(), move |conn| apply_hits(conn, &rows)).await?;
+        self.settle(&batch);
+        Ok(())
+    }
+
+    /// Blocking flush, for drop
+    fn flush_blocking(&self) -> Result<()> {
+        let batch: Vec<(String, PendingHits)> = self.pending.lock().drain().collect();
+        if batch.is_empty() {
+            return Ok(());
+        }
+        let mut conn = self.writer.acquire();
+        self.busy.run(|| apply_hits(&mut conn, &batch))
+    }
+
+    /// Remove written hits, keeping those recorded during the flush
+    fn settle(&self, written: &[(String, PendingHits)]) {
+        let mut pending = self.pending.lock();
+        for (key, hits) in written {
+            if let Some(current) = pending.get_mut(key) {
+                current.count = current.count.saturating_sub(hits.count);
+                if current.count == 0 {
+                    pending.remove(key);
+                }
+            }
+        }
+    }
+}
+
+impl Drop for HitBuffer {
+    fn drop(&mut self) {
+        if let Err(e) = self.flush_blocking() {
+            tracing::warn!("Failed to write buffered cache hits: {}", e.diagnostic());
+        }
+    }
+}
+
+fn apply_hits(conn: &mut Connection, hits: &[(String, PendingHits)]) -> Result<()> {
+    let tx = conn
+        .transaction_with_behavior(TransactionBehavior::Immediate)
+        .ctx_phase(Phase::CacheWrite)?;
+    {
+        let mut stmt = tx
+            .prepare_cached(
+                "UPDATE game_cache SET hit_count = hit_count + ?2, last_hit_at = ?3, last_accessed = ?3 WHERE query = ?1",
+            )
+            .ctx_phase(Phase::CacheWrite)?;
+        for (key, pending) in hits {
+            stmt.execute(params![key, pending.count, pending.last_hit_at.to_rfc3339()])
+                .ctx_phase(Phase::CacheWrite)?;
+        }
+    }
+    tx.commit().ctx_phase(Phase::CacheWrite)?;
+    Ok(())
+}
+
+/// Write buffered hits every `interval` until the cache is dropped
+async fn flush_hits_periodically(hits: std::sync::Weak<HitBuffer>, interval: std::time::Duration) {
+    let mut ticker = tokio::time::interval(interval);
+    ticker.tick().await;
+    loop {
+        ticker.tick().await;
+        let Some(hits) = hits.upgrade() else { break };
+        if let Err(e) = hits.flush().await {
+            tracing::warn!("Failed to write buffered cache hits, retrying later: {}", e.diagnostic());
+        }
+    }
+}
+
+impl SqliteCache {
+    /// Open (or create) cache database at path (":memory:" for in-memory)
+    pub async fn new(db_path: &str) -> Result<Self> {
+        Self::with_options(db_path, SqliteCacheOptions::default()).await
+    }
+
+    /// Open (or create) cache database with explicit connection settings
+    pub async fn with_options(db_path: &str, options: SqliteCacheOptions) -> Result<Self> {
+        let path = db_path.to_string();
+        let interval = std::time::Duration::from_millis(options.hit_flush_interval_ms.max(1));
+        let cache = tokio::task::spawn_blocking(move || Self::open(&path, &options))
+            .await
+            .map_err(|e| GameEngineError::Cache(format!("SQLite open task failed: {}", e)))??;
+        if let Some(hits) = &cache.hits {
+            tokio::spawn(flush_hits_periodically(Arc::downgrade(hits), interval));
+        }
+        Ok(cache)
+    }
+
+    fn open(db_path: &str, options: &SqliteCacheOptions) -> Result<Self> {
+        if let Some(level) = options.compression_level {
+            codec::check_level(level).map_err(|e| GameEngineError::Cache(format!("Compression level {}: {}", level, e)))?;
+        }
+        let in_memory = is_in_memory(db_path);
+        let mut conn = Connection::open(db_path)?;
+        options.apply(&conn)?;
+        if !in_memory {
+            let mode: String =
+                conn.pragma_update_and_check(None, "journal_mode", options.journal_mode.as_str(), |row| row.get(0))?;
+            if !mode.eq_ignore_ascii_case(&options.journal_mode) {
+                tracing::warn!("⚠️ SQLite journal mode is {} ({} requested)", mode, options.journal_mode);
+            }
+        }
+
+        migrate(&mut conn)?;
+
+        let writer = Arc::new(ConnectionPool::new(vec![conn]));
+        let readers = if in_memory {
+            writer.clone()
+        } else {
+            let conns = (0..options.read_connections.max(1))
+                .map(|_| {
+                    let conn = Connection::open(db_path)?;
+                    options.apply(&conn)?;
+                    Ok(conn)
+                })
+                .collect::<Result<Vec<_>>>()?;
+            Arc::new(ConnectionPool::new(conns))
+        };
+
+        let busy = Arc::new(BusyRetry {
+            policy: options.busy_retry_policy(),
+            retried: AtomicU64::new(0),
+        });
+
+        let hits = options.batch_hits.then(|| {
+            Arc::new(HitBuffer {
+                pending: Mutex::new(std::collections::HashMap::new()),
+                flushing: tokio::sync::Mutex::new(()),
+                max_keys: options.hit_flush_max_keys.max(1),
+                writer: writer.clone(),
+                busy: busy.clone(),
+            })
+        });
+
+        Ok(Self {
+            writer,
+            readers,
+            busy,
+            max_entries: options.max_entries,
+            eviction: options.eviction,
+            compression_level: options.compression_level,
+            hits,
+        })
+    }
+
+    /// Run `f` on a read connection, off the async executor
+    async fn read<T, F>(&self, f: F) -> Result<T>
+    where
+        F: Fn(&mut Connection) -> Result<T> + Send + 'static,
+        T: Send + 'static,
+    {
+        run_blocking(self.readers.clone(), self.busy.clone(), f).await
+    }
+
+    /// Run `f` on the write connection, off the async executor
+    async fn write<T, F>(&self, f: F) -> Result<T>
+    where
+        F: Fn(&mut Connection) -> Result<T> + Send + 'static,
+        T: Send + 'static,
+    {
+        run_blocking(self.writer.clone(), self.busy.clone(), f).await
+    }
+
+    /// Run `f` in a transaction on the write connection: either all of its
+    /// statements land or none do (rolled back when `f` fails)
+    async fn write_tx<T, F>(&self, f: F) -> Result<T>
+    where
+        F: Fn(&Transaction) -> Result<T> + Send + 'static,
+        T: Send + 'static,
+    {
+        self.write(move |conn| {
+            // Immediate: take the write lock up front, so a busy database
+            // fails (and is retried) before any statement ran
+            let tx = conn
+                .transaction_with_behavior(TransactionBehavior::Immediate)
+                .ctx_phase(Phase::CacheWrite)?;
+            let value = f(&tx)?;
+            tx.commit().ctx_phase(Phase::CacheWrite)?;
+            Ok(value)
+        })
+        .await
+    }
+
+    async fn write_entry(
+        &self,
+        query: &str,
+        game: &GameResult,
+        alternatives: &[SlimGameResult],
+        score: Option<f64>,
+        ranking_method: Option<&str>,
+        ttl: Option<&TtlDecision>,
+    ) -> Result<()> {
+        let key = normalize_query(query);
+        let ranking_method = ranking_method.map(String::from);
+        let game_data = StoredJson::encode(game.to_json().ctx_phase(Phase::CacheWrite)?, self.compression_level)?;
+        let alternatives =
+            StoredJson::encode(serde_json::to_string(alternatives).ctx_phase(Phase::CacheWrite)?, self.compression_level)?;
+        let expires_at = ttl.and_then(|ttl| ttl.expires_at).map(|at| at.to_rfc3339());
+        let ttl = ttl.map(serde_json::to_string).transpose().ctx_phase(Phase::CacheWrite)?;
+        let (max_entries, eviction) = (self.max_entries, self.eviction);
+        if max_entries.is_some() {
+            // Eviction ranks rows by their hits
+            self.flush().await?;
+        }
+
+        // One transaction: a busy database replays or rolls back the upsert
+        // and the eviction together
+        self.write_tx(move |tx| {
+            tx.execute(
+                "INSERT INTO game_cache (query, game_data, alternatives, hit_count, cached_at, score, ttl, last_accessed, ranking_method, expires_at)
+                 VALUES (?1, ?2, ?3, 0, ?4, ?5, ?6, ?4, ?7, ?8)
+                 ON CONFLICT(query) DO UPDATE SET
+                    game_data = excluded.game_data,
+                    alternatives = excluded.alternatives,
+                    cached_at = excluded.cached_at,
+                    last_accessed = excluded.last_accessed,
+                    score = excluded.score,
+                    ranking_method = excluded.ranking_method,
+                    ttl = excluded.ttl,
+                    no_result = 0,
+                    expires_at = excluded.expires_at",
+                params![key, game_data, alternatives, Utc::now().to_rfc3339(), score, ttl, ranking_method, expires_at],
+            )
+            .ctx_phase(Phase::CacheWrite)?;
+
+            if let Some(max_entries) = max_entries {
+                evict(tx, max_entries, eviction).ctx_phase(Phase::CacheWrite)?;
+            }
+            Ok(())
+        })
+        .await
+    }
+
+    /// Delete games whose `timestamp` expression is older than
+    /// `max_age_days`, and expired games and no-result tombstones, in one
+    /// transaction
+    async fn cleanup_older_than(&self, timestamp: &'static str, max_age_days: i64) -> Result<u64> {
+        // Buffered hits count as accesses
+        self.flush().await?;
+        let cutoff = (Utc::now() - Duration::days(max_age_days)).to_rfc3339();
+
+        let (expired, deleted) = self
+            .write_tx(move |tx| {
+                let expired = tx
+                    .execute("DELETE FROM game_cache WHERE expires_at < ?1", params![Utc::now().to_rfc3339()])
+                    .ctx_phase(Phase::CacheWrite)?;
+                let deleted = tx
+                    .execute(
+                        &format!("DELETE FROM game_cache WHERE no_result = 0 AND {} < ?1", timestamp),
+                        params![cutoff],
+                    )
+                    .ctx_phase(Phase::CacheWrite)?;
+                Ok((expired, deleted))
+            })
+            .await?;
+
+        if deleted > 0 {
+            tracing::info!("🧹 Cleaned up {} cache entries older than {} days", deleted, max_age_days);
+        }
+        if expired > 0 {
+            tracing::info!("🧹 Cleaned up {} expired entries and no-result entries", expired);
+        }
+
+        Ok((deleted + expired) as u64)
+    }
+
+    fn forget_hits(&self, doomed: impl Fn(&str) -> bool) {
+        if let Some(hits) = &self.hits {
+            hits.forget(doomed);
+        }
+    }
+
+    /// Write connection, blocking until it is free
+    #[cfg(test)]
+    pub(crate) fn connection(&self) -> PooledConnection {
+        self.writer.acquire()
+    }
+}
+
+fn is_in_memory(db_path: &str) -> bool {
+    db_path.is_empty() || db_path.contains(":memory:") || db_path.contains("mode=memory")
+}
+
+/// Run `f` on a pooled connection in the blocking thread pool, retried while
+/// the database is busy (the connection is kept between attempts)
+async fn run_blocking<T, F>(pool: Arc<ConnectionPool>, busy: Arc<BusyRetry>, f: F) -> Result<T>
+where
+    F: Fn(&mut Connection) -> Result<T> + Send + 'static,
+    T: Send + 'static,
+{
+    tokio::task::spawn_blocking(move || {
+        let mut conn = pool.acquire();
+        busy.run(|| f(&mut conn))
+    })
+        .await
+        .map_err(|e| GameEngineError::Cache(format!("SQLite task failed: {}", e)))?
+}
+
+/// Idle connections, handed out one at a time. A connection is returned on
+/// drop, including when the task using it panics.
+struct ConnectionPool {
+    idle: Mutex<Vec<Connection>>,
+    returned: Condvar,
+}
+
+impl ConnectionPool {
+    fn new(conns: Vec<Connection>) -> Self {
+        Self {
+            idle: Mutex::new(conns),
+            returned: Condvar::new(),
+        }
+    }
+
+    /// Take an idle connection, blocking until one is returned
+    fn acquire(self: &Arc<Self>) -> PooledConnection {
+        let mut idle = self.idle.lock();
+        loop {
+            if let Some(conn) = idle.pop() {
+                return PooledConnection {
+                    conn: Some(conn),
+                    pool: self.clone(),
+                };
+            }
+            self.returned.wait(&mut idle);
+        }
+    }
+}
+
+pub(crate) struct PooledConnection {
+    conn: Option<Connection>,
+    pool: Arc<ConnectionPool>,
+}
+
+impl Deref for PooledConnection {
+    type Target = Connection;
+
+    fn deref(&self) -> &Connection {
+        self.conn.as_ref().expect("connection present until drop")
+    }
+}
+
+impl DerefMut for PooledConnection {
+    fn deref_mut(&mut self) -> &mut Connection {
+        self.conn.as_mut().expect("connection present until drop")
+    }
+}
+
+impl Drop for PooledConnection {
+    fn drop(&mut self) {
+        if let Some(conn) = self.conn.take() {
+            self.pool.idle.lock().push(conn);
+            self.pool.returned.notify_one();
+        }
+    }
+}
+
+/// Schema change, applied once per database
+enum Migration {
+    /// Statements run as is
+    Sql(&'static str),
+    /// Column added unless already there (unversioned databases from older
+    /// binaries may have it)
+    AddColumn {
+        table: &'static str,
+        column: &'static str,
+        decl: &'static str,
+    },
+}
+
+impl Migration {
+    fn apply(&self, conn: &Connection) -> Result<()> {
+        match self {
+            Migration::Sql(sql) => Ok(conn.execute_batch(sql)?),
+            Migration::AddColumn { table, column, decl } => ensure_column(conn, table, column, decl),
+        }
+    }
+}
+
+/// Schema history, in order: version N is the database after step N. Append
+/// new steps, never edit or reorder released ones.
+const MIGRATIONS: &[Migration] = &[
+    // 1: the table shared with the Python bot
+    Migration::Sql(
+        "CREATE TABLE IF NOT EXISTS game_cache (
+            query TEXT PRIMARY KEY,
+            game_data TEXT NOT NULL,
+            alternatives TEXT,
+            hit_count INTEGER NOT NULL DEFAULT 0,
+            cached_at TEXT NOT NULL
+        );
+        CREATE INDEX IF NOT EXISTS idx_game_cache_cached_at ON game_cache(cached_at);",
+    ),
+    // 2: engine-only tables
+    Migration::Sql(
+        "CREATE TABLE IF NOT EXISTS provider_schema (
+            provider TEXT PRIMARY KEY,
+            fingerprint TEXT NOT NULL,
+            blessed_at TEXT NOT NULL
+        );
+        CREATE TABLE IF NOT EXISTS search_log (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            query TEXT NOT NULL,
+            resolved_name TEXT,
+            score REAL,
+            provider TEXT,
+            from_cache INTEGER NOT NULL,
+            latency_ms REAL NOT NULL,
+            timestamp TEXT NOT NULL
+        );
+        CREATE INDEX IF NOT EXISTS idx_search_log_timestamp ON search_log(timestamp);",
+    ),
+    // 3-7: game_cache columns
+    Migration::AddColumn { table: "game_cache", column: "no_result", decl: "INTEGER NOT NULL DEFAULT 0" },
+    Migration::AddColumn { table: "game_cache", column: "expires_at", decl: "TEXT" },
+    Migration::AddColumn { table: "game_cache", column: "score", decl: "REAL" },
+    Migration::AddColumn { table: "game_cache", column: "last_hit_at", decl: "TEXT" },
+    Migration::AddColumn { table: "game_cache", column: "ttl", decl: "TEXT" },
+    // 8: near-identical spellings, outside game_cache so stats only count games
+    Migration::Sql(
+        "CREATE TABLE IF NOT EXISTS query_alias (
+            alias TEXT PRIMARY KEY,
+            target TEXT NOT NULL,
+            created_at TEXT NOT NULL
+        );
+        CREATE INDEX IF NOT EXISTS idx_query_alias_target ON query_alias(target);",
+    ),
+    // 9: last save or hit, what `cleanup` goes by; existing rows start at
+    // their latest known one
+    Migration::Sql(
+        "ALTER TABLE game_cache ADD COLUMN last_accessed TEXT;
+        UPDATE game_cache SET last_accessed = MAX(cached_at, COALESCE(last_hit_at, cached_at));
+        CREATE INDEX IF NOT EXISTS idx_game_cache_last_accessed ON game_cache(last_accessed);",
+    ),
+    // 10: ranker of the cached match, reported again on cache hits
+    Migration::AddColumn { table: "game_cache", column: "ranking_method", decl: "TEXT" },
+];
+
+/// Schema version this binary creates and understands
+pub const SCHEMA_VERSION: u32 = MIGRATIONS.len() as u32;
+
+/// Bring the database to `SCHEMA_VERSION` in one transaction: a failed
+/// upgrade leaves the previous schema untouched. Databases written by a newer
+/// binary are refused rather than modified.
+fn migrate(conn: &mut Connection) -> Result<()> {
+    // Immediate: two processes opening the same file migrate one at a time
+    let tx = conn.transaction_with_behavior(TransactionBehavior::Immediate)?;
+    tx.execute_batch("CREATE TABLE IF NOT EXISTS schema_version (version INTEGER NOT NULL)")?;
+    let current = tx
+        .query_row("SELECT MAX(version) FROM schema_version", [], |row| row.get::<_, Option<u32>>(0))?
+        .unwrap_or(0);
+
+    if current > SCHEMA_VERSION {
+        return Err(GameEngineError::Cache(format!(
+            "Database schema version {} is newer than this binary supports ({}), upgrade the engine",
+            current, SCHEMA_VERSION
+        )));
+    }
+    if current == SCHEMA_VERSION {
+        return Ok(());
+    }
+
+    for migration in &MIGRATIONS[current as usize..] {
+        migration.apply(&tx)?;
+    }
+    tx.execute("DELETE FROM schema_version", [])?;
+    tx.execute("INSERT INTO schema_version (version) VALUES (?1)", params![SCHEMA_VERSION])?;
+    tx.commit()?;
+
+    tracing::info!("🔧 Migrated cache schema from version {} to {}", current, SCHEMA_VERSION);
+    Ok(())
+}
+
+/// Add column to table if missing
+fn ensure_column(conn: &Connection, table: &str, column: &str, decl: &str) -> Result<()> {
+    let exists = conn
+        .prepare(&format!("PRAGMA table_info({})", table))?
+        .query_map([], |row| row.get::<_, String>(1))?
+        .collect::<std::result::Result<Vec<_>, _>>()?
+        .iter()
+        .any(|name| name == column);
+
+    if !exists {
+        conn.execute_batch(&format!("ALTER TABLE {} ADD COLUMN {} {}", table, column, decl))?;
+        tracing::info!("🔧 Added column {}.{}", table, column);
+    }
+
+    Ok(())
+}
+
+/// Delete rows by `policy` until `game_cache` is back to 90% of
+/// `max_entries`, in batches within `tx` (the save that went over it).
+/// Returns the number deleted (0, without writing, when under the cap).
+fn evict(tx: &Transaction, max_entries: u64, policy: EvictionPolicy) -> Result<u64> {
+    let count: u64 = tx.query_row("SELECT COUNT(*) FROM game_cache", [], |row| row.get(0))?;
+    if count <= max_entries {
+        return Ok(0);
+    }
+
+    // Headroom, so the next saves do not each evict a single row
+    let target = max_entries - max_entries / 10;
+    let mut evicted = 0;
+    let mut stmt = tx.prepare(&format!(
+        "DELETE FROM game_cache WHERE query IN (SELECT query FROM game_cache ORDER BY {} LIMIT ?1)",
+        policy.order_by()
+    ))?;
+    while count - evicted > target {
+        let batch = (count - evicted - target).min(EVICTION_BATCH);
+        let deleted = stmt.execute(params![batch])? as u64;
+        if deleted == 0 {
+            break;
+        }
+        evicted += deleted;
+    }
+
+    tracing::info!(
+        "🧹 Evicted {} cache entries over the {}-entry cap ({:?}), {} left",
+        evicted,
+        max_entries,
+        policy,
+        count - evicted
+    );
+    Ok(evicted)
+}
+
+/// Scan `game_cache` for rows that cannot be served, deleting them when
+/// `repair` is set (run it in a transaction so the scan and the deletes see
+/// the same rows)
+fn check_integrity(conn: &Connection, repair: bool) -> Result<IntegrityReport> {
+    let storage_errors = conn
+        .prepare("PRAGMA integrity_check")?
+        .query_map([], |row| row.get::<_, String>(0))?
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+    let mut report = IntegrityReport {
+        storage_errors: storage_errors.into_iter().filter(|message| message != "ok").collect(),
+        ..Default::default()
+    };
+
+    let mut stmt = conn.prepare(
+        "SELECT query, game_data, alternatives, ttl, cached_at, no_result, expires_at FROM game_cache ORDER BY query",
+    )?;
+    let mut rows = stmt.query([])?;
+    while let Some(row) = rows.next()? {
+        let query: String = row.get(0)?;
+        let game_data = row.get::<_, StoredJson>(1)?.decode();
+        let alternatives = row.get::<_, Option<StoredJson>>(2)?.map(StoredJson::decode).transpose();
+        let ttl: Option<String> = row.get(3)?;
+        let cached_at: String = row.get(4)?;
+        let no_result: bool = row.get(5)?;
+        let expires_at: Option<String> = row.get(6)?;
+
+        if no_result {
+            match expires_at {
+                None => report.tombstones_without_expiry.push(query),
+                Some(expires_at) if parse_timestamp(&expires_at).is_err() || parse_timestamp(&cached_at).is_err() => {
+                    report.bad_timestamps.push(query)
+                }
+                Some(_) => {}
+            }
+            continue;
+        }
+
+        let decodes = game_data.is_ok_and(|json| GameResult::from_json(&json).is_ok())
+            && alternatives.is_ok_and(|json| json.is_none_or(|json| serde_json::from_str::<Vec<SlimGameResult>>(&json).is_ok()))
+            && ttl.is_none_or(|json| serde_json::from_str::<TtlDecision>(&json).is_ok());
+        if !decodes {
+            report.undecodable_entries.push(query);
+        } else if parse_timestamp(&cached_at).is_err() {
+            report.bad_timestamps.push(query);
+        }
+    }
+
+    // Aliases to rows the repair deletes count as dangling already
+    let broken: std::collections::HashSet<&String> =
+        report.undecodable_entries.iter().chain(&report.bad_timestamps).collect();
+    let aliases = conn
+        .prepare(
+            "SELECT alias, target, EXISTS(SELECT 1 FROM game_cache WHERE query = target AND no_result = 0)
+             FROM query_alias ORDER BY alias",
+        )?
+        .query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?, row.get::<_, bool>(2)?)))?
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+    report.dangling_aliases = aliases
+        .into_iter()
+        .filter(|(_, target, exists)| !exists || broken.contains(target))
+        .map(|(alias, _, _)| alias)
+        .collect();
+
+    if repair {
+        let mut delete_alias = conn.prepare("DELETE FROM query_alias WHERE alias = ?1")?;
+        for alias in &report.dangling_aliases {
+            report.repaired += delete_alias.execute(params![alias])? as u64;
+        }
+        let mut delete = conn.prepare("DELETE FROM game_cache WHERE query = ?1")?;
+        let broken = report
+            .undecodable_entries
+            .iter()
+            .chain(&report.bad_timestamps)
+            .chain(&report.tombstones_without_expiry);
+        for query in broken {
+            report.repaired += delete.execute(params![query])? as u64;
+        }
+    }
+
+    Ok(report)
+}
+
+/// Rewrite the games after `after` (by key, `RECOMPRESS_BATCH` of them) in
+/// the format of `level`, return what was done and the last key read (None
+/// once past the last row)
+fn recompress_batch(tx: &Transaction, after: &str, level: Option<i32>) -> Result<(RecompressReport, Option<String>)> {
+    let rows = tx
+        .prepare(
+            "SELECT query, game_data, alternatives FROM game_cache
+             WHERE query > ?1 AND no_result = 0 ORDER BY query LIMIT ?2",
+        )?
+        .query_map(params![after, RECOMPRESS_BATCH], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, StoredJson>(1)?, row.get::<_, Option<StoredJson>>(2)?))
+        })?
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+
+    let compressed = |stored: &StoredJson| matches!(stored, StoredJson::Blob(_));
+    let mut update = tx.prepare("UPDATE game_cache SET game_data = ?2, alternatives = ?3 WHERE query = ?1")?;
+    let mut report = RecompressReport::default();
+    for (query, game_data, alternatives) in &rows {
+        if compressed(game_data) == level.is_some() && alternatives.as_ref().is_none_or(|a| compressed(a) == level.is_some()) {
+            continue;
+        }
+        let decoded = (game_data.clone().decode(), alternatives.clone().map(StoredJson::decode).transpose());
+        let (Ok(game_data_json), Ok(alternatives_json)) = decoded else {
+            report.skipped += 1;
+            continue;
+        };
+
+        let rewritten = StoredJson::encode(game_data_json, level)?;
+        let rewritten_alternatives = alternatives_json.map(|json| StoredJson::encode(json, level)).transpose()?;
+        update.execute(params![query, rewritten, rewritten_alternatives])?;
+        report.rows += 1;
+        report.bytes_before += (game_data.size() + alternatives.as_ref().map_or(0, StoredJson::size)) as u64;
+        report.bytes_after += (rewritten.size() + rewritten_alternatives.as_ref().map_or(0, StoredJson::size)) as u64;
+    }
+
+    Ok((report, rows.last().map(|(query, _, _)| query.clone())))
+}
+
+/// The fields of a stored game `list` shows, the rest is skipped unparsed
+#[derive(Deserialize)]
+struct GameHeader {
+    #[serde(default)]
+    name: String,
+    #[serde(default = "unknown_provider")]
+    provider: String,
+}
+
+fn unknown_provider() -> String {
+    "unknown".to_string()
+}
+
+fn parse_timestamp(value: &str) -> Result<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(value)
+        .map(|dt| dt.with_timezone(&Utc))
+        .map_err(|e| GameEngineError::Cache(format!("Invalid timestamp '{}': {}", value, e)))
+}
+
+#[async_trait]
+impl GameCache for SqliteCache {
+    async fn get(&self, query: &str) -> Result<Option<CachedGame>> {
+        let key = normalize_query(query);
+        let buffered = self.hits.as_ref().map_or(0, |hits| hits.pending_count(&key));
+
+        let cached = self.read(move |conn| {
+            let row = conn
+                .query_row(
+                    "SELECT game_data, alternatives, hit_count, cached_at, score, ttl, ranking_method
+                     FROM game_cache WHERE query = ?1 AND no_result = 0",
+                    params![key],
+                    |row| {
+                        Ok((
+                            row.get::<_, StoredJson>(0)?,
+                            row.get::<_, Option<StoredJson>>(1)?,
+                            row.get::<_, i32>(2)?,
+                            row.get::<_, String>(3)?,
+                            row.get::<_, Option<f64>>(4)?,
+                            row.get::<_, Option<String>>(5)?,
+                            row.get::<_, Option<String>>(6)?,
+                        ))
+                    },
+                )
+                .optional()
+                .ctx_phase(Phase::CacheRead)?;
+
+            let Some((game_data, alternatives, hit_count, cached_at, score, ttl, ranking_method)) = row else {
+                return Ok(None);
+            };
+
+            let game_data = game_data.decode().ctx_phase(Phase::CacheDecode)?;
+            let game = GameResult::from_json(&game_data).ctx_phase(Phase::CacheDecode)?;
+            let alternatives = match alternatives.map(StoredJson::decode).transpose().ctx_phase(Phase::CacheDecode)? {
+                Some(json) => serde_json::from_str(&json).ctx_phase(Phase::CacheDecode)?,
+                None => Vec::new(),
+            };
+            let ttl = ttl
+                .map(|json| serde_json::from_str(&json))
+                .transpose()
+                .ctx_phase(Phase::CacheDecode)?;
+
+            Ok(Some(CachedGame {
+                query: key.clone(),
+                game,
+                alternatives,
+                score,
+                ranking_method,
+                hit_count,
+                ttl,
+                cached_at: parse_timestamp(&cached_at).ctx_phase(Phase::CacheDecode)?,
+            }))
+        })
+        .await?;
+
+        Ok(cached.map(|cached| CachedGame { hit_count: cached.hit_count.saturating_add(buffered as i32), ..cached }))
+    }
+
+    async fn save(&self, query: &str, game: &GameResult, alternatives: &[SlimGameResult]) -> Result<()> {
+        self.write_entry(query, game, alternatives, None, None, None).await
+    }
+
+    async fn save_scored(
+        &self,
+        query: &str,
+        game: &GameResult,
+        alternatives: &[SlimGameResult],
+        score: f64,
+    ) -> Result<()> {
+        self.write_entry(query, game, alternatives, Some(score), None, None).await
+    }
+
+    async fn save_with_ttl(
+        &self,
+        query: &str,
+        game: &GameResult,
+        alternatives: &[SlimGameResult],
+        score: f64,
+        ranking_method: &str,
+        ttl: &TtlDecision,
+    ) -> Result<()> {
+        self.write_entry(query, game, alternatives, Some(score), Some(ranking_method), Some(ttl)).await
+    }
+
+    async fn increment_hit(&self, query: &str) -> Result<()> {
+        let key = normalize_query(query);
+        if let Some(hits) = &self.hits {
+            if hits.record(key) {
+                hits.flush().await?;
+            }
+            return Ok(());
+        }
+
+        self.write(move |conn| {
+            conn.execute(
+                "UPDATE game_cache SET hit_count = hit_count + 1, last_hit_at = ?2, last_accessed = ?2 WHERE query = ?1",
+                params![key, Utc::now().to_rfc3339()],
+            )
+            .ctx_phase(Phase::CacheWrite)?;
+            Ok(())
+        })
+        .await
+    }
+
+    async fn flush(&self) -> Result<()> {
+        match &self.hits {
+            Some(hits) => hits.flush().await,
+            None => Ok(()),
+        }
+    }
+
+    async fn top_queries(&self, limit: usize, since: Option<DateTime<Utc>>) -> Result<Vec<(String, i32)>> {
+        self.flush().await?;
+        self.read(move |conn| {
+            let mut stmt = conn
+                .prepare(
+                    "SELECT query, hit_count FROM game_cache
+                     WHERE no_result = 0 AND (?2 IS NULL OR last_hit_at >= ?2)
+                     ORDER BY hit_count DESC, query ASC
+                     LIMIT ?1",
+                )
+                .ctx_phase(Phase::CacheRead)?;
+
+            let rows = stmt
+                .query_map(
+                    params![limit as i64, since.map(|s| s.to_rfc3339())],
+                    |row| Ok((row.get(0)?, row.get(1)?)),
+                )
+                .ctx_phase(Phase::CacheRead)?
+                .collect::<std::result::Result<Vec<_>, _>>()
+                .ctx_phase(Phase::CacheRead)?;
+
+            Ok(rows)
+        })
+        .await
+    }
+
+    async fn stats(&self) -> Result<CacheStats> {
+        self.flush().await?;
+        let now = Utc::now();
+        let cutoffs = [1, 7, 30].map(|days| (now - Duration::days(days)).to_rfc3339());
+        let (totals, top_queries) = self
+            .read(move |conn| {
+                // One aggregate pass; the age buckets are cumulative counts
+                // split below
+                let totals = conn
+                    .query_row(
+                        "SELECT
+                            COALESCE(SUM(no_result = 0), 0),
+                            COALESCE(SUM(CASE WHEN no_result = 0 THEN hit_count END), 0),
+                            MIN(CASE WHEN no_result = 0 THEN cached_at END),
+                            MAX(CASE WHEN no_result = 0 THEN cached_at END),
+                            COALESCE(SUM(no_result = 1), 0),
+                            (SELECT COUNT(*) FROM query_alias),
+                            COALESCE(SUM(no_result = 0 AND cached_at >= ?1), 0),
+                            COALESCE(SUM(no_result = 0 AND cached_at >= ?2), 0),
+                            COALESCE(SUM(no_result = 0 AND cached_at >= ?3), 0),
+                            COALESCE(SUM(LENGTH(CAST(game_data AS BLOB))), 0),
+                            (SELECT page_count * page_size FROM pragma_page_count(), pragma_page_size())
+                         FROM game_cache",
+                        params![cutoffs[0], cutoffs[1], cutoffs[2]],
+                        |row| {
+                            let mut counts = [0u64; 9];
+                            for (count, column) in counts.iter_mut().zip([0, 1, 4, 5, 6, 7, 8, 9, 10]) {
+                                *count = row.get::<_, i64>(column)?.max(0) as u64;
+                            }
+                            Ok((counts, row.get::<_, Option<String>>(2)?, row.get::<_, Option<String>>(3)?))
+                        },
+                    )
+                    .ctx_phase(Phase::CacheRead)?;
+
+                let mut stmt = conn
+                    .prepare_cached(
+                        "SELECT query, hit_count FROM game_cache WHERE no_result = 0
+                         ORDER BY hit_count DESC, query ASC LIMIT ?1",
+                    )
+                    .ctx_phase(Phase::CacheRead)?;
+                let top_queries = stmt
+                    .query_map(params![STATS_TOP_QUERIES as i64], |row| {
+                        Ok(TopQuery { query: row.get(0)?, hit_count: row.get(1)? })
+                    })
+                    .ctx_phase(Phase::CacheRead)?
+                    .collect::<std::result::Result<Vec<_>, _>>()
+                    .ctx_phase(Phase::CacheRead)?;
+
+                Ok((totals, top_queries))
+            })
+            .await?;
+
+        let ([total_entries, total_hits, no_result_entries, aliases, last_1d, last_7d, last_30d, data_bytes, file_bytes], oldest, newest) =
+            totals;
+
+        let avg_hit_count = if total_entries > 0 {
+            total_hits as f64 / total_entries as f64
+        } else {
+            0.0
+        };
+
+        Ok(CacheStats {
+            total_entries,
+            no_result_entries,
+            total_hits,
+            avg_hit_count,
+            oldest_entry: oldest.as_deref().map(parse_timestamp).transpose()?,
+            newest_entry: newest.as_deref().map(parse_timestamp).transpose()?,
+            busy_retries: self.busy.retried.load(Ordering::Relaxed),
+            aliases,
+            added_last_24h: last_1d,
+            added_last_7d: last_7d,
+            data_bytes: Some(data_bytes),
+            file_bytes: Some(file_bytes),
+            age_histogram: AgeHistogram {
+                under_1d: last_1d,
+                under_7d: last_7d - last_1d,
+                under_30d: last_30d - last_7d,
+                older: total_entries - last_30d,
+            },
+            top_queries,
+            ..Default::default()
+        })
+    }
+
+    async fn cleanup(&self, max_age_days: i64) -> Result<u64> {
+        // Rows written by the Python bot have no last_accessed
+        self.cleanup_older_than("COALESCE(last_accessed, cached_at)", max_age_days).await
+    }
+
+    async fn cleanup_by_insert_age(&self, max_age_days: i64) -> Result<u64> {
+        self.cleanup_older_than("cached_at", max_age_days).await
+    }
+
+    async fn delete(&self, query: &str) -> Result<bool> {
+        let key = normalize_query(query);
+        self.forget_hits(|k| k == key);
+
+        let deleted = self
+            .write(move |conn| {
+                conn.execute("DELETE FROM game_cache WHERE query = ?1", params![key])
+                    .ctx_phase(Phase::CacheWrite)
+            })
+            .await?;
+        Ok(deleted > 0)
+    }
+
+    async fn delete_matching(&self, pattern: &str) -> Result<u64> {
+        let like = pattern.to_string();
+        self.forget_hits(|k| like_matches(pattern, k));
+
+        let deleted = self
+            .write(move |conn| {
+                conn.execute("DELETE FROM game_cache WHERE query LIKE ?1 ESCAPE '\\'", params![like])
+                    .ctx_phase(Phase::CacheWrite)
+            })
+            .await?;
+        if deleted > 0 {
+            tracing::info!("🧹 Deleted {} cache entries matching '{}'", deleted, pattern);
+        }
+        Ok(deleted as u64)
+    }
+
+    async fn invalidate_where(&self, query: &str, patterns: &[String]) -> Result<u64> {
+        let key = normalize_query(query);
+        self.forget_hits(|k| k == key || patterns.iter().any(|pattern| like_matches(pattern, k)));
+        let patterns = patterns.to_vec();
+
+        self.write_tx(move |tx| {
+            let mut deleted = tx
+                .execute("DELETE FROM game_cache WHERE query = ?1", params![key])
+                .ctx_phase(Phase::CacheWrite)?;
+            tx.execute("DELETE FROM query_alias WHERE alias = ?1 OR target = ?1", params![key])
+                .ctx_phase(Phase::CacheWrite)?;
+            let mut entries = tx
+                .prepare("DELETE FROM game_cache WHERE query LIKE ?1 ESCAPE '\\'")
+                .ctx_phase(Phase::CacheWrite)?;
+            let mut aliases = tx
+                .prepare("DELETE FROM query_alias WHERE alias LIKE ?1 ESCAPE '\\' OR target LIKE ?1 ESCAPE '\\'")
+                .ctx_phase(Phase::CacheWrite)?;
+            for pattern in &patterns {
+                deleted += entries.execute(params![pattern]).ctx_phase(Phase::CacheWrite)?;
+                aliases.execute(params![pattern]).ctx_phase(Phase::CacheWrite)?;
+            }
+            Ok(deleted as u64)
+        })
+        .await
+    }
+
+    async fn integrity_check(&self, repair: bool) -> Result<IntegrityReport> {
+        let report = if repair {
+            self.write_tx(|tx| check_integrity(tx, true).ctx_phase(Phase::CacheWrite)).await?
+        } else {
+            self.read(|conn| check_integrity(conn, false).ctx_phase(Phase::CacheRead)).await?
+        };
+
+        if report.repaired > 0 {
+            tracing::info!("🔧 Repaired cache: deleted {} inconsistent entries", report.repaired);
+        }
+        Ok(report)
+    }
+
+    async fn recompress(&self) -> Result<RecompressReport> {
+        let level = self.compression_level;
+        let mut report = RecompressReport::default();
+        let mut after = String::new();
+        loop {
+            let cursor = after.clone();
+            let (batch, last) = self
+                .write_tx(move |tx| recompress_batch(tx, &cursor, level).ctx_phase(Phase::CacheWrite))
+                .await?;
+            report.rows += batch.rows;
+            report.bytes_before += batch.bytes_before;
+            report.bytes_after += batch.bytes_after;
+            report.skipped += batch.skipped;
+            match last {
+                Some(last) => after = last,
+                None => break,
+            }
+        }
+
+        tracing::info!(
+            "🗜️ Rewrote {} cache entries: {} -> {} bytes",
+            report.rows,
+            report.bytes_before,
+            report.bytes_after
+        );
+        Ok(report)
+    }
+
+    async fn keys_matching(&self, pattern: &str, limit: usize) -> Result<Vec<String>> {
+        let like = pattern.to_string();
+
+        self.read(move |conn| {
+            let mut stmt = conn
+                .prepare(
+                    "SELECT query FROM game_cache
+                     WHERE no_result = 0 AND query LIKE ?1 ESCAPE '\\'
+                     ORDER BY hit_count DESC, query ASC
+                     LIMIT ?2",
+                )
+                .ctx_phase(Phase::CacheRead)?;
+            let keys = stmt
+                .query_map(params![like, limit as i64], |row| row.get(0))
+                .ctx_phase(Phase::CacheRead)?
+                .collect::<std::result::Result<Vec<_>, _>>()
+                .ctx_phase(Phase::CacheRead)?;
+            Ok(keys)
+        })
+        .await
+    }
+
+    async fn list(&self, offset: usize, limit: usize, order: CacheOrder) -> Result<Vec<CacheEntrySummary>> {
+        self.flush().await?;
+        let order_by = match order {
+            CacheOrder::Newest => "cached_at DESC, query ASC",
+            CacheOrder::MostHit => "hit_count DESC, query ASC",
+            CacheOrder::Alphabetical => "query ASC",
+        };
+        let sql = format!(
+            "SELECT query, game_data, hit_count, cached_at, score FROM game_cache
+             WHERE no_result = 0
+             ORDER BY {}
+             LIMIT ?1 OFFSET ?2",
+            order_by
+        );
+
+        self.read(move |conn| {
+            let mut stmt = conn.prepare(&sql).ctx_phase(Phase::CacheRead)?;
+            let rows = stmt
+                .query_map(params![limit as i64, offset as i64], |row| {
+                    Ok((
+                        row.get::<_, String>(0)?,
+                        row.get::<_, StoredJson>(1)?,
+                        row.get::<_, i32>(2)?,
+                        row.get::<_, String>(3)?,
+                        row.get::<_, Option<f64>>(4)?,
+                    ))
+                })
+                .ctx_phase(Phase::CacheRead)?
+                .collect::<std::result::Result<Vec<_>, _>>()
+                .ctx_phase(Phase::CacheRead)?;
+
+            rows.into_iter()
+                .map(|(query, game_data, hit_count, cached_at, score)| {
+                    let game_data = game_data.decode().ctx_phase(Phase::CacheDecode)?;
+                    let header: GameHeader = serde_json::from_str(&game_data).ctx_phase(Phase::CacheDecode)?;
+                    Ok(CacheEntrySummary {
+                        query,
+                        name: header.name,
+                        provider: header.provider,
+                        hit_count,
+                        cached_at: parse_timestamp(&cached_at).ctx_phase(Phase::CacheDecode)?,
+                        score,
+                    })
+                })
+                .collect()
+        })
+        .await
+    }
+
+    async fn alias(&self, key: &str) -> Result<Option<String>> {
+        let key = normalize_query(key);
+
+        self.read(move |conn| {
+            conn.query_row("SELECT target FROM query_alias WHERE alias = ?1", params![key], |row| row.get(0))
+                .optional()
+                .ctx_phase(Phase::CacheRead)
+        })
+        .await
+    }
+
+    async fn save_alias(&self, alias: &str, target: &str) -> Result<()> {
+        let (alias, target) = (normalize_query(alias), normalize_query(target));
+
+        self.write(move |conn| {
+            conn.execute(
+                "INSERT INTO query_alias (alias, target, created_at) VALUES (?1, ?2, ?3)
+                 ON CONFLICT(alias) DO UPDATE SET target = excluded.target, created_at = excluded.created_at",
+                params![alias, target, Utc::now().to_rfc3339()],
+            )
+            .ctx_phase(Phase::CacheWrite)?;
+            Ok(())
+        })
+        .await
+    }
+
+    async fn save_no_result(&self, query: &str, ttl: Duration) -> Result<()> {
+        let key = normalize_query(query);
+        let now = Utc::now();
+
+        self.write(move |conn| {
+            // Never shadow a real cached game with a tombstone
+            conn.execute(
+                "INSERT INTO game_cache (query, game_data, alternatives, hit_count, cached_at, no_result, expires_at)
+                 VALUES (?1, 'null', NULL, 0, ?2, 1, ?3)
+                 ON CONFLICT(query) DO UPDATE SET
+                    cached_at = excluded.cached_at,
+                    expires_at = excluded.expires_at
+                 WHERE no_result = 1",
+                params![key, now.to_rfc3339(), (now + ttl).to_rfc3339()],
+            )
+            .ctx_phase(Phase::CacheWrite)?;
+            Ok(())
+        })
+        .await
+    }
+
+    async fn is_no_result(&self, query: &str) -> Result<bool> {
+        let key = normalize_query(query);
+
+        self.read(move |conn| {
+            let found = conn
+                .query_row(
+                    "SELECT 1 FROM game_cache WHERE query = ?1 AND no_result = 1 AND expires_at > ?2",
+                    params![key, Utc::now().to_rfc3339()],
+                    |_| Ok(()),
+                )
+                .optional()
+                .ctx_phase(Phase::CacheRead)?;
+            Ok(found.is_some())
+        })
+        .await
+    }
+
+    async fn schema_baseline(&self, provider: &str) -> Result<Option<Fingerprint>> {
+        let provider = provider.to_string();
+
+        self.read(move |conn| {
+            let data: Option<String> = conn
+                .query_row(
+                    "SELECT fingerprint FROM provider_schema WHERE provider = ?1",
+                    params![provider],
+                    |row| row.get(0),
+                )
+                .optional()
+                .ctx_phase(Phase::CacheRead)
+                .ctx_provider(&provider)?;
+
+            data.map(|data| serde_json::from_str(&data))
+                .transpose()
+                .ctx_phase(Phase::CacheDecode)
+                .ctx_provider(&provider)
+        })
+        .await
+    }
+
+    async fn save_schema_baseline(&self, provider: &str, fingerprint: &Fingerprint) -> Result<()> {
+        let data = serde_json::to_string(fingerprint).ctx_phase(Phase::CacheWrite)?;
+        let provider = provider.to_string();
+
+        self.write(move |conn| {
+            conn.execute(
+                "INSERT INTO provider_schema (provider, fingerprint, blessed_at) VALUES (?1, ?2, ?3)
+                 ON CONFLICT(provider) DO UPDATE SET
+                    fingerprint = excluded.fingerprint,
+                    blessed_at = excluded.blessed_at",
+                params![provider, data, Utc::now().to_rfc3339()],
+            )
+            .ctx_phase(Phase::CacheWrite)
+            .ctx_provider(&provider)?;
+            Ok(())
+        })
+        .await
+    }
+
+    async fn log_searches(&self, entries: &[SearchLogEntry]) -> Result<()> {
+        let entries = entries.to_vec();
+
+        self.write_tx(move |tx| {
+            let mut stmt = tx
+                .prepare(
+                    "INSERT INTO search_log (query, resolved_name, score, provider, from_cache, latency_ms, timestamp)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                )
+                .ctx_phase(Phase::CacheWrite)?;
+            for entry in &entries {
+                stmt.execute(params![
+                    entry.query,
+                    entry.resolved_name,
+                    entry.score,
+                    entry.provider,
+                    entry.from_cache,
+                    entry.latency_ms,
+                    entry.timestamp.to_rfc3339(),
+                ])
+                .ctx_phase(Phase::CacheWrite)?;
+            }
+            Ok(())
+        })
+        .await
+    }
+
+    async fn recent_searches(&self, limit: usize) -> Result<Vec<SearchLogEntry>> {
+        let rows = self
+            .read(move |conn| {
+                let mut stmt = conn
+                    .prepare(
+                        "SELECT query, resolved_name, score, provider, from_cache, latency_ms, timestamp
+                         FROM search_log ORDER BY id DESC LIMIT ?1",
+                    )
+                    .ctx_phase(Phase::CacheRead)?;
+
+                let rows = stmt
+                    .query_map(params![limit as i64], |row| {
+                        Ok((
+                            row.get::<_, String>(0)?,
+                            row.get::<_, Option<String>>(1)?,
+                            row.get::<_, Option<f64>>(2)?,
+                            row.get::<_, Option<String>>(3)?,
+                            row.get::<_, bool>(4)?,
+                            row.get::<_, f64>(5)?,
+                            row.get::<_, String>(6)?,
+                        ))
+                    })
+                    .ctx_phase(Phase::CacheRead)?
+                    .collect::<std::result::Result<Vec<_>, _>>()
+                    .ctx_phase(Phase::CacheRead)?;
+                Ok(rows)
+            })
+            .await?;
+
+        rows.into_iter()
+            .map(|(query, resolved_name, score, provider, from_cache, latency_ms, timestamp)| {
+                Ok(SearchLogEntry {
+                    query,
+                    resolved_name,
+                    score,
+                    provider,
+                    from_cache,
+                    latency_ms,
+                    timestamp: parse_timestamp(&timestamp).ctx_phase(Phase::CacheDecode)?,
+                })
+            })
+            .collect()
+    }
+
+    async fn cleanup_log(&self, max_age_days: i64) -> Result<u64> {
+        let cutoff = (Utc::now() - Duration::days(max_age_days)).to_rfc3339();
+
+        let deleted = self
+            .write(move |conn| {
+                conn.execute("DELETE FROM search_log WHERE timestamp < ?1", params![cutoff])
+                    .ctx_phase(Phase::CacheWrite)
+            })
+            .await?;
+
+        if deleted > 0 {
+            tracing::info!("🧹 Cleaned up {} search log entries older than {} days", deleted, max_age_days);
+        }
+
+        Ok(deleted as u64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cache::escape_like;
+
+    #[tokio::test]
+    async fn test_cache_create() {
+        let cache = SqliteCache::new(":memory:").await;
+        assert!(cache.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_cache_save_and_get() {
+        let cache = SqliteCache::new(":memory:").await.unwrap();
+        let game = GameResult::new("steam", "1794680", "Vampire Survivors");
+        let alt = GameResult::new("steam", "2", "Vampire Hunters");
+
+        cache.save("vampire survivors", &game, &[alt.into()]).await.unwrap();
+
+        let cached = cache.get("vampire survivors").await.unwrap().unwrap();
+        assert_eq!(cached.game.name, "Vampire Survivors");
+        assert_eq!(cached.alternatives.len(), 1);
+        assert_eq!(cached.hit_count, 0);
+
+        assert!(cache.get("unknown").await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_cache_normalize_query() {
+        let cache = SqliteCache::new(":memory:").await.unwrap();
+        let game = GameResult::new("steam", "730", "Counter-Strike 2");
+
+        cache.save("  Counter-Strike  ", &game, &[]).await.unwrap();
+
+        assert!(cache.get("counter-strike").await.unwrap().is_some());
+        assert!(cache.get("COUNTER-STRIKE").await.unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_cache_increment_hit() {
+        let cache = SqliteCache::new(":memory:").await.unwrap();
+        let game = GameResult::new("steam", "730", "Counter-Strike 2");
+
+        cache.save("cs2", &game, &[]).await.unwrap();
+        cache.increment_hit("cs2").await.unwrap();
+        cache.increment_hit("CS2").await.unwrap();
+
+        let cached = cache.get("cs2").await.unwrap().unwrap();
+        assert_eq!(cached.hit_count, 2);
+    }
+
+    #[tokio::test]
+    async fn test_delete_entries() {
+        let cache = SqliteCache::new(":memory:").await.unwrap();
+        for query in ["hades", "hades|fr", "hades ii", "100%_off", "100% off"] {
+            cache.save(query, &GameResult::new("steam", query, query), &[]).await.unwrap();
+        }
+        cache.save_no_result("kekw", Duration::hours(1)).await.unwrap();
+
+        assert!(cache.delete(" Hades ").await.unwrap());
+        assert!(!cache.delete("hades").await.unwrap());
+        assert!(cache.get("hades").await.unwrap().is_none());
+        assert!(cache.get("hades|fr").await.unwrap().is_some());
+        // Tombstones too
+        assert!(cache.delete("kekw").await.unwrap());
+        assert!(!cache.is_no_result("kekw").await.unwrap());
+
+        assert_eq!(cache.delete_matching("hades%").await.unwrap(), 2);
+        assert_eq!(cache.delete_matching(&escape_like("100%_off")).await.unwrap(), 1);
+        assert!(cache.get("100% off").await.unwrap().is_some());
+        assert_eq!(cache.delete_matching("nothing%").await.unwrap(), 0);
+        assert_eq!(cache.stats().await.unwrap().total_entries, 1);
+    }
+
+    #[tokio::test]
+    async fn test_top_queries() {
+        let cache = SqliteCache::new(":memory:").await.unwrap();
+        for (query, hits) in [("zelda", 3), ("hades", 5), ("celeste", 1), ("unused", 0)] {
+            cache.save(query, &GameResult::new("steam", query, query), &[]).await.unwrap();
+            for _ in 0..hits {
+                cache.increment_hit(query).await.unwrap();
+            }
+        }
+        cache.save_no_result("kekw", Duration::hours(1)).await.unwrap();
+
+        let top = cache.top_queries(2, None).await.unwrap();
+        assert_eq!(top, vec![("hades".to_string(), 5), ("zelda".to_string(), 3)]);
+        assert_eq!(cache.top_queries(10, None).await.unwrap().len(), 4);
+
+        // Only entries hit since the cutoff
+        cache.connection()
+            .execute(
+                "UPDATE game_cache SET last_hit_at = ?1 WHERE query = 'hades'",
+                params![(Utc::now() - Duration::days(40)).to_rfc3339()],
+            )
+            .unwrap();
+        let month = cache.top_queries(10, Some(Utc::now() - Duration::days(30))).await.unwrap();
+        assert_eq!(month, vec![("zelda".to_string(), 3), ("celeste".to_string(), 1)]);
+    }
+
+    #[tokio::test]
+    async fn test_cache_stats() {
+        let cache = SqliteCache::new(":memory:").await.unwrap();
+
+        let stats = cache.stats().await.unwrap();
+        assert_eq!(stats.total_entries, 0);
+        assert!(stats.oldest_entry.is_none());
+
+        cache.save("a", &GameResult::new("steam", "1", "A"), &[]).await.unwrap();
+        cache.save("b", &GameResult::new("steam", "2", "B"), &[]).await.unwrap();
+        cache.increment_hit("a").await.unwrap();
+
+        let stats = cache.stats().await.unwrap();
+        assert_eq!(stats.total_entries, 2);
+        assert_eq!(stats.total_hits, 1);
+        assert_eq!(stats.avg_hit_count, 0.5);
+        assert!(stats.newest_entry.is_some());
+        assert_eq!(stats.top_queries[0], TopQuery { query: "a".to_string(), hit_count: 1 });
+        assert!(stats.file_bytes.unwrap() > 0);
+        let data_bytes = stats.data_bytes.unwrap();
+
+        let game = GameResult::new("steam", "3", "Ç");
+        for (key, days) in [("c", 3), ("d", 10), ("e", 40), ("f", 400)] {
+            cache.save(key, &game, &[]).await.unwrap();
+            cache
+                .connection()
+                .execute(
+                    "UPDATE game_cache SET cached_at = ?1 WHERE query = ?2",
+                    params![(Utc::now() - Duration::days(days)).to_rfc3339(), key],
+                )
+                .unwrap();
+        }
+        cache.save_no_result("g", Duration::hours(1)).await.unwrap();
+
+        let stats = cache.stats().await.unwrap();
+        assert_eq!((stats.added_last_24h, stats.added_last_7d), (2, 3));
+        assert_eq!(stats.age_histogram, AgeHistogram { under_1d: 2, under_7d: 1, under_30d: 1, older: 2 });
+        // Bytes, not characters
+        let per_game = game.to_json().unwrap().len() as u64;
+        assert!(stats.data_bytes.unwrap() >= data_bytes + 4 * per_game);
+        assert_eq!(stats.top_queries.len(), 6);
+    }
+
+    /// Hit count as written in the table, buffered hits excluded
+    fn stored_hits(cache: &SqliteCache, key: &str) -> i64 {
+        cache.connection()
+            .query_row("SELECT hit_count FROM game_cache WHERE query = ?1", params![key], |row| row.get(0))
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_hits_are_batched() {
+        let cache = SqliteCache::new(":memory:").await.unwrap();
+        cache.save("hades", &GameResult::new("steam", "1", "Hades"), &[]).await.unwrap();
+        for _ in 0..3 {
+            cache.increment_hit("hades").await.unwrap();
+        }
+
+        assert_eq!(stored_hits(&cache, "hades"), 0);
+        assert_eq!(cache.get("hades").await.unwrap().unwrap().hit_count, 3);
+        // Read paths aggregating hits write them first
+        assert_eq!(cache.stats().await.unwrap().total_hits, 3);
+        assert_eq!(stored_hits(&cache, "hades"), 3);
+        assert_eq!(cache.get("hades").await.unwrap().unwrap().hit_count, 3);
+
+        // Deleted entries take their buffered hits with them
+        cache.increment_hit("hades").await.unwrap();
+        cache.delete("hades").await.unwrap();
+        cache.save("hades", &GameResult::new("steam", "1", "Hades"), &[]).await.unwrap();
+        cache.flush().await.unwrap();
+        assert_eq!(stored_hits(&cache, "hades"), 0);
+    }
+
+    #[tokio::test]
+    async fn test_hit_flush_triggers() {
+        let options = SqliteCacheOptions { hit_flush_max_keys: 2, hit_flush_interval_ms: 50, ..Default::default() };
+        let cache = SqliteCache::with_options(":memory:", options).await.unwrap();
+        for query in ["a", "b", "c"] {
+            cache.save(query, &GameResult::new("steam", query, query), &[]).await.unwrap();
+        }
+
+        // Too many pending queries
+        cache.increment_hit("a").await.unwrap();
+        assert_eq!(stored_hits(&cache, "a"), 0);
+        cache.increment_hit("b").await.unwrap();
+        assert_eq!((stored_hits(&cache, "a"), stored_hits(&cache, "b")), (1, 1));
+
+        // Interval
+        cache.increment_hit("c").await.unwrap();
+        tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+        assert_eq!(stored_hits(&cache, "c"), 1);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_concurrent_hits_counted_once() {
+        // Every hit past `hit_flush_max_keys` starts a flush, overlapping the
+        // others: each used to write the hits not settled yet again
+        for (options, keys, per_key) in [
+            (SqliteCacheOptions::default(), 300, 5),
+            (SqliteCacheOptions { hit_flush_max_keys: 1, ..Default::default() }, 1, 200),
+        ] {
+            let cache = Arc::new(SqliteCache::with_options(":memory:", options).await.unwrap());
+            for key in 0..keys {
+                cache.save(&key.to_string(), &GameResult::new("steam", "1", "Hades"), &[]).await.unwrap();
+            }
+            let tasks: Vec<_> = (0..keys * per_key)
+                .map(|i| {
+                    let cache = cache.clone();
+                    tokio::spawn(async move { cache.increment_hit(&(i % keys).to_string()).await })
+                })
+                .collect();
+            for task in tasks {
+                task.await.unwrap().unwrap();
+            }
+            assert_eq!(cache.stats().await.unwrap().total_hits, (keys * per_key) as u64);
+            assert_eq!(stored_hits(&cache, "0"), per_key as i64);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_buffered_hits_written_on_drop() {
+        let path = std::env::temp_dir().join(format!("kissbot_hit_buffer_{}.db", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let path = path.to_str().unwrap();
+
+        let cache = SqliteCache::new(path).await.unwrap();
+        cache.save("hades", &GameResult::new("steam", "1", "Hades"), &[]).await.unwrap();
+        cache.increment_hit("hades").await.unwrap();
+        drop(cache);
+
+        // Synchronous mode writes each hit as it happens
+        let options = SqliteCacheOptions { batch_hits: false, ..Default::default() };
+        let cache = SqliteCache::with_options(path, options).await.unwrap();
+        assert_eq!(stored_hits(&cache, "hades"), 1);
+        cache.increment_hit("hades").await.unwrap();
+        assert_eq!(stored_hits(&cache, "hades"), 2);
+
+        drop(cache);
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[tokio::test]
+    async fn test_cache_cleanup() {
+        let cache = SqliteCache::new(":memory:").await.unwrap();
+        cache.save("a", &GameResult::new("steam", "1", "A"), &[]).await.unwrap();
+
+        // Fresh entry survives a 30-day cleanup
+        assert_eq!(cache.cleanup(30).await.unwrap(), 0);
+
+        // Backdate entry and clean again
+        backdate(&cache, "cached_at = ?1, last_accessed = ?1", 60);
+        assert_eq!(cache.cleanup(30).await.unwrap(), 1);
+        assert_eq!(cache.stats().await.unwrap().total_entries, 0);
+    }
+
+    /// Set the given timestamp columns of every row to `days` ago
+    fn backdate(cache: &SqliteCache, assignments: &str, days: i64) {
+        cache.connection()
+            .execute(
+                &format!("UPDATE game_cache SET {}", assignments),
+                params![(Utc::now() - Duration::days(days)).to_rfc3339()],
+            )
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_cleanup_by_access_time() {
+        let cache = SqliteCache::new(":memory:").await.unwrap();
+        for query in ["hades", "celeste", "zelda"] {
+            cache.save(query, &GameResult::new("steam", query, query), &[]).await.unwrap();
+        }
+        backdate(&cache, "cached_at = ?1, last_accessed = ?1", 60);
+        // Hit every day since it was first cached
+        cache.increment_hit("hades").await.unwrap();
+        // Refreshed by a search
+        cache.save("celeste", &GameResult::new("steam", "celeste", "Celeste"), &[]).await.unwrap();
+        cache.connection()
+            .execute("UPDATE game_cache SET cached_at = ?1 WHERE query = 'celeste'", params![(Utc::now() - Duration::days(60)).to_rfc3339()])
+            .unwrap();
+
+        assert_eq!(cache.cleanup(30).await.unwrap(), 1);
+        assert!(cache.get("zelda").await.unwrap().is_none());
+        assert_eq!(cache.stats().await.unwrap().total_entries, 2);
+
+        // The old behavior still drops them
+        assert_eq!(cache.cleanup_by_insert_age(30).await.unwrap(), 2);
+        assert_eq!(cache.stats().await.unwrap().total_entries, 0);
+    }
+
+    #[tokio::test]
+    async fn test_last_accessed_backfilled_on_legacy_rows() {
+        let path = std::env::temp_dir().join(format!("kissbot_last_accessed_{}.db", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let game = GameResult::new("steam", "1", "Hades").to_json().unwrap();
+        let old = (Utc::now() - Duration::days(60)).to_rfc3339();
+        {
+            // Python bot table, before last_accessed existed
+            let conn = Connection::open(&path).unwrap();
+            conn.execute_batch(
+                "CREATE TABLE game_cache (
+                    query TEXT PRIMARY KEY,
+                    game_data TEXT NOT NULL,
+                    alternatives TEXT,
+                    hit_count INTEGER NOT NULL DEFAULT 0,
+                    cached_at TEXT NOT NULL
+                );",
+            )
+            .unwrap();
+            for query in ["hades", "zelda"] {
+                conn.execute("INSERT INTO game_cache VALUES (?1, ?2, '[]', 3, ?3)", params![query, game, old]).unwrap();
+            }
+        }
+
+        let cache = SqliteCache::new(path.to_str().unwrap()).await.unwrap();
+        let backfilled: Vec<Option<String>> = cache
+            .connection()
+            .prepare("SELECT last_accessed FROM game_cache ORDER BY query")
+            .unwrap()
+            .query_map([], |row| row.get(0))
+            .unwrap()
+            .collect::<std::result::Result<_, _>>()
+            .unwrap();
+        assert_eq!(backfilled, vec![Some(old.clone()), Some(old.clone())]);
+
+        // A row the Python bot writes afterwards has no last_accessed:
+        // cleanup falls back to cached_at
+        cache.connection()
+            .execute("INSERT INTO game_cache (query, game_data, hit_count, cached_at) VALUES ('celeste', ?1, 0, ?2)", params![game, old])
+            .unwrap();
+        cache.increment_hit("hades").await.unwrap();
+        assert_eq!(cache.cleanup(30).await.unwrap(), 2);
+        assert!(cache.get("hades").await.unwrap().is_some());
+
+        drop(cache);
+        let _ = std::fs::remove_file(&path);
+    }
+
+    /// Failpoint: make the next statement deleting `query` abort, after the
+    /// statements before it in the same mutation already ran
+    fn fail_on_delete(cache: &SqliteCache, query: &str) {
+        cache.connection()
+            .execute_batch(&format!(
+                "CREATE TEMP TRIGGER failpoint BEFORE DELETE ON game_cache WHEN old.query = '{}'
+                 BEGIN SELECT RAISE(ABORT, 'failpoint'); END;",
+                query
+            ))
+            .unwrap();
+    }
+
+    fn clear_failpoint(cache: &SqliteCache) {
+        cache.connection().execute_batch("DROP TRIGGER failpoint").unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_cleanup_rolls_back_on_failure() {
+        let cache = SqliteCache::new(":memory:").await.unwrap();
+        cache.save("a", &GameResult::new("steam", "1", "A"), &[]).await.unwrap();
+        backdate(&cache, "cached_at = ?1, last_accessed = ?1", 60);
+        cache.save_no_result("kekw", Duration::seconds(-1)).await.unwrap();
+
+        // The old entry is deleted first, then the tombstone delete fails
+        fail_on_delete(&cache, "kekw");
+        assert!(cache.cleanup(30).await.is_err());
+        let stats = cache.stats().await.unwrap();
+        assert_eq!((stats.total_entries, stats.no_result_entries), (1, 1));
+        assert!(cache.integrity_check(false).await.unwrap().is_ok());
+
+        clear_failpoint(&cache);
+        assert_eq!(cache.cleanup(30).await.unwrap(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_invalidate_where_is_atomic() {
+        let cache = SqliteCache::new(":memory:").await.unwrap();
+        for query in ["hades", "hades|fr", "hades@us", "hades ii"] {
+            cache.save(query, &GameResult::new("steam", query, query), &[]).await.unwrap();
+        }
+        let patterns = vec!["hades|%".to_string(), "hades@%".to_string()];
+
+        fail_on_delete(&cache, "hades@us");
+        assert!(cache.invalidate_where("hades", &patterns).await.is_err());
+        assert!(cache.get("hades").await.unwrap().is_some());
+        assert!(cache.get("hades|fr").await.unwrap().is_some());
+
+        clear_failpoint(&cache);
+        assert_eq!(cache.invalidate_where("Hades", &patterns).await.unwrap(), 3);
+        assert_eq!(cache.stats().await.unwrap().total_entries, 1);
+    }
+
+    #[tokio::test]
+    async fn test_integrity_check_and_repair() {
+        let cache = SqliteCache::new(":memory:").await.unwrap();
+        for query in ["zelda", "hades", "celeste", "kekw"] {
+            cache.save(query, &GameResult::new("steam", query, query), &[]).await.unwrap();
+        }
+        cache.save_no_result("pog", Duration::hours(1)).await.unwrap();
+        assert!(cache.integrity_check(false).await.unwrap().is_ok());
+
+        cache.connection()
+            .execute_batch(
+                "UPDATE game_cache SET alternatives = '{' WHERE query = 'hades';
+                 UPDATE game_cache SET cached_at = 'yesterday' WHERE query = 'celeste';
+                 UPDATE game_cache SET no_result = 1, game_data = 'null' WHERE query = 'kekw';
+                 UPDATE game_cache SET expires_at = 'soon' WHERE query = 'pog';",
+            )
+            .unwrap();
+
+        let report = cache.integrity_check(false).await.unwrap();
+        assert!(report.storage_errors.is_empty());
+        assert_eq!(report.undecodable_entries, vec!["hades"]);
+        assert_eq!(report.bad_timestamps, vec!["celeste", "pog"]);
+        assert_eq!(report.tombstones_without_expiry, vec!["kekw"]);
+        assert_eq!((report.problems(), report.repaired), (4, 0));
+        assert!(!report.is_ok());
+        // A check alone changes nothing (stats cannot even read the bad timestamp)
+        assert!(cache.stats().await.is_err());
+        let rows: i64 = cache.connection().query_row("SELECT COUNT(*) FROM game_cache", [], |row| row.get(0)).unwrap();
+        assert_eq!(rows, 5);
+
+        let report = cache.integrity_check(true).await.unwrap();
+        assert_eq!(report.repaired, 4);
+        assert!(report.is_ok());
+        assert_eq!(cache.integrity_check(false).await.unwrap(), IntegrityReport::default());
+        assert!(cache.get("zelda").await.unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_unknown_storage_format_is_undecodable() {
+        let cache = SqliteCache::new(":memory:").await.unwrap();
+        cache.save("zelda", &GameResult::new("steam", "1", "Zelda"), &[]).await.unwrap();
+        cache.connection().execute("UPDATE game_cache SET game_data = X'09FF' WHERE query = 'zelda'", []).unwrap();
+
+        assert!(cache.get("zelda").await.is_err());
+        assert_eq!(cache.integrity_check(false).await.unwrap().undecodable_entries, vec!["zelda"]);
+        assert_eq!(cache.recompress().await.unwrap(), RecompressReport { skipped: 1, ..Default::default() });
+    }
+
+    /// Storage type of each row's game data, by key
+    #[cfg(feature = "compression")]
+    fn storage_types(cache: &SqliteCache) -> Vec<(String, String)> {
+        cache
+            .connection()
+            .prepare("SELECT query, typeof(game_data) FROM game_cache ORDER BY query")
+            .unwrap()
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+            .unwrap()
+            .collect::<std::result::Result<_, _>>()
+            .unwrap()
+    }
+
+    #[cfg(feature = "compression")]
+    #[tokio::test]
+    async fn test_compressed_rows_and_recompress() {
+        let path = std::env::temp_dir().join(format!("kissbot_compression_{}.db", std::process::id()));
+        let db = path.to_str().unwrap();
+        remove_db(&path);
+        let mut hades = GameResult::new("steam", "1145360", "Hades");
+        hades.description = "<p>Defy the god of the dead as you hack and slash out of the Underworld.</p>".repeat(50);
+        let alternatives = vec![SlimGameResult::from(GameResult::new("steam", "1145350", "Hades II"))];
+        let types = |rows: &[(&str, &str)]| rows.iter().map(|(q, t)| (q.to_string(), t.to_string())).collect::<Vec<_>>();
+
+        let plain = SqliteCache::new(db).await.unwrap();
+        plain.save("hades", &hades, &alternatives).await.unwrap();
+        drop(plain);
+
+        // Rows written before compression was turned on stay readable
+        let options = SqliteCacheOptions { compression_level: Some(3), ..Default::default() };
+        let compressed = SqliteCache::with_options(db, options).await.unwrap();
+        compressed.save("hades ii", &hades, &alternatives).await.unwrap();
+        assert_eq!(storage_types(&compressed), types(&[("hades", "text"), ("hades ii", "blob")]));
+        for key in ["hades", "hades ii"] {
+            let cached = compressed.get(key).await.unwrap().unwrap();
+            assert_eq!(cached.game.description, hades.description);
+            assert_eq!(cached.alternatives[0].name, "Hades II");
+        }
+
+        let report = compressed.recompress().await.unwrap();
+        assert_eq!(report.rows, 1);
+        assert!(report.bytes_after * 5 < report.bytes_before);
+        assert_eq!(storage_types(&compressed), types(&[("hades", "blob"), ("hades ii", "blob")]));
+        assert_eq!(compressed.recompress().await.unwrap().rows, 0);
+        drop(compressed);
+
+        // And back to plain JSON, for the Python bot
+        let plain = SqliteCache::new(db).await.unwrap();
+        assert_eq!(plain.recompress().await.unwrap().rows, 2);
+        assert_eq!(storage_types(&plain), types(&[("hades", "text"), ("hades ii", "text")]));
+        assert_eq!(plain.get("hades ii").await.unwrap().unwrap().game.description, hades.description);
+        drop(plain);
+        remove_db(&path);
+    }
+
+    /// 1,000 saves into a 100-entry cache, every 20th entry hit right away
+    async fn fill_capped(eviction: EvictionPolicy) -> SqliteCache {
+        let options = SqliteCacheOptions { max_entries: Some(100), eviction, ..Default::default() };
+        let cache = SqliteCache::with_options(":memory:", options).await.unwrap();
+        for i in 0..1000 {
+            let query = format!("game {}", i);
+            cache.save(&query, &GameResult::new("steam", i.to_string(), "Game"), &[]).await.unwrap();
+            if i % 20 == 0 {
+                for _ in 0..3 {
+                    cache.increment_hit(&query).await.unwrap();
+                }
+            }
+        }
+        cache
+    }
+
+    #[tokio::test]
+    async fn test_failed_eviction_rolls_back_save() {
+        let options = SqliteCacheOptions { max_entries: Some(2), ..Default::default() };
+        let cache = SqliteCache::with_options(":memory:", options).await.unwrap();
+        cache.save("a", &GameResult::new("steam", "1", "A"), &[]).await.unwrap();
+        cache.save("b", &GameResult::new("steam", "2", "B"), &[]).await.unwrap();
+        cache.increment_hit("b").await.unwrap();
+
+        // Saved and evicted together, or neither
+        fail_on_delete(&cache, "a");
+        assert!(cache.save("c", &GameResult::new("steam", "3", "C"), &[]).await.is_err());
+        assert!(cache.get("c").await.unwrap().is_none());
+        assert_eq!(cache.stats().await.unwrap().total_entries, 2);
+
+        clear_failpoint(&cache);
+        cache.save("c", &GameResult::new("steam", "3", "C"), &[]).await.unwrap();
+        assert!(cache.get("a").await.unwrap().is_none());
+        assert!(cache.get("c").await.unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_eviction_keeps_most_hit_entries() {
+        for policy in [EvictionPolicy::LeastHit, EvictionPolicy::HitsAndAge] {
+            let cache = fill_capped(policy).await;
+            let stats = cache.stats().await.unwrap();
+            assert!((90..=100).contains(&stats.total_entries), "{:?}: {}", policy, stats.total_entries);
+
+            for i in (0..1000).step_by(20) {
+                let cached = cache.get(&format!("game {}", i)).await.unwrap();
+                assert_eq!(cached.map(|c| c.hit_count), Some(3), "{:?} evicted game {}", policy, i);
+            }
+            // Room left goes to the latest saves
+            assert!(cache.get("game 999").await.unwrap().is_some());
+            assert!(cache.get("game 1").await.unwrap().is_none());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_eviction_oldest_first() {
+        let cache = fill_capped(EvictionPolicy::OldestFirst).await;
+        assert!(cache.stats().await.unwrap().total_entries <= 100);
+        assert!(cache.get("game 0").await.unwrap().is_none());
+        assert!(cache.get("game 999").await.unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_aliases_outside_entries() {
+        let cache = SqliteCache::new(":memory:").await.unwrap();
+        for query in ["vampire survivors", "vampire hunters", "zelda"] {
+            cache.save(query, &GameResult::new("steam", query, query), &[]).await.unwrap();
+        }
+        cache.increment_hit("vampire hunters").await.unwrap();
+        cache.save_no_result("vampyr", Duration::hours(1)).await.unwrap();
+        assert_eq!(cache.keys_matching("vam%", 10).await.unwrap(), vec!["vampire hunters", "vampire survivors"]);
+        assert_eq!(cache.keys_matching("vam%", 1).await.unwrap().len(), 1);
+
+        cache.save_alias("Vampire Survivor", "vampire survivors").await.unwrap();
+        cache.save_alias("zeldaa", "zelda").await.unwrap();
+        assert_eq!(cache.alias("vampire survivor").await.unwrap().as_deref(), Some("vampire survivors"));
+        let stats = cache.stats().await.unwrap();
+        assert_eq!((stats.total_entries, stats.aliases), (3, 2));
+
+        // Doctor: an alias to a deleted entry dangles
+        cache.delete("zelda").await.unwrap();
+        let report = cache.integrity_check(true).await.unwrap();
+        assert_eq!(report.dangling_aliases, vec!["zeldaa"]);
+        assert!(report.is_ok());
+        assert_eq!(cache.alias("zeldaa").await.unwrap(), None);
+
+        // Invalidation takes the aliases to the entry along
+        cache.invalidate_where("vampire survivors", &[]).await.unwrap();
+        assert_eq!(cache.stats().await.unwrap().aliases, 0);
+    }
+
+    #[tokio::test]
+    async fn test_get_fresh_filters_on_age() {
+        let cache = SqliteCache::new(":memory:").await.unwrap();
+        cache.save("a", &GameResult::new("steam", "1", "A"), &[]).await.unwrap();
+        assert!(cache.get_fresh("a", Duration::days(7)).await.unwrap().is_some());
+
+        cache.connection()
+            .execute("UPDATE game_cache SET cached_at = ?1", params![(Utc::now() - Duration::days(8)).to_rfc3339()])
+            .unwrap();
+        assert!(cache.get_fresh("a", Duration::days(7)).await.unwrap().is_none());
+        assert!(cache.get_fresh("a", Duration::days(9)).await.unwrap().is_some());
+        // Expired, not deleted
+        assert!(cache.get("a").await.unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_cache_save_scored() {
+        let cache = SqliteCache::new(":memory:").await.unwrap();
+        let game = GameResult::new("steam", "1", "Hades");
+
+        cache.save("hades", &game, &[]).await.unwrap();
+        assert_eq!(cache.get("hades").await.unwrap().unwrap().score, None);
+
+        cache.save_scored("hades", &game, &[], 82.5).await.unwrap();
+        assert_eq!(cache.get("hades").await.unwrap().unwrap().score, Some(82.5));
+    }
+
+    #[tokio::test]
+    async fn test_score_column_added_to_legacy_table() {
+        let path = std::env::temp_dir().join(format!("kissbot_legacy_{}.db", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        {
+            let conn = Connection::open(&path).unwrap();
+            conn.execute_batch(
+                "CREATE TABLE game_cache (
+                    query TEXT PRIMARY KEY,
+                    game_data TEXT NOT NULL,
+                    alternatives TEXT,
+                    hit_count INTEGER NOT NULL DEFAULT 0,
+                    cached_at TEXT NOT NULL
+                );",
+            )
+            .unwrap();
+            conn.execute(
+                "INSERT INTO game_cache VALUES ('zelda', ?1, '[]', 3, ?2)",
+                params![GameResult::new("steam", "1", "Zelda").to_json().unwrap(), Utc::now().to_rfc3339()],
+            )
+            .unwrap();
+        }
+
+        let cache = SqliteCache::new(path.to_str().unwrap()).await.unwrap();
+        let cached = cache.get("zelda").await.unwrap().unwrap();
+        assert_eq!(cached.hit_count, 3);
+        assert_eq!(cached.score, None);
+
+        drop(cache);
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[tokio::test]
+    async fn test_legacy_python_database_upgraded() {
+        let path = std::env::temp_dir().join(format!("kissbot_python_schema_{}.db", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let cached_at = (Utc::now() - Duration::days(3)).to_rfc3339();
+        {
+            // As created by the Python bot: no schema_version, no engine columns
+            let conn = Connection::open(&path).unwrap();
+            conn.execute_batch(
+                "CREATE TABLE game_cache (
+                    query TEXT PRIMARY KEY,
+                    game_data TEXT NOT NULL,
+                    alternatives TEXT,
+                    hit_count INTEGER NOT NULL DEFAULT 0,
+                    cached_at TEXT NOT NULL
+                );",
+            )
+            .unwrap();
+            let alternatives = serde_json::to_string(&[SlimGameResult::from(GameResult::new("steam", "2", "Hades II"))]).unwrap();
+            conn.execute(
+                "INSERT INTO game_cache VALUES ('hades', ?1, ?2, 7, ?3)",
+                params![GameResult::new("steam", "1", "Hades").to_json().unwrap(), alternatives, cached_at],
+            )
+            .unwrap();
+        }
+
+        let cache = SqliteCache::new(path.to_str().unwrap()).await.unwrap();
+        let version: u32 = cache.connection().query_row("SELECT version FROM schema_version", [], |row| row.get(0)).unwrap();
+        assert_eq!(version, SCHEMA_VERSION);
+
+        let cached = cache.get("hades").await.unwrap().unwrap();
+        assert_eq!((cached.game.name.as_str(), cached.hit_count), ("Hades", 7));
+        assert_eq!(cached.alternatives[0].name, "Hades II");
+        assert_eq!(cached.cached_at, parse_timestamp(&cached_at).unwrap());
+        // New columns usable on the upgraded table
+        cache.save_no_result("kekw", Duration::hours(1)).await.unwrap();
+        cache.increment_hit("hades").await.unwrap();
+        assert_eq!(cache.top_queries(1, Some(Utc::now() - Duration::hours(1))).await.unwrap()[0].1, 8);
+        drop(cache);
+
+        // Reopening runs nothing again
+        let cache = SqliteCache::new(path.to_str().unwrap()).await.unwrap();
+        let rows: i64 = cache.connection().query_row("SELECT COUNT(*) FROM schema_version", [], |row| row.get(0)).unwrap();
+        assert_eq!(rows, 1);
+        assert!(cache.is_no_result("kekw").await.unwrap());
+        drop(cache);
+
+        // A newer binary's database is left alone
+        Connection::open(&path).unwrap().execute("UPDATE schema_version SET version = version + 1", []).unwrap();
+        let err = SqliteCache::new(path.to_str().unwrap()).await.err().unwrap();
+        assert!(err.to_string().contains("newer than this binary supports"), "{}", err);
+        let version: u32 = Connection::open(&path).unwrap().query_row("SELECT version FROM schema_version", [], |row| row.get(0)).unwrap();
+        assert_eq!(version, SCHEMA_VERSION + 1);
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[tokio::test]
+    async fn test_no_result_tombstone() {
+        let cache = SqliteCache::new(":memory:").await.unwrap();
+
+        cache.save_no_result("pog", Duration::hours(1)).await.unwrap();
+        assert!(cache.is_no_result("POG").await.unwrap());
+        assert!(cache.get("pog").await.unwrap().is_none());
+
+        let stats = cache.stats().await.unwrap();
+        assert_eq!(stats.total_entries, 0);
+        assert_eq!(stats.no_result_entries, 1);
+
+        // Real result replaces the tombstone
+        cache.save("pog", &GameResult::new("steam", "1", "Pog"), &[]).await.unwrap();
+        assert!(!cache.is_no_result("pog").await.unwrap());
+        assert!(cache.get("pog").await.unwrap().is_some());
+
+        // ...and a tombstone never overwrites a real result
+        cache.save_no_result("pog", Duration::hours(1)).await.unwrap();
+        assert!(cache.get("pog").await.unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_no_result_expiry_and_cleanup() {
+        let cache = SqliteCache::new(":memory:").await.unwrap();
+        cache.save("zelda", &GameResult::new("steam", "1", "Zelda"), &[]).await.unwrap();
+        cache.save_no_result("kekw", Duration::seconds(-1)).await.unwrap();
+        cache.save_no_result("pog", Duration::hours(1)).await.unwrap();
+
+        // Expired tombstone no longer short-circuits
+        assert!(!cache.is_no_result("kekw").await.unwrap());
+
+        // Only the expired tombstone is removed
+        assert_eq!(cache.cleanup(30).await.unwrap(), 1);
+        let stats = cache.stats().await.unwrap();
+        assert_eq!(stats.total_entries, 1);
+        assert_eq!(stats.no_result_entries, 1);
+    }
+
+    #[tokio::test]
+    async fn test_corrupted_entry_keeps_context_and_source() {
+        let cache = SqliteCache::new(":memory:").await.unwrap();
+        cache.save("zelda", &GameResult::new("steam", "1", "Zelda"), &[]).await.unwrap();
+
+        // Simulate on-disk corruption
+        cache.connection()
+            .execute("UPDATE game_cache SET game_data = 'not json' WHERE query = 'zelda'", [])
+            .unwrap();
+
+        let err = cache.get("zelda").await.ctx_query("zelda").unwrap_err();
+
+        let context = err.context().expect("context attached");
+        assert_eq!(context.query.as_deref(), Some("zelda"));
+        assert_eq!(context.phase, Some(Phase::CacheDecode));
+        assert!(matches!(err.root(), GameEngineError::Json(_)));
+
+        // Source chain reaches the original serde error
+        let mut source = std::error::Error::source(&err);
+        let mut found_serde = false;
+        while let Some(e) = source {
+            if e.downcast_ref::<serde_json::Error>().is_some() {
+                found_serde = true;
+            }
+            source = e.source();
+        }
+        assert!(found_serde);
+    }
+
+    #[tokio::test]
+    async fn test_search_log_roundtrip_and_cleanup() {
+        let cache = SqliteCache::new(":memory:").await.unwrap();
+        let entry = |query: &str, days_ago: i64| SearchLogEntry {
+            query: query.to_string(),
+            resolved_name: Some(query.to_uppercase()),
+            score: Some(90.0),
+            provider: Some("steam".to_string()),
+            from_cache: false,
+            latency_ms: 12.5,
+            timestamp: Utc::now() - Duration::days(days_ago),
+        };
+        cache.log_searches(&[entry("old", 40), entry("zelda", 1), entry("hades", 0)]).await.unwrap();
+
+        let recent = cache.recent_searches(2).await.unwrap();
+        assert_eq!(recent.len(), 2);
+        assert_eq!(recent[0].query, "hades");
+        assert_eq!(recent[1].resolved_name.as_deref(), Some("ZELDA"));
+
+        assert_eq!(cache.cleanup_log(30).await.unwrap(), 1);
+        assert_eq!(cache.recent_searches(10).await.unwrap().len(), 2);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_concurrent_load_does_not_starve_executor() {
+        let path = std::env::temp_dir().join(format!("kissbot_sqlite_load_{}.db", std::process::id()));
+        let cache = Arc::new(SqliteCache::new(path.to_str().unwrap()).await.unwrap());
+
+        // Ticks every 5ms; a blocked executor shows up as a long gap
+        let done = Arc::new(std::sync::atomic::AtomicBool::new(false));
+        let heartbeat = {
+            let done = done.clone();
+            tokio::spawn(async move {
+                let mut max_gap = std::time::Duration::ZERO;
+                let mut last = std::time::Instant::now();
+                while !done.load(std::sync::atomic::Ordering::Relaxed) {
+                    tokio::time::sleep(std::time::Duration::from_millis(5)).await;
+                    max_gap = max_gap.max(last.elapsed());
+                    last = std::time::Instant::now();
+                }
+                max_gap
+            })
+        };
+
+        let tasks: Vec<_> = (0..1000)
+            .map(|i| {
+                let cache = cache.clone();
+                tokio::spawn(async move {
+                    let query = format!("game {}", i % 100);
+                    if i % 2 == 0 {
+                        cache.save(&query, &GameResult::new("steam", i.to_string(), "Game"), &[]).await
+                    } else {
+                        cache.get(&query).await.map(|_| ())
+                    }
+                })
+            })
+            .collect();
+        for task in tasks {
+            task.await.unwrap().unwrap();
+        }
+        done.store(true, std::sync::atomic::Ordering::Relaxed);
+        let max_gap = heartbeat.await.unwrap();
+
+        // Even i: even keys only
+        assert_eq!(cache.stats().await.unwrap().total_entries, 50);
+        assert!(max_gap < std::time::Duration::from_millis(500), "executor stalled for {:?}", max_gap);
+
+        drop(cache);
+        for suffix in ["", "-wal", "-shm", "-journal"] {
+            let _ = std::fs::remove_file(format!("{}{}", path.display(), suffix));
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_two_instances_share_a_file() {
+        let path = std::env::temp_dir().join(format!("kissbot_sqlite_shared_{}.db", std::process::id()));
+        let path_str = path.to_str().unwrap();
+        // Like the Python bot and the engine, each with its own connections
+        let first = Arc::new(SqliteCache::new(path_str).await.unwrap());
+        let second = Arc::new(SqliteCache::new(path_str).await.unwrap());
+
+        let mode: String = first.connection().query_row("PRAGMA journal_mode", [], |row| row.get(0)).unwrap();
+        assert_eq!(mode, "wal");
+
+        let tasks: Vec<_> = (0..400)
+            .map(|i| {
+                let cache = if i % 2 == 0 { first.clone() } else { second.clone() };
+                tokio::spawn(async move {
+                    let query = format!("game {}", i);
+                    cache.save(&query, &GameResult::new("steam", i.to_string(), "Game"), &[]).await?;
+                    cache.increment_hit(&query).await?;
+                    cache.get(&format!("game {}", i / 2)).await.map(|_| ())
+                })
+            })
+            .collect();
+        for task in tasks {
+            task.await.unwrap().unwrap();
+        }
+
+        assert_eq!(first.stats().await.unwrap().total_entries, 400);
+        assert_eq!(second.stats().await.unwrap().total_hits, 400);
+
+        drop((first, second));
+        for suffix in ["", "-wal", "-shm"] {
+            let _ = std::fs::remove_file(format!("{}{}", path.display(), suffix));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_options_override_pragmas() {
+        let path = std::env::temp_dir().join(format!("kissbot_sqlite_pragmas_{}.db", std::process::id()));
+        let options = SqliteCacheOptions {
+            journal_mode: "delete".to_string(),
+            busy_timeout_ms: 250,
+            synchronous: "full".to_string(),
+            foreign_keys: false,
+            read_connections: 1,
+            ..Default::default()
+        };
+        let cache = SqliteCache::with_options(path.to_str().unwrap(), options).await.unwrap();
+
+        let conn = cache.connection();
+        let pragma = |name: &str| conn.query_row(&format!("PRAGMA {}", name), [], |row| row.get::<_, rusqlite::types::Value>(0)).unwrap();
+        assert_eq!(pragma("journal_mode"), rusqlite::types::Value::Text("delete".to_string()));
+        assert_eq!(pragma("busy_timeout"), rusqlite::types::Value::Integer(250));
+        // FULL
+        assert_eq!(pragma("synchronous"), rusqlite::types::Value::Integer(2));
+        assert_eq!(pragma("foreign_keys"), rusqlite::types::Value::Integer(0));
+        drop(conn);
+
+        // Defaults on a fresh connection pool
+        let defaults = SqliteCache::new(path.to_str().unwrap()).await.unwrap();
+        let conn = defaults.connection();
+        assert_eq!(conn.query_row("PRAGMA synchronous", [], |row| row.get::<_, i64>(0)).unwrap(), 1);
+        assert_eq!(conn.query_row("PRAGMA foreign_keys", [], |row| row.get::<_, i64>(0)).unwrap(), 1);
+        drop(conn);
+
+        drop((cache, defaults));
+        for suffix in ["", "-wal", "-shm", "-journal"] {
+            let _ = std::fs::remove_file(format!("{}{}", path.display(), suffix));
+        }
+    }
+
+    /// Cache on a temp file that gives up on locks at once, so only its own
+    /// retries can get past another connection's write transaction
+    async fn lock_test_cache(name: &str, busy_retries: u32) -> (SqliteCache, Connection, std::path::PathBuf) {
+        let path = std::env::temp_dir().join(format!("kissbot_sqlite_{}_{}.db", name, std::process::id()));
+        let options = SqliteCacheOptions {
+            busy_timeout_ms: 0,
+            busy_retries,
+            busy_retry_delay_ms: 20,
+            ..Default::default()
+        };
+        let cache = SqliteCache::with_options(path.to_str().unwrap(), options).await.unwrap();
+        let other = Connection::open(&path).unwrap();
+        other.execute_batch("BEGIN IMMEDIATE").unwrap();
+        (cache, other, path)
+    }
+
+    fn remove_db(path: &std::path::Path) {
+        for suffix in ["", "-wal", "-shm"] {
+            let _ = std::fs::remove_file(format!("{}{}", path.display(), suffix));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_save_retried_while_database_locked() {
+        let (cache, other, path) = lock_test_cache("busy", 20).await;
+
+        // Another process commits its write after ~100ms
+        let holder = std::thread::spawn(move || {
+            std::thread::sleep(std::time::Duration::from_millis(100));
+            other.execute_batch("COMMIT").unwrap();
+        });
+        cache.save("zelda", &GameResult::new("steam", "1", "Zelda"), &[]).await.unwrap();
+        holder.join().unwrap();
+
+        assert!(cache.get("zelda").await.unwrap().is_some());
+        assert!(cache.stats().await.unwrap().busy_retries >= 1);
+
+        drop(cache);
+        remove_db(&path);
+    }
+
+    #[tokio::test]
+    async fn test_persisting_lock_reported_after_retries() {
+        let (cache, other, path) = lock_test_cache("locked", 2).await;
+
+        let err = cache.save("zelda", &GameResult::new("steam", "1", "Zelda"), &[]).await.unwrap_err();
+        assert!(matches!(err.root(), GameEngineError::DatabaseLocked { attempts: 3, .. }), "{}", err.diagnostic());
+        assert_eq!(err.context().and_then(|c| c.phase), Some(Phase::CacheWrite));
+        assert!(err.root().to_string().contains("still locked after 3 attempts"));
+        assert_eq!(cache.stats().await.unwrap().busy_retries, 2);
+
+        // Reads are not blocked by a write transaction in WAL mode
+        assert!(cache.get("zelda").await.unwrap().is_none());
+
+        other.execute_batch("ROLLBACK").unwrap();
+        cache.save("zelda", &GameResult::new("steam", "1", "Zelda"), &[]).await.unwrap();
+        drop(cache);
+        remove_db(&path);
+    }
+
+    #[tokio::test]
+    async fn test_panic_in_query_returns_connection() {
+        let cache = SqliteCache::new(":memory:").await.unwrap();
+        let panicked = cache.write(|_| -> Result<()> { panic!("boom") }).await;
+        assert!(panicked.is_err());
+
+        // Connection back in the pool, nothing poisoned
+        cache.save("zelda", &GameResult::new("steam", "1", "Zelda"), &[]).await.unwrap();
+        assert!(cache.get("zelda").await.unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_schema_baseline_roundtrip() {
+        let cache = SqliteCache::new(":memory:").await.unwrap();
+        assert!(cache.schema_baseline("steam").await.unwrap().is_none());
+
+        let v1 = Fingerprint::from_body("search", r#"[{"appid": 1}]"#);
+        let v2 = Fingerprint::from_body("search", r#"[{"appid": 1, "name": "x"}]"#);
+        cache.save_schema_baseline("steam", &v1).await.unwrap();
+        cache.save_schema_baseline("steam", &v2).await.unwrap();
+
+        assert_eq!(cache.schema_baseline("steam").await.unwrap(), Some(v2));
+    }
+}
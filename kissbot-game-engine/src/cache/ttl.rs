@@ -0,0 +1,232 @@
+//! Adaptive cache TTL: how long a cached result is trusted before the next
+//! search refreshes it from the providers
+
+use chrono::{DateTime, Datelike, Duration, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::core::{GameResult, ReleaseStatus};
+
+/// Age in years from which a game gets the maximum base TTL
+const MATURE_AGE_YEARS: f64 = 10.0;
+
+/// Assumed age when the provider gives no release year
+const UNKNOWN_AGE_YEARS: f64 = 1.0;
+
+/// Bounds and growth of the adaptive TTL
+#[derive(Debug, Clone, PartialEq)]
+pub struct TtlPolicy {
+    /// TTL of brand new, early access and unreleased games
+    pub min: Duration,
+    /// Upper bound, reached by games `MATURE_AGE_YEARS` old
+    pub max: Duration,
+    /// TTL multiplier per consecutive refresh that found nothing new
+    pub unchanged_factor: f64,
+    /// Hard expiry of unreleased games, whose metadata keeps changing: past
+    /// it they are misses even if the providers are down (None: the global
+    /// max age applies)
+    pub unreleased_expiry: Option<Duration>,
+}
+
+impl Default for TtlPolicy {
+    fn default() -> Self {
+        Self {
+            min: Duration::days(1),
+            max: Duration::days(90),
+            unchanged_factor: 2.0,
+            unreleased_expiry: Some(Duration::days(3)),
+        }
+    }
+}
+
+/// TTL computed for a cache entry, and why
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TtlDecision {
+    pub ttl_secs: i64,
+    /// Entry is refreshed by the first search after this
+    pub refresh_after: DateTime<Utc>,
+    /// Consecutive refreshes that returned the same game data
+    pub unchanged_refreshes: u32,
+    /// Human-readable derivation ("released 2021 (5y old): base 45d 12h, ...")
+    pub reason: String,
+    /// Past this the entry is a miss and cleanup deletes it, in place of the
+    /// global max age (None: the global max age applies)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+impl TtlDecision {
+    /// Whether the entry should be refreshed at `now`
+    pub fn is_due(&self, now: DateTime<Utc>) -> bool {
+        now >= self.refresh_after
+    }
+}
+
+/// Announced but not out at `now`: coming soon on the store, or dated
+/// after this year
+fn is_upcoming(game: &GameResult, now: DateTime<Utc>) -> bool {
+    game.is_unreleased() || game.year.is_some_and(|year| year > now.year())
+}
+
+impl TtlPolicy {
+    /// TTL of `game` cached at `now`, after `unchanged_refreshes` refreshes
+    /// in a row found the same data. Older games get longer TTLs, between
+    /// `min` and `max`; unreleased games always get `min`, and expire after
+    /// `unreleased_expiry`.
+    pub fn decide(&self, game: &GameResult, unchanged_refreshes: u32, now: DateTime<Utc>) -> TtlDecision {
+        let min = self.min.num_seconds().max(1) as f64;
+        let max = (self.max.num_seconds() as f64).max(min);
+        let upcoming = is_upcoming(game, now);
+
+        let (ttl, mut reason) = match &game.release_status {
+            _ if upcoming => {
+                (min, format!("unreleased: fixed at min {}", format_secs(min)))
+            }
+            status => {
+                let (age, what) = match (status, game.year) {
+                    (Some(ReleaseStatus::EarlyAccess), _) => (0.0, "early access".to_string()),
+                    (_, Some(year)) => {
+                        let age = (now.year() - year).max(0);
+                        (age as f64, format!("released {} ({}y old)", year, age))
+                    }
+                    (_, None) => (UNKNOWN_AGE_YEARS, "release year unknown".to_string()),
+                };
+                let base = min + (max - min) * (age / MATURE_AGE_YEARS).min(1.0);
+                let mut reason = format!("{}: base {}", what, format_secs(base));
+
+                let factor = self.unchanged_factor.max(1.0).powi(unchanged_refreshes.min(64) as i32);
+                let mut ttl = base * factor;
+                if unchanged_refreshes > 0 {
+                    reason.push_str(&format!(
+                        ", x{} after {} unchanged refresh{}",
+                        factor,
+                        unchanged_refreshes,
+                        if unchanged_refreshes == 1 { "" } else { "es" }
+                    ));
+                }
+                if ttl > max {
+                    ttl = max;
+                    reason.push_str(&format!(", capped at max {}", format_secs(max)));
+                }
+                (ttl, reason)
+            }
+        };
+
+        let expiry = self.unreleased_expiry.filter(|_| upcoming);
+        if let Some(expiry) = expiry {
+            reason.push_str(&format!(", expires after {}", format_secs(expiry.num_seconds() as f64)));
+        }
+
+        let ttl_secs = ttl.round() as i64;
+        TtlDecision {
+            ttl_secs,
+            refresh_after: now + Duration::seconds(ttl_secs),
+            unchanged_refreshes,
+            reason,
+            expires_at: expiry.map(|expiry| now + expiry),
+        }
+    }
+}
+
+/// Whether a refresh brought nothing new (fetch time ignored)
+pub fn unchanged(previous: &GameResult, fresh: &GameResult) -> bool {
+    let mut fresh = fresh.clone();
+    fresh.fetched_at = previous.fetched_at;
+    *previous == fresh
+}
+
+/// "3d 4h", "12h", "45m"
+fn format_secs(secs: f64) -> String {
+    let secs = secs.round() as i64;
+    let (days, hours, minutes) = (secs / 86_400, secs % 86_400 / 3_600, secs % 3_600 / 60);
+    match (days, hours) {
+        (0, 0) => format!("{}m", minutes.max(1)),
+        (0, h) => format!("{}h", h),
+        (d, 0) => format!("{}d", d),
+        (d, h) => format!("{}d {}h", d, h),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn now() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 6, 1, 12, 0, 0).unwrap()
+    }
+
+    fn game(year: Option<i32>, status: Option<ReleaseStatus>) -> GameResult {
+        let mut game = GameResult::new("steam", "1", "Game");
+        game.year = year;
+        game.release_status = status;
+        game
+    }
+
+    fn ttl_days(game: &GameResult, unchanged: u32) -> f64 {
+        TtlPolicy::default().decide(game, unchanged, now()).ttl_secs as f64 / 86_400.0
+    }
+
+    #[test]
+    fn test_ttl_grows_with_age() {
+        let released = Some(ReleaseStatus::Released);
+        assert_eq!(ttl_days(&game(Some(2026), released.clone()), 0), 1.0);
+        assert!((ttl_days(&game(Some(2021), released.clone()), 0) - 45.5).abs() < 0.01);
+        assert_eq!(ttl_days(&game(Some(2012), released.clone()), 0), 90.0);
+        // Unknown year and unknown status: treated as one year old
+        assert!((ttl_days(&game(None, None), 0) - 9.9).abs() < 0.01);
+
+        let decision = TtlPolicy::default().decide(&game(Some(2012), released), 0, now());
+        assert_eq!(decision.reason, "released 2012 (14y old): base 90d");
+        assert_eq!(decision.refresh_after, now() + Duration::days(90));
+    }
+
+    #[test]
+    fn test_ttl_by_release_status() {
+        let unreleased = game(None, Some(ReleaseStatus::ComingSoon { date: Some("Q1 2027".to_string()) }));
+        assert_eq!(ttl_days(&unreleased, 0), 1.0);
+        // Never extended, the release can happen any day
+        assert_eq!(ttl_days(&unreleased, 5), 1.0);
+        let decision = TtlPolicy::default().decide(&unreleased, 0, now());
+        assert_eq!(decision.reason, "unreleased: fixed at min 1d, expires after 3d");
+        assert_eq!(decision.expires_at, Some(now() + Duration::days(3)));
+        // Dated after this year counts as unreleased too
+        let dated = TtlPolicy::default().decide(&game(Some(2027), None), 3, now());
+        assert_eq!((dated.ttl_secs, dated.expires_at), (86_400, Some(now() + Duration::days(3))));
+        let policy = TtlPolicy { unreleased_expiry: None, ..Default::default() };
+        assert_eq!(policy.decide(&unreleased, 0, now()).expires_at, None);
+        assert_eq!(TtlPolicy::default().decide(&game(Some(2026), None), 0, now()).expires_at, None);
+
+        // Early access games change like new ones, whatever their year
+        let early = game(Some(2018), Some(ReleaseStatus::EarlyAccess));
+        assert_eq!(ttl_days(&early, 0), 1.0);
+        assert_eq!(ttl_days(&early, 2), 4.0);
+    }
+
+    #[test]
+    fn test_unchanged_refreshes_extend_ttl() {
+        let recent = game(Some(2026), Some(ReleaseStatus::Released));
+        assert_eq!(ttl_days(&recent, 1), 2.0);
+        assert_eq!(ttl_days(&recent, 3), 8.0);
+        // Bounded by max
+        assert_eq!(ttl_days(&recent, 10), 90.0);
+        assert_eq!(ttl_days(&recent, u32::MAX), 90.0);
+
+        let decision = TtlPolicy::default().decide(&recent, 10, now());
+        assert_eq!(decision.unchanged_refreshes, 10);
+        assert_eq!(decision.reason, "released 2026 (0y old): base 1d, x1024 after 10 unchanged refreshes, capped at max 90d");
+
+        let old = game(Some(2021), Some(ReleaseStatus::Released));
+        assert_eq!(ttl_days(&old, 1), 90.0);
+    }
+
+    #[test]
+    fn test_unchanged_ignores_fetch_time() {
+        let previous = game(Some(2020), None);
+        let mut fresh = previous.clone();
+        fresh.fetched_at = previous.fetched_at + Duration::days(3);
+        assert!(unchanged(&previous, &fresh));
+
+        fresh.metacritic_score = Some(88);
+        assert!(!unchanged(&previous, &fresh));
+    }
+}
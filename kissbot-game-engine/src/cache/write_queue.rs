@@ -0,0 +1,266 @@
+use chrono::Duration;
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+
+use crate::cache::{like_matches, GameCache, TtlDecision};
+use crate::core::{GameResult, SlimGameResult};
+use crate::error::Result;
+use crate::providers::RetryPolicy;
+
+/// Settings of the failed cache write retry queue
+#[derive(Debug, Clone)]
+pub struct WriteRetryConfig {
+    /// Max pending writes, the oldest one is dropped when full
+    pub capacity: usize,
+    /// Retries per write before giving up on it
+    pub max_attempts: u32,
+    /// Delay between retry passes, growing while the cache keeps failing
+    pub backoff: RetryPolicy,
+}
+
+impl Default for WriteRetryConfig {
+    fn default() -> Self {
+        Self {
+            capacity: 256,
+            max_attempts: 5,
+            backoff: RetryPolicy {
+                base_delay: std::time::Duration::from_secs(5),
+                max_delay: std::time::Duration::from_secs(300),
+                ..Default::default()
+            },
+        }
+    }
+}
+
+/// Cache write that failed and is waiting to be replayed
+#[derive(Debug, Clone)]
+pub enum PendingWrite {
+    Scored {
+        key: String,
+        game: Box<GameResult>,
+        alternatives: Vec<SlimGameResult>,
+        score: f64,
+        ranking_method: String,
+        ttl: Option<TtlDecision>,
+    },
+    NoResult {
+        key: String,
+        ttl: Duration,
+    },
+}
+
+impl PendingWrite {
+    pub fn key(&self) -> &str {
+        match self {
+            PendingWrite::Scored { key, .. } | PendingWrite::NoResult { key, .. } => key,
+        }
+    }
+
+    async fn apply(&self, cache: &dyn GameCache) -> Result<()> {
+        match self {
+            PendingWrite::Scored { key, game, alternatives, score, ranking_method, ttl: Some(ttl) } => {
+                cache.save_with_ttl(key, game, alternatives, *score, ranking_method, ttl).await
+            }
+            PendingWrite::Scored { key, game, alternatives, score, ttl: None, .. } => {
+                cache.save_scored(key, game, alternatives, *score).await
+            }
+            PendingWrite::NoResult { key, ttl } => cache.save_no_result(key, *ttl).await,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Entry {
+    write: PendingWrite,
+    attempts: u32,
+}
+
+/// Retry queue counters
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct WriteRetryStats {
+    /// Writes waiting to be replayed
+    pub pending: u64,
+    /// Deferred writes that eventually landed
+    pub succeeded: u64,
+    /// Writes dropped because the queue was full or attempts ran out
+    pub dropped: u64,
+}
+
+/// Bounded in-memory queue of failed cache writes, replayed by
+/// `GameEngine::spawn_cache_write_retry` once the cache is healthy again
+#[derive(Debug)]
+pub struct WriteRetryQueue {
+    config: WriteRetryConfig,
+    entries: Mutex<VecDeque<Entry>>,
+    succeeded: AtomicU64,
+    dropped: AtomicU64,
+}
+
+impl WriteRetryQueue {
+    pub fn new(config: WriteRetryConfig) -> Self {
+        Self {
+            config,
+            entries: Mutex::new(VecDeque::new()),
+            succeeded: AtomicU64::new(0),
+            dropped: AtomicU64::new(0),
+        }
+    }
+
+    pub fn config(&self) -> &WriteRetryConfig {
+        &self.config
+    }
+
+    /// Queue a failed write, replacing any pending write for the same key
+    /// and dropping the oldest entry when full
+    pub fn push(&self, write: PendingWrite) {
+        if self.config.capacity == 0 {
+            self.dropped.fetch_add(1, Ordering::Relaxed);
+            return;
+        }
+
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        entries.retain(|e| e.write.key() != write.key());
+        if entries.len() >= self.config.capacity {
+            if let Some(oldest) = entries.pop_front() {
+                tracing::warn!("⚠️ Cache retry queue full, dropping write for '{}'", oldest.write.key());
+                self.dropped.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+        entries.push_back(Entry { write, attempts: 0 });
+    }
+
+    /// Discard a pending write superseded by a successful one
+    pub fn forget(&self, key: &str) {
+        self.entries
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .retain(|e| e.write.key() != key);
+    }
+
+    /// Discard pending writes for keys matching a LIKE `pattern` (see
+    /// `GameCache::delete_matching`), return number discarded
+    pub fn forget_matching(&self, pattern: &str) -> usize {
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        let before = entries.len();
+        entries.retain(|e| !like_matches(pattern, e.write.key()));
+        before - entries.len()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.lock().unwrap_or_else(|e| e.into_inner()).len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn stats(&self) -> WriteRetryStats {
+        WriteRetryStats {
+            pending: self.len() as u64,
+            succeeded: self.succeeded.load(Ordering::Relaxed),
+            dropped: self.dropped.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Replay pending writes oldest first. Stops at the first failure (the
+    /// cache is most likely still unhealthy) so the remaining entries keep
+    /// their attempts. Returns `(succeeded, failed)` for this pass.
+    pub async fn replay(&self, cache: &dyn GameCache) -> (usize, usize) {
+        let mut succeeded = 0;
+
+        loop {
+            let next = self.entries.lock().unwrap_or_else(|e| e.into_inner()).pop_front();
+            let Some(mut entry) = next else {
+                return (succeeded, 0);
+            };
+
+            match entry.write.apply(cache).await {
+                Ok(()) => {
+                    succeeded += 1;
+                    self.succeeded.fetch_add(1, Ordering::Relaxed);
+                }
+                Err(e) => {
+                    entry.attempts += 1;
+                    if entry.attempts >= self.config.max_attempts {
+                        tracing::warn!(
+                            "⚠️ Giving up cache write for '{}' after {} attempts: {}",
+                            entry.write.key(),
+                            entry.attempts,
+                            e.diagnostic()
+                        );
+                        self.dropped.fetch_add(1, Ordering::Relaxed);
+                    } else {
+                        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+                        // A newer write for this key queued meanwhile wins
+                        if !entries.iter().any(|e| e.write.key() == entry.write.key()) {
+                            entries.push_front(entry);
+                        }
+                    }
+                    return (succeeded, 1);
+                }
+            }
+        }
+    }
+}
+
+impl Default for WriteRetryQueue {
+    fn default() -> Self {
+        Self::new(WriteRetryConfig::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cache::{MemoryCache, NullCache};
+
+    fn write(key: &str) -> PendingWrite {
+        PendingWrite::NoResult {
+            key: key.to_string(),
+            ttl: Duration::hours(1),
+        }
+    }
+
+    fn queue(capacity: usize, max_attempts: u32) -> WriteRetryQueue {
+        WriteRetryQueue::new(WriteRetryConfig {
+            capacity,
+            max_attempts,
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn test_queue_is_bounded() {
+        let queue = queue(2, 5);
+        queue.push(write("a"));
+        queue.push(write("b"));
+        queue.push(write("a"));
+        assert_eq!(queue.len(), 2);
+
+        queue.push(write("c"));
+        assert_eq!(queue.stats(), WriteRetryStats { pending: 2, succeeded: 0, dropped: 1 });
+
+        queue.forget("c");
+        assert_eq!(queue.len(), 1);
+
+        queue.push(write("a|fr"));
+        assert_eq!(queue.forget_matching("a%"), 2);
+        assert!(queue.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_replay_lands_writes() {
+        let queue = queue(10, 5);
+        queue.push(write("a"));
+        queue.push(write("b"));
+
+        let cache = MemoryCache::default();
+        assert_eq!(queue.replay(&cache).await, (2, 0));
+        assert!(cache.is_no_result("a").await.unwrap());
+        assert_eq!(queue.stats(), WriteRetryStats { pending: 0, succeeded: 2, dropped: 0 });
+
+        // NullCache accepts everything, nothing left to do
+        assert_eq!(queue.replay(&NullCache).await, (0, 0));
+    }
+}
@@ -0,0 +1,622 @@
+//! Engine configuration file (YAML, TOML or JSON)
+//!
+//! ```yaml
+//! drakon_url: http://127.0.0.1:8000   # omit to rank in process
+//! cache:
+//!   backend: sqlite
+//!   path: kissbot.db
+//!   sqlite:
+//!     busy_timeout_ms: 5000
+//! providers:
+//!   - name: steam
+//!     timeout_secs: 10
+//!     max_attempts: 3
+//! search:
+//!   min_score: 75
+//!   provider_quota: 5
+//! http:
+//!   pool_max_idle_per_host: 16
+//! delta_profile:
+//!   jaccard_cap: 0.75
+//! ```
+
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::cache::codec;
+use crate::cache::sqlite::{JOURNAL_MODES, SYNCHRONOUS_LEVELS};
+use crate::cache::{CacheBackend, SqliteCacheOptions, TtlPolicy, DEFAULT_MEMORY_CAPACITY};
+use crate::core::PlatformFilterMode;
+use crate::engine::SearchOptions;
+use crate::ranking::DeltaProfile;
+use crate::error::{GameEngineError, Result};
+use crate::http::{HttpClient, HttpClientConfig};
+use crate::providers::{GameProvider, RetryPolicy, RetryingProvider, SteamProvider};
+
+/// Providers `ProviderConfig::name` may refer to
+pub const KNOWN_PROVIDERS: &[&str] = &["steam"];
+
+/// Everything needed to build a `GameEngine`, see `GameEngine::from_config`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct EngineConfig {
+    /// DRAKON ranking service; None ranks in process (`DeltaRanker`)
+    pub drakon_url: Option<String>,
+    pub cache: CacheConfig,
+    /// Providers registered in order (an empty list registers none)
+    pub providers: Vec<ProviderConfig>,
+    pub search: SearchConfig,
+    /// Connection pool of the HTTP client shared by providers and DRAKON
+    pub http: HttpClientConfig,
+    /// Weights and corrections of the in-process Δₛ³ ranker (TITLE
+    /// defaults for omitted fields)
+    pub delta_profile: DeltaProfile,
+}
+
+impl Default for EngineConfig {
+    fn default() -> Self {
+        Self {
+            drakon_url: None,
+            cache: CacheConfig::default(),
+            providers: vec![ProviderConfig::new("steam")],
+            search: SearchConfig::default(),
+            http: HttpClientConfig::default(),
+            delta_profile: DeltaProfile::title(),
+        }
+    }
+}
+
+/// Cache storage kind
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CacheKind {
+    Sqlite,
+    Memory,
+    None,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CacheConfig {
+    pub backend: CacheKind,
+    /// SQLite database file (":memory:" for a private in-memory database)
+    pub path: String,
+    /// Max entries of the `memory` backend
+    pub capacity: usize,
+    /// Connection settings of the `sqlite` backend
+    pub sqlite: SqliteCacheOptions,
+}
+
+impl Default for CacheConfig {
+    fn default() -> Self {
+        Self {
+            backend: CacheKind::Sqlite,
+            path: "kissbot.db".to_string(),
+            capacity: DEFAULT_MEMORY_CAPACITY,
+            sqlite: SqliteCacheOptions::default(),
+        }
+    }
+}
+
+impl CacheConfig {
+    pub fn backend(&self) -> CacheBackend {
+        match self.backend {
+            CacheKind::Sqlite => CacheBackend::Sqlite { path: self.path.clone(), options: self.sqlite.clone() },
+            CacheKind::Memory => CacheBackend::Memory { capacity: self.capacity },
+            CacheKind::None => CacheBackend::None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ProviderConfig {
+    /// One of `KNOWN_PROVIDERS`
+    pub name: String,
+    /// Registered but disabled providers can be enabled at runtime
+    pub enabled: bool,
+    pub api_key: Option<String>,
+    /// HTTP timeout per request
+    pub timeout_secs: f64,
+    /// Attempts per call on transient failures (1 disables retries)
+    pub max_attempts: u32,
+}
+
+impl Default for ProviderConfig {
+    fn default() -> Self {
+        Self {
+            name: String::new(),
+            enabled: true,
+            api_key: None,
+            timeout_secs: 10.0,
+            max_attempts: RetryPolicy::default().max_attempts,
+        }
+    }
+}
+
+impl ProviderConfig {
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            ..Default::default()
+        }
+    }
+
+    /// Instantiate the provider on the shared client, wrapped in its retry
+    /// policy
+    pub fn build(&self, http: &HttpClient) -> Result<Arc<dyn GameProvider>> {
+        let retry = RetryPolicy {
+            max_attempts: self.max_attempts,
+            ..Default::default()
+        };
+        let timeout = Duration::from_secs_f64(self.timeout_secs);
+
+        match self.name.as_str() {
+            "steam" => Ok(Arc::new(RetryingProvider::new(
+                SteamProvider::with_client(http.clone(), timeout),
+                retry,
+            ))),
+            other => Err(config_error("providers.name", format!("unknown provider '{}'", other))),
+        }
+    }
+}
+
+/// Serializable `SearchOptions`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SearchConfig {
+    pub cache_enabled: bool,
+    pub drakon_enabled: bool,
+    pub exact_score: f64,
+    pub min_score: f64,
+    pub not_found_score: f64,
+    pub max_alternatives: usize,
+    /// TTL of no-result tombstones in seconds (None disables negative caching)
+    pub no_result_ttl_secs: Option<i64>,
+    pub cache_min_score: f64,
+    pub cache_low_confidence: bool,
+    pub schema_sample_rate: f64,
+    pub enrich: bool,
+    pub provider_quota: Option<usize>,
+    pub log_queries: bool,
+    /// Adaptive cache TTL bounds in seconds
+    pub cache_ttl_min_secs: i64,
+    pub cache_ttl_max_secs: i64,
+    /// TTL multiplier per refresh that found nothing new
+    pub cache_ttl_unchanged_factor: f64,
+    /// Hard expiry of unreleased games in seconds (None: the max age applies)
+    pub cache_ttl_unreleased_expiry_secs: Option<i64>,
+    /// Max age of a served cache entry in seconds (None: no limit)
+    pub cache_max_age_secs: Option<i64>,
+    pub serve_stale_on_error: bool,
+    /// Max serialized size of a cache row in bytes (None: no limit)
+    pub cache_max_row_bytes: Option<usize>,
+    /// "strict" or "lenient" when no candidate is on the requested platforms
+    pub platform_filter: PlatformFilterMode,
+    /// Min ranker score to serve a near-identical cached query on a miss
+    /// (None disables the fuzzy cache lookup)
+    pub fuzzy_cache_min_score: Option<f64>,
+    /// Seconds past its TTL an entry is served while refreshed in the
+    /// background (None: refreshed before answering)
+    pub stale_while_revalidate_secs: Option<i64>,
+    /// Score gap under which candidates are ordered by year, then name
+    /// length (0 disables)
+    pub tie_epsilon: f64,
+    /// Candidates sent to DRAKON, the best by rapidfuzz (None: all)
+    pub drakon_prefilter: Option<usize>,
+    /// Weight of popularity in near-ties, 0-1 (None disables)
+    pub popularity_weight: Option<f64>,
+    /// Score gap to the best candidate within which popularity counts
+    pub popularity_band: f64,
+    /// Group the winner's editions in `editions` instead of `alternatives`
+    pub collapse_editions: bool,
+    /// Words marking an edition ("goty", "definitive"...)
+    pub edition_keywords: Vec<String>,
+    /// Consecutive DRAKON failures after which searches skip it
+    pub drakon_breaker_threshold: u32,
+    /// Seconds DRAKON is skipped before a search probes it again
+    pub drakon_breaker_cool_down_secs: i64,
+}
+
+impl Default for SearchConfig {
+    fn default() -> Self {
+        Self::from(&SearchOptions::default())
+    }
+}
+
+impl From<&SearchOptions> for SearchConfig {
+    fn from(options: &SearchOptions) -> Self {
+        Self {
+            cache_enabled: options.cache_enabled,
+            drakon_enabled: options.drakon_enabled,
+            exact_score: options.exact_score,
+            min_score: options.min_score,
+            not_found_score: options.not_found_score,
+            max_alternatives: options.max_alternatives,
+            no_result_ttl_secs: options.no_result_ttl.map(|ttl| ttl.num_seconds()),
+            cache_min_score: options.cache_min_score,
+            cache_low_confidence: options.cache_low_confidence,
+            schema_sample_rate: options.schema_sample_rate,
+            enrich: options.enrich,
+            provider_quota: options.provider_quota,
+            log_queries: options.log_queries,
+            cache_ttl_min_secs: options.cache_ttl.min.num_seconds(),
+            cache_ttl_max_secs: options.cache_ttl.max.num_seconds(),
+            cache_ttl_unchanged_factor: options.cache_ttl.unchanged_factor,
+            cache_ttl_unreleased_expiry_secs: options.cache_ttl.unreleased_expiry.map(|expiry| expiry.num_seconds()),
+            cache_max_age_secs: options.cache_max_age.map(|age| age.num_seconds()),
+            serve_stale_on_error: options.serve_stale_on_error,
+            cache_max_row_bytes: options.cache_max_row_bytes,
+            platform_filter: options.platform_filter,
+            fuzzy_cache_min_score: options.fuzzy_cache_min_score,
+            stale_while_revalidate_secs: options.stale_while_revalidate.map(|window| window.num_seconds()),
+            tie_epsilon: options.tie_epsilon,
+            drakon_prefilter: options.drakon_prefilter,
+            popularity_weight: options.popularity_weight,
+            popularity_band: options.popularity_band,
+            collapse_editions: options.collapse_editions,
+            edition_keywords: options.edition_keywords.clone(),
+            drakon_breaker_threshold: options.drakon_breaker_threshold,
+            drakon_breaker_cool_down_secs: options.drakon_breaker_cool_down.num_seconds(),
+        }
+    }
+}
+
+impl SearchConfig {
+    pub fn to_options(&self) -> SearchOptions {
+        SearchOptions {
+            cache_enabled: self.cache_enabled,
+            drakon_enabled: self.drakon_enabled,
+            exact_score: self.exact_score,
+            min_score: self.min_score,
+            not_found_score: self.not_found_score,
+            max_alternatives: self.max_alternatives,
+            no_result_ttl: self.no_result_ttl_secs.map(chrono::Duration::seconds),
+            cache_min_score: self.cache_min_score,
+            cache_low_confidence: self.cache_low_confidence,
+            schema_sample_rate: self.schema_sample_rate,
+            enrich: self.enrich,
+            provider_quota: self.provider_quota,
+            log_queries: self.log_queries,
+            cache_ttl: TtlPolicy {
+                min: chrono::Duration::seconds(self.cache_ttl_min_secs),
+                max: chrono::Duration::seconds(self.cache_ttl_max_secs),
+                unchanged_factor: self.cache_ttl_unchanged_factor,
+                unreleased_expiry: self.cache_ttl_unreleased_expiry_secs.map(chrono::Duration::seconds),
+            },
+            cache_max_age: self.cache_max_age_secs.map(chrono::Duration::seconds),
+            serve_stale_on_error: self.serve_stale_on_error,
+            cache_max_row_bytes: self.cache_max_row_bytes,
+            platform_filter: self.platform_filter,
+            fuzzy_cache_min_score: self.fuzzy_cache_min_score,
+            stale_while_revalidate: self.stale_while_revalidate_secs.map(chrono::Duration::seconds),
+            tie_epsilon: self.tie_epsilon,
+            drakon_prefilter: self.drakon_prefilter,
+            popularity_weight: self.popularity_weight,
+            popularity_band: self.popularity_band,
+            collapse_editions: self.collapse_editions,
+            edition_keywords: self.edition_keywords.clone(),
+            drakon_breaker_threshold: self.drakon_breaker_threshold,
+            drakon_breaker_cool_down: chrono::Duration::seconds(self.drakon_breaker_cool_down_secs),
+        }
+    }
+}
+
+fn config_error(field: impl Into<String>, message: impl Into<String>) -> GameEngineError {
+    GameEngineError::Config {
+        field: field.into(),
+        message: message.into(),
+    }
+}
+
+/// Deserialize keeping the path of the offending field
+fn parse<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<EngineConfig>
+where
+    D::Error: std::fmt::Display,
+{
+    let config: EngineConfig = serde_path_to_error::deserialize(deserializer)
+        .map_err(|e| config_error(e.path().to_string(), e.inner().to_string()))?;
+    config.validate()?;
+    Ok(config)
+}
+
+impl EngineConfig {
+    pub fn from_yaml(input: &str) -> Result<Self> {
+        parse(serde_yaml::Deserializer::from_str(input))
+    }
+
+    pub fn from_toml(input: &str) -> Result<Self> {
+        parse(toml::Deserializer::new(input))
+    }
+
+    pub fn from_json(input: &str) -> Result<Self> {
+        parse(&mut serde_json::Deserializer::from_str(input))
+    }
+
+    /// Load a `.yaml`/`.yml`, `.toml` or `.json` file
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let input = std::fs::read_to_string(path)
+            .map_err(|e| GameEngineError::Other(format!("Cannot read config {}: {}", path.display(), e)))?;
+
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("yaml") | Some("yml") => Self::from_yaml(&input),
+            Some("toml") => Self::from_toml(&input),
+            Some("json") => Self::from_json(&input),
+            _ => Err(GameEngineError::Other(format!(
+                "Unsupported config format {} (expected .yaml, .toml or .json)",
+                path.display()
+            ))),
+        }
+    }
+
+    /// Check values serde accepts but the engine cannot use
+    pub fn validate(&self) -> Result<()> {
+        if self.drakon_url.as_deref().is_some_and(|url| url.trim().is_empty()) {
+            return Err(config_error("drakon_url", "must not be empty (omit to rank in process)"));
+        }
+        self.delta_profile
+            .validate()
+            .map_err(|e| config_error(format!("delta_profile.{}", e.field), e.reason))?;
+        if self.cache.backend == CacheKind::Sqlite && self.cache.path.trim().is_empty() {
+            return Err(config_error("cache.path", "must not be empty"));
+        }
+        let sqlite = &self.cache.sqlite;
+        if !JOURNAL_MODES.contains(&sqlite.journal_mode.to_lowercase().as_str()) {
+            return Err(config_error(
+                "cache.sqlite.journal_mode",
+                format!("unknown mode '{}' (expected one of: {})", sqlite.journal_mode, JOURNAL_MODES.join(", ")),
+            ));
+        }
+        if !SYNCHRONOUS_LEVELS.contains(&sqlite.synchronous.to_lowercase().as_str()) {
+            return Err(config_error(
+                "cache.sqlite.synchronous",
+                format!("unknown level '{}' (expected one of: {})", sqlite.synchronous, SYNCHRONOUS_LEVELS.join(", ")),
+            ));
+        }
+        if sqlite.read_connections == 0 {
+            return Err(config_error("cache.sqlite.read_connections", "must be at least 1"));
+        }
+        if sqlite.max_entries == Some(0) {
+            return Err(config_error("cache.sqlite.max_entries", "must be at least 1 (null for no cap)"));
+        }
+        if sqlite.hit_flush_interval_ms == 0 {
+            return Err(config_error("cache.sqlite.hit_flush_interval_ms", "must be positive (batch_hits: false writes every hit)"));
+        }
+        if sqlite.hit_flush_max_keys == 0 {
+            return Err(config_error("cache.sqlite.hit_flush_max_keys", "must be at least 1"));
+        }
+        if let Some(level) = sqlite.compression_level {
+            codec::check_level(level).map_err(|message| config_error("cache.sqlite.compression_level", message))?;
+        }
+
+        let http = &self.http;
+        for (field, secs) in [
+            ("http.pool_idle_timeout_secs", http.pool_idle_timeout_secs),
+            ("http.connect_timeout_secs", http.connect_timeout_secs),
+            ("http.tcp_keepalive_secs", http.tcp_keepalive_secs.unwrap_or(1.0)),
+        ] {
+            if !(secs.is_finite() && secs > 0.0) {
+                return Err(config_error(field, "must be positive"));
+            }
+        }
+
+        for (i, provider) in self.providers.iter().enumerate() {
+            if !KNOWN_PROVIDERS.contains(&provider.name.as_str()) {
+                return Err(config_error(
+                    format!("providers[{}].name", i),
+                    format!("unknown provider '{}' (expected one of: {})", provider.name, KNOWN_PROVIDERS.join(", ")),
+                ));
+            }
+            if self.providers[..i].iter().any(|p| p.name == provider.name) {
+                return Err(config_error(
+                    format!("providers[{}].name", i),
+                    format!("provider '{}' listed twice", provider.name),
+                ));
+            }
+            if !(provider.timeout_secs.is_finite() && provider.timeout_secs > 0.0) {
+                return Err(config_error(format!("providers[{}].timeout_secs", i), "must be positive"));
+            }
+            if provider.max_attempts == 0 {
+                return Err(config_error(format!("providers[{}].max_attempts", i), "must be at least 1"));
+            }
+        }
+
+        let search = &self.search;
+        for (field, score) in [
+            ("search.exact_score", search.exact_score),
+            ("search.min_score", search.min_score),
+            ("search.not_found_score", search.not_found_score),
+            ("search.cache_min_score", search.cache_min_score),
+        ] {
+            if !(0.0..=100.0).contains(&score) {
+                return Err(config_error(field, "must be between 0 and 100"));
+            }
+        }
+        if !(0.0..=100.0).contains(&search.tie_epsilon) {
+            return Err(config_error("search.tie_epsilon", "must be between 0 and 100 (0 disables)"));
+        }
+        if search.drakon_prefilter == Some(0) {
+            return Err(config_error("search.drakon_prefilter", "must be at least 1 (null sends every candidate)"));
+        }
+        if search.popularity_weight.is_some_and(|weight| !(0.0..=1.0).contains(&weight)) {
+            return Err(config_error("search.popularity_weight", "must be between 0 and 1 (null disables)"));
+        }
+        if !(0.0..=100.0).contains(&search.popularity_band) {
+            return Err(config_error("search.popularity_band", "must be between 0 and 100"));
+        }
+        if search.drakon_breaker_threshold == 0 {
+            return Err(config_error("search.drakon_breaker_threshold", "must be at least 1"));
+        }
+        if search.drakon_breaker_cool_down_secs < 0 {
+            return Err(config_error("search.drakon_breaker_cool_down_secs", "must not be negative"));
+        }
+        if search.not_found_score > search.min_score {
+            return Err(config_error("search.not_found_score", "must not exceed min_score"));
+        }
+        if search.min_score > search.exact_score {
+            return Err(config_error("search.min_score", "must not exceed exact_score"));
+        }
+        if !(0.0..=1.0).contains(&search.schema_sample_rate) {
+            return Err(config_error("search.schema_sample_rate", "must be between 0 and 1"));
+        }
+        if matches!(search.no_result_ttl_secs, Some(ttl) if ttl <= 0) {
+            return Err(config_error("search.no_result_ttl_secs", "must be positive (omit to disable)"));
+        }
+        if search.cache_ttl_min_secs <= 0 {
+            return Err(config_error("search.cache_ttl_min_secs", "must be positive"));
+        }
+        if search.cache_ttl_max_secs < search.cache_ttl_min_secs {
+            return Err(config_error("search.cache_ttl_max_secs", "must be at least cache_ttl_min_secs"));
+        }
+        if !(search.cache_ttl_unchanged_factor >= 1.0 && search.cache_ttl_unchanged_factor.is_finite()) {
+            return Err(config_error("search.cache_ttl_unchanged_factor", "must be at least 1"));
+        }
+        if matches!(search.cache_ttl_unreleased_expiry_secs, Some(expiry) if expiry <= 0) {
+            return Err(config_error("search.cache_ttl_unreleased_expiry_secs", "must be positive (omit to disable)"));
+        }
+        if matches!(search.cache_max_age_secs, Some(age) if age <= 0) {
+            return Err(config_error("search.cache_max_age_secs", "must be positive (omit to disable)"));
+        }
+        if matches!(search.stale_while_revalidate_secs, Some(window) if window <= 0) {
+            return Err(config_error("search.stale_while_revalidate_secs", "must be positive (omit to disable)"));
+        }
+        if search.cache_max_row_bytes == Some(0) {
+            return Err(config_error("search.cache_max_row_bytes", "must be at least 1 (omit to disable)"));
+        }
+        if matches!(search.fuzzy_cache_min_score, Some(score) if !(score > 0.0 && score <= 100.0)) {
+            return Err(config_error("search.fuzzy_cache_min_score", "must be in (0, 100] (omit to disable)"));
+        }
+        if search.provider_quota == Some(0) {
+            return Err(config_error("search.provider_quota", "must be at least 1 (omit to disable)"));
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_yaml_and_toml_agree() {
+        let yaml = EngineConfig::from_yaml(
+            "cache:\n  backend: memory\n  capacity: 50\nproviders:\n  - name: steam\n    api_key: abc\n    timeout_secs: 2.5\nsearch:\n  min_score: 80\n  no_result_ttl_secs: null\ndelta_profile:\n  jaccard_cap: 0.75\n",
+        )
+        .unwrap();
+        let toml = EngineConfig::from_toml(
+            "[cache]\nbackend = \"memory\"\ncapacity = 50\n\n[[providers]]\nname = \"steam\"\napi_key = \"abc\"\ntimeout_secs = 2.5\n\n[search]\nmin_score = 80.0\n",
+        )
+        .unwrap();
+
+        // TOML has no null: an omitted TTL keeps the default
+        assert_eq!(toml.search.no_result_ttl_secs, Some(3600));
+        assert_eq!(yaml.search.no_result_ttl_secs, None);
+        assert_eq!(yaml.cache.backend(), CacheBackend::Memory { capacity: 50 });
+        assert_eq!(yaml.providers, toml.providers);
+        assert_eq!(yaml.providers[0].api_key.as_deref(), Some("abc"));
+        assert_eq!(toml.search.to_options().min_score, 80.0);
+        assert_eq!(toml.drakon_url, None);
+        assert_eq!(yaml.delta_profile, DeltaProfile { jaccard_cap: 0.75, ..DeltaProfile::title() });
+        assert_eq!(toml.delta_profile, DeltaProfile::title());
+    }
+
+    #[test]
+    fn test_errors_report_field_path() {
+        let field = |result: Result<EngineConfig>| match result.unwrap_err() {
+            GameEngineError::Config { field, .. } => field,
+            other => panic!("unexpected error: {}", other),
+        };
+
+        assert_eq!(field(EngineConfig::from_yaml("providers:\n  - name: steam\n  - name: igdb\n")), "providers[1].name");
+        assert_eq!(
+            field(EngineConfig::from_yaml("providers:\n  - name: steam\n    timeout_secs: -1\n")),
+            "providers[0].timeout_secs"
+        );
+        assert_eq!(field(EngineConfig::from_toml("[search]\nmin_score = \"high\"\n")), "search.min_score");
+        assert_eq!(field(EngineConfig::from_toml("[search]\nnot_found_score = 80.0\n")), "search.not_found_score");
+        assert_eq!(field(EngineConfig::from_yaml("cache:\n  backend: redis\n")), "cache.backend");
+        assert_eq!(field(EngineConfig::from_toml("drakon_url = \" \"\n")), "drakon_url");
+        assert_eq!(field(EngineConfig::from_toml("[search]\ntie_epsilon = -1.0\n")), "search.tie_epsilon");
+        assert_eq!(field(EngineConfig::from_yaml("search:\n  drakon_prefilter: 0\n")), "search.drakon_prefilter");
+        assert_eq!(field(EngineConfig::from_yaml("search:\n  popularity_weight: 1.5\n")), "search.popularity_weight");
+        assert_eq!(
+            field(EngineConfig::from_yaml("search:\n  drakon_breaker_threshold: 0\n")),
+            "search.drakon_breaker_threshold"
+        );
+        assert_eq!(
+            field(EngineConfig::from_yaml("delta_profile:\n  jaccard_weight: 0.6\n")),
+            "delta_profile.jaccard_weight"
+        );
+        assert_eq!(field(EngineConfig::from_yaml("delta_profile:\n  dlc_penalty: -1\n")), "delta_profile.dlc_penalty");
+        assert_eq!(field(EngineConfig::from_yaml("delta_profile:\n  wj: 0.4\n")), "delta_profile.wj");
+        assert_eq!(field(EngineConfig::from_json(r#"{"search": {"min_scor": 80}}"#)), "search.min_scor");
+        assert_eq!(
+            field(EngineConfig::from_yaml("search:\n  cache_ttl_max_secs: 60\n")),
+            "search.cache_ttl_max_secs"
+        );
+        assert_eq!(field(EngineConfig::from_toml("[search]\ncache_max_age_secs = 0\n")), "search.cache_max_age_secs");
+        assert_eq!(
+            field(EngineConfig::from_toml("[search]\nstale_while_revalidate_secs = -60\n")),
+            "search.stale_while_revalidate_secs"
+        );
+        assert_eq!(
+            field(EngineConfig::from_yaml("cache:\n  sqlite:\n    journal_mode: wall\n")),
+            "cache.sqlite.journal_mode"
+        );
+        assert_eq!(field(EngineConfig::from_toml("[cache.sqlite]\nbusy_timeout = 100\n")), "cache.sqlite.busy_timeout");
+        assert_eq!(field(EngineConfig::from_yaml("cache:\n  sqlite:\n    max_entries: 0\n")), "cache.sqlite.max_entries");
+        assert_eq!(
+            field(EngineConfig::from_yaml("cache:\n  sqlite:\n    hit_flush_interval_ms: 0\n")),
+            "cache.sqlite.hit_flush_interval_ms"
+        );
+        assert_eq!(
+            field(EngineConfig::from_yaml("cache:\n  sqlite:\n    compression_level: 30\n")),
+            "cache.sqlite.compression_level"
+        );
+        assert_eq!(
+            field(EngineConfig::from_toml("[search]\nfuzzy_cache_min_score = 120.0\n")),
+            "search.fuzzy_cache_min_score"
+        );
+        assert_eq!(
+            field(EngineConfig::from_yaml("cache:\n  sqlite:\n    eviction: random\n")),
+            "cache.sqlite.eviction"
+        );
+        assert_eq!(field(EngineConfig::from_yaml("http:\n  pool_idle_timeout_secs: 0\n")), "http.pool_idle_timeout_secs");
+        assert_eq!(field(EngineConfig::from_toml("[http]\nhttp2 = \"yes\"\n")), "http.http2");
+    }
+
+    #[test]
+    fn test_defaults_match_search_options() {
+        let config = EngineConfig::from_yaml("{}").unwrap();
+        assert_eq!(config, EngineConfig::default());
+
+        let options = config.search.to_options();
+        let defaults = SearchOptions::default();
+        assert_eq!(options.no_result_ttl, defaults.no_result_ttl);
+        assert_eq!(options.min_score, defaults.min_score);
+        assert_eq!(options.schema_sample_rate, defaults.schema_sample_rate);
+        assert_eq!(options.cache_ttl, defaults.cache_ttl);
+    }
+
+    #[tokio::test]
+    async fn test_engine_from_config() {
+        let config = EngineConfig::from_yaml(
+            "drakon_url: http://127.0.0.1:9\ncache:\n  backend: none\nproviders:\n  - name: steam\n    enabled: false\nsearch:\n  enrich: true\n",
+        )
+        .unwrap();
+        let engine = crate::GameEngine::from_config(&config).await.unwrap();
+
+        assert_eq!(engine.cache_backend(), "none");
+        assert_eq!(engine.provider_status(), vec![("steam".to_string(), false)]);
+        assert!(engine.options().enrich);
+        // Nothing listens on port 9, DRAKON falls back to rapidfuzz; without a
+        // URL the Δₛ³ ranker runs in process
+        assert_eq!(engine.ranker().name(), "rapidfuzz");
+        let offline = EngineConfig::from_yaml("cache:\n  backend: none\nproviders: []\n").unwrap();
+        assert_eq!(crate::GameEngine::from_config(&offline).await.unwrap().ranker().name(), "delta");
+    }
+}
@@ -29,6 +29,40 @@ where
     }
 }
 
+/// Release state of a game, as reported by its provider
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum ReleaseStatus {
+    Released,
+    /// Playable but unfinished (Steam "Early Access")
+    EarlyAccess,
+    /// Not out yet, `date` is the announced date when there is one
+    ComingSoon {
+        #[serde(default)]
+        date: Option<String>,
+    },
+}
+
+impl ReleaseStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ReleaseStatus::Released => "released",
+            ReleaseStatus::EarlyAccess => "early_access",
+            ReleaseStatus::ComingSoon { .. } => "coming_soon",
+        }
+    }
+
+    /// Short human label ("Early Access", "Coming soon: Q1 2027")
+    pub fn label(&self) -> String {
+        match self {
+            ReleaseStatus::Released => "Released".to_string(),
+            ReleaseStatus::EarlyAccess => "Early Access".to_string(),
+            ReleaseStatus::ComingSoon { date: Some(date) } => format!("Coming soon: {}", date),
+            ReleaseStatus::ComingSoon { date: None } => "Coming soon".to_string(),
+        }
+    }
+}
+
 /// Represents a game with all metadata from various providers
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct GameResult {
@@ -44,6 +78,11 @@ pub struct GameResult {
     #[serde(default)]
     pub name: String,
     
+    /// Alternative titles (localized names, abbreviations, working titles),
+    /// matched against the query as well as `name`
+    #[serde(default)]
+    pub aliases: Vec<String>,
+    
     /// Short description
     #[serde(default)]
     pub short_description: String,
@@ -89,6 +128,11 @@ pub struct GameResult {
     #[serde(default)]
     pub rating: Option<f64>,
     
+    /// Raw popularity signal (Steam recommendations, IGDB follows...), only
+    /// compared between candidates (see `ranking::apply_popularity`)
+    #[serde(default)]
+    pub popularity: Option<f64>,
+    
     /// Steam App ID (for Steam provider)
     #[serde(default)]
     pub steam_appid: Option<String>,
@@ -108,6 +152,14 @@ pub struct GameResult {
     /// Timestamp when this result was fetched
     #[serde(default = "Utc::now")]
     pub fetched_at: DateTime<Utc>,
+    
+    /// Providers whose data was merged into this result (empty when not enriched)
+    #[serde(default)]
+    pub sources: Vec<String>,
+
+    /// Release state (None when the provider does not report it)
+    #[serde(default)]
+    pub release_status: Option<ReleaseStatus>,
 }
 
 impl GameResult {
@@ -117,6 +169,7 @@ impl GameResult {
             provider: provider.into(),
             id: id.into(),
             name: name.into(),
+            aliases: Vec::new(),
             short_description: String::new(),
             description: String::new(),
             release_date: String::new(),
@@ -128,14 +181,22 @@ impl GameResult {
             tags: Vec::new(),
             metacritic_score: None,
             rating: None,
+            popularity: None,
             steam_appid: None,
             igdb_id: None,
             header_image: String::new(),
             url: String::new(),
             fetched_at: Utc::now(),
+            sources: Vec::new(),
+            release_status: None,
         }
     }
 
+    /// Name then aliases, in the order the rankers try them
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        std::iter::once(self.name.as_str()).chain(self.aliases.iter().map(String::as_str))
+    }
+
     /// Check if game is a DLC/expansion
     pub fn is_dlc(&self) -> bool {
         self.name.to_lowercase().contains("dlc") 
@@ -143,6 +204,60 @@ impl GameResult {
             || self.tags.iter().any(|tag| tag.to_lowercase() == "dlc")
     }
 
+    /// Whether the game is announced but not out yet (early access counts
+    /// as released, unknown status too)
+    pub fn is_unreleased(&self) -> bool {
+        matches!(self.release_status, Some(ReleaseStatus::ComingSoon { .. }))
+    }
+
+    /// Whether the game runs on one of the platform families in `families`
+    /// (as returned by `normalize_platforms`)
+    pub fn is_on_platform(&self, families: &[String]) -> bool {
+        self.platforms
+            .iter()
+            .any(|platform| families.contains(&crate::core::normalize_platform(platform)))
+    }
+
+    /// Whether `other` describes the same game: same Steam App ID, or same
+    /// name once trimmed and lowercased
+    pub fn is_same_game(&self, other: &GameResult) -> bool {
+        if let (Some(a), Some(b)) = (&self.steam_appid, &other.steam_appid) {
+            return a == b;
+        }
+        self.name.trim().to_lowercase() == other.name.trim().to_lowercase()
+    }
+
+    /// Fill empty aliases, genres, rating, metacritic score, header image and
+    /// release status from `other`, returns whether anything was filled
+    pub fn fill_missing_from(&mut self, other: &GameResult) -> bool {
+        let mut filled = false;
+        if self.aliases.is_empty() && !other.aliases.is_empty() {
+            self.aliases = other.aliases.clone();
+            filled = true;
+        }
+        if self.genres.is_empty() && !other.genres.is_empty() {
+            self.genres = other.genres.clone();
+            filled = true;
+        }
+        if self.rating.is_none() && other.rating.is_some() {
+            self.rating = other.rating;
+            filled = true;
+        }
+        if self.metacritic_score.is_none() && other.metacritic_score.is_some() {
+            self.metacritic_score = other.metacritic_score;
+            filled = true;
+        }
+        if self.header_image.is_empty() && !other.header_image.is_empty() {
+            self.header_image = other.header_image.clone();
+            filled = true;
+        }
+        if self.release_status.is_none() && other.release_status.is_some() {
+            self.release_status = other.release_status.clone();
+            filled = true;
+        }
+        filled
+    }
+
     /// Get display name (for logging/UI)
     pub fn display_name(&self) -> String {
         if let Some(year) = self.year {
@@ -169,6 +284,64 @@ impl Default for GameResult {
     }
 }
 
+/// Compact projection of a game, used for alternatives (in responses and in
+/// cache rows); the full record is fetched again by `provider` and `id`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SlimGameResult {
+    #[serde(default = "default_provider")]
+    pub provider: String,
+    
+    #[serde(default)]
+    pub id: String,
+    
+    #[serde(default)]
+    pub name: String,
+    
+    #[serde(default)]
+    #[serde(deserialize_with = "deserialize_year")]
+    pub year: Option<i32>,
+    
+    /// Ranking score (None for rows cached before scores were kept)
+    #[serde(default)]
+    pub score: Option<f64>,
+    
+    #[serde(default)]
+    pub url: String,
+    
+    #[serde(default)]
+    pub header_image: String,
+    
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub release_status: Option<ReleaseStatus>,
+}
+
+impl SlimGameResult {
+    pub fn from_game(game: &GameResult, score: Option<f64>) -> Self {
+        Self {
+            provider: game.provider.clone(),
+            id: game.id.clone(),
+            name: game.name.clone(),
+            year: game.year,
+            score,
+            url: game.url.clone(),
+            header_image: game.header_image.clone(),
+            release_status: game.release_status.clone(),
+        }
+    }
+}
+
+impl From<&GameResult> for SlimGameResult {
+    fn from(game: &GameResult) -> Self {
+        Self::from_game(game, None)
+    }
+}
+
+impl From<GameResult> for SlimGameResult {
+    fn from(game: GameResult) -> Self {
+        Self::from_game(&game, None)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -194,6 +367,50 @@ mod tests {
         assert!(game.is_dlc());
     }
 
+    #[test]
+    fn test_is_same_game() {
+        let steam = GameResult::new("steam", "1", "Hades");
+        let igdb = GameResult::new("igdb", "99", " HADES ");
+        assert!(steam.is_same_game(&igdb));
+
+        let mut steam = steam;
+        let mut other = GameResult::new("igdb", "42", "Hades II");
+        assert!(!steam.is_same_game(&other));
+
+        // Matching App IDs win over names, mismatching ones too
+        steam.steam_appid = Some("1145360".to_string());
+        other.steam_appid = Some("1145360".to_string());
+        assert!(steam.is_same_game(&other));
+        let mut igdb = igdb;
+        igdb.steam_appid = Some("1".to_string());
+        assert!(!steam.is_same_game(&igdb));
+    }
+
+    #[test]
+    fn test_fill_missing_from() {
+        let mut winner = GameResult::new("steam", "1", "Hades");
+        winner.header_image = "steam.jpg".to_string();
+
+        let mut other = GameResult::new("igdb", "99", "Hades");
+        other.genres = vec!["Roguelike".to_string()];
+        other.rating = Some(9.3);
+        other.header_image = "igdb.jpg".to_string();
+
+        assert!(winner.fill_missing_from(&other));
+        assert_eq!(winner.genres, vec!["Roguelike".to_string()]);
+        assert_eq!(winner.rating, Some(9.3));
+        assert_eq!(winner.metacritic_score, None);
+        assert_eq!(winner.header_image, "steam.jpg");
+        assert!(winner.aliases.is_empty());
+
+        other.aliases = vec!["Hades: Battle Out of Hell".to_string()];
+        assert!(winner.fill_missing_from(&other));
+        assert_eq!(winner.names().collect::<Vec<_>>(), vec!["Hades", "Hades: Battle Out of Hell"]);
+
+        // Nothing left to fill
+        assert!(!winner.fill_missing_from(&other));
+    }
+
     #[test]
     fn test_serialization() {
         let game = GameResult::new("steam", "730", "CS2");
@@ -201,4 +418,36 @@ mod tests {
         let deserialized = GameResult::from_json(&json).unwrap();
         assert_eq!(game.name, deserialized.name);
     }
+
+    #[test]
+    fn test_release_status_serialization() {
+        let mut game = GameResult::new("steam", "1", "Hades II");
+        game.release_status = Some(ReleaseStatus::ComingSoon { date: Some("Q1 2027".to_string()) });
+        let json = game.to_json().unwrap();
+        assert!(json.contains(r#""release_status":{"status":"coming_soon","date":"Q1 2027"}"#), "{}", json);
+        assert_eq!(GameResult::from_json(&json).unwrap().release_status, game.release_status);
+        assert!(game.is_unreleased());
+
+        // Rows cached before the field existed
+        let legacy = GameResult::from_json(r#"{"provider": "steam", "id": "1", "name": "Hades"}"#).unwrap();
+        assert_eq!(legacy.release_status, None);
+        assert!(!legacy.is_unreleased());
+
+        let early: ReleaseStatus = serde_json::from_str(r#"{"status": "early_access"}"#).unwrap();
+        assert_eq!(early, ReleaseStatus::EarlyAccess);
+    }
+
+    #[test]
+    fn test_slim_reads_full_rows() {
+        let mut game = GameResult::new("steam", "1145360", "Hades");
+        game.year = Some(2020);
+        game.description = "A rogue-like dungeon crawler".to_string();
+        game.url = "https://store.steampowered.com/app/1145360".to_string();
+
+        // Alternatives cached before slimming were full records
+        let slim: SlimGameResult = serde_json::from_str(&game.to_json().unwrap()).unwrap();
+        assert_eq!(slim, SlimGameResult::from(&game));
+        assert_eq!(slim.score, None);
+        assert!(!serde_json::to_string(&slim).unwrap().contains("rogue-like"));
+    }
 }
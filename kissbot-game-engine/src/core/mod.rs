@@ -1,5 +1,12 @@
 pub mod game_result;
+pub mod platform;
 pub mod search_response;
+pub mod text;
 
-pub use game_result::GameResult;
-pub use search_response::{SearchResponse, SearchResultType};
+pub use game_result::{GameResult, ReleaseStatus, SlimGameResult};
+pub use platform::{normalize_platform, normalize_platforms, PlatformFilterMode};
+pub use text::fold_text;
+pub use search_response::{
+    CacheMode, ProviderQuota, ScoreBonus, ScoreBreakdown, ScorePenalty, SearchResponse, SearchResultType, DEFAULT_EXACT_SCORE,
+    DEFAULT_FUZZY_SCORE, DEFAULT_NOT_FOUND_SCORE,
+};
@@ -0,0 +1,111 @@
+//! Platform names as users and providers write them ("PS5", "PlayStation 5",
+//! "Nintendo Switch"...) folded into one family name each
+
+use serde::{Deserialize, Serialize};
+
+/// What a platform-restricted search returns when no candidate is on the
+/// requested platforms
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PlatformFilterMode {
+    /// Best unfiltered candidate as `NotFound` (never cached)
+    #[default]
+    Strict,
+    /// Ignore the filter, flagged with `platform_filter_ignored`
+    Lenient,
+}
+
+/// Family of each known alias (lowercase, single-spaced)
+const PLATFORM_ALIASES: &[(&str, &str)] = &[
+    ("playstation", "playstation"),
+    ("ps", "playstation"),
+    ("psx", "playstation"),
+    ("psn", "playstation"),
+    ("ps1", "playstation"),
+    ("ps2", "playstation"),
+    ("ps3", "playstation"),
+    ("ps4", "playstation"),
+    ("ps5", "playstation"),
+    ("playstation 2", "playstation"),
+    ("playstation 3", "playstation"),
+    ("playstation 4", "playstation"),
+    ("playstation 5", "playstation"),
+    ("ps vita", "playstation"),
+    ("playstation vita", "playstation"),
+    ("xbox", "xbox"),
+    ("xbox 360", "xbox"),
+    ("xbox one", "xbox"),
+    ("xbox series", "xbox"),
+    ("xbox series x", "xbox"),
+    ("xbox series s", "xbox"),
+    ("xbox series x|s", "xbox"),
+    ("xsx", "xbox"),
+    ("switch", "switch"),
+    ("switch 2", "switch"),
+    ("nintendo switch", "switch"),
+    ("nintendo switch 2", "switch"),
+    ("ns", "switch"),
+    ("windows", "windows"),
+    ("win", "windows"),
+    ("pc", "windows"),
+    ("pc (microsoft windows)", "windows"),
+    ("mac", "mac"),
+    ("macos", "mac"),
+    ("osx", "mac"),
+    ("os x", "mac"),
+    ("linux", "linux"),
+    ("steamos", "linux"),
+    ("steam deck", "linux"),
+];
+
+/// Family name of a platform (`"PS5"` → `"playstation"`); unknown names are
+/// returned lowercased and single-spaced
+pub fn normalize_platform(name: &str) -> String {
+    let name = name
+        .to_lowercase()
+        .replace(['-', '_'], " ")
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    PLATFORM_ALIASES
+        .iter()
+        .find(|(alias, _)| *alias == name)
+        .map(|(_, family)| family.to_string())
+        .unwrap_or(name)
+}
+
+/// Normalized, sorted and deduplicated platform families
+pub fn normalize_platforms(names: &[String]) -> Vec<String> {
+    let mut families: Vec<String> = names
+        .iter()
+        .map(|name| normalize_platform(name))
+        .filter(|family| !family.is_empty())
+        .collect();
+    families.sort();
+    families.dedup();
+    families
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_aliases_share_a_family() {
+        for alias in ["ps5", "PS5", "PlayStation 5", "playstation", "Playstation-4", " ps  vita "] {
+            assert_eq!(normalize_platform(alias), "playstation", "{}", alias);
+        }
+        assert_eq!(normalize_platform("Nintendo Switch"), "switch");
+        assert_eq!(normalize_platform("Xbox Series X|S"), "xbox");
+        assert_eq!(normalize_platform("PC"), "windows");
+        assert_eq!(normalize_platform("Windows"), "windows");
+        assert_eq!(normalize_platform("Stadia"), "stadia");
+    }
+
+    #[test]
+    fn test_normalize_platforms() {
+        let names = ["Switch", "ps5", "PlayStation 5", ""].map(String::from);
+        assert_eq!(normalize_platforms(&names), vec!["playstation", "switch"]);
+    }
+}
@@ -1,18 +1,157 @@
 use serde::{Deserialize, Serialize};
-use crate::core::GameResult;
+use crate::core::{GameResult, ReleaseStatus, SlimGameResult};
+
+/// Default score at or above which a result is `Exact`
+pub const DEFAULT_EXACT_SCORE: f64 = 95.0;
+
+/// Default score at or above which a result is `Fuzzy`
+pub const DEFAULT_FUZZY_SCORE: f64 = 70.0;
+
+/// Default score below which a result is `NotFound`
+pub const DEFAULT_NOT_FOUND_SCORE: f64 = 40.0;
 
 /// Type of search result
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum SearchResultType {
-    /// Exact match (100% score)
+    /// Exact match (score >= exact threshold)
     Exact,
-    /// Fuzzy match (>70% score)
+    /// Fuzzy match (score >= fuzzy threshold)
     Fuzzy,
     /// Cache hit (from database)
     CacheHit,
     /// Fallback result (no good match)
     Fallback,
+    /// Best candidate scored below the not-found floor, probably not what
+    /// was asked for ("did you mean...?")
+    NotFound,
+}
+
+/// How a search uses the cache
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CacheMode {
+    /// Read the cache, write fresh results
+    #[default]
+    Use,
+    /// Skip the read, always write the fresh result (forced refresh)
+    Bypass,
+    /// Neither read nor write
+    Off,
+}
+
+impl CacheMode {
+    pub fn reads(&self) -> bool {
+        matches!(self, CacheMode::Use)
+    }
+
+    pub fn writes(&self) -> bool {
+        !matches!(self, CacheMode::Off)
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CacheMode::Use => "use",
+            CacheMode::Bypass => "bypass",
+            CacheMode::Off => "off",
+        }
+    }
+}
+
+impl std::str::FromStr for CacheMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "use" => Ok(CacheMode::Use),
+            "bypass" | "refresh" => Ok(CacheMode::Bypass),
+            "off" => Ok(CacheMode::Off),
+            other => Err(format!("Invalid cache mode '{}' (expected use, bypass or off)", other)),
+        }
+    }
+}
+
+/// Penalty subtracted from the raw similarity
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScorePenalty {
+    /// What triggered it (e.g. "dlc", "year_mismatch")
+    pub reason: String,
+    /// Points removed from the score
+    pub points: f64,
+}
+
+/// Bonus added to the raw similarity
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScoreBonus {
+    /// What triggered it
+    pub reason: String,
+    /// Points added to the score
+    pub points: f64,
+}
+
+/// Why a candidate got its score
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScoreBreakdown {
+    /// Ranker that produced the score
+    pub ranker: String,
+    /// Raw similarity before penalties (0.0 - 100.0)
+    pub similarity: f64,
+    /// Penalties applied on top of the similarity
+    #[serde(default)]
+    pub penalties: Vec<ScorePenalty>,
+    /// Bonuses applied on top of the similarity
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub bonuses: Vec<ScoreBonus>,
+    /// Query as the ranker compared it (only when ranked with `Ranker::rank_explain`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub normalized_query: Option<String>,
+    /// Candidate name as the ranker compared it (only when ranked with `Ranker::rank_explain`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub normalized_candidate: Option<String>,
+    /// Alias of the candidate that scored better than its name
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub matched_alias: Option<String>,
+    /// Query the candidates were ranked against when it had acronyms
+    /// expanded ("gta 5" → "Grand Theft Auto 5")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expanded_query: Option<String>,
+    /// Number of candidates ranked
+    pub candidates: usize,
+    /// Per-provider candidate counts before/after `SearchOptions::provider_quota`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub provider_quota: Vec<ProviderQuota>,
+}
+
+/// Candidates a provider returned vs. kept for ranking
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProviderQuota {
+    pub provider: String,
+    pub returned: usize,
+    pub kept: usize,
+}
+
+impl ScoreBreakdown {
+    pub fn new(ranker: impl Into<String>, similarity: f64, candidates: usize) -> Self {
+        Self {
+            ranker: ranker.into(),
+            similarity,
+            penalties: Vec::new(),
+            bonuses: Vec::new(),
+            normalized_query: None,
+            normalized_candidate: None,
+            matched_alias: None,
+            expanded_query: None,
+            candidates,
+            provider_quota: Vec::new(),
+        }
+    }
+
+    /// Final score: similarity minus penalties plus bonuses, within 0-100
+    pub fn score(&self) -> f64 {
+        let penalties: f64 = self.penalties.iter().map(|p| p.points).sum();
+        let bonuses: f64 = self.bonuses.iter().map(|b| b.points).sum();
+        (self.similarity - penalties + bonuses).clamp(0.0, 100.0)
+    }
 }
 
 /// Search response with game result and metadata
@@ -27,9 +166,14 @@ pub struct SearchResponse {
     /// Result type
     pub result_type: SearchResultType,
     
-    /// Alternative matches (if any)
+    /// Alternative matches (if any), compact: see `GameEngine::rehydrate`
     #[serde(default)]
-    pub alternatives: Vec<GameResult>,
+    pub alternatives: Vec<SlimGameResult>,
+    
+    /// Other editions of the matched game (GOTY, Definitive...), kept out
+    /// of `alternatives` (see `SearchOptions::collapse_editions`)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub editions: Vec<SlimGameResult>,
     
     /// Whether result came from cache
     pub from_cache: bool,
@@ -42,6 +186,24 @@ pub struct SearchResponse {
     
     /// Ranking method used (drakon, rapidfuzz)
     pub ranking_method: String,
+    
+    /// Cache mode the search ran with
+    #[serde(default)]
+    pub cache_mode: CacheMode,
+    
+    /// How the score was computed (fresh results only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub score_breakdown: Option<ScoreBreakdown>,
+    
+    /// Nothing matched the requested platforms, the filter was ignored
+    /// (lenient platform filtering only)
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub platform_filter_ignored: bool,
+    
+    /// Served past its TTL while a background refresh updates the entry
+    /// (see `SearchOptions::stale_while_revalidate`)
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub refreshing: bool,
 }
 
 impl SearchResponse {
@@ -59,16 +221,21 @@ impl SearchResponse {
             score,
             result_type,
             alternatives: Vec::new(),
+            editions: Vec::new(),
             from_cache,
             latency_ms,
             provider,
             ranking_method: String::from("unknown"),
+            cache_mode: CacheMode::default(),
+            score_breakdown: None,
+            platform_filter_ignored: false,
+            refreshing: false,
         }
     }
 
     /// Add alternative match
-    pub fn add_alternative(&mut self, game: GameResult) {
-        self.alternatives.push(game);
+    pub fn add_alternative(&mut self, game: impl Into<SlimGameResult>) {
+        self.alternatives.push(game.into());
     }
 
     /// Set ranking method
@@ -77,16 +244,21 @@ impl SearchResponse {
         self
     }
 
-    /// Check if match is good enough (score >= 70%)
+    /// Check if match is good enough (score >= default fuzzy threshold)
     pub fn is_good_match(&self) -> bool {
-        self.score >= 70.0
+        self.score >= DEFAULT_FUZZY_SCORE
     }
 
-    /// Get display string for logging
+    /// Get display string for logging (release status shown unless released)
     pub fn display(&self) -> String {
+        let mut name = self.game.display_name();
+        match &self.game.release_status {
+            Some(ReleaseStatus::Released) | None => {}
+            Some(status) => name.push_str(&format!(" <{}>", status.label())),
+        }
         format!(
             "{} - {}% ({}) [{}] {:?}",
-            self.game.display_name(),
+            name,
             self.score,
             self.provider,
             self.ranking_method,
@@ -110,6 +282,46 @@ mod tests {
         assert!(response.is_good_match());
     }
 
+    #[test]
+    fn test_cache_mode_parse() {
+        assert_eq!("bypass".parse::<CacheMode>(), Ok(CacheMode::Bypass));
+        assert_eq!("OFF".parse::<CacheMode>(), Ok(CacheMode::Off));
+        assert!("maybe".parse::<CacheMode>().is_err());
+        assert_eq!(serde_json::to_string(&CacheMode::Use).unwrap(), "\"use\"");
+    }
+
+    #[test]
+    fn test_score_breakdown() {
+        let mut breakdown = ScoreBreakdown::new("rapidfuzz", 92.0, 3);
+        assert_eq!(breakdown.score(), 92.0);
+
+        breakdown.penalties.push(ScorePenalty { reason: "dlc".to_string(), points: 15.0 });
+        assert_eq!(breakdown.score(), 77.0);
+        breakdown.bonuses.push(ScoreBonus { reason: "year".to_string(), points: 30.0 });
+        assert_eq!(breakdown.score(), 100.0);
+
+        // Only serialized when present
+        let game = GameResult::new("steam", "1", "Game");
+        let mut response = SearchResponse::new(game, 92.0, SearchResultType::Fuzzy, false, 1.0);
+        assert!(!serde_json::to_string(&response).unwrap().contains("score_breakdown"));
+        response.score_breakdown = Some(breakdown);
+        assert!(serde_json::to_string(&response).unwrap().contains("\"penalties\""));
+    }
+
+    #[test]
+    fn test_display_release_status() {
+        let mut game = GameResult::new("steam", "1", "Hades II");
+        game.year = Some(2024);
+        let mut response = SearchResponse::new(game, 95.0, SearchResultType::Fuzzy, false, 1.0);
+        assert!(response.display().starts_with("Hades II (2024) - 95%"));
+
+        response.game.release_status = Some(ReleaseStatus::EarlyAccess);
+        assert!(response.display().starts_with("Hades II (2024) <Early Access> - 95%"));
+
+        response.game.release_status = Some(ReleaseStatus::Released);
+        assert!(response.display().starts_with("Hades II (2024) - 95%"));
+    }
+
     #[test]
     fn test_good_match_threshold() {
         let game = GameResult::new("steam", "1", "Game");
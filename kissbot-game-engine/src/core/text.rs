@@ -0,0 +1,41 @@
+use unicode_normalization::{char::is_combining_mark, UnicodeNormalization};
+
+/// `text` with accents and typography folded away, for fuzzy matching and
+/// cache keys: ™/®/© removed, NFKD-decomposed without combining marks
+/// ("Pokémon™" → "Pokemon", "Über" → "Uber"), typographic apostrophes,
+/// quotes and dashes mapped to ASCII. Case is left as is.
+pub fn fold_text(text: &str) -> String {
+    text.chars()
+        // Before NFKD, which spells ™ out as "TM"
+        .filter(|c| !matches!(c, '™' | '®' | '©'))
+        .nfkd()
+        .filter(|c| !is_combining_mark(*c))
+        .map(|c| match c {
+            '\u{2018}' | '\u{2019}' | '\u{201A}' | '\u{201B}' | '\u{2032}' | '\u{02BC}' => '\'',
+            '\u{201C}' | '\u{201D}' | '\u{201E}' | '\u{2033}' => '"',
+            '\u{2010}'..='\u{2015}' | '\u{2212}' => '-',
+            c => c,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fold_text() {
+        // French
+        assert_eq!(fold_text("Réalité augmentée à Noël"), "Realite augmentee a Noel");
+        assert_eq!(fold_text("L’Île Crocodile"), "L'Ile Crocodile");
+        // German umlauts
+        assert_eq!(fold_text("Über die Brücke"), "Uber die Brucke");
+        assert_eq!(fold_text("Mädchen aus Köln"), "Madchen aus Koln");
+        // Trademark symbols and typography
+        assert_eq!(fold_text("Pokémon™ Legends: Arceus"), "Pokemon Legends: Arceus");
+        assert_eq!(fold_text("ELDEN RING® © FromSoftware"), "ELDEN RING  FromSoftware");
+        assert_eq!(fold_text("Half–Life — “Alyx”"), "Half-Life - \"Alyx\"");
+        // Already plain
+        assert_eq!(fold_text("Hades II"), "Hades II");
+    }
+}
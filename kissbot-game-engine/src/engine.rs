@@ -1,16 +1,189 @@
-use crate::core::{GameResult, SearchResponse, SearchResultType};
-use crate::cache::{GameCache, SqliteCache};
-use crate::ranking::{Ranker, DrakonRanker, RapidfuzzRanker};
-use crate::providers::GameProvider;
-use crate::error::{Result, GameEngineError};
-use std::sync::Arc;
+use crate::core::{
+    normalize_platforms, CacheMode, GameResult, PlatformFilterMode, ProviderQuota, ScoreBreakdown, SearchResponse, SearchResultType,
+    SlimGameResult, DEFAULT_EXACT_SCORE, DEFAULT_FUZZY_SCORE, DEFAULT_NOT_FOUND_SCORE,
+};
+use crate::analytics::{
+    DegradedProvider, GuardrailConfig, ProviderGuardrails, ProviderOutcome, QueryLog, RecentSearches,
+    SearchLogEntry, SearchSummary, TrendingConfig, TrendingDetector, TrendingQuery, QUERY_LOG_CAPACITY,
+};
+use crate::cache::{
+    escape_like, fit_row, normalize_query, ttl, CacheBackend, CacheEntrySummary, CacheOrder, CachedGame, GameCache, IntegrityReport, PendingWrite,
+    RecompressReport, TtlPolicy, WriteRetryConfig, WriteRetryQueue, DEFAULT_MEMORY_CAPACITY,
+};
+use crate::clock::{Clock, SystemClock};
+use crate::config::EngineConfig;
+use crate::ranking::drakon::DEFAULT_DRAKON_PREFILTER;
+use crate::ranking::resilient::{DEFAULT_BREAKER_COOL_DOWN, DEFAULT_BREAKER_THRESHOLD};
+use crate::ranking::{apply_popularity, break_ties, calibrate_ranked, editions, exact_key, prescore_game, Acronyms, Ranker, DeltaRanker, BreakerConfig, DrakonLimits, DrakonRanker, RankedCandidate, RapidfuzzRanker, ResilientRanker, DEFAULT_POPULARITY_BAND, DEFAULT_TIE_EPSILON, EXACT_MATCH};
+use crate::providers::{Fingerprint, GameProvider, SchemaMonitor};
+use crate::providers::schema::ProviderSchemaDrift;
+use crate::error::{Result, GameEngineError, Phase, ResultExt};
+use crate::http::{HttpClient, HttpClientConfig, HttpPoolStats};
+use crate::tasks::TaskCounter;
+use chrono::Duration;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, OnceLock, RwLock, Weak};
 use std::time::Instant;
 
+/// Default max serialized size of a cache row
+pub const DEFAULT_MAX_ROW_BYTES: usize = 256 * 1024;
+
+/// Default DRAKON API address
+pub const DEFAULT_DRAKON_URL: &str = "http://127.0.0.1:8000";
+
 /// Main game search engine orchestrator
 pub struct GameEngine {
     cache: Arc<dyn GameCache>,
-    ranker: Arc<dyn Ranker>,
+    cache_backend: &'static str,
+    ranker: RwLock<Arc<dyn Ranker>>,
+    /// Expanded in queries before ranking
+    acronyms: RwLock<Acronyms>,
     providers: Vec<Arc<dyn GameProvider>>,
+    disabled_providers: RwLock<HashSet<String>>,
+    options: SearchOptions,
+    trending: TrendingDetector,
+    schema: Arc<SchemaMonitor>,
+    write_queue: WriteRetryQueue,
+    guardrails: ProviderGuardrails,
+    /// Started on the first logged search
+    query_log: OnceLock<QueryLog>,
+    /// Latest successful searches, in memory only
+    recent: RecentSearches,
+    clock: Arc<dyn Clock>,
+    /// Results not cached for exceeding `cache_max_row_bytes`
+    oversized_rows: AtomicU64,
+    /// Searches that read the cache, and those it answered
+    cache_lookups: AtomicU64,
+    cache_hits: AtomicU64,
+    /// Background tasks spawned by the engine and still running
+    tasks: TaskCounter,
+    in_flight: TaskCounter,
+    /// Set by `enable_stale_refresh`, for `search` to spawn refreshes
+    handle: OnceLock<Weak<GameEngine>>,
+    /// Cache keys with a background refresh running
+    refreshing: Mutex<HashSet<String>>,
+    /// Shared by the providers built from config and every DRAKON ranker
+    http: HttpClient,
+}
+
+/// Readiness report: not ready when every enabled provider is degraded
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Readiness {
+    pub ready: bool,
+    pub degraded_providers: Vec<DegradedProvider>,
+}
+
+/// Internal counters, polled to check that a long-running engine stays
+/// bounded
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EngineInfo {
+    pub cache_backend: String,
+    pub ranker: String,
+    /// DRAKON monitor, write retry and query log tasks still running
+    pub background_tasks: usize,
+    pub in_flight_searches: usize,
+    /// Cached games (tombstones excluded)
+    pub cache_entries: u64,
+    /// Entry cap of the cache backend, None when unbounded
+    pub cache_capacity: Option<usize>,
+    pub pending_writes: usize,
+    /// Queries tracked by the trending detector
+    pub trending_queries: usize,
+    /// Summaries kept for `last_searches`
+    pub recent_searches: usize,
+    /// Connection reuse of the HTTP client shared by providers and DRAKON
+    #[serde(default)]
+    pub http: HttpPoolStats,
+}
+
+/// Background DRAKON health monitor settings
+#[derive(Debug, Clone)]
+pub struct DrakonMonitorConfig {
+    pub base_url: String,
+    /// Delay between health probes
+    pub interval: std::time::Duration,
+    /// Consecutive failed probes before downgrading to rapidfuzz
+    pub max_failures: u32,
+}
+
+impl Default for DrakonMonitorConfig {
+    fn default() -> Self {
+        Self {
+            base_url: DEFAULT_DRAKON_URL.to_string(),
+            interval: std::time::Duration::from_secs(30),
+            max_failures: 3,
+        }
+    }
+}
+
+/// Outcome of `GameEngine::warm`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct WarmReport {
+    /// Distinct queries after trimming and deduplication
+    pub total: usize,
+    /// Already cached and fresh, providers not called
+    pub cached: usize,
+    pub fetched: usize,
+    pub failed: usize,
+    pub failures: Vec<WarmFailure>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WarmFailure {
+    pub query: String,
+    pub error: String,
+}
+
+/// Queries between two warm-up progress logs
+const WARM_PROGRESS_EVERY: usize = 10;
+
+enum WarmOutcome {
+    Cached,
+    Fetched,
+    Failed(String, String),
+}
+
+/// Characters starting the cache key suffixes (see `SearchQuery::cache_key`)
+const CACHE_KEY_SUFFIXES: [char; 4] = ['|', '@', '#', '~'];
+
+/// `text` normalized for a cache key, `%` and the suffix characters
+/// percent-encoded so a query typed as `zelda|fr` is not read as a variant
+fn key_part(text: &str) -> String {
+    let mut part = String::new();
+    for c in normalize_query(text).chars() {
+        if c == '%' || CACHE_KEY_SUFFIXES.contains(&c) {
+            part.push_str(&format!("%{:02x}", c as u32));
+        } else {
+            part.push(c);
+        }
+    }
+    part
+}
+
+/// Cached keys ranked by a fuzzy cache lookup (most hit first among those
+/// sharing the query's first characters)
+const FUZZY_CACHE_CANDIDATES: usize = 200;
+
+/// Leading characters a cached key must share with the query to be ranked;
+/// shorter queries never get a fuzzy lookup
+const FUZZY_CACHE_PREFIX: usize = 3;
+
+/// Roman numerals told apart by `same_series_numbers`
+const ROMAN_NUMERALS: &[&str] = &["ii", "iii", "iv", "v", "vi", "vii", "viii", "ix", "x", "xi", "xii"];
+
+/// Background refresh of a cache key, removed from `GameEngine::refreshing`
+/// on drop so a refresh that panicked does not block the next ones
+struct RefreshGuard {
+    engine: Arc<GameEngine>,
+    key: String,
+}
+
+impl Drop for RefreshGuard {
+    fn drop(&mut self) {
+        self.engine.refreshing.lock().unwrap_or_else(|e| e.into_inner()).remove(&self.key);
+    }
 }
 
 /// Search query parameters
@@ -18,7 +191,80 @@ pub struct GameEngine {
 pub struct SearchQuery {
     pub query: String,
     pub max_results: usize,
-    pub use_cache: bool,
+    /// Cache read/write behavior (`Bypass` forces a refresh)
+    pub cache_mode: CacheMode,
+    /// Description language passed to providers (e.g. "fr")
+    pub locale: Option<String>,
+    /// Store region passed to providers (e.g. "FR"), affects pricing
+    pub country: Option<String>,
+    /// Only query these providers (by `name()`); `None` queries all enabled ones
+    pub providers: Option<Vec<String>>,
+    /// Drop announced-but-unreleased games from the candidates before ranking
+    pub exclude_unreleased: bool,
+    /// Only keep games on these platforms (aliases accepted: "ps5", "PlayStation 5"...)
+    pub platforms: Option<Vec<String>>,
+    /// Rank with `Ranker::rank_explain`, so `score_breakdown` carries the
+    /// strings the ranker compared (not part of the cache key)
+    pub explain: bool,
+}
+
+impl SearchQuery {
+    /// Cache key: normalized query, suffixed with locale/country when set
+    /// (`zelda`, `zelda|fr`, `zelda|fr|ca`, `zelda||ca`), with the sorted
+    /// provider restriction (`zelda@igdb,steam`), with `#released` when
+    /// unreleased games are excluded and with the sorted platform families
+    /// (`zelda~playstation,switch`). `%` and those separators are
+    /// percent-encoded in the parts (`zelda|fr` typed is `zelda%7cfr`)
+    pub fn cache_key(&self) -> String {
+        let mut key = key_part(&self.query);
+        if self.locale.is_some() || self.country.is_some() {
+            key.push('|');
+            key.push_str(&key_part(self.locale.as_deref().unwrap_or_default()));
+        }
+        if let Some(country) = &self.country {
+            key.push('|');
+            key.push_str(&key_part(country));
+        }
+        if let Some(providers) = &self.providers {
+            let mut names: Vec<String> = providers.iter().map(|p| key_part(p)).collect();
+            names.sort();
+            names.dedup();
+            key.push('@');
+            key.push_str(&names.join(","));
+        }
+        if self.exclude_unreleased {
+            key.push_str("#released");
+        }
+        if let Some(families) = self.platform_families() {
+            key.push('~');
+            key.push_str(&families.join(","));
+        }
+        key
+    }
+
+    /// Normalized platform restriction, `None` when unrestricted
+    pub fn platform_families(&self) -> Option<Vec<String>> {
+        self.platforms
+            .as_deref()
+            .map(normalize_platforms)
+            .filter(|families| !families.is_empty())
+    }
+}
+
+impl Default for SearchQuery {
+    fn default() -> Self {
+        Self {
+            query: String::new(),
+            max_results: 5,
+            cache_mode: CacheMode::Use,
+            locale: None,
+            country: None,
+            providers: None,
+            exclude_unreleased: false,
+            platforms: None,
+            explain: false,
+        }
+    }
 }
 
 /// Search options/configuration
@@ -26,8 +272,68 @@ pub struct SearchQuery {
 pub struct SearchOptions {
     pub cache_enabled: bool,
     pub drakon_enabled: bool,
+    /// Score at or above which a fresh result is `Exact`
+    pub exact_score: f64,
+    /// Score at or above which a fresh result is `Fuzzy` (below: `Fallback`)
     pub min_score: f64,
+    /// Score below which the best candidate is returned as `NotFound` (never cached)
+    pub not_found_score: f64,
     pub max_alternatives: usize,
+    /// TTL of no-result tombstones (None disables negative caching)
+    pub no_result_ttl: Option<Duration>,
+    /// Minimum ranking score for a result to be written to cache
+    pub cache_min_score: f64,
+    /// Also cache results below `cache_min_score` (served as `Fallback`)
+    pub cache_low_confidence: bool,
+    /// Fraction of live provider responses fingerprinted for schema drift (0 disables)
+    pub schema_sample_rate: f64,
+    /// Fill the winner's missing metadata from the same game found by other providers
+    pub enrich: bool,
+    /// Max candidates per provider passed to the ranker (best by name pre-score),
+    /// ignored when a single provider responded
+    pub provider_quota: Option<usize>,
+    /// Record every search in the persistent search log (written in the background)
+    pub log_queries: bool,
+    /// Adaptive TTL of cached results (refreshed by the first search past it)
+    pub cache_ttl: TtlPolicy,
+    /// Entries cached longer ago are misses whatever their TTL (None: no limit)
+    pub cache_max_age: Option<Duration>,
+    /// Serve an expired entry when the providers return nothing
+    pub serve_stale_on_error: bool,
+    /// Max serialized size of a cache row, alternatives dropped to fit
+    /// (None: no limit)
+    pub cache_max_row_bytes: Option<usize>,
+    /// Fall-through of platform-restricted searches matching nothing
+    pub platform_filter: PlatformFilterMode,
+    /// On a cache miss, serve the entry of a cached query the ranker scores
+    /// at least this against the query ("vampire survivor" for "vampire
+    /// survivors") and remember the pair as an alias (None disables)
+    pub fuzzy_cache_min_score: Option<f64>,
+    /// Serve an entry up to this long past its TTL right away (flagged
+    /// `refreshing`) and refresh it in the background, once per key; needs
+    /// `GameEngine::enable_stale_refresh` (None: refreshed before answering)
+    pub stale_while_revalidate: Option<Duration>,
+    /// Candidates ranked within this many points of each other are ordered
+    /// by year and name length (see `ranking::break_ties`, 0 disables)
+    pub tie_epsilon: f64,
+    /// Candidates sent to DRAKON, the best by a local rapidfuzz pass (None
+    /// sends all of them)
+    pub drakon_prefilter: Option<usize>,
+    /// Weight of the candidates' popularity in near-ties (see
+    /// `ranking::apply_popularity`, `DEFAULT_POPULARITY_WEIGHT` suggested;
+    /// None disables)
+    pub popularity_weight: Option<f64>,
+    /// Score gap to the best candidate within which popularity counts
+    pub popularity_band: f64,
+    /// Group the winner's editions (GOTY, Definitive...) under the base
+    /// game, in `SearchResponse::editions` instead of `alternatives`
+    pub collapse_editions: bool,
+    /// Words marking an edition (see `ranking::editions`)
+    pub edition_keywords: Vec<String>,
+    /// Consecutive DRAKON failures after which searches skip it for
+    /// `drakon_breaker_cool_down` (see `ranking::ResilientRanker`)
+    pub drakon_breaker_threshold: u32,
+    pub drakon_breaker_cool_down: Duration,
 }
 
 impl Default for SearchOptions {
@@ -35,8 +341,60 @@ impl Default for SearchOptions {
         Self {
             cache_enabled: true,
             drakon_enabled: true,
-            min_score: 70.0,
+            exact_score: DEFAULT_EXACT_SCORE,
+            min_score: DEFAULT_FUZZY_SCORE,
+            not_found_score: DEFAULT_NOT_FOUND_SCORE,
             max_alternatives: 5,
+            no_result_ttl: Some(Duration::hours(1)),
+            cache_min_score: 70.0,
+            cache_low_confidence: false,
+            schema_sample_rate: 0.05,
+            enrich: false,
+            provider_quota: None,
+            log_queries: false,
+            cache_ttl: TtlPolicy::default(),
+            cache_max_age: Some(Duration::days(7)),
+            serve_stale_on_error: true,
+            cache_max_row_bytes: Some(DEFAULT_MAX_ROW_BYTES),
+            platform_filter: PlatformFilterMode::default(),
+            fuzzy_cache_min_score: None,
+            stale_while_revalidate: None,
+            tie_epsilon: DEFAULT_TIE_EPSILON,
+            drakon_prefilter: Some(DEFAULT_DRAKON_PREFILTER),
+            popularity_weight: None,
+            popularity_band: DEFAULT_POPULARITY_BAND,
+            collapse_editions: false,
+            edition_keywords: editions::default_edition_keywords(),
+            drakon_breaker_threshold: DEFAULT_BREAKER_THRESHOLD,
+            drakon_breaker_cool_down: Duration::seconds(DEFAULT_BREAKER_COOL_DOWN.as_secs() as i64),
+        }
+    }
+}
+
+impl SearchOptions {
+    /// Candidate limits of the DRAKON rankers the engine creates
+    pub fn drakon_limits(&self) -> DrakonLimits {
+        DrakonLimits { prefilter: self.drakon_prefilter, ..Default::default() }
+    }
+
+    /// Circuit breaker around the DRAKON rankers the engine creates
+    pub fn drakon_breaker(&self) -> BreakerConfig {
+        BreakerConfig {
+            failure_threshold: self.drakon_breaker_threshold,
+            cool_down: self.drakon_breaker_cool_down.to_std().unwrap_or_default(),
+        }
+    }
+
+    /// Result type of a freshly ranked result scoring `score`
+    pub fn classify(&self, score: f64) -> SearchResultType {
+        if score >= self.exact_score {
+            SearchResultType::Exact
+        } else if score >= self.min_score {
+            SearchResultType::Fuzzy
+        } else if score >= self.not_found_score {
+            SearchResultType::Fallback
+        } else {
+            SearchResultType::NotFound
         }
     }
 }
@@ -44,136 +402,3090 @@ impl Default for SearchOptions {
 impl GameEngine {
     /// Create new game engine with default SQLite cache
     pub async fn new(db_path: impl AsRef<str>) -> Result<Self> {
-        let cache = Arc::new(SqliteCache::new(db_path.as_ref()).await?);
-        
-        // Try DRAKON first, fallback to rapidfuzz
-        let ranker: Arc<dyn Ranker> = match DrakonRanker::new("http://127.0.0.1:8000").await {
-            Ok(drakon) => {
-                tracing::info!("✅ DRAKON ranker initialized");
-                Arc::new(drakon)
+        Self::with_drakon_url(db_path, DEFAULT_DRAKON_URL).await
+    }
+
+    /// Create new game engine, trying DRAKON at `drakon_url` first
+    pub async fn with_drakon_url(db_path: impl AsRef<str>, drakon_url: &str) -> Result<Self> {
+        let backend = CacheBackend::sqlite(db_path.as_ref());
+        Self::with_backend(backend, drakon_url, SearchOptions::default()).await
+    }
+
+    /// Create engine backed by a capped in-memory cache (no database file)
+    pub async fn in_memory(options: SearchOptions) -> Result<Self> {
+        Self::with_backend(CacheBackend::Memory { capacity: DEFAULT_MEMORY_CAPACITY }, DEFAULT_DRAKON_URL, options).await
+    }
+
+    /// Create engine without any cache
+    pub async fn without_cache(options: SearchOptions) -> Result<Self> {
+        Self::with_backend(CacheBackend::None, DEFAULT_DRAKON_URL, options).await
+    }
+
+    /// Create engine on an explicit cache backend
+    pub async fn with_backend(backend: CacheBackend, drakon_url: &str, options: SearchOptions) -> Result<Self> {
+        let cache = backend.open().await?;
+        Self::with_cache(cache, backend.name(), drakon_url, options).await
+    }
+
+    /// Create engine from a configuration file's contents: cache, DRAKON URL
+    /// (none ranks in process with `delta_profile`), providers (disabled ones
+    /// are registered but switched off) and options
+    pub async fn from_config(config: &EngineConfig) -> Result<Self> {
+        config.validate()?;
+
+        let backend = config.cache.backend();
+        let cache = backend.open().await?;
+        let http = HttpClient::new(&config.http)?;
+        let mut engine =
+            Self::with_http_client(cache, backend.name(), config.drakon_url.as_deref(), config.search.to_options(), http).await?;
+        if config.drakon_url.is_none() {
+            engine.set_ranker(Arc::new(DeltaRanker::with_profile(config.delta_profile.clone())));
+        }
+        for provider in &config.providers {
+            engine.add_provider(provider.build(&engine.http)?);
+            if !provider.enabled {
+                engine.set_provider_enabled(&provider.name, false);
             }
-            Err(e) => {
-                tracing::warn!("⚠️ DRAKON unavailable, using rapidfuzz: {}", e);
-                Arc::new(RapidfuzzRanker::new())
+        }
+        Ok(engine)
+    }
+
+    /// Create engine on an already opened cache
+    pub async fn with_cache(
+        cache: Arc<dyn GameCache>,
+        cache_backend: &'static str,
+        drakon_url: &str,
+        options: SearchOptions,
+    ) -> Result<Self> {
+        let http = HttpClient::new(&HttpClientConfig::default())?;
+        Self::with_http_client(cache, cache_backend, Some(drakon_url), options, http).await
+    }
+
+    /// Create engine on an already opened cache, with the HTTP client
+    /// DRAKON (and providers built from config) share. Without a DRAKON URL
+    /// candidates are ranked in process by `DeltaRanker`
+    pub async fn with_http_client(
+        cache: Arc<dyn GameCache>,
+        cache_backend: &'static str,
+        drakon_url: Option<&str>,
+        options: SearchOptions,
+        http: HttpClient,
+    ) -> Result<Self> {
+        // Try DRAKON first, fallback to rapidfuzz
+        let ranker: Arc<dyn Ranker> = match drakon_url {
+            None => {
+                tracing::info!("✅ Δₛ³ ranker initialized (in process)");
+                Arc::new(DeltaRanker::new())
             }
+            Some(drakon_url) => match DrakonRanker::with_limits(drakon_url, http.clone(), options.drakon_limits()).await {
+                Ok(drakon) => {
+                    tracing::info!("✅ DRAKON ranker initialized");
+                    guarded_drakon(drakon, options.drakon_breaker())
+                }
+                Err(e) => {
+                    tracing::warn!("⚠️ DRAKON unavailable, using rapidfuzz: {}", e);
+                    Arc::new(RapidfuzzRanker::new())
+                }
+            },
         };
         
+        let schema = Arc::new(SchemaMonitor::new(options.schema_sample_rate));
+        
         Ok(Self {
             cache,
-            ranker,
+            cache_backend,
+            ranker: RwLock::new(ranker),
+            acronyms: RwLock::new(Acronyms::default()),
             providers: Vec::new(),
+            disabled_providers: RwLock::new(HashSet::new()),
+            options,
+            trending: TrendingDetector::default(),
+            schema,
+            write_queue: WriteRetryQueue::default(),
+            guardrails: ProviderGuardrails::default(),
+            query_log: OnceLock::new(),
+            recent: RecentSearches::default(),
+            clock: Arc::new(SystemClock),
+            oversized_rows: AtomicU64::new(0),
+            cache_lookups: AtomicU64::new(0),
+            cache_hits: AtomicU64::new(0),
+            tasks: TaskCounter::default(),
+            in_flight: TaskCounter::default(),
+            handle: OnceLock::new(),
+            refreshing: Mutex::new(HashSet::new()),
+            http,
         })
     }
 
+    /// HTTP client shared by the providers built from config and DRAKON,
+    /// to build other providers on the same connection pool
+    pub fn http_client(&self) -> &HttpClient {
+        &self.http
+    }
+
+    /// Replace search options
+    pub fn set_options(&mut self, options: SearchOptions) {
+        self.schema.set_sample_rate(options.schema_sample_rate);
+        self.options = options;
+    }
+
+    /// Current search options
+    pub fn options(&self) -> &SearchOptions {
+        &self.options
+    }
+
+    /// Cache backend name ("sqlite", "memory" or "none")
+    pub fn cache_backend(&self) -> &'static str {
+        self.cache_backend
+    }
+
+    /// Replace the trending detector (resets its counters)
+    pub fn set_trending_config(&mut self, config: TrendingConfig) {
+        self.trending = TrendingDetector::new(config);
+    }
+
+    /// Replace the failed cache write queue (pending writes are discarded)
+    pub fn set_write_retry_config(&mut self, config: WriteRetryConfig) {
+        self.write_queue = WriteRetryQueue::new(config);
+    }
+
+    /// Replace the provider coverage guardrails (resets their windows)
+    pub fn set_guardrail_config(&mut self, config: GuardrailConfig) {
+        self.guardrails = ProviderGuardrails::new(config);
+    }
+
+    /// Providers whose zero-result or error rate stayed above thresholds
+    pub fn degraded_providers(&self) -> Vec<DegradedProvider> {
+        self.guardrails.degraded()
+    }
+
+    /// Ready unless all enabled providers are degraded (or none is enabled)
+    pub fn readiness(&self) -> Readiness {
+        let degraded_providers = self.degraded_providers();
+        let ready = self
+            .enabled_providers()
+            .iter()
+            .any(|p| !self.guardrails.is_degraded(p.name()));
+        Readiness { ready, degraded_providers }
+    }
+
+    /// Queries whose request rate currently exceeds their baseline
+    pub fn trending(&self, limit: usize) -> Vec<TrendingQuery> {
+        self.trending.trending(limit)
+    }
+
+    /// Replace the time source (cache TTLs), for tests
+    pub fn set_clock(&mut self, clock: Arc<dyn Clock>) {
+        self.clock = clock;
+    }
+
+    /// Latest `n` successful searches, newest first (kept in memory, never
+    /// written anywhere)
+    pub fn last_searches(&self, n: usize) -> Vec<SearchSummary> {
+        self.recent.last(n)
+    }
+
     /// Add a game provider
     pub fn add_provider(&mut self, provider: Arc<dyn GameProvider>) {
+        provider.attach_schema_monitor(self.schema.clone());
         self.providers.push(provider);
     }
 
+    /// Registered provider by name
+    pub fn provider(&self, name: &str) -> Option<Arc<dyn GameProvider>> {
+        self.providers.iter().find(|p| p.name() == name).cloned()
+    }
+
+    /// Load blessed schema baselines of registered providers from the cache,
+    /// returns how many were found
+    pub async fn load_schema_baselines(&self) -> Result<usize> {
+        let mut loaded = 0;
+        for provider in &self.providers {
+            if let Some(baseline) = self.cache.schema_baseline(provider.name()).await? {
+                self.schema.set_baseline(provider.name(), baseline);
+                loaded += 1;
+            }
+        }
+        Ok(loaded)
+    }
+
+    /// Blessed schema baseline of a provider
+    pub async fn schema_baseline(&self, provider: &str) -> Result<Option<Fingerprint>> {
+        self.cache.schema_baseline(provider).await
+    }
+
+    /// Store `fingerprint` as the new baseline for `provider`
+    pub async fn bless_schema(&self, provider: &str, fingerprint: Fingerprint) -> Result<()> {
+        self.cache.save_schema_baseline(provider, &fingerprint).await?;
+        self.schema.set_baseline(provider, fingerprint);
+        tracing::info!("📐 Schema baseline blessed for provider {}", provider);
+        Ok(())
+    }
+
+    /// Schema drift of sampled provider responses against their baselines
+    pub fn schema_drift(&self) -> Vec<ProviderSchemaDrift> {
+        self.schema.drift()
+    }
+
+    /// Swap the ranker used by subsequent searches
+    pub fn set_ranker(&self, ranker: Arc<dyn Ranker>) {
+        let mut current = self.ranker.write().unwrap_or_else(|e| e.into_inner());
+        tracing::info!("🔀 Ranker switched: {} → {}", current.name(), ranker.name());
+        *current = ranker;
+    }
+
+    /// Current ranker
+    pub fn ranker(&self) -> Arc<dyn Ranker> {
+        self.ranker.read().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    /// Expand `acronym` to `expansion` in queries before ranking, on top of
+    /// (or replacing) the built-in gaming acronyms
+    pub fn add_alias(&self, acronym: &str, expansion: &str) {
+        self.acronyms.write().unwrap_or_else(|e| e.into_inner()).insert(acronym, expansion);
+    }
+
+    /// `query` with its acronyms expanded, None when it has none
+    pub fn expand_acronyms(&self, query: &str) -> Option<String> {
+        self.acronyms.read().unwrap_or_else(|e| e.into_inner()).expand(query)
+    }
+
+    /// Enable or disable a registered provider, returns false if unknown
+    pub fn set_provider_enabled(&self, name: &str, enabled: bool) -> bool {
+        if !self.providers.iter().any(|p| p.name() == name) {
+            return false;
+        }
+        
+        let mut disabled = self.disabled_providers.write().unwrap_or_else(|e| e.into_inner());
+        if enabled {
+            disabled.remove(name);
+        } else {
+            disabled.insert(name.to_string());
+        }
+        tracing::info!("Provider {} {}", name, if enabled { "enabled" } else { "disabled" });
+        true
+    }
+
+    /// Registered providers with their enabled flag
+    pub fn provider_status(&self) -> Vec<(String, bool)> {
+        let disabled = self.disabled_providers.read().unwrap_or_else(|e| e.into_inner());
+        self.providers
+            .iter()
+            .map(|p| (p.name().to_string(), !disabled.contains(p.name())))
+            .collect()
+    }
+
+    /// Providers currently enabled
+    fn enabled_providers(&self) -> Vec<Arc<dyn GameProvider>> {
+        let disabled = self.disabled_providers.read().unwrap_or_else(|e| e.into_inner());
+        self.providers
+            .iter()
+            .filter(|p| !disabled.contains(p.name()))
+            .cloned()
+            .collect()
+    }
+
+    /// Enabled providers a query may use, honoring its provider restriction.
+    /// Fails when none of the requested providers is registered.
+    fn providers_for(&self, query: &SearchQuery) -> Result<Vec<Arc<dyn GameProvider>>> {
+        let Some(wanted) = &query.providers else {
+            return Ok(self.enabled_providers());
+        };
+
+        if !self.providers.iter().any(|p| wanted.iter().any(|w| w == p.name())) {
+            return Err(GameEngineError::Provider {
+                provider: wanted.join(","),
+                message: "no such provider registered".to_string(),
+            });
+        }
+
+        Ok(self
+            .enabled_providers()
+            .into_iter()
+            .filter(|p| wanted.iter().any(|w| w == p.name()))
+            .collect())
+    }
+
+    /// Spawn a task re-probing DRAKON health: upgrades to `DrakonRanker` when
+    /// it comes back, downgrades to rapidfuzz after `max_failures` failed probes.
+    /// The task stops once the engine is dropped.
+    pub fn spawn_drakon_monitor(self: &Arc<Self>, config: DrakonMonitorConfig) -> tokio::task::JoinHandle<()> {
+        let engine = Arc::downgrade(self);
+        let http = self.http.clone();
+        let limits = self.options.drakon_limits();
+        let breaker = self.options.drakon_breaker();
+        
+        self.tasks.spawn(async move {
+            let mut failures = 0u32;
+            
+            loop {
+                tokio::time::sleep(config.interval).await;
+                if engine.strong_count() == 0 {
+                    break;
+                }
+                
+                let probe = DrakonRanker::with_limits(config.base_url.as_str(), http.clone(), limits).await;
+                let Some(engine) = engine.upgrade() else {
+                    break;
+                };
+                let using_drakon = engine.ranker().name() == "drakon";
+                
+                match probe {
+                    Ok(drakon) => {
+                        failures = 0;
+                        if !using_drakon {
+                            tracing::info!("✅ DRAKON is back, upgrading ranker");
+                            engine.set_ranker(guarded_drakon(drakon, breaker));
+                        }
+                    }
+                    Err(e) if using_drakon => {
+                        failures += 1;
+                        tracing::warn!("⚠️ DRAKON probe failed ({}/{}): {}", failures, config.max_failures, e);
+                        if failures >= config.max_failures {
+                            engine.set_ranker(Arc::new(RapidfuzzRanker::new()));
+                            failures = 0;
+                        }
+                    }
+                    Err(e) => {
+                        tracing::debug!("DRAKON still unavailable: {}", e);
+                    }
+                }
+            }
+        })
+    }
+
     /// Search for a game
     pub async fn search(&self, query: SearchQuery) -> Result<SearchResponse> {
+        let _in_flight = self.in_flight.guard();
+        let start = Instant::now();
+        let typed = query.query.clone();
+        self.trending.record(&normalize_query(&typed));
+        let result = self.run_search(query).await;
+        if let Ok(response) = &result {
+            self.recent.record(SearchSummary::new(typed.as_str(), response));
+        }
+        if self.options.log_queries {
+            self.log_search(typed, &result, start);
+        }
+        result
+    }
+
+    /// Queue a search log entry for the background writer
+    fn log_search(&self, query: String, result: &Result<SearchResponse>, start: Instant) {
+        let entry = match result {
+            Ok(response) => SearchLogEntry {
+                query,
+                resolved_name: Some(response.game.name.clone()),
+                score: Some(response.score),
+                provider: Some(response.game.provider.clone()),
+                from_cache: response.from_cache,
+                latency_ms: response.latency_ms,
+                timestamp: chrono::Utc::now(),
+            },
+            Err(_) => SearchLogEntry {
+                query,
+                resolved_name: None,
+                score: None,
+                provider: None,
+                from_cache: false,
+                latency_ms: start.elapsed().as_secs_f64() * 1000.0,
+                timestamp: chrono::Utc::now(),
+            },
+        };
+
+        self.query_log
+            .get_or_init(|| QueryLog::spawn(self.cache.clone(), QUERY_LOG_CAPACITY, &self.tasks))
+            .record(entry);
+    }
+
+    async fn run_search(&self, query: SearchQuery) -> Result<SearchResponse> {
         let start = Instant::now();
         
-        // Check cache first
-        if query.use_cache {
-            if let Some(cached) = self.cache.get(&query.query).await? {
-                let latency_ms = start.elapsed().as_secs_f64() * 1000.0;
-                
-                self.cache.increment_hit(&query.query).await?;
-                
-                return Ok(SearchResponse {
-                    game: cached.game,
-                    score: 100.0, // Cache hit = exact match
-                    result_type: SearchResultType::CacheHit,
-                    alternatives: cached.alternatives,
-                    from_cache: true,
-                    latency_ms,
-                    provider: "cache".to_string(),
-                    ranking_method: "cache".to_string(),
-                });
+        let providers = self.providers_for(&query).ctx_query(&query.query)?;
+        let read_cache = query.cache_mode.reads();
+        let cache_key = query.cache_key();
+        
+        if read_cache {
+            self.cache_lookups.fetch_add(1, Ordering::Relaxed);
+        }
+        
+        // Known no-result query: skip providers entirely
+        if read_cache && self.cache.is_no_result(&cache_key).await.ctx_query(&query.query)? {
+            tracing::debug!("Negative cache hit for '{}'", query.query);
+            self.cache_hits.fetch_add(1, Ordering::Relaxed);
+            return Err(GameEngineError::NoResults(query.query.clone()));
+        }
+        
+        // Check cache first; an entry past its TTL is refreshed, and kept
+        // to be served if the providers come back empty (entries past the
+        // max age are only looked up again then)
+        let platforms = query.platform_families();
+        let off_platform = |game: &GameResult| platforms.as_ref().is_some_and(|families| !game.is_on_platform(families));
+        let lenient = self.options.platform_filter == PlatformFilterMode::Lenient;
+        let mut stale = None;
+        if read_cache {
+            let cached = match self.fresh_entry(&cache_key).await.ctx_query(&query.query)? {
+                Some(cached) => Some((cache_key.clone(), cached)),
+                None => self.aliased_entry(&query, &cache_key).await,
+            };
+            if let Some((served_key, cached)) = cached {
+                let aliased = served_key != cache_key;
+                if off_platform(&cached.game) && !lenient {
+                    // Saved by a lenient search: a miss for strict ones
+                    tracing::debug!("Cached winner for '{}' not on the requested platforms", query.query);
+                } else if cached.ttl.as_ref().is_some_and(|ttl| ttl.is_due(self.clock.now())) {
+                    tracing::debug!("Cache entry for '{}' due for refresh", query.query);
+                    // Another spelling's entry is only served while fresh
+                    if !aliased && self.revalidates(&cached) && self.refresh_in_background(&query, &cache_key, &cached) {
+                        let ignored = off_platform(&cached.game);
+                        let mut response = self.cached_response(&query, &cache_key, cached, start).await?;
+                        response.platform_filter_ignored = ignored;
+                        response.refreshing = true;
+                        self.cache_hits.fetch_add(1, Ordering::Relaxed);
+                        return Ok(response);
+                    }
+                    if !aliased {
+                        stale = Some(cached);
+                    }
+                } else {
+                    let ignored = off_platform(&cached.game);
+                    let mut response = self.cached_response(&query, &served_key, cached, start).await?;
+                    response.platform_filter_ignored = ignored;
+                    self.cache_hits.fetch_add(1, Ordering::Relaxed);
+                    return Ok(response);
+                }
             }
         }
         
+        self.fetch(&query, providers, cache_key, stale, false, start).await
+    }
+    
+    /// Search the providers, rank and cache the result. `stale` is the
+    /// expired entry being refreshed; a background refresh (`revalidating`)
+    /// leaves it untouched when the providers return nothing.
+    async fn fetch(
+        &self,
+        query: &SearchQuery,
+        providers: Vec<Arc<dyn GameProvider>>,
+        cache_key: String,
+        stale: Option<CachedGame>,
+        revalidating: bool,
+        start: Instant,
+    ) -> Result<SearchResponse> {
+        let read_cache = query.cache_mode.reads();
+        let platforms = query.platform_families();
+        let off_platform = |game: &GameResult| platforms.as_ref().is_some_and(|families| !game.is_on_platform(families));
+        let lenient = self.options.platform_filter == PlatformFilterMode::Lenient;
+        
         // Fetch from providers
-        let mut all_candidates = Vec::new();
-        for provider in &self.providers {
-            match provider.search(&query.query).await {
-                Ok(mut results) => {
+        let mut per_provider: Vec<(String, Vec<GameResult>)> = Vec::new();
+        let mut failed = false;
+        for provider in providers {
+            let results = provider
+                .search_localized(&query.query, query.locale.as_deref(), query.country.as_deref())
+                .await
+                .ctx_phase(Phase::ProviderSearch)
+                .ctx_provider(provider.name())
+                .ctx_query(&query.query);
+            
+            match results {
+                Ok(results) => {
                     tracing::debug!("Provider {} returned {} results", provider.name(), results.len());
-                    all_candidates.append(&mut results);
+                    if results.is_empty() {
+                        self.guardrails.record(provider.name(), ProviderOutcome::Empty);
+                    } else {
+                        self.guardrails.record(provider.name(), ProviderOutcome::Results);
+                        per_provider.push((provider.name().to_string(), results));
+                    }
                 }
                 Err(e) => {
-                    tracing::warn!("Provider {} failed: {}", provider.name(), e);
+                    tracing::warn!("Provider {} failed: {}", provider.name(), e.diagnostic());
+                    self.guardrails.record(provider.name(), ProviderOutcome::Error);
+                    failed = true;
+                }
+            }
+        }
+        
+        if query.exclude_unreleased {
+            for (_, results) in per_provider.iter_mut() {
+                results.retain(|game| !game.is_unreleased());
+            }
+            per_provider.retain(|(_, results)| !results.is_empty());
+        }
+        
+        // Nothing on the requested platforms: strict searches still rank the
+        // other candidates for a "did you mean", lenient ones drop the filter
+        let mut platform_unmatched = false;
+        let mut platform_filter_ignored = false;
+        if platforms.is_some() {
+            let on_platform: Vec<(String, Vec<GameResult>)> = per_provider
+                .iter()
+                .map(|(name, results)| (name.clone(), results.iter().filter(|game| !off_platform(game)).cloned().collect::<Vec<_>>()))
+                .filter(|(_, results)| !results.is_empty())
+                .collect();
+            if !on_platform.is_empty() {
+                per_provider = on_platform;
+            } else if !per_provider.is_empty() {
+                tracing::warn!("No candidate for '{}' on the requested platforms", query.query);
+                if lenient {
+                    platform_filter_ignored = true;
+                } else {
+                    platform_unmatched = true;
                 }
             }
         }
         
+        let all_candidates: Vec<GameResult> = per_provider.iter().flat_map(|(_, r)| r.iter().cloned()).collect();
+        if all_candidates.is_empty() && revalidating {
+            return Err(GameEngineError::NoResults(query.query.clone()));
+        }
         if all_candidates.is_empty() {
+            let stale = match stale {
+                Some(stale) => Some(stale),
+                None if read_cache && self.options.serve_stale_on_error => self
+                    .cache
+                    .get(&cache_key)
+                    .await
+                    .ok()
+                    .flatten()
+                    .filter(|cached| lenient || !off_platform(&cached.game)),
+                None => None,
+            };
+            if let Some(stale) = stale.filter(|_| self.options.serve_stale_on_error) {
+                tracing::warn!("No provider results for '{}', serving expired cache entry", query.query);
+                let ignored = off_platform(&stale.game);
+                let mut response = self.cached_response(query, &cache_key, stale, start).await?;
+                response.platform_filter_ignored = ignored;
+                return Ok(response);
+            }
+            // Only when every provider answered: an outage or a rate limit
+            // would otherwise hide the query for the whole TTL
+            if let (true, false, Some(ttl)) = (query.cache_mode.writes(), failed, self.options.no_result_ttl) {
+                let saved = self.cache
+                    .save_no_result(&cache_key, ttl)
+                    .await
+                    .ctx_query(&query.query);
+                match saved {
+                    Ok(()) => self.write_queue.forget(&cache_key),
+                    Err(e) => {
+                        tracing::warn!("Failed to save no-result entry, queued for retry: {}", e.diagnostic());
+                        self.write_queue.push(PendingWrite::NoResult { key: cache_key, ttl });
+                    }
+                }
+            }
             return Err(GameEngineError::NoResults(query.query.clone()));
         }
         
-        // Rank candidates
-        let ranked = self.ranker.rank(&query.query, &all_candidates)?;
+        // Rank candidates, against the query with its acronyms spelled out
+        let expanded = self.expand_acronyms(&query.query);
+        let ranked_query = expanded.as_deref().unwrap_or(&query.query);
+        let (candidates, quota) = apply_provider_quota(ranked_query, &per_provider, self.options.provider_quota);
+        let ranker = self.ranker();
+        let query_key = exact_key(ranked_query);
+        let (exact, others): (Vec<GameResult>, Vec<GameResult>) =
+            candidates.iter().cloned().partition(|game| game.names().any(|name| exact_key(name) == query_key));
+        // A single candidate named (or aliased) like the query wins without a ranking pass
+        // (nor DRAKON round-trip); several (regional duplicates) are ranked
+        // among themselves. The others follow unscored, by name pre-score.
+        let (mut ranked, ranking_method) = if let [only] = exact.as_slice() {
+            let winner = if query.explain {
+                let mut breakdown = ScoreBreakdown::new(EXACT_MATCH, 100.0, candidates.len());
+                if exact_key(&only.name) != query_key {
+                    breakdown.matched_alias = only.aliases.iter().find(|alias| exact_key(alias) == query_key).cloned();
+                }
+                RankedCandidate::explained(only.clone(), breakdown)
+            } else {
+                RankedCandidate::new(only.clone(), 100.0)
+            };
+            (vec![winner], EXACT_MATCH.to_string())
+        } else {
+            let pool = if exact.is_empty() { &candidates } else { &exact };
+            // Named after the ranker that actually ranked (a guarded DRAKON
+            // may have fallen back)
+            let (mut ranked, method) = ranker
+                .rank_attributed(ranked_query, pool, query.explain)
+                .await
+                .ctx_phase(Phase::Ranking)
+                .ctx_query(&query.query)?;
+            calibrate_ranked(&mut ranked, &ranker.score_scale());
+            if let Some(weight) = self.options.popularity_weight {
+                apply_popularity(&mut ranked, weight, self.options.popularity_band);
+            }
+            break_ties(ranked_query, &mut ranked, self.options.tie_epsilon);
+            (ranked, method)
+        };
+        let scored = ranked.len();
+        if !exact.is_empty() {
+            let mut others = others;
+            others.sort_by(|a, b| prescore_game(ranked_query, b).total_cmp(&prescore_game(ranked_query, a)));
+            ranked.extend(others.into_iter().map(|game| RankedCandidate::new(game, 0.0)));
+        }
+        if self.options.collapse_editions {
+            editions::promote_canonical_edition(ranked_query, &mut ranked[..scored], &self.options.edition_keywords);
+        }
         
         if ranked.is_empty() {
             return Err(GameEngineError::NoResults(query.query.clone()));
         }
         
-        let best = ranked[0].clone();
-        let alternatives: Vec<GameResult> = ranked
+        let mut best = ranked[0].clone();
+        if let Some(breakdown) = best.breakdown.as_mut() {
+            breakdown.provider_quota = quota;
+            breakdown.expanded_query = expanded;
+        }
+        if self.options.enrich {
+            enrich(&mut best.game, &all_candidates);
+        }
+        // Duplicates merged into the winner are not alternatives
+        let merged = |game: &GameResult| {
+            self.options.enrich && game.provider != best.game.provider && best.game.is_same_game(game)
+        };
+        // Editions of the winner, when collapsed, don't count against
+        // `max_results` (they are split out of the alternatives below, and
+        // again on cache hits)
+        let keywords = &self.options.edition_keywords;
+        let base = self.options.collapse_editions.then(|| editions::edition_base(&best.game.name, keywords));
+        let is_edition = |game: &GameResult| base.as_ref().is_some_and(|base| editions::edition_base(&game.name, keywords) == *base);
+        let limit = query.max_results.saturating_sub(1);
+        let mut others = 0;
+        let alternatives: Vec<SlimGameResult> = ranked
             .iter()
+            .enumerate()
             .skip(1)
-            .take(query.max_results.saturating_sub(1))
-            .map(|r| r.game.clone())
+            .filter(|(_, r)| !merged(&r.game))
+            .filter(|(_, r)| {
+                is_edition(&r.game) || {
+                    others += 1;
+                    others <= limit
+                }
+            })
+            .map(|(i, r)| SlimGameResult::from_game(&r.game, (i < scored).then_some(r.score)))
             .collect();
         
-        // Save to cache
-        if query.use_cache && best.score >= 70.0 {
-            if let Err(e) = self.cache.save(&query.query, &best.game, &alternatives).await {
-                tracing::warn!("Failed to save to cache: {}", e);
+        // Save to cache (forced refreshes always replace the entry, likely
+        // wrong guesses are otherwise never saved, off-platform ones never)
+        let result_type = if platform_unmatched {
+            SearchResultType::NotFound
+        } else {
+            self.options.classify(best.score)
+        };
+        let cacheable = !platform_unmatched
+            && (query.cache_mode == CacheMode::Bypass
+                || (result_type != SearchResultType::NotFound
+                    && (best.score >= self.options.cache_min_score || self.options.cache_low_confidence)));
+        let stored = (query.cache_mode.writes() && cacheable)
+            .then(|| self.fit_cache_row(&cache_key, &best.game, &alternatives))
+            .flatten();
+        if let Some(stored) = stored {
+            // Refreshes that bring nothing new stretch the TTL
+            let unchanged_refreshes = match stale.as_ref().and_then(|s| s.ttl.as_ref().map(|ttl| (s, ttl))) {
+                Some((stale, ttl)) if ttl::unchanged(&stale.game, &best.game) => ttl.unchanged_refreshes.saturating_add(1),
+                _ => 0,
+            };
+            let ttl = self.options.cache_ttl.decide(&best.game, unchanged_refreshes, self.clock.now());
+            let saved = self.cache
+                .save_with_ttl(&cache_key, &best.game, &stored, best.score, &ranking_method, &ttl)
+                .await
+                .ctx_query(&query.query);
+            match saved {
+                Ok(()) => self.write_queue.forget(&cache_key),
+                Err(e) => {
+                    tracing::warn!("Failed to save to cache, queued for retry: {}", e.diagnostic());
+                    self.write_queue.push(PendingWrite::Scored {
+                        key: cache_key,
+                        game: Box::new(best.game.clone()),
+                        alternatives: stored,
+                        score: best.score,
+                        ranking_method: ranking_method.clone(),
+                        ttl: Some(ttl),
+                    });
+                }
             }
         }
         
         let latency_ms = start.elapsed().as_secs_f64() * 1000.0;
-        
-        let result_type = if best.score >= 95.0 {
-            SearchResultType::Exact
-        } else if best.score >= 70.0 {
-            SearchResultType::Fuzzy
-        } else {
-            SearchResultType::Fallback
-        };
+        let (editions, alternatives) = self.split_editions(&best.game.name, alternatives);
         
         Ok(SearchResponse {
             game: best.game.clone(),
             score: best.score,
             result_type,
             alternatives,
+            editions,
             from_cache: false,
             latency_ms,
             provider: best.game.provider.clone(),
-            ranking_method: self.ranker.name().to_string(),
+            ranking_method,
+            cache_mode: query.cache_mode,
+            score_breakdown: best.breakdown.clone(),
+            platform_filter_ignored,
+            refreshing: false,
         })
     }
     
-    /// Get cache statistics
-    pub async fn cache_stats(&self) -> Result<crate::cache::CacheStats> {
-        self.cache.stats().await
+    /// Whether an entry past its TTL is still recent enough to be served
+    /// while it is refreshed in the background
+    fn revalidates(&self, cached: &CachedGame) -> bool {
+        match (self.options.stale_while_revalidate, &cached.ttl) {
+            (Some(window), Some(ttl)) => self.clock.now() < ttl.refresh_after + window,
+            _ => false,
+        }
     }
-    
-    /// Clean up old cache entries
-    pub async fn cleanup_cache(&self, max_age_days: i64) -> Result<u64> {
-        self.cache.cleanup(max_age_days).await
+
+    /// Refresh `cache_key` in the background unless a refresh is already
+    /// running; false when background refreshes are not enabled
+    fn refresh_in_background(&self, query: &SearchQuery, cache_key: &str, stale: &CachedGame) -> bool {
+        let Some(engine) = self.handle.get().and_then(Weak::upgrade) else {
+            return false;
+        };
+        if !self.refreshing.lock().unwrap_or_else(|e| e.into_inner()).insert(cache_key.to_string()) {
+            return true;
+        }
+
+        tracing::debug!("Serving '{}' past its TTL, refreshing in the background", query.query);
+        let query = query.clone();
+        let refreshing = RefreshGuard { engine, key: cache_key.to_string() };
+        let stale = stale.clone();
+        self.tasks.spawn(async move {
+            let engine = &refreshing.engine;
+            let refreshed = match engine.providers_for(&query) {
+                Ok(providers) => engine.fetch(&query, providers, refreshing.key.clone(), Some(stale), true, Instant::now()).await,
+                Err(e) => Err(e),
+            };
+            if let Err(e) = refreshed {
+                tracing::warn!("⚠️ Background refresh of '{}' failed: {}", query.query, e.diagnostic());
+            }
+        });
+        true
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    /// Cache entry for `key`, unless past its expiry or the max age
+    async fn fresh_entry(&self, key: &str) -> Result<Option<CachedGame>> {
+        match self.options.cache_max_age {
+            Some(max_age) => self.cache.get_fresh(key, max_age).await,
+            None => Ok(self.cache.get(key).await?.filter(|cached| cached.is_fresh(None, chrono::Utc::now()))),
+        }
+    }
 
-    #[tokio::test]
-    async fn test_engine_creation() {
-        let result = GameEngine::new(":memory:").await;
-        assert!(result.is_ok());
+    /// Second-chance lookup after a cache miss: the entry of a near-identical
+    /// earlier query, with its key (see `SearchOptions::fuzzy_cache_min_score`).
+    /// Failures are logged and count as a miss.
+    async fn aliased_entry(&self, query: &SearchQuery, cache_key: &str) -> Option<(String, CachedGame)> {
+        let min_score = self.options.fuzzy_cache_min_score?;
+        match self.find_aliased_entry(query, cache_key, min_score).await {
+            Ok(found) => found,
+            Err(e) => {
+                tracing::warn!("Fuzzy cache lookup for '{}' failed: {}", query.query, e.diagnostic());
+                None
+            }
+        }
+    }
+
+    async fn find_aliased_entry(&self, query: &SearchQuery, cache_key: &str, min_score: f64) -> Result<Option<(String, CachedGame)>> {
+        if let Some(target) = self.cache.alias(cache_key).await? {
+            if let Some(cached) = self.fresh_entry(&target).await? {
+                return Ok(Some((target, cached)));
+            }
+        }
+
+        // Keys of the same variant (locale, providers...) sharing the first
+        // characters, ranked by their query part
+        let base = key_part(&query.query);
+        let Some(suffix) = cache_key.strip_prefix(base.as_str()) else {
+            return Ok(None);
+        };
+        if base.chars().count() <= FUZZY_CACHE_PREFIX {
+            return Ok(None);
+        }
+        let prefix: String = base.chars().take(FUZZY_CACHE_PREFIX).collect();
+        let keys = self.cache.keys_matching(&format!("{}%", escape_like(&prefix)), FUZZY_CACHE_CANDIDATES).await?;
+        let candidates: Vec<GameResult> = keys
+            .iter()
+            .filter_map(|key| {
+                let name = &key[..key.find(CACHE_KEY_SUFFIXES).unwrap_or(key.len())];
+                (key != cache_key && &key[name.len()..] == suffix && same_series_numbers(&base, name))
+                    .then(|| GameResult::new("cache", key.as_str(), name))
+            })
+            .collect();
+        if candidates.is_empty() {
+            return Ok(None);
+        }
+
+        let ranker = self.ranker();
+        let mut ranked = ranker.rank(&base, &candidates).await.ctx_phase(Phase::Ranking)?;
+        calibrate_ranked(&mut ranked, &ranker.score_scale());
+        let Some(best) = ranked.into_iter().next().filter(|best| best.score >= min_score) else {
+            return Ok(None);
+        };
+        let target = best.game.id;
+        let Some(cached) = self.fresh_entry(&target).await? else {
+            return Ok(None);
+        };
+
+        tracing::debug!("Serving cache entry '{}' for '{}' ({:.1}%)", target, cache_key, best.score);
+        if query.cache_mode.writes() {
+            if let Err(e) = self.cache.save_alias(cache_key, &target).await {
+                tracing::warn!("Failed to save cache alias '{}': {}", cache_key, e.diagnostic());
+            }
+        }
+        Ok(Some((target, cached)))
+    }
+
+    /// Alternatives to store with `game` so the row fits in the max row size
+    /// (trailing ones dropped), None when the game alone is too big
+    fn fit_cache_row(&self, cache_key: &str, game: &GameResult, alternatives: &[SlimGameResult]) -> Option<Vec<SlimGameResult>> {
+        let mut stored = alternatives.to_vec();
+        let Some(max_bytes) = self.options.cache_max_row_bytes else {
+            return Some(stored);
+        };
+        match fit_row(game, &mut stored, max_bytes) {
+            Ok(_) => Some(stored),
+            Err(size) => {
+                tracing::warn!("Cache row for '{}' is {} bytes (max {}), not cached", cache_key, size, max_bytes);
+                self.oversized_rows.fetch_add(1, Ordering::Relaxed);
+                None
+            }
+        }
+    }
+    
+    /// Full record of a compact game (an alternative), fetched again from
+    /// the provider that returned it
+    pub async fn rehydrate(&self, game: &SlimGameResult) -> Result<GameResult> {
+        let provider = self.provider(&game.provider).ok_or_else(|| GameEngineError::Provider {
+            provider: game.provider.clone(),
+            message: "no such provider registered".to_string(),
+        })?;
+        provider
+            .get_by_id(&game.id)
+            .await
+            .ctx_phase(Phase::ProviderSearch)
+            .ctx_provider(provider.name())
+            .ctx_query(&game.name)
+    }
+    
+    /// Editions of the game named `name` split out of `alternatives` when
+    /// `collapse_editions` is set (else none)
+    fn split_editions(&self, name: &str, alternatives: Vec<SlimGameResult>) -> (Vec<SlimGameResult>, Vec<SlimGameResult>) {
+        if self.options.collapse_editions {
+            editions::split_editions(name, alternatives, &self.options.edition_keywords)
+        } else {
+            (Vec::new(), alternatives)
+        }
+    }
+    
+    /// Response for a cache entry, counting the hit
+    async fn cached_response(
+        &self,
+        query: &SearchQuery,
+        cache_key: &str,
+        cached: CachedGame,
+        start: Instant,
+    ) -> Result<SearchResponse> {
+        let latency_ms = start.elapsed().as_secs_f64() * 1000.0;
+        
+        self.cache.increment_hit(cache_key).await.ctx_query(&query.query)?;
+        
+        // Legacy rows have no score: treat as exact match, scored by "cache"
+        let score = cached.score.unwrap_or(100.0);
+        let result_type = if score < self.options.cache_min_score {
+            SearchResultType::Fallback
+        } else {
+            SearchResultType::CacheHit
+        };
+        
+        let (editions, alternatives) = self.split_editions(&cached.game.name, cached.alternatives);
+        Ok(SearchResponse {
+            game: cached.game,
+            score,
+            result_type,
+            alternatives,
+            editions,
+            from_cache: true,
+            latency_ms,
+            provider: "cache".to_string(),
+            ranking_method: cached.ranking_method.unwrap_or_else(|| "cache".to_string()),
+            cache_mode: query.cache_mode,
+            score_breakdown: None,
+            platform_filter_ignored: false,
+            refreshing: false,
+        })
+    }
+    
+    /// Cache entry a search would read, with its TTL and the reason for it
+    /// (hit counter untouched)
+    pub async fn inspect_cache(&self, query: &SearchQuery) -> Result<Option<CachedGame>> {
+        self.cache.get(&query.cache_key()).await.ctx_query(&query.query)
+    }
+    
+    /// Get cache statistics
+    pub async fn cache_stats(&self) -> Result<crate::cache::CacheStats> {
+        let mut stats = self.cache.stats().await?;
+        let retries = self.write_queue.stats();
+        stats.pending_writes = retries.pending;
+        stats.write_retries_succeeded = retries.succeeded;
+        stats.write_retries_dropped = retries.dropped;
+        stats.oversized_rows = self.oversized_rows.load(Ordering::Relaxed);
+        let lookups = self.cache_lookups.load(Ordering::Relaxed);
+        if lookups > 0 {
+            stats.hit_rate = Some(self.cache_hits.load(Ordering::Relaxed) as f64 / lookups as f64);
+        }
+        Ok(stats)
+    }
+
+    /// Task, search and cache counters (reads the cache stats)
+    pub async fn info(&self) -> Result<EngineInfo> {
+        let stats = self.cache.stats().await?;
+        Ok(EngineInfo {
+            cache_backend: self.cache_backend.to_string(),
+            ranker: self.ranker().name().to_string(),
+            background_tasks: self.tasks.count(),
+            in_flight_searches: self.in_flight.count(),
+            cache_entries: stats.total_entries,
+            cache_capacity: self.cache.capacity(),
+            pending_writes: self.write_queue.len(),
+            trending_queries: self.trending.len(),
+            recent_searches: self.recent.len(),
+            http: self.http.stats(),
+        })
+    }
+
+    /// Replay failed cache writes once, returns `(succeeded, failed)`
+    /// (an unhealthy cache counts as one failure)
+    pub async fn retry_pending_writes(&self) -> (usize, usize) {
+        if self.write_queue.is_empty() || !self.cache.is_healthy().await {
+            return (0, usize::from(!self.write_queue.is_empty()));
+        }
+        self.write_queue.replay(self.cache.as_ref()).await
+    }
+
+    /// Let searches refresh expired entries in the background (see
+    /// `SearchOptions::stale_while_revalidate`). Refresh tasks hold the
+    /// engine until they finish.
+    pub fn enable_stale_refresh(self: &Arc<Self>) {
+        let _ = self.handle.set(Arc::downgrade(self));
+    }
+
+    /// Spawn a task replaying failed cache writes, backing off while the
+    /// cache keeps failing. The task stops once the engine is dropped.
+    pub fn spawn_cache_write_retry(self: &Arc<Self>) -> tokio::task::JoinHandle<()> {
+        let engine = Arc::downgrade(self);
+        let backoff = self.write_queue.config().backoff.clone();
+        
+        self.tasks.spawn(async move {
+            let mut failures = 0u32;
+            
+            loop {
+                let delay = if failures == 0 { backoff.base_delay } else { backoff.delay(failures + 1) };
+                tokio::time::sleep(delay).await;
+                let Some(engine) = engine.upgrade() else {
+                    break;
+                };
+                
+                let (succeeded, failed) = engine.retry_pending_writes().await;
+                if succeeded > 0 {
+                    tracing::info!("✅ Replayed {} deferred cache write(s)", succeeded);
+                }
+                failures = if failed == 0 { 0 } else { failures.saturating_add(1) };
+            }
+        })
+    }
+    
+    /// Most requested queries by hit count, optionally only those hit since `since`
+    pub async fn top_queries(&self, limit: usize, since: Option<chrono::DateTime<chrono::Utc>>) -> Result<Vec<(String, i32)>> {
+        self.cache.top_queries(limit, since).await
+    }
+    
+    /// Clean up cache entries not saved or hit in `max_age_days`
+    pub async fn cleanup_cache(&self, max_age_days: i64) -> Result<u64> {
+        self.cache.cleanup(max_age_days).await
+    }
+
+    /// Clean up cache entries cached more than `max_age_days` ago, even
+    /// those still hit
+    pub async fn cleanup_cache_by_insert_age(&self, max_age_days: i64) -> Result<u64> {
+        self.cache.cleanup_by_insert_age(max_age_days).await
+    }
+
+    /// Drop the cached result of `query` in every variant (locale, country,
+    /// providers, platforms) along with pending retried writes for them, so
+    /// the next search asks the providers again. Returns the number of
+    /// entries deleted. The deletes are one cache mutation: on failure no
+    /// variant is dropped.
+    pub async fn invalidate(&self, query: &str) -> Result<u64> {
+        let key = key_part(query);
+        let patterns: Vec<String> = CACHE_KEY_SUFFIXES
+            .iter()
+            .map(|suffix| format!("{}{}%", escape_like(&key), suffix))
+            .collect();
+        let deleted = self.cache.invalidate_where(&key, &patterns).await.ctx_query(query)?;
+
+        self.write_queue.forget(&key);
+        for pattern in &patterns {
+            self.write_queue.forget_matching(pattern);
+        }
+
+        if deleted > 0 {
+            tracing::info!("🗑️ Invalidated {} cache entries for '{}'", deleted, key);
+        }
+        Ok(deleted)
+    }
+
+    /// Delete cache entries and pending retried writes whose key matches a
+    /// SQL LIKE `pattern` (see `GameCache::delete_matching`)
+    pub async fn invalidate_matching(&self, pattern: &str) -> Result<u64> {
+        let deleted = self.cache.delete_matching(pattern).await?;
+        self.write_queue.forget_matching(pattern);
+        Ok(deleted)
+    }
+
+    /// Latest logged searches, newest first (see `SearchOptions::log_queries`)
+    pub async fn recent_searches(&self, limit: usize) -> Result<Vec<SearchLogEntry>> {
+        self.flush_query_log().await;
+        self.cache.recent_searches(limit).await
+    }
+
+    /// Check the cache for entries that cannot be served, deleting them when
+    /// `repair` is set (see `GameCache::integrity_check`)
+    pub async fn check_cache(&self, repair: bool) -> Result<IntegrityReport> {
+        self.cache.integrity_check(repair).await
+    }
+
+    /// Rewrite cached entries in the cache's current storage format, e.g.
+    /// after turning compression on or off (see `GameCache::recompress`)
+    pub async fn recompress_cache(&self) -> Result<RecompressReport> {
+        self.cache.recompress().await
+    }
+
+    /// Page through cached games for admin tooling (see `GameCache::list`)
+    pub async fn list_cache(&self, offset: usize, limit: usize, order: CacheOrder) -> Result<Vec<CacheEntrySummary>> {
+        self.cache.list(offset, limit, order).await
+    }
+
+    /// Fill the cache from a list of queries, `concurrency` at a time: the
+    /// bound keeps provider rate limits (retried with backoff by the
+    /// providers) from being hit. Queries with a fresh entry are skipped.
+    /// Warm-up searches stay out of trending, the recent list and the query
+    /// log.
+    pub async fn warm(self: &Arc<Self>, queries: impl IntoIterator<Item = String>, concurrency: usize) -> Result<WarmReport> {
+        let mut seen = HashSet::new();
+        let queue: std::collections::VecDeque<String> = queries
+            .into_iter()
+            .map(|q| q.trim().to_string())
+            .filter(|q| !q.is_empty() && seen.insert(normalize_query(q)))
+            .collect();
+        let mut report = WarmReport { total: queue.len(), ..Default::default() };
+        if queue.is_empty() {
+            return Ok(report);
+        }
+
+        let queue = Arc::new(std::sync::Mutex::new(queue));
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        let workers: Vec<_> = (0..concurrency.clamp(1, report.total))
+            .map(|_| {
+                let engine = self.clone();
+                let queue = queue.clone();
+                let tx = tx.clone();
+                tokio::spawn(async move {
+                    loop {
+                        let next = queue.lock().unwrap().pop_front();
+                        let Some(query) = next else { break };
+                        let outcome = engine.warm_one(query).await;
+                        if tx.send(outcome).is_err() {
+                            break;
+                        }
+                    }
+                })
+            })
+            .collect();
+        drop(tx);
+
+        let mut done = 0;
+        while let Some(outcome) = rx.recv().await {
+            match outcome {
+                WarmOutcome::Cached => report.cached += 1,
+                WarmOutcome::Fetched => report.fetched += 1,
+                WarmOutcome::Failed(query, error) => {
+                    report.failed += 1;
+                    report.failures.push(WarmFailure { query, error });
+                }
+            }
+            done += 1;
+            if done % WARM_PROGRESS_EVERY == 0 || done == report.total {
+                tracing::info!(
+                    "🔥 Warmed {}/{} queries ({} cached, {} fetched, {} failed)",
+                    done, report.total, report.cached, report.fetched, report.failed
+                );
+            }
+        }
+        for worker in workers {
+            worker.await.map_err(|e| GameEngineError::Other(format!("Warm-up worker failed: {}", e)))?;
+        }
+        Ok(report)
+    }
+
+    async fn warm_one(&self, typed: String) -> WarmOutcome {
+        let _in_flight = self.in_flight.guard();
+        let query = SearchQuery { query: typed.clone(), ..Default::default() };
+        match self.fresh_entry(&query.cache_key()).await {
+            Ok(Some(cached)) if !cached.ttl.as_ref().is_some_and(|ttl| ttl.is_due(self.clock.now())) => {
+                return WarmOutcome::Cached;
+            }
+            Ok(_) => {}
+            Err(e) => return WarmOutcome::Failed(typed, e.diagnostic()),
+        }
+        match self.run_search(query).await {
+            Ok(response) if response.from_cache => WarmOutcome::Cached,
+            Ok(_) => WarmOutcome::Fetched,
+            Err(e) => {
+                tracing::debug!("Warm-up of '{}' failed: {}", typed, e.diagnostic());
+                WarmOutcome::Failed(typed, e.diagnostic())
+            }
+        }
+    }
+
+    /// Delete search log entries older than `max_age_days`
+    pub async fn cleanup_log(&self, max_age_days: i64) -> Result<u64> {
+        self.cache.cleanup_log(max_age_days).await
+    }
+
+    /// Wait until searches logged so far are written
+    pub async fn flush_query_log(&self) {
+        if let Some(log) = self.query_log.get() {
+            log.flush().await;
+        }
+    }
+
+    /// Write everything still buffered (search log, cache hit counts),
+    /// before shutting down
+    pub async fn flush(&self) -> Result<()> {
+        self.flush_query_log().await;
+        self.cache.flush().await
+    }
+}
+
+/// DRAKON guarded by a circuit breaker, rapidfuzz ranking the calls it fails
+fn guarded_drakon(drakon: DrakonRanker, breaker: BreakerConfig) -> Arc<dyn Ranker> {
+    Arc::new(ResilientRanker::new(Arc::new(drakon), Arc::new(RapidfuzzRanker::new()), breaker))
+}
+
+/// Whether two queries name the same entry of a series: the same numbers
+/// and roman numerals ("hades" is not "hades ii", "fifa 23" not "fifa 24"),
+/// which string similarity alone scores as near-identical
+fn same_series_numbers(a: &str, b: &str) -> bool {
+    fn numbers(query: &str) -> Vec<&str> {
+        let mut numbers: Vec<&str> = query
+            .split(|c: char| !c.is_ascii_digit())
+            .filter(|run| !run.is_empty())
+            .chain(query.split(|c: char| !c.is_alphanumeric()).filter(|word| ROMAN_NUMERALS.contains(word)))
+            .collect();
+        numbers.sort_unstable();
+        numbers
+    }
+    numbers(a) == numbers(b)
+}
+
+/// Keep each provider's `quota` best candidates by name pre-score (all of
+/// them when a single provider responded), with per-provider counts
+fn apply_provider_quota(
+    query: &str,
+    per_provider: &[(String, Vec<GameResult>)],
+    quota: Option<usize>,
+) -> (Vec<GameResult>, Vec<ProviderQuota>) {
+    let Some(quota) = quota else {
+        return (per_provider.iter().flat_map(|(_, r)| r.iter().cloned()).collect(), Vec::new());
+    };
+    let limit = if per_provider.len() > 1 { quota } else { usize::MAX };
+
+    let mut candidates = Vec::new();
+    let mut counts = Vec::new();
+    for (provider, results) in per_provider {
+        let mut kept: Vec<&GameResult> = results.iter().collect();
+        if kept.len() > limit {
+            // Stable sort: ties keep the provider's own order
+            kept.sort_by(|a, b| {
+                prescore_game(query, b)
+                    .partial_cmp(&prescore_game(query, a))
+                    .unwrap_or(std::cmp::Ordering::Equal)
+            });
+            kept.truncate(limit);
+        }
+
+        counts.push(ProviderQuota {
+            provider: provider.clone(),
+            returned: results.len(),
+            kept: kept.len(),
+        });
+        candidates.extend(kept.into_iter().cloned());
+    }
+
+    (candidates, counts)
+}
+
+/// Merge metadata of the same game found by other providers into the winner,
+/// recording contributing providers in `sources`
+fn enrich(winner: &mut GameResult, candidates: &[GameResult]) {
+    let mut sources = vec![winner.provider.clone()];
+    for candidate in candidates {
+        if candidate.provider == winner.provider || !winner.is_same_game(candidate) {
+            continue;
+        }
+        if winner.fill_missing_from(candidate) && !sources.contains(&candidate.provider) {
+            sources.push(candidate.provider.clone());
+        }
+    }
+    winner.sources = sources;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// Provider returning fixed results and counting calls
+    struct MockProvider {
+        name: &'static str,
+        results: Vec<GameResult>,
+        calls: AtomicUsize,
+    }
+
+    impl MockProvider {
+        fn new(names: &[&str]) -> Self {
+            Self::named("mock", names)
+        }
+
+        fn named(name: &'static str, names: &[&str]) -> Self {
+            Self::with_games(
+                name,
+                names
+                    .iter()
+                    .enumerate()
+                    .map(|(i, game)| GameResult::new(name, i.to_string(), *game))
+                    .collect(),
+            )
+        }
+
+        fn with_games(name: &'static str, results: Vec<GameResult>) -> Self {
+            Self {
+                name,
+                results,
+                calls: AtomicUsize::new(0),
+            }
+        }
+
+        fn calls(&self) -> usize {
+            self.calls.load(Ordering::SeqCst)
+        }
+    }
+
+    #[async_trait]
+    impl GameProvider for MockProvider {
+        async fn search(&self, _query: &str) -> Result<Vec<GameResult>> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            Ok(self.results.clone())
+        }
+
+        async fn get_by_id(&self, id: &str) -> Result<GameResult> {
+            self.results
+                .iter()
+                .find(|g| g.id == id)
+                .cloned()
+                .ok_or_else(|| GameEngineError::NoResults(id.to_string()))
+        }
+
+        fn name(&self) -> &str {
+            self.name
+        }
+
+        async fn is_available(&self) -> bool {
+            true
+        }
+    }
+
+    /// Provider sampling every response body into the schema monitor
+    struct SchemaProvider {
+        body: &'static str,
+        monitor: std::sync::OnceLock<Arc<SchemaMonitor>>,
+    }
+
+    #[async_trait]
+    impl GameProvider for SchemaProvider {
+        async fn search(&self, _query: &str) -> Result<Vec<GameResult>> {
+            if let Some(monitor) = self.monitor.get().filter(|m| m.should_sample()) {
+                monitor.observe(self.name(), &self.schema_fingerprint("search", self.body));
+            }
+            Ok(vec![GameResult::new("schema", "1", "Zelda")])
+        }
+
+        async fn get_by_id(&self, id: &str) -> Result<GameResult> {
+            Err(GameEngineError::NoResults(id.to_string()))
+        }
+
+        fn name(&self) -> &str {
+            "schema"
+        }
+
+        async fn is_available(&self) -> bool {
+            true
+        }
+
+        fn attach_schema_monitor(&self, monitor: Arc<SchemaMonitor>) {
+            let _ = self.monitor.set(monitor);
+        }
+    }
+
+    /// Ranker scoring every candidate 50 under a configurable name
+    struct StubRanker(&'static str);
+
+    #[async_trait]
+    impl Ranker for StubRanker {
+        async fn rank(&self, _query: &str, candidates: &[GameResult]) -> Result<Vec<crate::ranking::RankedCandidate>> {
+            Ok(candidates
+                .iter()
+                .map(|g| crate::ranking::RankedCandidate::new(g.clone(), 50.0))
+                .collect())
+        }
+
+        fn name(&self) -> &str {
+            self.0
+        }
+    }
+
+    /// Minimal keep-alive HTTP server standing in for DRAKON: 200 to
+    /// everything (`/health`), and `/v1/rank` scores candidates named like
+    /// the query 99, the others 40
+    async fn fake_drakon() -> String {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                tokio::spawn(async move {
+                    let mut buf = Vec::new();
+                    let mut chunk = [0u8; 1024];
+                    while let Ok(n) = socket.read(&mut chunk).await {
+                        if n == 0 {
+                            return;
+                        }
+                        buf.extend_from_slice(&chunk[..n]);
+                        while let Some(end) = buf.windows(4).position(|w| w == b"\r\n\r\n") {
+                            let head = String::from_utf8_lossy(&buf[..end]).to_lowercase();
+                            let length: usize = head
+                                .lines()
+                                .find_map(|line| line.strip_prefix("content-length:"))
+                                .and_then(|value| value.trim().parse().ok())
+                                .unwrap_or(0);
+                            if buf.len() < end + 4 + length {
+                                break;
+                            }
+                            let body: Vec<u8> = buf.drain(..end + 4 + length).skip(end + 4).collect();
+                            let reply = if head.starts_with("post /v1/rank") { fake_rank(&body) } else { String::new() };
+                            let response = format!(
+                                "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\n\r\n{}",
+                                reply.len(),
+                                reply
+                            );
+                            if socket.write_all(response.as_bytes()).await.is_err() {
+                                return;
+                            }
+                        }
+                    }
+                });
+            }
+        });
+        format!("http://{}", addr)
+    }
+
+    fn fake_rank(body: &[u8]) -> String {
+        let request: serde_json::Value = serde_json::from_slice(body).unwrap();
+        let query = request["query"].as_str().unwrap().to_lowercase();
+        let results: Vec<serde_json::Value> = request["candidates"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .enumerate()
+            .map(|(index, candidate)| {
+                let name = candidate.as_str().unwrap();
+                let score = if name.to_lowercase() == query { 99.0 } else { 40.0 };
+                serde_json::json!({ "candidate": name, "score": score, "index": index })
+            })
+            .collect();
+        serde_json::json!({ "results": results }).to_string()
+    }
+
+    async fn wait_for_ranker(engine: &GameEngine, name: &str) -> bool {
+        for _ in 0..100 {
+            if engine.ranker().name() == name {
+                return true;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+        }
+        false
+    }
+
+    fn query(q: &str) -> SearchQuery {
+        SearchQuery {
+            query: q.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_engine_creation() {
+        let result = GameEngine::new(":memory:").await;
+        assert!(result.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_negative_cache_skips_providers() {
+        let mut engine = GameEngine::new(":memory:").await.unwrap();
+        let provider = Arc::new(MockProvider::new(&[]));
+        engine.add_provider(provider.clone());
+
+        assert!(matches!(engine.search(query("kekw")).await, Err(GameEngineError::NoResults(_))));
+        assert_eq!(provider.calls(), 1);
+
+        // Tombstone hit: providers not queried again
+        assert!(matches!(engine.search(query("KEKW")).await, Err(GameEngineError::NoResults(_))));
+        assert_eq!(provider.calls(), 1);
+
+        let stats = engine.cache_stats().await.unwrap();
+        assert_eq!(stats.total_entries, 0);
+        assert_eq!(stats.no_result_entries, 1);
+
+        // Forced refresh ignores the tombstone
+        let refresh = SearchQuery { cache_mode: CacheMode::Bypass, ..query("kekw") };
+        assert!(engine.search(refresh).await.is_err());
+        assert_eq!(provider.calls(), 2);
+    }
+
+    /// Provider failing (outage, rate limit) until switched back on
+    struct FailingProvider {
+        failing: std::sync::atomic::AtomicBool,
+        calls: AtomicUsize,
+    }
+
+    impl FailingProvider {
+        fn calls(&self) -> usize {
+            self.calls.load(Ordering::SeqCst)
+        }
+    }
+
+    #[async_trait]
+    impl GameProvider for FailingProvider {
+        async fn search(&self, query: &str) -> Result<Vec<GameResult>> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            if self.failing.load(Ordering::SeqCst) {
+                return Err(GameEngineError::ProviderStatus { provider: "failing".to_string(), status: 429 });
+            }
+            Ok(vec![GameResult::new("failing", "1", query)])
+        }
+
+        async fn get_by_id(&self, id: &str) -> Result<GameResult> {
+            Err(GameEngineError::NoResults(id.to_string()))
+        }
+
+        fn name(&self) -> &str {
+            "failing"
+        }
+
+        async fn is_available(&self) -> bool {
+            true
+        }
+    }
+
+    #[tokio::test]
+    async fn test_negative_cache_skips_provider_errors() {
+        let mut engine = GameEngine::new(":memory:").await.unwrap();
+        let failing = Arc::new(FailingProvider { failing: true.into(), calls: AtomicUsize::new(0) });
+        let empty = Arc::new(MockProvider::new(&[]));
+        engine.add_provider(failing.clone());
+        engine.add_provider(empty.clone());
+
+        // One provider down, the other empty: no tombstone
+        assert!(matches!(engine.search(query("hades")).await, Err(GameEngineError::NoResults(_))));
+        assert_eq!(engine.cache_stats().await.unwrap().no_result_entries, 0);
+        assert!(matches!(engine.search(query("hades")).await, Err(GameEngineError::NoResults(_))));
+        assert_eq!((failing.calls(), empty.calls()), (2, 2));
+
+        // Back up: found on the next search
+        failing.failing.store(false, Ordering::SeqCst);
+        assert_eq!(engine.search(query("hades")).await.unwrap().game.name, "hades");
+        assert_eq!(failing.calls(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_negative_cache_disabled() {
+        let mut engine = GameEngine::new(":memory:").await.unwrap();
+        engine.set_options(SearchOptions { no_result_ttl: None, ..Default::default() });
+        let provider = Arc::new(MockProvider::new(&[]));
+        engine.add_provider(provider.clone());
+
+        assert!(engine.search(query("pog")).await.is_err());
+        assert!(engine.search(query("pog")).await.is_err());
+        assert_eq!(provider.calls(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_invalidate_drops_every_variant() {
+        let mut engine = GameEngine::new(":memory:").await.unwrap();
+        let provider = Arc::new(MockProvider::new(&["Hades", "Hades II"]));
+        engine.add_provider(provider.clone());
+
+        let french = SearchQuery { locale: Some("fr".to_string()), ..query("hades") };
+        engine.search(query("hades")).await.unwrap();
+        engine.search(french.clone()).await.unwrap();
+        engine.search(query("hades ii")).await.unwrap();
+        assert_eq!(provider.calls(), 3);
+
+        assert_eq!(engine.invalidate(" HADES ").await.unwrap(), 2);
+        assert_eq!(engine.invalidate("hades").await.unwrap(), 0);
+        assert!(engine.inspect_cache(&french).await.unwrap().is_none());
+        // Other queries untouched
+        assert!(engine.search(query("hades ii")).await.unwrap().from_cache);
+
+        assert!(!engine.search(query("hades")).await.unwrap().from_cache);
+        assert_eq!(provider.calls(), 4);
+
+        assert_eq!(engine.invalidate_matching("hades%").await.unwrap(), 2);
+        assert_eq!(engine.cache_stats().await.unwrap().total_entries, 0);
+    }
+
+    #[tokio::test]
+    async fn test_fuzzy_cache_lookup_serves_near_duplicates() {
+        let mut engine = GameEngine::new(":memory:").await.unwrap();
+        engine.set_options(SearchOptions { fuzzy_cache_min_score: Some(90.0), ..Default::default() });
+        let provider = Arc::new(MockProvider::new(&["Vampire Survivors", "Hades", "Hades II"]));
+        engine.add_provider(provider.clone());
+
+        engine.search(query("vampire survivors")).await.unwrap();
+        engine.search(query("hades")).await.unwrap();
+        assert_eq!(provider.calls(), 2);
+
+        for typo in ["vampire survivor", "Vampire Survivers", "vampire survivor"] {
+            let response = engine.search(query(typo)).await.unwrap();
+            assert!(response.from_cache, "{}", typo);
+            assert_eq!(response.game.name, "Vampire Survivors");
+        }
+        assert_eq!(provider.calls(), 2);
+        // Aliases kept apart: the next lookup is exact, stats count games only
+        assert_eq!(engine.cache.alias("vampire survivor").await.unwrap().as_deref(), Some("vampire survivors"));
+        let stats = engine.cache_stats().await.unwrap();
+        assert_eq!((stats.total_entries, stats.aliases, stats.total_hits), (2, 2, 3));
+
+        // Never across sequels or cache variants
+        assert!(!engine.search(query("hades ii")).await.unwrap().from_cache);
+        let french = SearchQuery { locale: Some("fr".to_string()), ..query("vampire survivor") };
+        assert!(!engine.search(french).await.unwrap().from_cache);
+        assert_eq!(provider.calls(), 4);
+
+        // Invalidating an entry drops the aliases to it
+        engine.invalidate("vampire survivors").await.unwrap();
+        assert_eq!(engine.cache_stats().await.unwrap().aliases, 0);
+        assert!(!engine.search(query("vampire survivor")).await.unwrap().from_cache);
+    }
+
+    #[test]
+    fn test_same_series_numbers() {
+        assert!(same_series_numbers("vampire survivor", "vampire survivors"));
+        assert!(same_series_numbers("fifa 23 ultimate", "fifa ultimate 23"));
+        assert!(!same_series_numbers("hades", "hades ii"));
+        assert!(!same_series_numbers("fifa 23", "fifa 24"));
+        assert!(!same_series_numbers("witcher3", "witcher"));
+        // Roman numerals only as whole words
+        assert!(same_series_numbers("civilization", "civilisation"));
+    }
+
+    #[tokio::test]
+    async fn test_cache_min_score_skips_low_scores() {
+        let mut engine = GameEngine::new(":memory:").await.unwrap();
+        engine.set_options(SearchOptions { cache_min_score: 101.0, ..Default::default() });
+        engine.add_provider(Arc::new(MockProvider::new(&["Zelda"])));
+
+        engine.search(query("zelda")).await.unwrap();
+        assert_eq!(engine.cache_stats().await.unwrap().total_entries, 0);
+    }
+
+    #[test]
+    fn test_classify_thresholds() {
+        let options = SearchOptions::default();
+        assert_eq!(options.classify(100.0), SearchResultType::Exact);
+        assert_eq!(options.classify(95.0), SearchResultType::Exact);
+        assert_eq!(options.classify(94.9), SearchResultType::Fuzzy);
+        assert_eq!(options.classify(70.0), SearchResultType::Fuzzy);
+        assert_eq!(options.classify(69.9), SearchResultType::Fallback);
+        assert_eq!(options.classify(40.0), SearchResultType::Fallback);
+        assert_eq!(options.classify(39.9), SearchResultType::NotFound);
+
+        let strict = SearchOptions { exact_score: 99.0, min_score: 90.0, not_found_score: 80.0, ..Default::default() };
+        assert_eq!(strict.classify(95.0), SearchResultType::Fuzzy);
+        assert_eq!(strict.classify(75.0), SearchResultType::NotFound);
+    }
+
+    #[tokio::test]
+    async fn test_low_score_returns_not_found_with_best_guess() {
+        let mut engine = GameEngine::new(":memory:").await.unwrap();
+        engine.set_ranker(Arc::new(StubRanker("stub")));
+        engine.set_options(SearchOptions { not_found_score: 60.0, cache_low_confidence: true, ..Default::default() });
+        engine.add_provider(Arc::new(MockProvider::new(&["Zelda", "Zelda II"])));
+
+        // The stub scores everything 50
+        let response = engine.search(query("zelad")).await.unwrap();
+        assert_eq!(response.result_type, SearchResultType::NotFound);
+        assert_eq!(response.game.name, "Zelda");
+        assert_eq!(response.alternatives.len(), 1);
+        // Not cached, even with cache_low_confidence
+        assert_eq!(engine.cache_stats().await.unwrap().total_entries, 0);
+
+        engine.set_options(SearchOptions::default());
+        let response = engine.search(query("zelad")).await.unwrap();
+        assert_eq!(response.result_type, SearchResultType::Fallback);
+    }
+
+    #[tokio::test]
+    async fn test_cache_hit_reports_original_score() {
+        let mut engine = GameEngine::new(":memory:").await.unwrap();
+        engine.add_provider(Arc::new(MockProvider::new(&["Zelda", "Zelda II"])));
+
+        let fresh = engine.search(query("zelda")).await.unwrap();
+        // Still the ranker that scored it after a switch
+        engine.set_ranker(Arc::new(StubRanker("stub")));
+        let cached = engine.search(query("zelda")).await.unwrap();
+        assert!(cached.from_cache);
+        assert_eq!(cached.score, fresh.score);
+        assert_eq!(cached.ranking_method, fresh.ranking_method);
+        assert_ne!(cached.ranking_method, "stub");
+        assert_eq!(cached.result_type, SearchResultType::CacheHit);
+        assert_eq!(engine.inspect_cache(&query("zelda")).await.unwrap().unwrap().ranking_method, Some(fresh.ranking_method));
+
+        // Entries saved without a ranker
+        engine.cache.save("celeste", &GameResult::new("mock", "1", "Celeste"), &[]).await.unwrap();
+        let legacy = engine.search(query("celeste")).await.unwrap();
+        assert_eq!((legacy.score, legacy.ranking_method.as_str()), (100.0, "cache"));
+    }
+
+    #[tokio::test]
+    async fn test_acronyms_expanded_before_ranking() {
+        let mut engine = GameEngine::new(":memory:").await.unwrap();
+        engine.set_ranker(Arc::new(RapidfuzzRanker::new()));
+        engine.add_provider(Arc::new(MockProvider::new(&["Gta Racing", "Grand Theft Auto V", "Honkai: Star Rail"])));
+
+        let response = engine.search(SearchQuery { explain: true, ..query("gta 5") }).await.unwrap();
+        assert_eq!(response.game.name, "Grand Theft Auto V");
+        let breakdown = response.score_breakdown.unwrap();
+        assert_eq!(breakdown.expanded_query.as_deref(), Some("Grand Theft Auto 5"));
+        assert_eq!(breakdown.normalized_query.as_deref(), Some("grand theft auto 5"));
+
+        // Per-engine acronyms
+        assert_eq!(engine.expand_acronyms("hsr"), None);
+        engine.add_alias("hsr", "Honkai: Star Rail");
+        let response = engine.search(query("hsr")).await.unwrap();
+        assert_eq!(response.game.name, "Honkai: Star Rail");
+    }
+
+    #[tokio::test]
+    async fn test_score_breakdown_copied_from_ranker() {
+        let mut engine = GameEngine::new(":memory:").await.unwrap();
+        engine.set_ranker(Arc::new(RapidfuzzRanker::new()));
+        engine.add_provider(Arc::new(MockProvider::new(&["Zelda", "Zelda II"])));
+
+        let fresh = engine.search(query("zeld")).await.unwrap();
+        let breakdown = fresh.score_breakdown.unwrap();
+        assert_eq!(breakdown.ranker, "rapidfuzz");
+        assert_eq!(breakdown.candidates, 2);
+        assert_eq!(breakdown.score(), fresh.score);
+        assert_eq!(breakdown.normalized_query, None);
+
+        // Cache hits were not ranked
+        assert!(engine.search(query("zeld")).await.unwrap().score_breakdown.is_none());
+
+        let explained = SearchQuery { cache_mode: CacheMode::Bypass, explain: true, ..query("ZELD") };
+        let breakdown = engine.search(explained).await.unwrap().score_breakdown.unwrap();
+        assert_eq!(breakdown.normalized_query.as_deref(), Some("zeld"));
+        assert_eq!(breakdown.normalized_candidate.as_deref(), Some("zelda"));
+    }
+
+    #[tokio::test]
+    async fn test_cache_low_confidence_served_as_fallback() {
+        let mut engine = GameEngine::new(":memory:").await.unwrap();
+        engine.set_options(SearchOptions {
+            cache_min_score: 101.0,
+            cache_low_confidence: true,
+            ..Default::default()
+        });
+        let provider = Arc::new(MockProvider::new(&["Zelda"]));
+        engine.add_provider(provider.clone());
+
+        let fresh = engine.search(query("zelda")).await.unwrap();
+        let cached = engine.search(query("zelda")).await.unwrap();
+        assert_eq!(provider.calls(), 1);
+        assert!(cached.from_cache);
+        assert_eq!(cached.score, fresh.score);
+        assert_eq!(cached.result_type, SearchResultType::Fallback);
+    }
+
+    #[tokio::test]
+    async fn test_set_ranker_applies_to_next_search() {
+        let mut engine = GameEngine::new(":memory:").await.unwrap();
+        engine.add_provider(Arc::new(MockProvider::new(&["Zelda"])));
+
+        engine.set_ranker(Arc::new(StubRanker("stub")));
+        let response = engine.search(SearchQuery { cache_mode: CacheMode::Off, ..query("zeld") }).await.unwrap();
+        assert_eq!(response.ranking_method, "stub");
+        assert_eq!(response.score, 50.0);
+    }
+
+    #[tokio::test]
+    async fn test_popularity_prior_off_by_default() {
+        let mut engine = GameEngine::new(":memory:").await.unwrap();
+        engine.set_ranker(Arc::new(StubRanker("stub")));
+        let mut classic = GameResult::new("mock", "1", "DOOM Classic");
+        classic.popularity = Some(158_243.0);
+        let games = vec![classic, GameResult::new("mock", "2", "Doom Clone")];
+        engine.add_provider(Arc::new(MockProvider::with_games("mock", games)));
+        let uncached = || SearchQuery { cache_mode: CacheMode::Off, ..query("doom game") };
+
+        // Tied: the shorter name wins
+        assert_eq!(engine.search(uncached()).await.unwrap().game.name, "Doom Clone");
+
+        engine.set_options(SearchOptions { popularity_weight: Some(crate::ranking::DEFAULT_POPULARITY_WEIGHT), ..Default::default() });
+        let response = engine.search(uncached()).await.unwrap();
+        assert_eq!((response.game.name.as_str(), response.score), ("DOOM Classic", 52.5));
+    }
+
+    #[tokio::test]
+    async fn test_editions_collapsed_under_canonical_game() {
+        /// Scores candidates in provider order, 90 then 5 less each
+        struct InOrder;
+
+        #[async_trait]
+        impl Ranker for InOrder {
+            async fn rank(&self, _query: &str, candidates: &[GameResult]) -> Result<Vec<crate::ranking::RankedCandidate>> {
+                Ok(candidates
+                    .iter()
+                    .enumerate()
+                    .map(|(i, g)| crate::ranking::RankedCandidate::new(g.clone(), 90.0 - 5.0 * i as f64))
+                    .collect())
+            }
+
+            fn name(&self) -> &str {
+                "in-order"
+            }
+        }
+
+        let mut engine = GameEngine::new(":memory:").await.unwrap();
+        engine.set_ranker(Arc::new(InOrder));
+        engine.add_provider(Arc::new(MockProvider::new(&[
+            "The Witcher 3: Wild Hunt - Game of the Year Edition",
+            "The Witcher 3: Wild Hunt",
+            "The Witcher 3: Wild Hunt – Complete Edition",
+            "The Witcher 2: Assassins of Kings Enhanced Edition",
+            "The Witcher 3: Wild Hunt Gold Edition",
+        ])));
+        let witcher = || SearchQuery { max_results: 2, ..query("witcher 3") };
+
+        // Off by default: editions are alternatives like any other game
+        let plain = engine.search(SearchQuery { cache_mode: CacheMode::Off, ..witcher() }).await.unwrap();
+        assert_eq!(plain.game.name, "The Witcher 3: Wild Hunt - Game of the Year Edition");
+        assert!(plain.editions.is_empty());
+
+        engine.set_options(SearchOptions { collapse_editions: true, ..Default::default() });
+        for from_cache in [false, true] {
+            let response = engine.search(witcher()).await.unwrap();
+            assert_eq!(response.from_cache, from_cache);
+            assert_eq!(response.game.name, "The Witcher 3: Wild Hunt");
+            assert_eq!(
+                response.editions.iter().map(|e| e.name.as_str()).collect::<Vec<_>>(),
+                vec![
+                    "The Witcher 3: Wild Hunt - Game of the Year Edition",
+                    "The Witcher 3: Wild Hunt – Complete Edition",
+                    "The Witcher 3: Wild Hunt Gold Edition",
+                ]
+            );
+            assert_eq!(response.alternatives.len(), 1);
+            assert_eq!(response.alternatives[0].name, "The Witcher 2: Assassins of Kings Enhanced Edition");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_exact_match_skips_ranker() {
+        let mut engine = GameEngine::new(":memory:").await.unwrap();
+        engine.set_ranker(Arc::new(StubRanker("stub")));
+        engine.add_provider(Arc::new(MockProvider::new(&["Elden Ring Nightreign", "ELDEN RING™"])));
+        let uncached = |q| SearchQuery { cache_mode: CacheMode::Off, ..query(q) };
+
+        for q in ["elden ring", "Elden  Ring®"] {
+            let response = engine.search(uncached(q)).await.unwrap();
+            assert_eq!((response.game.name.as_str(), response.score), ("ELDEN RING™", 100.0), "{}", q);
+            assert_eq!(response.result_type, SearchResultType::Exact);
+            assert_eq!(response.ranking_method, "exact");
+            assert_eq!(response.score_breakdown, None);
+            assert_eq!(response.alternatives[0].name, "Elden Ring Nightreign");
+            assert_eq!(response.alternatives[0].score, None);
+        }
+        let explained = engine.search(SearchQuery { explain: true, ..uncached("elden ring") }).await.unwrap();
+        assert_eq!(explained.score_breakdown.unwrap().candidates, 2);
+
+        // No exact match: ranked as usual
+        assert_eq!(engine.search(uncached("elden")).await.unwrap().ranking_method, "stub");
+    }
+
+    #[tokio::test]
+    async fn test_exact_duplicates_ranked_among_themselves() {
+        let mut engine = GameEngine::new(":memory:").await.unwrap();
+        engine.set_ranker(Arc::new(StubRanker("stub")));
+        engine.add_provider(Arc::new(MockProvider::new(&["Elden Ring Nightreign", "Elden Ring", "ELDEN RING™"])));
+
+        let response = engine.search(SearchQuery { cache_mode: CacheMode::Off, ..query("elden ring") }).await.unwrap();
+        assert_eq!((response.ranking_method.as_str(), response.score), ("stub", 50.0));
+        assert_eq!(response.score_breakdown, None);
+        let alternatives: Vec<(&str, Option<f64>)> =
+            response.alternatives.iter().map(|alt| (alt.name.as_str(), alt.score)).collect();
+        assert_eq!(alternatives.len(), 2);
+        assert_eq!(alternatives[1], ("Elden Ring Nightreign", None));
+        assert_eq!(alternatives[0].1, Some(50.0));
+    }
+
+    #[tokio::test]
+    async fn test_exact_match_on_alias() {
+        let mut engine = GameEngine::new(":memory:").await.unwrap();
+        engine.set_ranker(Arc::new(StubRanker("stub")));
+        let mut localized = GameResult::new("mock", "1", "Biohazard 4");
+        localized.aliases = vec!["Resident Evil 4".to_string()];
+        let games = vec![GameResult::new("mock", "2", "Resident Evil Village"), localized];
+        engine.add_provider(Arc::new(MockProvider::with_games("mock", games)));
+
+        let response = engine.search(SearchQuery { explain: true, ..query("resident evil 4") }).await.unwrap();
+        assert_eq!((response.game.name.as_str(), response.score), ("Biohazard 4", 100.0));
+        assert_eq!(response.ranking_method, "exact");
+        assert_eq!(response.score_breakdown.unwrap().matched_alias.as_deref(), Some("Resident Evil 4"));
+    }
+
+    #[tokio::test]
+    async fn test_disabled_provider_is_skipped() {
+        let mut engine = GameEngine::new(":memory:").await.unwrap();
+        let provider = Arc::new(MockProvider::new(&["Zelda"]));
+        engine.add_provider(provider.clone());
+        let uncached = || SearchQuery { cache_mode: CacheMode::Off, ..query("zelda") };
+
+        assert!(engine.set_provider_enabled("mock", false));
+        assert_eq!(engine.provider_status(), vec![("mock".to_string(), false)]);
+        assert!(matches!(engine.search(uncached()).await, Err(GameEngineError::NoResults(_))));
+        assert_eq!(provider.calls(), 0);
+
+        assert!(engine.set_provider_enabled("mock", true));
+        assert!(engine.search(uncached()).await.is_ok());
+        assert_eq!(provider.calls(), 1);
+
+        assert!(!engine.set_provider_enabled("unknown", false));
+    }
+
+    #[tokio::test]
+    async fn test_provider_restriction() {
+        let mut engine = GameEngine::new(":memory:").await.unwrap();
+        let steam = Arc::new(MockProvider::named("steam", &["Zelda"]));
+        let igdb = Arc::new(MockProvider::named("igdb", &["Zelda"]));
+        engine.add_provider(steam.clone());
+        engine.add_provider(igdb.clone());
+        let only = |names: &[&str]| SearchQuery {
+            providers: Some(names.iter().map(|n| n.to_string()).collect()),
+            ..query("zelda")
+        };
+
+        let response = engine.search(only(&["igdb"])).await.unwrap();
+        assert_eq!(response.game.provider, "igdb");
+        assert_eq!((steam.calls(), igdb.calls()), (0, 1));
+
+        // Restricted searches don't share cache entries with each other
+        assert!(!engine.search(only(&["steam"])).await.unwrap().from_cache);
+        assert!(engine.search(only(&["igdb"])).await.unwrap().from_cache);
+        assert!(!engine.search(query("zelda")).await.unwrap().from_cache);
+        assert_eq!((steam.calls(), igdb.calls()), (2, 2));
+
+        let unknown = engine.search(only(&["gog"])).await.unwrap_err();
+        assert!(matches!(unknown.root(), GameEngineError::Provider { provider, .. } if provider == "gog"));
+    }
+
+    #[tokio::test]
+    async fn test_enrich_merges_secondary_providers() {
+        let mut engine = GameEngine::new(":memory:").await.unwrap();
+        engine.set_ranker(Arc::new(StubRanker("stub")));
+        engine.set_options(SearchOptions { enrich: true, ..Default::default() });
+
+        let mut steam_hades = GameResult::new("steam", "1145360", "Hades");
+        steam_hades.genres = vec!["Action".to_string()];
+        let mut igdb_hades = GameResult::new("igdb", "113112", "hades");
+        igdb_hades.genres = vec!["Roguelike".to_string()];
+        igdb_hades.rating = Some(9.3);
+        igdb_hades.header_image = "cover.jpg".to_string();
+        let rawg_hades = GameResult::new("rawg", "1", "Hades");
+
+        engine.add_provider(Arc::new(MockProvider::with_games("steam", vec![steam_hades])));
+        engine.add_provider(Arc::new(MockProvider::with_games(
+            "igdb",
+            vec![igdb_hades, GameResult::new("igdb", "2", "Hades II")],
+        )));
+        engine.add_provider(Arc::new(MockProvider::with_games("rawg", vec![rawg_hades])));
+
+        let response = engine.search(query("hades")).await.unwrap();
+        let game = &response.game;
+        assert_eq!(game.provider, "steam");
+        assert_eq!(game.genres, vec!["Action".to_string()]);
+        assert_eq!(game.rating, Some(9.3));
+        assert_eq!(game.header_image, "cover.jpg");
+        // rawg matched but had nothing to add
+        assert_eq!(game.sources, vec!["steam".to_string(), "igdb".to_string()]);
+        assert_eq!(response.alternatives.iter().map(|g| g.name.as_str()).collect::<Vec<_>>(), vec!["Hades II"]);
+
+        engine.set_options(SearchOptions::default());
+        let plain = engine.search(SearchQuery { cache_mode: CacheMode::Off, ..query("hades") }).await.unwrap();
+        assert!(plain.game.sources.is_empty());
+        assert_eq!(plain.game.rating, None);
+        assert_eq!(plain.alternatives.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_ties_broken_by_year() {
+        let mut engine = GameEngine::new(":memory:").await.unwrap();
+        engine.set_ranker(Arc::new(StubRanker("stub")));
+        engine.set_options(SearchOptions { cache_min_score: 0.0, ..Default::default() });
+
+        let mut original = GameResult::new("steam", "2050", "Resident Evil 4");
+        original.year = Some(2005);
+        let mut remake = GameResult::new("steam", "2050650", "Resident Evil 4");
+        remake.year = Some(2023);
+        engine.add_provider(Arc::new(MockProvider::with_games("steam", vec![original, remake])));
+
+        assert_eq!(engine.search(query("resident evil 4")).await.unwrap().game.year, Some(2023));
+        let dated = engine.search(query("resident evil 4 2005")).await.unwrap();
+        assert_eq!((dated.game.year, dated.alternatives[0].year), (Some(2005), Some(2023)));
+    }
+
+    #[tokio::test]
+    async fn test_exclude_unreleased_filters_candidates() {
+        use crate::core::ReleaseStatus;
+
+        let mut engine = GameEngine::new(":memory:").await.unwrap();
+        engine.set_ranker(Arc::new(StubRanker("stub")));
+        // Every candidate ties at 50: keep the providers' order
+        engine.set_options(SearchOptions { cache_min_score: 0.0, tie_epsilon: 0.0, ..Default::default() });
+
+        let mut sequel = GameResult::new("steam", "2", "Hades III");
+        sequel.release_status = Some(ReleaseStatus::ComingSoon { date: None });
+        let mut early = GameResult::new("steam", "1", "Hades II");
+        early.release_status = Some(ReleaseStatus::EarlyAccess);
+        engine.add_provider(Arc::new(MockProvider::with_games("steam", vec![sequel.clone(), early])));
+
+        let all = engine.search(query("hades")).await.unwrap();
+        assert_eq!(all.game.name, "Hades III");
+
+        let released = SearchQuery { exclude_unreleased: true, ..query("hades") };
+        let response = engine.search(released.clone()).await.unwrap();
+        assert_eq!(response.game.name, "Hades II");
+        assert!(!response.from_cache);
+        assert!(response.alternatives.is_empty());
+        // Cached apart from the unfiltered search
+        assert!(engine.search(released).await.unwrap().from_cache);
+
+        let mut engine = GameEngine::new(":memory:").await.unwrap();
+        engine.add_provider(Arc::new(MockProvider::with_games("steam", vec![sequel])));
+        let err = engine
+            .search(SearchQuery { exclude_unreleased: true, ..query("hades") })
+            .await
+            .unwrap_err();
+        assert!(matches!(err.root(), GameEngineError::NoResults(_)));
+    }
+
+    fn on(platforms: &[&str], name: &str) -> GameResult {
+        let mut game = GameResult::new("steam", name, name);
+        game.platforms = platforms.iter().map(|p| p.to_string()).collect();
+        game
+    }
+
+    fn on_platforms(q: &str, platforms: &[&str]) -> SearchQuery {
+        SearchQuery {
+            platforms: Some(platforms.iter().map(|p| p.to_string()).collect()),
+            ..query(q)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_platform_filter_strict_and_lenient() {
+        let mut engine = GameEngine::new(":memory:").await.unwrap();
+        engine.set_ranker(Arc::new(StubRanker("stub")));
+        engine.set_options(SearchOptions { cache_min_score: 0.0, ..Default::default() });
+        let games = vec![on(&["Windows", "Mac"], "Hades"), on(&["Nintendo Switch"], "Hades (Switch)")];
+        engine.add_provider(Arc::new(MockProvider::with_games("steam", games)));
+
+        let response = engine.search(on_platforms("hades", &["switch"])).await.unwrap();
+        assert_eq!(response.game.name, "Hades (Switch)");
+        assert!(response.alternatives.is_empty());
+        assert!(!response.platform_filter_ignored);
+        let response = engine.search(on_platforms("hades", &["PC", "Nintendo Switch"])).await.unwrap();
+        assert_eq!(response.alternatives.len(), 1);
+
+        // Strict: best unfiltered candidate as a "did you mean", never cached
+        let response = engine.search(on_platforms("hades", &["ps5"])).await.unwrap();
+        assert_eq!(response.result_type, SearchResultType::NotFound);
+        assert_eq!(response.game.name, "Hades");
+        assert!(!response.platform_filter_ignored);
+        assert!(!engine.search(on_platforms("hades", &["PlayStation 5"])).await.unwrap().from_cache);
+
+        // Lenient: the filter is dropped, and the response says so
+        engine.set_options(SearchOptions {
+            cache_min_score: 0.0,
+            platform_filter: PlatformFilterMode::Lenient,
+            ..Default::default()
+        });
+        let response = engine.search(on_platforms("hades", &["ps5"])).await.unwrap();
+        // Named exactly like the query, so not left to the stub's 50
+        assert_eq!(response.result_type, SearchResultType::Exact);
+        assert_eq!(response.game.name, "Hades");
+        assert_eq!(response.alternatives.len(), 1);
+        assert!(response.platform_filter_ignored);
+        let cached = engine.search(on_platforms("hades", &["ps5"])).await.unwrap();
+        assert!(cached.from_cache);
+        assert!(cached.platform_filter_ignored);
+    }
+
+    #[tokio::test]
+    async fn test_cached_winner_off_platform() {
+        let mut engine = GameEngine::new(":memory:").await.unwrap();
+        engine.set_ranker(Arc::new(StubRanker("stub")));
+        engine.set_options(SearchOptions {
+            cache_min_score: 0.0,
+            platform_filter: PlatformFilterMode::Lenient,
+            ..Default::default()
+        });
+        engine.add_provider(Arc::new(MockProvider::with_games("steam", vec![on(&["Windows"], "Celeste")])));
+
+        // Saved by a lenient search although not on PlayStation
+        assert!(engine.search(on_platforms("celeste", &["ps4"])).await.unwrap().platform_filter_ignored);
+
+        // Strict searches don't serve it, and don't overwrite it either
+        engine.set_options(SearchOptions { cache_min_score: 0.0, ..Default::default() });
+        let response = engine.search(on_platforms("celeste", &["playstation"])).await.unwrap();
+        assert!(!response.from_cache);
+        assert_eq!(response.result_type, SearchResultType::NotFound);
+        let entry = engine.inspect_cache(&on_platforms("celeste", &["ps5"])).await.unwrap().unwrap();
+        assert_eq!(entry.game.name, "Celeste");
+
+        // Entries on the requested platform are served as usual
+        assert!(!engine.search(on_platforms("celeste", &["windows"])).await.unwrap().from_cache);
+        assert!(engine.search(on_platforms("celeste", &["pc"])).await.unwrap().from_cache);
+    }
+
+    /// Provider whose results can be switched off (expired API key...)
+    struct SwitchProvider {
+        empty: std::sync::atomic::AtomicBool,
+    }
+
+    #[async_trait]
+    impl GameProvider for SwitchProvider {
+        async fn search(&self, query: &str) -> Result<Vec<GameResult>> {
+            if self.empty.load(Ordering::SeqCst) {
+                return Ok(Vec::new());
+            }
+            Ok(vec![GameResult::new("switch", "1", query)])
+        }
+
+        async fn get_by_id(&self, id: &str) -> Result<GameResult> {
+            Err(GameEngineError::NoResults(id.to_string()))
+        }
+
+        fn name(&self) -> &str {
+            "switch"
+        }
+
+        async fn is_available(&self) -> bool {
+            true
+        }
+    }
+
+    #[tokio::test]
+    async fn test_cache_ttl_refresh_with_mock_clock() {
+        use crate::clock::MockClock;
+        use crate::core::ReleaseStatus;
+        use chrono::TimeZone;
+
+        let clock = Arc::new(MockClock::new(chrono::Utc.with_ymd_and_hms(2026, 6, 1, 12, 0, 0).unwrap()));
+        let mut engine = GameEngine::new(":memory:").await.unwrap();
+        engine.set_ranker(Arc::new(RapidfuzzRanker::new()));
+        engine.set_clock(clock.clone());
+
+        let mut zelda = GameResult::new("steam", "1", "Zelda");
+        zelda.year = Some(2026);
+        zelda.release_status = Some(ReleaseStatus::Released);
+        let provider = Arc::new(MockProvider::with_games("steam", vec![zelda]));
+        engine.add_provider(provider.clone());
+
+        // New game: minimum TTL
+        engine.search(query("zelda")).await.unwrap();
+        let ttl = engine.inspect_cache(&query("zelda")).await.unwrap().unwrap().ttl.unwrap();
+        assert_eq!(ttl.ttl_secs, 86_400);
+        assert_eq!(ttl.reason, "released 2026 (0y old): base 1d");
+
+        clock.advance(Duration::hours(23));
+        assert!(engine.search(query("zelda")).await.unwrap().from_cache);
+        assert_eq!(provider.calls(), 1);
+
+        // Past the boundary: refreshed, nothing changed so the TTL doubles
+        clock.advance(Duration::hours(1));
+        assert!(!engine.search(query("zelda")).await.unwrap().from_cache);
+        assert_eq!(provider.calls(), 2);
+        let ttl = engine.inspect_cache(&query("zelda")).await.unwrap().unwrap().ttl.unwrap();
+        assert_eq!((ttl.ttl_secs, ttl.unchanged_refreshes), (2 * 86_400, 1));
+        assert_eq!(ttl.refresh_after, clock.now() + Duration::days(2));
+
+        clock.advance(Duration::hours(47));
+        assert!(engine.search(query("zelda")).await.unwrap().from_cache);
+        assert_eq!(provider.calls(), 2);
+
+        // Expired entry is still served when the refresh finds nothing
+        let mut engine = GameEngine::new(":memory:").await.unwrap();
+        engine.set_ranker(Arc::new(RapidfuzzRanker::new()));
+        engine.set_clock(clock.clone());
+        let switch = Arc::new(SwitchProvider { empty: Default::default() });
+        engine.add_provider(switch.clone());
+
+        engine.search(query("zelda")).await.unwrap();
+        clock.advance(Duration::days(30));
+        switch.empty.store(true, Ordering::SeqCst);
+        let response = engine.search(query("zelda")).await.unwrap();
+        assert!(response.from_cache);
+        assert_eq!(response.game.name, "zelda");
+    }
+
+    /// Games with a 20 KB description each, as merged results can get
+    fn bulky_games(names: &[&str]) -> Vec<GameResult> {
+        names
+            .iter()
+            .enumerate()
+            .map(|(i, name)| {
+                let mut game = GameResult::new("mock", i.to_string(), *name);
+                game.description = "lorem ipsum ".repeat(20 * 1024 / 12);
+                game.tags = vec!["Roguelike".to_string(); 50];
+                game.header_image = format!("https://cdn/{}.jpg", i);
+                game
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_cached_alternatives_are_slim() {
+        let games = bulky_games(&["Hades", "Hades II", "Hades III", "Hadestown", "Hades Star"]);
+        let mut engine = GameEngine::new(":memory:").await.unwrap();
+        engine.add_provider(Arc::new(MockProvider::with_games("mock", games.clone())));
+
+        let response = engine.search(query("hades")).await.unwrap();
+        assert_eq!(response.alternatives.len(), 4);
+        // The exact match skipped ranking, the alternatives were not scored
+        assert!(response.alternatives.iter().all(|alt| alt.score.is_none()));
+
+        let cached = engine.inspect_cache(&query("hades")).await.unwrap().unwrap();
+        let full: Vec<&GameResult> = games.iter().filter(|g| g.name != cached.game.name).collect();
+        let full_size = serde_json::to_vec(&cached.game).unwrap().len() + serde_json::to_vec(&full).unwrap().len();
+        let slim_size = crate::cache::row_size(&cached.game, &cached.alternatives);
+        assert!(full_size > 100 * 1024, "{}", full_size);
+        assert!(serde_json::to_vec(&cached.alternatives).unwrap().len() < 1024);
+        assert!(slim_size < full_size / 4, "{} vs {}", slim_size, full_size);
+
+        // Cached alternatives come back compact too
+        let hit = engine.search(query("hades")).await.unwrap();
+        assert!(hit.from_cache);
+        assert_eq!(hit.alternatives, response.alternatives);
+    }
+
+    #[tokio::test]
+    async fn test_cache_row_size_limit() {
+        let games = bulky_games(&["Hades", "Hades II", "Hades III"]);
+        let mut engine = GameEngine::new(":memory:").await.unwrap();
+        engine.add_provider(Arc::new(MockProvider::with_games("mock", games)));
+
+        // Room for the winner and a single alternative
+        let winner = engine.search(SearchQuery { cache_mode: CacheMode::Off, ..query("hades") }).await.unwrap();
+        let one_alt = crate::cache::row_size(&winner.game, &winner.alternatives[..1]);
+        engine.set_options(SearchOptions { cache_max_row_bytes: Some(one_alt + 10), ..Default::default() });
+        let response = engine.search(query("hades")).await.unwrap();
+        assert_eq!(response.alternatives.len(), 2);
+        let cached = engine.inspect_cache(&query("hades")).await.unwrap().unwrap();
+        assert_eq!(cached.alternatives, response.alternatives[..1]);
+        assert_eq!(engine.cache_stats().await.unwrap().oversized_rows, 0);
+
+        // The winner alone does not fit: not cached, counted
+        engine.set_options(SearchOptions { cache_max_row_bytes: Some(1024), ..Default::default() });
+        engine.search(query("hades 2")).await.unwrap();
+        assert!(engine.inspect_cache(&query("hades 2")).await.unwrap().is_none());
+        assert_eq!(engine.cache_stats().await.unwrap().oversized_rows, 1);
+    }
+
+    #[tokio::test]
+    async fn test_rehydrate_alternative() {
+        let games = bulky_games(&["Hades", "Hades II"]);
+        let mut engine = GameEngine::new(":memory:").await.unwrap();
+        engine.add_provider(Arc::new(MockProvider::with_games("mock", games.clone())));
+
+        engine.search(query("hades")).await.unwrap();
+        let hit = engine.search(query("hades")).await.unwrap();
+        assert!(hit.from_cache);
+
+        let chosen = &hit.alternatives[0];
+        let full = engine.rehydrate(chosen).await.unwrap();
+        assert_eq!(&full, games.iter().find(|g| g.id == chosen.id).unwrap());
+
+        let unknown = SlimGameResult { provider: "gog".to_string(), ..chosen.clone() };
+        assert!(matches!(engine.rehydrate(&unknown).await.unwrap_err().root(), GameEngineError::Provider { .. }));
+    }
+
+    #[tokio::test]
+    async fn test_cache_max_age_and_stale_fallback() {
+        let mut engine = GameEngine::new(":memory:").await.unwrap();
+        let switch = Arc::new(SwitchProvider { empty: Default::default() });
+        engine.add_provider(switch.clone());
+        engine.search(query("zelda")).await.unwrap();
+        assert!(engine.search(query("zelda")).await.unwrap().from_cache);
+
+        // Every entry past the max age: refetched, entry kept
+        engine.set_options(SearchOptions { cache_max_age: Some(Duration::zero()), ..Default::default() });
+        assert!(!engine.search(query("zelda")).await.unwrap().from_cache);
+        assert!(engine.inspect_cache(&query("zelda")).await.unwrap().is_some());
+
+        // Providers down: the expired copy is served...
+        switch.empty.store(true, Ordering::SeqCst);
+        let response = engine.search(query("zelda")).await.unwrap();
+        assert!(response.from_cache);
+        assert_eq!(response.game.name, "zelda");
+
+        // ...unless disabled
+        engine.set_options(SearchOptions {
+            cache_max_age: Some(Duration::zero()),
+            serve_stale_on_error: false,
+            ..Default::default()
+        });
+        let err = engine.search(query("zelda")).await.unwrap_err();
+        assert!(matches!(err.root(), GameEngineError::NoResults(_)));
+
+        // No limit: served as before
+        engine.set_options(SearchOptions { cache_max_age: None, ..Default::default() });
+        assert!(engine.search(query("zelda")).await.unwrap().from_cache);
+    }
+
+    #[tokio::test]
+    async fn test_guardrails_flag_silent_provider() {
+        let mut engine = GameEngine::new(":memory:").await.unwrap();
+        engine.set_guardrail_config(GuardrailConfig {
+            sustain: chrono::Duration::zero(),
+            min_samples: 5,
+            ..Default::default()
+        });
+        let switch = Arc::new(SwitchProvider { empty: Default::default() });
+        engine.add_provider(switch.clone());
+        engine.add_provider(Arc::new(MockProvider::new(&["Zelda"])));
+        let uncached = || SearchQuery { cache_mode: CacheMode::Off, ..query("zelda") };
+
+        for _ in 0..5 {
+            engine.search(uncached()).await.unwrap();
+        }
+        assert!(engine.degraded_providers().is_empty());
+
+        // Searches keep succeeding off the other provider
+        switch.empty.store(true, Ordering::SeqCst);
+        for _ in 0..50 {
+            engine.search(uncached()).await.unwrap();
+        }
+        let readiness = engine.readiness();
+        assert!(readiness.ready);
+        assert_eq!(readiness.degraded_providers.len(), 1);
+        assert_eq!(readiness.degraded_providers[0].provider, "switch");
+
+        // Not ready once the only remaining provider is the degraded one
+        engine.set_provider_enabled("mock", false);
+        assert!(!engine.readiness().ready);
+        engine.set_provider_enabled("mock", true);
+
+        switch.empty.store(false, Ordering::SeqCst);
+        for _ in 0..50 {
+            engine.search(uncached()).await.unwrap();
+        }
+        assert!(engine.degraded_providers().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_provider_quota_balances_candidates() {
+        let mut engine = GameEngine::new(":memory:").await.unwrap();
+        engine.set_ranker(Arc::new(RapidfuzzRanker::new()));
+        engine.set_options(SearchOptions { provider_quota: Some(3), ..Default::default() });
+
+        let mut steam_names: Vec<String> = (0..8).map(|i| format!("Filler {}", i)).collect();
+        steam_names.extend(["Hades II".to_string(), "Hades".to_string()]);
+        let steam_names: Vec<&str> = steam_names.iter().map(String::as_str).collect();
+        engine.add_provider(Arc::new(MockProvider::named("steam", &steam_names)));
+        engine.add_provider(Arc::new(MockProvider::named("gog", &["Hades", "Hades: Soundtrack"])));
+        let uncached = || SearchQuery { cache_mode: CacheMode::Off, explain: true, ..query("hades") };
+
+        let response = engine.search(uncached()).await.unwrap();
+        let breakdown = response.score_breakdown.unwrap();
+        // Only the two exact matches ("Hades" on both stores) were ranked
+        assert_eq!(breakdown.candidates, 2);
+        assert_eq!(
+            breakdown.provider_quota,
+            vec![
+                ProviderQuota { provider: "steam".to_string(), returned: 10, kept: 3 },
+                ProviderQuota { provider: "gog".to_string(), returned: 2, kept: 2 },
+            ]
+        );
+        // Pre-score kept the relevant Steam candidates
+        assert_eq!(response.game.name, "Hades");
+        assert!(response.alternatives.iter().any(|g| g.name == "Hades II"));
+
+        // A single responding provider is never capped
+        engine.set_provider_enabled("gog", false);
+        let breakdown = engine.search(uncached()).await.unwrap().score_breakdown.unwrap();
+        assert_eq!(breakdown.candidates, 10);
+        assert_eq!(breakdown.provider_quota[0].kept, 10);
+    }
+
+    #[test]
+    fn test_cache_key_includes_providers() {
+        let restricted = SearchQuery {
+            providers: Some(vec!["steam".to_string(), "IGDB".to_string(), "steam".to_string()]),
+            ..query("Zelda")
+        };
+        assert_eq!(restricted.cache_key(), "zelda@igdb,steam");
+
+        let localized = SearchQuery { locale: Some("fr".to_string()), ..restricted };
+        assert_eq!(localized.cache_key(), "zelda|fr@igdb,steam");
+
+        let released = SearchQuery { exclude_unreleased: true, ..localized };
+        assert_eq!(released.cache_key(), "zelda|fr@igdb,steam#released");
+
+        let on_platforms = SearchQuery {
+            platforms: Some(vec!["Switch".to_string(), "PS5".to_string(), "playstation 4".to_string()]),
+            ..released
+        };
+        assert_eq!(on_platforms.cache_key(), "zelda|fr@igdb,steam#released~playstation,switch");
+        let unrestricted = SearchQuery { platforms: Some(vec![" ".to_string()]), ..query("zelda") };
+        assert_eq!(unrestricted.cache_key(), "zelda");
+    }
+
+    /// DRAKON used to rank by blocking on the runtime from inside the
+    /// search, which panics on the server's multi-threaded runtime
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_drakon_ranks_on_multi_thread_runtime() {
+        let mut engine = GameEngine::new(":memory:").await.unwrap();
+        // Regional duplicates: named like the query, still ranked
+        engine.add_provider(Arc::new(MockProvider::new(&["Hades II", "Hades", "Hades"])));
+        engine.set_ranker(Arc::new(DrakonRanker::new(fake_drakon().await).await.unwrap()));
+
+        let response = engine.search(query("hades")).await.unwrap();
+        let score = crate::ranking::calibrate(99.0, &crate::ranking::calibration::DRAKON_SCALE);
+        assert_eq!((response.game.name.as_str(), response.score), ("Hades", score));
+        assert_eq!(response.ranking_method, "drakon");
+        assert_eq!((response.alternatives[0].name.as_str(), response.alternatives[0].score), ("Hades", Some(score)));
+        assert_eq!((response.alternatives[1].name.as_str(), response.alternatives[1].score), ("Hades II", None));
+    }
+
+    #[tokio::test]
+    async fn test_dead_drakon_falls_back_per_search() {
+        /// DRAKON gone after the engine started
+        struct Gone;
+
+        #[async_trait]
+        impl Ranker for Gone {
+            async fn rank(&self, _query: &str, _candidates: &[GameResult]) -> Result<Vec<crate::ranking::RankedCandidate>> {
+                Err(GameEngineError::DrakonApi("Request failed: connection refused".to_string()))
+            }
+
+            fn name(&self) -> &str {
+                "drakon"
+            }
+        }
+
+        let mut engine = GameEngine::new(":memory:").await.unwrap();
+        engine.add_provider(Arc::new(MockProvider::new(&["Hades", "Hades II"])));
+        let guarded = Arc::new(ResilientRanker::new(Arc::new(Gone), Arc::new(RapidfuzzRanker::new()), BreakerConfig::default()));
+        engine.set_ranker(guarded.clone());
+
+        for _ in 0..DEFAULT_BREAKER_THRESHOLD {
+            let response = engine.search(SearchQuery { cache_mode: CacheMode::Off, ..query("hadess") }).await.unwrap();
+            assert_eq!((response.game.name.as_str(), response.ranking_method.as_str()), ("Hades", "rapidfuzz"));
+        }
+        assert_eq!(guarded.state(), crate::ranking::CircuitState::Open);
+    }
+
+    #[tokio::test]
+    async fn test_guarded_ranker_explains_only_on_request() {
+        let mut engine = GameEngine::new(":memory:").await.unwrap();
+        engine.add_provider(Arc::new(MockProvider::new(&["Hades", "Hades II"])));
+        let guarded = ResilientRanker::new(Arc::new(StubRanker("drakon")), Arc::new(RapidfuzzRanker::new()), BreakerConfig::default());
+        engine.set_ranker(Arc::new(guarded));
+        let uncached = SearchQuery { cache_mode: CacheMode::Off, ..query("hadess") };
+
+        let response = engine.search(uncached.clone()).await.unwrap();
+        assert_eq!((response.ranking_method.as_str(), response.score_breakdown), ("drakon", None));
+
+        let explained = engine.search(SearchQuery { explain: true, ..uncached }).await.unwrap();
+        assert_eq!(explained.score_breakdown.unwrap().ranker, "drakon");
+    }
+
+    #[tokio::test]
+    async fn test_drakon_monitor_upgrades_when_healthy() {
+        let engine = Arc::new(GameEngine::new(":memory:").await.unwrap());
+        engine.set_ranker(Arc::new(RapidfuzzRanker::new()));
+
+        let handle = engine.spawn_drakon_monitor(DrakonMonitorConfig {
+            base_url: fake_drakon().await,
+            interval: std::time::Duration::from_millis(10),
+            max_failures: 1,
+        });
+
+        assert!(wait_for_ranker(&engine, "drakon").await);
+        handle.abort();
+    }
+
+    #[tokio::test]
+    async fn test_drakon_monitor_downgrades_after_failures() {
+        // Grab a free port, then close it so probes are refused
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        drop(listener);
+
+        let engine = Arc::new(GameEngine::new(":memory:").await.unwrap());
+        engine.set_ranker(Arc::new(StubRanker("drakon")));
+
+        let handle = engine.spawn_drakon_monitor(DrakonMonitorConfig {
+            base_url,
+            interval: std::time::Duration::from_millis(10),
+            max_failures: 2,
+        });
+
+        assert!(wait_for_ranker(&engine, "rapidfuzz").await);
+        handle.abort();
+    }
+
+    #[tokio::test]
+    async fn test_drakon_monitor_stops_with_engine() {
+        let engine = Arc::new(GameEngine::new(":memory:").await.unwrap());
+        let handle = engine.spawn_drakon_monitor(DrakonMonitorConfig {
+            interval: std::time::Duration::from_millis(10),
+            ..Default::default()
+        });
+
+        drop(engine);
+        tokio::time::timeout(std::time::Duration::from_secs(2), handle)
+            .await
+            .expect("monitor should exit once the engine is dropped")
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_searches_feed_trending() {
+        let mut engine = GameEngine::new(":memory:").await.unwrap();
+        engine.set_trending_config(TrendingConfig { min_count: 3, ..Default::default() });
+        engine.add_provider(Arc::new(MockProvider::new(&["Zelda"])));
+
+        for q in ["Zelda", "zelda ", "ZELDA"] {
+            engine.search(query(q)).await.unwrap();
+        }
+
+        let trending = engine.trending(10);
+        assert_eq!(trending.len(), 1);
+        assert_eq!(trending[0].query, "zelda");
+        assert_eq!(trending[0].recent_count, 3);
+    }
+
+    #[test]
+    fn test_cache_key_includes_locale() {
+        assert_eq!(query(" Zelda ").cache_key(), "zelda");
+
+        let fr = SearchQuery { locale: Some("fr".to_string()), ..query("Zelda") };
+        assert_eq!(fr.cache_key(), "zelda|fr");
+
+        let fr_ca = SearchQuery { country: Some("CA".to_string()), ..fr.clone() };
+        assert_eq!(fr_ca.cache_key(), "zelda|fr|ca");
+
+        let ca = SearchQuery { country: Some("CA".to_string()), ..query("zelda") };
+        assert_eq!(ca.cache_key(), "zelda||ca");
+    }
+
+    #[test]
+    fn test_cache_key_escapes_typed_separators() {
+        let variants = [
+            SearchQuery { locale: Some("fr".to_string()), ..query("zelda") },
+            SearchQuery { providers: Some(vec!["steam".to_string()]), ..query("zelda") },
+            SearchQuery { exclude_unreleased: true, ..query("zelda") },
+            SearchQuery { platforms: Some(vec!["pc".to_string()]), ..query("zelda") },
+        ];
+        for typed in ["zelda|fr", "zelda@steam", "zelda#released", "zelda~pc", "zelda%7cfr"] {
+            let key = query(typed).cache_key();
+            assert!(!key.contains(CACHE_KEY_SUFFIXES), "{}", key);
+            assert!(variants.iter().all(|variant| variant.cache_key() != key), "{}", key);
+        }
+        assert_eq!(query("Zelda|FR").cache_key(), "zelda%7cfr");
+        assert_eq!(query("100%").cache_key(), "100%25");
+        assert_ne!(query("zelda%7cfr").cache_key(), query("zelda|fr").cache_key());
+    }
+
+    #[tokio::test]
+    async fn test_typed_separators_never_hit_variants() {
+        let mut engine = GameEngine::new(":memory:").await.unwrap();
+        let provider = Arc::new(MockProvider::new(&["Zelda"]));
+        engine.add_provider(provider.clone());
+
+        let french = SearchQuery { locale: Some("fr".to_string()), ..query("zelda") };
+        engine.search(query("zelda|fr")).await.unwrap();
+        assert!(engine.inspect_cache(&french).await.unwrap().is_none());
+        assert!(!engine.search(french).await.unwrap().from_cache);
+
+        // Only the typed query's own entry goes
+        assert_eq!(engine.invalidate("zelda|fr").await.unwrap(), 1);
+        assert_eq!(engine.cache_stats().await.unwrap().total_entries, 1);
+    }
+
+    #[tokio::test]
+    async fn test_locales_cached_separately() {
+        let mut engine = GameEngine::new(":memory:").await.unwrap();
+        let provider = Arc::new(MockProvider::new(&["Zelda"]));
+        engine.add_provider(provider.clone());
+        let localized = |locale: &str| SearchQuery { locale: Some(locale.to_string()), ..query("zelda") };
+
+        engine.search(localized("fr")).await.unwrap();
+        engine.search(localized("en")).await.unwrap();
+        assert_eq!(provider.calls(), 2);
+
+        assert!(engine.search(localized("fr")).await.unwrap().from_cache);
+        assert_eq!(provider.calls(), 2);
+        assert_eq!(engine.cache_stats().await.unwrap().total_entries, 2);
+    }
+
+    #[tokio::test]
+    async fn test_schema_drift_against_blessed_baseline() {
+        let mut engine = GameEngine::new(":memory:").await.unwrap();
+        engine.set_options(SearchOptions { schema_sample_rate: 1.0, ..Default::default() });
+        engine.add_provider(Arc::new(SchemaProvider {
+            body: r#"[{"appid": 1, "name": "Zelda", "logo": "x.png"}]"#,
+            monitor: Default::default(),
+        }));
+
+        let baseline = Fingerprint::from_body("search", r#"[{"appid": 1, "name": "Zelda", "icon": "x.png"}]"#);
+        engine.bless_schema("schema", baseline.clone()).await.unwrap();
+        assert_eq!(engine.schema_baseline("schema").await.unwrap(), Some(baseline));
+
+        engine.search(SearchQuery { cache_mode: CacheMode::Off, ..query("zelda") }).await.unwrap();
+
+        let drift = engine.schema_drift();
+        assert_eq!(drift.len(), 1);
+        assert_eq!(drift[0].diff.added, vec!["search $[].logo: string".to_string()]);
+        assert_eq!(drift[0].diff.removed, vec!["search $[].icon: string".to_string()]);
+    }
+
+    /// Provider describing Zelda as "version <n>" on its n-th call, calls
+    /// after the first held until a permit is added to `gate`
+    struct VersionProvider {
+        calls: AtomicUsize,
+        gate: tokio::sync::Semaphore,
+    }
+
+    #[async_trait]
+    impl GameProvider for VersionProvider {
+        async fn search(&self, _query: &str) -> Result<Vec<GameResult>> {
+            let version = self.calls.fetch_add(1, Ordering::SeqCst) + 1;
+            if version > 1 {
+                self.gate.acquire().await.unwrap().forget();
+            }
+            let mut zelda = GameResult::new("mock", "1", "Zelda");
+            zelda.short_description = format!("version {}", version);
+            Ok(vec![zelda])
+        }
+
+        async fn get_by_id(&self, id: &str) -> Result<GameResult> {
+            Err(GameEngineError::NoResults(id.to_string()))
+        }
+
+        fn name(&self) -> &str {
+            "mock"
+        }
+
+        async fn is_available(&self) -> bool {
+            true
+        }
+    }
+
+    #[tokio::test]
+    async fn test_stale_while_revalidate() {
+        use crate::clock::MockClock;
+
+        let clock = Arc::new(MockClock::new(chrono::Utc::now()));
+        let mut engine = GameEngine::new(":memory:").await.unwrap();
+        engine.set_ranker(Arc::new(RapidfuzzRanker::new()));
+        engine.set_clock(clock.clone());
+        engine.set_options(SearchOptions { stale_while_revalidate: Some(Duration::days(1)), ..Default::default() });
+        let provider = Arc::new(VersionProvider { calls: AtomicUsize::new(0), gate: tokio::sync::Semaphore::new(0) });
+        engine.add_provider(provider.clone());
+        let engine = Arc::new(engine);
+        engine.enable_stale_refresh();
+
+        engine.search(query("zelda")).await.unwrap();
+        let ttl = engine.inspect_cache(&query("zelda")).await.unwrap().unwrap().ttl.unwrap();
+        clock.set(ttl.refresh_after + Duration::hours(1));
+
+        // Stale hits answer from cache while a single refresh is running
+        let (first, second) = tokio::join!(engine.search(query("zelda")), engine.search(query("zelda")));
+        for response in [first.unwrap(), second.unwrap()] {
+            assert!(response.from_cache && response.refreshing);
+            assert_eq!(response.game.short_description, "version 1");
+        }
+        while provider.calls.load(Ordering::SeqCst) < 2 {
+            tokio::task::yield_now().await;
+        }
+        assert!(engine.search(query("zelda")).await.unwrap().refreshing);
+        assert_eq!(provider.calls.load(Ordering::SeqCst), 2);
+
+        provider.gate.add_permits(1);
+        while !engine.refreshing.lock().unwrap().is_empty() {
+            tokio::task::yield_now().await;
+        }
+        let response = engine.search(query("zelda")).await.unwrap();
+        assert!(response.from_cache && !response.refreshing);
+        assert_eq!(response.game.short_description, "version 2");
+        assert_eq!(provider.calls.load(Ordering::SeqCst), 2);
+
+        // Past the window: refreshed before answering
+        let ttl = engine.inspect_cache(&query("zelda")).await.unwrap().unwrap().ttl.unwrap();
+        clock.set(ttl.refresh_after + Duration::days(2));
+        provider.gate.add_permits(1);
+        let response = engine.search(query("zelda")).await.unwrap();
+        assert!(!response.from_cache && !response.refreshing);
+        assert_eq!(response.game.short_description, "version 3");
+    }
+
+    /// Provider answering Zelda once, then panicking
+    struct PanickingProvider {
+        calls: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl GameProvider for PanickingProvider {
+        async fn search(&self, _query: &str) -> Result<Vec<GameResult>> {
+            if self.calls.fetch_add(1, Ordering::SeqCst) > 0 {
+                panic!("provider panicked");
+            }
+            Ok(vec![GameResult::new("mock", "1", "Zelda")])
+        }
+
+        async fn get_by_id(&self, id: &str) -> Result<GameResult> {
+            Err(GameEngineError::NoResults(id.to_string()))
+        }
+
+        fn name(&self) -> &str {
+            "mock"
+        }
+
+        async fn is_available(&self) -> bool {
+            true
+        }
+    }
+
+    #[tokio::test]
+    async fn test_panicked_refresh_clears_refreshing() {
+        use crate::clock::MockClock;
+
+        let clock = Arc::new(MockClock::new(chrono::Utc::now()));
+        let mut engine = GameEngine::new(":memory:").await.unwrap();
+        engine.set_ranker(Arc::new(RapidfuzzRanker::new()));
+        engine.set_clock(clock.clone());
+        engine.set_options(SearchOptions { stale_while_revalidate: Some(Duration::days(1)), ..Default::default() });
+        let provider = Arc::new(PanickingProvider { calls: AtomicUsize::new(0) });
+        engine.add_provider(provider.clone());
+        let engine = Arc::new(engine);
+        engine.enable_stale_refresh();
+
+        engine.search(query("zelda")).await.unwrap();
+        let ttl = engine.inspect_cache(&query("zelda")).await.unwrap().unwrap().ttl.unwrap();
+        clock.set(ttl.refresh_after + Duration::hours(1));
+
+        assert!(engine.search(query("zelda")).await.unwrap().refreshing);
+        while provider.calls.load(Ordering::SeqCst) < 2 || !engine.refreshing.lock().unwrap().is_empty() {
+            tokio::task::yield_now().await;
+        }
+        // The next stale hit starts another refresh
+        assert!(engine.search(query("zelda")).await.unwrap().refreshing);
+        while provider.calls.load(Ordering::SeqCst) < 3 {
+            tokio::task::yield_now().await;
+        }
+    }
+
+    /// Engine whose cache holds a stale "Zelda (cached)" entry for "zelda",
+    /// with a provider returning "Zelda (fresh)"
+    async fn seeded_engine() -> (GameEngine, Arc<MockProvider>) {
+        let mut engine = GameEngine::new(":memory:").await.unwrap();
+        let provider = Arc::new(MockProvider::new(&["Zelda (fresh)"]));
+        engine.add_provider(provider.clone());
+        engine
+            .cache
+            .save_scored("zelda", &GameResult::new("mock", "0", "Zelda (cached)"), &[], 90.0)
+            .await
+            .unwrap();
+        (engine, provider)
+    }
+
+    fn with_mode(mode: CacheMode) -> SearchQuery {
+        SearchQuery { cache_mode: mode, ..query("zelda") }
+    }
+
+    #[tokio::test]
+    async fn test_cache_mode_use_reads_cache() {
+        let (engine, provider) = seeded_engine().await;
+
+        let response = engine.search(with_mode(CacheMode::Use)).await.unwrap();
+        assert!(response.from_cache);
+        assert_eq!(response.game.name, "Zelda (cached)");
+        assert_eq!(response.cache_mode, CacheMode::Use);
+        assert_eq!(provider.calls(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_cache_mode_bypass_refreshes_entry() {
+        let (mut engine, provider) = seeded_engine().await;
+        // Write-through even below the usual threshold
+        engine.set_options(SearchOptions { cache_min_score: 101.0, ..Default::default() });
+
+        let response = engine.search(with_mode(CacheMode::Bypass)).await.unwrap();
+        assert!(!response.from_cache);
+        assert_eq!(response.game.name, "Zelda (fresh)");
+        assert_eq!(response.cache_mode, CacheMode::Bypass);
+        assert_eq!(provider.calls(), 1);
+
+        let cached = engine.cache.get("zelda").await.unwrap().unwrap();
+        assert_eq!(cached.game.name, "Zelda (fresh)");
+    }
+
+    #[tokio::test]
+    async fn test_cache_mode_off_leaves_cache_untouched() {
+        let (engine, provider) = seeded_engine().await;
+
+        let response = engine.search(with_mode(CacheMode::Off)).await.unwrap();
+        assert_eq!(response.game.name, "Zelda (fresh)");
+        assert_eq!(response.cache_mode, CacheMode::Off);
+        assert_eq!(provider.calls(), 1);
+
+        let cached = engine.cache.get("zelda").await.unwrap().unwrap();
+        assert_eq!(cached.game.name, "Zelda (cached)");
+        assert_eq!(cached.hit_count, 0);
+    }
+
+    /// Files in the working directory and temp dir
+    fn dir_snapshot() -> HashSet<std::path::PathBuf> {
+        [std::env::current_dir().unwrap(), std::env::temp_dir()]
+            .iter()
+            .flat_map(|dir| std::fs::read_dir(dir).unwrap())
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|path| {
+                let name = path.file_name().unwrap_or_default().to_string_lossy();
+                name.ends_with(".db") || name.ends_with("-journal") || name.ends_with("-wal")
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_memory_backend_end_to_end() {
+        let before = dir_snapshot();
+        let mut engine = GameEngine::in_memory(SearchOptions::default()).await.unwrap();
+        assert_eq!(engine.cache_backend(), "memory");
+        let provider = Arc::new(MockProvider::new(&["Zelda"]));
+        engine.add_provider(provider.clone());
+
+        assert!(!engine.search(query("zelda")).await.unwrap().from_cache);
+        assert!(engine.search(query("zelda")).await.unwrap().from_cache);
+        assert_eq!(provider.calls(), 1);
+        let stats = engine.cache_stats().await.unwrap();
+        assert_eq!((stats.total_hits, stats.hit_rate), (1, Some(0.5)));
+        assert_eq!(engine.top_queries(5, None).await.unwrap(), vec![("zelda".to_string(), 1)]);
+
+        assert!(dir_snapshot().is_subset(&before));
+    }
+
+    #[tokio::test]
+    async fn test_null_backend_end_to_end() {
+        let before = dir_snapshot();
+        let mut engine = GameEngine::without_cache(SearchOptions::default()).await.unwrap();
+        assert_eq!(engine.cache_backend(), "none");
+        let provider = Arc::new(MockProvider::new(&["Zelda"]));
+        engine.add_provider(provider.clone());
+
+        assert!(!engine.search(query("zelda")).await.unwrap().from_cache);
+        assert!(!engine.search(query("zelda")).await.unwrap().from_cache);
+        assert_eq!(provider.calls(), 2);
+
+        let stats = engine.cache_stats().await.unwrap();
+        assert_eq!(stats.total_entries, 0);
+        assert_eq!(stats.total_hits, 0);
+
+        assert!(dir_snapshot().is_subset(&before));
+    }
+
+    /// A row corrupted on disk fails the search with a diagnostic line
+    /// naming the query and phase, the serde message shown once
+    #[tokio::test]
+    async fn test_corrupted_entry_diagnostic() {
+        let cache = Arc::new(crate::cache::SqliteCache::new(":memory:").await.unwrap());
+        let mut engine = GameEngine::with_cache(cache.clone(), "sqlite", DEFAULT_DRAKON_URL, SearchOptions::default())
+            .await
+            .unwrap();
+        engine.add_provider(Arc::new(MockProvider::new(&["Zelda"])));
+        engine.search(query("zelda")).await.unwrap();
+        cache.connection()
+            .execute("UPDATE game_cache SET game_data = 'not json' WHERE query = 'zelda'", [])
+            .unwrap();
+
+        let line = engine.search(query("Zelda")).await.unwrap_err().diagnostic();
+        assert!(line.starts_with("JSON error:"), "{}", line);
+        assert!(line.contains("[cache_decode query=\"Zelda\"]"), "{}", line);
+        assert_eq!(line.matches("expected ident").count(), 1, "{}", line);
+    }
+
+    /// Memory cache whose writes fail while `failures` is not exhausted
+    struct FlakyCache {
+        inner: crate::cache::MemoryCache,
+        failures: AtomicUsize,
+    }
+
+    impl FlakyCache {
+        fn new(failures: usize) -> Self {
+            Self {
+                inner: Default::default(),
+                failures: AtomicUsize::new(failures),
+            }
+        }
+
+        fn fail_write(&self) -> Result<()> {
+            let left = self.failures.load(Ordering::SeqCst);
+            if left == 0 {
+                return Ok(());
+            }
+            self.failures.store(left - 1, Ordering::SeqCst);
+            Err(GameEngineError::Cache("disk full".to_string()))
+        }
+    }
+
+    #[async_trait]
+    impl GameCache for FlakyCache {
+        async fn get(&self, query: &str) -> Result<Option<crate::cache::CachedGame>> {
+            self.inner.get(query).await
+        }
+
+        async fn save(&self, query: &str, game: &GameResult, alternatives: &[SlimGameResult]) -> Result<()> {
+            self.fail_write()?;
+            self.inner.save(query, game, alternatives).await
+        }
+
+        async fn save_scored(&self, query: &str, game: &GameResult, alternatives: &[SlimGameResult], score: f64) -> Result<()> {
+            self.fail_write()?;
+            self.inner.save_scored(query, game, alternatives, score).await
+        }
+
+        async fn increment_hit(&self, query: &str) -> Result<()> {
+            self.inner.increment_hit(query).await
+        }
+
+        async fn flush(&self) -> Result<()> {
+            self.inner.flush().await
+        }
+
+        async fn stats(&self) -> Result<crate::cache::CacheStats> {
+            self.inner.stats().await
+        }
+
+        async fn cleanup(&self, max_age_days: i64) -> Result<u64> {
+            self.inner.cleanup(max_age_days).await
+        }
+    }
+
+    async fn flaky_engine(failures: usize, config: WriteRetryConfig) -> GameEngine {
+        let cache = Arc::new(FlakyCache::new(failures));
+        let mut engine = GameEngine::with_cache(cache, "flaky", DEFAULT_DRAKON_URL, SearchOptions::default())
+            .await
+            .unwrap();
+        engine.set_write_retry_config(config);
+        engine.add_provider(Arc::new(MockProvider::new(&["Zelda"])));
+        engine
+    }
+
+    #[tokio::test]
+    async fn test_failed_cache_write_is_retried() {
+        let engine = flaky_engine(2, WriteRetryConfig::default()).await;
+
+        // Search still succeeds, the write is deferred
+        assert!(!engine.search(query("zelda")).await.unwrap().from_cache);
+        assert_eq!(engine.cache_stats().await.unwrap().pending_writes, 1);
+
+        assert_eq!(engine.retry_pending_writes().await, (0, 1));
+        assert_eq!(engine.retry_pending_writes().await, (1, 0));
+
+        let stats = engine.cache_stats().await.unwrap();
+        assert_eq!(stats.pending_writes, 0);
+        assert_eq!(stats.write_retries_succeeded, 1);
+        assert_eq!(stats.total_entries, 1);
+        assert!(engine.search(query("zelda")).await.unwrap().from_cache);
+    }
+
+    #[tokio::test]
+    async fn test_failed_cache_write_gives_up() {
+        let config = WriteRetryConfig { max_attempts: 2, ..Default::default() };
+        let engine = flaky_engine(usize::MAX, config).await;
+
+        engine.search(query("zelda")).await.unwrap();
+        engine.retry_pending_writes().await;
+        engine.retry_pending_writes().await;
+
+        let stats = engine.cache_stats().await.unwrap();
+        assert_eq!(stats.pending_writes, 0);
+        assert_eq!(stats.write_retries_dropped, 1);
+        assert_eq!(stats.write_retries_succeeded, 0);
+    }
+
+    #[tokio::test]
+    async fn test_invalidate_drops_pending_write() {
+        let engine = flaky_engine(1, WriteRetryConfig::default()).await;
+        engine.search(query("zelda")).await.unwrap();
+        assert_eq!(engine.cache_stats().await.unwrap().pending_writes, 1);
+
+        // The bad result must not land once the cache recovers
+        assert_eq!(engine.invalidate("zelda").await.unwrap(), 0);
+        assert_eq!(engine.cache_stats().await.unwrap().pending_writes, 0);
+        assert_eq!(engine.retry_pending_writes().await, (0, 0));
+        assert!(engine.inspect_cache(&query("zelda")).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_cache_write_retry_task() {
+        let config = WriteRetryConfig {
+            backoff: crate::providers::RetryPolicy {
+                base_delay: std::time::Duration::from_millis(5),
+                max_delay: std::time::Duration::from_millis(20),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let engine = Arc::new(flaky_engine(3, config).await);
+        engine.search(query("zelda")).await.unwrap();
+
+        let handle = engine.spawn_cache_write_retry();
+        let mut landed = false;
+        for _ in 0..200 {
+            if engine.cache_stats().await.unwrap().write_retries_succeeded == 1 {
+                landed = true;
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(5)).await;
+        }
+        handle.abort();
+
+        assert!(landed);
+        assert_eq!(engine.cache_stats().await.unwrap().pending_writes, 0);
+    }
+
+    #[tokio::test]
+    async fn test_warm_skips_cached_queries() {
+        let mut engine = GameEngine::new(":memory:").await.unwrap();
+        let provider = Arc::new(MockProvider::new(&["Hades", "Celeste", "Hollow Knight"]));
+        engine.add_provider(provider.clone());
+        engine.search(query("hades")).await.unwrap();
+        let engine = Arc::new(engine);
+
+        let queries = ["hades", "Celeste", " celeste ", "", "hollow knight"].map(String::from);
+        let report = engine.warm(queries, 2).await.unwrap();
+        assert_eq!((report.total, report.cached, report.fetched, report.failed), (3, 1, 2, 0));
+        assert_eq!(provider.calls(), 3);
+        // Warm-up searches are not user searches
+        let info = engine.info().await.unwrap();
+        assert_eq!((info.trending_queries, info.recent_searches, info.in_flight_searches), (1, 1, 0));
+
+        let report = engine.warm(["celeste".to_string(), "hollow knight".to_string()], 4).await.unwrap();
+        assert_eq!((report.cached, report.fetched), (2, 0));
+        assert_eq!(provider.calls(), 3);
+        assert_eq!(engine.cache_stats().await.unwrap().total_hits, 0);
+
+        let mut empty = GameEngine::new(":memory:").await.unwrap();
+        empty.add_provider(Arc::new(MockProvider::new(&[])));
+        let report = Arc::new(empty).warm(vec!["pog".to_string()], 0).await.unwrap();
+        assert_eq!((report.total, report.failed), (1, 1));
+        assert_eq!(report.failures[0].query, "pog");
+    }
+
+    #[tokio::test]
+    async fn test_query_log_records_searches() {
+        let mut engine = GameEngine::new(":memory:").await.unwrap();
+        engine.set_ranker(Arc::new(RapidfuzzRanker::new()));
+        engine.add_provider(Arc::new(MockProvider::named("steam", &["Hades"])));
+
+        // Off by default
+        engine.search(query("hades")).await.unwrap();
+        assert!(engine.recent_searches(10).await.unwrap().is_empty());
+
+        engine.set_options(SearchOptions { log_queries: true, ..Default::default() });
+        engine.search(query("Hades")).await.unwrap();
+        let unknown = SearchQuery { providers: Some(vec!["gog".to_string()]), ..query("xyzzy") };
+        engine.search(unknown).await.unwrap_err();
+
+        let recent = engine.recent_searches(10).await.unwrap();
+        assert_eq!(recent.len(), 2);
+        assert_eq!(recent[0].query, "xyzzy");
+        assert_eq!(recent[0].resolved_name, None);
+        assert_eq!(recent[1].query, "Hades");
+        assert_eq!(recent[1].resolved_name.as_deref(), Some("Hades"));
+        assert_eq!(recent[1].provider.as_deref(), Some("steam"));
+        assert!(recent[1].from_cache);
+
+        assert_eq!(engine.cleanup_log(30).await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_last_searches_without_configuration() {
+        let mut engine = GameEngine::without_cache(SearchOptions::default()).await.unwrap();
+        engine.set_ranker(Arc::new(RapidfuzzRanker::new()));
+        engine.add_provider(Arc::new(MockProvider::named("steam", &["Hades", "Celeste"])));
+
+        engine.search(query("hades")).await.unwrap();
+        engine.search(query("celeste")).await.unwrap();
+        let unknown = SearchQuery { providers: Some(vec!["gog".to_string()]), ..query("xyzzy") };
+        engine.search(unknown).await.unwrap_err();
+
+        let last = engine.last_searches(5);
+        assert_eq!(last.len(), 2);
+        assert_eq!((last[0].query.as_str(), last[0].winner.as_str()), ("celeste", "Celeste"));
+        assert_eq!(last[0].provider, "steam");
+        assert!(!last[0].from_cache);
+        assert_eq!(engine.last_searches(1).len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_engine_info_counters() {
+        let options = SearchOptions { log_queries: true, ..Default::default() };
+        let mut engine = GameEngine::in_memory(options).await.unwrap();
+        engine.set_ranker(Arc::new(RapidfuzzRanker::new()));
+        engine.add_provider(Arc::new(MockProvider::named("steam", &["Hades", "Celeste"])));
+        let engine = Arc::new(engine);
+
+        let info = engine.info().await.unwrap();
+        assert_eq!((info.cache_backend.as_str(), info.ranker.as_str()), ("memory", "rapidfuzz"));
+        assert_eq!(info.background_tasks, 0);
+        assert_eq!(info.cache_capacity, Some(DEFAULT_MEMORY_CAPACITY));
+
+        engine.search(query("hades")).await.unwrap();
+        engine.search(query("celeste")).await.unwrap();
+        let retry = engine.spawn_cache_write_retry();
+
+        let info = engine.info().await.unwrap();
+        // Query log writer and write retry task
+        assert_eq!(info.background_tasks, 2);
+        assert_eq!(info.in_flight_searches, 0);
+        assert_eq!(info.cache_entries, 2);
+        assert_eq!((info.recent_searches, info.trending_queries), (2, 2));
+
+        retry.abort();
+        let _ = retry.await;
+        assert_eq!(engine.info().await.unwrap().background_tasks, 1);
     }
 }
@@ -1,3 +1,4 @@
+use std::fmt;
 use thiserror::Error;
 
 /// Main error type for the game engine
@@ -7,6 +8,14 @@ pub enum GameEngineError {
     #[error("Database error: {0}")]
     Database(#[from] rusqlite::Error),
 
+    /// SQLite kept reporting busy/locked through every retry
+    #[error("Database still locked after {attempts} attempts")]
+    DatabaseLocked {
+        attempts: u32,
+        #[source]
+        source: rusqlite::Error,
+    },
+
     /// HTTP request errors
     #[error("HTTP request failed: {0}")]
     HttpRequest(#[from] reqwest::Error),
@@ -23,19 +32,231 @@ pub enum GameEngineError {
     #[error("Provider '{provider}' error: {message}")]
     Provider { provider: String, message: String },
 
+    /// Provider answered with a non-success HTTP status
+    #[error("Provider '{provider}' returned HTTP {status}")]
+    ProviderStatus { provider: String, status: u16 },
+
     /// Cache errors
     #[error("Cache error: {0}")]
     Cache(String),
 
+    /// Invalid engine configuration, `field` is the path of the offending value
+    #[error("Invalid config at '{field}': {message}")]
+    Config { field: String, message: String },
+
     /// No results found
     #[error("No results found for query: {0}")]
     NoResults(String),
 
+    /// Error annotated with where it happened (query, provider, phase)
+    #[error("{context} failed")]
+    WithContext {
+        context: ErrorContext,
+        #[source]
+        source: Box<GameEngineError>,
+    },
+
     /// Generic errors
     #[error("{0}")]
     Other(String),
 }
 
+/// Pipeline phase an error occurred in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Phase {
+    CacheRead,
+    CacheDecode,
+    CacheWrite,
+    ProviderSearch,
+    Ranking,
+}
+
+impl Phase {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Phase::CacheRead => "cache_read",
+            Phase::CacheDecode => "cache_decode",
+            Phase::CacheWrite => "cache_write",
+            Phase::ProviderSearch => "provider_search",
+            Phase::Ranking => "ranking",
+        }
+    }
+}
+
+impl fmt::Display for Phase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Context attached to an error as it crosses layers
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ErrorContext {
+    pub query: Option<String>,
+    pub provider: Option<String>,
+    pub phase: Option<Phase>,
+}
+
+impl ErrorContext {
+    /// Fill fields not already set (inner context wins)
+    fn merge(&mut self, outer: ErrorContext) {
+        if self.query.is_none() {
+            self.query = outer.query;
+        }
+        if self.provider.is_none() {
+            self.provider = outer.provider;
+        }
+        if self.phase.is_none() {
+            self.phase = outer.phase;
+        }
+    }
+}
+
+impl fmt::Display for ErrorContext {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.phase.map(|p| p.as_str()).unwrap_or("operation"))?;
+        if let Some(provider) = &self.provider {
+            write!(f, " provider={}", provider)?;
+        }
+        if let Some(query) = &self.query {
+            write!(f, " query={:?}", query)?;
+        }
+        Ok(())
+    }
+}
+
+impl GameEngineError {
+    /// Attach context, merging into an existing context layer instead of nesting
+    pub fn with_context(self, context: ErrorContext) -> Self {
+        match self {
+            GameEngineError::WithContext { context: mut inner, source } => {
+                inner.merge(context);
+                GameEngineError::WithContext { context: inner, source }
+            }
+            other => GameEngineError::WithContext {
+                context,
+                source: Box::new(other),
+            },
+        }
+    }
+
+    /// Context attached to this error, if any
+    pub fn context(&self) -> Option<&ErrorContext> {
+        match self {
+            GameEngineError::WithContext { context, .. } => Some(context),
+            _ => None,
+        }
+    }
+
+    /// Underlying error with context layers stripped
+    pub fn root(&self) -> &GameEngineError {
+        match self {
+            GameEngineError::WithContext { source, .. } => source.root(),
+            other => other,
+        }
+    }
+
+    /// Unwrap context layers, returning the underlying error
+    pub fn into_root(self) -> GameEngineError {
+        match self {
+            GameEngineError::WithContext { source, .. } => source.into_root(),
+            other => other,
+        }
+    }
+
+    /// Whether retrying the same call may succeed: timeouts, connection
+    /// failures, HTTP 429 and 5xx. Everything else (404, bad JSON...) fails fast.
+    pub fn is_retryable(&self) -> bool {
+        match self.root() {
+            GameEngineError::HttpRequest(e) => {
+                e.is_timeout()
+                    || e.is_connect()
+                    || e.status().map(|s| is_retryable_status(s.as_u16())).unwrap_or(false)
+            }
+            GameEngineError::ProviderStatus { status, .. } => is_retryable_status(*status),
+            _ => false,
+        }
+    }
+
+    /// SQLite busy or locked error: another connection holds the lock,
+    /// retrying shortly after may succeed
+    pub fn is_database_busy(&self) -> bool {
+        matches!(
+            self.root(),
+            GameEngineError::Database(rusqlite::Error::SqliteFailure(e, _))
+                if matches!(e.code, rusqlite::ErrorCode::DatabaseBusy | rusqlite::ErrorCode::DatabaseLocked)
+        )
+    }
+
+    /// Full diagnostic line for internal logs: message, context and source
+    /// chain. A source whose message its parent's already shows (`#[from]`
+    /// variants such as `Json`) is not repeated.
+    pub fn diagnostic(&self) -> String {
+        let mut parent = self.root().to_string();
+        let mut line = parent.clone();
+        if let Some(context) = self.context() {
+            line.push_str(&format!(" [{}]", context));
+        }
+
+        let mut source = std::error::Error::source(self.root());
+        while let Some(err) = source {
+            let message = err.to_string();
+            if !parent.contains(&message) {
+                line.push_str(&format!(": {}", message));
+            }
+            parent = message;
+            source = err.source();
+        }
+
+        line
+    }
+}
+
+fn is_retryable_status(status: u16) -> bool {
+    status == 429 || (500..600).contains(&status)
+}
+
+/// Extension methods to attach context to results
+pub trait ResultExt<T> {
+    /// Attach the query being processed
+    fn ctx_query(self, query: impl Into<String>) -> Result<T>;
+
+    /// Attach the provider involved
+    fn ctx_provider(self, provider: impl Into<String>) -> Result<T>;
+
+    /// Attach the pipeline phase
+    fn ctx_phase(self, phase: Phase) -> Result<T>;
+}
+
+impl<T, E: Into<GameEngineError>> ResultExt<T> for std::result::Result<T, E> {
+    fn ctx_query(self, query: impl Into<String>) -> Result<T> {
+        self.map_err(|e| {
+            e.into().with_context(ErrorContext {
+                query: Some(query.into()),
+                ..Default::default()
+            })
+        })
+    }
+
+    fn ctx_provider(self, provider: impl Into<String>) -> Result<T> {
+        self.map_err(|e| {
+            e.into().with_context(ErrorContext {
+                provider: Some(provider.into()),
+                ..Default::default()
+            })
+        })
+    }
+
+    fn ctx_phase(self, phase: Phase) -> Result<T> {
+        self.map_err(|e| {
+            e.into().with_context(ErrorContext {
+                phase: Some(phase),
+                ..Default::default()
+            })
+        })
+    }
+}
+
 impl From<String> for GameEngineError {
     fn from(s: String) -> Self {
         GameEngineError::Other(s)
@@ -50,3 +271,71 @@ impl From<&str> for GameEngineError {
 
 /// Result type alias
 pub type Result<T> = std::result::Result<T, GameEngineError>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_context_merges_instead_of_nesting() {
+        let err: Result<()> = Err(GameEngineError::Cache("disk full".to_string()));
+        let err = err
+            .ctx_phase(Phase::CacheWrite)
+            .ctx_query("zelda")
+            .ctx_phase(Phase::Ranking)
+            .unwrap_err();
+
+        let context = err.context().unwrap();
+        assert_eq!(context.phase, Some(Phase::CacheWrite));
+        assert_eq!(context.query.as_deref(), Some("zelda"));
+        assert!(matches!(err.root(), GameEngineError::Cache(_)));
+        assert!(matches!(err.into_root(), GameEngineError::Cache(_)));
+    }
+
+    #[test]
+    fn test_is_retryable() {
+        let status = |status| GameEngineError::ProviderStatus { provider: "steam".to_string(), status };
+        assert!(status(502).is_retryable());
+        assert!(status(429).is_retryable());
+        assert!(!status(404).is_retryable());
+        assert!(!GameEngineError::NoResults("zelda".to_string()).is_retryable());
+
+        let wrapped = Err::<(), _>(status(503)).ctx_provider("steam").unwrap_err();
+        assert!(wrapped.is_retryable());
+    }
+
+    #[test]
+    fn test_diagnostic_includes_context_and_chain() {
+        let json_err = serde_json::from_str::<serde_json::Value>("{oops").unwrap_err();
+        let err = Err::<(), _>(json_err)
+            .ctx_phase(Phase::CacheDecode)
+            .ctx_query("hades")
+            .unwrap_err();
+
+        let line = err.diagnostic();
+        assert!(line.starts_with("JSON error:"));
+        assert!(line.contains("cache_decode"));
+        assert!(line.contains("\"hades\""));
+        assert_eq!(line.matches("key must be a string").count(), 1, "{}", line);
+
+        // Fixed messages still get their source
+        let sqlite_err = rusqlite::Error::SqliteFailure(rusqlite::ffi::Error::new(5), Some("database is locked".to_string()));
+        let locked = GameEngineError::DatabaseLocked { attempts: 3, source: sqlite_err };
+        assert!(locked.diagnostic().starts_with("Database still locked after 3 attempts: database is locked"));
+
+        // Client-facing message stays the plain root error
+        assert!(!err.root().to_string().contains("hades"));
+    }
+
+    #[test]
+    fn test_anyhow_chain_walkable() {
+        let json_err = serde_json::from_str::<serde_json::Value>("[").unwrap_err();
+        let err = Err::<(), _>(json_err).ctx_query("q").unwrap_err();
+        let err = anyhow::Error::from(err);
+
+        let chain: Vec<String> = err.chain().map(|e| e.to_string()).collect();
+        assert_eq!(chain.len(), 3);
+        assert!(chain[0].contains("query=\"q\""));
+        assert!(chain[1].starts_with("JSON error:"));
+    }
+}
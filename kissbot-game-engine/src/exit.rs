@@ -0,0 +1,205 @@
+//! Canonical error classification shared by the server, the CLI and the
+//! Python binding: one `ErrorCode` per kind of failure, one table entry per
+//! code (HTTP status, process exit code, retryable flag, user message)
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::GameEngineError;
+
+/// Stable kind of failure, as reported to users and callers
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCode {
+    /// Nothing matched the query
+    NoResults,
+    /// Bad request from the caller (unknown ranker, malformed parameter...)
+    InvalidInput,
+    /// Invalid engine configuration
+    Config,
+    /// Provider failed in a way retrying will not fix (404, bad payload...)
+    ProviderFailed,
+    /// Provider timed out, refused the connection, rate limited or 5xx'd
+    ProviderUnavailable,
+    /// DRAKON ranker unreachable or erroring
+    RankerUnavailable,
+    /// Cache backend failure
+    Cache,
+    /// Anything else
+    Internal,
+}
+
+/// How an `ErrorCode` surfaces in each interface
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ErrorMapping {
+    pub http_status: u16,
+    pub exit_code: i32,
+    /// Whether the same call may succeed later
+    pub retryable: bool,
+    /// User-facing message, `{error}` is replaced by the error's own message
+    pub message: &'static str,
+}
+
+impl ErrorCode {
+    /// Every code, for exhaustive checks and documentation
+    pub const ALL: &'static [ErrorCode] = &[
+        ErrorCode::NoResults,
+        ErrorCode::InvalidInput,
+        ErrorCode::Config,
+        ErrorCode::ProviderFailed,
+        ErrorCode::ProviderUnavailable,
+        ErrorCode::RankerUnavailable,
+        ErrorCode::Cache,
+        ErrorCode::Internal,
+    ];
+
+    /// The mapping table (exit codes follow sysexits.h where one fits)
+    pub const fn mapping(self) -> ErrorMapping {
+        const fn entry(http_status: u16, exit_code: i32, retryable: bool, message: &'static str) -> ErrorMapping {
+            ErrorMapping { http_status, exit_code, retryable, message }
+        }
+
+        match self {
+            ErrorCode::NoResults => entry(404, 3, false, "{error}"),
+            ErrorCode::InvalidInput => entry(400, 2, false, "{error}"),
+            ErrorCode::Config => entry(500, 78, false, "{error}"),
+            ErrorCode::ProviderFailed => entry(502, 69, false, "{error}"),
+            ErrorCode::ProviderUnavailable => entry(502, 69, true, "{error}, try again later"),
+            ErrorCode::RankerUnavailable => entry(503, 69, true, "{error}, try again later"),
+            ErrorCode::Cache => entry(500, 74, false, "Cache unavailable: {error}"),
+            ErrorCode::Internal => entry(500, 70, false, "Internal error: {error}"),
+        }
+    }
+
+    /// Classify an error (context wrappers are looked through)
+    pub fn of(err: &GameEngineError) -> Self {
+        match err.root() {
+            GameEngineError::NoResults(_) => ErrorCode::NoResults,
+            GameEngineError::Config { .. } => ErrorCode::Config,
+            e @ (GameEngineError::Provider { .. }
+            | GameEngineError::ProviderStatus { .. }
+            | GameEngineError::HttpRequest(_)) => {
+                if e.is_retryable() {
+                    ErrorCode::ProviderUnavailable
+                } else {
+                    ErrorCode::ProviderFailed
+                }
+            }
+            GameEngineError::DrakonApi(_) => ErrorCode::RankerUnavailable,
+            GameEngineError::Database(_) | GameEngineError::DatabaseLocked { .. } | GameEngineError::Cache(_) => {
+                ErrorCode::Cache
+            }
+            GameEngineError::Json(_) | GameEngineError::Other(_) | GameEngineError::WithContext { .. } => {
+                ErrorCode::Internal
+            }
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ErrorCode::NoResults => "no_results",
+            ErrorCode::InvalidInput => "invalid_input",
+            ErrorCode::Config => "config",
+            ErrorCode::ProviderFailed => "provider_failed",
+            ErrorCode::ProviderUnavailable => "provider_unavailable",
+            ErrorCode::RankerUnavailable => "ranker_unavailable",
+            ErrorCode::Cache => "cache",
+            ErrorCode::Internal => "internal",
+        }
+    }
+
+    pub fn http_status(self) -> u16 {
+        self.mapping().http_status
+    }
+
+    pub fn exit_code(self) -> i32 {
+        self.mapping().exit_code
+    }
+
+    pub fn retryable(self) -> bool {
+        self.mapping().retryable
+    }
+
+    /// Render the user-facing message for `error`
+    pub fn message(self, error: &str) -> String {
+        self.mapping().message.replace("{error}", error)
+    }
+}
+
+impl std::fmt::Display for ErrorCode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Code and user-facing message of an error (internal context stays out,
+/// log `GameEngineError::diagnostic` for that)
+pub fn describe(err: &GameEngineError) -> (ErrorCode, String) {
+    let code = ErrorCode::of(err);
+    (code, code.message(&err.root().to_string()))
+}
+
+/// Exit code and user-facing message for an error bubbled up to a
+/// binary's `main` (exit code 1 when it is not an engine error)
+pub fn report(err: &anyhow::Error) -> (i32, String) {
+    match err.chain().find_map(|e| e.downcast_ref::<GameEngineError>()) {
+        Some(e) => {
+            let (code, message) = describe(e);
+            (code.exit_code(), message)
+        }
+        None => (1, format!("{:#}", err)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::{Phase, ResultExt};
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_every_code_is_mapped() {
+        // Adding a variant without listing it here fails to compile
+        let listed = |code: ErrorCode| match code {
+            ErrorCode::NoResults
+            | ErrorCode::InvalidInput
+            | ErrorCode::Config
+            | ErrorCode::ProviderFailed
+            | ErrorCode::ProviderUnavailable
+            | ErrorCode::RankerUnavailable
+            | ErrorCode::Cache
+            | ErrorCode::Internal => ErrorCode::ALL.contains(&code),
+        };
+
+        let mut names = HashSet::new();
+        for &code in ErrorCode::ALL {
+            assert!(listed(code));
+            let mapping = code.mapping();
+            assert!((400..600).contains(&mapping.http_status), "{}", code);
+            assert!((2..=125).contains(&mapping.exit_code), "{}", code);
+            assert!(mapping.message.contains("{error}"), "{}", code);
+            assert!(names.insert(code.as_str()), "duplicate name {}", code);
+            assert_eq!(serde_json::to_value(code).unwrap(), code.as_str());
+        }
+    }
+
+    #[test]
+    fn test_classification() {
+        let no_results: crate::error::Result<()> = Err(GameEngineError::NoResults("zelda".to_string()));
+        let err = no_results.ctx_query("zelda").ctx_phase(Phase::Ranking).unwrap_err();
+        let (code, message) = describe(&err);
+        assert_eq!(code, ErrorCode::NoResults);
+        assert_eq!((code.http_status(), code.exit_code()), (404, 3));
+        assert_eq!(message, "No results found for query: zelda");
+
+        let status = |status| GameEngineError::ProviderStatus { provider: "steam".to_string(), status };
+        assert_eq!(ErrorCode::of(&status(503)), ErrorCode::ProviderUnavailable);
+        assert_eq!(ErrorCode::of(&status(404)), ErrorCode::ProviderFailed);
+        assert_eq!(ErrorCode::of(&GameEngineError::Cache("locked".to_string())), ErrorCode::Cache);
+
+        let config = GameEngineError::Config { field: "cache.path".to_string(), message: "empty".to_string() };
+        let (exit_code, message) = report(&anyhow::Error::from(config).context("loading engine.yaml"));
+        assert_eq!(exit_code, 78);
+        assert_eq!(message, "Invalid config at 'cache.path': empty");
+        assert_eq!(report(&anyhow::anyhow!("port taken")), (1, "port taken".to_string()));
+    }
+}
@@ -0,0 +1,212 @@
+//! HTTP client shared by every provider and the DRAKON ranker, so calls
+//! reuse one keep-alive connection pool instead of opening new TLS
+//! connections per provider
+
+use hyper::client::connect::dns::Name;
+use reqwest::dns::{Addrs, Resolve, Resolving};
+use reqwest::{Client, RequestBuilder, Response};
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::error::{GameEngineError, Result};
+
+/// Connection pool settings of the shared client
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct HttpClientConfig {
+    /// Idle connections kept open per host
+    pub pool_max_idle_per_host: usize,
+    /// Idle connections are closed after this long
+    pub pool_idle_timeout_secs: f64,
+    /// TCP keep-alive probe interval (None disables probes)
+    pub tcp_keepalive_secs: Option<f64>,
+    /// Offer HTTP/2 during the TLS handshake (hosts without it get HTTP/1.1);
+    /// false forces HTTP/1.1 everywhere
+    pub http2: bool,
+    pub connect_timeout_secs: f64,
+}
+
+impl Default for HttpClientConfig {
+    fn default() -> Self {
+        Self {
+            pool_max_idle_per_host: 16,
+            pool_idle_timeout_secs: 90.0,
+            tcp_keepalive_secs: Some(60.0),
+            http2: true,
+            connect_timeout_secs: 5.0,
+        }
+    }
+}
+
+/// Requests sent through a shared client and the connections they needed
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HttpPoolStats {
+    pub requests: u64,
+    /// Connections opened, counted on host name resolution (hosts given as
+    /// IP addresses skip it and are not counted)
+    pub new_connections: u64,
+    /// Requests served on an already open connection
+    pub reused_connections: u64,
+}
+
+#[derive(Debug, Default)]
+struct Counters {
+    requests: AtomicU64,
+    connects: AtomicU64,
+}
+
+/// Cheap to clone, clones share the pool and the counters
+#[derive(Debug, Clone)]
+pub struct HttpClient {
+    client: Client,
+    counters: Arc<Counters>,
+}
+
+impl HttpClient {
+    pub fn new(config: &HttpClientConfig) -> Result<Self> {
+        let counters = Arc::new(Counters::default());
+        let mut builder = Client::builder()
+            .pool_max_idle_per_host(config.pool_max_idle_per_host)
+            .pool_idle_timeout(Duration::from_secs_f64(config.pool_idle_timeout_secs))
+            .tcp_keepalive(config.tcp_keepalive_secs.map(Duration::from_secs_f64))
+            .connect_timeout(Duration::from_secs_f64(config.connect_timeout_secs))
+            .dns_resolver(Arc::new(CountingResolver { counters: counters.clone() }));
+        builder = if config.http2 {
+            builder.http2_adaptive_window(true)
+        } else {
+            builder.http1_only()
+        };
+
+        let client = builder.build().map_err(GameEngineError::HttpRequest)?;
+        Ok(Self { client, counters })
+    }
+
+    /// Underlying client, to build requests (send them with `send` so
+    /// they are counted)
+    pub fn client(&self) -> &Client {
+        &self.client
+    }
+
+    pub async fn send(&self, request: RequestBuilder) -> reqwest::Result<Response> {
+        self.counters.requests.fetch_add(1, Ordering::Relaxed);
+        request.send().await
+    }
+
+    pub fn stats(&self) -> HttpPoolStats {
+        let requests = self.counters.requests.load(Ordering::Relaxed);
+        let new_connections = self.counters.connects.load(Ordering::Relaxed);
+        HttpPoolStats {
+            requests,
+            new_connections,
+            reused_connections: requests.saturating_sub(new_connections),
+        }
+    }
+}
+
+impl Default for HttpClient {
+    fn default() -> Self {
+        Self::new(&HttpClientConfig::default()).expect("Failed to create HTTP client")
+    }
+}
+
+/// System resolver counting lookups: the pool only resolves a host to open
+/// a new connection
+struct CountingResolver {
+    counters: Arc<Counters>,
+}
+
+impl Resolve for CountingResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        self.counters.connects.fetch_add(1, Ordering::Relaxed);
+        Box::pin(async move {
+            // Port replaced by the connector
+            let addrs: Vec<_> = tokio::net::lookup_host((name.as_str(), 0)).await?.collect();
+            Ok(Box::new(addrs.into_iter()) as Addrs)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    /// Keep-alive HTTP/1.1 server answering `{}` to every request, counting
+    /// accepted connections
+    async fn keep_alive_server() -> (String, Arc<AtomicU64>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let accepted = Arc::new(AtomicU64::new(0));
+        let counter = accepted.clone();
+
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                counter.fetch_add(1, Ordering::SeqCst);
+                tokio::spawn(async move {
+                    let mut buf = Vec::new();
+                    let mut chunk = [0u8; 1024];
+                    while let Ok(n) = socket.read(&mut chunk).await {
+                        if n == 0 {
+                            break;
+                        }
+                        buf.extend_from_slice(&chunk[..n]);
+                        while let Some(end) = buf.windows(4).position(|w| w == b"\r\n\r\n") {
+                            buf.drain(..end + 4);
+                            let response = "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: 2\r\n\r\n{}";
+                            if socket.write_all(response.as_bytes()).await.is_err() {
+                                return;
+                            }
+                        }
+                    }
+                });
+            }
+        });
+
+        // A host name, so connections go through the counting resolver
+        (format!("http://localhost:{}", port), accepted)
+    }
+
+    #[tokio::test]
+    async fn test_sequential_requests_reuse_connection() {
+        let (url, accepted) = keep_alive_server().await;
+        let http = HttpClient::default();
+        // Clones share the pool, like providers and the ranker do
+        let other = http.clone();
+
+        for i in 0..5 {
+            let client = if i % 2 == 0 { &http } else { &other };
+            let response = client.send(client.client().get(format!("{}/search", url))).await.unwrap();
+            assert_eq!(response.text().await.unwrap(), "{}");
+        }
+
+        assert_eq!(accepted.load(Ordering::SeqCst), 1);
+        assert_eq!(
+            http.stats(),
+            HttpPoolStats { requests: 5, new_connections: 1, reused_connections: 4 }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_separate_clients_do_not_share_connections() {
+        let (url, accepted) = keep_alive_server().await;
+        for _ in 0..3 {
+            let http = HttpClient::new(&HttpClientConfig { http2: false, ..Default::default() }).unwrap();
+            http.send(http.client().get(&url)).await.unwrap().text().await.unwrap();
+        }
+        assert_eq!(accepted.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn test_disabled_pool_opens_a_connection_per_request() {
+        let (url, accepted) = keep_alive_server().await;
+        let http = HttpClient::new(&HttpClientConfig { pool_max_idle_per_host: 0, ..Default::default() }).unwrap();
+        for _ in 0..3 {
+            http.send(http.client().get(&url)).await.unwrap().text().await.unwrap();
+        }
+        assert_eq!(accepted.load(Ordering::SeqCst), 3);
+        assert_eq!(http.stats().new_connections, 3);
+    }
+}
@@ -19,7 +19,7 @@
 //!     let results = engine.search(SearchQuery {
 //!         query: "vampir survivor".to_string(),
 //!         max_results: 5,
-//!         use_cache: true,
+//!         ..Default::default()
 //!     }).await?;
 //!     
 //!     println!("Found: {} - {}%", results.game.name, results.score);
@@ -28,17 +28,28 @@
 //! ```
 
 pub mod core;
+pub mod analytics;
+pub mod build_info;
 pub mod cache;
+pub mod clock;
+pub mod config;
 pub mod ranking;
 pub mod providers;
 pub mod engine;
 pub mod error;
+pub mod exit;
+pub mod http;
+pub mod request;
+pub mod tasks;
 
 // Re-export primary types
-pub use core::{GameResult, SearchResponse, SearchResultType};
-pub use engine::{GameEngine, SearchQuery, SearchOptions};
+pub use core::{CacheMode, GameResult, PlatformFilterMode, ReleaseStatus, SlimGameResult, ScoreBreakdown, SearchResponse, SearchResultType};
+pub use engine::{GameEngine, SearchQuery, SearchOptions, DrakonMonitorConfig, EngineInfo, Readiness, WarmFailure, WarmReport};
 pub use error::{GameEngineError, Result};
+pub use request::SearchRequestOptions;
 pub use cache::GameCache;
+pub use config::EngineConfig;
+pub use build_info::{startup_banner, BuildInfo};
 
 // Python bindings
 #[cfg(feature = "python")]
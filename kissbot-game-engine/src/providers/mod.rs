@@ -1,10 +1,15 @@
 pub mod base;
+pub mod retry;
+pub mod schema;
 pub mod steam;
 
 use async_trait::async_trait;
+use std::sync::Arc;
 use crate::core::GameResult;
 use crate::error::Result;
 
+pub use retry::{RetryPolicy, RetryingProvider};
+pub use schema::{Fingerprint, SchemaDiff, SchemaMonitor};
 pub use steam::SteamProvider;
 
 /// Trait for game data providers (Steam, IGDB, RAWG, etc.)
@@ -13,6 +18,17 @@ pub trait GameProvider: Send + Sync {
     /// Search for games by query string
     async fn search(&self, query: &str) -> Result<Vec<GameResult>>;
     
+    /// Search with a locale (e.g. "fr") and country (e.g. "FR") hint.
+    /// Providers without localized data ignore them.
+    async fn search_localized(
+        &self,
+        query: &str,
+        _locale: Option<&str>,
+        _country: Option<&str>,
+    ) -> Result<Vec<GameResult>> {
+        self.search(query).await
+    }
+    
     /// Get game by ID
     async fn get_by_id(&self, id: &str) -> Result<GameResult>;
     
@@ -21,4 +37,17 @@ pub trait GameProvider: Send + Sync {
     
     /// Check if provider is available
     async fn is_available(&self) -> bool;
+    
+    /// Shape of a raw response body, for upstream API drift detection
+    fn schema_fingerprint(&self, endpoint: &str, body: &str) -> Fingerprint {
+        Fingerprint::from_body(endpoint, body)
+    }
+    
+    /// Receive the monitor live responses are sampled into
+    fn attach_schema_monitor(&self, _monitor: Arc<SchemaMonitor>) {}
+    
+    /// Fetch a reference response and fingerprint it (None if unsupported)
+    async fn sample_schema(&self) -> Result<Option<Fingerprint>> {
+        Ok(None)
+    }
 }
@@ -0,0 +1,279 @@
+use async_trait::async_trait;
+use std::collections::hash_map::RandomState;
+use std::future::Future;
+use std::hash::{BuildHasher, Hasher};
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::core::GameResult;
+use crate::error::{GameEngineError, Result};
+use crate::providers::{Fingerprint, GameProvider, SchemaMonitor};
+
+/// Retry policy for transient provider failures
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    /// Total attempts, including the first call (1 disables retries)
+    pub max_attempts: u32,
+    /// Delay before the first retry, doubled on each following one
+    pub base_delay: Duration,
+    /// Upper bound for a single delay
+    pub max_delay: Duration,
+    /// Fraction of each delay that is randomized (0.0 = none, 1.0 = full jitter)
+    pub jitter: f64,
+    /// Which errors are worth retrying
+    pub retryable: fn(&GameEngineError) -> bool,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            base_delay: Duration::from_millis(200),
+            max_delay: Duration::from_secs(2),
+            jitter: 0.5,
+            retryable: GameEngineError::is_retryable,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Policy that never retries
+    pub fn none() -> Self {
+        Self {
+            max_attempts: 1,
+            ..Default::default()
+        }
+    }
+
+    /// Delay before retry number `retry` (1-based)
+    pub fn delay(&self, retry: u32) -> Duration {
+        let exp = self
+            .base_delay
+            .saturating_mul(2u32.saturating_pow(retry.saturating_sub(1)))
+            .min(self.max_delay);
+
+        let jitter = self.jitter.clamp(0.0, 1.0);
+        if jitter == 0.0 {
+            return exp;
+        }
+        exp.mul_f64(1.0 - jitter * random_unit())
+    }
+
+    /// Run `call` until it succeeds, fails with a non-retryable error
+    /// or runs out of attempts
+    pub async fn run<T, F, Fut>(&self, provider: &str, mut call: F) -> Result<T>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        let max_attempts = self.max_attempts.max(1);
+        let mut attempt = 1;
+
+        loop {
+            match call().await {
+                Ok(value) => return Ok(value),
+                Err(e) if attempt < max_attempts && (self.retryable)(&e) => {
+                    let delay = self.delay(attempt);
+                    tracing::debug!(
+                        "🔁 Provider {} attempt {}/{} failed, retrying in {:?}: {}",
+                        provider,
+                        attempt,
+                        max_attempts,
+                        delay,
+                        e.diagnostic()
+                    );
+                    tokio::time::sleep(delay).await;
+                    attempt += 1;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+}
+
+/// Uniform value in [0, 1) from std's randomly seeded hasher
+fn random_unit() -> f64 {
+    let bits = RandomState::new().build_hasher().finish();
+    (bits >> 11) as f64 / (1u64 << 53) as f64
+}
+
+/// Provider wrapper retrying transient failures according to a `RetryPolicy`
+pub struct RetryingProvider<P> {
+    inner: P,
+    policy: RetryPolicy,
+}
+
+impl<P: GameProvider> RetryingProvider<P> {
+    pub fn new(inner: P, policy: RetryPolicy) -> Self {
+        Self { inner, policy }
+    }
+
+    pub fn inner(&self) -> &P {
+        &self.inner
+    }
+
+    pub fn policy(&self) -> &RetryPolicy {
+        &self.policy
+    }
+}
+
+#[async_trait]
+impl<P: GameProvider> GameProvider for RetryingProvider<P> {
+    async fn search(&self, query: &str) -> Result<Vec<GameResult>> {
+        self.policy.run(self.inner.name(), || self.inner.search(query)).await
+    }
+
+    async fn search_localized(
+        &self,
+        query: &str,
+        locale: Option<&str>,
+        country: Option<&str>,
+    ) -> Result<Vec<GameResult>> {
+        self.policy
+            .run(self.inner.name(), || self.inner.search_localized(query, locale, country))
+            .await
+    }
+
+    async fn get_by_id(&self, id: &str) -> Result<GameResult> {
+        self.policy.run(self.inner.name(), || self.inner.get_by_id(id)).await
+    }
+
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    async fn is_available(&self) -> bool {
+        self.inner.is_available().await
+    }
+
+    fn schema_fingerprint(&self, endpoint: &str, body: &str) -> Fingerprint {
+        self.inner.schema_fingerprint(endpoint, body)
+    }
+
+    fn attach_schema_monitor(&self, monitor: Arc<SchemaMonitor>) {
+        self.inner.attach_schema_monitor(monitor)
+    }
+
+    async fn sample_schema(&self) -> Result<Option<Fingerprint>> {
+        self.inner.sample_schema().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    /// Provider failing with `status` for the first `failures` calls
+    struct FlakyProvider {
+        failures: u32,
+        status: u16,
+        calls: AtomicU32,
+    }
+
+    impl FlakyProvider {
+        fn new(failures: u32, status: u16) -> Self {
+            Self {
+                failures,
+                status,
+                calls: AtomicU32::new(0),
+            }
+        }
+
+        fn calls(&self) -> u32 {
+            self.calls.load(Ordering::SeqCst)
+        }
+    }
+
+    #[async_trait]
+    impl GameProvider for FlakyProvider {
+        async fn search(&self, query: &str) -> Result<Vec<GameResult>> {
+            let call = self.calls.fetch_add(1, Ordering::SeqCst);
+            if call < self.failures {
+                return Err(GameEngineError::ProviderStatus {
+                    provider: "flaky".to_string(),
+                    status: self.status,
+                });
+            }
+            Ok(vec![GameResult::new("flaky", "1", query)])
+        }
+
+        async fn get_by_id(&self, id: &str) -> Result<GameResult> {
+            Err(GameEngineError::NoResults(id.to_string()))
+        }
+
+        fn name(&self) -> &str {
+            "flaky"
+        }
+
+        async fn is_available(&self) -> bool {
+            true
+        }
+    }
+
+    fn fast_policy(max_attempts: u32) -> RetryPolicy {
+        RetryPolicy {
+            max_attempts,
+            base_delay: Duration::from_millis(1),
+            max_delay: Duration::from_millis(5),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_retries_transient_failures() {
+        let provider = RetryingProvider::new(FlakyProvider::new(2, 502), fast_policy(3));
+
+        let results = provider.search("zelda").await.unwrap();
+        assert_eq!(results[0].name, "zelda");
+        assert_eq!(provider.inner().calls(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_gives_up_after_max_attempts() {
+        let provider = RetryingProvider::new(FlakyProvider::new(2, 503), fast_policy(2));
+
+        let err = provider.search("zelda").await.unwrap_err();
+        assert!(matches!(err, GameEngineError::ProviderStatus { status: 503, .. }));
+        assert_eq!(provider.inner().calls(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_not_found_fails_fast() {
+        let provider = RetryingProvider::new(FlakyProvider::new(2, 404), fast_policy(3));
+
+        assert!(provider.search("zelda").await.is_err());
+        assert_eq!(provider.inner().calls(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_custom_classification() {
+        let policy = RetryPolicy {
+            retryable: |e| matches!(e, GameEngineError::ProviderStatus { status: 404, .. }),
+            ..fast_policy(3)
+        };
+        let provider = RetryingProvider::new(FlakyProvider::new(2, 404), policy);
+
+        assert!(provider.search("zelda").await.is_ok());
+        assert_eq!(provider.inner().calls(), 3);
+    }
+
+    #[test]
+    fn test_backoff_is_exponential_and_capped() {
+        let policy = RetryPolicy {
+            base_delay: Duration::from_millis(100),
+            max_delay: Duration::from_millis(350),
+            jitter: 0.0,
+            ..Default::default()
+        };
+        assert_eq!(policy.delay(1), Duration::from_millis(100));
+        assert_eq!(policy.delay(2), Duration::from_millis(200));
+        assert_eq!(policy.delay(3), Duration::from_millis(350));
+
+        let jittered = RetryPolicy { jitter: 0.5, ..policy };
+        for _ in 0..20 {
+            let delay = jittered.delay(2);
+            assert!(delay > Duration::from_millis(100) && delay <= Duration::from_millis(200));
+        }
+    }
+}
@@ -0,0 +1,358 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeSet, HashMap};
+use std::fmt;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, RwLock};
+
+/// Shape of a provider response: sorted set of `endpoint $.json.path: type`
+/// entries. Array elements are merged under `[]`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Fingerprint {
+    pub paths: BTreeSet<String>,
+}
+
+impl Fingerprint {
+    /// Fingerprint a JSON document observed on `endpoint`
+    pub fn from_json(endpoint: &str, value: &Value) -> Self {
+        let mut fingerprint = Self::default();
+        fingerprint.walk(endpoint, "$", value, false);
+        fingerprint
+    }
+
+    /// Same as `from_json`, with top-level object keys collapsed to `*`
+    /// (for responses keyed by id, like Steam appdetails)
+    pub fn from_keyed_json(endpoint: &str, value: &Value) -> Self {
+        let mut fingerprint = Self::default();
+        fingerprint.walk(endpoint, "$", value, true);
+        fingerprint
+    }
+
+    /// Fingerprint a raw body (empty if it isn't JSON)
+    pub fn from_body(endpoint: &str, body: &str) -> Self {
+        serde_json::from_str(body)
+            .map(|value| Self::from_json(endpoint, &value))
+            .unwrap_or_default()
+    }
+
+    fn walk(&mut self, endpoint: &str, path: &str, value: &Value, wildcard_keys: bool) {
+        let kind = match value {
+            Value::Null => "null",
+            Value::Bool(_) => "bool",
+            Value::Number(_) => "number",
+            Value::String(_) => "string",
+            Value::Array(_) => "array",
+            Value::Object(_) => "object",
+        };
+        self.paths.insert(format!("{} {}: {}", endpoint, path, kind));
+
+        match value {
+            Value::Array(items) => {
+                let child = format!("{}[]", path);
+                for item in items {
+                    self.walk(endpoint, &child, item, false);
+                }
+            }
+            Value::Object(map) => {
+                for (key, item) in map {
+                    let key = if wildcard_keys { "*" } else { key.as_str() };
+                    self.walk(endpoint, &format!("{}.{}", path, key), item, false);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Merge another fingerprint into this one
+    pub fn extend(&mut self, other: &Fingerprint) {
+        self.paths.extend(other.paths.iter().cloned());
+    }
+
+    /// Paths added and removed relative to `baseline`
+    pub fn diff(&self, baseline: &Fingerprint) -> SchemaDiff {
+        SchemaDiff {
+            added: self.paths.difference(&baseline.paths).cloned().collect(),
+            removed: baseline.paths.difference(&self.paths).cloned().collect(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.paths.is_empty()
+    }
+}
+
+/// Difference between observed responses and a baseline
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SchemaDiff {
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+}
+
+impl SchemaDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty()
+    }
+}
+
+impl fmt::Display for SchemaDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for path in &self.added {
+            writeln!(f, "+ {}", path)?;
+        }
+        for path in &self.removed {
+            writeln!(f, "- {}", path)?;
+        }
+        Ok(())
+    }
+}
+
+/// Drift status of one provider
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProviderSchemaDrift {
+    pub provider: String,
+    /// Sampled responses since startup
+    pub samples: u64,
+    pub has_baseline: bool,
+    #[serde(flatten)]
+    pub diff: SchemaDiff,
+}
+
+#[derive(Debug, Default)]
+struct ProviderSchemaState {
+    baseline: Option<Fingerprint>,
+    /// Union of sampled fingerprints
+    observed: Fingerprint,
+    samples: u64,
+    /// Last reported diff, to warn only when drift changes
+    reported: SchemaDiff,
+}
+
+/// Samples live provider responses and compares their shape with a
+/// stored baseline per provider
+pub struct SchemaMonitor {
+    sample_rate_bits: AtomicU64,
+    counter: AtomicU64,
+    drift_events: AtomicU64,
+    providers: RwLock<HashMap<String, Mutex<ProviderSchemaState>>>,
+}
+
+impl SchemaMonitor {
+    pub fn new(sample_rate: f64) -> Self {
+        Self {
+            sample_rate_bits: AtomicU64::new(sample_rate.clamp(0.0, 1.0).to_bits()),
+            counter: AtomicU64::new(0),
+            drift_events: AtomicU64::new(0),
+            providers: RwLock::new(HashMap::new()),
+        }
+    }
+
+    pub fn sample_rate(&self) -> f64 {
+        f64::from_bits(self.sample_rate_bits.load(Ordering::Relaxed))
+    }
+
+    pub fn set_sample_rate(&self, sample_rate: f64) {
+        self.sample_rate_bits
+            .store(sample_rate.clamp(0.0, 1.0).to_bits(), Ordering::Relaxed);
+    }
+
+    /// Whether the next response should be fingerprinted (deterministic:
+    /// exactly `rate` of calls over time)
+    pub fn should_sample(&self) -> bool {
+        let rate = self.sample_rate();
+        if rate <= 0.0 {
+            return false;
+        }
+        let n = self.counter.fetch_add(1, Ordering::Relaxed) as f64;
+        ((n + 1.0) * rate).floor() > (n * rate).floor()
+    }
+
+    /// Number of times new drift was detected
+    pub fn drift_events(&self) -> u64 {
+        self.drift_events.load(Ordering::Relaxed)
+    }
+
+    fn with_state<T>(&self, provider: &str, f: impl FnOnce(&mut ProviderSchemaState) -> T) -> T {
+        {
+            let providers = self.providers.read().unwrap_or_else(|e| e.into_inner());
+            if let Some(state) = providers.get(provider) {
+                return f(&mut state.lock().unwrap_or_else(|e| e.into_inner()));
+            }
+        }
+
+        let mut providers = self.providers.write().unwrap_or_else(|e| e.into_inner());
+        let state = providers.entry(provider.to_string()).or_default();
+        let result = f(state.get_mut().unwrap_or_else(|e| e.into_inner()));
+        result
+    }
+
+    /// Set the baseline for a provider
+    pub fn set_baseline(&self, provider: &str, baseline: Fingerprint) {
+        self.with_state(provider, |state| {
+            state.baseline = Some(baseline);
+            state.reported = SchemaDiff::default();
+        });
+    }
+
+    /// Record a sampled fingerprint. Returns the drift when it changed
+    /// since the last report (and logs a warning).
+    pub fn observe(&self, provider: &str, fingerprint: &Fingerprint) -> Option<SchemaDiff> {
+        let diff = self.with_state(provider, |state| {
+            state.samples += 1;
+            state.observed.extend(fingerprint);
+
+            let baseline = state.baseline.as_ref()?;
+            let diff = state.observed.diff(baseline);
+            if diff == state.reported {
+                return None;
+            }
+            state.reported = diff.clone();
+            (!diff.is_empty()).then_some(diff)
+        })?;
+
+        self.drift_events.fetch_add(1, Ordering::Relaxed);
+        tracing::warn!(
+            "⚠️ Schema drift for provider {}: {} new path(s), {} missing path(s)\n{}",
+            provider,
+            diff.added.len(),
+            diff.removed.len(),
+            diff
+        );
+        Some(diff)
+    }
+
+    /// Observed fingerprint for a provider (union of samples)
+    pub fn observed(&self, provider: &str) -> Option<Fingerprint> {
+        let providers = self.providers.read().unwrap_or_else(|e| e.into_inner());
+        let state = providers.get(provider)?.lock().unwrap_or_else(|e| e.into_inner());
+        (!state.observed.is_empty()).then(|| state.observed.clone())
+    }
+
+    /// Drift status for every provider seen so far, sorted by name
+    pub fn drift(&self) -> Vec<ProviderSchemaDrift> {
+        let providers = self.providers.read().unwrap_or_else(|e| e.into_inner());
+        let mut drift: Vec<ProviderSchemaDrift> = providers
+            .iter()
+            .map(|(provider, state)| {
+                let state = state.lock().unwrap_or_else(|e| e.into_inner());
+                let diff = match (&state.baseline, state.samples) {
+                    (Some(baseline), samples) if samples > 0 => state.observed.diff(baseline),
+                    _ => SchemaDiff::default(),
+                };
+                ProviderSchemaDrift {
+                    provider: provider.clone(),
+                    samples: state.samples,
+                    has_baseline: state.baseline.is_some(),
+                    diff,
+                }
+            })
+            .collect();
+        drift.sort_by(|a, b| a.provider.cmp(&b.provider));
+        drift
+    }
+}
+
+impl Default for SchemaMonitor {
+    fn default() -> Self {
+        Self::new(0.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BASELINE_BODY: &str = r#"{
+        "730": {"success": true, "data": {"name": "Counter-Strike 2", "steam_appid": 730,
+            "genres": [{"id": "1", "description": "Action"}], "metacritic": {"score": 83}}}
+    }"#;
+
+    // `metacritic` removed, `ratings` added
+    const DRIFTED_BODY: &str = r#"{
+        "730": {"success": true, "data": {"name": "Counter-Strike 2", "steam_appid": 730,
+            "genres": [{"id": "1", "description": "Action"}], "ratings": {"esrb": "M"}}}
+    }"#;
+
+    fn fingerprint(body: &str) -> Fingerprint {
+        Fingerprint::from_keyed_json("appdetails", &serde_json::from_str(body).unwrap())
+    }
+
+    #[test]
+    fn test_fingerprint_paths() {
+        let fp = fingerprint(BASELINE_BODY);
+        assert!(fp.paths.contains("appdetails $.*.data.name: string"));
+        assert!(fp.paths.contains("appdetails $.*.data.genres[].description: string"));
+        assert!(fp.paths.contains("appdetails $.*.data.metacritic.score: number"));
+        assert!(!fp.paths.iter().any(|p| p.contains("730")));
+    }
+
+    #[test]
+    fn test_diff_added_and_removed_fields() {
+        let diff = fingerprint(DRIFTED_BODY).diff(&fingerprint(BASELINE_BODY));
+
+        assert_eq!(
+            diff.added,
+            vec![
+                "appdetails $.*.data.ratings.esrb: string".to_string(),
+                "appdetails $.*.data.ratings: object".to_string(),
+            ]
+        );
+        assert_eq!(
+            diff.removed,
+            vec![
+                "appdetails $.*.data.metacritic.score: number".to_string(),
+                "appdetails $.*.data.metacritic: object".to_string(),
+            ]
+        );
+        assert_eq!(
+            diff.to_string(),
+            "+ appdetails $.*.data.ratings.esrb: string\n\
+             + appdetails $.*.data.ratings: object\n\
+             - appdetails $.*.data.metacritic.score: number\n\
+             - appdetails $.*.data.metacritic: object\n"
+        );
+    }
+
+    #[test]
+    fn test_monitor_warns_once_per_drift() {
+        let monitor = SchemaMonitor::new(1.0);
+        monitor.set_baseline("steam", fingerprint(BASELINE_BODY));
+
+        assert!(monitor.observe("steam", &fingerprint(BASELINE_BODY)).is_none());
+        assert_eq!(monitor.drift_events(), 0);
+
+        let diff = monitor.observe("steam", &fingerprint(DRIFTED_BODY)).unwrap();
+        assert_eq!(diff.added.len(), 2);
+        // Baseline paths were seen in the first sample, so nothing is missing
+        assert!(diff.removed.is_empty());
+        assert_eq!(monitor.drift_events(), 1);
+
+        // Same drift again: no new warning
+        assert!(monitor.observe("steam", &fingerprint(DRIFTED_BODY)).is_none());
+        assert_eq!(monitor.drift_events(), 1);
+
+        let drift = monitor.drift();
+        assert_eq!(drift[0].provider, "steam");
+        assert_eq!(drift[0].samples, 3);
+        assert_eq!(drift[0].diff.added.len(), 2);
+    }
+
+    #[test]
+    fn test_monitor_reports_missing_paths() {
+        let monitor = SchemaMonitor::new(1.0);
+        monitor.set_baseline("steam", fingerprint(BASELINE_BODY));
+
+        let diff = monitor.observe("steam", &fingerprint(DRIFTED_BODY)).unwrap();
+        assert_eq!(diff.added.len(), 2);
+        assert_eq!(diff.removed.len(), 2);
+    }
+
+    #[test]
+    fn test_sample_rate() {
+        let monitor = SchemaMonitor::new(0.25);
+        let sampled = (0..100).filter(|_| monitor.should_sample()).count();
+        assert_eq!(sampled, 25);
+
+        monitor.set_sample_rate(0.0);
+        assert!(!monitor.should_sample());
+    }
+}
@@ -1,15 +1,25 @@
 use async_trait::async_trait;
-use reqwest::Client;
 use serde::Deserialize;
+use std::sync::{Arc, OnceLock};
 use std::time::Duration;
 
-use crate::core::GameResult;
-use crate::providers::GameProvider;
+use crate::core::{GameResult, ReleaseStatus};
+use crate::http::HttpClient;
+use crate::providers::{Fingerprint, GameProvider, SchemaMonitor};
 use crate::error::{Result, GameEngineError};
 
+/// Reference app used for availability checks and schema samples (CS2)
+const REFERENCE_APPID: &str = "730";
+
+/// Genre id of "Early Access", the same in every store language
+const EARLY_ACCESS_GENRE_ID: &str = "70";
+
 /// Steam API provider
 pub struct SteamProvider {
-    client: Client,
+    http: HttpClient,
+    /// Per request, the client being shared
+    timeout: Duration,
+    schema_monitor: OnceLock<Arc<SchemaMonitor>>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -54,15 +64,39 @@ struct SteamAppDetails {
     #[serde(default)]
     genres: Vec<SteamGenre>,
     #[serde(default)]
+    categories: Vec<SteamCategory>,
+    #[serde(default)]
     platforms: SteamPlatforms,
+    /// null for apps without any announced date
     #[serde(default)]
-    release_date: SteamReleaseDate,
+    release_date: Option<SteamReleaseDate>,
     #[serde(default)]
     metacritic: Option<SteamMetacritic>,
+    /// Review count, missing for apps without reviews
+    #[serde(default)]
+    recommendations: Option<SteamRecommendations>,
 }
 
 #[derive(Debug, Deserialize)]
 struct SteamGenre {
+    /// String in practice, kept loose so a number does not fail the whole app
+    #[serde(default)]
+    id: serde_json::Value,
+    description: String,
+}
+
+impl SteamGenre {
+    fn is_early_access(&self) -> bool {
+        let id = match &self.id {
+            serde_json::Value::String(id) => id.clone(),
+            other => other.to_string(),
+        };
+        id == EARLY_ACCESS_GENRE_ID || self.description.eq_ignore_ascii_case("early access")
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct SteamCategory {
     description: String,
 }
 
@@ -78,6 +112,8 @@ struct SteamPlatforms {
 
 #[derive(Debug, Deserialize, Default)]
 struct SteamReleaseDate {
+    #[serde(default)]
+    coming_soon: bool,
     #[serde(default)]
     date: String,
 }
@@ -87,15 +123,59 @@ struct SteamMetacritic {
     score: i32,
 }
 
+#[derive(Debug, Deserialize)]
+struct SteamRecommendations {
+    #[serde(default)]
+    total: u64,
+}
+
 impl SteamProvider {
-    /// Create new Steam provider
-    pub fn new(api_key: Option<String>) -> Self {
-        let client = Client::builder()
-            .timeout(Duration::from_secs(10))
-            .build()
-            .expect("Failed to create HTTP client");
+    /// Create new Steam provider (the key is kept for existing callers: the
+    /// store API needs none)
+    pub fn new(_api_key: Option<String>) -> Self {
+        Self::with_timeout(Duration::from_secs(10))
+    }
+    
+    /// Create new Steam provider with a custom HTTP timeout
+    pub fn with_timeout(timeout: Duration) -> Self {
+        Self::with_client(HttpClient::default(), timeout)
+    }
+    
+    /// Create new Steam provider on a shared HTTP client (the store API
+    /// needs no key)
+    pub fn with_client(http: HttpClient, timeout: Duration) -> Self {
+        Self {
+            http,
+            timeout,
+            schema_monitor: OnceLock::new(),
+        }
+    }
+    
+    /// Fingerprint a sampled fraction of live responses
+    fn sample_response(&self, endpoint: &str, body: &str) {
+        if let Some(monitor) = self.schema_monitor.get() {
+            if monitor.should_sample() {
+                monitor.observe(self.name(), &self.schema_fingerprint(endpoint, body));
+            }
+        }
+    }
+    
+    /// Fetch a response body as text
+    async fn fetch_body(&self, url: &str, what: &str) -> Result<String> {
+        let request = self.http.client().get(url).timeout(self.timeout);
+        let response = self.http.send(request).await?;
+        
+        if !response.status().is_success() {
+            return Err(GameEngineError::ProviderStatus {
+                provider: "steam".to_string(),
+                status: response.status().as_u16(),
+            });
+        }
         
-        Self { client }
+        response.text().await.map_err(|e| GameEngineError::Provider {
+            provider: "steam".to_string(),
+            message: format!("{} body read failed: {}", what, e),
+        })
     }
     
     /// Search Steam store
@@ -105,26 +185,11 @@ impl SteamProvider {
             urlencoding::encode(query)
         );
         
-        let response = self.client
-            .get(&url)
-            .send()
-            .await
-            .map_err(|e| GameEngineError::Provider {
-                provider: "steam".to_string(),
-                message: format!("Search request failed: {}", e),
-            })?;
-        
-        if !response.status().is_success() {
-            return Err(GameEngineError::Provider {
-                provider: "steam".to_string(),
-                message: format!("HTTP {}", response.status()),
-            });
-        }
+        let body = self.fetch_body(&url, "Search").await?;
+        self.sample_response("search", &body);
         
         // Steam API returns array directly, not wrapped in object
-        let apps: Vec<SteamApp> = response
-            .json()
-            .await
+        let apps: Vec<SteamApp> = serde_json::from_str(&body)
             .map_err(|e| GameEngineError::Provider {
                 provider: "steam".to_string(),
                 message: format!("Invalid JSON: {}", e),
@@ -135,23 +200,27 @@ impl SteamProvider {
     
     /// Get Steam app details
     async fn get_app_details(&self, appid: &str) -> Result<GameResult> {
-        let url = format!(
-            "https://store.steampowered.com/api/appdetails?appids={}",
-            appid
-        );
+        self.get_app_details_localized(appid, None, None).await
+    }
+    
+    /// Get Steam app details in a given language (`l=`) and store region (`cc=`)
+    async fn get_app_details_localized(
+        &self,
+        appid: &str,
+        locale: Option<&str>,
+        country: Option<&str>,
+    ) -> Result<GameResult> {
+        let url = app_details_url(appid, locale, country);
         
-        let response = self.client
-            .get(&url)
-            .send()
-            .await
-            .map_err(|e| GameEngineError::Provider {
-                provider: "steam".to_string(),
-                message: format!("Details request failed: {}", e),
-            })?;
+        let body = self.fetch_body(&url, "Details").await?;
+        self.sample_response("appdetails", &body);
         
-        let details_response: SteamAppDetailsResponse = response
-            .json()
-            .await
+        self.parse_app_details(appid, &body)
+    }
+    
+    /// Parse an appdetails response body for `appid`
+    fn parse_app_details(&self, appid: &str, body: &str) -> Result<GameResult> {
+        let details_response: SteamAppDetailsResponse = serde_json::from_str(body)
             .map_err(|e| GameEngineError::Provider {
                 provider: "steam".to_string(),
                 message: format!("Invalid JSON: {}", e),
@@ -193,12 +262,14 @@ impl SteamProvider {
             .map(|g| g.description.clone())
             .collect();
         
+        let release_date = details.release_date.as_ref().map(|r| r.date.as_str()).unwrap_or_default();
+        
         // Extract year from date (format: "MMM DD, YYYY" or just "YYYY")
-        let year = details.release_date.date
+        let year = release_date
             .split(',')
             .last()
             .and_then(|s| s.trim().parse::<i32>().ok())
-            .or_else(|| details.release_date.date.parse::<i32>().ok());
+            .or_else(|| release_date.parse::<i32>().ok());
         
         let mut game = GameResult::new(
             "steam",
@@ -208,13 +279,15 @@ impl SteamProvider {
         
         game.short_description = details.short_description.clone();
         game.description = details.detailed_description.clone();
-        game.release_date = details.release_date.date.clone();
+        game.release_date = release_date.to_string();
+        game.release_status = Some(release_status(details));
         game.year = year;
         game.developers = details.developers.clone();
         game.publishers = details.publishers.clone();
         game.genres = genres;
         game.platforms = platforms;
         game.metacritic_score = details.metacritic.as_ref().map(|m| m.score);
+        game.popularity = details.recommendations.as_ref().map(|r| r.total as f64);
         game.steam_appid = Some(details.steam_appid.to_string());
         game.header_image = details.header_image.clone();
         game.url = format!("https://store.steampowered.com/app/{}", details.steam_appid);
@@ -223,16 +296,92 @@ impl SteamProvider {
     }
 }
 
+/// Release status of an app: not out yet when flagged `coming_soon` or
+/// without a release date, else early access when in that genre/category
+fn release_status(details: &SteamAppDetails) -> ReleaseStatus {
+    match &details.release_date {
+        Some(release) if release.coming_soon => {
+            let date = release.date.trim();
+            ReleaseStatus::ComingSoon { date: (!date.is_empty()).then(|| date.to_string()) }
+        }
+        None => ReleaseStatus::ComingSoon { date: None },
+        Some(_) => {
+            let early_access = details.genres.iter().any(SteamGenre::is_early_access)
+                || details.categories.iter().any(|c| c.description.eq_ignore_ascii_case("early access"));
+            if early_access {
+                ReleaseStatus::EarlyAccess
+            } else {
+                ReleaseStatus::Released
+            }
+        }
+    }
+}
+
+/// Steam appdetails URL; `l=`/`cc=` only added when set
+fn app_details_url(appid: &str, locale: Option<&str>, country: Option<&str>) -> String {
+    let mut url = format!(
+        "https://store.steampowered.com/api/appdetails?appids={}",
+        appid
+    );
+    
+    if let Some(locale) = locale {
+        url.push_str(&format!("&l={}", urlencoding::encode(&steam_language(locale))));
+    }
+    if let Some(country) = country {
+        url.push_str(&format!("&cc={}", urlencoding::encode(&country.to_uppercase())));
+    }
+    
+    url
+}
+
+/// Map an ISO locale ("fr", "pt-BR") to Steam's language name ("french", "brazilian").
+/// Unknown values are passed through lowercased.
+fn steam_language(locale: &str) -> String {
+    let locale = locale.trim().to_lowercase().replace('_', "-");
+    let language = match locale.as_str() {
+        "pt-br" => "brazilian",
+        "zh-tw" | "zh-hk" => "tchinese",
+        "es-419" | "es-mx" => "latam",
+        _ => match locale.split('-').next().unwrap_or_default() {
+            "en" => "english",
+            "fr" => "french",
+            "de" => "german",
+            "es" => "spanish",
+            "it" => "italian",
+            "pt" => "portuguese",
+            "ru" => "russian",
+            "pl" => "polish",
+            "nl" => "dutch",
+            "tr" => "turkish",
+            "uk" => "ukrainian",
+            "ja" => "japanese",
+            "ko" => "koreana",
+            "zh" => "schinese",
+            _ => return locale,
+        },
+    };
+    language.to_string()
+}
+
 #[async_trait]
 impl GameProvider for SteamProvider {
     async fn search(&self, query: &str) -> Result<Vec<GameResult>> {
+        self.search_localized(query, None, None).await
+    }
+    
+    async fn search_localized(
+        &self,
+        query: &str,
+        locale: Option<&str>,
+        country: Option<&str>,
+    ) -> Result<Vec<GameResult>> {
         let apps = self.search_steam(query).await?;
         
         let mut results = Vec::new();
         
         // Fetch details for top results (limit to avoid rate limiting)
         for app in apps.iter().take(10) {
-            match self.get_app_details(&app.appid.to_string()).await {
+            match self.get_app_details_localized(&app.appid.to_string(), locale, country).await {
                 Ok(game) => results.push(game),
                 Err(e) => {
                     tracing::warn!("Failed to fetch details for {}: {}", app.name, e);
@@ -262,8 +411,30 @@ impl GameProvider for SteamProvider {
     }
     
     async fn is_available(&self) -> bool {
-        // Try to fetch a known app (CS2 - 730)
-        self.get_app_details("730").await.is_ok()
+        self.get_app_details(REFERENCE_APPID).await.is_ok()
+    }
+    
+    fn schema_fingerprint(&self, endpoint: &str, body: &str) -> Fingerprint {
+        match serde_json::from_str(body) {
+            // appdetails is keyed by appid
+            Ok(value) if endpoint == "appdetails" => Fingerprint::from_keyed_json(endpoint, &value),
+            Ok(value) => Fingerprint::from_json(endpoint, &value),
+            Err(_) => Fingerprint::default(),
+        }
+    }
+    
+    fn attach_schema_monitor(&self, monitor: Arc<SchemaMonitor>) {
+        let _ = self.schema_monitor.set(monitor);
+    }
+    
+    async fn sample_schema(&self) -> Result<Option<Fingerprint>> {
+        let search_url = "https://steamcommunity.com/actions/SearchApps/counter-strike";
+        let search = self.fetch_body(search_url, "Search").await?;
+        let details = self.fetch_body(&app_details_url(REFERENCE_APPID, None, None), "Details").await?;
+        
+        let mut fingerprint = self.schema_fingerprint("search", &search);
+        fingerprint.extend(&self.schema_fingerprint("appdetails", &details));
+        Ok(Some(fingerprint))
     }
 }
 
@@ -271,6 +442,98 @@ impl GameProvider for SteamProvider {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_app_details_url() {
+        assert_eq!(
+            app_details_url("730", None, None),
+            "https://store.steampowered.com/api/appdetails?appids=730"
+        );
+        assert_eq!(
+            app_details_url("730", Some("fr"), Some("fr")),
+            "https://store.steampowered.com/api/appdetails?appids=730&l=french&cc=FR"
+        );
+        assert_eq!(
+            app_details_url("730", None, Some("ca")),
+            "https://store.steampowered.com/api/appdetails?appids=730&cc=CA"
+        );
+    }
+
+    #[test]
+    fn test_appdetails_fingerprint_ignores_appid() {
+        let provider = SteamProvider::new(None);
+        let a = provider.schema_fingerprint("appdetails", r#"{"730": {"success": true}}"#);
+        let b = provider.schema_fingerprint("appdetails", r#"{"570": {"success": true}}"#);
+        assert_eq!(a, b);
+        assert!(a.paths.contains("appdetails $.*.success: bool"));
+    }
+
+    fn fixture(appid: &str, data: &str) -> GameResult {
+        let body = format!(r#"{{"{}": {{"success": true, "data": {}}}}}"#, appid, data);
+        SteamProvider::new(None).parse_app_details(appid, &body).unwrap()
+    }
+
+    #[test]
+    fn test_release_status_early_access() {
+        let game = fixture("1145350", r#"{
+            "name": "Hades II", "steam_appid": 1145350,
+            "genres": [{"id": "23", "description": "Indé"}, {"id": "70", "description": "Accès anticipé"}],
+            "release_date": {"coming_soon": false, "date": "May 6, 2024"}
+        }"#);
+        assert_eq!(game.release_status, Some(ReleaseStatus::EarlyAccess));
+        assert_eq!(game.year, Some(2024));
+
+        let by_category = fixture("1", r#"{
+            "name": "Game", "steam_appid": 1,
+            "categories": [{"id": 2, "description": "Single-player"}, {"id": 99, "description": "Early Access"}],
+            "release_date": {"coming_soon": false, "date": "2023"}
+        }"#);
+        assert_eq!(by_category.release_status, Some(ReleaseStatus::EarlyAccess));
+    }
+
+    #[test]
+    fn test_release_status_unreleased() {
+        let game = fixture("2", r#"{
+            "name": "Upcoming", "steam_appid": 2,
+            "genres": [{"id": "70", "description": "Early Access"}],
+            "release_date": {"coming_soon": true, "date": "Q1 2027"}
+        }"#);
+        assert_eq!(game.release_status, Some(ReleaseStatus::ComingSoon { date: Some("Q1 2027".to_string()) }));
+        assert_eq!(game.year, None);
+        assert!(game.is_unreleased());
+
+        let undated = fixture("3", r#"{"name": "Announced", "steam_appid": 3, "release_date": null}"#);
+        assert_eq!(undated.release_status, Some(ReleaseStatus::ComingSoon { date: None }));
+        assert_eq!(undated.release_date, "");
+    }
+
+    #[test]
+    fn test_release_status_released() {
+        let game = fixture("730", r#"{
+            "name": "Counter-Strike 2", "steam_appid": 730,
+            "genres": [{"id": "1", "description": "Action"}],
+            "categories": [{"id": 1, "description": "Multi-player"}],
+            "release_date": {"coming_soon": false, "date": "21 Aug, 2012"}
+        }"#);
+        assert_eq!(game.release_status, Some(ReleaseStatus::Released));
+        assert_eq!(game.year, Some(2012));
+        assert_eq!(game.genres, vec!["Action".to_string()]);
+        assert_eq!(game.popularity, None);
+    }
+
+    #[test]
+    fn test_recommendations_as_popularity() {
+        let game = fixture("2280", r#"{"name": "DOOM", "steam_appid": 2280, "recommendations": {"total": 158243}}"#);
+        assert_eq!(game.popularity, Some(158243.0));
+    }
+
+    #[test]
+    fn test_steam_language() {
+        assert_eq!(steam_language("fr-FR"), "french");
+        assert_eq!(steam_language("pt_BR"), "brazilian");
+        assert_eq!(steam_language("ko"), "koreana");
+        assert_eq!(steam_language("Swedish"), "swedish");
+    }
+
     #[tokio::test]
     #[ignore] // Requires network access
     async fn test_steam_search() {
@@ -3,9 +3,10 @@ use pyo3::types::PyDict;
 use std::sync::Arc;
 use tokio::runtime::Runtime;
 
-use crate::{GameEngine as RustGameEngine, SearchQuery as RustSearchQuery};
-use crate::core::{GameResult as RustGameResult, SearchResponse as RustSearchResponse};
-use crate::providers::SteamProvider;
+use crate::config::CacheKind;
+use crate::{CacheMode, EngineConfig, GameEngine as RustGameEngine, GameEngineError, SearchRequestOptions};
+use crate::exit::{self, ErrorCode};
+use crate::core::{GameResult as RustGameResult, ScoreBreakdown, SearchResponse as RustSearchResponse, SlimGameResult};
 
 /// Python wrapper for GameEngine
 #[pyclass]
@@ -17,44 +18,83 @@ struct GameEngine {
 #[pymethods]
 impl GameEngine {
     /// Create new GameEngine
+    ///
+    /// `config` is a dict shaped like the engine config file
+    /// (`{"cache": {"path": ...}, "providers": [{"name": "steam"}], "search": {...}}`);
+    /// `db_path`, when given, overrides `config["cache"]["path"]`, and
+    /// `backend` ("sqlite", "memory" or "none") `config["cache"]["backend"]`:
+    /// `GameEngine(backend="memory")` keeps everything in the process.
     #[new]
-    fn new(db_path: String) -> PyResult<Self> {
+    #[pyo3(signature = (db_path=None, config=None, backend=None))]
+    fn new(py: Python, db_path: Option<String>, config: Option<&PyDict>, backend: Option<&str>) -> PyResult<Self> {
+        let mut config = match config {
+            Some(dict) => {
+                let json: String = py.import("json")?.call_method1("dumps", (dict,))?.extract()?;
+                EngineConfig::from_json(&json).map_err(to_py_err)?
+            }
+            None => EngineConfig::default(),
+        };
+        if let Some(db_path) = db_path {
+            config.cache.path = db_path;
+        }
+        if let Some(backend) = backend {
+            config.cache.backend = match backend {
+                "sqlite" => CacheKind::Sqlite,
+                "memory" => CacheKind::Memory,
+                "none" => CacheKind::None,
+                other => {
+                    return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                        "Unknown cache backend '{}' (expected sqlite, memory or none)",
+                        other
+                    )))
+                }
+            };
+        }
+        
         let runtime = Arc::new(
             Runtime::new()
                 .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(e.to_string()))?
         );
         
-        let engine = runtime.block_on(async {
-            let mut engine = RustGameEngine::new(&db_path).await
-                .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(e.to_string()))?;
-            
-            // Add Steam provider
-            let steam = Arc::new(SteamProvider::new(None));
-            engine.add_provider(steam);
-            
-            Ok::<_, PyErr>(engine)
-        })?;
+        let engine = Arc::new(runtime.block_on(RustGameEngine::from_config(&config)).map_err(to_py_err)?);
+        // Background refreshes run on the engine's own runtime
+        engine.enable_stale_refresh();
         
-        Ok(Self {
-            engine: Arc::new(engine),
-            runtime,
-        })
+        Ok(Self { engine, runtime })
     }
     
     /// Search for a game
-    fn search(&self, query: String, max_results: Option<usize>, use_cache: Option<bool>) -> PyResult<PyObject> {
-        let search_query = RustSearchQuery {
-            query,
-            max_results: max_results.unwrap_or(5),
-            use_cache: use_cache.unwrap_or(true),
-        };
+    ///
+    /// Keyword arguments are the options of the HTTP API: `max_results`,
+    /// `cache_mode` ("use", "bypass" or "off"), `locale`, `country`,
+    /// `providers`, `exclude_unreleased`, `platforms` (e.g. ["ps5", "switch"])
+    /// and `explain` (adds `score_breakdown`).
+    /// `use_cache=False` is kept as a shorthand for `cache_mode="off"`.
+    #[pyo3(signature = (query, use_cache=None, **kwargs))]
+    fn search(
+        &self,
+        query: String,
+        use_cache: Option<bool>,
+        kwargs: Option<&PyDict>,
+    ) -> PyResult<PyObject> {
+        let mut options = serde_json::Map::new();
+        for (name, value) in kwargs.into_iter().flatten() {
+            options.insert(name.extract()?, py_to_json(value)?);
+        }
+        let options = SearchRequestOptions::from_kwargs(options)
+            .map_err(PyErr::new::<pyo3::exceptions::PyTypeError, _>)?;
+        
+        let default_mode = if use_cache == Some(false) { CacheMode::Off } else { CacheMode::Use };
+        let search_query = options.to_query(query, default_mode);
         
         let engine = self.engine.clone();
         let result = self.runtime.block_on(async move {
-            engine.search(search_query).await
-                .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(e.to_string()))
+            engine.search(search_query).await.map_err(to_py_err)
         })?;
         
+        let mut result = result;
+        options.apply(&mut result);
+        
         // Convert to Python dict
         Python::with_gil(|py| {
             search_response_to_py(py, &result)
@@ -65,35 +105,66 @@ impl GameEngine {
     fn cache_stats(&self) -> PyResult<PyObject> {
         let engine = self.engine.clone();
         let stats = self.runtime.block_on(async move {
-            engine.cache_stats().await
-                .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(e.to_string()))
+            engine.cache_stats().await.map_err(to_py_err)
         })?;
         
         Python::with_gil(|py| {
             let dict = PyDict::new(py);
             dict.set_item("total_entries", stats.total_entries)?;
+            dict.set_item("no_result_entries", stats.no_result_entries)?;
             dict.set_item("total_hits", stats.total_hits)?;
             dict.set_item("avg_hit_count", stats.avg_hit_count)?;
             Ok(dict.into())
         })
     }
     
+    /// Full record of an alternative, fetched again from its provider
+    fn rehydrate(&self, provider: String, id: String) -> PyResult<PyObject> {
+        let engine = self.engine.clone();
+        let game = SlimGameResult::from_game(&RustGameResult::new(provider, id, ""), None);
+        let full = self.runtime.block_on(async move {
+            engine.rehydrate(&game).await.map_err(to_py_err)
+        })?;
+        
+        Python::with_gil(|py| game_result_to_py(py, &full))
+    }
+    
+    /// Expand `acronym` to `expansion` in queries before ranking
+    fn add_alias(&self, acronym: &str, expansion: &str) {
+        self.engine.add_alias(acronym, expansion);
+    }
+    
     /// Clean up old cache entries
     fn cleanup_cache(&self, max_age_days: i64) -> PyResult<u64> {
         let engine = self.engine.clone();
         self.runtime.block_on(async move {
-            engine.cleanup_cache(max_age_days).await
-                .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(e.to_string()))
+            engine.cleanup_cache(max_age_days).await.map_err(to_py_err)
         })
     }
 }
 
+/// Python exception for an engine error, chosen by its `ErrorCode`
+fn to_py_err(err: GameEngineError) -> PyErr {
+    use pyo3::exceptions::{PyConnectionError, PyLookupError, PyRuntimeError, PyValueError};
+    
+    let (code, message) = exit::describe(&err);
+    match code {
+        ErrorCode::NoResults => PyLookupError::new_err(message),
+        ErrorCode::InvalidInput | ErrorCode::Config => PyValueError::new_err(message),
+        ErrorCode::ProviderFailed | ErrorCode::ProviderUnavailable | ErrorCode::RankerUnavailable => {
+            PyConnectionError::new_err(message)
+        }
+        ErrorCode::Cache | ErrorCode::Internal => PyRuntimeError::new_err(message),
+    }
+}
+
 /// Convert GameResult to Python dict
 fn game_result_to_py(py: Python, game: &RustGameResult) -> PyResult<PyObject> {
     let dict = PyDict::new(py);
     dict.set_item("provider", &game.provider)?;
     dict.set_item("id", &game.id)?;
     dict.set_item("name", &game.name)?;
+    dict.set_item("aliases", &game.aliases)?;
     dict.set_item("short_description", &game.short_description)?;
     dict.set_item("description", &game.description)?;
     dict.set_item("release_date", &game.release_date)?;
@@ -105,10 +176,27 @@ fn game_result_to_py(py: Python, game: &RustGameResult) -> PyResult<PyObject> {
     dict.set_item("tags", &game.tags)?;
     dict.set_item("metacritic_score", game.metacritic_score)?;
     dict.set_item("rating", game.rating)?;
+    dict.set_item("popularity", game.popularity)?;
     dict.set_item("steam_appid", &game.steam_appid)?;
     dict.set_item("igdb_id", &game.igdb_id)?;
     dict.set_item("header_image", &game.header_image)?;
     dict.set_item("url", &game.url)?;
+    dict.set_item("sources", &game.sources)?;
+    dict.set_item("release_status", game.release_status.as_ref().map(|s| s.as_str()))?;
+    Ok(dict.into())
+}
+
+/// Convert a compact alternative to Python dict
+fn slim_game_to_py(py: Python, game: &SlimGameResult) -> PyResult<PyObject> {
+    let dict = PyDict::new(py);
+    dict.set_item("provider", &game.provider)?;
+    dict.set_item("id", &game.id)?;
+    dict.set_item("name", &game.name)?;
+    dict.set_item("year", game.year)?;
+    dict.set_item("score", game.score)?;
+    dict.set_item("url", &game.url)?;
+    dict.set_item("header_image", &game.header_image)?;
+    dict.set_item("release_status", game.release_status.as_ref().map(|s| s.as_str()))?;
     Ok(dict.into())
 }
 
@@ -122,18 +210,91 @@ fn search_response_to_py(py: Python, response: &RustSearchResponse) -> PyResult<
     // Alternatives
     let alternatives: PyResult<Vec<PyObject>> = response.alternatives
         .iter()
-        .map(|alt| game_result_to_py(py, alt))
+        .map(|alt| slim_game_to_py(py, alt))
         .collect();
     dict.set_item("alternatives", alternatives?)?;
     
+    // Editions of the game (collapse_editions)
+    let editions: PyResult<Vec<PyObject>> = response.editions
+        .iter()
+        .map(|edition| slim_game_to_py(py, edition))
+        .collect();
+    dict.set_item("editions", editions?)?;
+    
     dict.set_item("from_cache", response.from_cache)?;
     dict.set_item("latency_ms", response.latency_ms)?;
     dict.set_item("provider", &response.provider)?;
     dict.set_item("ranking_method", &response.ranking_method)?;
+    dict.set_item("cache_mode", response.cache_mode.as_str())?;
+    dict.set_item("platform_filter_ignored", response.platform_filter_ignored)?;
+    dict.set_item("refreshing", response.refreshing)?;
+    if let Some(breakdown) = &response.score_breakdown {
+        dict.set_item("score_breakdown", score_breakdown_to_py(py, breakdown)?)?;
+    }
     
     Ok(dict.into())
 }
 
+/// Convert ScoreBreakdown to Python dict
+fn score_breakdown_to_py(py: Python, breakdown: &ScoreBreakdown) -> PyResult<PyObject> {
+    let dict = PyDict::new(py);
+    dict.set_item("ranker", &breakdown.ranker)?;
+    dict.set_item("similarity", breakdown.similarity)?;
+    dict.set_item("score", breakdown.score())?;
+    dict.set_item("candidates", breakdown.candidates)?;
+    let penalties: PyResult<Vec<PyObject>> = breakdown
+        .penalties
+        .iter()
+        .map(|penalty| {
+            let item = PyDict::new(py);
+            item.set_item("reason", &penalty.reason)?;
+            item.set_item("points", penalty.points)?;
+            Ok(item.into())
+        })
+        .collect();
+    dict.set_item("penalties", penalties?)?;
+    let bonuses: PyResult<Vec<PyObject>> = breakdown
+        .bonuses
+        .iter()
+        .map(|bonus| {
+            let item = PyDict::new(py);
+            item.set_item("reason", &bonus.reason)?;
+            item.set_item("points", bonus.points)?;
+            Ok(item.into())
+        })
+        .collect();
+    dict.set_item("bonuses", bonuses?)?;
+    dict.set_item("normalized_query", &breakdown.normalized_query)?;
+    dict.set_item("normalized_candidate", &breakdown.normalized_candidate)?;
+    dict.set_item("matched_alias", &breakdown.matched_alias)?;
+    dict.set_item("expanded_query", &breakdown.expanded_query)?;
+    Ok(dict.into())
+}
+
+/// Python keyword argument value as JSON (None, bool, int, float, str, list)
+fn py_to_json(value: &PyAny) -> PyResult<serde_json::Value> {
+    use serde_json::Value;
+    
+    if value.is_none() {
+        Ok(Value::Null)
+    } else if let Ok(b) = value.downcast::<pyo3::types::PyBool>() {
+        Ok(Value::Bool(b.is_true()))
+    } else if let Ok(i) = value.extract::<i64>() {
+        Ok(Value::from(i))
+    } else if let Ok(f) = value.extract::<f64>() {
+        Ok(Value::from(f))
+    } else if let Ok(s) = value.extract::<String>() {
+        Ok(Value::String(s))
+    } else if let Ok(items) = value.extract::<Vec<&PyAny>>() {
+        items.into_iter().map(py_to_json).collect::<PyResult<Vec<_>>>().map(Value::Array)
+    } else {
+        Err(PyErr::new::<pyo3::exceptions::PyTypeError, _>(format!(
+            "unsupported argument type: {}",
+            value.get_type().name()?
+        )))
+    }
+}
+
 /// Python module
 #[pymodule]
 fn kissbot_game_engine(_py: Python, m: &PyModule) -> PyResult<()> {
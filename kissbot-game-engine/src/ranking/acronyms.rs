@@ -0,0 +1,263 @@
+//! Gaming acronyms viewers type ("gta 5", "botw", "cod mw2"), expanded to
+//! the words the rankers can match against store titles
+
+use std::collections::HashMap;
+
+/// Built-in acronyms; sequels share the series expansion plus their number
+/// ("gta 5") unless a title spells the number differently
+const DEFAULT_ACRONYMS: &[(&str, &str)] = &[
+    // Shooters
+    ("cod", "Call of Duty"),
+    ("mw", "Modern Warfare"),
+    ("mw2", "Modern Warfare 2"),
+    ("mw3", "Modern Warfare 3"),
+    ("bo2", "Black Ops II"),
+    ("bo3", "Black Ops III"),
+    ("bo4", "Black Ops 4"),
+    ("cs", "Counter-Strike"),
+    ("cs2", "Counter-Strike 2"),
+    ("csgo", "Counter-Strike: Global Offensive"),
+    ("tf2", "Team Fortress 2"),
+    ("l4d", "Left 4 Dead"),
+    ("l4d2", "Left 4 Dead 2"),
+    ("hl", "Half-Life"),
+    ("hl2", "Half-Life 2"),
+    ("hla", "Half-Life: Alyx"),
+    ("bf", "Battlefield"),
+    ("bf1", "Battlefield 1"),
+    ("bf4", "Battlefield 4"),
+    ("bf2042", "Battlefield 2042"),
+    ("r6", "Rainbow Six"),
+    ("r6s", "Tom Clancy's Rainbow Six Siege"),
+    ("ow", "Overwatch"),
+    ("ow2", "Overwatch 2"),
+    ("pubg", "PUBG: Battlegrounds"),
+    ("eft", "Escape from Tarkov"),
+    ("drg", "Deep Rock Galactic"),
+    ("hd2", "Helldivers 2"),
+    // Open world and action
+    ("gta", "Grand Theft Auto"),
+    ("gta4", "Grand Theft Auto IV"),
+    ("gtaiv", "Grand Theft Auto IV"),
+    ("gta5", "Grand Theft Auto V"),
+    ("gtav", "Grand Theft Auto V"),
+    ("gtasa", "Grand Theft Auto: San Andreas"),
+    ("rdr", "Red Dead Redemption"),
+    ("rdr2", "Red Dead Redemption 2"),
+    ("tlou", "The Last of Us"),
+    ("tlou2", "The Last of Us Part II"),
+    ("gow", "God of War"),
+    ("dmc", "Devil May Cry"),
+    ("dmc5", "Devil May Cry 5"),
+    ("mgs", "Metal Gear Solid"),
+    ("mgs3", "Metal Gear Solid 3: Snake Eater"),
+    ("mgs5", "Metal Gear Solid V: The Phantom Pain"),
+    ("mgsv", "Metal Gear Solid V: The Phantom Pain"),
+    ("mgr", "Metal Gear Rising: Revengeance"),
+    ("re", "Resident Evil"),
+    ("re2", "Resident Evil 2"),
+    ("re4", "Resident Evil 4"),
+    ("re7", "Resident Evil 7"),
+    ("re8", "Resident Evil Village"),
+    ("cp2077", "Cyberpunk 2077"),
+    ("nms", "No Man's Sky"),
+    ("sot", "Sea of Thieves"),
+    // Nintendo
+    ("loz", "The Legend of Zelda"),
+    ("botw", "The Legend of Zelda: Breath of the Wild"),
+    ("totk", "The Legend of Zelda: Tears of the Kingdom"),
+    ("oot", "The Legend of Zelda: Ocarina of Time"),
+    ("alttp", "The Legend of Zelda: A Link to the Past"),
+    ("smb", "Super Mario Bros."),
+    ("sm64", "Super Mario 64"),
+    ("smo", "Super Mario Odyssey"),
+    ("ttyd", "Paper Mario: The Thousand-Year Door"),
+    ("mk8", "Mario Kart 8"),
+    ("mk8dx", "Mario Kart 8 Deluxe"),
+    ("ssbu", "Super Smash Bros. Ultimate"),
+    ("ssbm", "Super Smash Bros. Melee"),
+    ("acnh", "Animal Crossing: New Horizons"),
+    ("dkc", "Donkey Kong Country"),
+    ("fe", "Fire Emblem"),
+    ("fe3h", "Fire Emblem: Three Houses"),
+    ("xc2", "Xenoblade Chronicles 2"),
+    ("xc3", "Xenoblade Chronicles 3"),
+    // RPGs
+    ("ff", "Final Fantasy"),
+    ("ff7", "Final Fantasy VII"),
+    ("ffvii", "Final Fantasy VII"),
+    ("ff7r", "Final Fantasy VII Remake"),
+    ("ffx", "Final Fantasy X"),
+    ("ffxii", "Final Fantasy XII"),
+    ("ff14", "Final Fantasy XIV"),
+    ("ffxiv", "Final Fantasy XIV"),
+    ("ff15", "Final Fantasy XV"),
+    ("ffxv", "Final Fantasy XV"),
+    ("ff16", "Final Fantasy XVI"),
+    ("ffxvi", "Final Fantasy XVI"),
+    ("kh", "Kingdom Hearts"),
+    ("kh2", "Kingdom Hearts II"),
+    ("kh3", "Kingdom Hearts III"),
+    ("p4g", "Persona 4 Golden"),
+    ("p3r", "Persona 3 Reload"),
+    ("p5", "Persona 5"),
+    ("p5r", "Persona 5 Royal"),
+    ("smt", "Shin Megami Tensei"),
+    ("tes", "The Elder Scrolls"),
+    ("tes5", "The Elder Scrolls V: Skyrim"),
+    ("eso", "The Elder Scrolls Online"),
+    ("fo3", "Fallout 3"),
+    ("fo4", "Fallout 4"),
+    ("fo76", "Fallout 76"),
+    ("fnv", "Fallout: New Vegas"),
+    ("tw3", "The Witcher 3: Wild Hunt"),
+    ("bg3", "Baldur's Gate 3"),
+    ("dos2", "Divinity: Original Sin 2"),
+    ("kotor", "Star Wars: Knights of the Old Republic"),
+    ("kotor2", "Star Wars: Knights of the Old Republic II"),
+    ("ds2", "Dark Souls II"),
+    ("ds3", "Dark Souls III"),
+    ("er", "Elden Ring"),
+    ("mh", "Monster Hunter"),
+    ("mhw", "Monster Hunter: World"),
+    ("mhr", "Monster Hunter Rise"),
+    ("poe", "Path of Exile"),
+    ("poe2", "Path of Exile 2"),
+    // Online
+    ("lol", "League of Legends"),
+    ("wow", "World of Warcraft"),
+    ("swtor", "Star Wars: The Old Republic"),
+    ("gw2", "Guild Wars 2"),
+    ("osrs", "Old School RuneScape"),
+    ("bdo", "Black Desert Online"),
+    ("hs", "Hearthstone"),
+    ("mtga", "Magic: The Gathering Arena"),
+    ("dbd", "Dead by Daylight"),
+    ("rl", "Rocket League"),
+    // Strategy and simulation
+    ("sc", "StarCraft"),
+    ("sc2", "StarCraft II"),
+    ("civ", "Sid Meier's Civilization"),
+    ("civ5", "Sid Meier's Civilization V"),
+    ("civ6", "Sid Meier's Civilization VI"),
+    ("aoe", "Age of Empires"),
+    ("aoe2", "Age of Empires II"),
+    ("aoe4", "Age of Empires IV"),
+    ("ck2", "Crusader Kings II"),
+    ("ck3", "Crusader Kings III"),
+    ("eu4", "Europa Universalis IV"),
+    ("hoi4", "Hearts of Iron IV"),
+    ("ksp", "Kerbal Space Program"),
+    ("ksp2", "Kerbal Space Program 2"),
+    ("ets2", "Euro Truck Simulator 2"),
+    ("ats", "American Truck Simulator"),
+    ("fs22", "Farming Simulator 22"),
+    // Racing and fighting
+    ("nfs", "Need for Speed"),
+    ("gt7", "Gran Turismo 7"),
+    ("fh4", "Forza Horizon 4"),
+    ("fh5", "Forza Horizon 5"),
+    ("mk11", "Mortal Kombat 11"),
+    ("sf", "Street Fighter"),
+    ("sf5", "Street Fighter V"),
+    ("sf6", "Street Fighter 6"),
+    ("ggst", "Guilty Gear -Strive-"),
+    // Indies
+    ("hk", "Hollow Knight"),
+    ("sts", "Slay the Spire"),
+    ("ror", "Risk of Rain"),
+    ("ror2", "Risk of Rain 2"),
+    ("tboi", "The Binding of Isaac"),
+    ("dst", "Don't Starve Together"),
+    ("pvz", "Plants vs. Zombies"),
+    ("sotn", "Castlevania: Symphony of the Night"),
+];
+
+/// Acronym → expansion table, keys matched against whole query words only
+/// ("botw" expands, "botwx" doesn't)
+#[derive(Debug, Clone)]
+pub struct Acronyms {
+    table: HashMap<String, String>,
+}
+
+impl Acronyms {
+    /// Table without any acronym
+    pub fn empty() -> Self {
+        Self { table: HashMap::new() }
+    }
+
+    /// Add or replace an acronym (case-insensitive)
+    pub fn insert(&mut self, acronym: &str, expansion: &str) {
+        self.table.insert(acronym.trim().to_lowercase(), expansion.trim().to_string());
+    }
+
+    pub fn len(&self) -> usize {
+        self.table.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.table.is_empty()
+    }
+
+    /// `query` with every acronym word replaced by its expansion, None when
+    /// no word is an acronym. Punctuation around a word doesn't prevent a
+    /// match ("botw?" expands)
+    pub fn expand(&self, query: &str) -> Option<String> {
+        let mut expanded = false;
+        let words: Vec<&str> = query
+            .split_whitespace()
+            .map(|word| {
+                let key = word.trim_matches(|c: char| !c.is_alphanumeric()).to_lowercase();
+                match self.table.get(&key) {
+                    Some(expansion) => {
+                        expanded = true;
+                        expansion.as_str()
+                    }
+                    None => word,
+                }
+            })
+            .collect();
+        expanded.then(|| words.join(" "))
+    }
+}
+
+impl Default for Acronyms {
+    /// The built-in table of common gaming acronyms
+    fn default() -> Self {
+        let mut acronyms = Self::empty();
+        for (acronym, expansion) in DEFAULT_ACRONYMS {
+            acronyms.insert(acronym, expansion);
+        }
+        acronyms
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_expands_whole_words_only() {
+        let acronyms = Acronyms::default();
+        assert_eq!(acronyms.expand("gta 5").as_deref(), Some("Grand Theft Auto 5"));
+        assert_eq!(acronyms.expand("COD mw2").as_deref(), Some("Call of Duty Modern Warfare 2"));
+        assert_eq!(acronyms.expand("botw?").as_deref(), Some("The Legend of Zelda: Breath of the Wild"));
+        assert_eq!(acronyms.expand("botwx"), None);
+        assert_eq!(acronyms.expand("hades"), None);
+        // Prefixes and substrings of a word are left alone
+        assert_eq!(acronyms.expand("cosmic"), None);
+    }
+
+    #[test]
+    fn test_custom_acronyms() {
+        let mut acronyms = Acronyms::empty();
+        assert_eq!(acronyms.expand("hsr"), None);
+        acronyms.insert(" HSR ", "Honkai: Star Rail");
+        assert_eq!(acronyms.expand("hsr").as_deref(), Some("Honkai: Star Rail"));
+
+        let mut defaults = Acronyms::default();
+        assert!(defaults.len() >= 100);
+        defaults.insert("ffxiv", "Final Fantasy XIV Online");
+        assert_eq!(defaults.expand("ffxiv").as_deref(), Some("Final Fantasy XIV Online"));
+    }
+}
@@ -0,0 +1,212 @@
+//! Calibration of ranker scores onto a common 0-100 scale: Jaro-Winkler
+//! and Δₛ³ spread matches differently, and the engine thresholds (exact,
+//! fuzzy, cache) should mean the same whichever ranker is active. Tables
+//! are derived from the targeted dataset by the `calibrate-scores` binary.
+
+use crate::core::{ScoreBonus, ScorePenalty};
+use crate::ranking::RankedCandidate;
+
+/// Calibrated score of the ground truth at `GOOD_MATCH_QUANTILE`: 3 correct
+/// matches out of 4 score at least this
+pub const GOOD_MATCH_SCORE: f64 = 90.0;
+
+/// Calibrated score of unrelated titles at `BAD_MATCH_QUANTILE`
+pub const BAD_MATCH_SCORE: f64 = 40.0;
+
+/// Calibrated score of the median unrelated title
+pub const TYPICAL_BAD_SCORE: f64 = 20.0;
+
+/// Quantile of ground-truth raw scores mapped to `GOOD_MATCH_SCORE`
+pub const GOOD_MATCH_QUANTILE: f64 = 0.25;
+
+/// Quantile of distractor raw scores mapped to `BAD_MATCH_SCORE`
+pub const BAD_MATCH_QUANTILE: f64 = 0.99;
+
+/// Piecewise-linear map of a ranker's raw scores onto the common scale:
+/// `(raw, calibrated)` points, both ascending. Raw scores outside the
+/// table take the calibrated score of its nearest end.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ScoreScale(&'static [(f64, f64)]);
+
+impl ScoreScale {
+    /// Scores already on the common scale
+    pub const IDENTITY: ScoreScale = ScoreScale(&[]);
+
+    pub const fn new(points: &'static [(f64, f64)]) -> Self {
+        Self(points)
+    }
+
+    pub fn points(&self) -> &'static [(f64, f64)] {
+        self.0
+    }
+
+    pub fn is_identity(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+/// `RapidfuzzRanker` (Jaro-Winkler × 100, penalties applied)
+pub const RAPIDFUZZ_SCALE: ScoreScale = ScoreScale::new(&[
+    (0.00, 0.0),
+    (47.34, 20.0),
+    (66.83, 40.0),
+    (92.63, 90.0),
+    (100.00, 100.0),
+]);
+
+/// `DeltaRanker` (TITLE profile)
+pub const DELTA_SCALE: ScoreScale = ScoreScale::new(&[
+    (0.00, 0.0),
+    (27.43, 20.0),
+    (46.92, 40.0),
+    (70.00, 90.0),
+    (100.00, 100.0),
+]);
+
+/// `DrakonRanker`: DRAKON serves the Δₛ³ algorithm, so until a table is
+/// derived against a live service it shares the in-process one
+pub const DRAKON_SCALE: ScoreScale = DELTA_SCALE;
+
+/// `raw` on the common scale
+pub fn calibrate(raw: f64, scale: &ScoreScale) -> f64 {
+    let points = scale.points();
+    let (Some(&(first_raw, first)), Some(&(last_raw, last))) = (points.first(), points.last()) else {
+        return raw;
+    };
+    if raw <= first_raw {
+        return first;
+    }
+    if raw >= last_raw {
+        return last;
+    }
+    points
+        .windows(2)
+        .find(|pair| raw <= pair[1].0)
+        .map_or(last, |pair| {
+            let ((x0, y0), (x1, y1)) = (pair[0], pair[1]);
+            y0 + (raw - x0) / (x1 - x0) * (y1 - y0)
+        })
+}
+
+/// Calibrate a ranking in place, recording the change as a `calibration`
+/// bonus or penalty. The map is monotonic, so the order is kept.
+pub fn calibrate_ranked(ranked: &mut [RankedCandidate], scale: &ScoreScale) {
+    if scale.is_identity() {
+        return;
+    }
+    for candidate in ranked.iter_mut() {
+        let calibrated = calibrate(candidate.score, scale);
+        let points = calibrated - candidate.score;
+        candidate.score = calibrated;
+        if let (Some(breakdown), true) = (candidate.breakdown.as_mut(), points != 0.0) {
+            let reason = "calibration".to_string();
+            if points > 0.0 {
+                breakdown.bonuses.push(ScoreBonus { reason, points });
+            } else {
+                breakdown.penalties.push(ScorePenalty { reason, points: -points });
+            }
+        }
+    }
+}
+
+/// `q` quantile (0-1) of `scores`, by nearest rank
+pub fn quantile(scores: &[f64], q: f64) -> Option<f64> {
+    let mut sorted = scores.to_vec();
+    sorted.sort_by(f64::total_cmp);
+    let last = sorted.len().checked_sub(1)?;
+    Some(sorted[((last as f64) * q.clamp(0.0, 1.0)).round() as usize])
+}
+
+/// Table mapping raw scores of ground truths (`positives`) and unrelated
+/// titles (`negatives`) onto the anchors: 0 → 0, median negative →
+/// `TYPICAL_BAD_SCORE`, `BAD_MATCH_QUANTILE` negative → `BAD_MATCH_SCORE`,
+/// `GOOD_MATCH_QUANTILE` positive → `GOOD_MATCH_SCORE`, 100 → 100. An anchor
+/// whose raw score doesn't exceed the previous one's (rankers that can't
+/// tell both sets apart) is dropped to keep the map increasing.
+pub fn derive_scale(positives: &[f64], negatives: &[f64]) -> Vec<(f64, f64)> {
+    let anchors = [
+        Some((0.0, 0.0)),
+        quantile(negatives, 0.5).map(|raw| (raw, TYPICAL_BAD_SCORE)),
+        quantile(negatives, BAD_MATCH_QUANTILE).map(|raw| (raw, BAD_MATCH_SCORE)),
+        quantile(positives, GOOD_MATCH_QUANTILE).map(|raw| (raw, GOOD_MATCH_SCORE)),
+        Some((100.0, 100.0)),
+    ];
+    let mut points: Vec<(f64, f64)> = Vec::new();
+    for (raw, calibrated) in anchors.into_iter().flatten() {
+        if points.last().is_none_or(|&(last, _)| raw > last) {
+            points.push(((raw * 100.0).round() / 100.0, calibrated));
+        }
+    }
+    points
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::GameResult;
+    use crate::ranking::{DeltaProfile, DeltaRanker, Ranker, RapidfuzzRanker};
+
+    #[test]
+    fn test_calibrate_interpolates() {
+        let scale = ScoreScale::new(&[(0.0, 0.0), (50.0, 20.0), (80.0, 90.0), (100.0, 100.0)]);
+        assert_eq!(calibrate(25.0, &scale), 10.0);
+        assert_eq!(calibrate(65.0, &scale), 55.0);
+        assert_eq!(calibrate(100.0, &scale), 100.0);
+        assert_eq!(calibrate(-3.0, &scale), 0.0);
+        assert_eq!(calibrate(42.0, &ScoreScale::IDENTITY), 42.0);
+    }
+
+    #[test]
+    fn test_derive_scale() {
+        let positives: Vec<f64> = (80..=100).map(f64::from).collect();
+        let negatives: Vec<f64> = (0..=60).map(f64::from).collect();
+        let points = derive_scale(&positives, &negatives);
+        assert_eq!(points, vec![(0.0, 0.0), (30.0, 20.0), (59.0, 40.0), (85.0, 90.0), (100.0, 100.0)]);
+
+        // Overlapping sets: the good-match anchor can't sit above the bad one
+        let points = derive_scale(&[50.0], &negatives);
+        assert_eq!(points.iter().map(|p| p.1).collect::<Vec<_>>(), vec![0.0, 20.0, 40.0, 100.0]);
+    }
+
+    #[test]
+    fn test_calibration_recorded() {
+        let mut ranked = vec![RankedCandidate::explained(
+            GameResult::new("steam", "1", "Hades"),
+            crate::core::ScoreBreakdown::new("rapidfuzz", 60.0, 1),
+        )];
+        calibrate_ranked(&mut ranked, &RAPIDFUZZ_SCALE);
+        let breakdown = ranked[0].breakdown.as_ref().unwrap();
+        assert_eq!(breakdown.penalties[0].reason, "calibration");
+        assert!((breakdown.score() - ranked[0].score).abs() < 1e-9);
+    }
+
+    /// Calibrated score of `name` for `query`
+    async fn calibrated(ranker: &dyn Ranker, query: &str, name: &str) -> f64 {
+        let mut ranked = ranker.rank(query, &[GameResult::new("steam", "1", name)]).await.unwrap();
+        calibrate_ranked(&mut ranked, &ranker.score_scale());
+        ranked[0].score
+    }
+
+    #[tokio::test]
+    async fn test_rankers_agree_on_good_and_bad_matches() {
+        let rankers: Vec<Box<dyn Ranker>> = vec![Box::new(RapidfuzzRanker::new()), Box::new(DeltaRanker::new())];
+        for ranker in &rankers {
+            for (query, name) in [("the witcher 3 wild hunt", "The Witcher 3: Wild Hunt"), ("counter strike 2", "Counter-Strike 2")] {
+                let score = calibrated(ranker.as_ref(), query, name).await;
+                assert!(score >= GOOD_MATCH_SCORE, "{} {} → {}: {}", ranker.name(), query, name, score);
+            }
+            for (query, name) in [("vampire survivor", "Left 4 Dead"), ("stardew valey", "Counter-Strike 2")] {
+                let score = calibrated(ranker.as_ref(), query, name).await;
+                assert!(score <= BAD_MATCH_SCORE, "{} {} → {}: {}", ranker.name(), query, name, score);
+            }
+        }
+
+        // DRAKON serves Δₛ³: its raw scores are the in-process ones
+        let profile = DeltaProfile::title();
+        for (name, good) in [("The Witcher 3: Wild Hunt", true), ("Left 4 Dead", false)] {
+            let score = calibrate((1.0 - profile.delta("the witcher 3 wild hunt", name)) * 100.0, &DRAKON_SCALE);
+            assert_eq!(score >= GOOD_MATCH_SCORE, good, "drakon {}: {}", name, score);
+            assert_eq!(score <= BAD_MATCH_SCORE, !good, "drakon {}: {}", name, score);
+        }
+    }
+}
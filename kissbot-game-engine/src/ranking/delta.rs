@@ -0,0 +1,178 @@
+use async_trait::async_trait;
+
+pub use delta_s3::{DeltaProfile, InvalidProfile};
+use delta_s3::PreparedQuery;
+
+use crate::core::{GameResult, ScoreBreakdown};
+use crate::ranking::calibration::DELTA_SCALE;
+use crate::ranking::{score_fields, sort_ranked, FieldWeights, Ranker, RankedCandidate, ScoreScale};
+use crate::error::Result;
+
+/// Δₛ³ ranking in process, through the `delta-s3` crate: the algorithm
+/// DRAKON serves over HTTP, without the service
+pub struct DeltaRanker {
+    profile: DeltaProfile,
+    fields: FieldWeights,
+}
+
+impl DeltaRanker {
+    /// Ranker with the TITLE profile
+    pub fn new() -> Self {
+        Self::with_profile(DeltaProfile::title())
+    }
+
+    /// Ranker with tuned weights and corrections (check them with
+    /// `DeltaProfile::validate` first)
+    pub fn with_profile(profile: DeltaProfile) -> Self {
+        Self { profile, fields: FieldWeights::default() }
+    }
+
+    /// Same ranker matching the query against the candidates' fields with
+    /// these weights (`FieldWeights::NAME_ONLY` for the name alone)
+    pub fn with_field_weights(mut self, fields: FieldWeights) -> Self {
+        self.fields = fields;
+        self
+    }
+
+    pub fn profile(&self) -> &DeltaProfile {
+        &self.profile
+    }
+
+    /// Candidates sorted by their score over the weighted fields (see
+    /// `score_fields`), with the tokens compared (as the profile tokenizes them) in their
+    /// breakdown when `explain` is set. The query is tokenized once for all
+    /// of them.
+    fn rank_with(&self, query: &str, candidates: &[GameResult], explain: bool) -> Vec<RankedCandidate> {
+        let prepared = PreparedQuery::new(query, &self.profile);
+        let normalized = |text: &str| explain.then(|| self.profile.tokenize(text).join(" "));
+        let normalized_query = explain.then(|| prepared.tokens().join(" "));
+
+        let mut ranked: Vec<RankedCandidate> = candidates
+            .iter()
+            .map(|game| {
+                let breakdown = score_fields(game, &self.fields, |name| {
+                    // Δ is a distance (0 = identical, 1 = unrelated)
+                    let score = (1.0 - prepared.delta(name)) * 100.0;
+                    let mut breakdown = ScoreBreakdown::new(self.name(), score, candidates.len());
+                    breakdown.normalized_query = normalized_query.clone();
+                    breakdown.normalized_candidate = normalized(name);
+                    breakdown
+                });
+                RankedCandidate::explained(game.clone(), breakdown)
+            })
+            .collect();
+
+        sort_ranked(&mut ranked);
+
+        ranked
+    }
+}
+
+impl Default for DeltaRanker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl Ranker for DeltaRanker {
+    async fn rank(&self, query: &str, candidates: &[GameResult]) -> Result<Vec<RankedCandidate>> {
+        Ok(self.rank_with(query, candidates, false))
+    }
+
+    async fn rank_explain(&self, query: &str, candidates: &[GameResult]) -> Result<Vec<RankedCandidate>> {
+        Ok(self.rank_with(query, candidates, true))
+    }
+
+    fn name(&self) -> &str {
+        "delta"
+    }
+
+    fn score_scale(&self) -> ScoreScale {
+        DELTA_SCALE
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_delta_ranker() {
+        let candidates = vec![
+            GameResult::new("steam", "1", "Vampire Survivors"),
+            GameResult::new("steam", "2", "Vampire The Masquerade"),
+            GameResult::new("steam", "3", "Survivor.io"),
+        ];
+
+        let ranked = DeltaRanker::new().rank("vampir survivor", &candidates).await.unwrap();
+
+        assert_eq!(ranked.len(), 3);
+        assert_eq!(ranked[0].game.name, "Vampire Survivors");
+        assert!(ranked[0].score > 50.0);
+        assert!(ranked[0].score > ranked[1].score);
+    }
+
+    #[tokio::test]
+    async fn test_delta_exact_match_and_breakdown() {
+        let candidates = vec![
+            GameResult::new("steam", "1", "Hades II"),
+            GameResult::new("steam", "2", "Hades"),
+        ];
+
+        let ranked = DeltaRanker::new().rank("Hades", &candidates).await.unwrap();
+        assert_eq!(ranked[0].game.name, "Hades");
+        assert!(ranked[0].score > 90.0 && ranked[0].score <= 100.0);
+        let breakdown = ranked[0].breakdown.as_ref().unwrap();
+        assert_eq!((breakdown.ranker.as_str(), breakdown.candidates), ("delta", 2));
+    }
+
+    #[tokio::test]
+    async fn test_delta_profile_changes_scores() {
+        let candidates = vec![GameResult::new("steam", "1", "Portal 2 GOTY Edition")];
+        let penalized = DeltaRanker::new().rank("portal", &candidates).await.unwrap();
+        let lenient = DeltaRanker::with_profile(DeltaProfile { dlc_penalty: 0.0, ..DeltaProfile::title() })
+            .rank("portal", &candidates)
+            .await
+            .unwrap();
+        assert!(lenient[0].score > penalized[0].score);
+    }
+
+    #[tokio::test]
+    async fn test_delta_explain_details() {
+        let candidates = vec![GameResult::new("steam", "1", "Hades II")];
+        let ranked = DeltaRanker::new().rank_explain("HADES 2", &candidates).await.unwrap();
+        let breakdown = ranked[0].breakdown.as_ref().unwrap();
+        assert_eq!(breakdown.normalized_query.as_deref(), Some("hades 2"));
+        assert_eq!(breakdown.normalized_candidate.as_deref(), Some("hades 2"));
+    }
+
+    #[tokio::test]
+    async fn test_delta_name_only_weights_ignore_other_fields() {
+        let mut game = GameResult::new("steam", "1", "Hollow Knight");
+        game.aliases = vec!["Hollow Knight Voidheart Edition".to_string()];
+        game.developers = vec!["Team Cherry".to_string()];
+        game.genres = vec!["Metroidvania".to_string()];
+        let ranker = DeltaRanker::new().with_field_weights(FieldWeights::NAME_ONLY);
+        for query in ["hollow knight", "team cherry", "metroidvania"] {
+            let fields = ranker.rank_explain(query, &[game.clone()]).await.unwrap();
+            let name = ranker.rank_explain(query, &[GameResult::new("steam", "1", "Hollow Knight")]).await.unwrap();
+            assert_eq!((fields[0].score, &fields[0].breakdown), (name[0].score, &name[0].breakdown), "{}", query);
+        }
+
+        let weighted = DeltaRanker::new().rank("team cherry", &[game]).await.unwrap();
+        assert_eq!(weighted[0].breakdown.as_ref().unwrap().bonuses[0].reason, "developers");
+    }
+
+    #[tokio::test]
+    async fn test_delta_ranks_against_aliases() {
+        let mut localized = GameResult::new("igdb", "1", "Dai-2-ji Super Robot Taisen");
+        localized.aliases = vec!["Super Robot Wars 2".to_string()];
+        let ranked = DeltaRanker::new().rank("super robot wars 2", &[localized.clone()]).await.unwrap();
+        assert_eq!(ranked[0].breakdown.as_ref().unwrap().matched_alias.as_deref(), Some("Super Robot Wars 2"));
+
+        localized.aliases.clear();
+        let unaliased = DeltaRanker::new().rank("super robot wars 2", &[localized]).await.unwrap();
+        assert!(ranked[0].score > unaliased[0].score);
+    }
+}
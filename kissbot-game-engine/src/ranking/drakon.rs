@@ -1,15 +1,46 @@
-use reqwest::Client;
+use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 use std::time::Duration;
+use tokio::task::JoinSet;
 
-use crate::core::GameResult;
-use crate::ranking::{Ranker, RankedCandidate};
+use crate::core::{GameResult, ScoreBreakdown};
+use crate::http::HttpClient;
+use crate::ranking::calibration::DRAKON_SCALE;
+use crate::ranking::{sort_ranked, Ranker, RankedCandidate, RapidfuzzRanker, ScoreScale};
 use crate::error::{Result, GameEngineError};
 
 /// DRAKON HTTP API client for Δₛ³ V3 fuzzy ranking
 pub struct DrakonRanker {
-    client: Client,
+    http: HttpClient,
     base_url: String,
+    limits: DrakonLimits,
+}
+
+/// Per request timeout: DRAKON runs locally, rapidfuzz takes over when slow
+const DRAKON_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// Candidates kept by the local rapidfuzz pass before calling DRAKON
+pub const DEFAULT_DRAKON_PREFILTER: usize = 32;
+
+/// Most candidates DRAKON accepts in one `/v1/rank` request
+pub const DRAKON_MAX_BATCH: usize = 64;
+
+/// How many candidates reach DRAKON, and in how many requests
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DrakonLimits {
+    /// Only the top N candidates by rapidfuzz are sent (None sends all)
+    pub prefilter: Option<usize>,
+    /// Larger lists are split into requests of this size, sent concurrently
+    pub batch_size: usize,
+}
+
+impl Default for DrakonLimits {
+    fn default() -> Self {
+        Self {
+            prefilter: Some(DEFAULT_DRAKON_PREFILTER),
+            batch_size: DRAKON_MAX_BATCH,
+        }
+    }
 }
 
 #[derive(Debug, Serialize)]
@@ -34,79 +65,130 @@ struct RankedResult {
 impl DrakonRanker {
     /// Create new DRAKON ranker
     pub async fn new(base_url: impl Into<String>) -> Result<Self> {
+        Self::with_client(base_url, HttpClient::default()).await
+    }
+    
+    /// Create new DRAKON ranker on a shared HTTP client
+    pub async fn with_client(base_url: impl Into<String>, http: HttpClient) -> Result<Self> {
+        Self::with_limits(base_url, http, DrakonLimits::default()).await
+    }
+
+    /// Create new DRAKON ranker on a shared HTTP client, sending at most
+    /// `limits.prefilter` candidates per query
+    pub async fn with_limits(base_url: impl Into<String>, http: HttpClient, limits: DrakonLimits) -> Result<Self> {
         let base_url = base_url.into();
-        let client = Client::builder()
-            .timeout(Duration::from_millis(500))
-            .build()
-            .map_err(|e| GameEngineError::HttpRequest(e))?;
         
         // Health check
         let health_url = format!("{}/health", base_url);
-        client.get(&health_url)
-            .send()
+        http.send(http.client().get(&health_url).timeout(DRAKON_TIMEOUT))
             .await
             .map_err(|e| GameEngineError::DrakonApi(format!("Health check failed: {}", e)))?;
         
-        Ok(Self { client, base_url })
+        Ok(Self { http, base_url, limits })
+    }
+
+    pub fn limits(&self) -> DrakonLimits {
+        self.limits
     }
     
-    /// Rank candidates via DRAKON HTTP API
+    /// Rank candidates via DRAKON HTTP API, one request per batch of
+    /// `limits.batch_size` names; indices are into `candidates`
     async fn rank_http(&self, query: &str, candidates: &[String]) -> Result<Vec<(usize, f64)>> {
         let url = format!("{}/v1/rank", self.base_url);
+        let batch_size = self.limits.batch_size.max(1);
         
-        let request = RankRequest {
-            query: query.to_string(),
-            candidates: candidates.to_vec(),
-        };
-        
-        let response = self.client
-            .post(&url)
-            .json(&request)
-            .send()
-            .await
-            .map_err(|e| GameEngineError::DrakonApi(format!("Request failed: {}", e)))?;
-        
-        if !response.status().is_success() {
-            return Err(GameEngineError::DrakonApi(format!(
-                "HTTP {}: {}",
-                response.status(),
-                response.text().await.unwrap_or_default()
-            )));
+        let mut batches = JoinSet::new();
+        for (n, batch) in candidates.chunks(batch_size).enumerate() {
+            let request = RankRequest {
+                query: query.to_string(),
+                candidates: batch.to_vec(),
+            };
+            let (http, url) = (self.http.clone(), url.clone());
+            batches.spawn(async move { (n * batch_size, post_rank(&http, &url, &request).await) });
         }
         
-        let rank_response: RankResponse = response
-            .json()
-            .await
-            .map_err(|e| GameEngineError::DrakonApi(format!("Invalid JSON: {}", e)))?;
-        
-        Ok(rank_response
-            .results
-            .into_iter()
-            .map(|r| (r.index, r.score))
-            .collect())
+        let mut scores = Vec::with_capacity(candidates.len());
+        while let Some(joined) = batches.join_next().await {
+            let (offset, results) = joined.map_err(|e| GameEngineError::DrakonApi(format!("Request task failed: {}", e)))?;
+            scores.extend(results?.into_iter().map(|r| (offset + r.index, r.score)));
+        }
+        Ok(scores)
+    }
+}
+
+async fn post_rank(http: &HttpClient, url: &str, request: &RankRequest) -> Result<Vec<RankedResult>> {
+    let response = http
+        .send(http.client().post(url).json(request).timeout(DRAKON_TIMEOUT))
+        .await
+        .map_err(|e| GameEngineError::DrakonApi(format!("Request failed: {}", e)))?;
+    
+    if !response.status().is_success() {
+        return Err(GameEngineError::DrakonApi(format!(
+            "HTTP {}: {}",
+            response.status(),
+            response.text().await.unwrap_or_default()
+        )));
     }
+    
+    let rank_response: RankResponse = response
+        .json()
+        .await
+        .map_err(|e| GameEngineError::DrakonApi(format!("Invalid JSON: {}", e)))?;
+    Ok(rank_response.results)
 }
 
+#[async_trait]
 impl Ranker for DrakonRanker {
-    fn rank(&self, query: &str, candidates: &[GameResult]) -> Result<Vec<RankedCandidate>> {
-        // Convert to candidate names
-        let names: Vec<String> = candidates.iter().map(|g| g.name.clone()).collect();
+    async fn rank(&self, query: &str, candidates: &[GameResult]) -> Result<Vec<RankedCandidate>> {
+        // Shortlist locally: DRAKON's time grows with the list and long
+        // catalogs trip the request timeout
+        let shortlist: Vec<GameResult>;
+        let sent = match self.limits.prefilter {
+            Some(top_n) if candidates.len() > top_n => {
+                let ranked = RapidfuzzRanker::new().rank(query, candidates).await?;
+                shortlist = ranked.into_iter().take(top_n).map(|c| c.game).collect();
+                &shortlist[..]
+            }
+            _ => candidates,
+        };
+        
+        // Convert to candidate names, aliases sent as extra names of the
+        // same game (alias index 0 is the name)
+        let owners: Vec<(usize, usize)> = sent
+            .iter()
+            .enumerate()
+            .flat_map(|(game, g)| (0..=g.aliases.len()).map(move |alias| (game, alias)))
+            .collect();
+        let names: Vec<String> = sent.iter().flat_map(|g| g.names().map(str::to_string)).collect();
         
-        // Call DRAKON HTTP API (blocking tokio runtime)
-        let runtime = tokio::runtime::Handle::current();
-        let scores = runtime.block_on(self.rank_http(query, &names))?;
+        // Call DRAKON HTTP API
+        let scores = self.rank_http(query, &names).await?;
         
-        // Map back to GameResult with scores
-        let mut ranked: Vec<RankedCandidate> = scores
-            .into_iter()
-            .map(|(idx, score)| RankedCandidate {
-                game: candidates[idx].clone(),
-                score,
+        // Map back to GameResult with the best score of its names (the name
+        // wins ties)
+        let mut best: Vec<Option<(usize, f64)>> = vec![None; sent.len()];
+        for (idx, score) in scores {
+            let Some(&(game, alias)) = owners.get(idx) else { continue };
+            let better = match best[game] {
+                Some((kept, kept_score)) => score > kept_score || (score == kept_score && alias < kept),
+                None => true,
+            };
+            if better {
+                best[game] = Some((alias, score));
+            }
+        }
+        let mut ranked: Vec<RankedCandidate> = sent
+            .iter()
+            .zip(best)
+            .filter_map(|(game, best)| {
+                let (alias, score) = best?;
+                let mut breakdown = ScoreBreakdown::new(self.name(), score, candidates.len());
+                breakdown.matched_alias = alias.checked_sub(1).map(|i| game.aliases[i].clone());
+                Some(RankedCandidate::explained(game.clone(), breakdown))
             })
             .collect();
         
-        // Sort by score descending
-        ranked.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal));
+        sort_ranked(&mut ranked);
         
         Ok(ranked)
     }
@@ -114,11 +196,139 @@ impl Ranker for DrakonRanker {
     fn name(&self) -> &str {
         "drakon"
     }
+
+    fn score_scale(&self) -> ScoreScale {
+        DRAKON_SCALE
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::sync::{Arc, Mutex};
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    /// Keep-alive DRAKON stand-in scoring 99 a name equal to the query and
+    /// 40 the others, recording how many candidates each `/v1/rank` carried
+    async fn recording_drakon() -> (String, Arc<Mutex<Vec<usize>>>) {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let batches = Arc::new(Mutex::new(Vec::new()));
+        let recorded = batches.clone();
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                let recorded = recorded.clone();
+                tokio::spawn(async move {
+                    let mut buf = Vec::new();
+                    let mut chunk = [0u8; 4096];
+                    while let Ok(n) = socket.read(&mut chunk).await {
+                        if n == 0 {
+                            return;
+                        }
+                        buf.extend_from_slice(&chunk[..n]);
+                        while let Some(end) = buf.windows(4).position(|w| w == b"\r\n\r\n") {
+                            let head = String::from_utf8_lossy(&buf[..end]).to_lowercase();
+                            let length: usize = head
+                                .lines()
+                                .find_map(|line| line.strip_prefix("content-length:"))
+                                .and_then(|value| value.trim().parse().ok())
+                                .unwrap_or(0);
+                            if buf.len() < end + 4 + length {
+                                break;
+                            }
+                            let body: Vec<u8> = buf.drain(..end + 4 + length).skip(end + 4).collect();
+                            let reply = if head.starts_with("post /v1/rank") {
+                                let request: serde_json::Value = serde_json::from_slice(&body).unwrap();
+                                let query = request["query"].as_str().unwrap().to_lowercase();
+                                let names = request["candidates"].as_array().unwrap();
+                                recorded.lock().unwrap().push(names.len());
+                                let results: Vec<serde_json::Value> = names
+                                    .iter()
+                                    .enumerate()
+                                    .map(|(index, name)| {
+                                        let name = name.as_str().unwrap();
+                                        let score = if name.to_lowercase() == query { 99.0 } else { 40.0 };
+                                        serde_json::json!({ "candidate": name, "score": score, "index": index })
+                                    })
+                                    .collect();
+                                serde_json::json!({ "results": results }).to_string()
+                            } else {
+                                String::new()
+                            };
+                            let response = format!(
+                                "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\n\r\n{}",
+                                reply.len(),
+                                reply
+                            );
+                            if socket.write_all(response.as_bytes()).await.is_err() {
+                                return;
+                            }
+                        }
+                    }
+                });
+            }
+        });
+        (format!("http://{}", addr), batches)
+    }
+
+    fn catalog(size: usize) -> Vec<GameResult> {
+        (0..size).map(|i| GameResult::new("catalog", i.to_string(), format!("Game {}", i))).collect()
+    }
+
+    #[tokio::test]
+    async fn test_prefilter_sends_top_candidates_only() {
+        let (url, batches) = recording_drakon().await;
+        let ranker = DrakonRanker::with_client(url, HttpClient::default()).await.unwrap();
+
+        let ranked = ranker.rank("game 150", &catalog(200)).await.unwrap();
+        assert_eq!(*batches.lock().unwrap(), vec![DEFAULT_DRAKON_PREFILTER]);
+        assert_eq!(ranked.len(), DEFAULT_DRAKON_PREFILTER);
+        assert_eq!((ranked[0].game.id.as_str(), ranked[0].score), ("150", 99.0));
+        assert_eq!(ranked[0].breakdown.as_ref().unwrap().candidates, 200);
+    }
+
+    #[tokio::test]
+    async fn test_large_lists_are_split_into_batches() {
+        let (url, batches) = recording_drakon().await;
+        let limits = DrakonLimits { prefilter: None, batch_size: 64 };
+        let ranker = DrakonRanker::with_limits(url, HttpClient::default(), limits).await.unwrap();
+
+        let ranked = ranker.rank("game 150", &catalog(200)).await.unwrap();
+        let mut sizes = batches.lock().unwrap().clone();
+        sizes.sort();
+        assert_eq!(sizes, vec![8, 64, 64, 64]);
+        assert_eq!(ranked.len(), 200);
+        // Indices of later batches map back past the earlier ones
+        assert_eq!((ranked[0].game.name.as_str(), ranked[0].score), ("Game 150", 99.0));
+        let mut ids: Vec<usize> = ranked.iter().map(|c| c.game.id.parse().unwrap()).collect();
+        ids.sort();
+        assert_eq!(ids, (0..200).collect::<Vec<_>>());
+    }
+
+    #[tokio::test]
+    async fn test_short_lists_skip_the_prefilter() {
+        let (url, batches) = recording_drakon().await;
+        let ranker = DrakonRanker::with_client(url, HttpClient::default()).await.unwrap();
+
+        let ranked = ranker.rank("game 3", &catalog(5)).await.unwrap();
+        assert_eq!(*batches.lock().unwrap(), vec![5]);
+        assert_eq!(ranked[0].game.name, "Game 3");
+    }
+
+    #[tokio::test]
+    async fn test_aliases_sent_as_extra_names() {
+        let (url, batches) = recording_drakon().await;
+        let ranker = DrakonRanker::with_client(url, HttpClient::default()).await.unwrap();
+        let mut candidates = catalog(3);
+        candidates[2].aliases = vec!["Biohazard".to_string(), "Resident Evil".to_string()];
+
+        let ranked = ranker.rank("resident evil", &candidates).await.unwrap();
+        assert_eq!(*batches.lock().unwrap(), vec![5]);
+        assert_eq!(ranked.len(), 3);
+        assert_eq!((ranked[0].game.name.as_str(), ranked[0].score), ("Game 2", 99.0));
+        assert_eq!(ranked[0].breakdown.as_ref().unwrap().matched_alias.as_deref(), Some("Resident Evil"));
+        assert_eq!(ranked[1].breakdown.as_ref().unwrap().matched_alias, None);
+    }
 
     #[tokio::test]
     #[ignore] // Requires DRAKON server running
@@ -131,7 +341,7 @@ mod tests {
             GameResult::new("steam", "3", "Survivor.io"),
         ];
         
-        let ranked = ranker.rank("vampir survivor", &candidates).unwrap();
+        let ranked = ranker.rank("vampir survivor", &candidates).await.unwrap();
         
         assert_eq!(ranked.len(), 3);
         assert_eq!(ranked[0].game.name, "Vampire Survivors");
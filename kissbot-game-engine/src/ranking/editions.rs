@@ -0,0 +1,155 @@
+//! Edition variants ("The Witcher 3: Wild Hunt - Game of the Year Edition")
+//! grouped under the game they are an edition of
+
+use crate::core::SlimGameResult;
+use crate::ranking::RankedCandidate;
+
+/// Words marking an edition of a game rather than another game; multi-word
+/// entries match as a whole
+pub const DEFAULT_EDITION_KEYWORDS: &[&str] = &[
+    "game of the year", "goty", "definitive", "remastered", "remaster", "complete", "enhanced",
+    "deluxe", "ultimate", "gold", "premium", "standard", "anniversary", "director's cut",
+    "directors cut", "collector's", "collectors", "special", "digital", "edition",
+];
+
+/// `DEFAULT_EDITION_KEYWORDS` as the owned list `SearchOptions` holds
+pub fn default_edition_keywords() -> Vec<String> {
+    DEFAULT_EDITION_KEYWORDS.iter().map(|kw| kw.to_string()).collect()
+}
+
+/// Lowercase words of `text` (apostrophes kept) padded with one space, so
+/// keywords match whole words only
+fn padded_words(text: &str) -> String {
+    let words: Vec<String> = text
+        .split(|c: char| !(c.is_alphanumeric() || c == '\'' || c == '’'))
+        .map(|word| word.trim_matches(|c| c == '\'' || c == '’').replace('’', "'").to_lowercase())
+        .filter(|word| !word.is_empty())
+        .collect();
+    format!(" {} ", words.join(" "))
+}
+
+/// Name of a game without its edition keywords, lowercased and single-spaced
+/// ("The Witcher 3: Wild Hunt – Complete Edition" → "the witcher 3 wild hunt");
+/// editions of the same game share it
+pub fn edition_base(name: &str, keywords: &[String]) -> String {
+    let mut words = padded_words(name);
+    for keyword in keywords {
+        let keyword = padded_words(keyword);
+        if keyword.trim().is_empty() {
+            continue;
+        }
+        while let Some(pos) = words.find(&keyword) {
+            words.replace_range(pos..pos + keyword.len(), " ");
+        }
+    }
+    words.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Edition keywords of `name` the query asks for, and those it doesn't
+fn keyword_counts(query: &str, name: &str, keywords: &[String]) -> (usize, usize) {
+    let (query, name) = (padded_words(query), padded_words(name));
+    let found: Vec<String> = keywords
+        .iter()
+        .map(|keyword| padded_words(keyword))
+        .filter(|keyword| !keyword.trim().is_empty() && name.contains(keyword.as_str()))
+        .collect();
+    let requested = found.iter().filter(|keyword| query.contains(keyword.as_str())).count();
+    (requested, found.len() - requested)
+}
+
+/// Put the canonical edition of the winner's game first: of the candidates
+/// sharing its `edition_base`, the one with the most edition keywords the
+/// query asks for, then the fewest it doesn't (the base game for "witcher
+/// 3", the GOTY edition for "witcher 3 goty"), the best ranked on a tie.
+/// The others keep their order.
+pub fn promote_canonical_edition(query: &str, ranked: &mut [RankedCandidate], keywords: &[String]) {
+    let Some(winner) = ranked.first() else {
+        return;
+    };
+    let base = edition_base(&winner.game.name, keywords);
+    let canonical = ranked
+        .iter()
+        .enumerate()
+        .filter(|(_, candidate)| edition_base(&candidate.game.name, keywords) == base)
+        .min_by_key(|(pos, candidate)| {
+            let (requested, unrequested) = keyword_counts(query, &candidate.game.name, keywords);
+            (std::cmp::Reverse(requested), unrequested, *pos)
+        })
+        .map(|(pos, _)| pos);
+    if let Some(pos) = canonical {
+        ranked[..=pos].rotate_right(1);
+    }
+}
+
+/// `alternatives` split into the editions of `name` (same `edition_base`)
+/// and the other games, each in its original order
+pub fn split_editions(
+    name: &str,
+    alternatives: Vec<SlimGameResult>,
+    keywords: &[String],
+) -> (Vec<SlimGameResult>, Vec<SlimGameResult>) {
+    let base = edition_base(name, keywords);
+    alternatives
+        .into_iter()
+        .partition(|alt| edition_base(&alt.name, keywords) == base)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::GameResult;
+
+    const WITCHER: &[&str] = &[
+        "The Witcher 3: Wild Hunt - Game of the Year Edition",
+        "The Witcher 3: Wild Hunt",
+        "The Witcher 3: Wild Hunt – Complete Edition",
+        "The Witcher 2: Assassins of Kings Enhanced Edition",
+        "The Witcher 3: Wild Hunt Gold Edition",
+    ];
+
+    fn ranked(names: &[&str]) -> Vec<RankedCandidate> {
+        names
+            .iter()
+            .enumerate()
+            .map(|(i, name)| RankedCandidate::new(GameResult::new("steam", i.to_string(), *name), 90.0 - i as f64))
+            .collect()
+    }
+
+    #[test]
+    fn test_edition_base() {
+        let keywords = default_edition_keywords();
+        for name in [WITCHER[0], WITCHER[1], WITCHER[2], WITCHER[4]] {
+            assert_eq!(edition_base(name, &keywords), "the witcher 3 wild hunt", "{}", name);
+        }
+        assert_eq!(edition_base(WITCHER[3], &keywords), "the witcher 2 assassins of kings");
+        assert_eq!(edition_base("Death Stranding Director's Cut", &keywords), "death stranding");
+        // Whole words only
+        assert_eq!(edition_base("Goldeneye", &keywords), "goldeneye");
+    }
+
+    #[test]
+    fn test_canonical_edition_promoted() {
+        let keywords = default_edition_keywords();
+        let mut witcher = ranked(WITCHER);
+        promote_canonical_edition("witcher 3", &mut witcher, &keywords);
+        assert_eq!(witcher[0].game.name, "The Witcher 3: Wild Hunt");
+        assert_eq!(witcher[1].game.name, WITCHER[0]);
+
+        // Asked for by name: the edition stays
+        let mut goty = ranked(WITCHER);
+        promote_canonical_edition("witcher 3 game of the year", &mut goty, &keywords);
+        assert_eq!(goty[0].game.name, WITCHER[0]);
+    }
+
+    #[test]
+    fn test_split_editions() {
+        let keywords = default_edition_keywords();
+        let alternatives: Vec<SlimGameResult> = WITCHER
+            .iter()
+            .map(|name| SlimGameResult::from_game(&GameResult::new("steam", *name, *name), None))
+            .collect();
+        let (editions, others) = split_editions(WITCHER[1], alternatives[2..].to_vec(), &keywords);
+        assert_eq!(editions.iter().map(|e| e.name.as_str()).collect::<Vec<_>>(), vec![WITCHER[2], WITCHER[4]]);
+        assert_eq!(others.len(), 1);
+    }
+}
@@ -0,0 +1,237 @@
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::core::{GameResult, ScoreBreakdown};
+use crate::ranking::{calibrate_ranked, sort_ranked, DeltaRanker, Ranker, RankedCandidate, RapidfuzzRanker};
+use crate::error::Result;
+
+/// Constant of reciprocal rank fusion: ranks past the first few weigh
+/// about the same
+pub const RRF_K: f64 = 60.0;
+
+/// How `EnsembleRanker` combines the scores of its two rankers
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum EnsembleMode {
+    /// Weighted mean of the scores (weights of the first and second ranker)
+    Weighted(f64, f64),
+    /// Best of the two scores
+    Max,
+    /// Reciprocal rank fusion: `1 / (RRF_K + rank)` summed over both
+    /// rankings, scaled so first in both scores 100
+    Rrf,
+}
+
+impl EnsembleMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EnsembleMode::Weighted(..) => "weighted",
+            EnsembleMode::Max => "max",
+            EnsembleMode::Rrf => "rrf",
+        }
+    }
+}
+
+impl Default for EnsembleMode {
+    fn default() -> Self {
+        EnsembleMode::Weighted(0.5, 0.5)
+    }
+}
+
+impl std::str::FromStr for EnsembleMode {
+    type Err = String;
+
+    /// `weighted` (0.5/0.5), `max` or `rrf`
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "weighted" | "mean" => Ok(EnsembleMode::default()),
+            "max" => Ok(EnsembleMode::Max),
+            "rrf" | "rank-fusion" | "rank_fusion" => Ok(EnsembleMode::Rrf),
+            other => Err(format!("Invalid ensemble mode '{}' (expected weighted, max or rrf)", other)),
+        }
+    }
+}
+
+/// Two rankers run side by side, their scores combined: rapidfuzz
+/// over-rewards shared prefixes, Δₛ³ is harsh on short queries, and each
+/// tends to catch the other's misses
+pub struct EnsembleRanker {
+    first: Arc<dyn Ranker>,
+    second: Arc<dyn Ranker>,
+    mode: EnsembleMode,
+}
+
+impl EnsembleRanker {
+    pub fn new(first: Arc<dyn Ranker>, second: Arc<dyn Ranker>, mode: EnsembleMode) -> Self {
+        Self { first, second, mode }
+    }
+
+    /// Rapidfuzz and in-process Δₛ³ (TITLE profile) combined with `mode`
+    pub fn rapidfuzz_delta(mode: EnsembleMode) -> Self {
+        Self::new(Arc::new(RapidfuzzRanker::new()), Arc::new(DeltaRanker::new()), mode)
+    }
+
+    pub fn mode(&self) -> EnsembleMode {
+        self.mode
+    }
+
+    async fn rank_with(&self, query: &str, candidates: &[GameResult], explain: bool) -> Result<Vec<RankedCandidate>> {
+        let (mut first, mut second) = if explain {
+            tokio::try_join!(self.first.rank_explain(query, candidates), self.second.rank_explain(query, candidates))?
+        } else {
+            tokio::try_join!(self.first.rank(query, candidates), self.second.rank(query, candidates))?
+        };
+        // Combined on the common scale
+        calibrate_ranked(&mut first, &self.first.score_scale());
+        calibrate_ranked(&mut second, &self.second.score_scale());
+
+        // Score and 1-based rank of each candidate in the second ranking
+        let others: HashMap<(&str, &str), (f64, usize)> = second
+            .iter()
+            .enumerate()
+            .map(|(pos, c)| ((c.game.provider.as_str(), c.game.id.as_str()), (c.score.clamp(0.0, 100.0), pos + 1)))
+            .collect();
+        let missing = (0.0, second.len() + 1);
+
+        let mut ranked: Vec<RankedCandidate> = first
+            .iter()
+            .enumerate()
+            .map(|(pos, candidate)| {
+                let score = candidate.score.clamp(0.0, 100.0);
+                let key = (candidate.game.provider.as_str(), candidate.game.id.as_str());
+                let (other_score, other_rank) = others.get(&key).copied().unwrap_or(missing);
+                let combined = match self.mode {
+                    EnsembleMode::Weighted(w1, w2) if w1 + w2 > 0.0 => (w1 * score + w2 * other_score) / (w1 + w2),
+                    EnsembleMode::Weighted(..) => 0.0,
+                    EnsembleMode::Max => score.max(other_score),
+                    EnsembleMode::Rrf => {
+                        let fused = 1.0 / (RRF_K + (pos + 1) as f64) + 1.0 / (RRF_K + other_rank as f64);
+                        fused / (2.0 / (RRF_K + 1.0)) * 100.0
+                    }
+                };
+
+                if !explain {
+                    return RankedCandidate::new(candidate.game.clone(), combined);
+                }
+                let mut breakdown = ScoreBreakdown::new(self.name(), combined, candidates.len());
+                if let Some(inner) = &candidate.breakdown {
+                    breakdown.normalized_query = inner.normalized_query.clone();
+                    breakdown.normalized_candidate = inner.normalized_candidate.clone();
+                    breakdown.matched_alias = inner.matched_alias.clone();
+                }
+                RankedCandidate::explained(candidate.game.clone(), breakdown)
+            })
+            .collect();
+
+        sort_ranked(&mut ranked);
+
+        Ok(ranked)
+    }
+}
+
+impl Default for EnsembleRanker {
+    fn default() -> Self {
+        Self::rapidfuzz_delta(EnsembleMode::default())
+    }
+}
+
+#[async_trait]
+impl Ranker for EnsembleRanker {
+    async fn rank(&self, query: &str, candidates: &[GameResult]) -> Result<Vec<RankedCandidate>> {
+        self.rank_with(query, candidates, false).await
+    }
+
+    async fn rank_explain(&self, query: &str, candidates: &[GameResult]) -> Result<Vec<RankedCandidate>> {
+        self.rank_with(query, candidates, true).await
+    }
+
+    fn name(&self) -> &str {
+        "ensemble"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Fixed score per candidate id
+    struct Fixed(&'static [(&'static str, f64)]);
+
+    #[async_trait]
+    impl Ranker for Fixed {
+        async fn rank(&self, _query: &str, candidates: &[GameResult]) -> Result<Vec<RankedCandidate>> {
+            let mut ranked: Vec<RankedCandidate> = candidates
+                .iter()
+                .map(|game| {
+                    let score = self.0.iter().find(|(id, _)| *id == game.id).map_or(0.0, |(_, score)| *score);
+                    RankedCandidate::new(game.clone(), score)
+                })
+                .collect();
+            sort_ranked(&mut ranked);
+            Ok(ranked)
+        }
+
+        fn name(&self) -> &str {
+            "fixed"
+        }
+    }
+
+    fn candidates() -> Vec<GameResult> {
+        vec![GameResult::new("steam", "1", "Portal"), GameResult::new("steam", "2", "Portal 2")]
+    }
+
+    async fn scores(mode: EnsembleMode) -> Vec<(String, f64)> {
+        let ranker = EnsembleRanker::new(
+            Arc::new(Fixed(&[("1", 90.0), ("2", 70.0)])),
+            Arc::new(Fixed(&[("1", 40.0), ("2", 80.0)])),
+            mode,
+        );
+        let ranked = ranker.rank("portal", &candidates()).await.unwrap();
+        ranked.into_iter().map(|c| (c.game.id, c.score)).collect()
+    }
+
+    #[tokio::test]
+    async fn test_weighted_mean() {
+        assert_eq!(scores(EnsembleMode::default()).await, vec![("2".to_string(), 75.0), ("1".to_string(), 65.0)]);
+        assert_eq!(scores(EnsembleMode::Weighted(3.0, 1.0)).await[0], ("1".to_string(), 77.5));
+    }
+
+    #[tokio::test]
+    async fn test_max() {
+        assert_eq!(scores(EnsembleMode::Max).await, vec![("1".to_string(), 90.0), ("2".to_string(), 80.0)]);
+    }
+
+    #[tokio::test]
+    async fn test_rank_fusion_ties_split_rankings() {
+        let fused = scores(EnsembleMode::Rrf).await;
+        // First once and second once each
+        assert_eq!(fused[0].1, fused[1].1);
+        assert!(fused[0].1 < 100.0);
+    }
+
+    #[tokio::test]
+    async fn test_rapidfuzz_delta_ensemble() {
+        let candidates = vec![
+            GameResult::new("steam", "1", "Vampire Survivors"),
+            GameResult::new("steam", "2", "Left 4 Dead"),
+        ];
+        for mode in [EnsembleMode::default(), EnsembleMode::Max, EnsembleMode::Rrf] {
+            let ranked = EnsembleRanker::rapidfuzz_delta(mode).rank_explain("vampire survivors", &candidates).await.unwrap();
+            assert_eq!(ranked[0].game.name, "Vampire Survivors", "{}", mode.as_str());
+            let breakdown = ranked[0].breakdown.as_ref().unwrap();
+            assert_eq!(breakdown.ranker, "ensemble");
+            assert!(breakdown.normalized_query.is_some());
+
+            let plain = EnsembleRanker::rapidfuzz_delta(mode).rank("vampire survivors", &candidates).await.unwrap();
+            assert_eq!((plain[0].score, plain[0].breakdown.as_ref()), (ranked[0].score, None));
+        }
+    }
+
+    #[test]
+    fn test_mode_parsing() {
+        assert_eq!("RRF".parse::<EnsembleMode>(), Ok(EnsembleMode::Rrf));
+        assert_eq!("rank-fusion".parse::<EnsembleMode>(), Ok(EnsembleMode::Rrf));
+        assert_eq!("weighted".parse::<EnsembleMode>(), Ok(EnsembleMode::Weighted(0.5, 0.5)));
+        assert!("vote".parse::<EnsembleMode>().is_err());
+    }
+}
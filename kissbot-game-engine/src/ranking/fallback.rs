@@ -1,66 +1,179 @@
+use async_trait::async_trait;
 use rapidfuzz::distance::jaro_winkler;
 
-use crate::core::GameResult;
-use crate::ranking::{Ranker, RankedCandidate};
+use crate::core::{fold_text, GameResult, ScoreBreakdown, ScorePenalty};
+use crate::ranking::calibration::RAPIDFUZZ_SCALE;
+use crate::ranking::{score_fields, sort_ranked, FieldWeights, Ranker, RankedCandidate, ScoreScale};
 use crate::error::Result;
 
+/// Score multiplier of editions, DLC and soundtracks the query doesn't ask for
+pub const DEFAULT_DLC_PENALTY: f64 = 0.93;
+
+/// Score multiplier of store items that aren't games (dedicated servers, SDKs...)
+pub const DEFAULT_NON_GAME_PENALTY: f64 = 0.80;
+
+/// Words marking an edition or add-on of a game (the `delta-s3` DLC list,
+/// plus what Steam search returns next to base games)
+const DLC_KEYWORDS: &[&str] = &[
+    "goty", "definitive", "remaster", "remastered", "hd", "edition", "dlc", "season pass",
+    "bundle", "trilogy", "collection", "enhanced", "complete", "ultimate", "deluxe", "premium",
+    "gold", "soundtrack", "ost", "upgrade", "demo", "artbook", "art book", "expansion",
+];
+
+/// Words marking a store item that isn't a game
+const NON_GAME_KEYWORDS: &[&str] = &[
+    "dedicated server", "server tools", "sdk", "mod tools", "modding tools", "level editor",
+    "benchmark", "wallpaper",
+];
+
 /// Rapidfuzz-based ranker (fallback when DRAKON unavailable)
-pub struct RapidfuzzRanker;
+pub struct RapidfuzzRanker {
+    dlc_penalty: f64,
+    non_game_penalty: f64,
+    fold: bool,
+    fields: FieldWeights,
+}
 
 impl RapidfuzzRanker {
     pub fn new() -> Self {
-        Self
+        Self::with_penalties(DEFAULT_DLC_PENALTY, DEFAULT_NON_GAME_PENALTY)
     }
-}
 
-impl Default for RapidfuzzRanker {
-    fn default() -> Self {
-        Self::new()
+    /// Ranker multiplying the score of DLC/editions and non-game items by
+    /// these factors (1.0 disables a penalty)
+    pub fn with_penalties(dlc_penalty: f64, non_game_penalty: f64) -> Self {
+        Self::with_options(dlc_penalty, non_game_penalty, true)
     }
-}
 
-impl Ranker for RapidfuzzRanker {
-    fn rank(&self, query: &str, candidates: &[GameResult]) -> Result<Vec<RankedCandidate>> {
-        let query_lower = query.to_lowercase();
+    /// `with_penalties`, with accent and symbol folding (`fold_text`) of the
+    /// query and names before comparing them turned on or off
+    pub fn with_options(dlc_penalty: f64, non_game_penalty: f64, fold: bool) -> Self {
+        Self { dlc_penalty, non_game_penalty, fold, fields: FieldWeights::default() }
+    }
+
+    /// Same ranker matching the query against the candidates' fields with
+    /// these weights (`FieldWeights::NAME_ONLY` for the name alone)
+    pub fn with_field_weights(mut self, fields: FieldWeights) -> Self {
+        self.fields = fields;
+        self
+    }
+
+    /// Lowercased `text`, folded unless disabled
+    fn normalize(&self, text: &str) -> String {
+        if self.fold {
+            fold_text(text).to_lowercase()
+        } else {
+            text.to_lowercase()
+        }
+    }
+
+    /// Penalties of `name` the query doesn't explain, as points off `similarity`
+    fn penalties(&self, query: &str, name: &str, similarity: f64) -> Vec<ScorePenalty> {
+        let query = words(query);
+        let name = words(name);
+        let flagged = |keywords: &[&str]| {
+            keywords.iter().any(|kw| {
+                let kw = format!(" {} ", kw);
+                name.contains(&kw) && !query.contains(&kw)
+            })
+        };
+
+        let mut penalties = Vec::new();
+        let mut score = similarity;
+        for (reason, factor, keywords) in [
+            ("dlc", self.dlc_penalty, DLC_KEYWORDS),
+            ("non_game", self.non_game_penalty, NON_GAME_KEYWORDS),
+        ] {
+            if factor < 1.0 && flagged(keywords) {
+                let points = score * (1.0 - factor);
+                score -= points;
+                penalties.push(ScorePenalty { reason: reason.to_string(), points });
+            }
+        }
+        penalties
+    }
+
+    /// Candidates sorted by their score over the weighted fields (see
+    /// `score_fields`), with the compared strings in their breakdown when
+    /// `explain` is set
+    fn rank_with(&self, query: &str, candidates: &[GameResult], explain: bool) -> Vec<RankedCandidate> {
+        let query_lower = self.normalize(query);
         
         let mut ranked: Vec<RankedCandidate> = candidates
             .iter()
             .map(|game| {
-                let name_lower = game.name.to_lowercase();
-                
-                // Jaro-Winkler similarity (0.0 - 1.0)
-                let score = jaro_winkler::normalized_similarity(
-                    query_lower.chars(),
-                    name_lower.chars(),
-                );
-                
-                // Convert to percentage (0-100)
-                let score_pct = score * 100.0;
-                
-                RankedCandidate {
-                    game: game.clone(),
-                    score: score_pct,
-                }
+                let breakdown = score_fields(game, &self.fields, |name| {
+                    let name_lower = self.normalize(name);
+                    
+                    // Jaro-Winkler similarity (0.0 - 1.0)
+                    let score = jaro_winkler::normalized_similarity(
+                        query_lower.chars(),
+                        name_lower.chars(),
+                    );
+                    
+                    // Convert to percentage (0-100)
+                    let score_pct = score * 100.0;
+                    
+                    let mut breakdown = ScoreBreakdown::new(self.name(), score_pct, candidates.len());
+                    breakdown.penalties = self.penalties(&query_lower, &name_lower, score_pct);
+                    if explain {
+                        breakdown.normalized_query = Some(query_lower.clone());
+                        breakdown.normalized_candidate = Some(name_lower);
+                    }
+                    breakdown
+                });
+                RankedCandidate::explained(game.clone(), breakdown)
             })
             .collect();
         
-        // Sort by score descending
-        ranked.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal));
+        sort_ranked(&mut ranked);
         
-        Ok(ranked)
+        ranked
+    }
+}
+
+/// Lowercase words of `text` joined by single spaces and padded with one,
+/// so keywords match whole words only ("ost" doesn't match "ghost")
+fn words(text: &str) -> String {
+    let words: Vec<String> = text
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect();
+    format!(" {} ", words.join(" "))
+}
+
+impl Default for RapidfuzzRanker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl Ranker for RapidfuzzRanker {
+    async fn rank(&self, query: &str, candidates: &[GameResult]) -> Result<Vec<RankedCandidate>> {
+        Ok(self.rank_with(query, candidates, false))
+    }
+
+    async fn rank_explain(&self, query: &str, candidates: &[GameResult]) -> Result<Vec<RankedCandidate>> {
+        Ok(self.rank_with(query, candidates, true))
     }
     
     fn name(&self) -> &str {
         "rapidfuzz"
     }
+
+    fn score_scale(&self) -> ScoreScale {
+        RAPIDFUZZ_SCALE
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    #[test]
-    fn test_rapidfuzz_ranker() {
+    #[tokio::test]
+    async fn test_rapidfuzz_ranker() {
         let ranker = RapidfuzzRanker::new();
         
         let candidates = vec![
@@ -69,23 +182,169 @@ mod tests {
             GameResult::new("steam", "3", "Left 4 Dead"),
         ];
         
-        let ranked = ranker.rank("vampire survivor", &candidates).unwrap();
+        let ranked = ranker.rank("vampire survivor", &candidates).await.unwrap();
         
         assert_eq!(ranked.len(), 3);
         assert_eq!(ranked[0].game.name, "Vampire Survivors");
         assert!(ranked[0].score > ranked[1].score);
     }
 
-    #[test]
-    fn test_rapidfuzz_exact_match() {
+    #[tokio::test]
+    async fn test_rapidfuzz_exact_match() {
         let ranker = RapidfuzzRanker::new();
         
         let candidates = vec![
             GameResult::new("steam", "1", "Counter-Strike 2"),
         ];
         
-        let ranked = ranker.rank("Counter-Strike 2", &candidates).unwrap();
+        let ranked = ranker.rank("Counter-Strike 2", &candidates).await.unwrap();
         
         assert_eq!(ranked[0].score, 100.0);
     }
+
+    #[tokio::test]
+    async fn test_rapidfuzz_breakdown() {
+        let ranker = RapidfuzzRanker::new();
+        let candidates = vec![
+            GameResult::new("steam", "1", "Hades"),
+            GameResult::new("steam", "2", "Hades II"),
+        ];
+
+        let ranked = ranker.rank("hades", &candidates).await.unwrap();
+        let breakdown = ranked[0].breakdown.as_ref().unwrap();
+        assert_eq!(breakdown.ranker, "rapidfuzz");
+        assert_eq!(breakdown.candidates, 2);
+        assert_eq!(breakdown.similarity, ranked[0].score);
+        assert!(breakdown.penalties.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_rapidfuzz_base_game_beats_soundtrack() {
+        let ranker = RapidfuzzRanker::new();
+        let candidates = vec![
+            GameResult::new("steam", "1", "Portal 2 - Soundtrack"),
+            GameResult::new("steam", "2", "Portal 2"),
+        ];
+
+        let ranked = ranker.rank("portal 2", &candidates).await.unwrap();
+        assert_eq!(ranked[0].game.name, "Portal 2");
+        assert!(ranked[0].breakdown.as_ref().unwrap().penalties.is_empty());
+
+        let soundtrack = ranked[1].breakdown.as_ref().unwrap();
+        assert_eq!(soundtrack.penalties.len(), 1);
+        assert_eq!(soundtrack.penalties[0].reason, "dlc");
+        assert!((ranked[1].score - soundtrack.similarity * DEFAULT_DLC_PENALTY).abs() < 1e-9);
+    }
+
+    #[tokio::test]
+    async fn test_rapidfuzz_keyword_in_query_not_penalized() {
+        let candidates = vec![
+            GameResult::new("steam", "1", "Portal 2 - Soundtrack"),
+            GameResult::new("steam", "2", "Ghost of Tsushima"),
+        ];
+
+        let ranked = RapidfuzzRanker::new().rank("portal 2 soundtrack", &candidates).await.unwrap();
+        assert!(ranked.iter().all(|c| c.breakdown.as_ref().unwrap().penalties.is_empty()));
+    }
+
+    #[tokio::test]
+    async fn test_rapidfuzz_non_game_penalty() {
+        let candidates = vec![GameResult::new("steam", "1", "Valheim Dedicated Server")];
+
+        let ranked = RapidfuzzRanker::new().rank("valheim", &candidates).await.unwrap();
+        let breakdown = ranked[0].breakdown.as_ref().unwrap();
+        assert_eq!(breakdown.penalties[0].reason, "non_game");
+        assert!((ranked[0].score - breakdown.similarity * DEFAULT_NON_GAME_PENALTY).abs() < 1e-9);
+
+        let lenient = RapidfuzzRanker::with_penalties(1.0, 1.0).rank("valheim", &candidates).await.unwrap();
+        assert_eq!(lenient[0].score, breakdown.similarity);
+    }
+
+    #[tokio::test]
+    async fn test_rapidfuzz_explain_details() {
+        let ranker = RapidfuzzRanker::new();
+        let candidates = vec![GameResult::new("steam", "1", "Portal 2 - Soundtrack")];
+
+        let plain = ranker.rank("Portal 2", &candidates).await.unwrap();
+        assert_eq!(plain[0].breakdown.as_ref().unwrap().normalized_query, None);
+
+        let explained = ranker.rank_explain("Portal 2", &candidates).await.unwrap();
+        let breakdown = explained[0].breakdown.as_ref().unwrap();
+        assert_eq!(breakdown.normalized_query.as_deref(), Some("portal 2"));
+        assert_eq!(breakdown.normalized_candidate.as_deref(), Some("portal 2 - soundtrack"));
+        assert_eq!(breakdown.penalties[0].reason, "dlc");
+        assert_eq!(explained[0].score, plain[0].score);
+    }
+
+    #[tokio::test]
+    async fn test_rapidfuzz_folds_accents_and_symbols() {
+        let folding = RapidfuzzRanker::new();
+        let plain = RapidfuzzRanker::with_options(DEFAULT_DLC_PENALTY, DEFAULT_NON_GAME_PENALTY, false);
+        for (query, name) in [
+            ("pokemon", "Pokémon™"),
+            ("realite virtuelle", "Réalité Virtuelle"),
+            ("uber die brucke", "Über die Brücke"),
+            ("assassin's creed", "Assassin’s Creed®"),
+        ] {
+            let candidates = vec![GameResult::new("steam", "1", name)];
+            assert_eq!(folding.rank(query, &candidates).await.unwrap()[0].score, 100.0, "{}", name);
+            assert!(plain.rank(query, &candidates).await.unwrap()[0].score < 100.0, "{}", name);
+        }
+
+        let candidates = vec![GameResult::new("steam", "1", "Pokémon™ Legends: Arceus")];
+        let explained = folding.rank_explain("Pokémon Legends", &candidates).await.unwrap();
+        let breakdown = explained[0].breakdown.as_ref().unwrap();
+        assert_eq!(breakdown.normalized_query.as_deref(), Some("pokemon legends"));
+        assert_eq!(breakdown.normalized_candidate.as_deref(), Some("pokemon legends: arceus"));
+    }
+
+    #[tokio::test]
+    async fn test_rapidfuzz_ranks_against_aliases() {
+        let mut replicant = GameResult::new("igdb", "1", "ニーア レプリカント");
+        replicant.aliases = vec!["Nier Replicant ver.1.22".to_string(), "Nier Replicant".to_string()];
+        let candidates = vec![GameResult::new("igdb", "2", "NieR:Automata"), replicant];
+
+        let ranked = RapidfuzzRanker::new().rank_explain("nier replicant ver.1.22", &candidates).await.unwrap();
+        // Aliases weigh 0.9 by default
+        assert_eq!((ranked[0].game.id.as_str(), ranked[0].score), ("1", 90.0));
+        let breakdown = ranked[0].breakdown.as_ref().unwrap();
+        assert_eq!(breakdown.matched_alias.as_deref(), Some("Nier Replicant ver.1.22"));
+        assert_eq!(breakdown.normalized_candidate.as_deref(), Some("nier replicant ver.1.22"));
+        // The name itself matched best: no alias recorded
+        assert_eq!(ranked[1].breakdown.as_ref().unwrap().matched_alias, None);
+    }
+
+    fn elden_ring() -> GameResult {
+        let mut game = GameResult::new("steam", "1245620", "ELDEN RING");
+        game.aliases = vec!["Elden Ring GOTY".to_string()];
+        game.developers = vec!["FromSoftware, Inc.".to_string()];
+        game.publishers = vec!["Bandai Namco Entertainment".to_string()];
+        game.genres = vec!["Action".to_string(), "RPG".to_string()];
+        game
+    }
+
+    #[tokio::test]
+    async fn test_rapidfuzz_name_only_weights_ignore_other_fields() {
+        let ranker = RapidfuzzRanker::new().with_field_weights(FieldWeights::NAME_ONLY);
+        let bare = [GameResult::new("steam", "1245620", "ELDEN RING")];
+        for query in ["elden ring", "elden ring goty", "fromsoftware", "rpg"] {
+            let fields = ranker.rank_explain(query, &[elden_ring()]).await.unwrap();
+            let name = ranker.rank_explain(query, &bare).await.unwrap();
+            assert_eq!((fields[0].score, &fields[0].breakdown), (name[0].score, &name[0].breakdown), "{}", query);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_rapidfuzz_matches_developers() {
+        let mut hollow_knight = GameResult::new("steam", "367520", "Hollow Knight");
+        hollow_knight.developers = vec!["Team Cherry".to_string()];
+        let candidates = vec![GameResult::new("steam", "413150", "Stardew Valley"), hollow_knight, elden_ring()];
+
+        let ranked = RapidfuzzRanker::new().rank_explain("fromsoftware new game", &candidates).await.unwrap();
+        assert_eq!(ranked[0].game.name, "ELDEN RING");
+        assert_eq!(ranked[0].breakdown.as_ref().unwrap().bonuses[0].reason, "developers");
+
+        let titles_only = RapidfuzzRanker::new().with_field_weights(FieldWeights::NAME_ONLY);
+        assert_ne!(titles_only.rank("fromsoftware new game", &candidates).await.unwrap()[0].game.name, "ELDEN RING");
+    }
 }
@@ -0,0 +1,139 @@
+//! Text fields of a candidate the rankers match the query against, and how
+//! much each one counts: "fromsoft new game" names no title, only a
+//! developer
+
+use crate::core::{GameResult, ScoreBonus, ScoreBreakdown, ScorePenalty};
+
+/// Weight of each field of `CandidateText` (0 ignores the field)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FieldWeights {
+    pub name: f64,
+    pub aliases: f64,
+    /// Developers and publishers
+    pub developers: f64,
+    pub genres: f64,
+}
+
+impl FieldWeights {
+    /// The name alone, as rankers matched before aliases and metadata
+    pub const NAME_ONLY: FieldWeights = FieldWeights { name: 1.0, aliases: 0.0, developers: 0.0, genres: 0.0 };
+}
+
+impl Default for FieldWeights {
+    fn default() -> Self {
+        Self { name: 1.0, aliases: 0.9, developers: 0.3, genres: 0.1 }
+    }
+}
+
+/// Text fields of a `GameResult`, as the rankers read them
+#[derive(Debug, Clone, PartialEq)]
+pub struct CandidateText<'a> {
+    pub name: &'a str,
+    pub aliases: Vec<&'a str>,
+    /// Developers then publishers, without repeats
+    pub developers: Vec<&'a str>,
+    pub genres: Vec<&'a str>,
+}
+
+impl<'a> From<&'a GameResult> for CandidateText<'a> {
+    fn from(game: &'a GameResult) -> Self {
+        let mut developers: Vec<&str> = Vec::new();
+        for company in game.developers.iter().chain(&game.publishers) {
+            if !developers.contains(&company.as_str()) {
+                developers.push(company);
+            }
+        }
+        Self {
+            name: &game.name,
+            aliases: game.aliases.iter().map(String::as_str).collect(),
+            developers,
+            genres: game.genres.iter().map(String::as_str).collect(),
+        }
+    }
+}
+
+/// `breakdown` scaled by `weight`, the difference recorded as a `reason` penalty
+fn weighted(mut breakdown: ScoreBreakdown, weight: f64, reason: &str) -> ScoreBreakdown {
+    if weight != 1.0 {
+        let points = breakdown.score() * (1.0 - weight);
+        breakdown.penalties.push(ScorePenalty { reason: reason.to_string(), points });
+    }
+    breakdown
+}
+
+/// Breakdown of a candidate over its fields, `score` comparing the query to
+/// one text: the best of the weighted name and aliases (`matched_alias` set
+/// when an alias wins, the name winning ties), then for developers and
+/// genres a bonus of `weight × (field − title)` when the field matches the
+/// query better than the title does. With `FieldWeights::NAME_ONLY` this is
+/// the name's breakdown, untouched.
+pub fn score_fields(game: &GameResult, weights: &FieldWeights, mut score: impl FnMut(&str) -> ScoreBreakdown) -> ScoreBreakdown {
+    let text = CandidateText::from(game);
+    let mut best = weighted(score(text.name), weights.name, "name");
+    if weights.aliases > 0.0 {
+        for alias in &text.aliases {
+            let breakdown = weighted(score(alias), weights.aliases, "alias");
+            if breakdown.score() > best.score() {
+                best = ScoreBreakdown { matched_alias: Some(alias.to_string()), ..breakdown };
+            }
+        }
+    }
+
+    let title = best.score();
+    for (reason, weight, texts) in [
+        ("developers", weights.developers, &text.developers),
+        ("genres", weights.genres, &text.genres),
+    ] {
+        if weight <= 0.0 {
+            continue;
+        }
+        let field = texts.iter().map(|text| score(text).score()).fold(0.0, f64::max);
+        let points = weight * (field - title);
+        if points > 0.0 {
+            best.bonuses.push(ScoreBonus { reason: reason.to_string(), points });
+        }
+    }
+    best
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 100 for texts equal to "fromsoftware", 30 for the others
+    fn fixed(text: &str) -> ScoreBreakdown {
+        ScoreBreakdown::new("fixed", if text == "FromSoftware" { 100.0 } else { 30.0 }, 1)
+    }
+
+    fn elden_ring() -> GameResult {
+        let mut game = GameResult::new("steam", "1245620", "ELDEN RING");
+        game.aliases = vec!["Elden Ring Tarnished Edition".to_string()];
+        game.developers = vec!["FromSoftware".to_string()];
+        game.publishers = vec!["FromSoftware".to_string(), "Bandai Namco".to_string()];
+        game.genres = vec!["Action".to_string(), "RPG".to_string()];
+        game
+    }
+
+    #[test]
+    fn test_candidate_text() {
+        let game = elden_ring();
+        let text = CandidateText::from(&game);
+        assert_eq!(text.name, "ELDEN RING");
+        assert_eq!(text.developers, vec!["FromSoftware", "Bandai Namco"]);
+        assert_eq!(text.genres, vec!["Action", "RPG"]);
+    }
+
+    #[test]
+    fn test_developer_match_adds_bonus() {
+        let breakdown = score_fields(&elden_ring(), &FieldWeights::default(), fixed);
+        assert_eq!(breakdown.bonuses.len(), 1);
+        assert_eq!(breakdown.bonuses[0].reason, "developers");
+        assert!((breakdown.score() - (30.0 + 0.3 * 70.0)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_name_only_is_the_name() {
+        let breakdown = score_fields(&elden_ring(), &FieldWeights::NAME_ONLY, fixed);
+        assert_eq!(breakdown, fixed("ELDEN RING"));
+    }
+}
@@ -1,19 +1,192 @@
+pub mod acronyms;
+pub mod calibration;
+pub mod delta;
 pub mod drakon;
+pub mod editions;
+pub mod ensemble;
 pub mod fallback;
+pub mod fields;
+pub mod resilient;
 
-use crate::core::GameResult;
+use async_trait::async_trait;
+
+use crate::core::{GameResult, ScoreBonus, ScoreBreakdown, ScorePenalty};
 use crate::error::Result;
 
-pub use drakon::DrakonRanker;
+pub use acronyms::Acronyms;
+pub use calibration::{calibrate, calibrate_ranked, ScoreScale};
+pub use delta::{DeltaProfile, DeltaRanker};
+pub use drakon::{DrakonLimits, DrakonRanker};
+pub use ensemble::{EnsembleMode, EnsembleRanker};
 pub use fallback::RapidfuzzRanker;
+pub use fields::{score_fields, CandidateText, FieldWeights};
+pub use resilient::{BreakerConfig, CircuitState, ResilientRanker};
 
 /// Trait for ranking/fuzzy matching implementations
+#[async_trait]
 pub trait Ranker: Send + Sync {
     /// Rank candidates against query, return sorted by score (highest first)
-    fn rank(&self, query: &str, candidates: &[GameResult]) -> Result<Vec<RankedCandidate>>;
+    async fn rank(&self, query: &str, candidates: &[GameResult]) -> Result<Vec<RankedCandidate>>;
+
+    /// `rank`, with breakdowns detailed enough to debug a mis-ranking (the
+    /// normalized strings compared); rankers without more to tell rank as usual
+    async fn rank_explain(&self, query: &str, candidates: &[GameResult]) -> Result<Vec<RankedCandidate>> {
+        self.rank(query, candidates).await
+    }
+
+    /// `rank` (`rank_explain` when `explain`) with the name of the ranker
+    /// that actually ranked, which a ranker handing the call to another one
+    /// (see `ResilientRanker`) reports without building breakdowns
+    async fn rank_attributed(&self, query: &str, candidates: &[GameResult], explain: bool) -> Result<(Vec<RankedCandidate>, String)> {
+        let ranked = if explain {
+            self.rank_explain(query, candidates).await?
+        } else {
+            self.rank(query, candidates).await?
+        };
+        Ok((ranked, self.name().to_string()))
+    }
     
     /// Get ranker name for logging
     fn name(&self) -> &str;
+
+    /// Table putting this ranker's raw scores on the common scale the
+    /// engine thresholds expect (see `calibration`); rankers already on it
+    /// keep the identity
+    fn score_scale(&self) -> ScoreScale {
+        ScoreScale::IDENTITY
+    }
+}
+
+/// Score gap under which two candidates count as tied (see `break_ties`)
+pub const DEFAULT_TIE_EPSILON: f64 = 1.0;
+
+/// Order every ranker returns: score descending, then name, then provider
+/// and id, so candidates with equal scores come out the same whatever order
+/// the providers answered in. NaN scores are set to 0 first.
+pub fn sort_ranked(ranked: &mut [RankedCandidate]) {
+    for candidate in ranked.iter_mut().filter(|c| c.score.is_nan()) {
+        candidate.score = 0.0;
+    }
+    ranked.sort_by(|a, b| {
+        b.score
+            .total_cmp(&a.score)
+            .then_with(|| a.game.name.cmp(&b.game.name))
+            .then_with(|| a.game.provider.cmp(&b.game.provider))
+            .then_with(|| a.game.id.cmp(&b.game.id))
+    });
+}
+
+/// Release year spelled out in a query ("resident evil 4 2005")
+pub fn query_year(query: &str) -> Option<i32> {
+    query
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|token| token.len() == 4)
+        .filter_map(|token| token.parse().ok())
+        .find(|year| (1950..=2100).contains(year))
+}
+
+/// Post-ranking pass shared by every ranker: candidates scoring within
+/// `epsilon` of the best of their group are ordered by the year given in
+/// the query, then newest release, then shortest name, instead of by
+/// whatever order the providers returned them in. Scores are left as they
+/// are (a group's winner may score a little below the runner-up);
+/// `epsilon` 0 keeps the ranker's order.
+pub fn break_ties(query: &str, ranked: &mut [RankedCandidate], epsilon: f64) {
+    if epsilon <= 0.0 {
+        return;
+    }
+    let year = query_year(query);
+    let preference = |candidate: &RankedCandidate| {
+        let game = &candidate.game;
+        (
+            std::cmp::Reverse(year.is_some() && game.year == year),
+            std::cmp::Reverse(game.year),
+            game.name.chars().count(),
+        )
+    };
+
+    let mut start = 0;
+    while start < ranked.len() {
+        let top = ranked[start].score;
+        let end = start + ranked[start..].iter().take_while(|c| top - c.score <= epsilon).count();
+        ranked[start..end].sort_by_key(preference);
+        start = end;
+    }
+}
+
+/// Weight of the popularity prior when enabled (see `apply_popularity`)
+pub const DEFAULT_POPULARITY_WEIGHT: f64 = 0.05;
+
+/// Score gap to the best candidate within which the popularity prior applies
+pub const DEFAULT_POPULARITY_BAND: f64 = 3.0;
+
+/// Popularity prior for near-ties ("doom" among a dozen "DOOM something"):
+/// candidates scoring within `band` points of the best are rescored
+/// `score * (1 - weight) + popularity * weight`, popularity being
+/// log-scaled to 0-100 against the most popular of them (unknown counts as
+/// 0), then re-sorted. Candidates outside the band are left alone and stay
+/// behind it; nothing happens with a single candidate in the band or no
+/// popularity known there. The change is recorded as a `popularity` bonus
+/// or penalty.
+pub fn apply_popularity(ranked: &mut [RankedCandidate], weight: f64, band: f64) {
+    let Some(top) = ranked.first().map(|c| c.score) else {
+        return;
+    };
+    let end = ranked.iter().take_while(|c| top - c.score <= band).count();
+    let most_popular = ranked[..end].iter().filter_map(|c| c.game.popularity).fold(0.0, f64::max);
+    if weight <= 0.0 || end < 2 || most_popular <= 0.0 {
+        return;
+    }
+
+    let floor = ranked.get(end).map_or(f64::NEG_INFINITY, |c| c.score);
+    for candidate in &mut ranked[..end] {
+        let popularity = candidate.game.popularity.unwrap_or(0.0).max(0.0).ln_1p() / most_popular.ln_1p() * 100.0;
+        let blended = (candidate.score * (1.0 - weight) + popularity * weight).max(floor);
+        let points = blended - candidate.score;
+        candidate.score = blended;
+        if let Some(breakdown) = candidate.breakdown.as_mut() {
+            let reason = "popularity".to_string();
+            if points >= 0.0 {
+                breakdown.bonuses.push(ScoreBonus { reason, points });
+            } else {
+                breakdown.penalties.push(ScorePenalty { reason, points: -points });
+            }
+        }
+    }
+    sort_ranked(&mut ranked[..end]);
+}
+
+/// `ranking_method` of results matched by name without a ranking pass
+pub const EXACT_MATCH: &str = "exact";
+
+/// Name as compared by the engine's exact-match fast path: lowercase,
+/// without ™/®/© and with whitespace collapsed ("ELDEN RING™" → "elden ring")
+pub fn exact_key(name: &str) -> String {
+    name.split(|c: char| c.is_whitespace() || matches!(c, '™' | '®' | '©'))
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+/// Cheap name pre-score used to trim candidate lists before ranking:
+/// share of query words found in the name, +1 for an exact match
+pub fn prescore(query: &str, name: &str) -> f64 {
+    let query = query.trim().to_lowercase();
+    let name = name.trim().to_lowercase();
+    let words: Vec<&str> = query.split_whitespace().collect();
+    if words.is_empty() {
+        return 0.0;
+    }
+
+    let found = words.iter().filter(|w| name.contains(*w)).count();
+    let exact = if name == query { 1.0 } else { 0.0 };
+    found as f64 / words.len() as f64 + exact
+}
+
+/// Best `prescore` of a game's name and aliases
+pub fn prescore_game(query: &str, game: &GameResult) -> f64 {
+    game.names().map(|name| prescore(query, name)).fold(0.0, f64::max)
 }
 
 /// Candidate with similarity score
@@ -21,10 +194,190 @@ pub trait Ranker: Send + Sync {
 pub struct RankedCandidate {
     pub game: GameResult,
     pub score: f64,
+    /// How `score` was computed, copied into `SearchResponse::score_breakdown`
+    pub breakdown: Option<ScoreBreakdown>,
 }
 
 impl RankedCandidate {
     pub fn new(game: GameResult, score: f64) -> Self {
-        Self { game, score }
+        Self { game, score, breakdown: None }
+    }
+
+    /// Candidate scored from its breakdown
+    pub fn explained(game: GameResult, breakdown: ScoreBreakdown) -> Self {
+        Self {
+            game,
+            score: breakdown.score(),
+            breakdown: Some(breakdown),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_exact_key() {
+        assert_eq!(exact_key("ELDEN RING™"), "elden ring");
+        assert_eq!(exact_key("  Half-Life®   2 "), "half-life 2");
+        assert_eq!(exact_key("Portal\t2"), "portal 2");
+        assert_ne!(exact_key("Hades II"), exact_key("hades"));
+    }
+
+    #[test]
+    fn test_prescore() {
+        assert_eq!(prescore("Hades", " hades "), 2.0);
+        assert_eq!(prescore("hades", "Hades II"), 1.0);
+        assert_eq!(prescore("vampire survivors", "Vampire: The Masquerade"), 0.5);
+        assert_eq!(prescore("hades", "Celeste"), 0.0);
+        assert_eq!(prescore("  ", "Celeste"), 0.0);
+    }
+
+    fn candidate(name: &str, year: Option<i32>, score: f64) -> RankedCandidate {
+        let mut game = GameResult::new("steam", name, name);
+        game.year = year;
+        RankedCandidate::new(game, score)
+    }
+
+    fn names(ranked: &[RankedCandidate]) -> Vec<&str> {
+        ranked.iter().map(|c| c.game.name.as_str()).collect()
+    }
+
+    #[test]
+    fn test_query_year() {
+        assert_eq!(query_year("resident evil 4 2005"), Some(2005));
+        assert_eq!(query_year("Resident Evil 4 (2023)"), Some(2023));
+        assert_eq!(query_year("resident evil 4"), None);
+        assert_eq!(query_year("1942"), None);
+    }
+
+    #[test]
+    fn test_break_ties_prefers_year_from_query() {
+        let mut ranked = vec![
+            candidate("Resident Evil 4", Some(2023), 96.0),
+            candidate("Resident Evil 4", Some(2005), 95.5),
+        ];
+        break_ties("resident evil 4 2005", &mut ranked, DEFAULT_TIE_EPSILON);
+        assert_eq!(ranked[0].game.year, Some(2005));
+    }
+
+    #[test]
+    fn test_break_ties_prefers_newer_release() {
+        let mut ranked = vec![
+            candidate("Resident Evil 4", Some(2005), 96.0),
+            candidate("Resident Evil 4", None, 95.8),
+            candidate("Resident Evil 4", Some(2023), 95.5),
+        ];
+        break_ties("resident evil 4", &mut ranked, DEFAULT_TIE_EPSILON);
+        let years: Vec<Option<i32>> = ranked.iter().map(|c| c.game.year).collect();
+        assert_eq!(years, vec![Some(2023), Some(2005), None]);
+
+        // Same ranking whatever order the providers returned them in
+        ranked.reverse();
+        break_ties("resident evil 4", &mut ranked, DEFAULT_TIE_EPSILON);
+        assert_eq!(ranked[0].game.year, Some(2023));
+    }
+
+    #[test]
+    fn test_break_ties_prefers_shorter_name() {
+        let mut ranked = vec![
+            candidate("Hades: Deluxe", Some(2020), 90.0),
+            candidate("Hades", Some(2020), 89.5),
+            candidate("Hades II", Some(2024), 80.0),
+        ];
+        break_ties("hades", &mut ranked, DEFAULT_TIE_EPSILON);
+        assert_eq!(names(&ranked), vec!["Hades", "Hades: Deluxe", "Hades II"]);
+
+        // Outside epsilon (or with epsilon 0) the ranker's order stands
+        let mut ranked = vec![candidate("Hades: Deluxe", Some(2020), 90.0), candidate("Hades", Some(2020), 88.0)];
+        break_ties("hades", &mut ranked, DEFAULT_TIE_EPSILON);
+        assert_eq!(names(&ranked), vec!["Hades: Deluxe", "Hades"]);
+        let mut ranked = vec![candidate("Hades: Deluxe", Some(2020), 90.0), candidate("Hades", Some(2020), 89.5)];
+        break_ties("hades", &mut ranked, 0.0);
+        assert_eq!(names(&ranked), vec!["Hades: Deluxe", "Hades"]);
+    }
+
+    #[tokio::test]
+    async fn test_equal_scores_ranked_in_stable_order() {
+        let mut candidates = vec![
+            GameResult::new("steam", "2", "Hades"),
+            GameResult::new("igdb", "9", "Hades"),
+            GameResult::new("steam", "1", "Hades"),
+            GameResult::new("gog", "3", "Celeste"),
+            GameResult::new("steam", "4", "Hades II"),
+            GameResult::new("igdb", "5", "Celeste"),
+        ];
+        let ranker = RapidfuzzRanker::new();
+        let expected = ranker.rank("hades", &candidates).await.unwrap();
+        let key = |ranked: &[RankedCandidate]| -> Vec<(String, String)> {
+            ranked.iter().map(|c| (c.game.provider.clone(), c.game.id.clone())).collect()
+        };
+        assert_eq!(&key(&expected)[..3], &[
+            ("igdb".to_string(), "9".to_string()),
+            ("steam".to_string(), "1".to_string()),
+            ("steam".to_string(), "2".to_string()),
+        ]);
+
+        // Shuffle with a fixed LCG (no rand dependency)
+        let mut seed: u64 = 42;
+        for _ in 0..100 {
+            for i in (1..candidates.len()).rev() {
+                seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+                candidates.swap(i, (seed >> 33) as usize % (i + 1));
+            }
+            let ranked = ranker.rank("hades", &candidates).await.unwrap();
+            assert_eq!(key(&ranked), key(&expected));
+        }
+    }
+
+    fn popular(name: &str, score: f64, popularity: Option<f64>) -> RankedCandidate {
+        let mut game = GameResult::new("steam", name, name);
+        game.popularity = popularity;
+        RankedCandidate::explained(game, ScoreBreakdown::new("test", score, 4))
+    }
+
+    #[test]
+    fn test_popularity_breaks_near_ties() {
+        let mut ranked = vec![
+            popular("DOOM Shovelware", 99.0, None),
+            popular("DOOM", 98.0, Some(158_243.0)),
+            popular("DOOM Clicker", 97.5, Some(12.0)),
+            popular("Doom Eternal", 80.0, Some(250_000.0)),
+        ];
+        apply_popularity(&mut ranked, DEFAULT_POPULARITY_WEIGHT, DEFAULT_POPULARITY_BAND);
+        assert_eq!(names(&ranked), vec!["DOOM", "DOOM Shovelware", "DOOM Clicker", "Doom Eternal"]);
+        let winner = ranked[0].breakdown.as_ref().unwrap();
+        assert_eq!(winner.bonuses[0].reason, "popularity");
+        assert_eq!(winner.score(), ranked[0].score);
+        assert_eq!(ranked[1].breakdown.as_ref().unwrap().penalties[0].reason, "popularity");
+        // Outside the band: untouched, and still behind it
+        assert_eq!(ranked[3].score, 80.0);
+        assert!(ranked[2].score >= 80.0);
+    }
+
+    #[test]
+    fn test_popularity_leaves_clear_winners_alone() {
+        let clear = || vec![popular("Doom", 99.0, None), popular("Doom Eternal", 90.0, Some(250_000.0))];
+        let mut ranked = clear();
+        apply_popularity(&mut ranked, DEFAULT_POPULARITY_WEIGHT, DEFAULT_POPULARITY_BAND);
+        assert_eq!(ranked.iter().map(|c| c.score).collect::<Vec<_>>(), vec![99.0, 90.0]);
+        assert!(ranked[0].breakdown.as_ref().unwrap().bonuses.is_empty());
+
+        // Near-tie without any popularity known, or weight 0
+        let mut unknown = vec![popular("Doom", 99.0, None), popular("Doom II", 98.5, None)];
+        apply_popularity(&mut unknown, DEFAULT_POPULARITY_WEIGHT, DEFAULT_POPULARITY_BAND);
+        assert_eq!(names(&unknown), vec!["Doom", "Doom II"]);
+        let mut ranked = vec![popular("Doom", 99.0, None), popular("Doom II", 98.5, Some(10.0))];
+        apply_popularity(&mut ranked, 0.0, DEFAULT_POPULARITY_BAND);
+        assert_eq!((names(&ranked), ranked[0].score), (vec!["Doom", "Doom II"], 99.0));
+    }
+
+    #[test]
+    fn test_sort_ranked_treats_nan_as_zero() {
+        let mut ranked = vec![candidate("B", None, f64::NAN), candidate("A", None, 0.0), candidate("C", None, 10.0)];
+        sort_ranked(&mut ranked);
+        assert_eq!(names(&ranked), vec!["C", "A", "B"]);
+        assert_eq!(ranked[2].score, 0.0);
     }
 }
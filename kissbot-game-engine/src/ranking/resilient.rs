@@ -0,0 +1,293 @@
+use async_trait::async_trait;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::core::{GameResult, ScoreBreakdown};
+use crate::ranking::{calibrate_ranked, Ranker, RankedCandidate};
+use crate::error::Result;
+
+/// Consecutive failures of the primary ranker opening the circuit
+pub const DEFAULT_BREAKER_THRESHOLD: u32 = 3;
+
+/// How long an open circuit skips the primary ranker before probing it again
+pub const DEFAULT_BREAKER_COOL_DOWN: Duration = Duration::from_secs(30);
+
+/// When `ResilientRanker` stops calling its primary ranker, and for how long
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BreakerConfig {
+    /// Consecutive failures opening the circuit (at least 1)
+    pub failure_threshold: u32,
+    /// Time the circuit stays open before a call probes the primary again
+    pub cool_down: Duration,
+}
+
+impl Default for BreakerConfig {
+    fn default() -> Self {
+        Self {
+            failure_threshold: DEFAULT_BREAKER_THRESHOLD,
+            cool_down: DEFAULT_BREAKER_COOL_DOWN,
+        }
+    }
+}
+
+/// State of a `ResilientRanker` circuit
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CircuitState {
+    /// Calls go to the primary ranker
+    Closed,
+    /// Calls go to the fallback until the cool-down ends
+    Open,
+    /// One call is probing the primary, the others use the fallback
+    HalfOpen,
+}
+
+#[derive(Debug)]
+enum Circuit {
+    Closed { failures: u32 },
+    Open { until: Instant },
+    /// A probe started at `since`; another may start after a cool-down, in
+    /// case that one never came back (search cancelled)
+    HalfOpen { since: Instant },
+}
+
+/// Primary ranker (DRAKON) guarded by a circuit breaker: a failed call is
+/// ranked again by the fallback so the search still succeeds, and after
+/// `failure_threshold` failures in a row the primary is skipped for
+/// `cool_down`, then probed by a single call. `rank_attributed` names the
+/// ranker that actually ranked (`ranking_method` of the response), as do
+/// the breakdowns when explaining, and scores come out calibrated with
+/// that ranker's scale.
+pub struct ResilientRanker {
+    primary: Arc<dyn Ranker>,
+    fallback: Arc<dyn Ranker>,
+    config: BreakerConfig,
+    circuit: Mutex<Circuit>,
+}
+
+impl ResilientRanker {
+    pub fn new(primary: Arc<dyn Ranker>, fallback: Arc<dyn Ranker>, config: BreakerConfig) -> Self {
+        Self {
+            primary,
+            fallback,
+            config,
+            circuit: Mutex::new(Circuit::Closed { failures: 0 }),
+        }
+    }
+
+    pub fn state(&self) -> CircuitState {
+        match *self.circuit.lock().unwrap_or_else(|e| e.into_inner()) {
+            Circuit::Closed { .. } => CircuitState::Closed,
+            Circuit::Open { .. } => CircuitState::Open,
+            Circuit::HalfOpen { .. } => CircuitState::HalfOpen,
+        }
+    }
+
+    /// Whether this call goes to the primary ranker (half-opening an open
+    /// circuit past its cool-down)
+    fn try_primary(&self) -> bool {
+        let mut circuit = self.circuit.lock().unwrap_or_else(|e| e.into_inner());
+        let now = Instant::now();
+        match *circuit {
+            Circuit::Closed { .. } => true,
+            Circuit::Open { until } if now < until => false,
+            Circuit::HalfOpen { since } if now.duration_since(since) < self.config.cool_down => false,
+            Circuit::Open { .. } | Circuit::HalfOpen { .. } => {
+                tracing::info!("🔌 Probing {} ranker", self.primary.name());
+                *circuit = Circuit::HalfOpen { since: now };
+                true
+            }
+        }
+    }
+
+    fn record_success(&self) {
+        let mut circuit = self.circuit.lock().unwrap_or_else(|e| e.into_inner());
+        if !matches!(*circuit, Circuit::Closed { .. }) {
+            tracing::info!("✅ {} ranker is back, circuit closed", self.primary.name());
+        }
+        *circuit = Circuit::Closed { failures: 0 };
+    }
+
+    fn record_failure(&self) {
+        let mut circuit = self.circuit.lock().unwrap_or_else(|e| e.into_inner());
+        let failures = match *circuit {
+            Circuit::Closed { failures } => failures + 1,
+            // A failed probe reopens right away
+            Circuit::Open { .. } | Circuit::HalfOpen { .. } => self.config.failure_threshold,
+        };
+        *circuit = if failures >= self.config.failure_threshold.max(1) {
+            tracing::warn!(
+                "⚠️ {} ranker failed {} times, using {} for {:?}",
+                self.primary.name(),
+                failures,
+                self.fallback.name(),
+                self.config.cool_down
+            );
+            Circuit::Open { until: Instant::now() + self.config.cool_down }
+        } else {
+            Circuit::Closed { failures }
+        };
+    }
+
+    async fn rank_with(&self, query: &str, candidates: &[GameResult], explain: bool) -> Result<(Vec<RankedCandidate>, String)> {
+        if self.try_primary() {
+            match rank_by(self.primary.as_ref(), query, candidates, explain).await {
+                Ok(ranked) => {
+                    self.record_success();
+                    return Ok(attributed(ranked, self.primary.name(), candidates.len(), explain));
+                }
+                Err(e) => {
+                    tracing::warn!("⚠️ {} ranking failed, using {}: {}", self.primary.name(), self.fallback.name(), e);
+                    self.record_failure();
+                }
+            }
+        }
+        let ranked = rank_by(self.fallback.as_ref(), query, candidates, explain).await?;
+        Ok(attributed(ranked, self.fallback.name(), candidates.len(), explain))
+    }
+}
+
+/// Ranking of `ranker`, calibrated
+async fn rank_by(ranker: &dyn Ranker, query: &str, candidates: &[GameResult], explain: bool) -> Result<Vec<RankedCandidate>> {
+    let mut ranked = if explain {
+        ranker.rank_explain(query, candidates).await?
+    } else {
+        ranker.rank(query, candidates).await?
+    };
+    calibrate_ranked(&mut ranked, &ranker.score_scale());
+    Ok(ranked)
+}
+
+/// `ranked` with the name of `ranker`, also given by a breakdown on every
+/// candidate when `explain` is set
+fn attributed(mut ranked: Vec<RankedCandidate>, ranker: &str, candidates: usize, explain: bool) -> (Vec<RankedCandidate>, String) {
+    if explain {
+        for candidate in ranked.iter_mut().filter(|c| c.breakdown.is_none()) {
+            candidate.breakdown = Some(ScoreBreakdown::new(ranker, candidate.score, candidates));
+        }
+    }
+    (ranked, ranker.to_string())
+}
+
+#[async_trait]
+impl Ranker for ResilientRanker {
+    async fn rank(&self, query: &str, candidates: &[GameResult]) -> Result<Vec<RankedCandidate>> {
+        Ok(self.rank_with(query, candidates, false).await?.0)
+    }
+
+    async fn rank_explain(&self, query: &str, candidates: &[GameResult]) -> Result<Vec<RankedCandidate>> {
+        Ok(self.rank_with(query, candidates, true).await?.0)
+    }
+
+    async fn rank_attributed(&self, query: &str, candidates: &[GameResult], explain: bool) -> Result<(Vec<RankedCandidate>, String)> {
+        self.rank_with(query, candidates, explain).await
+    }
+
+    /// The primary's name: the engine's DRAKON monitor treats a guarded
+    /// DRAKON as DRAKON
+    fn name(&self) -> &str {
+        self.primary.name()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::GameEngineError;
+    use crate::ranking::RapidfuzzRanker;
+    use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+    /// Primary failing while `down` is set, counting its calls
+    #[derive(Default)]
+    struct Flaky {
+        down: AtomicBool,
+        calls: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl Ranker for Flaky {
+        async fn rank(&self, _query: &str, candidates: &[GameResult]) -> Result<Vec<RankedCandidate>> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            if self.down.load(Ordering::SeqCst) {
+                return Err(GameEngineError::DrakonApi("Request failed: connection refused".to_string()));
+            }
+            Ok(candidates.iter().map(|g| RankedCandidate::new(g.clone(), 99.0)).collect())
+        }
+
+        fn name(&self) -> &str {
+            "drakon"
+        }
+    }
+
+    fn guarded(cool_down: Duration) -> (Arc<Flaky>, ResilientRanker) {
+        let primary = Arc::new(Flaky::default());
+        let config = BreakerConfig { failure_threshold: 2, cool_down };
+        let ranker = ResilientRanker::new(primary.clone(), Arc::new(RapidfuzzRanker::new()), config);
+        (primary, ranker)
+    }
+
+    async fn ranked_by(ranker: &ResilientRanker) -> String {
+        let candidates = vec![GameResult::new("steam", "1", "Hades")];
+        ranker.rank_attributed("hades", &candidates, false).await.unwrap().1
+    }
+
+    #[tokio::test]
+    async fn test_failed_call_falls_back() {
+        let (primary, ranker) = guarded(DEFAULT_BREAKER_COOL_DOWN);
+        assert_eq!(ranked_by(&ranker).await, "drakon");
+
+        primary.down.store(true, Ordering::SeqCst);
+        assert_eq!(ranked_by(&ranker).await, "rapidfuzz");
+        assert_eq!(ranker.state(), CircuitState::Closed);
+        assert_eq!(ranker.name(), "drakon");
+    }
+
+    #[tokio::test]
+    async fn test_circuit_opens_after_threshold() {
+        let (primary, ranker) = guarded(DEFAULT_BREAKER_COOL_DOWN);
+        primary.down.store(true, Ordering::SeqCst);
+        ranked_by(&ranker).await;
+        ranked_by(&ranker).await;
+        assert_eq!(ranker.state(), CircuitState::Open);
+
+        // Skipped while open, even once back up
+        primary.down.store(false, Ordering::SeqCst);
+        assert_eq!(ranked_by(&ranker).await, "rapidfuzz");
+        assert_eq!(primary.calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_half_open_probe() {
+        let (primary, ranker) = guarded(Duration::ZERO);
+        primary.down.store(true, Ordering::SeqCst);
+        ranked_by(&ranker).await;
+        ranked_by(&ranker).await;
+
+        // Failed probe: open again
+        assert_eq!(ranked_by(&ranker).await, "rapidfuzz");
+        assert_eq!((ranker.state(), primary.calls.load(Ordering::SeqCst)), (CircuitState::Open, 3));
+
+        primary.down.store(false, Ordering::SeqCst);
+        assert_eq!(ranked_by(&ranker).await, "drakon");
+        assert_eq!(ranker.state(), CircuitState::Closed);
+    }
+
+    #[tokio::test]
+    async fn test_breakdowns_only_when_explained() {
+        let (_, ranker) = guarded(DEFAULT_BREAKER_COOL_DOWN);
+        let candidates = vec![GameResult::new("steam", "1", "Hades")];
+        assert_eq!(ranker.rank("hades", &candidates).await.unwrap()[0].breakdown, None);
+
+        let explained = ranker.rank_explain("hades", &candidates).await.unwrap();
+        assert_eq!(explained[0].breakdown.as_ref().unwrap().ranker, "drakon");
+    }
+
+    #[tokio::test]
+    async fn test_success_resets_failures() {
+        let (primary, ranker) = guarded(DEFAULT_BREAKER_COOL_DOWN);
+        for down in [true, false, true] {
+            primary.down.store(down, Ordering::SeqCst);
+            ranked_by(&ranker).await;
+        }
+        assert_eq!(ranker.state(), CircuitState::Closed);
+    }
+}
@@ -0,0 +1,261 @@
+//! Search options shared by the HTTP API, the CLI and the Python bindings.
+//! Every surface builds its request from `SearchRequestOptions`, so a new
+//! option is one field here plus its wiring tests below.
+
+use serde::{Deserialize, Deserializer, Serialize};
+
+use crate::core::{CacheMode, SearchResponse};
+use crate::engine::SearchQuery;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "cli", derive(clap::Args))]
+#[serde(default)]
+pub struct SearchRequestOptions {
+    /// Maximum results
+    #[cfg_attr(feature = "cli", arg(short, long, default_value_t = 5))]
+    pub max_results: usize,
+
+    /// "use", "bypass" (forced refresh) or "off"
+    #[cfg_attr(feature = "cli", arg(long))]
+    pub cache_mode: Option<CacheMode>,
+
+    /// Description language (e.g. fr)
+    #[cfg_attr(feature = "cli", arg(long))]
+    pub locale: Option<String>,
+
+    /// Store region (e.g. FR)
+    #[cfg_attr(feature = "cli", arg(long))]
+    pub country: Option<String>,
+
+    /// Only query these providers (all enabled ones when empty)
+    #[cfg_attr(feature = "cli", arg(long = "provider"))]
+    #[serde(deserialize_with = "list_or_csv")]
+    pub providers: Vec<String>,
+
+    /// Skip announced games that are not out yet
+    #[cfg_attr(feature = "cli", arg(long, visible_alias = "released-only"))]
+    pub exclude_unreleased: bool,
+
+    /// Only keep games on these platforms (e.g. ps5, switch)
+    #[cfg_attr(feature = "cli", arg(long = "platform"))]
+    #[serde(deserialize_with = "list_or_csv")]
+    pub platforms: Vec<String>,
+
+    /// Include the score breakdown in the response
+    #[cfg_attr(feature = "cli", arg(short = 'v', long, visible_alias = "verbose"))]
+    pub explain: bool,
+}
+
+impl Default for SearchRequestOptions {
+    fn default() -> Self {
+        Self {
+            max_results: 5,
+            cache_mode: None,
+            locale: None,
+            country: None,
+            providers: Vec::new(),
+            exclude_unreleased: false,
+            platforms: Vec::new(),
+            explain: false,
+        }
+    }
+}
+
+impl SearchRequestOptions {
+    /// Option names as spelled in HTTP bodies and Python kwargs
+    pub fn field_names() -> Vec<String> {
+        match serde_json::to_value(Self::default()) {
+            Ok(serde_json::Value::Object(fields)) => fields.keys().cloned().collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Options from keyword arguments converted to JSON values; unknown
+    /// names are rejected rather than silently ignored
+    pub fn from_kwargs(kwargs: serde_json::Map<String, serde_json::Value>) -> Result<Self, String> {
+        let known = Self::field_names();
+        if let Some(unknown) = kwargs.keys().find(|name| !known.contains(name)) {
+            return Err(format!("unexpected keyword argument '{}'", unknown));
+        }
+        serde_json::from_value(serde_json::Value::Object(kwargs)).map_err(|e| e.to_string())
+    }
+
+    /// Engine query for `query`, with `default_cache_mode` when no cache mode
+    /// was requested (surfaces map their own shorthands to it)
+    pub fn to_query(&self, query: impl Into<String>, default_cache_mode: CacheMode) -> SearchQuery {
+        SearchQuery {
+            query: query.into(),
+            max_results: self.max_results,
+            cache_mode: self.cache_mode.unwrap_or(default_cache_mode),
+            locale: self.locale.clone(),
+            country: self.country.clone(),
+            providers: (!self.providers.is_empty()).then(|| self.providers.clone()),
+            exclude_unreleased: self.exclude_unreleased,
+            platforms: (!self.platforms.is_empty()).then(|| self.platforms.clone()),
+            explain: self.explain,
+        }
+    }
+
+    /// Drop what the caller did not ask for from `response`
+    pub fn apply(&self, response: &mut SearchResponse) {
+        if !self.explain {
+            response.score_breakdown = None;
+        }
+    }
+}
+
+/// `["a", "b"]`, `"a,b"` or null
+fn list_or_csv<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<String>, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum ListOrCsv {
+        List(Vec<String>),
+        Csv(String),
+    }
+
+    Ok(match Option::<ListOrCsv>::deserialize(deserializer)? {
+        Some(ListOrCsv::List(items)) => items,
+        Some(ListOrCsv::Csv(csv)) => csv
+            .split(',')
+            .map(|item| item.trim().to_string())
+            .filter(|item| !item.is_empty())
+            .collect(),
+        None => Vec::new(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    /// Every option set to a non-default value, as an HTTP body
+    fn http_fixture() -> serde_json::Value {
+        json!({
+            "max_results": 3,
+            "cache_mode": "bypass",
+            "locale": "fr",
+            "country": "FR",
+            "providers": ["steam"],
+            "exclude_unreleased": true,
+            "platforms": "ps5,switch",
+            "explain": true,
+        })
+    }
+
+    /// The same options as CLI flags
+    #[cfg(feature = "cli")]
+    const CLI_FIXTURE: &[&str] = &[
+        "--max-results", "3",
+        "--cache-mode", "bypass",
+        "--locale", "fr",
+        "--country", "FR",
+        "--provider", "steam",
+        "--exclude-unreleased",
+        "--platform", "ps5", "--platform", "switch",
+        "--explain",
+    ];
+
+    /// The same options as Python kwargs, converted to JSON by the bindings
+    fn python_fixture() -> serde_json::Map<String, serde_json::Value> {
+        let kwargs = json!({
+            "max_results": 3,
+            "cache_mode": "bypass",
+            "locale": "fr",
+            "country": "FR",
+            "providers": ["steam"],
+            "exclude_unreleased": true,
+            "platforms": ["ps5", "switch"],
+            "explain": true,
+        });
+        kwargs.as_object().cloned().unwrap()
+    }
+
+    fn expected() -> SearchRequestOptions {
+        SearchRequestOptions {
+            max_results: 3,
+            cache_mode: Some(CacheMode::Bypass),
+            locale: Some("fr".to_string()),
+            country: Some("FR".to_string()),
+            providers: vec!["steam".to_string()],
+            exclude_unreleased: true,
+            platforms: vec!["ps5".to_string(), "switch".to_string()],
+            explain: true,
+        }
+    }
+
+    #[cfg(feature = "cli")]
+    #[derive(clap::Parser)]
+    struct Cli {
+        #[command(flatten)]
+        options: SearchRequestOptions,
+    }
+
+    #[test]
+    fn test_every_field_on_every_surface() {
+        let fields = SearchRequestOptions::field_names();
+        assert_eq!(fields.len(), 8);
+
+        let http = http_fixture();
+        let python = python_fixture();
+        for field in &fields {
+            assert!(http.get(field).is_some(), "'{}' missing from the HTTP fixture", field);
+            assert!(python.contains_key(field), "'{}' missing from the Python kwargs fixture", field);
+        }
+
+        #[cfg(feature = "cli")]
+        {
+            use clap::CommandFactory;
+            let command = Cli::command();
+            for field in &fields {
+                let arg = command.get_arguments().find(|arg| arg.get_id() == field.as_str());
+                let arg = arg.unwrap_or_else(|| panic!("'{}' has no CLI flag", field));
+                let flag = format!("--{}", arg.get_long().unwrap());
+                assert!(CLI_FIXTURE.contains(&flag.as_str()), "'{}' missing from the CLI fixture", flag);
+            }
+        }
+    }
+
+    #[test]
+    fn test_http_body() {
+        let options: SearchRequestOptions = serde_json::from_value(http_fixture()).unwrap();
+        assert_eq!(options, expected());
+
+        let options: SearchRequestOptions = serde_json::from_value(json!({"providers": null})).unwrap();
+        assert_eq!(options, SearchRequestOptions::default());
+    }
+
+    #[cfg(feature = "cli")]
+    #[test]
+    fn test_cli_flags() {
+        use clap::Parser;
+        let args = std::iter::once("search").chain(CLI_FIXTURE.iter().copied());
+        assert_eq!(Cli::parse_from(args).options, expected());
+        assert_eq!(Cli::parse_from(["search"]).options, SearchRequestOptions::default());
+        // Former flag names
+        let legacy = Cli::parse_from(["search", "--released-only", "--verbose"]).options;
+        assert!(legacy.exclude_unreleased && legacy.explain);
+    }
+
+    #[test]
+    fn test_python_kwargs() {
+        assert_eq!(SearchRequestOptions::from_kwargs(python_fixture()).unwrap(), expected());
+
+        let mut kwargs = serde_json::Map::new();
+        kwargs.insert("max_result".to_string(), json!(3));
+        let err = SearchRequestOptions::from_kwargs(kwargs).unwrap_err();
+        assert!(err.contains("max_result"), "{}", err);
+    }
+
+    #[test]
+    fn test_to_query() {
+        let query = SearchRequestOptions::default().to_query("hades", CacheMode::Off);
+        assert_eq!(query.cache_mode, CacheMode::Off);
+        assert_eq!((query.providers, query.platforms), (None, None));
+
+        let query = expected().to_query("hades", CacheMode::Off);
+        assert_eq!(query.cache_mode, CacheMode::Bypass);
+        assert_eq!(query.providers, Some(vec!["steam".to_string()]));
+        assert!(query.exclude_unreleased);
+    }
+}
@@ -0,0 +1,70 @@
+//! Live counts of the engine's background tasks and in-flight searches,
+//! reported by `GameEngine::info` to spot leaks on long-running servers
+
+use std::future::Future;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use tokio::task::JoinHandle;
+
+/// Number of live `TaskGuard`s handed out
+#[derive(Debug, Clone, Default)]
+pub struct TaskCounter(Arc<AtomicUsize>);
+
+impl TaskCounter {
+    pub fn count(&self) -> usize {
+        self.0.load(Ordering::Relaxed)
+    }
+
+    /// Count one until the guard is dropped
+    pub fn guard(&self) -> TaskGuard {
+        self.0.fetch_add(1, Ordering::Relaxed);
+        TaskGuard(self.0.clone())
+    }
+
+    /// Spawn `future` on the current runtime, counted until it finishes or
+    /// is aborted
+    pub fn spawn<F>(&self, future: F) -> JoinHandle<F::Output>
+    where
+        F: Future + Send + 'static,
+        F::Output: Send + 'static,
+    {
+        let guard = self.guard();
+        tokio::spawn(async move {
+            let _guard = guard;
+            future.await
+        })
+    }
+}
+
+#[derive(Debug)]
+pub struct TaskGuard(Arc<AtomicUsize>);
+
+impl Drop for TaskGuard {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_counts_running_tasks() {
+        let tasks = TaskCounter::default();
+        let guard = tasks.guard();
+        assert_eq!(tasks.count(), 1);
+        drop(guard);
+        assert_eq!(tasks.count(), 0);
+
+        let done = tasks.spawn(async { 42 });
+        assert_eq!(done.await.unwrap(), 42);
+        assert_eq!(tasks.count(), 0);
+
+        let pending = tasks.spawn(std::future::pending::<()>());
+        assert_eq!(tasks.count(), 1);
+        pending.abort();
+        let _ = pending.await;
+        assert_eq!(tasks.count(), 0);
+    }
+}
@@ -0,0 +1,21 @@
+#![cfg(feature = "cli")]
+
+use assert_cmd::Command;
+use kissbot_game_engine::exit::ErrorCode;
+
+#[test]
+fn test_cli_config_error_exit_code() {
+    let path = std::env::temp_dir().join(format!("kissbot_bad_config_{}.yaml", std::process::id()));
+    std::fs::write(&path, "providers:\n  - name: steam\n    timeout_secs: -1\n").unwrap();
+
+    let output = Command::cargo_bin("game-engine-cli")
+        .unwrap()
+        .args(["--config", path.to_str().unwrap(), "stats"])
+        .output()
+        .unwrap();
+    let _ = std::fs::remove_file(&path);
+
+    assert_eq!(output.status.code(), Some(ErrorCode::Config.exit_code()));
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("providers[0].timeout_secs"), "{}", stderr);
+}
@@ -1,4 +1,4 @@
-use kissbot_game_engine::{GameEngine, SearchQuery, providers::SteamProvider};
+use kissbot_game_engine::{CacheMode, GameEngine, SearchQuery, providers::SteamProvider};
 use std::sync::Arc;
 
 #[tokio::test]
@@ -14,7 +14,8 @@ async fn test_engine_integration() {
     let query = SearchQuery {
         query: "counter-strike".to_string(),
         max_results: 5,
-        use_cache: true,
+        cache_mode: CacheMode::Use,
+        ..Default::default()
     };
     
     // Note: This test requires network access and will be slow
@@ -0,0 +1,25 @@
+#![cfg(feature = "cli")]
+
+use assert_cmd::Command;
+
+#[test]
+fn test_short_soak_run_writes_report() {
+    let report = std::env::temp_dir().join(format!("kissbot_soak_{}.csv", std::process::id()));
+
+    let output = Command::cargo_bin("soak")
+        .unwrap()
+        .args(["--duration-secs", "3", "--sample-secs", "1", "--warmup-secs", "1"])
+        .args(["--queries", "200", "--concurrency", "4", "--cleanup-secs", "1"])
+        // Too short for a meaningful RSS slope
+        .args(["--max-rss-slope-kib", "1000000000"])
+        .args(["--report", report.to_str().unwrap()])
+        .output()
+        .unwrap();
+    let csv = std::fs::read_to_string(&report).unwrap_or_default();
+    let _ = std::fs::remove_file(&report);
+
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    let mut lines = csv.lines();
+    assert!(lines.next().unwrap().starts_with("elapsed_secs,rss_kib,background_tasks"));
+    assert!(lines.count() >= 3, "{}", csv);
+}
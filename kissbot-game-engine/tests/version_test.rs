@@ -0,0 +1,53 @@
+use assert_cmd::Command;
+use kissbot_game_engine::{BuildInfo, VERSION};
+
+fn version_json(bin: &str) -> BuildInfo {
+    let output = Command::cargo_bin(bin)
+        .unwrap()
+        .args(["--version", "--json"])
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{} --version --json failed", bin);
+    serde_json::from_slice(&output.stdout).unwrap()
+}
+
+/// Every `[[bin]]` of the manifest whose required features this test build
+/// has, so a new binary without `--version` fails here
+fn built_bins() -> Vec<String> {
+    let manifest: toml::Value = std::fs::read_to_string(concat!(env!("CARGO_MANIFEST_DIR"), "/Cargo.toml"))
+        .unwrap()
+        .parse()
+        .unwrap();
+    let enabled = BuildInfo::new("version_test").features;
+    manifest["bin"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter(|bin| {
+            bin.get("required-features")
+                .and_then(|features| features.as_array())
+                .is_none_or(|features| features.iter().all(|f| enabled.iter().any(|e| Some(e.as_str()) == f.as_str())))
+        })
+        .map(|bin| bin["name"].as_str().unwrap().to_string())
+        .collect()
+}
+
+#[test]
+fn test_every_bin_prints_version() {
+    for bin in built_bins() {
+        let info = version_json(&bin);
+        assert_eq!((info.name.as_str(), info.version.as_str()), (bin.as_str(), VERSION));
+        assert!(!info.git_sha.is_empty());
+
+        let output = Command::cargo_bin(&bin).unwrap().arg("--version").output().unwrap();
+        let line = String::from_utf8(output.stdout).unwrap();
+        assert!(line.starts_with(&format!("{} {} (", bin, VERSION)), "{}: {}", bin, line);
+    }
+}
+
+#[cfg(feature = "server")]
+#[test]
+fn test_server_version_json() {
+    let info = version_json("game-engine-server");
+    assert!(info.features.iter().any(|f| f == "server"));
+}
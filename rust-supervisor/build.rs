@@ -0,0 +1,8 @@
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // VERGEN_GIT_SHA and VERGEN_BUILD_TIMESTAMP for --version and the startup banner
+    vergen::EmitBuilder::builder()
+        .build_timestamp()
+        .git_sha(true)
+        .emit()?;
+    Ok(())
+}
@@ -0,0 +1,305 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+// ============================================================================
+// Configuration
+// ============================================================================
+
+/// Runtime state file extensions living in the pid directory
+const STATE_EXTENSIONS: &[&str] = &["pid", "cmd", "result", "heartbeat", "drain"];
+
+#[derive(Debug, Clone)]
+pub struct HousekeepingConfig {
+    pub pid_dir: PathBuf,
+    pub log_dir: PathBuf,
+    /// State files younger than this are never touched
+    pub stale_after: Duration,
+    /// Total size cap for `log_dir` (only rotated logs are deleted)
+    pub max_log_bytes: u64,
+    /// Interval between scheduled passes
+    pub interval: Duration,
+}
+
+impl Default for HousekeepingConfig {
+    fn default() -> Self {
+        Self {
+            pid_dir: PathBuf::from("pids"),
+            log_dir: PathBuf::from("logs"),
+            stale_after: Duration::from_secs(3600),
+            max_log_bytes: 200 * 1024 * 1024,
+            interval: Duration::from_secs(3600),
+        }
+    }
+}
+
+// ============================================================================
+// Report
+// ============================================================================
+
+#[derive(Debug, Default)]
+pub struct HousekeepingReport {
+    pub stale_files: Vec<PathBuf>,
+    pub rotated_logs: Vec<PathBuf>,
+    pub freed_bytes: u64,
+}
+
+impl HousekeepingReport {
+    pub fn is_empty(&self) -> bool {
+        self.stale_files.is_empty() && self.rotated_logs.is_empty()
+    }
+
+    pub fn summary(&self) -> String {
+        format!(
+            "{} stale state file(s), {} rotated log(s), {:.1} MB freed",
+            self.stale_files.len(),
+            self.rotated_logs.len(),
+            self.freed_bytes as f64 / (1024.0 * 1024.0)
+        )
+    }
+}
+
+// ============================================================================
+// Housekeeping pass
+// ============================================================================
+
+/// Run one housekeeping pass (missing directories are skipped)
+pub fn run(config: &HousekeepingConfig, now: SystemTime) -> io::Result<HousekeepingReport> {
+    let mut report = HousekeepingReport::default();
+
+    if config.pid_dir.is_dir() {
+        clean_state_files(config, now, &mut report)?;
+    }
+
+    if config.log_dir.is_dir() {
+        enforce_log_cap(config, &mut report)?;
+    }
+
+    Ok(report)
+}
+
+fn clean_state_files(
+    config: &HousekeepingConfig,
+    now: SystemTime,
+    report: &mut HousekeepingReport,
+) -> io::Result<()> {
+    for entry in fs::read_dir(&config.pid_dir)? {
+        let path = entry?.path();
+        let is_state_file = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(|e| STATE_EXTENSIONS.contains(&e))
+            .unwrap_or(false);
+
+        if !is_state_file || !path.is_file() {
+            continue;
+        }
+
+        let modified = fs::metadata(&path)?.modified()?;
+        let age = now.duration_since(modified).unwrap_or_default();
+        if age < config.stale_after {
+            continue;
+        }
+
+        if owner_pid(&path).map(pid_alive).unwrap_or(false) {
+            continue;
+        }
+
+        let size = fs::metadata(&path)?.len();
+        fs::remove_file(&path)?;
+        report.freed_bytes += size;
+        report.stale_files.push(path);
+    }
+
+    Ok(())
+}
+
+/// PID owning a state file: its own content for `.pid` files, otherwise
+/// the sibling `<stem>.pid` file
+fn owner_pid(path: &Path) -> Option<i32> {
+    let pid_file = if path.extension().and_then(|e| e.to_str()) == Some("pid") {
+        path.to_path_buf()
+    } else {
+        path.with_extension("pid")
+    };
+
+    fs::read_to_string(pid_file).ok()?.trim().parse().ok()
+}
+
+fn pid_alive(pid: i32) -> bool {
+    #[cfg(unix)]
+    {
+        use nix::errno::Errno;
+        use nix::sys::signal::kill;
+        use nix::unistd::Pid;
+
+        // Signal 0 only checks existence; EPERM means it exists but isn't ours
+        matches!(kill(Pid::from_raw(pid), None), Ok(()) | Err(Errno::EPERM))
+    }
+
+    #[cfg(not(unix))]
+    {
+        let _ = pid;
+        true
+    }
+}
+
+/// Rotated logs look like `bot.log.1`, `bot.log.2.gz`, `hub.out.2025-11-30`
+fn is_rotated_log(path: &Path) -> bool {
+    let name = match path.file_name().and_then(|n| n.to_str()) {
+        Some(name) => name,
+        None => return false,
+    };
+
+    [".log.", ".out."].iter().any(|marker| name.contains(marker)) || name.ends_with(".gz")
+}
+
+fn enforce_log_cap(config: &HousekeepingConfig, report: &mut HousekeepingReport) -> io::Result<()> {
+    let mut total: u64 = 0;
+    let mut rotated: Vec<(SystemTime, u64, PathBuf)> = Vec::new();
+
+    for entry in fs::read_dir(&config.log_dir)? {
+        let path = entry?.path();
+        if !path.is_file() {
+            continue;
+        }
+
+        let metadata = fs::metadata(&path)?;
+        total += metadata.len();
+
+        if is_rotated_log(&path) {
+            rotated.push((metadata.modified()?, metadata.len(), path));
+        }
+    }
+
+    // Oldest rotations go first; active logs are never deleted
+    rotated.sort();
+    for (_, size, path) in rotated {
+        if total <= config.max_log_bytes {
+            break;
+        }
+        fs::remove_file(&path)?;
+        total -= size;
+        report.freed_bytes += size;
+        report.rotated_logs.push(path);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::File;
+    use std::io::Write;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("kissbot_hk_{}_{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("pids")).unwrap();
+        fs::create_dir_all(dir.join("logs")).unwrap();
+        dir
+    }
+
+    fn write_file(path: &Path, content: &[u8], age: Duration) {
+        let mut file = File::create(path).unwrap();
+        file.write_all(content).unwrap();
+        file.set_modified(SystemTime::now() - age).unwrap();
+    }
+
+    fn dead_pid() -> u32 {
+        let mut child = std::process::Command::new("true").spawn().unwrap();
+        let pid = child.id();
+        child.wait().unwrap();
+        pid
+    }
+
+    fn config(dir: &Path) -> HousekeepingConfig {
+        HousekeepingConfig {
+            pid_dir: dir.join("pids"),
+            log_dir: dir.join("logs"),
+            stale_after: Duration::from_secs(600),
+            max_log_bytes: 1000,
+            interval: Duration::from_secs(3600),
+        }
+    }
+
+    #[test]
+    fn test_removes_only_stale_files_of_dead_owners() {
+        let dir = temp_dir("stale");
+        let pids = dir.join("pids");
+        let old = Duration::from_secs(3600);
+        let alive = std::process::id().to_string();
+        let dead = dead_pid().to_string();
+
+        // Crashed run: old files, owner gone
+        write_file(&pids.join("crashed.pid"), dead.as_bytes(), old);
+        write_file(&pids.join("crashed.cmd"), b"restart", old);
+        write_file(&pids.join("crashed.heartbeat"), b"0", old);
+        // Orphan result file with no pid file at all
+        write_file(&pids.join("supervisor.result"), b"ok", old);
+        // Live process: old but owner alive
+        write_file(&pids.join("live.pid"), alive.as_bytes(), old);
+        write_file(&pids.join("live.drain"), b"", old);
+        // Recent file of a dead owner: too young to touch
+        write_file(&pids.join("fresh.pid"), dead.as_bytes(), Duration::from_secs(10));
+        // Unrelated file
+        write_file(&pids.join("notes.txt"), b"keep", old);
+
+        let report = run(&config(&dir), SystemTime::now()).unwrap();
+
+        let mut removed: Vec<String> = report
+            .stale_files
+            .iter()
+            .map(|p| p.file_name().unwrap().to_string_lossy().to_string())
+            .collect();
+        removed.sort();
+        assert_eq!(
+            removed,
+            vec!["crashed.cmd", "crashed.heartbeat", "crashed.pid", "supervisor.result"]
+        );
+        assert!(pids.join("live.pid").exists());
+        assert!(pids.join("live.drain").exists());
+        assert!(pids.join("fresh.pid").exists());
+        assert!(pids.join("notes.txt").exists());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_log_cap_deletes_oldest_rotations_first() {
+        let dir = temp_dir("logs");
+        let logs = dir.join("logs");
+        let chunk = vec![b'x'; 400];
+
+        write_file(&logs.join("bot.log"), &chunk, Duration::from_secs(10_000));
+        write_file(&logs.join("bot.log.3"), &chunk, Duration::from_secs(3000));
+        write_file(&logs.join("bot.log.2"), &chunk, Duration::from_secs(2000));
+        write_file(&logs.join("bot.log.1"), &chunk, Duration::from_secs(1000));
+
+        // 1600 bytes total, cap 1000: the two oldest rotations must go
+        let report = run(&config(&dir), SystemTime::now()).unwrap();
+
+        assert_eq!(report.rotated_logs.len(), 2);
+        assert!(!logs.join("bot.log.3").exists());
+        assert!(!logs.join("bot.log.2").exists());
+        assert!(logs.join("bot.log.1").exists());
+        assert!(logs.join("bot.log").exists());
+        assert_eq!(report.freed_bytes, 800);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_missing_directories_are_skipped() {
+        let cfg = HousekeepingConfig {
+            pid_dir: PathBuf::from("/nonexistent/kissbot/pids"),
+            log_dir: PathBuf::from("/nonexistent/kissbot/logs"),
+            ..HousekeepingConfig::default()
+        };
+
+        let report = run(&cfg, SystemTime::now()).unwrap();
+        assert!(report.is_empty());
+    }
+}
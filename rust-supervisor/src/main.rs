@@ -1,18 +1,53 @@
+mod housekeeping;
+
 use anyhow::Result;
-use serde::{Deserialize, Serialize};
+use housekeeping::HousekeepingConfig;
+use serde::Deserialize;
 use signal_hook::consts::signal::*;
 use signal_hook_tokio::Signals;
 use std::collections::HashMap;
 use std::path::PathBuf;
 use std::process::Stdio;
 use std::sync::Arc;
-use std::time::{Duration, Instant};
+use std::time::{Duration, Instant, SystemTime};
 use tokio::process::{Child, Command};
 use tokio::sync::RwLock;
 use tokio::time::sleep;
 use tracing::{error, info, warn};
 use futures::StreamExt;
 
+// ============================================================================
+// Build info
+// ============================================================================
+
+const VERSION: &str = env!("CARGO_PKG_VERSION");
+const GIT_SHA: &str = env!("VERGEN_GIT_SHA");
+const BUILD_DATE: &str = env!("VERGEN_BUILD_TIMESTAMP");
+
+fn git_sha() -> &'static str {
+    // vergen placeholder outside a git checkout
+    match GIT_SHA {
+        "VERGEN_IDEMPOTENT_OUTPUT" => "unknown",
+        sha => sha,
+    }
+}
+
+/// `--version` line, or the same fields as JSON (shape shared with the game engine binaries)
+fn version_output(json: bool) -> String {
+    if json {
+        serde_json::json!({
+            "name": "kissbot-supervisor",
+            "version": VERSION,
+            "git_sha": git_sha(),
+            "build_date": BUILD_DATE,
+            "features": [],
+        })
+        .to_string()
+    } else {
+        format!("kissbot-supervisor {} ({} {})", VERSION, git_sha(), BUILD_DATE)
+    }
+}
+
 // ============================================================================
 // Configuration
 // ============================================================================
@@ -35,6 +70,7 @@ struct SupervisorConfig {
     enable_hub: bool,
     hub_socket: PathBuf,
     health_check_interval: Duration,
+    housekeeping: HousekeepingConfig,
 }
 
 // ============================================================================
@@ -578,7 +614,47 @@ impl Supervisor {
         Ok(())
     }
 
+    async fn housekeep(&self) -> Result<()> {
+        let config = self.config.housekeeping.clone();
+        let report =
+            tokio::task::spawn_blocking(move || housekeeping::run(&config, SystemTime::now()))
+                .await??;
+
+        if report.is_empty() {
+            info!("🧹 Housekeeping: nothing to clean");
+        } else {
+            info!("🧹 Housekeeping: {}", report.summary());
+            for path in report.stale_files.iter().chain(report.rotated_logs.iter()) {
+                info!("     removed {}", path.display());
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn housekeeping_loop(&self) {
+        let interval = self.config.housekeeping.interval;
+        let mut elapsed = Duration::ZERO;
+
+        while *self.running.read().await {
+            sleep(Duration::from_secs(2)).await;
+            elapsed += Duration::from_secs(2);
+
+            if elapsed >= interval {
+                elapsed = Duration::ZERO;
+                if let Err(e) = self.housekeep().await {
+                    warn!("⚠️  Housekeeping failed: {}", e);
+                }
+            }
+        }
+    }
+
     async fn run(&self) -> Result<()> {
+        // Clean leftovers from previous (crashed) runs before starting
+        if let Err(e) = self.housekeep().await {
+            warn!("⚠️  Housekeeping failed: {}", e);
+        }
+
         // Start all processes
         self.start_all().await?;
 
@@ -586,7 +662,7 @@ impl Supervisor {
         self.print_status().await;
 
         // Setup signal handling
-        let mut signals = Signals::new(&[SIGTERM, SIGINT])?;
+        let mut signals = Signals::new([SIGTERM, SIGINT])?;
         let running = Arc::clone(&self.running);
 
         tokio::spawn(async move {
@@ -596,8 +672,9 @@ impl Supervisor {
             }
         });
 
-        // Run health check loop
-        self.health_check_loop().await?;
+        // Run health check and scheduled housekeeping loops
+        let (health, _) = tokio::join!(self.health_check_loop(), self.housekeeping_loop());
+        health?;
 
         // Cleanup
         info!("🧹 Cleaning up...");
@@ -625,11 +702,18 @@ async fn main() -> Result<()> {
     // Parse arguments (simple version - could use clap)
     let args: Vec<String> = std::env::args().collect();
 
+    if args.iter().any(|a| a == "--version" || a == "-V") {
+        println!("{}", version_output(args.iter().any(|a| a == "--json")));
+        return Ok(());
+    }
+
     let mut config_path = PathBuf::from("config/config.yaml");
     let mut use_db = false;
     let mut db_path = PathBuf::from("kissbot.db");
     let mut enable_hub = false;
     let mut hub_socket = PathBuf::from("/tmp/kissbot_hub.sock");
+    let mut housekeeping = HousekeepingConfig::default();
+    let mut housekeep_only = false;
 
     // Simple arg parsing
     let mut i = 1;
@@ -655,12 +739,46 @@ async fn main() -> Result<()> {
                 hub_socket = PathBuf::from(&args[i + 1]);
                 i += 2;
             }
+            "--pid-dir" => {
+                housekeeping.pid_dir = PathBuf::from(&args[i + 1]);
+                i += 2;
+            }
+            "--log-dir" => {
+                housekeeping.log_dir = PathBuf::from(&args[i + 1]);
+                i += 2;
+            }
+            "--stale-after-secs" => {
+                housekeeping.stale_after = Duration::from_secs(args[i + 1].parse()?);
+                i += 2;
+            }
+            "--log-max-mb" => {
+                housekeeping.max_log_bytes = args[i + 1].parse::<u64>()? * 1024 * 1024;
+                i += 2;
+            }
+            "--housekeep-interval-secs" => {
+                housekeeping.interval = Duration::from_secs(args[i + 1].parse()?);
+                i += 2;
+            }
+            "housekeep" => {
+                housekeep_only = true;
+                i += 1;
+            }
             _ => i += 1,
         }
     }
 
+    // One-shot housekeeping: report and exit without starting processes
+    if housekeep_only {
+        let report = housekeeping::run(&housekeeping, SystemTime::now())?;
+        println!("🧹 Housekeeping: {}", report.summary());
+        for path in report.stale_files.iter().chain(report.rotated_logs.iter()) {
+            println!("     removed {}", path.display());
+        }
+        return Ok(());
+    }
+
     println!("{}", "=".repeat(90));
-    println!("KissBot Supervisor (Rust)");
+    println!("KissBot Supervisor (Rust) {} ({} {})", VERSION, git_sha(), BUILD_DATE);
     println!("Config: {}", config_path.display());
     println!("Token Source: {}", if use_db { "DATABASE" } else { "YAML" });
     if use_db {
@@ -686,6 +804,7 @@ async fn main() -> Result<()> {
         enable_hub,
         hub_socket,
         health_check_interval: Duration::from_secs(30),
+        housekeeping,
     };
 
     let supervisor = Supervisor::new(config).await?;
@@ -0,0 +1,156 @@
+"""
+delta_s3 Python bindings - parity with the Rust unit tests
+
+The same assertions as delta-s3-rust/src/lib.rs, through the `delta_s3`
+module. Skipped unless it is installed:
+
+    cd delta-s3-rust && maturin develop --release
+    pytest tests-ci/test_delta_s3_bindings.py
+"""
+
+import pytest
+
+delta_s3 = pytest.importorskip("delta_s3")
+
+
+class TestTitleDelta:
+    """semantic_delta_title / semantic_delta_v3"""
+
+    def test_exact_match(self):
+        assert delta_s3.semantic_delta_v3("zelda", "zelda") < 0.1
+
+    def test_roman_numeral(self):
+        assert delta_s3.semantic_delta_v3("doom 2", "DOOM II") < 0.3
+        assert delta_s3.semantic_delta_v3("civilization 6", "Civilization VI") < 0.3
+
+    def test_dlc_debias(self):
+        plain = delta_s3.semantic_delta_v3("portal", "Portal 2")
+        goty = delta_s3.semantic_delta_v3("portal", "Portal 2 GOTY Edition")
+        assert goty > plain
+
+    @pytest.mark.parametrize("query,game", [
+        ("gta v", "Grand Theft Auto V"),
+        ("cod black ops", "Call of Duty: Black Ops"),
+        ("tf2", "Team Fortress 2"),
+        ("csgo", "Counter-Strike: Global Offensive"),
+        ("botw", "The Legend of Zelda: Breath of the Wild"),
+        ("ffxiv", "Final Fantasy XIV"),
+        ("rdr2", "Red Dead Redemption 2"),
+    ])
+    def test_gaming_acronyms(self, query, game):
+        delta = delta_s3.semantic_delta_title(query, game)
+        assert delta < 0.3
+        spelled_out = delta_s3.DeltaConfig(gaming_acronyms=False)
+        assert spelled_out.delta(query, game) > delta + 0.2
+
+    def test_v3_keeps_titles_in_title_mode(self):
+        title = "The Legend of Zelda: Breath of the Wild"
+        assert delta_s3.semantic_delta_v3(title, "Zelda") == delta_s3.semantic_delta_title(title, "Zelda")
+
+    def test_batch_matches_single(self):
+        titles = ["Portal 2", "Portal", "Hades"]
+        assert delta_s3.delta_batch("portal", titles) == [
+            delta_s3.semantic_delta_title("portal", title) for title in titles
+        ]
+
+
+class TestDeltaConfig:
+    """DeltaConfig keyword arguments, JSON and validation"""
+
+    def test_tokenize(self):
+        assert delta_s3.DeltaConfig.title().tokenize("GTA V") == ["grand", "theft", "auto", "5"]
+        assert delta_s3.DeltaConfig.sentence().tokenize("The Cat is on the Mat") == ["cat", "mat"]
+
+    def test_kwargs_over_title_defaults(self):
+        config = delta_s3.DeltaConfig(negation_penalty=0.0)
+        assert config.delta("portal", "Portal 2") == delta_s3.semantic_delta_title("portal", "Portal 2")
+        assert delta_s3.DeltaConfig.from_json(config.to_json()).to_json() == config.to_json()
+
+    def test_word_lists(self):
+        query = "rayman legends"
+        french = delta_s3.DeltaConfig(dlc_keywords=["intégrale"])
+        assert french.delta(query, "Rayman Legends Intégrale") > french.delta(query, "Rayman Legends")
+
+    def test_validation(self):
+        with pytest.raises(ValueError, match="must sum to 1"):
+            delta_s3.DeltaConfig(jaccard_weight=0.5)
+        with pytest.raises(ValueError):
+            delta_s3.DeltaConfig.from_json("{not json")
+
+    def test_sentence_negation(self):
+        sentence = delta_s3.DeltaConfig.sentence()
+        assert sentence.delta("the game is fun", "this game is really fun") < sentence.delta("the game is fun", "the game is not fun")
+
+
+class TestCorpus:
+    """Corpus (DeltaCatalog): top_k, best, deltas, push / extend / remove"""
+
+    TITLES = ["Portal 2", "Portal", "Portal 2 GOTY Edition", "Portal", "Hades", "Half-Life 2", "Portal Knights"]
+
+    def test_top_k_sorts_every_delta(self):
+        corpus = delta_s3.Corpus(self.TITLES)
+        assert len(corpus) == len(self.TITLES)
+        deltas = corpus.deltas("portal")
+        ranked = sorted(enumerate(deltas), key=lambda pair: (pair[1], pair[0]))
+        top = corpus.top_k("portal", 4)
+        assert top == ranked[:4]
+        assert [index for index, _ in top[:2]] == [1, 3]
+        assert len(corpus.top_k("portal", 100)) == len(self.TITLES)
+        assert corpus.top_k("portal", 0) == []
+
+    def test_best(self):
+        corpus = delta_s3.Corpus(self.TITLES)
+        index, delta = corpus.best("hades")
+        assert corpus.title(index) == "Hades"
+        assert delta == delta_s3.semantic_delta_title("hades", "Hades")
+        assert delta_s3.Corpus([]).best("portal") is None
+        with pytest.raises(IndexError):
+            corpus.title(len(self.TITLES))
+
+    def test_batch_matches_single(self):
+        corpus = delta_s3.Corpus(self.TITLES)
+        queries = ["portal", "hades", "half life 2"]
+        assert corpus.top_k_batch(queries, 3) == [corpus.top_k(query, 3) for query in queries]
+
+    def test_config(self):
+        titles = ["the game is fun", "the game is not fun"]
+        sentence = delta_s3.DeltaConfig.sentence()
+        corpus = delta_s3.Corpus(titles, sentence)
+        assert corpus.deltas("this game is fun") == delta_s3.delta_batch("this game is fun", titles, sentence)
+        assert corpus.best("this game is fun")[0] == 0
+
+    def test_idf(self):
+        titles = ["The Witcher", "The Witness", "The Forest", "The Long Dark", "Witcher Adventure Game"]
+        plain, idf = delta_s3.Corpus(titles), delta_s3.Corpus(titles, idf=True)
+        # A shared "the" counts for less
+        assert idf.deltas("the witcher")[1] > plain.deltas("the witcher")[1]
+        assert idf.best("the witcher")[0] == 0
+        assert plain.deltas("the witcher") == delta_s3.delta_batch("the witcher", titles)
+
+    def test_updates(self):
+        corpus = delta_s3.Corpus(self.TITLES)
+        assert corpus.push("Hades II") == len(self.TITLES)
+        corpus.extend(["Portal Stories: Mel", "Hollow Knight"])
+        assert corpus.remove(0) == "Portal 2"
+        with pytest.raises(IndexError):
+            corpus.remove(100)
+        titles = self.TITLES[1:] + ["Hades II", "Portal Stories: Mel", "Hollow Knight"]
+        assert [corpus.title(index) for index in range(len(corpus))] == titles
+        assert corpus.top_k("hades 2", 3) == delta_s3.Corpus(titles).top_k("hades 2", 3)
+
+    def test_concurrent_updates(self):
+        from concurrent.futures import ThreadPoolExecutor
+
+        corpus = delta_s3.Corpus(["Hades"])
+
+        def rank(_):
+            # Appends never move a title: every index stays valid
+            return [(index, corpus.title(index)) for index, _ in corpus.top_k("game 7", 3)]
+
+        with ThreadPoolExecutor(4) as pool:
+            readers = [pool.submit(rank, n) for n in range(200)]
+            for n in range(200):
+                corpus.push(f"Game {n}")
+        for reader in readers:
+            for index, title in reader.result():
+                assert corpus.title(index) == title